@@ -12,6 +12,9 @@ class Howmany < Formula
     cd "howmany-core" do
       system "cargo", "install", *std_cargo_args
     end
+
+    generate_completions_from_executable(bin/"howmany", "completions")
+    (man1/"howmany.1").write Utils.safe_popen_read(bin/"howmany", "man")
   end
 
   test do
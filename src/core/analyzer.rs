@@ -0,0 +1,221 @@
+use crate::core::counter::CodeCounter;
+use crate::core::detector::{extension_key, FileDetector};
+use crate::core::filters::FileFilter;
+use crate::core::stats::aggregation::Interruption;
+use crate::core::stats::{AggregatedStats, StatsCalculator};
+use crate::utils::errors::{HowManyError, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Directory analysis for programs embedding howmany: walks a directory,
+/// counts its user-created files and computes the project statistics.
+///
+/// A long analysis can be bounded with `with_timeout`, or started with `spawn`
+/// and cancelled through its `AnalysisHandle`. Either way it stops between
+/// files and returns the statistics of the files counted so far, with
+/// `metadata.incomplete` saying why.
+pub struct Analyzer {
+    filter: FileFilter,
+    detector: FileDetector,
+    counter: CodeCounter,
+    calculator: StatsCalculator,
+    timeout: Option<Duration>,
+}
+
+impl Analyzer {
+    pub fn new() -> Self {
+        Self {
+            filter: FileFilter::new(),
+            detector: FileDetector::new(),
+            counter: CodeCounter::new(),
+            calculator: StatsCalculator::new(),
+            timeout: None,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: FileFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_detector(mut self, detector: FileDetector) -> Self {
+        self.detector = detector;
+        self
+    }
+
+    pub fn with_counter(mut self, counter: CodeCounter) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    pub fn with_stats_calculator(mut self, calculator: StatsCalculator) -> Self {
+        self.calculator = calculator;
+        self
+    }
+
+    /// Stop counting once `timeout` has passed since the analysis started
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Analyze `root` on the calling thread
+    pub fn analyze(&self, root: impl AsRef<Path>) -> Result<AggregatedStats> {
+        self.run(root.as_ref(), &AtomicBool::new(false))
+    }
+
+    /// Analyze `root` on a background thread, returning a handle to cancel or wait for it
+    pub fn spawn(self, root: impl Into<PathBuf>) -> AnalysisHandle {
+        let root = root.into();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let cancelled = Arc::clone(&cancelled);
+            thread::spawn(move || self.run(&root, &cancelled))
+        };
+
+        AnalysisHandle { cancelled, thread }
+    }
+
+    fn run(&self, root: &Path, cancelled: &AtomicBool) -> Result<AggregatedStats> {
+        let started = Instant::now();
+        let mut interruption = None;
+        let mut file_stats = Vec::new();
+        let mut individual_files = Vec::new();
+
+        for entry in self.filter.walk_directory(root) {
+            if cancelled.load(Ordering::Relaxed) {
+                interruption = Some(Interruption::Cancelled);
+                break;
+            }
+            if self.timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+                interruption = Some(Interruption::TimedOut);
+                break;
+            }
+
+            let path = entry.path();
+            let relative_path = Path::new(".").join(path.strip_prefix(root).unwrap_or(path));
+            if !path.is_file() || !self.detector.is_user_created_file(&relative_path) {
+                continue;
+            }
+
+            // Unreadable and binary files are left out, as in a command-line run
+            let Ok(stats) = self.counter.count_file(path) else {
+                continue;
+            };
+            let extension = stats.language_key(extension_key(path).unwrap_or_else(|| "no_ext".to_string()));
+            file_stats.push((extension, stats.clone()));
+            individual_files.push((path.to_string_lossy().to_string(), stats));
+        }
+
+        let code_stats = self.counter.aggregate_stats(file_stats);
+        let mut stats = self.calculator.calculate_project_stats(&code_stats, &individual_files)?;
+        stats.metadata.incomplete = interruption;
+        Ok(stats)
+    }
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An analysis running on a background thread, started by `Analyzer::spawn`
+pub struct AnalysisHandle {
+    cancelled: Arc<AtomicBool>,
+    thread: JoinHandle<Result<AggregatedStats>>,
+}
+
+impl AnalysisHandle {
+    /// Stop the analysis after the file being counted; `wait` then returns
+    /// the statistics of the files counted so far
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Wait for the analysis to finish or stop
+    pub fn wait(self) -> Result<AggregatedStats> {
+        self.thread
+            .join()
+            .map_err(|_| HowManyError::counter("Analysis thread panicked"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {\n    println!(\"hi\");\n}\n").unwrap();
+        fs::write(dir.path().join("util.py"), "# helpers\ndef f():\n    return 1\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_analyze_directory() {
+        let dir = project();
+        let stats = Analyzer::new().analyze(dir.path()).unwrap();
+
+        assert_eq!(stats.basic.total_files, 2);
+        assert_eq!(stats.basic.total_lines, 6);
+        assert_eq!(stats.metadata.incomplete, None);
+    }
+
+    #[test]
+    fn test_interrupted_analysis_is_flagged() {
+        let dir = project();
+
+        let stats = Analyzer::new().with_timeout(Duration::ZERO).analyze(dir.path()).unwrap();
+        assert_eq!(stats.metadata.incomplete, Some(Interruption::TimedOut));
+        assert_eq!(stats.basic.total_files, 0);
+
+        let stats = Analyzer::new().run(dir.path(), &AtomicBool::new(true)).unwrap();
+        assert_eq!(stats.metadata.incomplete, Some(Interruption::Cancelled));
+        assert_eq!(stats.basic.total_files, 0);
+
+        let stats = Analyzer::new().spawn(dir.path()).wait().unwrap();
+        assert_eq!(stats.basic.total_files, 2);
+        assert_eq!(stats.metadata.incomplete, None);
+    }
+
+    #[test]
+    fn test_structures_by_language() {
+        let dir = project();
+        // Types without functions still count toward their language
+        fs::write(dir.path().join("shapes.rs"), "pub struct Point {\n    x: i32,\n}\n\npub enum Shape {\n    Dot,\n}\n\npub trait Area {}\n").unwrap();
+        fs::write(dir.path().join("shop.py"), "class Cart:\n    def add(self, item):\n        pass\n\n    def total(self):\n        return 0\n\nclass Item:\n    def price(self):\n        return 1\n").unwrap();
+
+        let complexity = Analyzer::new().analyze(dir.path()).unwrap().complexity;
+        let rust = &complexity.complexity_by_extension["rs"];
+        assert_eq!((rust.struct_count, rust.enum_count, rust.trait_count, rust.total_structures), (1, 1, 1, 3));
+        let python = &complexity.complexity_by_extension["py"];
+        assert_eq!((python.class_count, python.max_methods_per_class), (2, 2));
+        assert_eq!(python.methods_per_class, 1.5);
+        assert_eq!(complexity.max_methods_per_class, 2);
+        assert_eq!(complexity.total_structures, 5);
+    }
+
+    #[test]
+    fn test_objective_c_m_files_are_their_own_language() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Greeter.m"), "#import <Foundation/Foundation.h>\n\n@implementation Greeter\n- (void)greet {\n    NSLog(@\"hi\");\n}\n@end\n").unwrap();
+        fs::write(dir.path().join("square.m"), "% Squares\nfunction y = square(x)\n    y = x.^2;\nend\n").unwrap();
+
+        let stats = Analyzer::new().analyze(dir.path()).unwrap();
+        let by_extension = &stats.basic.stats_by_extension;
+        assert_eq!((by_extension["mm"].file_count, by_extension["mm"].code_lines), (1, 6));
+        assert_eq!((by_extension["m"].file_count, by_extension["m"].code_lines), (1, 3));
+        assert!(stats.complexity.complexity_by_extension.contains_key("mm"));
+        assert!(stats.complexity.complexity_by_extension.contains_key("m"));
+    }
+}
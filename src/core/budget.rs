@@ -0,0 +1,181 @@
+use crate::core::stats::basic::BasicStats;
+use crate::utils::config::BudgetConfig;
+use crate::utils::errors::{HowManyError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Share of a cap, in percent, at which a budget warns unless `warn_at` is set
+pub const DEFAULT_WARN_PERCENT: f64 = 80.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetMetric {
+    CodeLines,
+    Lines,
+    Files,
+}
+
+impl BudgetMetric {
+    pub const ALL: [BudgetMetric; 3] = [BudgetMetric::CodeLines, BudgetMetric::Lines, BudgetMetric::Files];
+
+    /// Name of the cap's metric as in JSON output, e.g. `code_lines`
+    pub fn name(&self) -> &'static str {
+        match self {
+            BudgetMetric::CodeLines => "code_lines",
+            BudgetMetric::Lines => "lines",
+            BudgetMetric::Files => "files",
+        }
+    }
+
+    pub fn value(&self, basic: &BasicStats) -> usize {
+        match self {
+            BudgetMetric::CodeLines => basic.code_lines,
+            BudgetMetric::Lines => basic.total_lines,
+            BudgetMetric::Files => basic.total_files,
+        }
+    }
+
+    pub fn limit(&self, config: &BudgetConfig) -> Option<usize> {
+        match self {
+            BudgetMetric::CodeLines => config.max_code_lines,
+            BudgetMetric::Lines => config.max_lines,
+            BudgetMetric::Files => config.max_files,
+        }
+    }
+}
+
+impl fmt::Display for BudgetMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BudgetMetric::CodeLines => "code lines",
+            BudgetMetric::Lines => "lines",
+            BudgetMetric::Files => "files",
+        })
+    }
+}
+
+/// How close a budget is to its caps, ordered from best to worst
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetStatus {
+    #[default]
+    Ok,
+    /// At or past the warning share of a cap, but within it
+    Warning,
+    Exceeded,
+}
+
+/// Consumption of one cap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub metric: BudgetMetric,
+    pub used: usize,
+    pub limit: usize,
+    /// `used` as a percentage of `limit`
+    pub percent: f64,
+    pub status: BudgetStatus,
+}
+
+/// Consumption of the caps set for one `[budget]` glob
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetStats {
+    pub pattern: String,
+    pub usage: Vec<BudgetUsage>,
+    /// Worst status of the caps
+    pub status: BudgetStatus,
+}
+
+impl BudgetStats {
+    /// Measure the totals of the files matching `pattern` against its caps
+    pub fn new(pattern: &str, config: &BudgetConfig, basic: &BasicStats) -> Self {
+        let warn_at = config.warn_at.unwrap_or(DEFAULT_WARN_PERCENT);
+        let usage: Vec<BudgetUsage> = BudgetMetric::ALL
+            .iter()
+            .filter_map(|metric| {
+                let limit = metric.limit(config)?;
+                let used = metric.value(basic);
+                let percent = if limit > 0 {
+                    used as f64 / limit as f64 * 100.0
+                } else if used > 0 {
+                    f64::INFINITY
+                } else {
+                    0.0
+                };
+                let status = if used > limit {
+                    BudgetStatus::Exceeded
+                } else if percent >= warn_at {
+                    BudgetStatus::Warning
+                } else {
+                    BudgetStatus::Ok
+                };
+                Some(BudgetUsage { metric: *metric, used, limit, percent, status })
+            })
+            .collect();
+        let status = usage.iter().map(|usage| usage.status).max().unwrap_or_default();
+
+        Self { pattern: pattern.to_string(), usage, status }
+    }
+}
+
+/// Reject a budget that sets no cap or has a warning share outside 0-100%
+pub fn validate(pattern: &str, config: &BudgetConfig) -> Result<()> {
+    if BudgetMetric::ALL.iter().all(|metric| metric.limit(config).is_none()) {
+        return Err(HowManyError::invalid_config(format!(
+            "Budget \"{}\" sets none of max_code_lines, max_lines or max_files",
+            pattern
+        )));
+    }
+    if let Some(warn_at) = config.warn_at {
+        if !(0.0..=100.0).contains(&warn_at) {
+            return Err(HowManyError::invalid_config(format!(
+                "Budget \"{}\" has warn_at = {}, expected a percentage between 0 and 100",
+                pattern, warn_at
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::stats::basic::BasicStatsCalculator;
+    use crate::core::types::CodeStats;
+
+    fn basic(files: usize, lines: usize, code_lines: usize) -> BasicStats {
+        let code_stats = CodeStats {
+            total_files: files,
+            total_lines: lines,
+            total_code_lines: code_lines,
+            ..CodeStats::default()
+        };
+        BasicStatsCalculator::new().calculate_project_basic_stats(&code_stats).unwrap()
+    }
+
+    #[test]
+    fn test_budget_status() {
+        let config = BudgetConfig { max_code_lines: Some(20_000), max_files: Some(100), ..BudgetConfig::default() };
+
+        let stats = BudgetStats::new("src/parser/**", &config, &basic(50, 30_000, 10_000));
+        assert_eq!(stats.usage.len(), 2);
+        assert_eq!(stats.usage[0].metric, BudgetMetric::CodeLines);
+        assert_eq!(stats.usage[0].percent, 50.0);
+        assert_eq!(stats.status, BudgetStatus::Ok);
+
+        // Approaching one cap warns, passing another exceeds
+        let stats = BudgetStats::new("src/parser/**", &config, &basic(101, 30_000, 18_000));
+        assert_eq!(stats.usage[0].status, BudgetStatus::Warning);
+        assert_eq!(stats.usage[1].status, BudgetStatus::Exceeded);
+        assert_eq!(stats.status, BudgetStatus::Exceeded);
+
+        let config = BudgetConfig { warn_at: Some(95.0), ..config };
+        assert_eq!(BudgetStats::new("src/**", &config, &basic(10, 0, 18_000)).status, BudgetStatus::Ok);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("src/**", &BudgetConfig::default()).is_err());
+        assert!(validate("src/**", &BudgetConfig { max_lines: Some(10), warn_at: Some(120.0), ..BudgetConfig::default() }).is_err());
+        assert!(validate("src/**", &BudgetConfig { max_lines: Some(10), ..BudgetConfig::default() }).is_ok());
+    }
+}
@@ -0,0 +1,352 @@
+use std::ops::Range;
+
+/// Markdown fence languages that aren't themselves an extension, by extension
+const FENCE_ALIASES: &[(&str, &str)] = &[
+    ("rust", "rs"), ("python", "py"), ("python3", "py"), ("javascript", "js"), ("typescript", "ts"),
+    ("shell", "sh"), ("golang", "go"), ("ruby", "rb"), ("csharp", "cs"), ("c#", "cs"), ("c++", "cpp"),
+    ("kotlin", "kt"), ("yml", "yaml"), ("haskell", "hs"), ("elixir", "ex"), ("perl", "pl"),
+    ("bash", "sh"), ("julia", "jl"), ("clojure", "clj"),
+];
+
+/// Lines of a file written in another language than the file itself: the
+/// `<script>` and `<style>` blocks of a component or page, a fenced code
+/// block in Markdown, a code chunk of a literate program, or the HTML around
+/// the `<?php ?>` tags of a PHP template. They are classified and counted as
+/// `extension`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Region {
+    pub extension: String,
+    /// Indexes of the block's lines, without the opening and closing tag or fence
+    pub lines: Range<usize>,
+    /// Bytes before the code on each line, the `>` of literate Haskell bird tracks
+    pub prefix: usize,
+}
+
+/// Language the lines outside any region are classified as, for files that
+/// can embed other languages. The markup of a Vue or Svelte component is
+/// HTML, and the prose of a literate program is documentation like Markdown,
+/// though their lines still count toward the file's own extension.
+pub(super) fn host_language(extension: &str) -> Option<&'static str> {
+    match extension {
+        "vue" | "svelte" | "html" | "htm" => Some("html"),
+        "md" | "rmd" | "qmd" | "org" | "lhs" => Some("md"),
+        "php" => Some("php"),
+        _ => None,
+    }
+}
+
+/// Embedded blocks of a file with `extension`, in order. Code blocks naming
+/// their language are only split out when `is_known` recognizes its extension.
+pub(super) fn regions(extension: &str, lines: &[&str], is_known: impl Fn(&str) -> bool) -> Vec<Region> {
+    match extension {
+        "vue" | "svelte" | "html" | "htm" => markup_regions(lines),
+        "md" | "rmd" | "qmd" => fence_regions(lines, is_known),
+        "org" => org_block_regions(lines, is_known),
+        "lhs" => literate_haskell_regions(lines),
+        "php" => php_markup_regions(lines),
+        _ => Vec::new(),
+    }
+}
+
+/// Runs of HTML lines outside the `<?php ?>` tags of a PHP file. A line with
+/// any PHP on it, even just a tag (`<p><?= $name ?></p>`), counts as PHP.
+fn php_markup_regions(lines: &[&str]) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut in_php = false;
+    let mut markup_start = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let mut has_php = in_php;
+        let mut rest = *line;
+        loop {
+            if in_php {
+                match rest.find("?>") {
+                    Some(end) => {
+                        in_php = false;
+                        rest = &rest[end + 2..];
+                    }
+                    None => break,
+                }
+            } else {
+                match php_open_tag(rest) {
+                    Some(start) => {
+                        in_php = true;
+                        has_php = true;
+                        rest = &rest[start..];
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        match (has_php, markup_start) {
+            (false, None) => markup_start = Some(index),
+            (true, Some(start)) => {
+                regions.push(Region { extension: "html".to_string(), lines: start..index, prefix: 0 });
+                markup_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = markup_start {
+        regions.push(Region { extension: "html".to_string(), lines: start..lines.len(), prefix: 0 });
+    }
+
+    // A PHP file without markup is PHP throughout
+    regions.retain(|region| region.lines.len() < lines.len());
+    regions
+}
+
+/// Offset just past the first PHP opening tag in `text` (`<?php`, `<?=` or a
+/// short `<?`), ignoring `<?xml` declarations
+fn php_open_tag(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(position) = text[offset..].find("<?") {
+        let start = offset + position;
+        let after = &text[start + 2..];
+        if after.len() >= 3 && after[..3].eq_ignore_ascii_case("php") {
+            return Some(start + 5);
+        }
+        if after.starts_with('=') {
+            return Some(start + 3);
+        }
+        if after.is_empty() || after.starts_with(char::is_whitespace) {
+            return Some(start + 2);
+        }
+        offset = start + 2;
+    }
+    None
+}
+
+/// `<script>` and `<style>` blocks whose tags are on lines of their own
+fn markup_regions(lines: &[&str]) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let trimmed = lines[index].trim_start().to_ascii_lowercase();
+        let tag = ["script", "style"].into_iter().find(|tag| {
+            trimmed.strip_prefix('<').and_then(|rest| rest.strip_prefix(tag)).is_some_and(|rest| rest.starts_with(['>', ' ', '\t']) || rest.is_empty())
+        });
+        let Some(tag) = tag else {
+            index += 1;
+            continue;
+        };
+
+        // The opening tag may spread its attributes over several lines
+        let Some(tag_end) = (index..lines.len()).find(|line| lines[*line].contains('>')) else {
+            break;
+        };
+        let attributes = lines[index..=tag_end].join(" ").to_ascii_lowercase();
+        let closing = format!("</{}", tag);
+        let after_tag = &lines[tag_end][lines[tag_end].find('>').unwrap_or(0)..];
+        if after_tag.to_ascii_lowercase().contains(&closing) {
+            // `<script src="app.js"></script>` or a one-line block stays markup
+            index = tag_end + 1;
+            continue;
+        }
+
+        let Some(close) = (tag_end + 1..lines.len()).find(|line| lines[*line].to_ascii_lowercase().contains(&closing)) else {
+            break;
+        };
+        let extension = match tag {
+            "script" => script_extension(&attributes),
+            _ => Some(style_extension(&attributes)),
+        };
+        if let Some(extension) = extension {
+            regions.push(Region { extension: extension.to_string(), lines: tag_end + 1..close, prefix: 0 });
+        }
+        index = close + 1;
+    }
+
+    regions
+}
+
+/// Language of a `<script>` block from its `lang` or `type` attribute; `None`
+/// for client-side templates and other non-script contents
+fn script_extension(attributes: &str) -> Option<&'static str> {
+    if let Some(lang) = attribute(attributes, "lang") {
+        return match lang {
+            "ts" | "typescript" => Some("ts"),
+            "tsx" => Some("tsx"),
+            "jsx" => Some("jsx"),
+            _ => Some("js"),
+        };
+    }
+    match attribute(attributes, "type") {
+        None | Some("module" | "text/javascript" | "application/javascript" | "text/babel") => Some("js"),
+        Some("text/typescript" | "application/typescript") => Some("ts"),
+        Some("application/json" | "application/ld+json" | "importmap") => Some("json"),
+        Some(_) => None,
+    }
+}
+
+/// Language of a `<style>` block from its `lang` attribute
+fn style_extension(attributes: &str) -> &'static str {
+    match attribute(attributes, "lang") {
+        Some("scss") => "scss",
+        Some("sass") => "sass",
+        Some("less") => "less",
+        Some("stylus" | "styl") => "styl",
+        _ => "css",
+    }
+}
+
+/// Value of `name="value"` (or single-quoted, or bare) in a lowercased tag
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes;
+    while let Some(position) = rest.find(name) {
+        let preceded = rest[..position].ends_with([' ', '\t', '<']);
+        let after = rest[position + name.len()..].trim_start();
+        rest = &rest[position + name.len()..];
+        let Some(value) = after.strip_prefix('=').filter(|_| preceded) else {
+            continue;
+        };
+        let value = value.trim_start();
+        return match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next(),
+            _ => value.split([' ', '>', '\t']).next(),
+        };
+    }
+    None
+}
+
+/// Fenced code blocks (```` ```rust ````, `~~~ python`) with a known language,
+/// including the R Markdown and Quarto chunks (```` ```{r setup} ````)
+fn fence_regions(lines: &[&str], is_known: impl Fn(&str) -> bool) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let trimmed = lines[index].trim();
+        let Some(fence) = ["```", "~~~"].into_iter().find(|fence| trimmed.starts_with(fence)) else {
+            index += 1;
+            continue;
+        };
+
+        let Some(close) = (index + 1..lines.len()).find(|line| lines[*line].trim().starts_with(fence)) else {
+            break;
+        };
+        // The language is the first word of the info string (`rust,ignore`, `js title="app.js"`, `{r echo=FALSE}`)
+        let info = trimmed[fence.len()..].trim_start_matches(fence.as_bytes()[0] as char).trim().trim_start_matches(['{', '.']);
+        if let Some(extension) = block_extension(info, &is_known) {
+            regions.push(Region { extension, lines: index + 1..close, prefix: 0 });
+        }
+        index = close + 1;
+    }
+
+    regions
+}
+
+/// Org-mode source blocks (`#+BEGIN_SRC python :results output`) with a known language
+fn org_block_regions(lines: &[&str], is_known: impl Fn(&str) -> bool) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let trimmed = lines[index].trim();
+        let Some(info) = trimmed.get(..11).filter(|start| start.eq_ignore_ascii_case("#+begin_src")).map(|_| &trimmed[11..]) else {
+            index += 1;
+            continue;
+        };
+
+        let Some(close) = (index + 1..lines.len()).find(|line| lines[*line].trim().to_ascii_lowercase().starts_with("#+end_src")) else {
+            break;
+        };
+        if let Some(extension) = block_extension(info.trim(), &is_known) {
+            regions.push(Region { extension, lines: index + 1..close, prefix: 0 });
+        }
+        index = close + 1;
+    }
+
+    regions
+}
+
+/// Code of a literate Haskell file: runs of `>` bird-track lines, and
+/// `\begin{code}` … `\end{code}` blocks
+fn literate_haskell_regions(lines: &[&str]) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        if lines[index].starts_with('>') {
+            let end = (index..lines.len()).find(|line| !lines[*line].starts_with('>')).unwrap_or(lines.len());
+            regions.push(Region { extension: "hs".to_string(), lines: index..end, prefix: 1 });
+            index = end;
+        } else if lines[index].trim_end() == "\\begin{code}" {
+            let Some(close) = (index + 1..lines.len()).find(|line| lines[*line].trim_end() == "\\end{code}") else {
+                break;
+            };
+            regions.push(Region { extension: "hs".to_string(), lines: index + 1..close, prefix: 0 });
+            index = close + 1;
+        } else {
+            index += 1;
+        }
+    }
+
+    regions
+}
+
+/// Extension of a code block's language, the first word of its `info` string,
+/// when `is_known` recognizes it
+fn block_extension(info: &str, is_known: impl Fn(&str) -> bool) -> Option<String> {
+    let language = info.split([',', ' ', '{', '}']).next().unwrap_or("").to_lowercase();
+    let extension = FENCE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == language)
+        .map_or(language.as_str(), |(_, extension)| extension);
+    (!extension.is_empty() && is_known(extension)).then(|| extension.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(extension: &str, text: &str) -> Vec<Region> {
+        let lines: Vec<&str> = text.lines().collect();
+        regions(extension, &lines, |extension| ["rs", "py", "js", "ts", "r"].contains(&extension))
+    }
+
+    fn region(extension: &str, lines: Range<usize>) -> Region {
+        Region { extension: extension.to_string(), lines, prefix: 0 }
+    }
+
+    #[test]
+    fn test_component_regions() {
+        let vue = "<template>\n  <p>{{ msg }}</p>\n</template>\n\n<script setup lang=\"ts\">\nconst msg: string = 'hi'\n</script>\n\n<style scoped lang='scss'>\np { color: red; }\n</style>\n";
+        assert_eq!(split("vue", vue), vec![region("ts", 5..6), region("scss", 9..10)]);
+
+        // External and one-line scripts stay markup, client-side templates aren't scripts
+        let html = "<script src=\"app.js\"></script>\n<script>go()</script>\n<script type=\"text/x-template\">\n<p></p>\n</script>\n<script\n  type=\"module\">\nimport './a.js'\n</script>\n";
+        assert_eq!(split("html", html), vec![region("js", 7..8)]);
+    }
+
+    #[test]
+    fn test_markdown_fences() {
+        let markdown = "# Usage\n\n```rust,ignore\nfn main() {}\n```\n\n```console\n$ run\n```\n\n~~~python\nprint(1)\n~~~\n\n```\nplain\n```\n";
+        assert_eq!(split("md", markdown), vec![region("rs", 3..4), region("py", 11..12)]);
+
+        assert!(split("rs", "fn main() {}\n").is_empty());
+    }
+
+    #[test]
+    fn test_literate_chunks() {
+        let rmd = "---\ntitle: Report\n---\n\n```{r setup, include=FALSE}\nlibrary(dplyr)\n```\n\n```{python}\nprint(1)\n```\n";
+        assert_eq!(split("rmd", rmd), vec![region("r", 5..6), region("py", 9..10)]);
+
+        let org = "* Setup\n#+BEGIN_SRC python :results output\nprint(1)\n#+END_SRC\n#+begin_src emacs-lisp\n(message \"hi\")\n#+end_src\n";
+        assert_eq!(split("org", org), vec![region("py", 2..3)]);
+
+        let lhs = "Adds two numbers.\n\n> add :: Int -> Int -> Int\n> add x y = x + y\n\n\\begin{code}\nmain = print (add 1 2)\n\\end{code}\n";
+        assert_eq!(split("lhs", lhs), vec![Region { prefix: 1, ..region("hs", 2..4) }, region("hs", 6..7)]);
+    }
+
+    #[test]
+    fn test_php_markup() {
+        let template = "<?xml version=\"1.0\"?>\n<ul>\n<?php foreach ($items as $item): ?>\n  <li><?= $item ?></li>\n<?php endforeach; ?>\n</ul>\n<?php\n$total = count($items);\n\n// Done\n";
+        assert_eq!(split("php", template), vec![region("html", 0..2), region("html", 5..6)]);
+
+        // Plain PHP scripts have no markup, even with a closing tag
+        assert!(split("php", "<?php\necho 'hi';\n?>\n").is_empty());
+    }
+}
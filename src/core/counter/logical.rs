@@ -0,0 +1,146 @@
+use super::string_literal_length;
+
+/// How a language separates its statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StatementStyle {
+    /// `;` ends a statement and `{` opens a block (C, Java, JavaScript, Rust, ...)
+    Terminated,
+    /// The end of a line ends a statement unless a bracket is still open or the
+    /// line ends with `\`; `;` separates further statements on the same line
+    LineBased,
+}
+
+const TERMINATED_EXTENSIONS: &[&str] = &[
+    "c", "h", "cpp", "cc", "cxx", "hpp", "hh", "hxx", "cs", "java", "js", "jsx", "mjs", "cjs",
+    "ts", "tsx", "rs", "php", "dart", "zig", "m", "mm", "pl", "pm", "css", "scss", "less", "sql",
+    "proto", "nix", "adb", "ads", "ada", "pas", "dpr", "lpr", "cu", "cuh", "v", "vh", "sv", "svh",
+    "vhd", "vhdl",
+];
+
+const LINE_BASED_EXTENSIONS: &[&str] = &[
+    "py", "pyw", "rb", "go", "swift", "kt", "kts", "scala", "lua", "r", "jl", "ex", "exs",
+    "sh", "bash", "zsh", "fish", "ps1", "hs", "elm", "nim", "cr", "tf", "tfvars", "hcl",
+    "f", "for", "f77", "ftn", "f90", "f95", "f03", "f08", "cob", "cbl", "cpy", "s", "asm",
+];
+
+/// Keywords that close a block opened by an earlier statement, like `}` does
+const CLOSING_KEYWORDS: &[&str] = &["end", "fi", "done", "esac", "endif", "endfor", "endwhile", "endfunction"];
+
+/// Counter of logical lines of code (LLOC): statements rather than physical
+/// lines, so `a = 1; b = 2` counts twice and a call wrapped over five lines
+/// counts once. It is a lexical estimate from statement terminators, block
+/// openers and open brackets, not a parse.
+pub(super) struct LogicalLines<'a> {
+    style: StatementStyle,
+    comment_prefixes: &'a [String],
+    /// Open `(` and `[` carried over from earlier lines
+    depth: usize,
+    /// A line-based statement has started but not ended yet
+    pending: bool,
+    count: usize,
+}
+
+impl<'a> LogicalLines<'a> {
+    /// Counter for `extension`, or `None` for formats without statements (data, markup, prose)
+    pub(super) fn new(extension: &str, comment_prefixes: &'a [String]) -> Option<Self> {
+        let style = if TERMINATED_EXTENSIONS.contains(&extension) {
+            StatementStyle::Terminated
+        } else if LINE_BASED_EXTENSIONS.contains(&extension) {
+            StatementStyle::LineBased
+        } else {
+            return None;
+        };
+
+        Some(Self { style, comment_prefixes, depth: 0, pending: false, count: 0 })
+    }
+
+    /// Add the code of one line; a trailing single-line comment is ignored
+    pub(super) fn add(&mut self, code: &str) {
+        let code = code.trim();
+        if self.style == StatementStyle::LineBased && CLOSING_KEYWORDS.contains(&code) {
+            return;
+        }
+
+        let bytes = code.as_bytes();
+        let mut index = 0;
+        while index < bytes.len() {
+            let rest = &bytes[index..];
+            if self.comment_prefixes.iter().any(|prefix| rest.starts_with(prefix.as_bytes())) {
+                break;
+            }
+
+            let byte = bytes[index];
+            if matches!(byte, b'"' | b'\'' | b'`') {
+                if let Some(length) = string_literal_length(&rest[1..], byte) {
+                    self.pending = true;
+                    index += length + 2;
+                    continue;
+                }
+            }
+
+            match (self.style, byte) {
+                (_, b'(' | b'[') => self.depth += 1,
+                (_, b')' | b']') => self.depth = self.depth.saturating_sub(1),
+                (StatementStyle::Terminated, b';') if self.depth == 0 => self.count += 1,
+                (StatementStyle::Terminated, b'{') => self.count += 1,
+                (StatementStyle::LineBased, b';') if self.depth == 0 => self.end_statement(),
+                _ => {}
+            }
+            if !byte.is_ascii_whitespace() && !matches!(byte, b')' | b']' | b'}' | b';' | b',') {
+                self.pending = true;
+            }
+            index += 1;
+        }
+
+        if self.style == StatementStyle::LineBased && self.depth == 0 && !code.ends_with('\\') {
+            self.end_statement();
+        }
+    }
+
+    fn end_statement(&mut self) {
+        if self.pending {
+            self.count += 1;
+            self.pending = false;
+        }
+    }
+
+    pub(super) fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(extension: &str, prefixes: &[&str], code: &str) -> usize {
+        let prefixes: Vec<String> = prefixes.iter().map(|prefix| prefix.to_string()).collect();
+        let mut logical = LogicalLines::new(extension, &prefixes).unwrap();
+        for line in code.lines() {
+            logical.add(line);
+        }
+        logical.count()
+    }
+
+    #[test]
+    fn test_terminated_statements() {
+        // Header, two statements on one line, and a loop whose `;`s are inside parentheses
+        let c = "int main() {\n    int a = 1; int b = 2;\n    for (int i = 0; i < 3; i++) {\n        a += i;\n    }\n    printf(\"%d;\", a); // done;\n}\n";
+        assert_eq!(count("c", &["//"], c), 6);
+
+        // A call spread over several lines is one statement
+        let js = "const total = sum(\n    1,\n    2\n);\n";
+        assert_eq!(count("js", &["//"], js), 1);
+    }
+
+    #[test]
+    fn test_line_based_statements() {
+        let python = "def f(x):\n    a = 1; b = 2\n    return g(\n        a,\n        b,\n    )\nprint('#;')  # note; here\n";
+        assert_eq!(count("py", &["#"], python), 5);
+
+        let shell = "if true; then\n    echo hi \\\n        there\nfi\n";
+        assert_eq!(count("sh", &["#"], shell), 3);
+
+        assert!(LogicalLines::new("json", &[]).is_none());
+    }
+}
@@ -1,11 +1,28 @@
-use std::collections::HashMap;
-use std::fs;
-use std::io::{BufRead, BufReader};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::ops::{Deref, Range};
 use std::path::Path;
-use crate::utils::errors::Result;
-use crate::core::types::{CodeStats, FileStats};
+use memmap2::Mmap;
+use crate::core::stats::complexity::languages::blocks::{BlockTracker, BraceScanner};
+use crate::core::stats::complexity::languages::resolve_extension;
+use crate::core::detector::extension_key;
+use crate::core::plugins;
+use crate::utils::compression::{self, DEFAULT_MAX_DECOMPRESSED_SIZE};
+use crate::utils::config::DocumentationConfig;
+use crate::utils::encoding;
+use crate::utils::errors::{HowManyError, Result};
+use crate::core::types::{ClassifiedLine, CodeStats, FileStats, LineKind, LineLengthStats};
 use crate::core::stats::{StatsCalculator, AggregatedStats};
 
+mod embedded;
+mod logical;
+mod tokens;
+use logical::LogicalLines;
+use tokens::measure_text;
+
+/// Files at least this large are memory-mapped instead of read into a buffer
+pub const MMAP_MIN_BYTES: u64 = 1024 * 1024;
+
 #[derive(Debug, Clone)]
 struct CommentPattern {
     single_line: Vec<String>,
@@ -14,9 +31,163 @@ struct CommentPattern {
     doc_patterns: Vec<String>, // JSDoc, rustdoc, etc.
 }
 
+/// Classification of one line while classifying a file, with the code part
+/// of a code line (before any block comment) for counting statements
+struct LineClass<'t> {
+    kind: LineKind,
+    inline_comment: bool,
+    code: Option<&'t str>,
+}
+
+impl LineClass<'_> {
+    fn new(kind: LineKind) -> Self {
+        Self { kind, inline_comment: false, code: None }
+    }
+}
+
+/// Languages whose block comments nest, so `/* a /* b */ c */` is one comment
+const NESTED_COMMENT_EXTENSIONS: &[&str] = &["rs", "swift", "hs", "lhs", "scala", "kt", "kts", "ml", "mli", "dart"];
+
+/// Fixed-form languages, where an indicator character in one column makes the
+/// whole line a comment: `(extension, zero-based column, indicators)`. COBOL's
+/// indicator area is column 7, after the sequence numbers; fixed-form Fortran
+/// comments start in column 1, which a statement never does.
+const FIXED_FORM_COMMENTS: &[(&str, usize, &str)] = &[
+    ("cob", 6, "*/"), ("cbl", 6, "*/"), ("cpy", 6, "*/"),
+    ("f", 0, "Cc*!"), ("for", 0, "Cc*!"), ("f77", 0, "Cc*!"), ("ftn", 0, "Cc*!"),
+];
+
+/// Whether `line` is a comment by the fixed-form column rule of `extension`
+fn is_fixed_form_comment(line: &str, extension: &str) -> bool {
+    FIXED_FORM_COMMENTS.iter().any(|(fixed_extension, column, indicators)| {
+        *fixed_extension == extension && line.as_bytes().get(*column).is_some_and(|byte| indicators.as_bytes().contains(byte))
+    })
+}
+
+/// Pattern for extensions without comment syntax: every non-blank line is code
+static NO_COMMENTS: CommentPattern = CommentPattern {
+    single_line: Vec::new(),
+    multi_line_start: Vec::new(),
+    multi_line_end: Vec::new(),
+    doc_patterns: Vec::new(),
+};
+
+/// Contents of a file on disk, mapped rather than copied when it is large
+enum SourceBytes {
+    Mapped(Mmap),
+    Read(Vec<u8>),
+}
+
+impl SourceBytes {
+    fn open(path: &Path, file_size: u64) -> Result<Self> {
+        if file_size < MMAP_MIN_BYTES {
+            return Ok(Self::Read(fs::read(path)?));
+        }
+        let file = File::open(path)?;
+        // SAFETY: the map is only read. A file truncated by another process while it
+        // is counted can fault, the same trade-off grep tools make for large files.
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self::Mapped(map))
+    }
+}
+
+/// Comment nesting depth after scanning `text`, which starts `depth` levels
+/// deep. Scanning stops when the outermost comment closes.
+fn nested_comment_depth(text: &str, start: &str, end: &str, mut depth: usize) -> usize {
+    let mut rest = text;
+    loop {
+        let next_start = rest.find(start);
+        let next_end = rest.find(end);
+        let (position, length) = match (next_start, next_end) {
+            (Some(s), Some(e)) if s < e => {
+                depth += 1;
+                (s, start.len())
+            }
+            (Some(s), None) => {
+                depth += 1;
+                (s, start.len())
+            }
+            (_, Some(e)) => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return 0;
+                }
+                (e, end.len())
+            }
+            (None, None) => return depth,
+        };
+        rest = &rest[position + length..];
+    }
+}
+
+/// Byte offset of the first `pattern` in `line` that isn't inside a string
+/// literal. A quote without a closing quote on the line, like a Rust lifetime,
+/// doesn't start a string.
+fn find_outside_strings(line: &str, pattern: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index..].starts_with(pattern.as_bytes()) {
+            return Some(index);
+        }
+        let byte = bytes[index];
+        if matches!(byte, b'"' | b'\'' | b'`') {
+            if let Some(length) = string_literal_length(&bytes[index + 1..], byte) {
+                index += length + 2;
+                continue;
+            }
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Length of a string literal's contents up to its unescaped closing `quote`
+fn string_literal_length(rest: &[u8], quote: u8) -> Option<usize> {
+    let mut index = 0;
+    while index < rest.len() {
+        match rest[index] {
+            b'\\' => index += 2,
+            byte if byte == quote => return Some(index),
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+/// Whether a code line ends with a single-line comment (`let x = 5; // why`).
+/// The comment marker must follow whitespace, so `a//b` or `$#` aren't comments.
+fn has_trailing_comment(line: &str, pattern: &CommentPattern) -> bool {
+    pattern.single_line.iter().any(|marker| {
+        let mut offset = 0;
+        while let Some(index) = find_outside_strings(&line[offset..], marker) {
+            let index = offset + index;
+            if index > 0 && line[..index].ends_with(char::is_whitespace) {
+                return true;
+            }
+            offset = index + marker.len();
+        }
+        false
+    })
+}
+
+impl Deref for SourceBytes {
+    type Target = [u8];
+    
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(map) => map,
+            Self::Read(contents) => contents,
+        }
+    }
+}
+
 pub struct CodeCounter {
     comment_patterns: HashMap<String, CommentPattern>,
     stats_calculator: StatsCalculator,
+    max_decompressed_size: u64,
+    max_file_bytes: Option<u64>,
+    documentation: DocumentationConfig,
 }
 
 impl CodeCounter {
@@ -72,6 +243,8 @@ impl CodeCounter {
         comment_patterns.insert("cxx".to_string(), c_pattern.clone());
         comment_patterns.insert("h".to_string(), c_pattern.clone());
         comment_patterns.insert("hpp".to_string(), c_pattern.clone());
+        comment_patterns.insert("cu".to_string(), c_pattern.clone());
+        comment_patterns.insert("cuh".to_string(), c_pattern.clone());
         
         // C# patterns
         comment_patterns.insert("cs".to_string(), CommentPattern {
@@ -332,6 +505,124 @@ impl CodeCounter {
             doc_patterns: vec!["///".to_string(), "//!".to_string()],
         });
         
+        // Terraform / HCL patterns
+        let hcl_pattern = CommentPattern {
+            single_line: vec!["#".to_string(), "//".to_string()],
+            multi_line_start: vec!["/*".to_string()],
+            multi_line_end: vec!["*/".to_string()],
+            doc_patterns: vec![],
+        };
+        comment_patterns.insert("tf".to_string(), hcl_pattern.clone());
+        comment_patterns.insert("tfvars".to_string(), hcl_pattern.clone());
+        comment_patterns.insert("hcl".to_string(), hcl_pattern);
+        
+        // Protocol Buffers patterns
+        comment_patterns.insert("proto".to_string(), CommentPattern {
+            single_line: vec!["//".to_string()],
+            multi_line_start: vec!["/*".to_string()],
+            multi_line_end: vec!["*/".to_string()],
+            doc_patterns: vec!["///".to_string(), "/**".to_string()],
+        });
+        
+        // GraphQL patterns (block strings are descriptions, its doc comments)
+        let graphql_pattern = CommentPattern {
+            single_line: vec!["#".to_string()],
+            multi_line_start: vec!["\"\"\"".to_string()],
+            multi_line_end: vec!["\"\"\"".to_string()],
+            doc_patterns: vec!["\"\"\"".to_string()],
+        };
+        comment_patterns.insert("graphql".to_string(), graphql_pattern.clone());
+        comment_patterns.insert("gql".to_string(), graphql_pattern);
+        
+        // Nix patterns
+        comment_patterns.insert("nix".to_string(), CommentPattern {
+            single_line: vec!["#".to_string()],
+            multi_line_start: vec!["/*".to_string()],
+            multi_line_end: vec!["*/".to_string()],
+            doc_patterns: vec!["/**".to_string()],
+        });
+        
+        // COBOL patterns (`*>` in free format and after code; fixed-format
+        // comment lines are recognized by their indicator column)
+        let cobol_pattern = CommentPattern {
+            single_line: vec!["*>".to_string()],
+            multi_line_start: vec![],
+            multi_line_end: vec![],
+            doc_patterns: vec![],
+        };
+        for extension in ["cob", "cbl", "cpy"] {
+            comment_patterns.insert(extension.to_string(), cobol_pattern.clone());
+        }
+        
+        // Fortran patterns (fixed-form `C` and `*` comment lines are recognized by column)
+        let fortran_pattern = CommentPattern {
+            single_line: vec!["!".to_string()],
+            multi_line_start: vec![],
+            multi_line_end: vec![],
+            doc_patterns: vec!["!>".to_string(), "!!".to_string()],
+        };
+        for extension in ["f", "for", "f77", "ftn", "f90", "f95", "f03", "f08"] {
+            comment_patterns.insert(extension.to_string(), fortran_pattern.clone());
+        }
+        
+        // Ada patterns
+        let ada_pattern = CommentPattern {
+            single_line: vec!["--".to_string()],
+            multi_line_start: vec![],
+            multi_line_end: vec![],
+            doc_patterns: vec![],
+        };
+        for extension in ["adb", "ads", "ada"] {
+            comment_patterns.insert(extension.to_string(), ada_pattern.clone());
+        }
+        
+        // Pascal / Delphi patterns
+        let pascal_pattern = CommentPattern {
+            single_line: vec!["//".to_string()],
+            multi_line_start: vec!["{".to_string(), "(*".to_string()],
+            multi_line_end: vec!["}".to_string(), "*)".to_string()],
+            doc_patterns: vec!["///".to_string(), "{**".to_string(), "(**".to_string()],
+        };
+        for extension in ["pas", "dpr", "lpr"] {
+            comment_patterns.insert(extension.to_string(), pascal_pattern.clone());
+        }
+        
+        // Assembly patterns: GNU as takes `#` (x86), `//` (AArch64) and `/* */`,
+        // NASM and MASM `;`
+        comment_patterns.insert("s".to_string(), CommentPattern {
+            single_line: vec!["#".to_string(), "//".to_string(), ";".to_string()],
+            multi_line_start: vec!["/*".to_string()],
+            multi_line_end: vec!["*/".to_string()],
+            doc_patterns: vec![],
+        });
+        comment_patterns.insert("asm".to_string(), CommentPattern {
+            single_line: vec![";".to_string()],
+            multi_line_start: vec![],
+            multi_line_end: vec![],
+            doc_patterns: vec![";;".to_string()],
+        });
+        
+        // Verilog / SystemVerilog patterns
+        let verilog_pattern = CommentPattern {
+            single_line: vec!["//".to_string()],
+            multi_line_start: vec!["/*".to_string()],
+            multi_line_end: vec!["*/".to_string()],
+            doc_patterns: vec!["///".to_string(), "/**".to_string()],
+        };
+        for extension in ["v", "vh", "sv", "svh"] {
+            comment_patterns.insert(extension.to_string(), verilog_pattern.clone());
+        }
+        
+        // VHDL patterns (block comments since VHDL-2008)
+        let vhdl_pattern = CommentPattern {
+            single_line: vec!["--".to_string()],
+            multi_line_start: vec!["/*".to_string()],
+            multi_line_end: vec!["*/".to_string()],
+            doc_patterns: vec!["--!".to_string()],
+        };
+        comment_patterns.insert("vhd".to_string(), vhdl_pattern.clone());
+        comment_patterns.insert("vhdl".to_string(), vhdl_pattern);
+        
         // YAML patterns (comments only)
         comment_patterns.insert("yaml".to_string(), CommentPattern {
             single_line: vec!["#".to_string()],
@@ -346,6 +637,23 @@ impl CodeCounter {
             doc_patterns: vec!["##".to_string()],
         });
         
+        // Build file patterns, keyed by language as these files have no extension of their own
+        let build_file_pattern = CommentPattern {
+            single_line: vec!["#".to_string()],
+            multi_line_start: vec![],
+            multi_line_end: vec![],
+            doc_patterns: vec!["##".to_string()],
+        };
+        comment_patterns.insert("makefile".to_string(), build_file_pattern.clone());
+        comment_patterns.insert("dockerfile".to_string(), build_file_pattern);
+        // CMake also has bracket comments (`#[[ ... ]]`)
+        comment_patterns.insert("cmake".to_string(), CommentPattern {
+            single_line: vec!["#".to_string()],
+            multi_line_start: vec!["#[[".to_string()],
+            multi_line_end: vec!["]]".to_string()],
+            doc_patterns: vec!["#[[.rst:".to_string()],
+        });
+        
         // TOML patterns
         comment_patterns.insert("toml".to_string(), CommentPattern {
             single_line: vec!["#".to_string()],
@@ -506,71 +814,302 @@ impl CodeCounter {
             doc_patterns: vec![],
         });
         
+        // Languages added by plugins
+        for language in plugins::language_definitions() {
+            let pattern = CommentPattern {
+                single_line: language.line_comments.clone(),
+                multi_line_start: language.block_comments.iter().map(|(start, _)| start.clone()).collect(),
+                multi_line_end: language.block_comments.iter().map(|(_, end)| end.clone()).collect(),
+                doc_patterns: language.doc_comments.clone(),
+            };
+            for extension in &language.extensions {
+                comment_patterns.insert(extension.clone(), pattern.clone());
+            }
+        }
+        
         Self { 
             comment_patterns,
             stats_calculator: StatsCalculator::new(),
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+            max_file_bytes: None,
+            documentation: DocumentationConfig::default(),
         }
     }
 
+    /// Limit on the decompressed contents of `.gz` files
+    pub fn with_max_decompressed_size(mut self, max_size: u64) -> Self {
+        self.max_decompressed_size = max_size;
+        self
+    }
+
+    /// Refuse files larger than `max_bytes` before reading them, so a
+    /// minified bundle or data dump can't stall the count
+    pub fn with_max_file_bytes(mut self, max_bytes: Option<u64>) -> Self {
+        self.max_file_bytes = max_bytes;
+        self
+    }
+
+    /// How prose files (Markdown, reStructuredText) add to the documentation totals
+    pub fn with_documentation(mut self, documentation: DocumentationConfig) -> Self {
+        self.documentation = documentation;
+        self
+    }
+
+    /// Count lines in a file. Single-file gzip sources (e.g. `dump.sql.gz`) are
+    /// decompressed and counted as the language of the inner file; the reported
+    /// size stays the size on disk. Files of `MMAP_MIN_BYTES` or more are
+    /// memory-mapped, so UTF-8 sources are counted without being copied.
     pub fn count_file(&self, path: &Path) -> Result<FileStats> {
-        let file = fs::File::open(path)?;
-        let reader = BufReader::new(file);
+        let file_size = fs::metadata(path)?.len();
+        self.check_file_size(path, file_size)?;
         
-        let extension = path.extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+        if let Some(inner_path) = compression::decompressed_path(path) {
+            let contents = compression::read_gzip(path, self.max_decompressed_size)?;
+            return self.count_decoded(path, &contents, &Self::extension_of(&inner_path), file_size);
+        }
         
-        // Special handling for Markdown files
-        if extension == "md" {
-            let metadata = fs::metadata(path)?;
-            let file_size = metadata.len();
-            return self.count_markdown_file(reader, file_size);
+        self.count_decoded(path, &SourceBytes::open(path, file_size)?, &Self::extension_of(path), file_size)
+    }
+    
+    /// Count lines in contents already in memory, such as an archive entry,
+    /// as the language of `path`
+    pub fn count_contents(&self, path: &Path, contents: &[u8]) -> Result<FileStats> {
+        self.check_file_size(path, contents.len() as u64)?;
+        self.count_decoded(path, contents, &Self::extension_of(path), contents.len() as u64)
+    }
+    
+    /// Classification of every line of a file, exactly as `count_file` counts
+    /// them: one entry per line, in order, with 1-based line numbers
+    pub fn classify_file(&self, path: &Path) -> Result<Vec<ClassifiedLine>> {
+        let file_size = fs::metadata(path)?.len();
+        self.check_file_size(path, file_size)?;
+        
+        if let Some(inner_path) = compression::decompressed_path(path) {
+            let contents = compression::read_gzip(path, self.max_decompressed_size)?;
+            return self.classify_decoded(path, &contents, &Self::extension_of(&inner_path), file_size);
         }
         
-        let mut total_lines = 0;
-        let mut code_lines = 0;
-        let mut comment_lines = 0;
-        let mut blank_lines = 0;
-        let mut doc_lines = 0;
-        
-        let comment_pattern = self.comment_patterns.get(&extension).cloned().unwrap_or_else(|| {
-            CommentPattern {
-                single_line: vec![],
-                multi_line_start: vec![],
-                multi_line_end: vec![],
-                doc_patterns: vec![],
+        self.classify_decoded(path, &SourceBytes::open(path, file_size)?, &Self::extension_of(path), file_size)
+    }
+    
+    /// Classify contents already in memory as the language of `path`
+    pub fn classify_contents(&self, path: &Path, contents: &[u8]) -> Result<Vec<ClassifiedLine>> {
+        self.check_file_size(path, contents.len() as u64)?;
+        self.classify_decoded(path, contents, &Self::extension_of(path), contents.len() as u64)
+    }
+    
+    /// Fail with `FileTooLarge` if `size` is over the `with_max_file_bytes` limit
+    pub fn check_file_size(&self, path: &Path, size: u64) -> Result<()> {
+        match self.max_file_bytes {
+            Some(limit) if size > limit => Err(HowManyError::file_too_large(path.display().to_string(), size, limit)),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Decode contents of any encoding and count them, recording a non-UTF-8 encoding.
+    /// Binary contents under a source-like name (`data`, `script`) are refused.
+    /// Extensions shared by several languages (`.m`) are resolved from the contents.
+    fn count_decoded(&self, path: &Path, contents: &[u8], extension: &str, file_size: u64) -> Result<FileStats> {
+        if encoding::looks_binary(contents) {
+            return Err(HowManyError::binary_content(path.display().to_string(), file_size));
+        }
+        let decoded = encoding::decode(contents);
+        let counted_extension = resolve_extension(extension, decoded.text.lines());
+        let mut stats = self.count_lines(&decoded.text, counted_extension, file_size);
+        stats.encoding = decoded.encoding.map(str::to_string);
+        stats.language = (counted_extension != extension).then(|| counted_extension.to_string());
+        stats.metrics = plugins::measure(counted_extension, &decoded.text);
+        Ok(stats)
+    }
+    
+    fn classify_decoded(&self, path: &Path, contents: &[u8], extension: &str, file_size: u64) -> Result<Vec<ClassifiedLine>> {
+        if encoding::looks_binary(contents) {
+            return Err(HowManyError::binary_content(path.display().to_string(), file_size));
+        }
+        let decoded = encoding::decode(contents);
+        let extension = resolve_extension(extension, decoded.text.lines());
+        let mut lines = Vec::new();
+        self.classify_with_regions(&decoded.text, extension, |_, text, class| {
+            lines.push(ClassifiedLine {
+                number: lines.len() + 1,
+                kind: class.kind,
+                inline_comment: class.inline_comment,
+                text: text.to_string(),
+            });
+        });
+        Ok(lines)
+    }
+    
+    /// Count the Rust `#[cfg(test)]` items of a source file on their own, so they
+    /// can be moved from the production to the test bucket. `None` for other
+    /// languages and for files without inline tests.
+    pub fn count_inline_tests(&self, path: &Path) -> Result<Option<FileStats>> {
+        if Self::extension_of(path) != "rs" {
+            return Ok(None);
+        }
+        self.count_inline_tests_in(path, &fs::read(path)?)
+    }
+    
+    /// [`count_inline_tests`](Self::count_inline_tests) for contents already in memory
+    pub fn count_inline_tests_in(&self, path: &Path, contents: &[u8]) -> Result<Option<FileStats>> {
+        let source = encoding::decode(contents).text;
+        if Self::extension_of(path) != "rs" || !source.contains("#[cfg(test)]") {
+            return Ok(None);
+        }
+        
+        let lines: Vec<&str> = source.lines().collect();
+        let test_source: String = rust_test_items(&lines)
+            .into_iter()
+            .flat_map(|range| lines[range].iter().map(|line| format!("{}\n", line)))
+            .collect();
+        if test_source.is_empty() {
+            return Ok(None);
+        }
+        
+        Ok(Some(self.count_lines(&test_source, "rs", test_source.len() as u64)))
+    }
+    
+    /// Lowercased extension, or language of a build file named by convention
+    fn extension_of(path: &Path) -> String {
+        extension_key(path).unwrap_or_default().to_lowercase()
+    }
+    
+    /// Count the lines of `text` by their classification. Blocks of other
+    /// languages embedded in the file are also counted on their own, in `embedded`.
+    fn count_lines(&self, text: &str, extension: &str, file_size: u64) -> FileStats {
+        let mut stats = FileStats { file_size, ..FileStats::default() };
+        let mut logical_lines = self.logical_lines(extension);
+        let mut embedded: BTreeMap<String, EmbeddedLines> = BTreeMap::new();
+        
+        self.classify_with_regions(text, extension, |line_extension, text, line| {
+            add_line(&mut stats, text, &line);
+            if line_extension != extension {
+                embedded
+                    .entry(line_extension.to_string())
+                    .or_insert_with(|| EmbeddedLines { stats: FileStats::default(), logical_lines: self.logical_lines(line_extension) })
+                    .add(text, &line);
+            } else if let (Some(logical_lines), Some(code)) = (logical_lines.as_mut(), line.code) {
+                logical_lines.add(code);
+            }
+        });
+        
+        stats.logical_lines = logical_lines.map_or(0, |logical_lines| logical_lines.count());
+        let measures = measure_text(text);
+        stats.characters = measures.characters;
+        stats.non_whitespace_characters = measures.non_whitespace_characters;
+        stats.tokens = measures.tokens;
+        for (embedded_extension, lines) in embedded {
+            let mut embedded_stats = lines.stats;
+            embedded_stats.logical_lines = lines.logical_lines.map_or(0, |logical_lines| logical_lines.count());
+            stats.logical_lines += embedded_stats.logical_lines;
+            stats.embedded.insert(embedded_extension, embedded_stats);
+        }
+        stats
+    }
+    
+    /// Statement counter for `extension`, if it has statements
+    fn logical_lines(&self, extension: &str) -> Option<LogicalLines<'_>> {
+        let comment_pattern = self.comment_patterns.get(extension).unwrap_or(&NO_COMMENTS);
+        LogicalLines::new(extension, &comment_pattern.single_line)
+    }
+    
+    /// Classify each line of `text` like `classify_lines`, with the blocks of
+    /// other languages embedded in it (`<script>` and `<style>` in HTML, Vue and
+    /// Svelte files, fenced code in Markdown, chunks of literate programs)
+    /// classified as their own language.
+    /// `visit` also gets the extension a line counts toward: its block's, or
+    /// `extension` for the rest of the file.
+    fn classify_with_regions<'t>(&self, text: &'t str, extension: &str, mut visit: impl FnMut(&str, &'t str, LineClass<'t>)) {
+        let Some(host_language) = embedded::host_language(extension) else {
+            return self.classify_lines(text.lines(), extension, |text, class| visit(extension, text, class));
+        };
+        let lines: Vec<&'t str> = text.lines().collect();
+        let regions = embedded::regions(extension, &lines, |language| self.comment_patterns.contains_key(language));
+        if regions.is_empty() {
+            return self.classify_lines(lines.into_iter(), extension, |text, class| visit(extension, text, class));
+        }
+        
+        let mut region_of = vec![None; lines.len()];
+        let mut classes: Vec<Option<LineClass<'t>>> = (0..lines.len()).map(|_| None).collect();
+        for (region_index, region) in regions.iter().enumerate() {
+            region_of[region.lines.clone()].fill(Some(region_index));
+            let mut indexes = region.lines.clone();
+            self.classify_lines(lines[region.lines.clone()].iter().map(|line| &line[region.prefix..]), &region.extension, |_, class| {
+                if let Some(index) = indexes.next() {
+                    classes[index] = Some(class);
+                }
+            });
+        }
+        // The rest of the file is classified as one text, so a comment can span a block
+        let host_indexes: Vec<usize> = (0..lines.len()).filter(|index| region_of[*index].is_none()).collect();
+        let mut indexes = host_indexes.iter();
+        self.classify_lines(host_indexes.iter().map(|index| lines[*index]), host_language, |_, class| {
+            if let Some(index) = indexes.next() {
+                classes[*index] = Some(class);
             }
         });
         
+        for (index, class) in classes.into_iter().enumerate() {
+            let line_extension = region_of[index].map_or(extension, |region: usize| regions[region].extension.as_str());
+            if let Some(class) = class {
+                visit(line_extension, lines[index], class);
+            }
+        }
+    }
+    
+    /// Classify each line, calling `visit` with the line and its class.
+    /// Lines are borrowed slices of the text, so no line is copied however large
+    /// the file is.
+    fn classify_lines<'t>(&self, lines: impl Iterator<Item = &'t str>, extension: &str, mut visit: impl FnMut(&'t str, LineClass<'t>)) {
+        // Special handling for Markdown files
+        if extension == "md" {
+            return self.classify_markdown_lines(lines, visit);
+        }
+        
+        let comment_pattern = self.comment_patterns.get(extension).unwrap_or(&NO_COMMENTS);
+        
+        let nested_comments = NESTED_COMMENT_EXTENSIONS.contains(&extension);
         let mut in_multi_line_comment = false;
         let mut in_doc_comment = false;
-        let mut multi_line_end_pattern = String::new();
+        let mut multi_line_start_pattern = "";
+        let mut multi_line_end_pattern = "";
+        let mut comment_depth = 0;
         
-        for line in reader.lines() {
-            let line = line?;
-            total_lines += 1;
-            
+        for line in lines {
             let trimmed = line.trim();
             
             if trimmed.is_empty() {
-                blank_lines += 1;
+                visit(line, LineClass::new(LineKind::Blank));
                 continue;
             }
             
+            if !in_multi_line_comment && is_fixed_form_comment(line, extension) {
+                visit(line, LineClass::new(LineKind::Comment));
+                continue;
+            }
+            
+            // Part of the line inside the comment, where nesting is tracked
+            let mut comment_text = trimmed;
+            // Code before a block comment makes the line code with an inline comment
+            let mut code_before_comment = None;
+            let mut opened_here = false;
+            
             // Check for multi-line comment start/end
             if !in_multi_line_comment {
                 for start_pattern in &comment_pattern.multi_line_start {
-                    if trimmed.contains(start_pattern) {
+                    if let Some(start) = find_outside_strings(trimmed, start_pattern) {
                         in_multi_line_comment = true;
+                        opened_here = true;
+                        code_before_comment = (start > 0).then(|| &trimmed[..start]);
+                        multi_line_start_pattern = start_pattern;
+                        comment_text = &trimmed[start..];
                         // Find corresponding end pattern
                         let start_index = comment_pattern.multi_line_start.iter()
                             .position(|p| p == start_pattern)
                             .unwrap_or(0);
                         multi_line_end_pattern = comment_pattern.multi_line_end
                             .get(start_index)
-                            .cloned()
-                            .unwrap_or_else(|| start_pattern.clone());
+                            .unwrap_or(start_pattern);
                         
                         // Check if it's a documentation comment
                         in_doc_comment = comment_pattern.doc_patterns.iter()
@@ -581,61 +1120,56 @@ impl CodeCounter {
                 }
             }
             
-            if in_multi_line_comment {
+            let class = if in_multi_line_comment {
                 let is_doc_line = in_doc_comment;
-                if trimmed.contains(&multi_line_end_pattern) {
+                let closed = if nested_comments {
+                    comment_depth = nested_comment_depth(comment_text, multi_line_start_pattern, multi_line_end_pattern, comment_depth);
+                    comment_depth == 0
+                } else if opened_here {
+                    // After the start marker, so `"""` doesn't close the docstring it opens
+                    comment_text[multi_line_start_pattern.len()..].contains(multi_line_end_pattern)
+                } else {
+                    trimmed.contains(multi_line_end_pattern)
+                };
+                if closed {
                     in_multi_line_comment = false;
                     in_doc_comment = false;
                 }
                 
-                if is_doc_line {
-                    doc_lines += 1;
+                if let Some(code) = code_before_comment {
+                    LineClass { kind: LineKind::Code, inline_comment: true, code: Some(code) }
+                } else if is_doc_line {
+                    LineClass::new(LineKind::Doc)
                 } else {
-                    comment_lines += 1;
+                    LineClass::new(LineKind::Comment)
                 }
-            } else if self.is_single_line_comment(trimmed, &comment_pattern) {
+            } else if self.is_single_line_comment(trimmed, comment_pattern) {
                 // Check if it's a documentation comment
-                if self.is_doc_comment(trimmed, &comment_pattern) {
-                    doc_lines += 1;
+                if self.is_doc_comment(trimmed, comment_pattern) {
+                    LineClass::new(LineKind::Doc)
                 } else {
-                    comment_lines += 1;
+                    LineClass::new(LineKind::Comment)
                 }
             } else {
-                code_lines += 1;
-            }
+                LineClass {
+                    kind: LineKind::Code,
+                    inline_comment: has_trailing_comment(trimmed, comment_pattern),
+                    code: Some(trimmed),
+                }
+            };
+            visit(line, class);
         }
-        
-        let metadata = fs::metadata(path)?;
-        let file_size = metadata.len();
-        
-        Ok(FileStats {
-            total_lines,
-            code_lines,
-            comment_lines,
-            blank_lines,
-            file_size,
-            doc_lines,
-        })
     }
     
-    fn count_markdown_file(&self, reader: BufReader<fs::File>, file_size: u64) -> Result<FileStats> {
-        let mut total_lines = 0;
-        let mut code_lines = 0; // Code blocks
-        let mut comment_lines = 0; // HTML comments
-        let mut blank_lines = 0;
-        let mut doc_lines = 0; // Markdown content
-        
+    fn classify_markdown_lines<'t>(&self, lines: impl Iterator<Item = &'t str>, mut visit: impl FnMut(&'t str, LineClass<'t>)) {
         let mut in_code_block = false;
         let mut in_html_comment = false;
         
-        for line in reader.lines() {
-            let line = line?;
-            total_lines += 1;
-            
+        for line in lines {
             let trimmed = line.trim();
             
             if trimmed.is_empty() {
-                blank_lines += 1;
+                visit(line, LineClass::new(LineKind::Blank));
                 continue;
             }
             
@@ -645,38 +1179,27 @@ impl CodeCounter {
             }
             
             if in_html_comment {
-                comment_lines += 1;
                 if trimmed.ends_with("-->") {
                     in_html_comment = false;
                 }
+                visit(line, LineClass::new(LineKind::Comment));
                 continue;
             }
             
             // Check for code blocks (fenced with ``` or indented)
             if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
                 in_code_block = !in_code_block;
-                code_lines += 1;
+                visit(line, LineClass::new(LineKind::Code));
                 continue;
             }
             
             if in_code_block || trimmed.starts_with("    ") || trimmed.starts_with("\t") {
-                code_lines += 1;
+                visit(line, LineClass::new(LineKind::Code));
             } else {
                 // Regular markdown content is documentation
-                doc_lines += 1;
+                visit(line, LineClass::new(LineKind::Doc));
             }
         }
-        
-        // File size is passed as parameter from metadata
-        
-        Ok(FileStats {
-            total_lines,
-            code_lines,
-            comment_lines,
-            blank_lines,
-            file_size,
-            doc_lines,
-        })
     }
     
     fn is_single_line_comment(&self, line: &str, pattern: &CommentPattern) -> bool {
@@ -731,6 +1254,13 @@ impl CodeCounter {
         let mut total_blank_lines = 0;
         let mut total_size = 0;
         let mut total_doc_lines = 0;
+        let mut total_inline_comment_lines = 0;
+        let mut total_logical_lines = 0;
+        let mut total_characters = 0;
+        let mut total_non_whitespace_characters = 0;
+        let mut total_tokens = 0;
+        let mut line_lengths = LineLengthStats::default();
+        let mut prose_doc_lines = 0;
         let mut stats_by_extension: HashMap<String, (usize, FileStats)> = HashMap::new();
         
         for (extension, stats) in file_stats {
@@ -740,26 +1270,33 @@ impl CodeCounter {
             total_comment_lines += stats.comment_lines;
             total_blank_lines += stats.blank_lines;
             total_size += stats.file_size;
-            total_doc_lines += stats.doc_lines;
+            total_inline_comment_lines += stats.inline_comment_lines;
+            total_logical_lines += stats.logical_lines;
+            total_characters += stats.characters;
+            total_non_whitespace_characters += stats.non_whitespace_characters;
+            total_tokens += stats.tokens;
+            line_lengths.merge(&stats.line_lengths);
+            if self.documentation.is_prose(&extension) {
+                prose_doc_lines += stats.doc_lines;
+            } else {
+                total_doc_lines += stats.doc_lines;
+            }
             
-            let entry = stats_by_extension.entry(extension).or_insert((0, FileStats {
-                total_lines: 0,
-                code_lines: 0,
-                comment_lines: 0,
-                blank_lines: 0,
-                file_size: 0,
-                doc_lines: 0,
-            }));
+            // Embedded blocks count toward their own language, without adding a file to it
+            for (embedded_extension, embedded_stats) in &stats.embedded {
+                let entry = stats_by_extension.entry(embedded_extension.clone()).or_insert((0, FileStats::default()));
+                add_extension_stats(&mut entry.1, embedded_stats);
+            }
+            let own_stats = stats.embedded.values().fold(stats.clone(), |own_stats, part| own_stats.saturating_sub(part));
             
+            let entry = stats_by_extension.entry(extension).or_insert((0, FileStats::default()));
             entry.0 += 1; // file count
-            entry.1.total_lines += stats.total_lines;
-            entry.1.code_lines += stats.code_lines;
-            entry.1.comment_lines += stats.comment_lines;
-            entry.1.blank_lines += stats.blank_lines;
-            entry.1.file_size += stats.file_size;
-            entry.1.doc_lines += stats.doc_lines;
+            add_extension_stats(&mut entry.1, &own_stats);
         }
         
+        // Prose is weighted as a whole, so rounding doesn't add up across many small files
+        total_doc_lines += self.documentation.prose_doc_lines(prose_doc_lines);
+        
         CodeStats {
             total_files,
             total_lines,
@@ -768,15 +1305,104 @@ impl CodeCounter {
             total_blank_lines,
             total_size,
             total_doc_lines,
+            total_inline_comment_lines,
+            total_logical_lines,
+            total_characters,
+            total_non_whitespace_characters,
+            total_tokens,
+            line_lengths,
             stats_by_extension,
         }
     }
 } 
 
+/// Counts of one embedded language in a file, with its own statement counter
+struct EmbeddedLines<'a> {
+    stats: FileStats,
+    logical_lines: Option<LogicalLines<'a>>,
+}
+
+impl EmbeddedLines<'_> {
+    fn add(&mut self, text: &str, line: &LineClass) {
+        add_line(&mut self.stats, text, line);
+        if let (Some(logical_lines), Some(code)) = (self.logical_lines.as_mut(), line.code) {
+            logical_lines.add(code);
+        }
+        // Characters and bytes include the line ending
+        let measures = measure_text(text);
+        self.stats.characters += measures.characters + 1;
+        self.stats.non_whitespace_characters += measures.non_whitespace_characters;
+        self.stats.tokens += measures.tokens;
+        self.stats.file_size += text.len() as u64 + 1;
+    }
+}
+
+/// Add one classified line to the line counts of `stats`
+fn add_line(stats: &mut FileStats, text: &str, line: &LineClass) {
+    stats.total_lines += 1;
+    stats.line_lengths.add_line(text.chars().count());
+    match line.kind {
+        LineKind::Code => stats.code_lines += 1,
+        LineKind::Comment => stats.comment_lines += 1,
+        LineKind::Doc => stats.doc_lines += 1,
+        LineKind::Blank => stats.blank_lines += 1,
+    }
+    if line.inline_comment {
+        stats.inline_comment_lines += 1;
+    }
+}
+
+/// Add a file's counts to the totals of its extension
+fn add_extension_stats(totals: &mut FileStats, stats: &FileStats) {
+    totals.total_lines += stats.total_lines;
+    totals.code_lines += stats.code_lines;
+    totals.comment_lines += stats.comment_lines;
+    totals.blank_lines += stats.blank_lines;
+    totals.file_size += stats.file_size;
+    totals.doc_lines += stats.doc_lines;
+    totals.inline_comment_lines += stats.inline_comment_lines;
+    totals.logical_lines += stats.logical_lines;
+    totals.characters += stats.characters;
+    totals.non_whitespace_characters += stats.non_whitespace_characters;
+    totals.tokens += stats.tokens;
+    totals.line_lengths.merge(&stats.line_lengths);
+    for (name, value) in &stats.metrics {
+        *totals.metrics.entry(name.clone()).or_insert(0.0) += value;
+    }
+}
+
+/// Line ranges of `#[cfg(test)]` items, from the attribute to the item's closing brace
+fn rust_test_items(lines: &[&str]) -> Vec<Range<usize>> {
+    let mut items = Vec::new();
+    let mut index = 0;
+    
+    while index < lines.len() {
+        if !lines[index].trim_start().starts_with("#[cfg(test)]") {
+            index += 1;
+            continue;
+        }
+        
+        let start = index;
+        let mut tracker = BlockTracker::new(BraceScanner::new());
+        while index < lines.len() {
+            let closed = tracker.feed(lines[index]);
+            index += 1;
+            if closed {
+                break;
+            }
+        }
+        items.push(start..index);
+    }
+    
+    items
+}
+
 /// A wrapper around CodeCounter that adds caching functionality
 pub struct CachedCodeCounter {
     counter: CodeCounter,
     cache: crate::utils::cache::FileCache,
+    caching: bool,
+    shared_cache: Option<crate::utils::shared_cache::SharedCache>,
     cache_hits: usize,
     cache_misses: usize,
 }
@@ -786,19 +1412,87 @@ impl CachedCodeCounter {
         let cache = crate::utils::cache::FileCache::load()
             .unwrap_or_else(|_| crate::utils::cache::FileCache::new());
         
+        Self::with_cache(cache)
+    }
+    
+    /// Counter caching in the file cache of the project at `root` rather than the shared one
+    pub fn for_project(root: &Path) -> Self {
+        let cache = crate::utils::cache::FileCache::load_for_project(root)
+            .unwrap_or_else(|_| crate::utils::cache::FileCache::new());
+        
+        Self::with_cache(cache)
+    }
+    
+    fn with_cache(cache: crate::utils::cache::FileCache) -> Self {
         Self {
             counter: CodeCounter::new(),
             cache,
+            caching: true,
+            shared_cache: None,
             cache_hits: 0,
             cache_misses: 0,
         }
     }
     
+    /// Without caching (`--no-cache`), every file is counted and the cache is left as it is
+    pub fn with_caching(mut self, caching: bool) -> Self {
+        self.caching = caching;
+        self
+    }
+    
+    /// Files the cache keeps at most, evicting the least recently used
+    pub fn with_max_cache_entries(mut self, max_entries: Option<usize>) -> Self {
+        self.cache = self.cache.with_max_entries(max_entries);
+        self
+    }
+    
+    /// Limit on the decompressed contents of `.gz` files
+    pub fn with_max_decompressed_size(mut self, max_size: u64) -> Self {
+        self.counter = self.counter.with_max_decompressed_size(max_size);
+        self
+    }
+    
+    pub fn with_max_file_bytes(mut self, max_bytes: Option<u64>) -> Self {
+        self.counter = self.counter.with_max_file_bytes(max_bytes);
+        self
+    }
+    
+    /// How prose files (Markdown, reStructuredText) add to the documentation totals
+    pub fn with_documentation(mut self, documentation: DocumentationConfig) -> Self {
+        self.counter = self.counter.with_documentation(documentation);
+        self
+    }
+    
+    /// Hash function for the content hashes stored in the cache
+    pub fn with_hash_algorithm(mut self, algorithm: crate::utils::hashing::HashAlgorithm) -> Self {
+        self.cache = self.cache.with_hash_algorithm(algorithm);
+        self
+    }
+    
+    /// Whether cached counts are found by modification time or by contents
+    pub fn with_cache_key(mut self, cache_key: crate::utils::cache::CacheKey) -> Self {
+        self.cache = self.cache.with_cache_key(cache_key);
+        self
+    }
+    
     pub fn count_file(&mut self, path: &Path) -> Result<FileStats> {
+        // Results cached before the size limit was lowered don't bypass it
+        if self.counter.max_file_bytes.is_some() {
+            self.counter.check_file_size(path, fs::metadata(path)?.len())?;
+        }
+        
+        if !self.caching {
+            return self.counter.count_file(path);
+        }
+        
         // Check if file is in cache
-        if let Some(cached_stats) = self.cache.get(path) {
+        if let Some(cached_stats) = self.cache.get(path).cloned() {
             self.cache_hits += 1;
-            return Ok(cached_stats.clone());
+            // Contents counted elsewhere get an entry, so the next run finds the file by its modification time
+            if !self.cache.touch(path) {
+                let _ = self.cache.insert(path.to_path_buf(), cached_stats.clone());
+            }
+            return Ok(cached_stats);
         }
         
         // Count file using the underlying counter
@@ -811,10 +1505,51 @@ impl CachedCodeCounter {
         Ok(file_stats)
     }
     
+    /// Count in-memory contents; these have no file on disk and bypass the cache
+    pub fn count_contents(&self, path: &Path, contents: &[u8]) -> Result<FileStats> {
+        self.counter.count_contents(path, contents)
+    }
+    
+    pub fn count_inline_tests(&self, path: &Path) -> Result<Option<FileStats>> {
+        self.counter.count_inline_tests(path)
+    }
+    
+    /// Classify every line of a file; classifications are never cached
+    pub fn classify_file(&self, path: &Path) -> Result<Vec<ClassifiedLine>> {
+        self.counter.classify_file(path)
+    }
+    
+    pub fn count_inline_tests_in(&self, path: &Path, contents: &[u8]) -> Result<Option<FileStats>> {
+        self.counter.count_inline_tests_in(path, contents)
+    }
+    
     pub fn save_cache(&self) -> Result<()> {
+        if !self.caching {
+            return Ok(());
+        }
         self.cache.save()
     }
     
+    /// Add the records of a shared cache to the local one, returning how many
+    /// were added. The records are pushed back by `push_shared_cache`.
+    pub fn pull_shared_cache(&mut self, shared_cache: crate::utils::shared_cache::SharedCache) -> Result<usize> {
+        if !self.caching {
+            return Ok(0);
+        }
+        let records = shared_cache.pull();
+        self.shared_cache = Some(shared_cache);
+        Ok(records?.map_or(0, |records| self.cache.merge_shared(records)))
+    }
+    
+    /// Upload the counts and analyses of the counted files to the shared cache,
+    /// when files were counted that it didn't have
+    pub fn push_shared_cache(&self) -> Result<()> {
+        match &self.shared_cache {
+            Some(shared_cache) if self.caching && shared_cache.pushes() && self.cache_misses > 0 => shared_cache.push(&self.cache.shared_records()),
+            _ => Ok(()),
+        }
+    }
+    
     pub fn cleanup_cache(&mut self) {
         self.cache.cleanup_missing_files();
     }
@@ -823,6 +1558,11 @@ impl CachedCodeCounter {
         self.cache.size()
     }
     
+    /// Cache of complexity analyses saved along with the counts, `None` without caching
+    pub fn analysis_cache(&self) -> Option<crate::utils::cache::AnalysisCache> {
+        self.caching.then(|| self.cache.analysis_cache())
+    }
+    
     pub fn clear_cache(&mut self) {
         self.cache.clear();
     }
@@ -862,6 +1602,9 @@ impl CachedCodeCounter {
 mod tests {
     use super::*;
     use crate::testing::test_utils::TestProject;
+    use crate::core::stats::aggregation::StatsMerger;
+    use crate::utils::config::ProseDocs;
+    use crate::utils::languages::LanguageRegistry;
     
     #[test]
     fn test_rust_file_counting() {
@@ -906,6 +1649,36 @@ mod tests {
         assert!(stats.doc_lines > 0); // JSDoc comments
     }
     
+    #[test]
+    fn test_inline_rust_tests_counting() {
+        let source = r#"pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "}" inside a string does not close the module
+    #[test]
+    fn adds() {
+        assert_eq!(add(1, 2), 3, "}");
+    }
+}
+
+#[cfg(test)]
+mod fixtures;
+"#;
+        let lines: Vec<&str> = source.lines().collect();
+        assert_eq!(rust_test_items(&lines), vec![4..14, 15..17]);
+        
+        let counter = CodeCounter::new();
+        let stats = counter.count_inline_tests_in(Path::new("lib.rs"), source.as_bytes()).unwrap().unwrap();
+        assert_eq!((stats.total_lines, stats.code_lines, stats.comment_lines, stats.blank_lines), (12, 10, 1, 1));
+        assert!(counter.count_inline_tests_in(Path::new("lib.rs"), b"fn main() {}\n").unwrap().is_none());
+        assert!(counter.count_inline_tests_in(Path::new("lib.py"), source.as_bytes()).unwrap().is_none());
+    }
+    
     #[test]
     fn test_markdown_file_counting() {
         let project = TestProject::new("test_markdown").unwrap();
@@ -976,6 +1749,16 @@ More documentation.
                 blank_lines: 10,
                 file_size: 1000,
                 doc_lines: 15,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
             ("rs".to_string(), FileStats {
                 total_lines: 50,
@@ -984,6 +1767,16 @@ More documentation.
                 blank_lines: 5,
                 file_size: 500,
                 doc_lines: 8,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
             ("py".to_string(), FileStats {
                 total_lines: 80,
@@ -992,6 +1785,16 @@ More documentation.
                 blank_lines: 5,
                 file_size: 800,
                 doc_lines: 12,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
         ];
         
@@ -1017,6 +1820,27 @@ More documentation.
         assert_eq!(python_stats.1.total_lines, 80);
     }
     
+    #[test]
+    fn test_prose_documentation_policy() {
+        let file_stats = vec![
+            ("rs".to_string(), FileStats { total_lines: 20, code_lines: 12, doc_lines: 8, ..FileStats::default() }),
+            ("md".to_string(), FileStats { total_lines: 30, doc_lines: 25, blank_lines: 5, ..FileStats::default() }),
+            ("md".to_string(), FileStats { total_lines: 16, doc_lines: 15, blank_lines: 1, ..FileStats::default() }),
+        ];
+        let counter_with = |prose| CodeCounter::new().with_documentation(DocumentationConfig {
+            prose,
+            ..DocumentationConfig::default()
+        });
+        
+        assert_eq!(counter_with(ProseDocs::Full).aggregate_stats(file_stats.clone()).total_doc_lines, 48);
+        assert_eq!(counter_with(ProseDocs::Weighted).aggregate_stats(file_stats.clone()).total_doc_lines, 18);
+        let excluded = counter_with(ProseDocs::Excluded).aggregate_stats(file_stats);
+        assert_eq!(excluded.total_doc_lines, 8);
+        // The Markdown breakdown still shows its prose
+        assert_eq!(excluded.stats_by_extension["md"].1.doc_lines, 40);
+        assert_eq!(excluded.total_lines, 66);
+    }
+    
     #[test]
     fn test_comment_patterns() {
         let counter = CodeCounter::new();
@@ -1084,6 +1908,14 @@ More documentation.
         assert!(zig_pattern.single_line.contains(&"//".to_string()));
         assert!(zig_pattern.doc_patterns.contains(&"///".to_string()));
         
+        // Test HCL, Protobuf, GraphQL and Nix patterns
+        let hcl_pattern = counter.comment_patterns.get("tf").unwrap();
+        assert!(hcl_pattern.single_line.contains(&"#".to_string()));
+        assert!(hcl_pattern.single_line.contains(&"//".to_string()));
+        assert!(counter.comment_patterns.get("proto").unwrap().doc_patterns.contains(&"///".to_string()));
+        assert!(counter.comment_patterns.get("graphql").unwrap().doc_patterns.contains(&"\"\"\"".to_string()));
+        assert!(counter.comment_patterns.get("nix").unwrap().multi_line_start.contains(&"/*".to_string()));
+        
         // Test Clojure patterns
         assert!(counter.comment_patterns.contains_key("clj"));
         let clj_pattern = counter.comment_patterns.get("clj").unwrap();
@@ -1231,7 +2063,55 @@ def test_function():
             }
         }
     }
-    
+
+    #[test]
+    fn test_gzip_file_counting() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let project = TestProject::new("test_gzip").unwrap();
+        let content = "-- Users table\nCREATE TABLE users (id INT);\n\nINSERT INTO users VALUES (1);\n";
+        let file_path = project.root.join("dump.sql.gz");
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content.as_bytes()).unwrap();
+        std::fs::write(&file_path, encoder.finish().unwrap()).unwrap();
+
+        let stats = CodeCounter::new().count_file(&file_path).unwrap();
+        assert_eq!(stats.total_lines, 4);
+        assert_eq!(stats.code_lines, 2);
+        assert_eq!(stats.comment_lines + stats.doc_lines, 1);
+        assert_eq!(stats.blank_lines, 1);
+        assert_eq!(stats.file_size, std::fs::metadata(&file_path).unwrap().len());
+
+        // Contents larger than the limit are rejected instead of being read into memory
+        let limited = CodeCounter::new().with_max_decompressed_size(16);
+        assert!(limited.count_file(&file_path).is_err());
+    }
+
+    #[test]
+    fn test_skipped_files() {
+        use crate::core::stats::aggregation::{SkipReason, SkippedFile};
+
+        let project = TestProject::new("test_skipped").unwrap();
+        let bundle = project.create_file("bundle.min.js", &"var a=1;".repeat(100)).unwrap();
+        let data = project.root.join("data.js");
+        std::fs::write(&data, b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00").unwrap();
+
+        let counter = CodeCounter::new().with_max_file_bytes(Some(512));
+        let error = counter.count_file(&bundle).unwrap_err();
+        let skipped = SkippedFile::from_error("bundle.min.js".to_string(), &error).unwrap();
+        assert_eq!((skipped.reason, skipped.size), (SkipReason::TooLarge, 800));
+        let error = counter.count_file(&data).unwrap_err();
+        assert_eq!(SkippedFile::from_error("data.js".to_string(), &error).unwrap().reason, SkipReason::Binary);
+
+        // Without a limit the bundle is counted; a missing file is a real failure
+        assert!(CodeCounter::new().count_file(&bundle).is_ok());
+        let error = counter.count_file(&project.root.join("missing.js")).unwrap_err();
+        assert!(SkippedFile::from_error("missing.js".to_string(), &error).is_none());
+    }
+
     #[test]
     fn test_very_long_lines() {
         let project = TestProject::new("test_long_lines").unwrap();
@@ -1248,7 +2128,56 @@ def test_function():
         assert_eq!(stats.comment_lines, 2);
         assert_eq!(stats.code_lines, 1);
     }
-    
+
+    /// A Rust source of at least `min_bytes` and the number of 8-line units it repeats
+    fn large_rust_source(min_bytes: usize) -> (String, usize) {
+        let unit = "/// Adds one\nfn add_one(x: i32) -> i32 {\n    // increment\n    x + 1\n}\n\n/* block\n   comment */\n";
+        let units = min_bytes / unit.len() + 1;
+        (unit.repeat(units), units)
+    }
+
+    #[test]
+    fn test_mapped_large_file_counting() {
+        let project = TestProject::new("test_mapped").unwrap();
+        let (content, units) = large_rust_source(MMAP_MIN_BYTES as usize);
+        let file_path = project.create_file("large.rs", &content).unwrap();
+        let counter = CodeCounter::new();
+
+        // The mapped file counts exactly like the same contents in memory
+        let mapped = counter.count_file(&file_path).unwrap();
+        let buffered = counter.count_contents(&file_path, content.as_bytes()).unwrap();
+        assert_eq!(mapped, buffered);
+
+        assert_eq!(mapped.total_lines, units * 8);
+        assert_eq!(mapped.doc_lines, units);
+        assert_eq!(mapped.code_lines, units * 3);
+        assert_eq!(mapped.comment_lines, units * 3);
+        assert_eq!(mapped.blank_lines, units);
+    }
+
+    /// Throughput on a large file: `cargo test --release -- --ignored bench_large_file --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_large_file_counting() {
+        let project = TestProject::new("bench_large").unwrap();
+        let (content, units) = large_rust_source(256 * 1024 * 1024);
+        let file_path = project.create_file("huge.rs", &content).unwrap();
+        let counter = CodeCounter::new();
+
+        let start = std::time::Instant::now();
+        let stats = counter.count_file(&file_path).unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(stats.total_lines, units * 8);
+        eprintln!(
+            "{} lines, {} MB in {:?} ({:.0} MB/s)",
+            stats.total_lines,
+            content.len() / (1024 * 1024),
+            elapsed,
+            content.len() as f64 / (1024.0 * 1024.0) / elapsed.as_secs_f64()
+        );
+    }
+
     #[test]
     fn test_nested_comments() {
         let project = TestProject::new("test_nested").unwrap();
@@ -1269,6 +2198,226 @@ fn main() {
         assert!(stats.code_lines >= 2); // Function definition and body
     }
     
+    #[test]
+    fn test_nested_block_comments() {
+        let project = TestProject::new("test_nested_block").unwrap();
+        let counter = CodeCounter::new();
+        
+        let rust = "/* Outer comment\n   /* example */ /* another */\n   still outer\n*/\nfn main() {}\n";
+        let stats = counter.count_file(&project.create_file("nested.rs", rust).unwrap()).unwrap();
+        assert_eq!(stats.comment_lines, 4);
+        assert_eq!(stats.code_lines, 1);
+        
+        let haskell = "{- outer\n{- inner -}\nstill outer -}\nmain = pure ()\n";
+        let stats = counter.count_file(&project.create_file("Main.hs", haskell).unwrap()).unwrap();
+        assert_eq!(stats.comment_lines, 3);
+        assert_eq!(stats.code_lines, 1);
+        
+        // C comments don't nest: the first */ ends the comment
+        let c = "/* outer\n/* inner */\nint x;\n";
+        let stats = counter.count_file(&project.create_file("nested.c", c).unwrap()).unwrap();
+        assert_eq!(stats.comment_lines, 2);
+        assert_eq!(stats.code_lines, 1);
+    }
+    
+    #[test]
+    fn test_build_file_comments() {
+        let counter = CodeCounter::new();
+        
+        let makefile = "# Build everything\nall: app\n\napp: main.o\n\t$(CC) -o app main.o # link\n";
+        let makefile_stats = counter.count_contents(Path::new("Makefile"), makefile.as_bytes()).unwrap();
+        assert_eq!((makefile_stats.code_lines, makefile_stats.comment_lines, makefile_stats.blank_lines), (3, 1, 1));
+        
+        let dockerfile = "# syntax=docker/dockerfile:1\nFROM rust:1.80\nRUN cargo build --release\n";
+        let dockerfile_stats = counter.count_contents(Path::new("Dockerfile.dev"), dockerfile.as_bytes()).unwrap();
+        assert_eq!((dockerfile_stats.code_lines, dockerfile_stats.comment_lines), (2, 1));
+        
+        let cmake = "#[[ Builds the\n   library ]]\ncmake_minimum_required(VERSION 3.20)\n# Sources\nadd_library(core src/core.c)\n";
+        let cmake_stats = counter.count_contents(Path::new("CMakeLists.txt"), cmake.as_bytes()).unwrap();
+        assert_eq!((cmake_stats.code_lines, cmake_stats.comment_lines), (2, 3));
+        
+        // Each gets a row of its own, not one for `txt` or no extension
+        let project_stats = counter.aggregate_stats(vec![
+            (extension_key(Path::new("Makefile")).unwrap(), makefile_stats),
+            (extension_key(Path::new("Dockerfile.dev")).unwrap(), dockerfile_stats),
+            (extension_key(Path::new("CMakeLists.txt")).unwrap(), cmake_stats),
+        ]);
+        let mut keys: Vec<&str> = project_stats.stats_by_extension.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["cmake", "dockerfile", "makefile"]);
+    }
+    
+    #[test]
+    fn test_legacy_language_comments() {
+        let project = TestProject::new("test_legacy_comments").unwrap();
+        let counter = CodeCounter::new();
+        
+        // Fixed-format COBOL: `*` in the indicator column after the sequence numbers
+        let cobol = "000100 IDENTIFICATION DIVISION.\n000200* Payroll report\n000300/\n000400 PROCEDURE DIVISION.\n000500     DISPLAY 'HI'. *> greet\n";
+        let stats = counter.count_file(&project.create_file("payroll.cbl", cobol).unwrap()).unwrap();
+        assert_eq!((stats.code_lines, stats.comment_lines, stats.inline_comment_lines), (3, 2, 1));
+        
+        // Fixed-form Fortran comments start in column 1; `!` works anywhere
+        let fortran = "C     Compute the sum\n      PROGRAM SUM\n*     Header\n      X = 1 ! one\n      END\n";
+        let stats = counter.count_file(&project.create_file("sum.f", fortran).unwrap()).unwrap();
+        assert_eq!((stats.code_lines, stats.comment_lines), (3, 2));
+        // In free form a `C` in column 1 is code
+        let stats = counter.count_file(&project.create_file("sum.f90", "CALL run()\n! done\n").unwrap()).unwrap();
+        assert_eq!((stats.code_lines, stats.comment_lines), (1, 1));
+        
+        let ada = "-- Greeting\nprocedure Hello is\nbegin\n   null; -- nothing\nend Hello;\n";
+        let stats = counter.count_file(&project.create_file("hello.adb", ada).unwrap()).unwrap();
+        assert_eq!((stats.code_lines, stats.comment_lines, stats.inline_comment_lines), (4, 1, 1));
+        
+        let pascal = "{ Unit header\n  spanning lines }\nprogram Hello;\n(* block *)\nbegin\n  WriteLn('{ not a comment }'); // greet\nend.\n";
+        let stats = counter.count_file(&project.create_file("hello.pas", pascal).unwrap()).unwrap();
+        assert_eq!((stats.code_lines, stats.comment_lines, stats.inline_comment_lines), (4, 3, 1));
+    }
+
+    #[test]
+    fn test_inline_comments() {
+        let project = TestProject::new("test_inline_comments").unwrap();
+        let counter = CodeCounter::new();
+
+        let rust = "let x = 5; // why\nlet y = 1; /* note */\nlet url = \"http://example.com\";\n// alone\nlet z = 2;\n";
+        let stats = counter.count_file(&project.create_file("inline.rs", rust).unwrap()).unwrap();
+        assert_eq!(stats.code_lines, 4);
+        assert_eq!(stats.comment_lines, 1);
+        // The `//` inside the string literal is not a comment
+        assert_eq!(stats.inline_comment_lines, 2);
+
+        let python = "x = 1  # set x\ncolor = '#fff'\n";
+        let stats = counter.count_file(&project.create_file("inline.py", python).unwrap()).unwrap();
+        assert_eq!(stats.code_lines, 2);
+        assert_eq!(stats.inline_comment_lines, 1);
+    }
+
+    #[test]
+    fn test_logical_lines() {
+        let project = TestProject::new("test_logical_lines").unwrap();
+        let counter = CodeCounter::new();
+
+        // A dense one-liner and a call spread over several lines
+        let rust = "fn main() { let a = 1; let b = 2; }\nlet c = f( // note\n    1,\n    2,\n);\n";
+        let stats = counter.count_file(&project.create_file("logical.rs", rust).unwrap()).unwrap();
+        assert_eq!(stats.code_lines, 5);
+        assert_eq!(stats.logical_lines, 4);
+
+        let python = "import os\n\nvalues = [\n    1,\n    2,\n]\n# comment; not code\nx = 1; y = 2\n";
+        let stats = counter.count_file(&project.create_file("logical.py", python).unwrap()).unwrap();
+        assert_eq!(stats.logical_lines, 4);
+
+        // Data formats have no statements
+        let stats = counter.count_file(&project.create_file("data.json", "{\"a\": 1}\n").unwrap()).unwrap();
+        assert_eq!(stats.logical_lines, 0);
+    }
+
+    #[test]
+    fn test_text_measures() {
+        let project = TestProject::new("test_text_measures").unwrap();
+        let counter = CodeCounter::new();
+
+        let stats = counter.count_file(&project.create_file("measures.rs", "let total = 1;\n").unwrap()).unwrap();
+        assert_eq!(stats.characters, 15);
+        assert_eq!(stats.non_whitespace_characters, 11);
+        // let, total (2), =, 1, ;
+        assert_eq!(stats.tokens, 6);
+
+        let project_stats = counter.aggregate_stats(vec![("a.rs".to_string(), stats.clone()), ("b.rs".to_string(), stats)]);
+        assert_eq!(project_stats.total_characters, 30);
+        assert_eq!(project_stats.total_tokens, 12);
+    }
+
+    #[test]
+    fn test_line_lengths() {
+        let project = TestProject::new("test_line_lengths").unwrap();
+        let counter = CodeCounter::new();
+
+        let source = format!("{}\r\n\n{}\n// é\n", "a".repeat(90), "b".repeat(130));
+        let stats = counter.count_file(&project.create_file("long.rs", &source).unwrap()).unwrap();
+        // Line endings aren't counted, and lengths are in characters
+        assert_eq!(stats.line_lengths.max, 130);
+        assert_eq!(stats.line_lengths.total, 90 + 130 + 4);
+        assert_eq!((stats.line_lengths.over_80, stats.line_lengths.over_100, stats.line_lengths.over_120), (2, 1, 1));
+
+        let project_stats = counter.aggregate_stats(vec![("rs".to_string(), stats.clone()), ("rs".to_string(), stats)]);
+        assert_eq!(project_stats.line_lengths.max, 130);
+        assert_eq!(project_stats.line_lengths.over_80, 4);
+        assert_eq!(project_stats.stats_by_extension["rs"].1.line_lengths.over_120, 2);
+    }
+
+    #[test]
+    fn test_embedded_languages() {
+        let counter = CodeCounter::new();
+
+        let vue = "<template>\n  <!-- greeting -->\n  <p>{{ msg }}</p>\n</template>\n\n<script lang=\"ts\">\n// message\nconst msg: string = 'hi';\n</script>\n\n<style>\np { color: red; }\n</style>\n";
+        let stats = counter.count_contents(Path::new("Hello.vue"), vue.as_bytes()).unwrap();
+        // The template's HTML comment is a doc comment, as in an HTML file
+        assert_eq!((stats.total_lines, stats.code_lines, stats.comment_lines, stats.doc_lines, stats.blank_lines), (13, 9, 1, 1, 2));
+        let script = &stats.embedded["ts"];
+        assert_eq!((script.total_lines, script.code_lines, script.comment_lines, script.logical_lines), (2, 1, 1, 1));
+        assert_eq!(stats.embedded["css"].code_lines, 1);
+
+        let project_stats = counter.aggregate_stats(vec![("vue".to_string(), stats)]);
+        let (vue_files, vue_stats) = &project_stats.stats_by_extension["vue"];
+        assert_eq!((*vue_files, vue_stats.total_lines, vue_stats.code_lines, vue_stats.comment_lines, vue_stats.doc_lines), (1, 10, 7, 0, 1));
+        let (ts_files, ts_stats) = &project_stats.stats_by_extension["ts"];
+        assert_eq!((*ts_files, ts_stats.code_lines), (0, 1));
+        assert_eq!(project_stats.total_lines, 13);
+
+        // Fenced code counts as its language, prose and untagged fences stay Markdown
+        let markdown = "# Usage\n\n```rust\n// Print\nfn main() {}\n```\n\n```\nplain\n```\n";
+        let stats = counter.count_contents(Path::new("README.md"), markdown.as_bytes()).unwrap();
+        let rust = &stats.embedded["rs"];
+        assert_eq!((rust.code_lines, rust.comment_lines), (1, 1));
+        assert_eq!(stats.embedded.len(), 1);
+        assert_eq!(stats.code_lines, 6);
+    }
+
+    #[test]
+    fn test_objective_c_and_matlab_share_m() {
+        let counter = CodeCounter::new();
+
+        let objc = "#import <Foundation/Foundation.h>\n\n// Greets\n@implementation Greeter\n- (void)greet {\n    NSLog(@\"100% hi\");\n}\n@end\n";
+        let stats = counter.count_contents(Path::new("Greeter.m"), objc.as_bytes()).unwrap();
+        assert_eq!((stats.code_lines, stats.comment_lines, stats.blank_lines), (6, 1, 1));
+
+        let matlab = "% Greets\nfunction greet()\n    disp('hi'); // not a comment\nend\n";
+        let stats = counter.count_contents(Path::new("greet.m"), matlab.as_bytes()).unwrap();
+        assert_eq!((stats.code_lines, stats.comment_lines), (3, 1));
+    }
+
+    #[test]
+    fn test_classify_lines() {
+        let project = TestProject::new("test_classify_lines").unwrap();
+        let counter = CodeCounter::new();
+
+        let rust = "/// Docs\nfn main() {\n\n    /* block\n       comment */\n    let x = 5; // why\n}\n";
+        let path = project.create_file("classify.rs", rust).unwrap();
+        let lines = counter.classify_file(&path).unwrap();
+
+        let kinds: Vec<LineKind> = lines.iter().map(|line| line.kind).collect();
+        assert_eq!(kinds, vec![
+            LineKind::Doc,
+            LineKind::Code,
+            LineKind::Blank,
+            LineKind::Comment,
+            LineKind::Comment,
+            LineKind::Code,
+            LineKind::Code,
+        ]);
+        assert_eq!(lines[5].number, 6);
+        assert_eq!(lines[5].text, "    let x = 5; // why");
+        assert!(lines[5].inline_comment);
+        assert!(!lines[1].inline_comment);
+
+        // The classification is what the counts are made of
+        let stats = counter.count_file(&path).unwrap();
+        assert_eq!(stats.total_lines, lines.len());
+        assert_eq!(stats.code_lines, kinds.iter().filter(|kind| **kind == LineKind::Code).count());
+        assert_eq!(stats.inline_comment_lines, lines.iter().filter(|line| line.inline_comment).count());
+    }
+
     #[test]
     fn test_comment_patterns_comprehensive() {
         let counter = CodeCounter::new();
@@ -1279,6 +2428,8 @@ fn main() {
             "cs", "go", "rb", "php", "swift", "kt", "scala", "html", "css", "scss", "sass",
             "md", "yaml", "yml", "json", "toml", "xml", "sh", "bash", "zsh", "fish", "ps1",
             "elm", "jl", "sql", "ex", "exs", "zig", "clj", "cljs", "fs", "fsx", "fsi",
+            "tf", "hcl", "proto", "graphql", "nix", "cob", "cbl", "f", "f90", "adb", "ads", "pas",
+            "s", "asm", "v", "sv", "vhd", "cu", "cuh", "makefile", "dockerfile", "cmake",
         ];
         
         for lang in expected_languages {
@@ -1358,6 +2509,16 @@ fn main() {
             doc_lines: 5,
             blank_lines: 10,
             file_size: 2000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
         stats_by_extension.insert("py".to_string(), (1, FileStats {
             total_lines: 50,
@@ -1366,6 +2527,16 @@ fn main() {
             doc_lines: 2,
             blank_lines: 5,
             file_size: 1000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
         
         let code_stats = CodeStats {
@@ -1377,6 +2548,12 @@ fn main() {
             total_blank_lines: 15,
             total_size: 3000,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
         
         let individual_files = vec![
@@ -1387,6 +2564,16 @@ fn main() {
                 doc_lines: 2,
                 blank_lines: 5,
                 file_size: 1000,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
             ("lib.rs".to_string(), FileStats {
                 total_lines: 50,
@@ -1395,6 +2582,16 @@ fn main() {
                 doc_lines: 3,
                 blank_lines: 5,
                 file_size: 1000,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
             ("script.py".to_string(), FileStats {
                 total_lines: 50,
@@ -1403,6 +2600,16 @@ fn main() {
                 doc_lines: 2,
                 blank_lines: 5,
                 file_size: 1000,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
         ];
         
@@ -1420,6 +2627,80 @@ fn main() {
         assert_eq!(aggregated_stats.metadata.file_count_analyzed, 3);
         assert!(aggregated_stats.metadata.languages_detected.len() >= 2);
     }
+
+    #[test]
+    fn test_complexity_ignores_unanalyzed_languages() {
+        let project = TestProject::new("test_unanalyzed").unwrap();
+        let source = project.create_file("lib.rs", "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n").unwrap();
+        let config = project.create_file("deploy.yaml", &"key: value\n".repeat(5000)).unwrap();
+        let counter = CodeCounter::new();
+
+        let project_stats = |paths: &[&Path]| {
+            let individual_files: Vec<_> = paths
+                .iter()
+                .map(|path| (path.to_string_lossy().to_string(), counter.count_file(path).unwrap()))
+                .collect();
+            let file_stats = individual_files
+                .iter()
+                .map(|(path, stats)| (CodeCounter::extension_of(Path::new(path)), stats.clone()))
+                .collect();
+            counter.calculate_project_stats(&counter.aggregate_stats(file_stats), &individual_files).unwrap()
+        };
+        let with_config = project_stats(&[&source, &config]);
+        let source_only = project_stats(&[&source]);
+
+        assert_eq!(with_config.metadata.complexity_not_analyzed, ["yaml"]);
+        assert!(source_only.metadata.complexity_not_analyzed.is_empty());
+        // 5000 lines of YAML neither dilute nor penalize the Rust function's metrics
+        let (with_config, source_only) = (&with_config.complexity.quality_metrics, &source_only.complexity.quality_metrics);
+        assert_eq!(with_config.code_health_score, source_only.code_health_score);
+        assert_eq!(with_config.maintainability_index, source_only.maintainability_index);
+        assert_eq!(with_config.function_size_health, source_only.function_size_health);
+    }
+    
+    #[test]
+    fn test_group_by_language() {
+        let project = TestProject::new("test_group_by_language").unwrap();
+        let paths = [
+            project.create_file("app.ts", "export function add(a: number, b: number) {\n    return a + b;\n}\n").unwrap(),
+            project.create_file("view.tsx", "// A view\nexport const View = () => <div />;\n").unwrap(),
+            project.create_file("vec.hpp", "#pragma once\nstruct Vec { int x; };\n").unwrap(),
+            project.create_file("vec.cpp", "int length(int x) {\n    return x;\n}\n").unwrap(),
+            project.create_file("deploy.yaml", "key: value\n").unwrap(),
+        ];
+        let counter = CodeCounter::new();
+        let individual_files: Vec<_> = paths
+            .iter()
+            .map(|path| (path.to_string_lossy().to_string(), counter.count_file(path).unwrap()))
+            .collect();
+        let file_stats = individual_files
+            .iter()
+            .map(|(path, stats)| (CodeCounter::extension_of(Path::new(path)), stats.clone()))
+            .collect();
+        let mut stats = counter.calculate_project_stats(&counter.aggregate_stats(file_stats), &individual_files).unwrap();
+        let by_extension = stats.clone();
+        StatsMerger::new().group_by_language(&mut stats, &LanguageRegistry::new()).unwrap();
+
+        let mut languages: Vec<_> = stats.basic.stats_by_extension.keys().map(String::as_str).collect();
+        languages.sort();
+        assert_eq!(languages, ["C++", "TypeScript", "YAML"]);
+        assert_eq!(stats.metadata.languages_detected, ["C++", "TypeScript", "YAML"]);
+        assert_eq!(stats.metadata.complexity_not_analyzed, ["YAML"]);
+
+        let typescript = &stats.basic.stats_by_extension["TypeScript"];
+        let (ts, tsx) = (&by_extension.basic.stats_by_extension["ts"], &by_extension.basic.stats_by_extension["tsx"]);
+        assert_eq!(typescript.file_count, 2);
+        assert_eq!(typescript.code_lines, ts.code_lines + tsx.code_lines);
+        assert_eq!(stats.ratios.file_distribution["TypeScript"], 40.0);
+        assert_eq!(
+            stats.complexity.complexity_by_extension["C++"].function_count,
+            by_extension.complexity.complexity_by_extension.get("cpp").map_or(0, |complexity| complexity.function_count)
+                + by_extension.complexity.complexity_by_extension.get("hpp").map_or(0, |complexity| complexity.function_count)
+        );
+        // Grouping only relabels rows
+        assert_eq!(stats.basic.code_lines, by_extension.basic.code_lines);
+        assert_eq!(stats.ratios.quality_metrics.overall_quality_score, by_extension.ratios.quality_metrics.overall_quality_score);
+    }
     
     #[test]
     fn test_aggregate_stats_functionality() {
@@ -1433,6 +2714,16 @@ fn main() {
                 doc_lines: 5,
                 blank_lines: 10,
                 file_size: 2000,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
             ("rs".to_string(), FileStats {
                 total_lines: 50,
@@ -1441,6 +2732,16 @@ fn main() {
                 doc_lines: 2,
                 blank_lines: 5,
                 file_size: 1000,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
             ("py".to_string(), FileStats {
                 total_lines: 80,
@@ -1449,6 +2750,16 @@ fn main() {
                 doc_lines: 3,
                 blank_lines: 5,
                 file_size: 1500,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
         ];
         
@@ -1533,4 +2844,90 @@ fn main() {
         assert!(stats.code_lines >= 2000); // At least 2 lines per function
         assert!(stats.total_lines >= 4000); // At least 4 lines per iteration
     }
+    
+    /// A `redis-cli` that keeps the value of every key in the file named by
+    /// the server URL, and fails like an unreachable server when its
+    /// directory doesn't exist
+    #[cfg(unix)]
+    fn stub_redis_cli() {
+        use std::os::unix::fs::PermissionsExt;
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        
+        INSTALL.call_once(|| {
+            let bin = std::env::temp_dir().join(format!("howmany-stub-bin-{}", std::process::id()));
+            std::fs::create_dir_all(&bin).unwrap();
+            let script = bin.join("redis-cli");
+            std::fs::write(&script, r#"#!/bin/sh
+while [ $# -gt 0 ]; do
+    case "$1" in
+        -u) store="${2#redis://}"; shift 2 ;;
+        GET|SET)
+            [ -d "$(dirname "$store")" ] || { echo "Could not connect to Redis at $store" >&2; exit 1; }
+            if [ "$1" = GET ]; then cat "$store" 2>/dev/null || echo; else cat > "$store"; fi
+            exit 0 ;;
+        *) shift ;;
+    esac
+done
+"#).unwrap();
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+            let path = std::env::var_os("PATH").unwrap_or_default();
+            let paths = std::iter::once(bin).chain(std::env::split_paths(&path));
+            std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+        });
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_shared_cache_pull_push_and_fallback() {
+        use crate::utils::config::RemoteCacheConfig;
+        use crate::utils::shared_cache::SharedCache;
+        
+        stub_redis_cli();
+        let store = tempfile::tempdir().unwrap();
+        let shared = |records: &Path| SharedCache::new(&RemoteCacheConfig {
+            url: format!("redis://{}", records.display()),
+            name: Some("app".to_string()),
+            ..RemoteCacheConfig::default()
+        }).unwrap();
+        let runner = || CachedCodeCounter::with_cache(crate::utils::cache::FileCache::new());
+        let checkout = |files: &[(&str, &str)]| {
+            let dir = tempfile::tempdir().unwrap();
+            for (name, contents) in files {
+                std::fs::write(dir.path().join(name), contents).unwrap();
+            }
+            dir
+        };
+        let records = store.path().join("records");
+        let main_rs = "fn main() {\n    println!(\"hi\");\n}\n";
+        
+        // The first runner finds nothing and pushes what it counted
+        let first = checkout(&[("main.rs", main_rs), ("app.py", "print('app')\n")]);
+        let mut counter = runner();
+        assert_eq!(counter.pull_shared_cache(shared(&records)).unwrap(), 0);
+        for name in ["main.rs", "app.py"] {
+            counter.count_file(&first.path().join(name)).unwrap();
+        }
+        counter.push_shared_cache().unwrap();
+        
+        // The second reuses main.rs by its contents, and pushes app.py back along with its own lib.rs
+        let second = checkout(&[("main.rs", main_rs), ("lib.rs", "pub mod app;\n")]);
+        let mut counter = runner();
+        assert_eq!(counter.pull_shared_cache(shared(&records)).unwrap(), 2);
+        for name in ["main.rs", "lib.rs"] {
+            counter.count_file(&second.path().join(name)).unwrap();
+        }
+        assert_eq!(counter.cache_stats(), (1, 1));
+        counter.cleanup_cache();
+        counter.push_shared_cache().unwrap();
+        
+        let mut counter = runner();
+        assert_eq!(counter.pull_shared_cache(shared(&records)).unwrap(), 3);
+        
+        // An unreachable cache fails the pull and push, and files are still counted
+        let mut counter = runner();
+        assert!(counter.pull_shared_cache(shared(&store.path().join("down/records"))).is_err());
+        let stats = counter.count_file(&first.path().join("main.rs")).unwrap();
+        assert_eq!((stats.code_lines, counter.cache_stats()), (3, (0, 1)));
+        assert!(counter.push_shared_cache().is_err());
+    }
 } 
\ No newline at end of file
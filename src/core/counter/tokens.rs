@@ -0,0 +1,61 @@
+/// Average characters of a word or number per LLM token; subword vocabularies
+/// split long identifiers into pieces of about this size
+const CHARACTERS_PER_WORD_TOKEN: usize = 4;
+
+/// Character counts and an approximate LLM token count of a text
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) struct TextMeasures {
+    pub characters: usize,
+    pub non_whitespace_characters: usize,
+    pub tokens: usize,
+}
+
+/// Count the characters of `text` and estimate its tokens the way subword
+/// tokenizers split source code: a run of ASCII letters, digits and `_` is one
+/// token per `CHARACTERS_PER_WORD_TOKEN` characters, and every other
+/// non-whitespace character (punctuation, operators, non-ASCII text) is a token.
+/// Whitespace is folded into the neighbouring tokens.
+pub(super) fn measure_text(text: &str) -> TextMeasures {
+    let mut measures = TextMeasures::default();
+    let mut word_length: usize = 0;
+
+    for character in text.chars() {
+        measures.characters += 1;
+        if character.is_ascii_alphanumeric() || character == '_' {
+            measures.non_whitespace_characters += 1;
+            word_length += 1;
+            continue;
+        }
+
+        measures.tokens += word_length.div_ceil(CHARACTERS_PER_WORD_TOKEN);
+        word_length = 0;
+        if !character.is_whitespace() {
+            measures.non_whitespace_characters += 1;
+            measures.tokens += 1;
+        }
+    }
+
+    measures.tokens += word_length.div_ceil(CHARACTERS_PER_WORD_TOKEN);
+    measures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_text() {
+        let measures = measure_text("let total = count_lines(x);\n");
+        assert_eq!(measures.characters, 28);
+        assert_eq!(measures.non_whitespace_characters, 24);
+        // let, total (2), =, count_lines (3), (, x, ), ;
+        assert_eq!(measures.tokens, 11);
+
+        // Characters, not bytes
+        let measures = measure_text("// Grüße");
+        assert_eq!(measures.characters, 8);
+        assert_eq!(measures.tokens, 6);
+
+        assert_eq!(measure_text(""), TextMeasures::default());
+    }
+}
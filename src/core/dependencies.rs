@@ -0,0 +1,417 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Directories of installed or vendored packages and build output, whose
+/// manifests belong to dependencies rather than the project
+const VENDORED_DIRS: &[&str] = &[
+    "node_modules", "bower_components", "vendor", "third_party", "target", "build", "dist",
+    ".venv", "venv", "site-packages", ".tox", ".git",
+];
+
+/// Package ecosystem of a dependency manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ecosystem {
+    /// `Cargo.toml`
+    Cargo,
+    /// `package.json`
+    Npm,
+    /// `go.mod`
+    Go,
+    /// `requirements*.txt` and `pyproject.toml`
+    Python,
+    /// `pom.xml`
+    Maven,
+}
+
+impl Ecosystem {
+    /// Ecosystem of the manifest at `path`, or `None` for other files.
+    /// Python requirement files go by `requirements.txt`, `requirements-dev.txt`,
+    /// `dev-requirements.txt` or any `.txt` in a `requirements/` directory.
+    pub fn of_manifest(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        match file_name {
+            "Cargo.toml" => Some(Self::Cargo),
+            "package.json" => Some(Self::Npm),
+            "go.mod" => Some(Self::Go),
+            "pyproject.toml" => Some(Self::Python),
+            "pom.xml" => Some(Self::Maven),
+            _ if is_requirements_file(path) => Some(Self::Python),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cargo => "Cargo",
+            Self::Npm => "npm",
+            Self::Go => "Go",
+            Self::Python => "Python",
+            Self::Maven => "Maven",
+        }
+    }
+}
+
+/// Dependencies declared in one manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestDependencies {
+    pub path: String,
+    pub ecosystem: Ecosystem,
+    /// Dependencies the project needs to build or run
+    pub direct: usize,
+    /// Dependencies only needed to develop or test it
+    pub dev: usize,
+}
+
+/// Dependency totals of the manifests of one ecosystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EcosystemDependencies {
+    pub ecosystem: Ecosystem,
+    pub manifests: usize,
+    pub direct: usize,
+    pub dev: usize,
+}
+
+/// Dependencies declared in the manifests below the analyzed paths, counted
+/// per manifest: a package required by two manifests counts twice
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyStats {
+    pub direct: usize,
+    pub dev: usize,
+    /// Totals per ecosystem, most dependencies first
+    pub ecosystems: Vec<EcosystemDependencies>,
+    /// Manifests by path
+    pub manifests: Vec<ManifestDependencies>,
+    /// Manifests that could not be read or parsed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unreadable: Vec<String>,
+}
+
+impl DependencyStats {
+    /// Totals of `manifests`
+    pub fn new(mut manifests: Vec<ManifestDependencies>, mut unreadable: Vec<String>) -> Self {
+        manifests.sort_by(|a, b| a.path.cmp(&b.path));
+        manifests.dedup_by(|a, b| a.path == b.path);
+        unreadable.sort();
+        unreadable.dedup();
+
+        let mut ecosystems: BTreeMap<Ecosystem, EcosystemDependencies> = BTreeMap::new();
+        for manifest in &manifests {
+            let totals = ecosystems.entry(manifest.ecosystem).or_insert(EcosystemDependencies {
+                ecosystem: manifest.ecosystem,
+                manifests: 0,
+                direct: 0,
+                dev: 0,
+            });
+            totals.manifests += 1;
+            totals.direct += manifest.direct;
+            totals.dev += manifest.dev;
+        }
+        let mut ecosystems: Vec<_> = ecosystems.into_values().collect();
+        ecosystems.sort_by(|a, b| (b.direct + b.dev).cmp(&(a.direct + a.dev)).then(a.ecosystem.cmp(&b.ecosystem)));
+
+        Self {
+            direct: manifests.iter().map(|manifest| manifest.direct).sum(),
+            dev: manifests.iter().map(|manifest| manifest.dev).sum(),
+            ecosystems,
+            manifests,
+            unreadable,
+        }
+    }
+
+    /// Combine the inventories of several reports; a manifest in more than one is counted once
+    pub fn merge<'a>(stats_list: impl IntoIterator<Item = &'a DependencyStats>) -> Option<DependencyStats> {
+        let mut manifests = Vec::new();
+        let mut unreadable = Vec::new();
+        let mut any = false;
+        for stats in stats_list {
+            any = true;
+            manifests.extend(stats.manifests.iter().cloned());
+            unreadable.extend(stats.unreadable.iter().cloned());
+        }
+        any.then(|| Self::new(manifests, unreadable))
+    }
+}
+
+/// Reads dependency manifests and counts what they declare
+pub struct DependencyScanner;
+
+impl DependencyScanner {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Inventory of the manifests among `paths`; other files are passed over
+    pub fn scan(&self, paths: &[PathBuf]) -> DependencyStats {
+        let mut manifests = Vec::new();
+        let mut unreadable = Vec::new();
+
+        for path in paths {
+            let Some(ecosystem) = Ecosystem::of_manifest(path) else {
+                continue;
+            };
+            let counts = std::fs::read_to_string(path).ok().and_then(|contents| count_dependencies(path, ecosystem, &contents));
+            match counts {
+                Some((direct, dev)) => manifests.push(ManifestDependencies {
+                    path: path.to_string_lossy().to_string(),
+                    ecosystem,
+                    direct,
+                    dev,
+                }),
+                None => unreadable.push(path.to_string_lossy().to_string()),
+            }
+        }
+
+        DependencyStats::new(manifests, unreadable)
+    }
+}
+
+impl Default for DependencyScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `path` lies in a directory of installed packages or build output, such as `node_modules/`
+pub fn is_vendored(path: &Path) -> bool {
+    path.components().any(|component| matches!(component, Component::Normal(name) if name.to_str().is_some_and(|name| VENDORED_DIRS.contains(&name))))
+}
+
+/// Direct and dev dependencies declared in `contents`, or `None` when it doesn't parse
+pub fn count_dependencies(path: &Path, ecosystem: Ecosystem, contents: &str) -> Option<(usize, usize)> {
+    match ecosystem {
+        Ecosystem::Cargo => count_cargo(contents),
+        Ecosystem::Npm => count_npm(contents),
+        Ecosystem::Go => Some((count_go(contents), 0)),
+        Ecosystem::Python if path.file_name().is_some_and(|name| name == "pyproject.toml") => count_pyproject(contents),
+        Ecosystem::Python => {
+            let requirements = count_requirements(contents);
+            Some(if is_dev_name(&path.to_string_lossy()) { (0, requirements) } else { (requirements, 0) })
+        }
+        Ecosystem::Maven => Some(count_maven(contents)),
+    }
+}
+
+/// `[dependencies]` and `[build-dependencies]` are direct, `[dev-dependencies]`
+/// dev, also under `[target.'cfg(...)']`. `[workspace.dependencies]` only
+/// declares versions for the members, so it isn't counted.
+fn count_cargo(contents: &str) -> Option<(usize, usize)> {
+    let manifest: toml::Table = toml::from_str(contents).ok()?;
+    let tables = |table: &toml::Table| {
+        let count = |key: &str| table.get(key).and_then(toml::Value::as_table).map_or(0, toml::Table::len);
+        (count("dependencies") + count("build-dependencies"), count("dev-dependencies"))
+    };
+
+    let (mut direct, mut dev) = tables(&manifest);
+    for target in manifest.get("target").and_then(toml::Value::as_table).into_iter().flat_map(toml::Table::values) {
+        if let Some(target) = target.as_table() {
+            let (target_direct, target_dev) = tables(target);
+            direct += target_direct;
+            dev += target_dev;
+        }
+    }
+    Some((direct, dev))
+}
+
+/// `dependencies`, `peerDependencies` and `optionalDependencies` are direct, `devDependencies` dev
+fn count_npm(contents: &str) -> Option<(usize, usize)> {
+    let manifest: serde_json::Value = serde_json::from_str(contents).ok()?;
+    let count = |key: &str| manifest.get(key).and_then(serde_json::Value::as_object).map_or(0, serde_json::Map::len);
+    Some((count("dependencies") + count("peerDependencies") + count("optionalDependencies"), count("devDependencies")))
+}
+
+/// Modules of the `require` directives, other than those marked `// indirect`
+fn count_go(contents: &str) -> usize {
+    let mut count = 0;
+    let mut in_block = false;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        let requirement = if in_block {
+            if line.starts_with(')') {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim_start();
+            if rest.starts_with('(') {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+
+        let (module, comment) = requirement.split_once("//").unwrap_or((requirement, ""));
+        if !module.trim().is_empty() && comment.trim() != "indirect" {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// PEP 621 `project.dependencies` and the extras of `project.optional-dependencies`
+/// are direct, except extras named for development such as `dev` or `test`.
+/// PEP 735 `dependency-groups` are dev. For Poetry, `tool.poetry.dependencies`
+/// other than `python` are direct and its dev dependencies and groups dev.
+fn count_pyproject(contents: &str) -> Option<(usize, usize)> {
+    let manifest: toml::Table = toml::from_str(contents).ok()?;
+    let array_len = |value: &toml::Value| value.as_array().map_or(0, Vec::len);
+    let table = |value: Option<&toml::Value>| value.and_then(toml::Value::as_table).cloned().unwrap_or_default();
+    let mut direct = 0;
+    let mut dev = 0;
+
+    let project = table(manifest.get("project"));
+    direct += project.get("dependencies").map_or(0, array_len);
+    for (extra, requirements) in table(project.get("optional-dependencies")) {
+        if is_dev_name(&extra) {
+            dev += array_len(&requirements);
+        } else {
+            direct += array_len(&requirements);
+        }
+    }
+    dev += table(manifest.get("dependency-groups")).values().map(array_len).sum::<usize>();
+
+    let poetry = table(table(manifest.get("tool")).get("poetry"));
+    direct += table(poetry.get("dependencies")).keys().filter(|name| *name != "python").count();
+    dev += table(poetry.get("dev-dependencies")).len();
+    for group in table(poetry.get("group")).values() {
+        dev += group.get("dependencies").and_then(toml::Value::as_table).map_or(0, toml::Table::len);
+    }
+    Some((direct, dev))
+}
+
+/// Requirement lines, without comments, blank lines and options such as
+/// `-r base.txt`; editable installs (`-e`) are requirements
+fn count_requirements(contents: &str) -> usize {
+    contents
+        .lines()
+        .map(|line| line.split(" #").next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| !line.starts_with('-') || line.starts_with("-e ") || line.starts_with("--editable"))
+        .count()
+}
+
+/// `<dependency>` elements, those of `test` scope dev. Those under
+/// `<dependencyManagement>` only pin versions and those under `<build>`
+/// belong to plugins, so neither is counted.
+fn count_maven(contents: &str) -> (usize, usize) {
+    let comments = Regex::new(r"(?s)<!--.*?-->").unwrap();
+    let declarations_only = Regex::new(r"(?s)<dependencyManagement>.*?</dependencyManagement>|<build>.*?</build>").unwrap();
+    let dependency = Regex::new(r"(?s)<dependency>(.*?)</dependency>").unwrap();
+    let test_scope = Regex::new(r"<scope>\s*test\s*</scope>").unwrap();
+
+    let contents = comments.replace_all(contents, "");
+    let contents = declarations_only.replace_all(&contents, "");
+    dependency.captures_iter(&contents).fold((0, 0), |(direct, dev), captures| {
+        if test_scope.is_match(&captures[1]) {
+            (direct, dev + 1)
+        } else {
+            (direct + 1, dev)
+        }
+    })
+}
+
+fn is_requirements_file(path: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let Some(stem) = file_name.strip_suffix(".txt") else {
+        return false;
+    };
+    stem.starts_with("requirements") || stem.ends_with("requirements") || path.parent().and_then(Path::file_name).is_some_and(|dir| dir == "requirements")
+}
+
+/// Whether a requirements file or dependency group is named for development, as `requirements-dev.txt` or `[test]`
+fn is_dev_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    let file_name = name.rsplit(['/', '\\']).next().unwrap_or(&name);
+    file_name
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| matches!(word, "dev" | "develop" | "development" | "test" | "tests" | "testing" | "lint" | "docs" | "ci"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(file_name: &str, contents: &str) -> Option<(usize, usize)> {
+        let path = Path::new(file_name);
+        count_dependencies(path, Ecosystem::of_manifest(path).unwrap(), contents)
+    }
+
+    #[test]
+    fn test_manifests_recognized() {
+        assert_eq!(Ecosystem::of_manifest(Path::new("crates/core/Cargo.toml")), Some(Ecosystem::Cargo));
+        assert_eq!(Ecosystem::of_manifest(Path::new("requirements-dev.txt")), Some(Ecosystem::Python));
+        assert_eq!(Ecosystem::of_manifest(Path::new("requirements/base.txt")), Some(Ecosystem::Python));
+        assert_eq!(Ecosystem::of_manifest(Path::new("notes.txt")), None);
+        assert_eq!(Ecosystem::of_manifest(Path::new("package-lock.json")), None);
+        assert!(is_vendored(Path::new("./web/node_modules/react/package.json")));
+        assert!(!is_vendored(Path::new("./web/package.json")));
+    }
+
+    #[test]
+    fn test_cargo_and_npm() {
+        let cargo = "[package]\nname = \"app\"\n\n[dependencies]\nserde = \"1\"\ntoml = { version = \"0.8\" }\n\n[build-dependencies]\ncc = \"1\"\n\n[dev-dependencies]\ntempfile = \"3\"\n\n[target.'cfg(unix)'.dependencies]\nlibc = \"0.2\"\n\n[workspace.dependencies]\nregex = \"1\"\n";
+        assert_eq!(count("Cargo.toml", cargo), Some((4, 1)));
+        assert_eq!(count("Cargo.toml", "[dependencies"), None);
+
+        let npm = r#"{"name": "web", "dependencies": {"react": "^18", "zod": "^3"}, "peerDependencies": {"react-dom": "^18"}, "devDependencies": {"vite": "^5"}}"#;
+        assert_eq!(count("package.json", npm), Some((3, 1)));
+    }
+
+    #[test]
+    fn test_go_and_maven() {
+        let go = "module example.com/app\n\ngo 1.22\n\nrequire github.com/spf13/cobra v1.8.0\n\nrequire (\n\tgolang.org/x/sync v0.7.0\n\tgithub.com/inconshreveable/mousetrap v1.1.0 // indirect\n)\n";
+        assert_eq!(count("go.mod", go), Some((2, 0)));
+
+        let pom = "<project>\n  <dependencyManagement><dependencies><dependency><artifactId>bom</artifactId></dependency></dependencies></dependencyManagement>\n  <dependencies>\n    <dependency><artifactId>guava</artifactId></dependency>\n    <!-- <dependency><artifactId>old</artifactId></dependency> -->\n    <dependency><artifactId>junit</artifactId><scope>test</scope></dependency>\n  </dependencies>\n  <build><plugins><plugin><dependencies><dependency><artifactId>asm</artifactId></dependency></dependencies></plugin></plugins></build>\n</project>\n";
+        assert_eq!(count("pom.xml", pom), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_python() {
+        let requirements = "# runtime\nrequests>=2.31  # http\n\n-r base.txt\n-e ./vendor/lib\nnumpy\n";
+        assert_eq!(count("requirements.txt", requirements), Some((3, 0)));
+        assert_eq!(count("requirements-dev.txt", "pytest\nruff\n"), Some((0, 2)));
+
+        let pep621 = "[project]\ndependencies = [\"httpx\", \"pydantic>=2\"]\n\n[project.optional-dependencies]\ncli = [\"typer\"]\ntest = [\"pytest\", \"pytest-cov\"]\n\n[dependency-groups]\nlint = [\"ruff\"]\n";
+        assert_eq!(count("pyproject.toml", pep621), Some((3, 3)));
+
+        let poetry = "[tool.poetry.dependencies]\npython = \"^3.11\"\nflask = \"^3\"\n\n[tool.poetry.group.dev.dependencies]\nblack = \"*\"\n";
+        assert_eq!(count("pyproject.toml", poetry), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_totals_and_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = |name: &str, contents: &str| {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+        let paths = vec![
+            manifest("Cargo.toml", "[dependencies]\nserde = \"1\"\n[dev-dependencies]\ntempfile = \"3\"\n"),
+            manifest("web/package.json", r#"{"dependencies": {"react": "^18", "zod": "^3", "axios": "^1"}}"#),
+            manifest("web/old/package.json", "{ not json"),
+            manifest("README.md", "# app"),
+        ];
+
+        let stats = DependencyScanner::new().scan(&paths);
+        assert_eq!((stats.direct, stats.dev), (4, 1));
+        assert_eq!(stats.manifests.len(), 2);
+        assert_eq!(stats.unreadable.len(), 1);
+        assert_eq!(stats.ecosystems[0], EcosystemDependencies { ecosystem: Ecosystem::Npm, manifests: 1, direct: 3, dev: 0 });
+
+        // Shards of the same checkout don't count a manifest twice
+        let merged = DependencyStats::merge([&stats, &stats]).unwrap();
+        assert_eq!(merged, stats);
+        assert_eq!(DependencyStats::merge([]), None);
+    }
+}
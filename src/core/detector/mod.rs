@@ -1,13 +1,40 @@
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
+use crate::core::explain::Exclusion;
 use crate::core::patterns::PatternMatcher;
+use crate::core::plugins;
+use crate::core::types::FileStats;
+use crate::utils::encoding::{self, BINARY_SNIFF_BYTES};
 
 pub mod patterns;
 use patterns::{ExternalPatterns, CodeExtensions};
+use patterns::fixtures::FixturePatterns;
+use patterns::generated::{GeneratedPatterns, LICENSE_ONLY_MAX_BYTES};
+use patterns::minified::MinifiedPatterns;
+use patterns::test_code::TestCodePatterns;
+use patterns::category::{CategoryPatterns, FileCategory};
+use patterns::build_files::build_language;
+
+/// Key a file's lines are reported under in `stats_by_extension`: the language
+/// of a build file named by convention (`makefile`, `dockerfile`, `cmake`) or
+/// of a file a plugin knows by name, otherwise its extension. `None` for other
+/// files without an extension.
+pub fn extension_key(path: &Path) -> Option<String> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy());
+    let named = file_name.as_deref().and_then(|name| build_language(name).map(str::to_string).or_else(|| plugins::file_name_key(name)));
+    named.or_else(|| path.extension().map(|extension| extension.to_string_lossy().to_string()))
+}
 
 pub struct FileDetector {
     external_patterns: ExternalPatterns,
     code_extensions: CodeExtensions,
     pattern_matcher: PatternMatcher,
+    fixture_patterns: FixturePatterns,
+    generated_patterns: GeneratedPatterns,
+    minified_patterns: MinifiedPatterns,
+    test_code_patterns: TestCodePatterns,
+    category_patterns: CategoryPatterns,
 }
 
 impl FileDetector {
@@ -16,25 +43,28 @@ impl FileDetector {
             external_patterns: ExternalPatterns::new(),
             code_extensions: CodeExtensions::new(),
             pattern_matcher: PatternMatcher::new(),
+            fixture_patterns: FixturePatterns::new(),
+            generated_patterns: GeneratedPatterns::new(),
+            minified_patterns: MinifiedPatterns::new(),
+            test_code_patterns: TestCodePatterns::new(),
+            category_patterns: CategoryPatterns::new(),
         }
     }
 
     pub fn is_user_created_file(&self, path: &Path) -> bool {
         let path_str = path.to_string_lossy();
         
-        // First check if it should be ignored based on common patterns
-        if self.pattern_matcher.should_ignore_file(&path_str) {
+        if self.is_excluded(&path_str) {
             return false;
         }
         
-        // Check if it matches external/dependency patterns
-        if self.external_patterns.matches(&path_str) {
-            return false;
+        if let Some(verdict) = plugins::is_user_created_file(path) {
+            return verdict;
         }
         
-        // Check if it matches build/cache patterns
-        if self.pattern_matcher.matches_build_cache_pattern(&path_str) {
-            return false;
+        // Build files are known by name, whatever their extension (`Dockerfile.dev`)
+        if path.file_name().is_some_and(|name| build_language(&name.to_string_lossy()).is_some() || plugins::file_name_key(&name.to_string_lossy()).is_some()) {
+            return true;
         }
         
         // Check if it's a code file we care about
@@ -60,6 +90,126 @@ impl FileDetector {
         false
     }
 
+    /// Snapshot or golden-output test fixture, counted in its own bucket rather than as user code
+    pub fn is_test_fixture(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        !self.is_excluded(&path_str) && self.fixture_patterns.matches(&path_str)
+    }
+
+    /// Generated source by naming convention (`*.pb.go`, `*_pb2.py`, `__generated__/`, ...),
+    /// counted in its own bucket rather than as user code
+    pub fn is_generated_code(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        !self.is_excluded(&path_str) && self.generated_patterns.matches(&path_str)
+    }
+
+    /// Source map of a script or stylesheet, found even though `.map` isn't a
+    /// code extension so it can be reported with the generated code
+    pub fn is_source_map(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        !self.is_excluded(&path_str) && self.generated_patterns.is_source_map(&path_str)
+    }
+
+    /// Detector using these generated-code patterns (header markers from `[generated]`)
+    pub fn with_generated_patterns(mut self, generated_patterns: GeneratedPatterns) -> Self {
+        self.generated_patterns = generated_patterns;
+        self
+    }
+
+    /// Whether file contents start with a generator marker (`@generated`, `DO NOT EDIT`)
+    /// or are nothing but a license header
+    pub fn has_generated_marker(&self, contents: &[u8]) -> bool {
+        self.generated_patterns.has_marker(contents) || self.generated_patterns.is_license_only(contents)
+    }
+
+    /// Read the start of a file on disk and check it for a generator marker
+    pub fn has_generated_header(&self, path: &Path) -> bool {
+        // One byte past the license-only limit, so longer files are never taken as whole
+        let mut header = Vec::new();
+        File::open(path)
+            .and_then(|file| file.take(LICENSE_ONLY_MAX_BYTES as u64 + 1).read_to_end(&mut header))
+            .is_ok_and(|_| self.has_generated_marker(&header))
+    }
+
+    /// Minified script or stylesheet by name (`*.min.js`, `*-min.css`)
+    pub fn is_minified_name(&self, path: &Path) -> bool {
+        self.minified_patterns.matches(&path.to_string_lossy())
+    }
+
+    /// Minified script or stylesheet by name, or by counted lines that are long
+    /// on average and packed with varied characters; `contents` is only read
+    /// for files with long lines
+    pub fn is_minified(&self, path: &Path, stats: &FileStats, contents: impl FnOnce() -> Option<Vec<u8>>) -> bool {
+        let path_str = path.to_string_lossy();
+        if self.minified_patterns.matches(&path_str) {
+            return true;
+        }
+        self.minified_patterns.is_minifiable(&path_str)
+            && self.minified_patterns.has_long_lines(&stats.line_lengths, stats.total_lines)
+            && contents().is_some_and(|contents| self.minified_patterns.is_dense(&contents))
+    }
+
+    /// Probe the start of a file on disk for binary content, which the
+    /// extension lists miss for files like `data` or `script`
+    pub fn has_binary_content(&self, path: &Path) -> bool {
+        let mut head = Vec::new();
+        File::open(path)
+            .and_then(|file| file.take(BINARY_SNIFF_BYTES as u64).read_to_end(&mut head))
+            .is_ok_and(|_| encoding::looks_binary(&head))
+    }
+
+    /// Test source by path convention (`tests/`, `*_test.go`, `*.spec.ts`, ...),
+    /// counted in the test bucket rather than as production code
+    pub fn is_test_code(&self, path: &Path) -> bool {
+        self.test_code_patterns.matches(&path.to_string_lossy())
+    }
+
+    /// Examples, benchmarks or docs by directory (`examples/`, `benches/`, `docs/`),
+    /// reported with the other files but scored separately
+    pub fn file_category(&self, path: &Path) -> Option<FileCategory> {
+        self.category_patterns.category(&path.to_string_lossy())
+    }
+
+    /// Rule that keeps a walked file out of the analysis, or `None` for the
+    /// files it reads: user code, test fixtures and source maps
+    pub fn exclusion(&self, path: &Path) -> Option<Exclusion> {
+        if self.is_user_created_file(path) || self.is_test_fixture(path) || self.is_source_map(path) {
+            return None;
+        }
+        
+        let path_str = path.to_string_lossy();
+        let is_binary = path.extension().is_some_and(|extension| self.pattern_matcher.is_binary_file(&extension.to_string_lossy()));
+        Some(if self.pattern_matcher.should_ignore_file(&path_str) {
+            Exclusion::IgnoredName
+        } else if self.external_patterns.matches(&path_str) {
+            Exclusion::External
+        } else if self.pattern_matcher.matches_build_cache_pattern(&path_str) {
+            Exclusion::BuildOutput
+        } else if plugins::is_user_created_file(path) == Some(false) {
+            Exclusion::Plugin
+        } else if is_binary {
+            Exclusion::BinaryExtension
+        } else {
+            Exclusion::NotCode
+        })
+    }
+
+    /// Ignored, external/dependency, or build/cache paths
+    fn is_excluded(&self, path_str: &str) -> bool {
+        // First check if it should be ignored based on common patterns
+        if self.pattern_matcher.should_ignore_file(path_str) {
+            return true;
+        }
+        
+        // Check if it matches external/dependency patterns
+        if self.external_patterns.matches(path_str) {
+            return true;
+        }
+        
+        // Check if it matches build/cache patterns
+        self.pattern_matcher.matches_build_cache_pattern(path_str)
+    }
+
     pub fn is_code_file(&self, path: &Path) -> bool {
         if let Some(extension) = path.extension() {
             let ext_str = extension.to_string_lossy().to_lowercase();
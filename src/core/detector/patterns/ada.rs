@@ -0,0 +1,66 @@
+use regex::Regex;
+
+pub struct AdaPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl AdaPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // GNAT library information
+            Regex::new(r"\.ali$").unwrap(),
+
+            // Alire dependency cache
+            Regex::new(r"(^|/)alire/cache/").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // GNAT build output
+            Regex::new(r"\.ali$").unwrap(),
+            Regex::new(r"(^|/)b~[^/]*\.ad[bs]$").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Bodies and specifications
+            "adb".to_string(),
+            "ads".to_string(),
+            "ada".to_string(),
+
+            // GNAT project files
+            "gpr".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Alire manifest
+            "alire.toml",
+        ]
+    }
+}
+
+impl Default for AdaPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
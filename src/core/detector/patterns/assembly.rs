@@ -0,0 +1,61 @@
+use regex::Regex;
+
+pub struct AssemblyPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl AssemblyPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Object files and listings
+            Regex::new(r"\.o$").unwrap(),
+            Regex::new(r"\.obj$").unwrap(),
+            Regex::new(r"\.lst$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Object files
+            Regex::new(r"\.o$").unwrap(),
+            Regex::new(r"\.obj$").unwrap(),
+        ];
+
+        let extensions = vec![
+            // GNU as, NASM and MASM sources
+            "s".to_string(),
+            "asm".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Linker scripts
+            "linker.ld", "link.ld",
+        ]
+    }
+}
+
+impl Default for AssemblyPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
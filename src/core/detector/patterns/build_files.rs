@@ -0,0 +1,63 @@
+pub struct BuildFilePatterns {
+    extensions: Vec<String>,
+}
+
+impl BuildFilePatterns {
+    pub fn new() -> Self {
+        let extensions = vec![
+            // Make includes
+            "mk".to_string(),
+            "mak".to_string(),
+
+            // CMake modules
+            "cmake".to_string(),
+
+            // Named Dockerfiles (`app.dockerfile`)
+            "dockerfile".to_string(),
+        ];
+
+        Self { extensions }
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Make
+            "Makefile", "makefile", "GNUmakefile", "BSDmakefile", "Makefile.am", "Makefile.in",
+
+            // Container images
+            "Dockerfile", "Containerfile",
+
+            // CMake
+            "CMakeLists.txt",
+        ]
+    }
+}
+
+impl Default for BuildFilePatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Language of a build file, which its name gives rather than its extension:
+/// `makefile` for `Makefile`, `GNUmakefile` and `*.mk`; `dockerfile` for
+/// `Dockerfile`, `Dockerfile.dev` and `Containerfile`; `cmake` for
+/// `CMakeLists.txt` and `*.cmake`. `None` for other files.
+pub fn build_language(file_name: &str) -> Option<&'static str> {
+    let name = file_name.to_lowercase();
+    let extension = name.rsplit_once('.').map(|(_, extension)| extension);
+
+    if matches!(name.as_str(), "makefile" | "gnumakefile" | "bsdmakefile" | "makefile.am" | "makefile.in") || matches!(extension, Some("mk" | "mak")) {
+        Some("makefile")
+    } else if matches!(name.as_str(), "dockerfile" | "containerfile") || name.starts_with("dockerfile.") || extension == Some("dockerfile") {
+        Some("dockerfile")
+    } else if name == "cmakelists.txt" || extension == Some("cmake") {
+        Some("cmake")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of supporting code kept apart from the project's own sources: sample
+/// programs, benchmarks and documentation snippets. Such files are counted and
+/// reported, but by default left out of the project quality score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Examples,
+    Benches,
+    Docs,
+}
+
+impl FileCategory {
+    pub const ALL: [FileCategory; 3] = [FileCategory::Examples, FileCategory::Benches, FileCategory::Docs];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileCategory::Examples => "examples",
+            FileCategory::Benches => "benches",
+            FileCategory::Docs => "docs",
+        }
+    }
+}
+
+impl fmt::Display for FileCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Directory names that put the files below them in a category
+pub struct CategoryPatterns {
+    directories: Vec<(FileCategory, Vec<&'static str>)>,
+}
+
+impl CategoryPatterns {
+    pub fn new() -> Self {
+        let directories = vec![
+            (FileCategory::Examples, vec!["examples", "example", "samples", "sample", "demos", "demo"]),
+            (FileCategory::Benches, vec!["benches", "bench", "benchmarks", "benchmark"]),
+            (FileCategory::Docs, vec!["docs", "doc", "documentation"]),
+        ];
+
+        Self { directories }
+    }
+
+    /// Category of the outermost directory of `path_str` that has one
+    pub fn category(&self, path_str: &str) -> Option<FileCategory> {
+        let normalized = path_str.replace('\\', "/");
+        let mut components: Vec<&str> = normalized.split('/').collect();
+        // The file name itself never decides the category
+        components.pop();
+
+        components.into_iter().find_map(|component| {
+            self.directories
+                .iter()
+                .find(|(_, names)| names.iter().any(|name| component.eq_ignore_ascii_case(name)))
+                .map(|(category, _)| *category)
+        })
+    }
+}
+
+impl Default for CategoryPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directory_categories() {
+        let patterns = CategoryPatterns::new();
+
+        assert_eq!(patterns.category("./examples/basic.rs"), Some(FileCategory::Examples));
+        assert_eq!(patterns.category("./crates/core/benches/parse.rs"), Some(FileCategory::Benches));
+        assert_eq!(patterns.category("./Docs/guide/setup.py"), Some(FileCategory::Docs));
+        assert_eq!(patterns.category("docs\\api.md"), Some(FileCategory::Docs));
+        // The outermost categorized directory wins
+        assert_eq!(patterns.category("./docs/examples/intro.rs"), Some(FileCategory::Docs));
+
+        assert_eq!(patterns.category("./src/main.rs"), None);
+        assert_eq!(patterns.category("./src/docs.rs"), None);
+        assert_eq!(patterns.category("./src/examples_util/mod.rs"), None);
+    }
+}
@@ -0,0 +1,58 @@
+use regex::Regex;
+
+pub struct CobolPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl CobolPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Compiler listings
+            Regex::new(r"\.lst$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Compiler listings
+            Regex::new(r"\.lst$").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Programs
+            "cob".to_string(),
+            "cbl".to_string(),
+
+            // Copybooks
+            "cpy".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+impl Default for CobolPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,60 @@
+use regex::Regex;
+
+pub struct CudaPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl CudaPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Compiled kernels
+            Regex::new(r"\.ptx$").unwrap(),
+            Regex::new(r"\.cubin$").unwrap(),
+            Regex::new(r"\.fatbin$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Kernel cache
+            Regex::new(r"\.nv/ComputeCache/").unwrap(),
+        ];
+
+        let extensions = vec![
+            // CUDA sources and headers
+            "cu".to_string(),
+            "cuh".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Build configuration
+            "CMakeLists.txt",
+        ]
+    }
+}
+
+impl Default for CudaPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
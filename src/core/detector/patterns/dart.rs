@@ -20,13 +20,6 @@ impl DartPatterns {
             Regex::new(r"\.flutter-plugins-dependencies").unwrap(),
             Regex::new(r"\.fvm/").unwrap(),
             
-            // Generated files
-            Regex::new(r"\.g\.dart$").unwrap(),
-            Regex::new(r"\.freezed\.dart$").unwrap(),
-            Regex::new(r"\.gr\.dart$").unwrap(),
-            Regex::new(r"\.config\.dart$").unwrap(),
-            Regex::new(r"\.part\.dart$").unwrap(),
-            
             // iOS build artifacts
             Regex::new(r"ios/\.symlinks/").unwrap(),
             Regex::new(r"ios/Flutter/").unwrap(),
@@ -0,0 +1,69 @@
+use regex::Regex;
+
+/// Snapshot and golden-output test fixtures.
+///
+/// These files are produced by test runners (Jest, insta, syrupy, ApprovalTests,
+/// golden-file tests) and grow in lock-step with the tests that write them, so
+/// they are reported in their own bucket instead of as user code.
+pub struct FixturePatterns {
+    fixture_patterns: Vec<Regex>,
+}
+
+impl FixturePatterns {
+    pub fn new() -> Self {
+        let fixture_patterns = vec![
+            // Jest / Vitest snapshot directories
+            Regex::new(r"(^|/)__snapshots__/").unwrap(),
+            
+            // Snapshot files (Jest .snap, Rust insta .snap, Python syrupy .ambr)
+            Regex::new(r"\.snap(\.new)?$").unwrap(),
+            Regex::new(r"\.ambr$").unwrap(),
+            
+            // Golden files, and golden directories under a test tree (Go testdata,
+            // generic golden tests); a top-level `golden/` may well be source
+            Regex::new(r"\.golden(\.[^/]+)?$").unwrap(),
+            Regex::new(r"(^|/)(tests?|testdata)/(.*/)?goldens?/").unwrap(),
+            
+            // ApprovalTests approved/received outputs
+            Regex::new(r"\.(approved|received)\.[^/]+$").unwrap(),
+            
+            // Expected-output fixtures (e.g. foo.expected, foo.expected.json)
+            Regex::new(r"\.expected(\.[^/]+)?$").unwrap(),
+        ];
+
+        Self { fixture_patterns }
+    }
+
+    pub fn matches(&self, path_str: &str) -> bool {
+        let normalized = path_str.replace('\\', "/");
+        self.fixture_patterns.iter().any(|pattern| pattern.is_match(&normalized))
+    }
+}
+
+impl Default for FixturePatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golden_directories() {
+        let patterns = FixturePatterns::new();
+
+        assert!(patterns.matches("tests/golden/output.txt"));
+        assert!(patterns.matches("./crates/parser/tests/fixtures/goldens/expr.json"));
+        assert!(patterns.matches("pkg/render/testdata/golden/page.html"));
+        assert!(patterns.matches("test\\goldens\\report.csv"));
+        assert!(patterns.matches("src/render/page.golden"));
+
+        // Golden directories outside a test tree may hold real code
+        assert!(!patterns.matches("golden/main.go"));
+        assert!(!patterns.matches("./src/golden/ratio.rs"));
+        assert!(!patterns.matches("services/goldens/api.py"));
+        assert!(!patterns.matches("latest/golden/app.js"));
+    }
+}
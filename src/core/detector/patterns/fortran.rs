@@ -0,0 +1,66 @@
+use regex::Regex;
+
+pub struct FortranPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl FortranPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Compiled module interfaces (not `.mod`, which is also `go.mod`)
+            Regex::new(r"\.smod$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // fpm build directory
+            Regex::new(r"(^|/)build/gfortran_").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Fixed-form sources
+            "f".to_string(),
+            "for".to_string(),
+            "f77".to_string(),
+            "ftn".to_string(),
+
+            // Free-form sources
+            "f90".to_string(),
+            "f95".to_string(),
+            "f03".to_string(),
+            "f08".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Fortran package manager
+            "fpm.toml",
+        ]
+    }
+}
+
+impl Default for FortranPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
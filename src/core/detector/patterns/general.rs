@@ -38,7 +38,7 @@ impl GeneralPatterns {
             
             // Documentation
             "md".to_string(), "rst".to_string(), "txt".to_string(),
-            "adoc".to_string(), "asciidoc".to_string(),
+            "adoc".to_string(), "asciidoc".to_string(), "org".to_string(),
         ];
 
         Self {
@@ -0,0 +1,253 @@
+use crate::core::filters::glob::GlobPatterns;
+use crate::utils::errors::{HowManyError, Result};
+use regex::Regex;
+use std::path::Path;
+
+/// Lines at the top of a file searched for a generator marker by default
+pub const HEADER_LINES: usize = 10;
+
+/// Largest file checked for license boilerplate; longer files are never only a license
+pub const LICENSE_ONLY_MAX_BYTES: usize = 8192;
+
+/// Built-in header markers by name, each matched inside a comment line
+pub const MARKERS: &[(&str, &str)] = &[
+    // Go's `// Code generated by protoc-gen-go. DO NOT EDIT.` and similar
+    ("code-generated", r"\bCode generated\b"),
+    // Meta's and Pants' `@generated`
+    ("@generated", r"@generated\b"),
+    ("do-not-edit", r"DO NOT EDIT"),
+    // .NET's `<auto-generated>`, "Autogenerated file", "automatically generated by"
+    ("auto-generated", r"<auto-generated|\b[Aa]uto-?generated\b|AUTO-?GENERATED|\b[Aa]utomatically generated\b"),
+];
+
+/// Marker name for files with nothing but a license header, often left behind by generators
+pub const LICENSE_ONLY: &str = "license-only";
+
+/// Generated sources, recognized by the naming conventions of common code
+/// generators or by the marker comment they write at the top of a file.
+#[derive(Clone)]
+pub struct GeneratedPatterns {
+    path_patterns: Vec<Regex>,
+    source_map_pattern: Regex,
+    declaration_pattern: Regex,
+    /// Directories of hand-written `.d.ts` files, which count as user code
+    handwritten_declarations: Option<GlobPatterns>,
+    header_pattern: Option<Regex>,
+    header_lines: usize,
+    license_only: bool,
+    comment_line: Regex,
+    license_pattern: Regex,
+}
+
+impl GeneratedPatterns {
+    pub fn new() -> Self {
+        let markers: Vec<String> = MARKERS.iter().map(|(name, _)| name.to_string()).chain([LICENSE_ONLY.to_string()]).collect();
+        Self::with_markers(HEADER_LINES, &markers, &[]).expect("built-in generator markers are valid")
+    }
+
+    /// Patterns with a chosen set of header markers: built-in ones by name
+    /// (see `MARKERS` and `LICENSE_ONLY`), plus custom marker texts
+    pub fn with_markers(header_lines: usize, markers: &[String], custom_markers: &[String]) -> Result<Self> {
+        let path_patterns = vec![
+            // Generated directories (Relay, OpenAPI and GraphQL clients)
+            Regex::new(r"(^|/)(__generated__|\.?generated)/").unwrap(),
+
+            // Protocol Buffers, gRPC and Thrift
+            Regex::new(r"\.pb\.(go|cc|h|swift|dart)$").unwrap(),
+            Regex::new(r"\.pb\.gw\.go$").unwrap(),
+            Regex::new(r"_pb2(_grpc)?\.pyi?$").unwrap(),
+            Regex::new(r"_(grpc_)?pb\.(js|d\.ts)$").unwrap(),
+            Regex::new(r"(^|/)gen-[a-z]+/").unwrap(),
+
+            // go generate, stringer and Kubernetes code generators
+            Regex::new(r"_(gen|generated|string)\.go$").unwrap(),
+            Regex::new(r"(^|/)(bindata\.go|zz_generated\.[^/]+)$").unwrap(),
+
+            // build_runner, freezed, auto_route and injectable output
+            Regex::new(r"\.(g|freezed|gr|config|part)\.dart$").unwrap(),
+
+            // .NET designers and source generators, R.swift (`foo.Designer.cs`, `R.generated.swift`)
+            Regex::new(r"(?i)\.designer\.(cs|vb)$").unwrap(),
+            Regex::new(r"\.g\.(i\.)?cs$").unwrap(),
+            Regex::new(r"\.generated\.[^/]+$").unwrap(),
+        ];
+
+        let mut alternatives = Vec::new();
+        for name in markers.iter().filter(|name| name.as_str() != LICENSE_ONLY) {
+            let (_, pattern) = MARKERS.iter().find(|(marker, _)| marker == name).ok_or_else(|| {
+                let known: Vec<_> = MARKERS.iter().map(|(marker, _)| *marker).chain([LICENSE_ONLY]).collect();
+                HowManyError::invalid_config(format!("Unknown generated marker '{}' (expected one of: {})", name, known.join(", ")))
+            })?;
+            alternatives.push(pattern.to_string());
+        }
+        alternatives.extend(custom_markers.iter().map(|marker| regex::escape(marker)));
+
+        // Markers only count inside a comment, so code that merely mentions them isn't flagged
+        let header_pattern = (!alternatives.is_empty()).then(|| {
+            Regex::new(&format!(r"(?m)^\s*(//|/?\*|#|--|;|%|<!--|\(\*)[^\n]*({})", alternatives.join("|"))).unwrap()
+        });
+
+        Ok(Self {
+            path_patterns,
+            // Source maps written next to bundles and compiled stylesheets
+            source_map_pattern: Regex::new(r"\.(m|c)?(js|ts|css)\.map$").unwrap(),
+            // TypeScript declarations emitted by `tsc --declaration` and bundlers
+            declaration_pattern: Regex::new(r"\.d\.(m|c)?ts$").unwrap(),
+            handwritten_declarations: None,
+            header_pattern,
+            header_lines: header_lines.max(1),
+            license_only: markers.iter().any(|name| name == LICENSE_ONLY),
+            comment_line: Regex::new(r"^\s*(//|/\*|\*|#($|[\s#!*=-])|--|;|%|<!--|-->|\(\*)").unwrap(),
+            license_pattern: Regex::new(r"(?i)SPDX-License-Identifier|\bcopyright\b|\blicensed under\b|permission is hereby granted|all rights reserved").unwrap(),
+        })
+    }
+
+    /// Patterns that count the `.d.ts` files below these globs (`types/`,
+    /// `src/global.d.ts`) as hand-written code
+    pub fn with_handwritten_declarations(mut self, patterns: &[String]) -> Result<Self> {
+        self.handwritten_declarations = if patterns.is_empty() {
+            None
+        } else {
+            Some(GlobPatterns::new(patterns)?)
+        };
+        Ok(self)
+    }
+
+    pub fn matches(&self, path_str: &str) -> bool {
+        let normalized = path_str.replace('\\', "/");
+        self.path_patterns.iter().any(|pattern| pattern.is_match(&normalized))
+            || self.source_map_pattern.is_match(&normalized)
+            || (self.declaration_pattern.is_match(&normalized) && !self.is_handwritten_declaration(&normalized))
+    }
+
+    /// Source map of a script or stylesheet (`app.js.map`, `index.d.ts.map`)
+    pub fn is_source_map(&self, path_str: &str) -> bool {
+        self.source_map_pattern.is_match(path_str)
+    }
+
+    fn is_handwritten_declaration(&self, normalized: &str) -> bool {
+        self.handwritten_declarations.as_ref().is_some_and(|patterns| {
+            patterns.is_match(Path::new(normalized.strip_prefix("./").unwrap_or(normalized)))
+        })
+    }
+
+    /// Number of lines at the top of a file searched for a marker
+    pub fn header_lines(&self) -> usize {
+        self.header_lines
+    }
+
+    /// Whether the first header lines of `contents` carry a generator marker
+    /// (`// Code generated by protoc-gen-go. DO NOT EDIT.`, `# @generated`)
+    pub fn has_marker(&self, contents: &[u8]) -> bool {
+        let Some(header_pattern) = &self.header_pattern else {
+            return false;
+        };
+        let header_end = contents
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'\n')
+            .nth(self.header_lines - 1)
+            .map_or(contents.len(), |(index, _)| index);
+        header_pattern.is_match(&String::from_utf8_lossy(&contents[..header_end]))
+    }
+
+    /// Whether `contents`, a whole file, is nothing but comments carrying a license
+    /// notice (an `__init__.py` or `mod.rs` with only the project's license header)
+    pub fn is_license_only(&self, contents: &[u8]) -> bool {
+        if !self.license_only || contents.len() > LICENSE_ONLY_MAX_BYTES {
+            return false;
+        }
+        let text = String::from_utf8_lossy(contents);
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty()).peekable();
+        lines.peek().is_some()
+            && self.license_pattern.is_match(&text)
+            && lines.all(|line| self.comment_line.is_match(line))
+    }
+}
+
+impl Default for GeneratedPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_paths() {
+        let patterns = GeneratedPatterns::new();
+
+        assert!(patterns.matches("./api/user.pb.go"));
+        assert!(patterns.matches("./proto/user_pb2_grpc.py"));
+        assert!(patterns.matches("./lib/model.freezed.dart"));
+        assert!(patterns.matches("./web/src/__generated__/Query.graphql.ts"));
+        assert!(patterns.matches(r".\Forms\Main.Designer.cs"));
+        assert!(patterns.matches("./dist/app.js.map"));
+        assert!(patterns.matches("./lib/index.d.ts"));
+        assert!(patterns.matches("./lib/index.d.mts"));
+        // Names that merely contain "build", "schema" or "gen" are user code
+        assert!(!patterns.matches("./src/schema.rs"));
+        assert!(!patterns.matches("./src/builder.rs"));
+        assert!(!patterns.matches("./src/codegen/emit.rs"));
+        assert!(!patterns.matches("./src/index.ts"));
+        assert!(!patterns.matches("./src/sitemap.ts"));
+    }
+
+    #[test]
+    fn test_handwritten_declarations() {
+        let patterns = GeneratedPatterns::new()
+            .with_handwritten_declarations(&["types".to_string(), "src/env.d.ts".to_string()])
+            .unwrap();
+
+        assert!(!patterns.matches("./types/globals.d.ts"));
+        assert!(!patterns.matches("./src/env.d.ts"));
+        assert!(patterns.matches("./lib/index.d.ts"));
+        // Other generated files below the globs are still generated
+        assert!(patterns.matches("./types/api.pb.go"));
+    }
+
+    #[test]
+    fn test_generated_markers() {
+        let patterns = GeneratedPatterns::new();
+
+        assert!(patterns.has_marker(b"// Code generated by protoc-gen-go. DO NOT EDIT.\n\npackage api\n"));
+        assert!(patterns.has_marker(b"#!/usr/bin/env python\n# @generated by pants\n"));
+        assert!(patterns.has_marker(b"//------\n// <auto-generated>\n//------\n"));
+        // Only in a comment near the top of the file
+        assert!(!patterns.has_marker(b"const MARKER: &str = \"@generated\";\n"));
+        let late_marker = format!("{}// DO NOT EDIT\n", "fn f() {}\n".repeat(HEADER_LINES));
+        assert!(!patterns.has_marker(late_marker.as_bytes()));
+        assert!(patterns.has_marker(b"// Code generated by mockery v2.20.0\npackage mocks\n"));
+        assert!(patterns.has_marker(b"/* Autogenerated file. */\nint x;\n"));
+    }
+
+    #[test]
+    fn test_configured_markers() {
+        let markers = vec!["code-generated".to_string()];
+        let patterns = GeneratedPatterns::with_markers(2, &markers, &["Generated with Buf".to_string()]).unwrap();
+
+        assert!(patterns.has_marker(b"// Code generated by sqlc.\n"));
+        assert!(patterns.has_marker(b"# Generated with Buf (v1.28)\n"));
+        // Disabled markers and markers below the configured header are ignored
+        assert!(!patterns.has_marker(b"// @generated\n"));
+        assert!(!patterns.has_marker(b"package a\n\n// Code generated by sqlc.\n"));
+        assert!(!patterns.is_license_only(b"// Copyright 2024 Example Inc.\n"));
+
+        assert!(GeneratedPatterns::with_markers(HEADER_LINES, &["generated-ish".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_license_only_files() {
+        let patterns = GeneratedPatterns::new();
+
+        assert!(patterns.is_license_only(b"# Copyright 2024 Example Inc.\n#\n# Licensed under the Apache License, Version 2.0\n"));
+        assert!(patterns.is_license_only(b"/*\n * SPDX-License-Identifier: MIT\n */\n\n"));
+        // Any code, or comments without a license, make it a regular file
+        assert!(!patterns.is_license_only(b"// SPDX-License-Identifier: MIT\npub mod parser;\n"));
+        assert!(!patterns.is_license_only(b"/* SPDX-License-Identifier: MIT */\n#include \"parser.h\"\n"));
+        assert!(!patterns.is_license_only(b"// TODO: write the parser\n"));
+        assert!(!patterns.is_license_only(b""));
+    }
+}
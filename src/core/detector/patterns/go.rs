@@ -37,13 +37,6 @@ impl GoPatterns {
             Regex::new(r"bin/").unwrap(),
             Regex::new(r"dist/").unwrap(),
             
-            // Go-specific generated files
-            Regex::new(r"\.pb\.go$").unwrap(),
-            Regex::new(r"\.pb\.gw\.go$").unwrap(),
-            Regex::new(r"bindata\.go$").unwrap(),
-            Regex::new(r"_gen\.go$").unwrap(),
-            Regex::new(r"_generated\.go$").unwrap(),
-            
             // IDE files
             Regex::new(r"\.vscode/").unwrap(),
             Regex::new(r"\.idea/").unwrap(),
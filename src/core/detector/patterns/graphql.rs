@@ -0,0 +1,59 @@
+use regex::Regex;
+
+pub struct GraphqlPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl GraphqlPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Introspection results downloaded from a server
+            Regex::new(r"graphql\.schema\.json$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Codegen cache
+            Regex::new(r"\.graphql-codegen/").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Schemas and operations
+            "graphql".to_string(),
+            "gql".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Tooling configuration
+            "schema.graphql", ".graphqlrc", ".graphqlrc.yml", "graphql.config.yml",
+            "codegen.yml", "codegen.ts",
+        ]
+    }
+}
+
+impl Default for GraphqlPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
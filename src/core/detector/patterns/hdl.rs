@@ -0,0 +1,74 @@
+use regex::Regex;
+
+pub struct HdlPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl HdlPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Vivado and Quartus project output
+            Regex::new(r"\.Xil/").unwrap(),
+            Regex::new(r"\.runs/").unwrap(),
+            Regex::new(r"(^|/)incremental_db/").unwrap(),
+
+            // Simulator output
+            Regex::new(r"(^|/)obj_dir/").unwrap(),
+            Regex::new(r"(^|/)xsim\.dir/").unwrap(),
+            Regex::new(r"\.vcd$").unwrap(),
+            Regex::new(r"\.fst$").unwrap(),
+            Regex::new(r"\.ghw$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Verilator and simulator libraries
+            Regex::new(r"(^|/)obj_dir/").unwrap(),
+            Regex::new(r"(^|/)xsim\.dir/").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Verilog and SystemVerilog
+            "v".to_string(),
+            "vh".to_string(),
+            "sv".to_string(),
+            "svh".to_string(),
+
+            // VHDL
+            "vhd".to_string(),
+            "vhdl".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // FuseSoC and Bender manifests
+            "Bender.yml", "Bender.lock",
+        ]
+    }
+}
+
+impl Default for HdlPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
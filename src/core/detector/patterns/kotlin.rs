@@ -100,7 +100,6 @@ impl KotlinPatterns {
             Regex::new(r"package-lock\.json").unwrap(),  // For Kotlin/JS
             
             // Kotlin/JS specific
-            Regex::new(r"\.js\.map$").unwrap(),
             Regex::new(r"kotlin-js-store/").unwrap(),
         ];
 
@@ -0,0 +1,131 @@
+use crate::core::types::LineLengthStats;
+use regex::Regex;
+
+/// Average characters per line from which a script or stylesheet may be minified
+pub const MIN_AVERAGE_LINE_LENGTH: usize = 200;
+
+/// Shannon entropy, in bits per byte, that long lines need to count as minified.
+/// Packed code uses most of the printable ASCII range; long lines of a few
+/// repeated characters (ASCII art, `=====` rulers, padding) stay below it.
+pub const MIN_ENTROPY_BITS: f64 = 4.0;
+
+/// Extensions of the files bundlers and minifiers produce
+const MINIFIABLE_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "css"];
+
+/// Minified or bundled scripts and stylesheets, recognized by the `.min.`
+/// naming convention or by long lines of densely packed code.
+#[derive(Clone)]
+pub struct MinifiedPatterns {
+    name_pattern: Regex,
+}
+
+impl MinifiedPatterns {
+    pub fn new() -> Self {
+        Self {
+            // `jquery.min.js`, `bootstrap-min.css`, `app.min.mjs`
+            name_pattern: Regex::new(r"(?i)[.-]min\.(m|c)?(js|css)$").unwrap(),
+        }
+    }
+
+    /// Whether the file name marks minifier output
+    pub fn matches(&self, path_str: &str) -> bool {
+        self.name_pattern.is_match(path_str)
+    }
+
+    /// Whether the file's extension is one minifiers produce, so its contents are worth checking
+    pub fn is_minifiable(&self, path_str: &str) -> bool {
+        path_str
+            .rsplit_once('.')
+            .is_some_and(|(_, extension)| MINIFIABLE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+    }
+
+    /// Whether a file's lines are long enough on average to be minified
+    pub fn has_long_lines(&self, line_lengths: &LineLengthStats, total_lines: usize) -> bool {
+        total_lines > 0 && line_lengths.total / total_lines >= MIN_AVERAGE_LINE_LENGTH
+    }
+
+    /// Whether `contents` is varied enough to be packed code rather than repetition
+    pub fn is_dense(&self, contents: &[u8]) -> bool {
+        shannon_entropy(contents) >= MIN_ENTROPY_BITS
+    }
+}
+
+impl Default for MinifiedPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shannon entropy of `bytes` in bits per byte: 0 for a single repeated byte,
+/// 8 for uniformly random data, around 4.5 to 5.5 for source code
+pub fn shannon_entropy(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts = [0usize; 256];
+    for byte in bytes {
+        counts[*byte as usize] += 1;
+    }
+
+    let total = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_lengths(text: &str) -> (LineLengthStats, usize) {
+        let mut stats = LineLengthStats::default();
+        for line in text.lines() {
+            stats.add_line(line.chars().count());
+        }
+        (stats, text.lines().count())
+    }
+
+    #[test]
+    fn test_minified_names() {
+        let patterns = MinifiedPatterns::new();
+
+        assert!(patterns.matches("./static/jquery.min.js"));
+        assert!(patterns.matches("./css/bootstrap-min.css"));
+        assert!(patterns.matches("./dist/app.MIN.mjs"));
+        assert!(!patterns.matches("./src/admin.js"));
+        assert!(!patterns.matches("./src/min.js"));
+
+        assert!(patterns.is_minifiable("./dist/bundle.js"));
+        assert!(!patterns.is_minifiable("./src/main.rs"));
+    }
+
+    #[test]
+    fn test_minified_contents() {
+        let patterns = MinifiedPatterns::new();
+
+        let bundle = "!function(e,t){\"use strict\";var n=e.document,r=Object.getPrototypeOf,i=[].slice;function o(e){return null!=e&&e===e.window}".repeat(20);
+        let (stats, lines) = line_lengths(&bundle);
+        assert!(patterns.has_long_lines(&stats, lines));
+        assert!(patterns.is_dense(bundle.as_bytes()));
+
+        // Hand-written code has short lines
+        let source = "function add(a, b) {\n    return a + b;\n}\n".repeat(50);
+        let (stats, lines) = line_lengths(&source);
+        assert!(!patterns.has_long_lines(&stats, lines));
+
+        // Long lines of a few repeated characters are not packed code
+        let ruler = format!("/*{}*/\n", "=".repeat(400)).repeat(3);
+        let (stats, lines) = line_lengths(&ruler);
+        assert!(patterns.has_long_lines(&stats, lines));
+        assert!(!patterns.is_dense(ruler.as_bytes()));
+
+        assert_eq!(shannon_entropy(b""), 0.0);
+        assert_eq!(shannon_entropy(b"abab"), 1.0);
+    }
+}
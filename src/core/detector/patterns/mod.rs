@@ -5,6 +5,11 @@ pub mod java;
 pub mod cpp;
 pub mod web;
 pub mod general;
+pub mod fixtures;
+pub mod generated;
+pub mod minified;
+pub mod test_code;
+pub mod category;
 pub mod dotnet;
 pub mod go;
 pub mod ruby;
@@ -22,6 +27,18 @@ pub mod dart;
 pub mod perl;
 pub mod r;
 pub mod matlab;
+pub mod terraform;
+pub mod protobuf;
+pub mod graphql;
+pub mod nix;
+pub mod cobol;
+pub mod fortran;
+pub mod ada;
+pub mod pascal;
+pub mod assembly;
+pub mod hdl;
+pub mod cuda;
+pub mod build_files;
 
 use regex::Regex;
 use nodejs::NodejsPatterns;
@@ -48,6 +65,18 @@ use dart::DartPatterns;
 use perl::PerlPatterns;
 use r::RPatterns;
 use matlab::MatlabPatterns;
+use terraform::TerraformPatterns;
+use protobuf::ProtobufPatterns;
+use graphql::GraphqlPatterns;
+use nix::NixPatterns;
+use cobol::CobolPatterns;
+use fortran::FortranPatterns;
+use ada::AdaPatterns;
+use pascal::PascalPatterns;
+use assembly::AssemblyPatterns;
+use hdl::HdlPatterns;
+use cuda::CudaPatterns;
+use build_files::BuildFilePatterns;
 
 pub struct ExternalPatterns {
     patterns: Vec<Regex>,
@@ -130,6 +159,39 @@ impl ExternalPatterns {
         
         let matlab = MatlabPatterns::new();
         patterns.extend(matlab.get_external_patterns().iter().cloned());
+        
+        let terraform = TerraformPatterns::new();
+        patterns.extend(terraform.get_external_patterns().iter().cloned());
+        
+        let protobuf = ProtobufPatterns::new();
+        patterns.extend(protobuf.get_external_patterns().iter().cloned());
+        
+        let graphql = GraphqlPatterns::new();
+        patterns.extend(graphql.get_external_patterns().iter().cloned());
+        
+        let nix = NixPatterns::new();
+        patterns.extend(nix.get_external_patterns().iter().cloned());
+        
+        let cobol = CobolPatterns::new();
+        patterns.extend(cobol.get_external_patterns().iter().cloned());
+        
+        let fortran = FortranPatterns::new();
+        patterns.extend(fortran.get_external_patterns().iter().cloned());
+        
+        let ada = AdaPatterns::new();
+        patterns.extend(ada.get_external_patterns().iter().cloned());
+        
+        let pascal = PascalPatterns::new();
+        patterns.extend(pascal.get_external_patterns().iter().cloned());
+        
+        let assembly = AssemblyPatterns::new();
+        patterns.extend(assembly.get_external_patterns().iter().cloned());
+        
+        let hdl = HdlPatterns::new();
+        patterns.extend(hdl.get_external_patterns().iter().cloned());
+        
+        let cuda = CudaPatterns::new();
+        patterns.extend(cuda.get_external_patterns().iter().cloned());
 
         Self { patterns }
     }
@@ -220,6 +282,47 @@ impl CodeExtensions {
         
         let matlab = MatlabPatterns::new();
         extensions.extend(matlab.get_extensions().iter().cloned());
+        
+        let terraform = TerraformPatterns::new();
+        extensions.extend(terraform.get_extensions().iter().cloned());
+        
+        let protobuf = ProtobufPatterns::new();
+        extensions.extend(protobuf.get_extensions().iter().cloned());
+        
+        let graphql = GraphqlPatterns::new();
+        extensions.extend(graphql.get_extensions().iter().cloned());
+        
+        let nix = NixPatterns::new();
+        extensions.extend(nix.get_extensions().iter().cloned());
+        
+        let cobol = CobolPatterns::new();
+        extensions.extend(cobol.get_extensions().iter().cloned());
+        
+        let fortran = FortranPatterns::new();
+        extensions.extend(fortran.get_extensions().iter().cloned());
+        
+        let ada = AdaPatterns::new();
+        extensions.extend(ada.get_extensions().iter().cloned());
+        
+        let pascal = PascalPatterns::new();
+        extensions.extend(pascal.get_extensions().iter().cloned());
+        
+        let assembly = AssemblyPatterns::new();
+        extensions.extend(assembly.get_extensions().iter().cloned());
+        
+        let hdl = HdlPatterns::new();
+        extensions.extend(hdl.get_extensions().iter().cloned());
+        
+        let cuda = CudaPatterns::new();
+        extensions.extend(cuda.get_extensions().iter().cloned());
+        
+        let build_files = BuildFilePatterns::new();
+        extensions.extend(build_files.get_extensions().iter().cloned());
+
+        // Languages added by plugins
+        for language in crate::core::plugins::language_definitions() {
+            extensions.extend(language.extensions);
+        }
 
         Self { extensions }
     }
@@ -255,9 +358,59 @@ impl CodeExtensions {
         script_names.extend(PerlPatterns::get_script_names());
         script_names.extend(RPatterns::get_script_names());
         script_names.extend(MatlabPatterns::get_script_names());
+        script_names.extend(TerraformPatterns::get_script_names());
+        script_names.extend(ProtobufPatterns::get_script_names());
+        script_names.extend(GraphqlPatterns::get_script_names());
+        script_names.extend(NixPatterns::get_script_names());
+        script_names.extend(CobolPatterns::get_script_names());
+        script_names.extend(FortranPatterns::get_script_names());
+        script_names.extend(AdaPatterns::get_script_names());
+        script_names.extend(PascalPatterns::get_script_names());
+        script_names.extend(AssemblyPatterns::get_script_names());
+        script_names.extend(HdlPatterns::get_script_names());
+        script_names.extend(CudaPatterns::get_script_names());
+        script_names.extend(BuildFilePatterns::get_script_names());
         
         script_names
     }
 }
 
- 
\ No newline at end of file
+ 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_language_extensions() {
+        let extensions = CodeExtensions::new();
+        for extension in ["cob", "cbl", "cpy", "f", "for", "f77", "f90", "f08", "adb", "ads", "pas", "dpr", "lpr"] {
+            assert!(extensions.contains(extension), "{} is not a code extension", extension);
+        }
+
+        // Build output of these toolchains isn't counted
+        let external = ExternalPatterns::new();
+        assert!(external.matches("./src/hello.ali"));
+        assert!(external.matches("./lib/x86_64-linux/unit.ppu"));
+        assert!(external.matches("./src/__history/Main.pas.~1~"));
+        assert!(!external.matches("./src/hello.adb"));
+    }
+
+    #[test]
+    fn test_build_files_by_name() {
+        use build_files::build_language;
+        use crate::core::detector::{extension_key, FileDetector};
+        use std::path::Path;
+
+        for (name, language) in [("Makefile", "makefile"), ("GNUmakefile", "makefile"), ("rules.mk", "makefile"), ("Dockerfile", "dockerfile"), ("Dockerfile.dev", "dockerfile"), ("api.dockerfile", "dockerfile"), ("CMakeLists.txt", "cmake"), ("FindZLIB.cmake", "cmake")] {
+            assert_eq!(build_language(name), Some(language), "{}", name);
+        }
+        assert_eq!(build_language("Makefile.PL"), None);
+        assert_eq!(build_language("notes.txt"), None);
+
+        assert_eq!(extension_key(Path::new("src/CMakeLists.txt")).as_deref(), Some("cmake"));
+        assert_eq!(extension_key(Path::new("src/lib.rs")).as_deref(), Some("rs"));
+        assert_eq!(extension_key(Path::new("LICENSE")), None);
+        assert!(FileDetector::new().is_user_created_file(Path::new("docker/Dockerfile.prod")));
+    }
+}
@@ -0,0 +1,60 @@
+use regex::Regex;
+
+pub struct NixPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl NixPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Environments cached by nix-direnv
+            Regex::new(r"\.direnv/").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Evaluation cache
+            Regex::new(r"\.direnv/").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Nix expressions
+            "nix".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Flakes and classic entry points
+            "flake.nix", "flake.lock", "default.nix", "shell.nix",
+
+            // direnv integration
+            ".envrc",
+        ]
+    }
+}
+
+impl Default for NixPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
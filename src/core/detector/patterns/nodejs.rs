@@ -46,12 +46,6 @@ impl NodejsPatterns {
             Regex::new(r"\.netlify/").unwrap(),
             Regex::new(r"\.firebase/").unwrap(),
             
-            // Generated files
-            Regex::new(r"\.d\.ts\.map$").unwrap(),
-            Regex::new(r"\.js\.map$").unwrap(),
-            Regex::new(r"\.min\.js$").unwrap(),
-            Regex::new(r"\.min\.css$").unwrap(),
-            
             // Compiled TypeScript
             Regex::new(r"\.tsbuildinfo").unwrap(),
             
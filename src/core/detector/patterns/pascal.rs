@@ -0,0 +1,68 @@
+use regex::Regex;
+
+pub struct PascalPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl PascalPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Compiled units
+            Regex::new(r"\.ppu$").unwrap(),
+            Regex::new(r"\.dcu$").unwrap(),
+
+            // Delphi IDE history
+            Regex::new(r"__history/").unwrap(),
+            Regex::new(r"__recovery/").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Lazarus backups
+            Regex::new(r"(^|/)backup/").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Units and programs
+            "pas".to_string(),
+            "dpr".to_string(),
+            "lpr".to_string(),
+
+            // Forms
+            "dfm".to_string(),
+            "lfm".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Lazarus projects
+            "Makefile.fpc",
+        ]
+    }
+}
+
+impl Default for PascalPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
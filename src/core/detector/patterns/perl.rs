@@ -21,8 +21,8 @@ impl PerlPatterns {
             Regex::new(r"META\.yml").unwrap(),
             Regex::new(r"META\.json").unwrap(),
             
-            // ExtUtils::MakeMaker artifacts
-            Regex::new(r"Makefile$").unwrap(),
+            // ExtUtils::MakeMaker artifacts; the generated `Makefile` can't be told
+            // from a hand-written one by name, so it is counted as a Makefile
             Regex::new(r"Makefile\.old").unwrap(),
             Regex::new(r"pm_to_blib").unwrap(),
             
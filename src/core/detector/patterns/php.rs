@@ -123,13 +123,6 @@ impl PhpPatterns {
             Regex::new(r"\.twig\.cache").unwrap(),
             Regex::new(r"\.smarty\.cache").unwrap(),
             
-            // Minified files
-            Regex::new(r"\.min\.js$").unwrap(),
-            Regex::new(r"\.min\.css$").unwrap(),
-            
-            // Source maps
-            Regex::new(r"\.map$").unwrap(),
-            
             // Bower
             Regex::new(r"bower_components/").unwrap(),
             
@@ -252,13 +245,6 @@ impl PhpPatterns {
             // Frontend build artifacts
             Regex::new(r"node_modules/").unwrap(),
             Regex::new(r"bower_components/").unwrap(),
-            
-            // Minified files
-            Regex::new(r"\.min\.js$").unwrap(),
-            Regex::new(r"\.min\.css$").unwrap(),
-            
-            // Source maps
-            Regex::new(r"\.map$").unwrap(),
         ];
 
         let extensions = vec![
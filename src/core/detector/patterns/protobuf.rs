@@ -0,0 +1,58 @@
+use regex::Regex;
+
+pub struct ProtobufPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl ProtobufPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Compiled descriptor sets (generated bindings are left to the generated-code detector)
+            Regex::new(r"\.desc$").unwrap(),
+            Regex::new(r"\.binpb$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Buf cache
+            Regex::new(r"\.buf/").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Protocol Buffers definitions
+            "proto".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Buf configuration
+            "buf.yaml", "buf.gen.yaml", "buf.work.yaml", "buf.lock",
+        ]
+    }
+}
+
+impl Default for ProtobufPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
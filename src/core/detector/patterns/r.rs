@@ -97,6 +97,9 @@ impl RPatterns {
             "rmd".to_string(),
             "Rmd".to_string(),
             
+            // Quarto documents
+            "qmd".to_string(),
+            
             // R Notebook
             "rnw".to_string(),
             "Rnw".to_string(),
@@ -156,9 +156,6 @@ impl SwiftPatterns {
             // Sourcery
             Regex::new(r"\.sourcery/").unwrap(),
             
-            // R.swift
-            Regex::new(r"R\.generated\.swift").unwrap(),
-            
             // SwiftGen
             Regex::new(r"\.swiftgen/").unwrap(),
             
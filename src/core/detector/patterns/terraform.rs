@@ -0,0 +1,74 @@
+use regex::Regex;
+
+pub struct TerraformPatterns {
+    external_patterns: Vec<Regex>,
+    cache_patterns: Vec<Regex>,
+    extensions: Vec<String>,
+}
+
+impl TerraformPatterns {
+    pub fn new() -> Self {
+        let external_patterns = vec![
+            // Downloaded providers and modules
+            Regex::new(r"\.terraform/").unwrap(),
+            Regex::new(r"\.terragrunt-cache/").unwrap(),
+
+            // State, which Terraform writes and may hold secrets
+            Regex::new(r"\.tfstate$").unwrap(),
+            Regex::new(r"\.tfstate\.backup$").unwrap(),
+            Regex::new(r"\.tfplan$").unwrap(),
+            Regex::new(r"crash\.log$").unwrap(),
+        ];
+
+        let cache_patterns = vec![
+            // Provider and module cache
+            Regex::new(r"\.terraform/").unwrap(),
+            Regex::new(r"\.terragrunt-cache/").unwrap(),
+        ];
+
+        let extensions = vec![
+            // Terraform and HCL sources
+            "tf".to_string(),
+            "tfvars".to_string(),
+            "hcl".to_string(),
+        ];
+
+        Self {
+            external_patterns,
+            cache_patterns,
+            extensions,
+        }
+    }
+
+    pub fn get_external_patterns(&self) -> &[Regex] {
+        &self.external_patterns
+    }
+
+    pub fn get_cache_patterns(&self) -> &[Regex] {
+        &self.cache_patterns
+    }
+
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn get_script_names() -> Vec<&'static str> {
+        vec![
+            // Terraform root modules
+            "main.tf", "variables.tf", "outputs.tf", "providers.tf",
+            "versions.tf", "terraform.tfvars",
+
+            // Terragrunt
+            "terragrunt.hcl",
+
+            // Lock file
+            ".terraform.lock.hcl",
+        ]
+    }
+}
+
+impl Default for TerraformPatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,54 @@
+use regex::Regex;
+
+/// Test sources, recognized by the directory and file naming conventions of
+/// common test runners.
+///
+/// Inline Rust test modules (`#[cfg(test)]`) live in production files and are
+/// split out line by line by the counter instead.
+pub struct TestCodePatterns {
+    test_patterns: Vec<Regex>,
+}
+
+impl TestCodePatterns {
+    pub fn new() -> Self {
+        let test_patterns = vec![
+            // Test directories (Rust integration tests, pytest, JUnit, Jest, RSpec)
+            Regex::new(r"(^|/)(tests?|__tests__|spec|specs)/").unwrap(),
+            Regex::new(r"(^|/)src/it/").unwrap(),
+
+            // Out-of-line Rust test modules (`#[cfg(test)] mod tests;`)
+            Regex::new(r"(^|/)tests?\.rs$").unwrap(),
+
+            // Go, Rust, C/C++, Elixir and Erlang test files (`foo_test.go`, `foo_SUITE.erl`)
+            Regex::new(r"_(test|tests)\.[^/]+$").unwrap(),
+            Regex::new(r"_SUITE\.erl$").unwrap(),
+
+            // pytest and unittest modules
+            Regex::new(r"(^|/)test_[^/]+\.py$").unwrap(),
+            Regex::new(r"(^|/)conftest\.py$").unwrap(),
+
+            // Jest, Vitest, Mocha and Jasmine (`foo.test.ts`, `foo.spec.js`)
+            Regex::new(r"\.(test|spec)\.[cm]?[jt]sx?$").unwrap(),
+
+            // RSpec and Minitest
+            Regex::new(r"_spec\.rb$").unwrap(),
+
+            // JUnit, xUnit, XCTest, PHPUnit and Kotest classes (`FooTest.java`, `FooTests.cs`)
+            Regex::new(r"[a-z0-9]Tests?\.(java|kt|kts|scala|groovy|cs|swift|php)$").unwrap(),
+            Regex::new(r"[a-z0-9](Spec|IT)\.(java|kt|kts|scala|groovy)$").unwrap(),
+        ];
+
+        Self { test_patterns }
+    }
+
+    pub fn matches(&self, path_str: &str) -> bool {
+        let normalized = path_str.replace('\\', "/");
+        self.test_patterns.iter().any(|pattern| pattern.is_match(&normalized))
+    }
+}
+
+impl Default for TestCodePatterns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
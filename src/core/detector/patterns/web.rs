@@ -39,10 +39,6 @@ impl WebPatterns {
             Regex::new(r"vendor/").unwrap(),
             Regex::new(r"libs/").unwrap(),
             Regex::new(r"lib/").unwrap(),
-            
-            // Generated files
-            Regex::new(r"\.generated/").unwrap(),
-            Regex::new(r"generated/").unwrap(),
         ];
 
         let cache_patterns = vec![
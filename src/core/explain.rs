@@ -0,0 +1,347 @@
+use crate::core::filters::FileFilter;
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::format;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Example paths kept per rule for the summary table
+const EXAMPLES_PER_RULE: usize = 3;
+
+/// Rule that keeps a path out of the counted totals, in the order the
+/// analysis applies them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exclusion {
+    /// Hidden file or directory, walked with `--hidden`
+    Hidden,
+    /// Matched by `.gitignore`, `.ignore`, `.howmanyignore` or `--ignore-file`
+    IgnoreFile,
+    /// Matched by an `--ignore` pattern or the config's `custom_ignore_patterns`
+    IgnorePattern,
+    /// Not matched by the `--include` patterns or the config's `custom_include_patterns`
+    NotIncluded,
+    /// OS, editor, temporary or version control file (`.DS_Store`, `*.swp`)
+    IgnoredName,
+    /// Dependency directory (`node_modules/`, `vendor/`, `site-packages/`)
+    External,
+    /// Build output or cache (`target/`, `dist/`, `__pycache__/`)
+    BuildOutput,
+    /// Rejected by a language plugin
+    Plugin,
+    /// Extension of a binary format (images, archives, compiled objects)
+    BinaryExtension,
+    /// Extension no counted language uses
+    NotCode,
+    /// Outside the `--shard` this run counts
+    Shard,
+    /// Not matched by the `--ext` filter
+    ExtensionFilter,
+    /// Larger than `--max-file-size`
+    TooLarge,
+    /// Not matched by the git pathspecs
+    Pathspec,
+    /// Binary content under a source-like name
+    BinaryContent,
+    /// Could not be read or decoded
+    Unreadable,
+    /// Test fixture, counted with `--include-fixtures`
+    Fixture,
+    /// Generated by name or marker comment, counted with `--include-generated`
+    Generated,
+    /// Minified by name or content, counted with `--include-minified`
+    Minified,
+}
+
+impl std::fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Self::Hidden => "hidden (--hidden walks it)",
+            Self::IgnoreFile => "ignored by .gitignore, .ignore, .howmanyignore or --ignore-file",
+            Self::IgnorePattern => "matched by --ignore or custom_ignore_patterns",
+            Self::NotIncluded => "not matched by --include or custom_include_patterns",
+            Self::IgnoredName => "OS, editor, temporary or version control file",
+            Self::External => "external dependency",
+            Self::BuildOutput => "build output or cache",
+            Self::Plugin => "rejected by a plugin",
+            Self::BinaryExtension => "binary file extension",
+            Self::NotCode => "not a code file",
+            Self::Shard => "outside --shard",
+            Self::ExtensionFilter => "not matched by --ext",
+            Self::TooLarge => "larger than the size limit",
+            Self::Pathspec => "not matched by the pathspecs",
+            Self::BinaryContent => "binary content",
+            Self::Unreadable => "unreadable",
+            Self::Fixture => "test fixture (--include-fixtures counts it)",
+            Self::Generated => "generated code (--include-generated counts it)",
+            Self::Minified => "minified (--include-minified counts it)",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+/// Whether one path was counted, or the rule that excluded it. A directory the
+/// walk skipped stands for everything below it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// `None` when the file is counted
+    pub exclusion: Option<Exclusion>,
+}
+
+/// Paths excluded by one rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExclusionSummary {
+    pub rule: Exclusion,
+    pub description: String,
+    pub paths: usize,
+    pub examples: Vec<PathBuf>,
+}
+
+/// Explanations of every path an analysis came across (`--explain`)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExplainReport {
+    pub counted: usize,
+    pub excluded: Vec<ExclusionSummary>,
+    pub paths: Vec<Explanation>,
+}
+
+impl ExplainReport {
+    pub fn new(mut paths: Vec<Explanation>) -> Self {
+        paths.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut by_rule: BTreeMap<Exclusion, ExclusionSummary> = BTreeMap::new();
+        for explanation in &paths {
+            let Some(rule) = explanation.exclusion else {
+                continue;
+            };
+            let summary = by_rule.entry(rule).or_insert_with(|| ExclusionSummary {
+                rule,
+                description: rule.to_string(),
+                paths: 0,
+                examples: Vec::new(),
+            });
+            summary.paths += 1;
+            if summary.examples.len() < EXAMPLES_PER_RULE {
+                summary.examples.push(explanation.path.clone());
+            }
+        }
+
+        let mut excluded: Vec<_> = by_rule.into_values().collect();
+        excluded.sort_by(|a, b| b.paths.cmp(&a.paths).then(a.rule.cmp(&b.rule)));
+
+        Self {
+            counted: paths.iter().filter(|explanation| explanation.exclusion.is_none()).count(),
+            excluded,
+            paths,
+        }
+    }
+
+    /// Explanation of `path`, or of the skipped directory it lies in
+    pub fn explain(&self, path: &Path) -> Option<&Explanation> {
+        path.ancestors().find_map(|candidate| {
+            self.paths
+                .binary_search_by(|explanation| explanation.path.as_path().cmp(candidate))
+                .ok()
+                .map(|index| &self.paths[index])
+                .filter(|explanation| explanation.path == path || (explanation.is_dir && explanation.exclusion.is_some()))
+        })
+    }
+
+    /// Verdict on `path` alone, as `explained_path` gives it
+    pub fn verdict(&self, path: &Path) -> PathVerdict {
+        let explanation = self.explain(path);
+        // Paths in a skipped directory share its verdict
+        let excluded_with = explanation.filter(|explanation| explanation.path != path).map(|explanation| explanation.path.clone());
+        let rule = explanation.and_then(|explanation| explanation.exclusion);
+        let verdict = match (explanation, rule) {
+            (None, _) if path.is_dir() => "walked".to_string(),
+            (None, _) => "not reached by the walk (below --max-depth)".to_string(),
+            (Some(_), None) => "counted".to_string(),
+            (Some(_), Some(rule)) => match &excluded_with {
+                Some(directory) => format!("excluded, in {}: {}", directory.display(), rule),
+                None => format!("excluded: {}", rule),
+            },
+        };
+
+        PathVerdict {
+            counted: explanation.is_some() && rule.is_none(),
+            excluded_with,
+            path: path.to_path_buf(),
+            rule,
+            verdict,
+        }
+    }
+
+    /// The counted and excluded totals, then a row per rule with example paths
+    pub fn render_text(&self, roots: &[PathBuf]) -> String {
+        let roots: Vec<_> = roots.iter().map(|root| root.display().to_string()).collect();
+        let excluded: usize = self.excluded.iter().map(|summary| summary.paths).sum();
+        let mut output = format!(
+            "{} files counted and {} paths excluded below {} (a skipped directory counts once)\n",
+            format::format_number(self.counted),
+            format::format_number(excluded),
+            roots.join(", ")
+        );
+        if self.excluded.is_empty() {
+            return output;
+        }
+
+        let width = self.excluded.iter().map(|summary| summary.description.len()).max().unwrap_or(0);
+        output.push_str(&format!("\n{:<width$}  {:>8}\n", "Excluded by", "Paths", width = width));
+        for summary in &self.excluded {
+            output.push_str(&format!("{:<width$}  {:>8}\n", summary.description, format::format_number(summary.paths), width = width));
+            let examples: Vec<_> = summary.examples.iter().map(|example| example.display().to_string()).collect();
+            output.push_str(&format!("  e.g. {}\n", examples.join(", ")));
+        }
+        output
+    }
+}
+
+/// Whether one path asked about with `--explain <path>` is counted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathVerdict {
+    pub counted: bool,
+    /// Skipped directory the path lies in, whose verdict it shares
+    pub excluded_with: Option<PathBuf>,
+    pub path: PathBuf,
+    pub rule: Option<Exclusion>,
+    pub verdict: String,
+}
+
+impl std::fmt::Display for PathVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.verdict)
+    }
+}
+
+/// Explain every path below `roots`: what `filter` skips, then each walked
+/// file with the rule `exclusion` gives it from its root and path, `None` to
+/// count it. A file reachable from several roots is explained once.
+pub fn explain_walk(roots: &[PathBuf], filter: &FileFilter, mut exclusion: impl FnMut(&Path, &Path) -> Option<Exclusion>) -> ExplainReport {
+    let mut explanations = Vec::new();
+    let mut seen = HashSet::new();
+    for root in roots {
+        for (skipped_path, is_dir, exclusion) in filter.skipped_paths(root) {
+            explanations.push(Explanation { path: skipped_path, is_dir, exclusion: Some(exclusion) });
+        }
+
+        for entry in filter.walk_directory(root) {
+            let entry_path = entry.path();
+            if !entry_path.is_file() || !seen.insert(entry_path.to_path_buf()) {
+                continue;
+            }
+            explanations.push(Explanation { path: entry_path.to_path_buf(), is_dir: false, exclusion: exclusion(root, entry_path) });
+        }
+    }
+    ExplainReport::new(explanations)
+}
+
+/// `path` as the walk of the root it lies in reaches it (`./src/main.rs`)
+pub fn explained_path(roots: &[PathBuf], path: &Path) -> Result<PathBuf> {
+    let canonical = path.canonicalize().map_err(|e| HowManyError::filter(format!("Cannot explain {}: {}", path.display(), e)))?;
+    roots
+        .iter()
+        .find_map(|root| {
+            let relative = canonical.strip_prefix(root.canonicalize().ok()?).ok()?;
+            Some(root.join(relative))
+        })
+        .ok_or_else(|| {
+            let roots: Vec<_> = roots.iter().map(|root| root.display().to_string()).collect();
+            HowManyError::filter(format!("{} is not below {}", path.display(), roots.join(", ")))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explanation(path: &str, is_dir: bool, exclusion: Option<Exclusion>) -> Explanation {
+        Explanation { path: PathBuf::from(path), is_dir, exclusion }
+    }
+
+    #[test]
+    fn test_exclusions_summarized_by_rule() {
+        let report = ExplainReport::new(vec![
+            explanation("./src/main.rs", false, None),
+            explanation("./target", true, Some(Exclusion::IgnoreFile)),
+            explanation("./logo.png", false, Some(Exclusion::BinaryExtension)),
+            explanation("./dist", true, Some(Exclusion::IgnoreFile)),
+            explanation("./src/lib.rs", false, None),
+        ]);
+
+        assert_eq!(report.counted, 2);
+        assert_eq!(report.excluded.len(), 2);
+        assert_eq!(report.excluded[0].rule, Exclusion::IgnoreFile);
+        assert_eq!(report.excluded[0].paths, 2);
+        assert_eq!(report.excluded[0].examples, vec![PathBuf::from("./dist"), PathBuf::from("./target")]);
+        assert_eq!(report.excluded[1].rule, Exclusion::BinaryExtension);
+    }
+
+    #[test]
+    fn test_paths_in_skipped_directories_explained_by_the_directory() {
+        let report = ExplainReport::new(vec![
+            explanation("./src/main.rs", false, None),
+            explanation("./target", true, Some(Exclusion::IgnoreFile)),
+        ]);
+
+        assert_eq!(report.explain(Path::new("./src/main.rs")).unwrap().exclusion, None);
+        assert_eq!(report.explain(Path::new("./target/debug/howmany")).unwrap().path, PathBuf::from("./target"));
+        // Files the walk never came across have no explanation
+        assert!(report.explain(Path::new("./src/gone.rs")).is_none());
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let report = ExplainReport::new(vec![
+            explanation("./src/main.rs", false, None),
+            explanation("./target", true, Some(Exclusion::IgnoreFile)),
+            explanation("./logo.png", false, Some(Exclusion::BinaryExtension)),
+        ]);
+
+        let counted = report.verdict(Path::new("./src/main.rs"));
+        assert!(counted.counted);
+        assert_eq!(counted.to_string(), "./src/main.rs: counted");
+
+        let within = report.verdict(Path::new("./target/debug/howmany"));
+        assert_eq!((within.counted, within.rule, within.excluded_with), (false, Some(Exclusion::IgnoreFile), Some(PathBuf::from("./target"))));
+        assert_eq!(within.verdict, format!("excluded, in ./target: {}", Exclusion::IgnoreFile));
+
+        assert_eq!(report.verdict(Path::new("./logo.png")).verdict, "excluded: binary file extension");
+        assert_eq!(report.verdict(Path::new("./deep/gone.rs")).verdict, "not reached by the walk (below --max-depth)");
+
+        let text = report.render_text(&[PathBuf::from(".")]);
+        assert!(text.starts_with("1 files counted and 2 paths excluded below . (a skipped directory counts once)\n"));
+        assert!(text.contains("  e.g. ./logo.png\n"));
+    }
+
+    #[test]
+    fn test_explain_walk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        for (path, contents) in [("src/main.rs", "fn main() {}\n"), ("notes.txt", "todo\n"), (".cache/state.json", "{}\n")] {
+            std::fs::create_dir_all(root.join(path).parent().unwrap()).unwrap();
+            std::fs::write(root.join(path), contents).unwrap();
+        }
+        let filter = FileFilter::new().respect_hidden(true);
+
+        // `src` is reached from both roots but explained once
+        let roots = [root.clone(), root.join("src")];
+        let mut asked = Vec::new();
+        let report = explain_walk(&roots, &filter, |walked_root, path| {
+            asked.push((walked_root.to_path_buf(), path.to_path_buf()));
+            (path.extension()? == "txt").then_some(Exclusion::NotCode)
+        });
+        assert_eq!(asked.len(), 2);
+        assert!(asked.iter().all(|(walked_root, _)| walked_root == &root));
+        assert_eq!(report.counted, 1);
+        assert_eq!(report.explain(&root.join("notes.txt")).unwrap().exclusion, Some(Exclusion::NotCode));
+        assert_eq!(report.explain(&root.join(".cache/state.json")).unwrap().path, root.join(".cache"));
+
+        assert_eq!(explained_path(&roots, &root.join("src/main.rs")).unwrap(), root.join("src/main.rs"));
+        let outside = tempfile::tempdir().unwrap();
+        let error = explained_path(&roots, outside.path()).unwrap_err();
+        assert!(error.to_string().contains("is not below"), "{}", error);
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::Path;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use crate::utils::errors::{HowManyError, Result};
+
+/// Compiled path globs with gitignore-style conventions.
+///
+/// Supports `*`, `?`, `**`, character classes (`[a-z]`), alternation (`{a,b}`)
+/// and `!` negation. A pattern without a `/` matches at any depth, a leading
+/// `/` anchors it to the root, and a pattern matching a directory also matches
+/// everything below it.
+#[derive(Debug, Clone)]
+pub struct GlobPatterns {
+    positive: GlobSet,
+    negated: GlobSet,
+}
+
+impl GlobPatterns {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self> {
+        let mut positive = GlobSetBuilder::new();
+        let mut negated = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let pattern = pattern.as_ref().trim();
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let (builder, body) = match pattern.strip_prefix('!') {
+                Some(body) => (&mut negated, body),
+                None => (&mut positive, pattern),
+            };
+
+            for expanded in Self::expand(body) {
+                let glob = GlobBuilder::new(&expanded)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|e| HowManyError::filter(format!("Invalid glob pattern '{}': {}", pattern, e)))?;
+                builder.add(glob);
+            }
+        }
+
+        let build = |builder: GlobSetBuilder| {
+            builder.build().map_err(|e| HowManyError::filter(format!("Invalid glob patterns: {}", e)))
+        };
+
+        Ok(Self {
+            positive: build(positive)?,
+            negated: build(negated)?,
+        })
+    }
+
+    /// Rewrite a gitignore-style pattern into equivalent globset globs
+    fn expand(pattern: &str) -> Vec<String> {
+        let pattern = pattern.strip_prefix("./").unwrap_or(pattern).trim_end_matches('/');
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+
+        let base = if anchored {
+            pattern.to_string()
+        } else {
+            format!("**/{}", pattern)
+        };
+
+        vec![format!("{}/**", base), base]
+    }
+
+    /// No positive patterns, so nothing can match
+    pub fn is_empty(&self) -> bool {
+        self.positive.is_empty()
+    }
+
+    pub fn has_negations(&self) -> bool {
+        !self.negated.is_empty()
+    }
+
+    /// Matches at least one pattern and none of the negated ones
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.positive.is_match(path) && !self.negated.is_match(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn globs(patterns: &[&str]) -> GlobPatterns {
+        GlobPatterns::new(patterns).unwrap()
+    }
+
+    #[test]
+    fn test_unanchored_patterns_match_at_any_depth() {
+        let patterns = globs(&["*.tmp", "node_modules/"]);
+        assert!(patterns.is_match(Path::new("a.tmp")));
+        assert!(patterns.is_match(Path::new("src/deep/a.tmp")));
+        assert!(patterns.is_match(Path::new("web/node_modules/lib/index.js")));
+        assert!(!patterns.is_match(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn test_anchored_and_double_star_patterns() {
+        let patterns = globs(&["/build", "src/**/generated/*.rs"]);
+        assert!(patterns.is_match(Path::new("build/out.rs")));
+        assert!(!patterns.is_match(Path::new("crates/build/out.rs")));
+        assert!(patterns.is_match(Path::new("src/generated/a.rs")));
+        assert!(patterns.is_match(Path::new("src/api/v1/generated/a.rs")));
+        // `*` does not cross directory separators
+        assert!(!patterns.is_match(Path::new("src/generated/nested/a.rs")));
+    }
+
+    #[test]
+    fn test_character_classes_and_negation() {
+        let patterns = globs(&["tests/fixture[0-9]/**", "!tests/fixture1/keep.rs"]);
+        assert!(patterns.is_match(Path::new("tests/fixture2/a.rs")));
+        assert!(!patterns.is_match(Path::new("tests/fixtureA/a.rs")));
+        assert!(!patterns.is_match(Path::new("tests/fixture1/keep.rs")));
+        assert!(patterns.has_negations());
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_reported() {
+        assert!(GlobPatterns::new(&["src/[a-"]).is_err());
+        assert!(GlobPatterns::new::<&str>(&[]).unwrap().is_empty());
+    }
+}
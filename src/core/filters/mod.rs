@@ -1,6 +1,16 @@
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use ignore::{WalkBuilder, DirEntry};
+use crate::core::explain::Exclusion;
 use crate::core::patterns::PatternMatcher;
+use crate::utils::errors::{HowManyError, Result};
+
+pub mod glob;
+pub use glob::GlobPatterns;
+
+/// Per-directory ignore file with gitignore syntax, read alongside `.gitignore`
+pub const HOWMANYIGNORE_FILE: &str = ".howmanyignore";
 
 pub struct FileFilter {
     // Use gitignore-style filtering
@@ -8,6 +18,10 @@ pub struct FileFilter {
     respect_hidden: bool,
     max_depth: Option<usize>,
     custom_ignores: Vec<String>,
+    include_patterns: Vec<String>,
+    ignore_globs: Option<GlobPatterns>,
+    include_globs: Option<GlobPatterns>,
+    ignore_files: Vec<PathBuf>,
     pattern_matcher: PatternMatcher,
 }
 
@@ -18,6 +32,10 @@ impl FileFilter {
             respect_hidden: true,
             max_depth: None,
             custom_ignores: Vec::new(),
+            include_patterns: Vec::new(),
+            ignore_globs: None,
+            include_globs: None,
+            ignore_files: Vec::new(),
             pattern_matcher: PatternMatcher::new(),
         }
     }
@@ -27,9 +45,36 @@ impl FileFilter {
         self
     }
     
-    pub fn with_custom_ignores(mut self, ignores: Vec<String>) -> Self {
+    /// Skip paths matching these globs, relative to the walked directory (see [`GlobPatterns`])
+    pub fn with_custom_ignores(mut self, ignores: Vec<String>) -> Result<Self> {
         self.custom_ignores.extend(ignores);
-        self
+        self.ignore_globs = Some(GlobPatterns::new(&self.custom_ignores)?);
+        Ok(self)
+    }
+    
+    /// Restrict analysis to paths matching these globs (e.g. `src/**`).
+    /// With only negated patterns, everything except those paths is included.
+    pub fn with_include_patterns(mut self, patterns: Vec<String>) -> Result<Self> {
+        self.include_patterns.extend(patterns);
+        
+        let mut effective = self.include_patterns.clone();
+        if effective.iter().all(|pattern| pattern.trim_start().starts_with('!')) {
+            effective.insert(0, "**".to_string());
+        }
+        self.include_globs = Some(GlobPatterns::new(&effective)?);
+        Ok(self)
+    }
+    
+    /// Apply additional gitignore-style files to the whole walk.
+    /// Patterns are relative to the directory containing each file.
+    pub fn with_ignore_files(mut self, paths: Vec<PathBuf>) -> Result<Self> {
+        for path in &paths {
+            if !path.is_file() {
+                return Err(HowManyError::filter(format!("Ignore file not found: {}", path.display())));
+            }
+        }
+        self.ignore_files.extend(paths);
+        Ok(self)
     }
     
     pub fn respect_gitignore(mut self, respect: bool) -> Self {
@@ -56,14 +101,97 @@ impl FileFilter {
             builder.max_depth(Some(depth));
         }
         
-        // Add custom ignore patterns directly to the builder
-        for pattern in &self.custom_ignores {
-            builder.add_custom_ignore_filename(pattern);
+        // Dedicated .howmanyignore files are honored in every directory, like .gitignore
+        builder.add_custom_ignore_filename(HOWMANYIGNORE_FILE);
+        
+        for ignore_file in &self.ignore_files {
+            // Malformed lines are skipped rather than aborting the walk
+            let _ = builder.add_ignore(ignore_file);
+        }
+        
+        if self.ignore_globs.is_some() || self.include_globs.is_some() {
+            let root = path_ref.to_path_buf();
+            let ignores = self.ignore_globs.clone();
+            let includes = self.include_globs.clone();
+            
+            builder.filter_entry(move |entry| {
+                if entry.depth() == 0 {
+                    return true;
+                }
+                
+                let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+                let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+                
+                if let Some(ignores) = &ignores {
+                    // Keep descending into ignored directories when a negation could re-include a child
+                    if ignores.is_match(relative) && !(is_dir && ignores.has_negations()) {
+                        return false;
+                    }
+                }
+                
+                // Directories are always traversed so nested matches are still found
+                match &includes {
+                    Some(includes) => is_dir || includes.is_match(relative),
+                    None => true,
+                }
+            });
         }
         
         builder.build().filter_map(|entry| entry.ok())
     }
     
+    /// Paths below `path` that `walk_directory` skips, with the rule that
+    /// skips each. A skipped directory is listed once, for everything in it.
+    pub fn skipped_paths<P: AsRef<Path>>(&self, path: P) -> Vec<(PathBuf, bool, Exclusion)> {
+        let path_ref = path.as_ref();
+        let walked: HashSet<PathBuf> = self.walk_directory(path_ref).map(DirEntry::into_path).collect();
+        let skipped = Arc::new(Mutex::new(Vec::new()));
+        
+        // The same walk with no rules applied, stopping at every path the filtered one skipped
+        let mut builder = WalkBuilder::new(path_ref);
+        builder.standard_filters(false);
+        if let Some(depth) = self.max_depth {
+            builder.max_depth(Some(depth));
+        }
+        let recorder = Arc::clone(&skipped);
+        builder.filter_entry(move |entry| {
+            if walked.contains(entry.path()) {
+                return true;
+            }
+            let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+            recorder.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push((entry.path().to_path_buf(), is_dir));
+            false
+        });
+        builder.build().for_each(drop);
+        
+        let skipped = std::mem::take(&mut *skipped.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+        skipped
+            .into_iter()
+            .map(|(skipped_path, is_dir)| {
+                let exclusion = self.walk_exclusion(path_ref, &skipped_path, is_dir);
+                (skipped_path, is_dir, exclusion)
+            })
+            .collect()
+    }
+    
+    /// Rule that made the walk of `root` skip `path`. The walk doesn't say
+    /// which rule matched, so the ones that can be checked here are checked,
+    /// and ignore files are left as the cause of the rest.
+    fn walk_exclusion(&self, root: &Path, path: &Path, is_dir: bool) -> Exclusion {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let is_hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        
+        if self.respect_hidden && is_hidden {
+            Exclusion::Hidden
+        } else if self.ignore_globs.as_ref().is_some_and(|globs| globs.is_match(relative)) {
+            Exclusion::IgnorePattern
+        } else if !is_dir && self.include_globs.as_ref().is_some_and(|globs| !globs.is_match(relative)) {
+            Exclusion::NotIncluded
+        } else {
+            Exclusion::IgnoreFile
+        }
+    }
+    
     pub fn should_include_file(&self, path: &Path) -> bool {
         let path_str = path.to_string_lossy();
         
@@ -78,10 +206,8 @@ impl FileFilter {
         }
         
         // Check against custom ignore patterns
-        for pattern in &self.custom_ignores {
-            if self.matches_pattern(&path_str, pattern) {
-                return false;
-            }
+        if self.ignore_globs.as_ref().is_some_and(|globs| globs.is_match(path)) {
+            return false;
         }
         
         // Check if it's a binary file
@@ -92,37 +218,6 @@ impl FileFilter {
             }
         }
         
-        // Check if it's a generated file
-        if let Some(filename) = path.file_name() {
-            let filename_str = filename.to_string_lossy();
-            if self.pattern_matcher.is_generated_file(&filename_str) {
-                return false;
-            }
-        }
-        
         true
     }
-    
-    fn matches_pattern(&self, path: &str, pattern: &str) -> bool {
-        // Simple glob-like matching for custom patterns
-        if pattern.ends_with('/') {
-            // Directory pattern
-            let dir_pattern = &pattern[..pattern.len() - 1];
-            return path.contains(dir_pattern);
-        } else if pattern.starts_with("*.") {
-            // Extension pattern
-            let ext = &pattern[2..];
-            return path.ends_with(ext);
-        } else if pattern.contains('*') {
-            // Wildcard pattern - simple implementation
-            let parts: Vec<&str> = pattern.split('*').collect();
-            if parts.len() == 2 {
-                return path.starts_with(parts[0]) && path.ends_with(parts[1]);
-            }
-        } else {
-            // Exact match
-            return path.contains(pattern);
-        }
-        false
-    }
-} 
\ No newline at end of file
+}
\ No newline at end of file
@@ -0,0 +1,345 @@
+use crate::core::counter::CodeCounter;
+use crate::core::history::{git, BlobReader};
+use crate::core::stats::complexity::languages::{get_language_analyzer, resolve_extension};
+use crate::core::stats::complexity::FunctionThresholds;
+use crate::utils::encoding;
+use crate::utils::errors::Result;
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Limits a changed file must stay within, from the SARIF rules of the same metrics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GateThresholds {
+    pub max_file_lines: usize,
+    pub functions: FunctionThresholds,
+}
+
+/// Largest values a file reaches: its length and the worst of its functions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileMeasures {
+    pub lines: usize,
+    pub cyclomatic_complexity: usize,
+    pub cognitive_complexity: usize,
+    pub nesting_depth: usize,
+    pub function_length: usize,
+    pub parameters: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GateMetric {
+    FileLines,
+    CyclomaticComplexity,
+    CognitiveComplexity,
+    NestingDepth,
+    FunctionLength,
+    Parameters,
+}
+
+impl GateMetric {
+    pub const ALL: [GateMetric; 6] = [
+        GateMetric::FileLines,
+        GateMetric::CyclomaticComplexity,
+        GateMetric::CognitiveComplexity,
+        GateMetric::NestingDepth,
+        GateMetric::FunctionLength,
+        GateMetric::Parameters,
+    ];
+
+    pub fn value(&self, measures: &FileMeasures) -> usize {
+        match self {
+            GateMetric::FileLines => measures.lines,
+            GateMetric::CyclomaticComplexity => measures.cyclomatic_complexity,
+            GateMetric::CognitiveComplexity => measures.cognitive_complexity,
+            GateMetric::NestingDepth => measures.nesting_depth,
+            GateMetric::FunctionLength => measures.function_length,
+            GateMetric::Parameters => measures.parameters,
+        }
+    }
+
+    pub fn limit(&self, thresholds: &GateThresholds) -> usize {
+        match self {
+            GateMetric::FileLines => thresholds.max_file_lines,
+            GateMetric::CyclomaticComplexity => thresholds.functions.max_cyclomatic_complexity,
+            GateMetric::CognitiveComplexity => thresholds.functions.max_cognitive_complexity,
+            GateMetric::NestingDepth => thresholds.functions.max_nesting_depth,
+            GateMetric::FunctionLength => thresholds.functions.max_function_length,
+            GateMetric::Parameters => thresholds.functions.max_parameters,
+        }
+    }
+}
+
+impl fmt::Display for GateMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GateMetric::FileLines => "file lines",
+            GateMetric::CyclomaticComplexity => "cyclomatic complexity",
+            GateMetric::CognitiveComplexity => "cognitive complexity",
+            GateMetric::NestingDepth => "nesting depth",
+            GateMetric::FunctionLength => "function length",
+            GateMetric::Parameters => "parameters",
+        })
+    }
+}
+
+/// A changed file over a limit, with its value before the change (`None` for a new file)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GateViolation {
+    pub path: String,
+    pub metric: GateMetric,
+    pub before: Option<usize>,
+    pub after: usize,
+    pub limit: usize,
+}
+
+/// Result of gating the changes against a base revision
+#[derive(Debug, Clone, Serialize)]
+pub struct GateReport {
+    pub base: String,
+    /// Commit the changes are compared with: where the current branch forked from `base`
+    pub merge_base: String,
+    pub files_checked: usize,
+    /// Limits a changed file satisfied before and exceeds now, which fail the gate
+    pub violations: Vec<GateViolation>,
+    /// Limits a changed file already exceeded before the change, which are allowed
+    pub legacy: Vec<GateViolation>,
+}
+
+impl GateReport {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// A file changed since the merge base, with paths relative to the repository root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub path: String,
+    /// Path at the merge base, `None` for an added or untracked file
+    pub old_path: Option<String>,
+}
+
+/// Worst-offender gate for pull requests: fails only when a changed file crosses
+/// a limit it satisfied at the base, so legacy files over a limit can still be
+/// edited while no file newly degrades past one. New files must satisfy every limit.
+pub struct DiffGate {
+    path: PathBuf,
+    base: String,
+    thresholds: GateThresholds,
+}
+
+impl DiffGate {
+    pub fn new(path: impl Into<PathBuf>, base: impl Into<String>, thresholds: GateThresholds) -> Self {
+        Self {
+            path: path.into(),
+            base: base.into(),
+            thresholds,
+        }
+    }
+
+    /// Compare the working tree under the gated path with the merge base of `base`
+    /// and `HEAD`. `accept` decides which changed paths, relative to the gated
+    /// path, are checked.
+    pub fn check(&self, counter: &CodeCounter, accept: impl Fn(&Path) -> bool) -> Result<GateReport> {
+        let merge_base = git(&self.path, &["merge-base", &self.base, "HEAD"])?.trim().to_string();
+        let root = PathBuf::from(git(&self.path, &["rev-parse", "--show-toplevel"])?.trim());
+        let prefix = git(&self.path, &["rev-parse", "--show-prefix"])?.trim().to_string();
+
+        let diff = git(&self.path, &["diff", "--name-status", "-z", "-M", &merge_base, "--", "."])?;
+        let untracked = git(&self.path, &["ls-files", "--others", "--exclude-standard", "--full-name", "-z", "--", "."])?;
+        let mut changes = parse_name_status(&diff);
+        changes.extend(untracked.split('\0').filter(|path| !path.is_empty()).map(|path| ChangedFile {
+            path: path.to_string(),
+            old_path: None,
+        }));
+
+        let mut blobs = BlobReader::spawn(&self.path)?;
+        let mut report = GateReport {
+            base: self.base.clone(),
+            merge_base: merge_base.clone(),
+            files_checked: 0,
+            violations: Vec::new(),
+            legacy: Vec::new(),
+        };
+
+        for change in changes {
+            let relative_path = Path::new(change.path.strip_prefix(&prefix).unwrap_or(&change.path));
+            if !accept(relative_path) {
+                continue;
+            }
+            // Unreadable, binary and oversized files aren't gated, as they aren't counted
+            let Ok(contents) = fs::read(root.join(&change.path)) else {
+                continue;
+            };
+            let Ok(after) = measure(counter, relative_path, &contents) else {
+                continue;
+            };
+            let before = match &change.old_path {
+                Some(old_path) => {
+                    let contents = blobs.read(&format!("{}:{}", merge_base, old_path))?;
+                    measure(counter, relative_path, &contents).ok()
+                }
+                None => None,
+            };
+
+            report.files_checked += 1;
+            let (violations, legacy) = compare(&relative_path.to_string_lossy(), before.as_ref(), &after, &self.thresholds);
+            report.violations.extend(violations);
+            report.legacy.extend(legacy);
+        }
+
+        Ok(report)
+    }
+}
+
+/// Measure a file's contents; `path` picks the language
+pub fn measure(counter: &CodeCounter, path: &Path, contents: &[u8]) -> Result<FileMeasures> {
+    let stats = counter.count_contents(path, contents)?;
+    let mut measures = FileMeasures {
+        lines: stats.total_lines,
+        ..FileMeasures::default()
+    };
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+    let lines: Vec<String> = encoding::decode(contents).text.lines().map(str::to_string).collect();
+    if let Some(analyzer) = get_language_analyzer(resolve_extension(&extension, lines.iter().map(String::as_str))) {
+        for function in analyzer.analyze_functions(&lines)? {
+            measures.cyclomatic_complexity = measures.cyclomatic_complexity.max(function.cyclomatic_complexity);
+            measures.cognitive_complexity = measures.cognitive_complexity.max(function.cognitive_complexity);
+            measures.nesting_depth = measures.nesting_depth.max(function.nesting_depth);
+            measures.function_length = measures.function_length.max(function.line_count);
+            measures.parameters = measures.parameters.max(function.parameter_count);
+        }
+    }
+
+    Ok(measures)
+}
+
+/// Split the limits a file exceeds after a change into newly crossed ones and
+/// ones it already exceeded before
+pub fn compare(
+    path: &str,
+    before: Option<&FileMeasures>,
+    after: &FileMeasures,
+    thresholds: &GateThresholds,
+) -> (Vec<GateViolation>, Vec<GateViolation>) {
+    let mut violations = Vec::new();
+    let mut legacy = Vec::new();
+
+    for metric in GateMetric::ALL {
+        let limit = metric.limit(thresholds);
+        let value = metric.value(after);
+        if value <= limit {
+            continue;
+        }
+        let previous = before.map(|before| metric.value(before));
+        let violation = GateViolation {
+            path: path.to_string(),
+            metric,
+            before: previous,
+            after: value,
+            limit,
+        };
+        if previous.is_some_and(|previous| previous > limit) {
+            legacy.push(violation);
+        } else {
+            violations.push(violation);
+        }
+    }
+
+    (violations, legacy)
+}
+
+/// Parse `git diff --name-status -z -M` output. Deleted files are left out, and
+/// a renamed file keeps its old path so it is compared with its previous contents.
+pub fn parse_name_status(output: &str) -> Vec<ChangedFile> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut changes = Vec::new();
+
+    while let Some(status) = fields.next() {
+        let kind = status.chars().next().unwrap_or(' ');
+        let Some(path) = fields.next() else {
+            break;
+        };
+        match kind {
+            // Renames and copies list the old path, then the new one
+            'R' | 'C' => {
+                let Some(new_path) = fields.next() else {
+                    break;
+                };
+                changes.push(ChangedFile {
+                    path: new_path.to_string(),
+                    old_path: Some(path.to_string()),
+                });
+            }
+            'A' => changes.push(ChangedFile { path: path.to_string(), old_path: None }),
+            'D' => {}
+            _ => changes.push(ChangedFile {
+                path: path.to_string(),
+                old_path: Some(path.to_string()),
+            }),
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds() -> GateThresholds {
+        GateThresholds {
+            max_file_lines: 100,
+            functions: FunctionThresholds::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_name_status() {
+        let changes = parse_name_status("M\0src/a.rs\0A\0src/new.rs\0D\0old.rs\0R087\0src/b.rs\0src/c.rs\0");
+
+        assert_eq!(changes, vec![
+            ChangedFile { path: "src/a.rs".to_string(), old_path: Some("src/a.rs".to_string()) },
+            ChangedFile { path: "src/new.rs".to_string(), old_path: None },
+            ChangedFile { path: "src/c.rs".to_string(), old_path: Some("src/b.rs".to_string()) },
+        ]);
+    }
+
+    #[test]
+    fn test_only_newly_crossed_limits_fail() {
+        let thresholds = thresholds();
+        let small = FileMeasures { lines: 80, ..FileMeasures::default() };
+        let large = FileMeasures { lines: 150, ..FileMeasures::default() };
+        let larger = FileMeasures { lines: 400, ..FileMeasures::default() };
+
+        // Crossing the limit fails, as does a new file over it
+        let (violations, legacy) = compare("a.rs", Some(&small), &large, &thresholds);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].metric, GateMetric::FileLines);
+        assert_eq!((violations[0].before, violations[0].after), (Some(80), 150));
+        assert!(legacy.is_empty());
+        assert_eq!(compare("new.rs", None, &large, &thresholds).0.len(), 1);
+
+        // A file already over the limit may grow; it is reported as legacy
+        let (violations, legacy) = compare("legacy.rs", Some(&large), &larger, &thresholds);
+        assert!(violations.is_empty());
+        assert_eq!(legacy.len(), 1);
+
+        assert_eq!(compare("a.rs", Some(&large), &small, &thresholds), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn test_measure_functions() {
+        let counter = CodeCounter::new();
+        let source = "fn f(a: i32, b: i32) -> i32 {\n    if a > b {\n        a\n    } else {\n        b\n    }\n}\n";
+        let measures = measure(&counter, Path::new("f.rs"), source.as_bytes()).unwrap();
+
+        assert_eq!(measures.lines, 7);
+        assert_eq!(measures.parameters, 2);
+        assert!(measures.cyclomatic_complexity >= 2);
+        assert_eq!(measure(&counter, Path::new("notes.txt"), b"a\nb\n").unwrap().cyclomatic_complexity, 0);
+    }
+}
@@ -0,0 +1,459 @@
+use crate::core::counter::CodeCounter;
+use crate::core::detector::extension_key;
+use crate::core::stats::StatsCalculator;
+use crate::core::types::FileStats;
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::cache;
+use crate::utils::languages::LanguageRegistry;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Commits sampled when `--samples` is not given
+pub const DEFAULT_HISTORY_SAMPLES: usize = 20;
+
+/// Totals of one sampled commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySnapshot {
+    pub commit: String,
+    /// Tag name, or the abbreviated commit hash
+    pub label: String,
+    pub date: DateTime<Utc>,
+    pub files: usize,
+    pub total_lines: usize,
+    pub code_lines: usize,
+    pub comment_lines: usize,
+    pub doc_lines: usize,
+    /// Code lines per language, or per file extension with `--by-extension`
+    pub languages: BTreeMap<String, usize>,
+    pub code_health_score: f64,
+    pub documentation_coverage: f64,
+}
+
+/// Growth of a repository over its sampled commits, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryReport {
+    pub repository: String,
+    pub revision: String,
+    pub snapshots: Vec<HistorySnapshot>,
+    /// Snapshots read from the history cache instead of being recounted
+    pub cached_snapshots: usize,
+}
+
+impl HistoryReport {
+    /// Sum the code lines of every snapshot by language. Snapshots are counted
+    /// and cached by extension, so either grouping comes from the same cache.
+    pub fn group_by_language(&mut self, registry: &LanguageRegistry) {
+        for snapshot in &mut self.snapshots {
+            let mut languages = BTreeMap::new();
+            for (extension, code_lines) in &snapshot.languages {
+                *languages.entry(registry.key(extension)).or_insert(0) += code_lines;
+            }
+            snapshot.languages = languages;
+        }
+    }
+}
+
+/// A commit chosen for the report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub commit: String,
+    pub label: String,
+    pub timestamp: i64,
+}
+
+/// Reads past trees straight from the git object store, without checking them out,
+/// and counts each sampled commit like a directory analysis would
+pub struct HistoryAnalyzer {
+    repository: PathBuf,
+    revision: String,
+    samples: usize,
+    tags: bool,
+    by_extension: bool,
+    use_cache: bool,
+}
+
+impl HistoryAnalyzer {
+    pub fn new(repository: impl Into<PathBuf>) -> Self {
+        Self {
+            repository: repository.into(),
+            revision: "HEAD".to_string(),
+            samples: DEFAULT_HISTORY_SAMPLES,
+            tags: false,
+            by_extension: false,
+            use_cache: true,
+        }
+    }
+
+    pub fn with_revision(mut self, revision: impl Into<String>) -> Self {
+        self.revision = revision.into();
+        self
+    }
+
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Sample release tags instead of first-parent commits
+    pub fn with_tags(mut self, tags: bool) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Key the counts of each commit by file extension, with an Objective-C
+    /// `.m` under `m`, for reports that aren't grouped by language
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.by_extension = by_extension;
+        self
+    }
+
+    pub fn with_cache(mut self, use_cache: bool) -> Self {
+        self.use_cache = use_cache;
+        self
+    }
+
+    /// Commits to analyze, oldest first, spread evenly over the history
+    pub fn sample_revisions(&self) -> Result<Vec<Revision>> {
+        let revisions = if self.tags {
+            let output = git(&self.repository, &[
+                "for-each-ref",
+                "--sort=creatordate",
+                "--format=%(refname:short)%09%(objectname)%09%(*objectname)%09%(creatordate:unix)",
+                "refs/tags",
+            ])?;
+            parse_tag_refs(&output)
+        } else {
+            // Only commits touching the analyzed directory, so a subdirectory's history stays relevant
+            let output = git(&self.repository, &["log", "--first-parent", "--format=%H%x09%ct", &self.revision, "--", "."])?;
+            let mut revisions = parse_commit_log(&output);
+            revisions.reverse();
+            revisions
+        };
+
+        if revisions.is_empty() {
+            return Err(HowManyError::repository(if self.tags {
+                format!("No tags found in {}", self.repository.display())
+            } else {
+                format!("No commits found for {} in {}", self.revision, self.repository.display())
+            }));
+        }
+
+        Ok(sample_evenly(revisions, self.samples))
+    }
+
+    /// Count every sampled commit. `accept` decides which tracked paths are counted,
+    /// and `settings` identifies those choices in the cache, so changed filters recount.
+    pub fn analyze(
+        &self,
+        counter: &CodeCounter,
+        settings: &str,
+        accept: impl Fn(&Path) -> bool,
+        mut progress: impl FnMut(&Revision),
+    ) -> Result<HistoryReport> {
+        let revisions = self.sample_revisions()?;
+        let mut cache = if self.use_cache { HistoryCache::load() } else { HistoryCache::default() };
+        let mut blobs = BlobReader::spawn(&self.repository)?;
+        // Most files are unchanged between samples, so each blob is counted once
+        let mut blob_stats: HashMap<String, FileStats> = HashMap::new();
+        let stats_calculator = StatsCalculator::new();
+
+        let mut snapshots = Vec::new();
+        let mut cached_snapshots = 0;
+
+        for revision in &revisions {
+            let key = format!("{}|{}", revision.commit, settings);
+            if let Some(snapshot) = cache.entries.get(&key) {
+                snapshots.push(HistorySnapshot {
+                    label: revision.label.clone(),
+                    ..snapshot.clone()
+                });
+                cached_snapshots += 1;
+                continue;
+            }
+            progress(revision);
+
+            let tree = git(&self.repository, &["ls-tree", "-r", "-z", &revision.commit])?;
+            let mut file_stats = Vec::new();
+            for entry in parse_ls_tree(&tree) {
+                if !accept(&entry.path) {
+                    continue;
+                }
+                let stats = match blob_stats.get(&entry.object) {
+                    Some(stats) => stats.clone(),
+                    None => {
+                        let contents = blobs.read(&entry.object)?;
+                        let stats = counter.count_contents(&entry.path, &contents)?;
+                        blob_stats.insert(entry.object.clone(), stats.clone());
+                        stats
+                    }
+                };
+                let extension = stats.row_key(extension_key(&entry.path).unwrap_or_else(|| "no_ext".to_string()), self.by_extension);
+                file_stats.push((extension, stats));
+            }
+
+            let code_stats = counter.aggregate_stats(file_stats);
+            let aggregated = stats_calculator.calculate_project_stats(&code_stats, &[])?;
+            let snapshot = HistorySnapshot {
+                commit: revision.commit.clone(),
+                label: revision.label.clone(),
+                date: Utc.timestamp_opt(revision.timestamp, 0).single().unwrap_or_default(),
+                files: code_stats.total_files,
+                total_lines: code_stats.total_lines,
+                code_lines: code_stats.total_code_lines,
+                comment_lines: code_stats.total_comment_lines,
+                doc_lines: code_stats.total_doc_lines,
+                languages: code_stats.stats_by_extension
+                    .iter()
+                    .map(|(ext, (_, stats))| (ext.clone(), stats.code_lines))
+                    .collect(),
+                code_health_score: aggregated.complexity.quality_metrics.code_health_score,
+                documentation_coverage: aggregated.complexity.quality_metrics.documentation_coverage,
+            };
+            cache.entries.insert(key, snapshot.clone());
+            snapshots.push(snapshot);
+        }
+
+        if self.use_cache && cached_snapshots < snapshots.len() {
+            cache.save()?;
+        }
+
+        Ok(HistoryReport {
+            repository: self.repository.display().to_string(),
+            revision: if self.tags { "tags".to_string() } else { self.revision.clone() },
+            snapshots,
+            cached_snapshots,
+        })
+    }
+}
+
+/// Snapshots of past commits, which never change once counted
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryCache {
+    cache_version: u32,
+    entries: HashMap<String, HistorySnapshot>,
+}
+
+impl HistoryCache {
+    const CACHE_VERSION: u32 = 1;
+
+    /// A missing, unreadable or outdated cache starts empty
+    fn load() -> Self {
+        Self::cache_path()
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .ok()
+            .and_then(|content| serde_json::from_str::<HistoryCache>(&content).ok())
+            .filter(|cache| cache.cache_version == Self::CACHE_VERSION)
+            .unwrap_or_default()
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.cache_version = Self::CACHE_VERSION;
+        let cache_path = Self::cache_path()?;
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(cache_path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn cache_path() -> Result<PathBuf> {
+        Ok(cache::cache_dir()?.join(cache::HISTORY_CACHE_NAME))
+    }
+}
+
+/// Long-running `git cat-file --batch` process for reading blobs by object id
+pub(crate) struct BlobReader {
+    process: std::process::Child,
+    stdout: BufReader<std::process::ChildStdout>,
+}
+
+impl BlobReader {
+    pub(crate) fn spawn(repository: &Path) -> Result<Self> {
+        let mut process = Command::new("git")
+            .args(["cat-file", "--batch"])
+            .current_dir(repository)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| HowManyError::repository(format!("Failed to run git: {}", e)))?;
+        let stdout = process
+            .stdout
+            .take()
+            .map(BufReader::new)
+            .ok_or_else(|| HowManyError::repository("Failed to read from git cat-file"))?;
+
+        Ok(Self { process, stdout })
+    }
+
+    pub(crate) fn read(&mut self, object: &str) -> Result<Vec<u8>> {
+        let stdin = self
+            .process
+            .stdin
+            .as_mut()
+            .ok_or_else(|| HowManyError::repository("Failed to write to git cat-file"))?;
+        writeln!(stdin, "{}", object)?;
+        stdin.flush()?;
+
+        // Header: `<object> <type> <size>`, or `<object> missing`
+        let mut header = String::new();
+        self.stdout.read_line(&mut header)?;
+        let size: usize = header
+            .split_whitespace()
+            .nth(2)
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(|| HowManyError::repository(format!("Failed to read object {}: {}", object, header.trim())))?;
+
+        let mut contents = vec![0; size];
+        self.stdout.read_exact(&mut contents)?;
+        // Each object is followed by a newline
+        let mut newline = [0; 1];
+        self.stdout.read_exact(&mut newline)?;
+        Ok(contents)
+    }
+}
+
+impl Drop for BlobReader {
+    fn drop(&mut self) {
+        // Closing stdin lets git exit
+        drop(self.process.stdin.take());
+        let _ = self.process.wait();
+    }
+}
+
+/// A regular file of a tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    pub object: String,
+    pub path: PathBuf,
+}
+
+/// Parse `git ls-tree -r -z` output, keeping regular files. Symlinks and
+/// submodule commits are left out.
+pub fn parse_ls_tree(output: &str) -> Vec<TreeEntry> {
+    output
+        .split('\0')
+        .filter_map(|record| {
+            let (info, path) = record.split_once('\t')?;
+            let mut fields = info.split(' ');
+            let mode = fields.next()?;
+            let kind = fields.next()?;
+            let object = fields.next()?;
+            (kind == "blob" && mode != "120000").then(|| TreeEntry {
+                object: object.to_string(),
+                path: PathBuf::from(path),
+            })
+        })
+        .collect()
+}
+
+/// Parse `git log --format=%H%x09%ct` output, newest first like git prints it
+fn parse_commit_log(output: &str) -> Vec<Revision> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (commit, timestamp) = line.split_once('\t')?;
+            Some(Revision {
+                commit: commit.to_string(),
+                label: commit.chars().take(8).collect(),
+                timestamp: timestamp.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Parse `git for-each-ref` tag lines of name, object, peeled object and date.
+/// Annotated tags point at a tag object, so the peeled commit is used for them.
+fn parse_tag_refs(output: &str) -> Vec<Revision> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [name, object, peeled, timestamp] = fields[..] else {
+                return None;
+            };
+            Some(Revision {
+                commit: if peeled.is_empty() { object } else { peeled }.to_string(),
+                label: name.to_string(),
+                timestamp: timestamp.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Pick `count` items spread evenly from first to last, always keeping both ends
+pub fn sample_evenly<T>(items: Vec<T>, count: usize) -> Vec<T> {
+    if count == 0 || items.len() <= count {
+        return items;
+    }
+    if count == 1 {
+        return items.into_iter().last().into_iter().collect();
+    }
+
+    let last = items.len() - 1;
+    let picks: Vec<usize> = (0..count).map(|i| i * last / (count - 1)).collect();
+    items
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| picks.contains(index))
+        .map(|(_, item)| item)
+        .collect()
+}
+
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| HowManyError::repository(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(HowManyError::repository(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_evenly_keeps_both_ends() {
+        let items: Vec<usize> = (0..10).collect();
+
+        assert_eq!(sample_evenly(items.clone(), 4), vec![0, 3, 6, 9]);
+        assert_eq!(sample_evenly(items.clone(), 1), vec![9]);
+        assert_eq!(sample_evenly(items.clone(), 20).len(), 10);
+    }
+
+    #[test]
+    fn test_parse_ls_tree() {
+        let output = "100644 blob 1111111111111111111111111111111111111111\tsrc/main.rs\0\
+                      120000 blob 2222222222222222222222222222222222222222\tlink.rs\0\
+                      160000 commit 3333333333333333333333333333333333333333\tvendor/dep\0";
+
+        assert_eq!(parse_ls_tree(output), vec![TreeEntry {
+            object: "1111111111111111111111111111111111111111".to_string(),
+            path: PathBuf::from("src/main.rs"),
+        }]);
+    }
+
+    #[test]
+    fn test_parse_tag_refs_peels_annotated_tags() {
+        let output = "v0.1.0\taaaa\t\t1700000000\nv0.2.0\tbbbb\tcccc\t1710000000\n";
+        let revisions = parse_tag_refs(output);
+
+        assert_eq!(revisions[0].commit, "aaaa");
+        assert_eq!(revisions[1].commit, "cccc");
+        assert_eq!(revisions[1].label, "v0.2.0");
+    }
+}
@@ -0,0 +1,252 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Bytes at the top of a file searched for a license header, enough for
+/// a copyright block followed by the GPL or BSD boilerplate
+pub const HEADER_BYTES: usize = 4096;
+
+/// Extensions of data and prose formats that can't hold a comment or
+/// conventionally go without a header
+const HEADERLESS_EXTENSIONS: &[&str] = &["json", "md", "markdown", "txt", "lock", "csv", "tsv", "svg"];
+
+/// License texts, usually not the full text itself, that are declared
+/// by file names instead of a header
+const LICENSE_FILE_NAMES: &[&str] = &["license", "licence", "copying", "notice", "unlicense"];
+
+/// Boilerplate of common licenses by SPDX identifier, matched against the
+/// header with comment markers stripped and whitespace collapsed. Narrower
+/// texts come first: the LGPL and AGPL mention the GPL, BSD-3 extends BSD-2.
+const BOILERPLATE: &[(&str, &str)] = &[
+    ("AGPL-3.0", r"gnu affero general public license"),
+    ("LGPL-2.1", r"gnu (lesser|library) general public license\b.{0,160}?version 2"),
+    ("LGPL-3.0", r"gnu lesser general public license"),
+    ("GPL-2.0", r"gnu general public license\b.{0,160}?version 2"),
+    ("GPL-3.0", r"gnu general public license"),
+    ("Apache-2.0", r"apache license,? version 2\.0"),
+    ("MPL-2.0", r"mozilla public license,? v(ersion|\.) ?2\.0"),
+    ("EPL-2.0", r"eclipse public license,? v(ersion|\.)? ?2\.0"),
+    ("MIT", r"permission is hereby granted, free of charge, to any person obtaining a copy"),
+    ("ISC", r"permission to use, copy, modify, and(/or)? distribute this software for any purpose with or without fee is hereby granted"),
+    ("BSD-3-Clause", r"redistribution and use in source and binary forms.*neither the name"),
+    ("BSD-2-Clause", r"redistribution and use in source and binary forms"),
+    ("Unlicense", r"this is free and unencumbered software released into the public domain"),
+];
+
+/// Files under one license
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseCount {
+    /// SPDX identifier or expression (`MIT`, `MIT OR Apache-2.0`)
+    pub license: String,
+    pub files: usize,
+}
+
+/// License headers of the analyzed files
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseStats {
+    /// Files that were expected to carry a header
+    pub files: usize,
+    /// Files with an SPDX identifier or recognized license boilerplate
+    pub licensed: usize,
+    /// Licensed files per license, most files first
+    pub licenses: Vec<LicenseCount>,
+    /// Files without a recognized license header, by path
+    pub missing: Vec<String>,
+}
+
+impl LicenseStats {
+    /// Share of the files with a license header, in percent
+    pub fn coverage(&self) -> f64 {
+        if self.files == 0 {
+            0.0
+        } else {
+            self.licensed as f64 / self.files as f64 * 100.0
+        }
+    }
+
+    /// Combine results for disjoint file sets
+    pub fn merge<'a>(stats_list: impl IntoIterator<Item = &'a LicenseStats>) -> Option<LicenseStats> {
+        let mut merged: Option<LicenseStats> = None;
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        for stats in stats_list {
+            let total = merged.get_or_insert_with(LicenseStats::default);
+            total.files += stats.files;
+            total.licensed += stats.licensed;
+            total.missing.extend(stats.missing.iter().cloned());
+            for count in &stats.licenses {
+                *counts.entry(count.license.clone()).or_default() += count.files;
+            }
+        }
+
+        let mut merged = merged?;
+        merged.licenses = rank_licenses(counts);
+        merged.missing.sort();
+        Some(merged)
+    }
+}
+
+/// Reads the top of each file and recognizes its license by an
+/// `SPDX-License-Identifier` line or by common license boilerplate
+pub struct LicenseScanner {
+    spdx_pattern: Regex,
+    comment_marker: Regex,
+    boilerplate: Vec<(&'static str, Regex)>,
+}
+
+impl LicenseScanner {
+    pub fn new() -> Self {
+        Self {
+            spdx_pattern: Regex::new(r"SPDX-License-Identifier:\s*([^\r\n]*)").unwrap(),
+            comment_marker: Regex::new(r"^\s*(//+!?|/\*+!?|\*+/?|#+!?|--+|;+|%+|<!--|\(\*|\{-|'|(?i:rem)\b)?").unwrap(),
+            boilerplate: BOILERPLATE
+                .iter()
+                .map(|(license, pattern)| (*license, Regex::new(pattern).expect("built-in license patterns are valid")))
+                .collect(),
+        }
+    }
+
+    /// License headers of `paths`; files that can't hold a header, license
+    /// texts themselves and unreadable files are passed over
+    pub fn scan(&self, paths: &[PathBuf]) -> LicenseStats {
+        let mut stats = LicenseStats::default();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        for path in paths.iter().filter(|path| expects_header(path)) {
+            let mut header = Vec::new();
+            if File::open(path).and_then(|file| file.take(HEADER_BYTES as u64).read_to_end(&mut header)).is_err() {
+                continue;
+            }
+            stats.files += 1;
+            match self.detect(&String::from_utf8_lossy(&header)) {
+                Some(license) => {
+                    stats.licensed += 1;
+                    *counts.entry(license).or_default() += 1;
+                }
+                None => stats.missing.push(path.to_string_lossy().to_string()),
+            }
+        }
+
+        stats.licenses = rank_licenses(counts);
+        stats.missing.sort();
+        stats
+    }
+
+    /// License declared by the start of a file: the expression of its first
+    /// SPDX identifier, or the license whose boilerplate it contains
+    pub fn detect(&self, header: &str) -> Option<String> {
+        let spdx = self.spdx_pattern.captures_iter(header).find_map(|captures| {
+            let expression = captures[1].trim_end().trim_end_matches("*/").trim_end_matches("-->").trim_end_matches("*)").trim_end_matches("-}").trim();
+            (!expression.is_empty()).then(|| expression.to_string())
+        });
+        if spdx.is_some() {
+            return spdx;
+        }
+
+        let text = header
+            .lines()
+            .map(|line| self.comment_marker.replace(line, ""))
+            .flat_map(|line| line.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.boilerplate
+            .iter()
+            .find(|(_, pattern)| pattern.is_match(&text))
+            .map(|(license, _)| license.to_string())
+    }
+}
+
+impl Default for LicenseScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a file at `path` is expected to start with a license header
+fn expects_header(path: &Path) -> bool {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let stem = file_name.split(['.', '-', '_']).next().unwrap_or_default();
+    if LICENSE_FILE_NAMES.contains(&stem) {
+        return false;
+    }
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+    !extension.is_some_and(|extension| HEADERLESS_EXTENSIONS.contains(&extension.as_str()))
+}
+
+fn rank_licenses(counts: HashMap<String, usize>) -> Vec<LicenseCount> {
+    let mut licenses: Vec<_> = counts.into_iter().map(|(license, files)| LicenseCount { license, files }).collect();
+    licenses.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.license.cmp(&b.license)));
+    licenses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spdx_identifiers() {
+        let scanner = LicenseScanner::new();
+        assert_eq!(scanner.detect("// SPDX-License-Identifier: MIT\nfn main() {}\n").as_deref(), Some("MIT"));
+        assert_eq!(scanner.detect("#!/usr/bin/env python3\n# SPDX-License-Identifier: MIT OR Apache-2.0\n").as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(scanner.detect("/* SPDX-License-Identifier: GPL-2.0-only */\n").as_deref(), Some("GPL-2.0-only"));
+        assert_eq!(scanner.detect("<!-- SPDX-License-Identifier: CC-BY-4.0 -->\n<html></html>\n").as_deref(), Some("CC-BY-4.0"));
+        assert_eq!(scanner.detect("// SPDX-License-Identifier:\n").as_deref(), None);
+    }
+
+    #[test]
+    fn test_boilerplate() {
+        let scanner = LicenseScanner::new();
+        let apache = "/*\n * Copyright 2024 Example Corp.\n *\n * Licensed under the Apache License, Version 2.0 (the \"License\");\n * you may not use this file except in compliance with the License.\n */\npackage app;\n";
+        assert_eq!(scanner.detect(apache).as_deref(), Some("Apache-2.0"));
+
+        let gpl = "# This program is free software: you can redistribute it and/or modify\n# it under the terms of the GNU General Public License as published by\n# the Free Software Foundation, either version 3 of the License, or\n# (at your option) any later version.\n";
+        assert_eq!(scanner.detect(gpl).as_deref(), Some("GPL-3.0"));
+        let lgpl = gpl.replace("GNU General", "GNU Lesser General").replace("version 3", "version 2.1");
+        assert_eq!(scanner.detect(&lgpl).as_deref(), Some("LGPL-2.1"));
+
+        let mit = "// Copyright (c) 2023 Jane Doe\n//\n// Permission is hereby granted, free of charge, to any person obtaining a copy\n// of this software and associated documentation files (the \"Software\"),\n";
+        assert_eq!(scanner.detect(mit).as_deref(), Some("MIT"));
+
+        let bsd = "/*-\n * Redistribution and use in source and binary forms, with or without\n * modification, are permitted provided that the following conditions are met:\n * 3. Neither the name of the copyright holder nor the names of its\n */\n";
+        assert_eq!(scanner.detect(bsd).as_deref(), Some("BSD-3-Clause"));
+
+        // A copyright line alone doesn't say under which license the file is
+        assert_eq!(scanner.detect("// Copyright 2024 Example Corp.\nfn main() {}\n"), None);
+    }
+
+    #[test]
+    fn test_scan_and_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = |name: &str, contents: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+        let paths = vec![
+            file("main.rs", "// SPDX-License-Identifier: MIT\nfn main() {}\n"),
+            file("lib.rs", "// SPDX-License-Identifier: MIT\npub mod app;\n"),
+            file("app.py", "# Licensed under the Apache License, Version 2.0\nimport os\n"),
+            file("util.go", "package util\n"),
+            file("README.md", "# App\n"),
+            file("LICENSE-MIT", "Permission is hereby granted, free of charge, to any person obtaining a copy\n"),
+            dir.path().join("deleted.rs"),
+        ];
+
+        let stats = LicenseScanner::new().scan(&paths);
+        assert_eq!((stats.files, stats.licensed), (4, 3));
+        assert_eq!(stats.licenses, vec![
+            LicenseCount { license: "MIT".to_string(), files: 2 },
+            LicenseCount { license: "Apache-2.0".to_string(), files: 1 },
+        ]);
+        assert_eq!(stats.missing, vec![dir.path().join("util.go").to_string_lossy().to_string()]);
+        assert_eq!(stats.coverage(), 75.0);
+
+        let merged = LicenseStats::merge([&stats, &stats]).unwrap();
+        assert_eq!((merged.files, merged.licensed, merged.missing.len()), (8, 6, 2));
+        assert_eq!(merged.licenses[0], LicenseCount { license: "MIT".to_string(), files: 4 });
+        assert_eq!(LicenseStats::merge([]), None);
+    }
+}
@@ -251,34 +251,18 @@ lazy_static! {
         "whl".to_string(), "egg".to_string(), "gem".to_string(), "nupkg".to_string(),
         "snupkg".to_string(), "phar".to_string(),
     ];
-
-    /// Generated file indicators - compiled once
-    static ref GENERATED_INDICATORS: Vec<String> = vec![
-        "generated".to_string(), "auto".to_string(), "autogen".to_string(),
-        "codegen".to_string(), "_gen".to_string(), ".gen".to_string(),
-        "build".to_string(), "dist".to_string(), "out".to_string(),
-        "output".to_string(), "bin".to_string(), "obj".to_string(),
-        "bundle".to_string(), "minified".to_string(), ".min.".to_string(),
-        "compiled".to_string(), "protobuf".to_string(), ".pb.".to_string(),
-        "thrift".to_string(), ".thrift.".to_string(), "swagger".to_string(),
-        "openapi".to_string(), "schema".to_string(), "_generated".to_string(),
-        "bindata".to_string(), ".pb.gw.".to_string(),
-    ];
 }
 
 /// Common patterns shared between detector and filters
 pub struct CommonPatterns {
     /// Binary file extensions
     pub binary_extensions: Vec<String>,
-    /// Generated file indicators
-    pub generated_indicators: Vec<String>,
 }
 
 impl CommonPatterns {
     pub fn new() -> Self {
         Self {
             binary_extensions: BINARY_EXTENSIONS.clone(),
-            generated_indicators: GENERATED_INDICATORS.clone(),
         }
     }
 
@@ -307,12 +291,6 @@ impl CommonPatterns {
         BINARY_EXTENSIONS.contains(&extension.to_lowercase())
     }
 
-    /// Check if a filename indicates a generated file
-    pub fn is_generated_file(&self, filename: &str) -> bool {
-        let filename_lower = filename.to_lowercase();
-        GENERATED_INDICATORS.iter().any(|indicator| filename_lower.contains(indicator))
-    }
-
     /// Get all patterns that should be ignored (combines OS, IDE, temp, VCS)
     pub fn get_ignore_patterns(&self) -> Vec<&Regex> {
         let mut patterns = Vec::new();
@@ -377,11 +355,6 @@ impl PatternMatcher {
         self.common.is_binary_extension(extension)
     }
 
-    /// Check if a file is generated based on filename
-    pub fn is_generated_file(&self, filename: &str) -> bool {
-        self.common.is_generated_file(filename)
-    }
-
     /// Check if a path matches build/cache patterns
     pub fn matches_build_cache_pattern(&self, path_str: &str) -> bool {
         self.language_build.matches_build_pattern(path_str)
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::core::stats::complexity::languages::LanguageAnalyzer;
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::languages::{Language, LanguageRegistry};
+
+/// Directory of a project, or of the user config, holding plugin files
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// A language a plugin adds: the files it claims and its comment syntax
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LanguageDefinition {
+    pub name: String,
+    /// Extensions of the language, the most common one first
+    pub extensions: Vec<String>,
+    /// Files known by name (`Jenkinsfile`), counted under the first extension
+    pub file_names: Vec<String>,
+    pub line_comments: Vec<String>,
+    /// Start and end of block comments, e.g. `[["/*", "*/"]]`
+    pub block_comments: Vec<(String, String)>,
+    pub doc_comments: Vec<String>,
+}
+
+/// Extension point for counting languages, file detection and metrics that
+/// howmany doesn't know about. Every hook is optional.
+///
+/// Library users register plugins with [`register`] before counting;
+/// languages and line-pattern metrics can also be declared in TOML files
+/// that [`discover`] loads from a plugins directory.
+pub trait Plugin: Send + Sync {
+    /// Name the plugin is reported and registered under
+    fn name(&self) -> &str;
+
+    /// Languages the plugin counts, which howmany must not count already
+    fn languages(&self) -> Vec<LanguageDefinition> {
+        Vec::new()
+    }
+
+    /// Complexity analyzer for files with `extension`
+    fn analyzer(&self, _extension: &str) -> Option<Box<dyn LanguageAnalyzer>> {
+        None
+    }
+
+    /// Whether a file is user code, overriding howmany's own detection; `None` leaves it to howmany
+    fn is_user_created_file(&self, _path: &Path) -> Option<bool> {
+        None
+    }
+
+    /// Metrics of one file by name, from its decoded text. They are summed per language.
+    fn measure(&self, _extension: &str, _text: &str) -> Vec<(String, f64)> {
+        Vec::new()
+    }
+}
+
+/// Metric counting the lines a pattern matches, declared in a plugin file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricDefinition {
+    pub name: String,
+    /// Regular expression a counted line matches
+    pub pattern: String,
+    /// Extensions measured; every file when empty
+    pub extensions: Vec<String>,
+}
+
+/// Contents of a plugin file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PluginFile {
+    language: Vec<LanguageDefinition>,
+    metric: Vec<MetricDefinition>,
+}
+
+/// Plugin declared in a TOML file, named after the file
+pub struct DeclarativePlugin {
+    name: String,
+    languages: Vec<LanguageDefinition>,
+    metrics: Vec<(MetricDefinition, Regex)>,
+}
+
+impl DeclarativePlugin {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let file: PluginFile = toml::from_str(&content)
+            .map_err(|e| HowManyError::invalid_config(format!("Failed to parse plugin {}: {}", path.display(), e.message().trim())))?;
+        let metrics = file
+            .metric
+            .into_iter()
+            .map(|metric| {
+                Regex::new(&metric.pattern)
+                    .map(|regex| (metric.clone(), regex))
+                    .map_err(|e| HowManyError::invalid_config(format!("Invalid pattern of metric '{}' in {}: {}", metric.name, path.display(), e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name: path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().to_string()),
+            languages: file.language,
+            metrics,
+        })
+    }
+}
+
+impl Plugin for DeclarativePlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn languages(&self) -> Vec<LanguageDefinition> {
+        self.languages.clone()
+    }
+
+    fn measure(&self, extension: &str, text: &str) -> Vec<(String, f64)> {
+        self.metrics
+            .iter()
+            .filter(|(metric, _)| metric.extensions.is_empty() || metric.extensions.iter().any(|candidate| candidate.eq_ignore_ascii_case(extension)))
+            .map(|(metric, regex)| (metric.name.clone(), text.lines().filter(|line| regex.is_match(line)).count() as f64))
+            .collect()
+    }
+}
+
+/// A registered plugin language, with the `Language` reports group it under
+struct PluginLanguage {
+    language: &'static Language,
+    definition: LanguageDefinition,
+}
+
+#[derive(Default)]
+struct Registry {
+    plugins: Vec<Arc<dyn Plugin>>,
+    languages: Vec<PluginLanguage>,
+}
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Registry> = RwLock::new(Registry::default());
+}
+
+fn read_registry() -> std::sync::RwLockReadGuard<'static, Registry> {
+    REGISTRY.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Register a plugin for the rest of the process. Counters and detectors
+/// created afterwards pick up its languages. Fails when the plugin's name is
+/// taken or one of its languages claims a name or extension that is already counted.
+pub fn register(plugin: Arc<dyn Plugin>) -> Result<()> {
+    let registry = LanguageRegistry::new();
+    let mut languages = Vec::new();
+
+    for mut definition in plugin.languages() {
+        definition.extensions = definition.extensions.iter().map(|extension| extension.trim_start_matches('.').to_lowercase()).collect();
+        if definition.name.trim().is_empty() || definition.extensions.is_empty() {
+            return Err(HowManyError::invalid_config(format!("Plugin '{}' has a language without a name or extensions", plugin.name())));
+        }
+        if let Some(language) = registry.named(&definition.name) {
+            return Err(HowManyError::invalid_config(format!("Plugin '{}' adds {}, which is already a language", plugin.name(), language.name)));
+        }
+        if let Some((extension, language)) = definition.extensions.iter().find_map(|extension| registry.language(extension).map(|language| (extension, language))) {
+            return Err(HowManyError::invalid_config(format!("Plugin '{}' claims .{}, which is already counted as {}", plugin.name(), extension, language.name)));
+        }
+        languages.push(definition);
+    }
+
+    let mut registered = REGISTRY.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if registered.plugins.iter().any(|existing| existing.name() == plugin.name()) {
+        return Err(HowManyError::invalid_config(format!("A plugin named '{}' is already registered", plugin.name())));
+    }
+    for definition in languages {
+        // Registrations last for the process, so their languages live as long as the built-in ones
+        let extensions: Vec<&'static str> = definition.extensions.iter().map(|extension| &*Box::leak(extension.clone().into_boxed_str())).collect();
+        let language = Box::leak(Box::new(Language {
+            name: Box::leak(definition.name.clone().into_boxed_str()),
+            extensions: Box::leak(extensions.into_boxed_slice()),
+        }));
+        registered.languages.push(PluginLanguage { language, definition });
+    }
+    registered.plugins.push(plugin);
+    Ok(())
+}
+
+/// Directories plugin files are discovered in: the user's
+/// (`~/.config/howmany/plugins`), then the project's (`.howmany/plugins`)
+pub fn plugin_directories(project_root: &Path) -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = dirs::config_dir().map(|dir| dir.join("howmany").join(PLUGINS_DIR)).into_iter().collect();
+    directories.push(project_root.join(".howmany").join(PLUGINS_DIR));
+    directories
+}
+
+/// Register the `*.toml` plugin files of `directory`, in name order.
+/// Returns how many were registered; a missing directory has none.
+pub fn load_directory(directory: &Path) -> Result<usize> {
+    if !directory.is_dir() {
+        return Ok(0);
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "toml"))
+        .collect();
+    paths.sort();
+
+    for path in &paths {
+        register(Arc::new(DeclarativePlugin::load(path)?))?;
+    }
+    Ok(paths.len())
+}
+
+/// Register the plugin files of every `plugin_directories` entry for `project_root`
+pub fn discover(project_root: &Path) -> Result<usize> {
+    plugin_directories(project_root).iter().map(|directory| load_directory(directory)).sum()
+}
+
+/// Names of the registered plugins, in registration order
+pub fn plugin_names() -> Vec<String> {
+    read_registry().plugins.iter().map(|plugin| plugin.name().to_string()).collect()
+}
+
+/// Languages of the registered plugins, as reports group them
+pub fn languages() -> Vec<&'static Language> {
+    read_registry().languages.iter().map(|language| language.language).collect()
+}
+
+/// Definitions of the registered plugins' languages
+pub fn language_definitions() -> Vec<LanguageDefinition> {
+    read_registry().languages.iter().map(|language| language.definition.clone()).collect()
+}
+
+/// Key of a file a plugin language knows by name: the language's first extension
+pub fn file_name_key(file_name: &str) -> Option<String> {
+    read_registry()
+        .languages
+        .iter()
+        .find(|language| language.definition.file_names.iter().any(|name| name.eq_ignore_ascii_case(file_name)))
+        .map(|language| language.definition.extensions[0].clone())
+}
+
+/// Complexity analyzer a plugin provides for `extension`
+pub fn analyzer(extension: &str) -> Option<Box<dyn LanguageAnalyzer>> {
+    read_registry().plugins.iter().find_map(|plugin| plugin.analyzer(extension))
+}
+
+/// The first plugin verdict on whether a file is user code
+pub fn is_user_created_file(path: &Path) -> Option<bool> {
+    read_registry().plugins.iter().find_map(|plugin| plugin.is_user_created_file(path))
+}
+
+/// Plugin metrics of one file, by name
+pub fn measure(extension: &str, text: &str) -> BTreeMap<String, f64> {
+    let mut metrics = BTreeMap::new();
+    for plugin in &read_registry().plugins {
+        for (name, value) in plugin.measure(extension, text) {
+            *metrics.entry(name).or_insert(0.0) += value;
+        }
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::counter::CodeCounter;
+    use crate::core::detector::{extension_key, FileDetector};
+
+    // The registry is shared by every test, so each test adds its own languages
+
+    struct Detector;
+
+    impl Plugin for Detector {
+        fn name(&self) -> &str {
+            "detector"
+        }
+
+        fn is_user_created_file(&self, path: &Path) -> Option<bool> {
+            path.to_string_lossy().contains("plugin_vendored/").then_some(false)
+        }
+    }
+
+    #[test]
+    fn test_declarative_language_is_counted() {
+        let directory = tempfile::tempdir().unwrap();
+        let plugin = r##"[[language]]
+name = "Jsonnet"
+extensions = ["jsonnet", ".libsonnet"]
+file_names = ["Jsonnetfile"]
+line_comments = ["//", "#"]
+block_comments = [["/*", "*/"]]
+
+[[metric]]
+name = "imports"
+pattern = "^\\s*import "
+extensions = ["jsonnet"]
+"##;
+        fs::write(directory.path().join("jsonnet.toml"), plugin).unwrap();
+
+        assert_eq!(load_directory(directory.path()).unwrap(), 1);
+        assert!(plugin_names().contains(&"jsonnet".to_string()));
+        assert_eq!(LanguageRegistry::new().key("libsonnet"), "Jsonnet");
+        assert_eq!(extension_key(Path::new("deploy/Jsonnetfile")).as_deref(), Some("jsonnet"));
+        assert!(FileDetector::new().is_user_created_file(Path::new("deploy/main.libsonnet")));
+
+        let source = "import 'lib.libsonnet'\n// Settings\n# More\n/* block\n*/\n{ a: 1 }\n";
+        let stats = CodeCounter::new().count_contents(Path::new("main.jsonnet"), source.as_bytes()).unwrap();
+        assert_eq!(stats.code_lines, 2);
+        assert_eq!(stats.comment_lines, 4);
+        assert_eq!(stats.metrics.get("imports"), Some(&1.0));
+    }
+
+    #[test]
+    fn test_plugins_cannot_take_counted_languages() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(directory.path().join("rusty.toml"), "[[language]]\nname = \"Rusty\"\nextensions = [\"rs\"]\n").unwrap();
+        let error = load_directory(directory.path()).unwrap_err().to_string();
+        assert!(error.contains("already counted as Rust"), "{}", error);
+
+        fs::write(directory.path().join("rusty.toml"), "[[metric]]\nname = \"broken\"\npattern = \"(\"\n").unwrap();
+        assert!(load_directory(directory.path()).is_err());
+    }
+
+    #[test]
+    fn test_detector_plugin_overrides_detection() {
+        register(Arc::new(Detector)).unwrap();
+        assert!(register(Arc::new(Detector)).is_err());
+
+        let detector = FileDetector::new();
+        assert!(!detector.is_user_created_file(Path::new("src/plugin_vendored/lib.rs")));
+        assert!(detector.is_user_created_file(Path::new("src/lib.rs")));
+    }
+}
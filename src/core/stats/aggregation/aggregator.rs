@@ -1,8 +1,9 @@
 use crate::core::stats::basic::BasicStats;
 use crate::core::stats::complexity::ComplexityStats;
+use crate::core::stats::complexity::languages::has_language_analyzer;
 use crate::core::stats::ratios::RatioStats;
 use crate::utils::errors::Result;
-use super::types::{AggregatedStats, StatsMetadata, AnalysisDepth};
+use super::types::{AggregatedStats, StatsMetadata, AnalysisCapabilities, AnalysisDepth, FixtureStats, GeneratedStats, MinifiedStats};
 use super::merging::StatsMerger;
 use std::collections::HashMap;
 
@@ -34,15 +35,37 @@ impl StatsAggregator {
             file_count_analyzed: 1,
             total_bytes_analyzed: basic.total_size,
             languages_detected: vec!["unknown".to_string()], // Will be updated by caller
+            complexity_not_analyzed: Vec::new(),
             analysis_depth: AnalysisDepth::Complete,
+            capabilities: AnalysisCapabilities::default(),
+            performance: None,
+            incomplete: None,
         };
         
-        AggregatedStats {
+        let mut stats = AggregatedStats {
             basic,
             complexity,
             ratios,
             metadata,
-        }
+            fixtures: FixtureStats::default(),
+            generated: GeneratedStats::default(),
+            minified: MinifiedStats::default(),
+            non_utf8_files: Vec::new(),
+            skipped_files: Vec::new(),
+            normalization_conflicts: Vec::new(),
+            per_path: Vec::new(),
+            nested_projects: Vec::new(),
+            areas: Vec::new(),
+            budgets: Vec::new(),
+            categories: Vec::new(),
+            ownership: None,
+            churn: None,
+            dependencies: None,
+            licenses: None,
+            test_split: None,
+        };
+        Self::update_capabilities(&mut stats);
+        stats
     }
     
     /// Aggregate statistics for a project
@@ -53,6 +76,12 @@ impl StatsAggregator {
         ratios: RatioStats,
     ) -> AggregatedStats {
         let languages_detected: Vec<String> = basic.stats_by_extension.keys().cloned().collect();
+        let mut complexity_not_analyzed: Vec<String> = languages_detected
+            .iter()
+            .filter(|extension| !has_language_analyzer(extension))
+            .cloned()
+            .collect();
+        complexity_not_analyzed.sort();
         
         let metadata = StatsMetadata {
             calculation_time_ms: 0, // Will be set by caller
@@ -61,15 +90,37 @@ impl StatsAggregator {
             file_count_analyzed: basic.total_files,
             total_bytes_analyzed: basic.total_size,
             languages_detected,
+            complexity_not_analyzed,
             analysis_depth: AnalysisDepth::Complete,
+            capabilities: AnalysisCapabilities::default(),
+            performance: None,
+            incomplete: None,
         };
         
-        AggregatedStats {
+        let mut stats = AggregatedStats {
             basic,
             complexity,
             ratios,
             metadata,
-        }
+            fixtures: FixtureStats::default(),
+            generated: GeneratedStats::default(),
+            minified: MinifiedStats::default(),
+            non_utf8_files: Vec::new(),
+            skipped_files: Vec::new(),
+            normalization_conflicts: Vec::new(),
+            per_path: Vec::new(),
+            nested_projects: Vec::new(),
+            areas: Vec::new(),
+            budgets: Vec::new(),
+            categories: Vec::new(),
+            ownership: None,
+            churn: None,
+            dependencies: None,
+            licenses: None,
+            test_split: None,
+        };
+        Self::update_capabilities(&mut stats);
+        stats
     }
     
     /// Merge multiple aggregated statistics
@@ -97,6 +148,11 @@ impl StatsAggregator {
         stats.metadata.calculation_time_ms = start_time.elapsed().as_millis() as u64;
     }
     
+    /// Record which optional analyses produced data in the statistics
+    pub fn update_capabilities(stats: &mut AggregatedStats) {
+        stats.metadata.capabilities = AnalysisCapabilities::of(stats);
+    }
+    
     /// Get access to the internal merger for advanced operations
     pub fn merger(&self) -> &StatsMerger {
         &self.merger
@@ -1,10 +1,15 @@
-use crate::core::stats::basic::BasicStats;
-use crate::core::stats::complexity::ComplexityStats;
+use crate::core::stats::basic::{BasicStats, ExtensionStats};
+use crate::core::stats::complexity::{ComplexityStats, ExtensionComplexity, FileQuality, FunctionComplexityDetail};
 use crate::core::stats::ratios::RatioStats;
-use crate::core::types::{CodeStats, FileStats};
+use crate::core::types::{CodeStats, FileStats, LineLengthStats};
 use crate::utils::errors::{Result, HowManyError};
-use super::types::{AggregatedStats, StatsMetadata};
-use std::collections::HashMap;
+use crate::utils::languages::LanguageRegistry;
+use crate::core::dependencies::DependencyStats;
+use crate::core::licenses::LicenseStats;
+use crate::utils::churn::ChurnStats;
+use crate::utils::ownership::OwnershipStats;
+use super::types::{AggregatedStats, AnalysisCapabilities, AreaStats, StatsMetadata, CategoryStats, FixtureStats, GeneratedStats, MinifiedStats, TestSplitStats};
+use std::collections::{BTreeMap, HashMap};
 
 /// Handles merging of different statistics types
 pub struct StatsMerger {
@@ -25,7 +30,9 @@ impl StatsMerger {
         }
         
         if stats_list.len() == 1 {
-            return Ok(stats_list.into_iter().next().unwrap());
+            let mut stats = stats_list.into_iter().next().unwrap();
+            stats.metadata.capabilities = AnalysisCapabilities { complexity: Self::analyzed_complexity(&stats), ..AnalysisCapabilities::of(&stats) };
+            return Ok(stats);
         }
         
         // Merge basic stats
@@ -35,82 +42,215 @@ impl StatsMerger {
         let merged_complexity = self.merge_complexity_stats(&stats_list)?;
         
         // Merge ratio stats
-        let merged_ratios = self.merge_ratio_stats(&stats_list)?;
+        let mut merged_ratios = self.merge_ratio_stats(&stats_list)?;
+        let test_split = self.merge_test_split_stats(&stats_list)?;
+        merged_ratios.test_to_code_ratio = match &test_split {
+            Some(split) if stats_list.iter().all(|stats| stats.test_split.is_some()) => split.test_to_code_ratio(),
+            _ => Self::merge_test_to_code_ratio(&stats_list),
+        };
         
         // Create merged metadata
         let merged_metadata = self.merge_metadata(&stats_list)?;
         
-        Ok(AggregatedStats {
+        let complexity = stats_list.iter().any(Self::analyzed_complexity);
+        let mut merged = AggregatedStats {
             basic: merged_basic,
             complexity: merged_complexity,
             ratios: merged_ratios,
             metadata: merged_metadata,
+            fixtures: self.merge_fixture_stats(&stats_list),
+            generated: self.merge_generated_stats(&stats_list),
+            minified: self.merge_minified_stats(&stats_list),
+            non_utf8_files: stats_list
+                .iter()
+                .flat_map(|stats| stats.non_utf8_files.iter().cloned())
+                .collect(),
+            skipped_files: stats_list
+                .iter()
+                .flat_map(|stats| stats.skipped_files.iter().cloned())
+                .collect(),
+            normalization_conflicts: stats_list
+                .iter()
+                .flat_map(|stats| stats.normalization_conflicts.iter().cloned())
+                .collect(),
+            per_path: stats_list
+                .iter()
+                .flat_map(|stats| stats.per_path.iter().cloned())
+                .collect(),
+            nested_projects: stats_list
+                .iter()
+                .flat_map(|stats| stats.nested_projects.iter().cloned())
+                .collect(),
+            areas: self.merge_area_stats(&stats_list)?,
+            budgets: stats_list
+                .iter()
+                .flat_map(|stats| stats.budgets.iter().cloned())
+                .collect(),
+            categories: self.merge_category_stats(&stats_list)?,
+            ownership: OwnershipStats::merge(stats_list.iter().filter_map(|stats| stats.ownership.as_ref())),
+            churn: ChurnStats::merge(stats_list.iter().filter_map(|stats| stats.churn.as_ref())),
+            dependencies: DependencyStats::merge(stats_list.iter().filter_map(|stats| stats.dependencies.as_ref())),
+            licenses: LicenseStats::merge(stats_list.iter().filter_map(|stats| stats.licenses.as_ref())),
+            test_split,
+        };
+        merged.metadata.capabilities = AnalysisCapabilities { complexity, ..AnalysisCapabilities::of(&merged) };
+        Ok(merged)
+    }
+    
+    /// Test-to-code ratio of reports that kept only the ratio, not the production
+    /// and test totals (no `--split-tests`). A report's production code lines
+    /// follow from its code lines and ratio, and weigh its ratio.
+    fn merge_test_to_code_ratio(stats_list: &[AggregatedStats]) -> f64 {
+        let (test_lines, production_lines) = stats_list.iter().fold((0.0, 0.0), |(test_lines, production_lines), stats| {
+            let ratio = stats.ratios.test_to_code_ratio;
+            let production = stats.basic.code_lines as f64 / (1.0 + ratio);
+            (test_lines + ratio * production, production_lines + production)
+        });
+        if production_lines > 0.0 {
+            test_lines / production_lines
+        } else {
+            0.0
+        }
+    }
+    
+    /// Whether complexity was analyzed for merged results; reports written
+    /// before capabilities were recorded only show it through their functions
+    fn analyzed_complexity(stats: &AggregatedStats) -> bool {
+        stats.metadata.capabilities.complexity || stats.complexity.function_count > 0
+    }
+    
+    /// Merge production and test buckets, when any of the inputs has them
+    pub fn merge_test_split_stats(&self, stats_list: &[AggregatedStats]) -> Result<Option<TestSplitStats>> {
+        let splits: Vec<&TestSplitStats> = stats_list.iter().filter_map(|stats| stats.test_split.as_ref()).collect();
+        if splits.is_empty() {
+            return Ok(None);
+        }
+        
+        Ok(Some(TestSplitStats {
+            production: self.merge_basic(splits.iter().map(|split| &split.production))?,
+            test: self.merge_basic(splits.iter().map(|split| &split.test))?,
+        }))
+    }
+    
+    /// Merge the totals of each directory category; it stays scored only if every input scored it
+    pub fn merge_category_stats(&self, stats_list: &[AggregatedStats]) -> Result<Vec<CategoryStats>> {
+        let mut by_category: BTreeMap<_, Vec<&CategoryStats>> = BTreeMap::new();
+        for category_stats in stats_list.iter().flat_map(|stats| &stats.categories) {
+            by_category.entry(category_stats.category).or_default().push(category_stats);
+        }
+        
+        by_category
+            .into_iter()
+            .map(|(category, entries)| {
+                Ok(CategoryStats {
+                    category,
+                    basic: self.merge_basic(entries.iter().map(|entry| &entry.basic))?,
+                    scored: entries.iter().all(|entry| entry.scored),
+                })
+            })
+            .collect()
+    }
+    
+    /// Merge the totals of areas with the same name, in the order they first appear
+    pub fn merge_area_stats(&self, stats_list: &[AggregatedStats]) -> Result<Vec<AreaStats>> {
+        let mut by_name: Vec<(&str, Vec<&AreaStats>)> = Vec::new();
+        for area in stats_list.iter().flat_map(|stats| &stats.areas) {
+            match by_name.iter_mut().find(|(name, _)| *name == area.name) {
+                Some((_, entries)) => entries.push(area),
+                None => by_name.push((&area.name, vec![area])),
+            }
+        }
+        
+        by_name
+            .into_iter()
+            .map(|(name, entries)| {
+                Ok(AreaStats {
+                    name: name.to_string(),
+                    basic: self.merge_basic(entries.iter().map(|entry| &entry.basic))?,
+                })
+            })
+            .collect()
+    }
+    
+    /// Merge test fixture buckets
+    pub fn merge_fixture_stats(&self, stats_list: &[AggregatedStats]) -> FixtureStats {
+        stats_list.iter().fold(FixtureStats::default(), |mut merged, stats| {
+            merged.file_count += stats.fixtures.file_count;
+            merged.total_lines += stats.fixtures.total_lines;
+            merged.total_size += stats.fixtures.total_size;
+            merged.included_in_totals |= stats.fixtures.included_in_totals;
+            merged
+        })
+    }
+    
+    /// Merge generated code buckets
+    pub fn merge_generated_stats(&self, stats_list: &[AggregatedStats]) -> GeneratedStats {
+        stats_list.iter().fold(GeneratedStats::default(), |mut merged, stats| {
+            merged.file_count += stats.generated.file_count;
+            merged.total_lines += stats.generated.total_lines;
+            merged.code_lines += stats.generated.code_lines;
+            merged.total_size += stats.generated.total_size;
+            merged.included_in_totals |= stats.generated.included_in_totals;
+            merged
+        })
+    }
+    
+    /// Merge minified file buckets
+    pub fn merge_minified_stats(&self, stats_list: &[AggregatedStats]) -> MinifiedStats {
+        stats_list.iter().fold(MinifiedStats::default(), |mut merged, stats| {
+            merged.file_count += stats.minified.file_count;
+            merged.total_lines += stats.minified.total_lines;
+            merged.total_size += stats.minified.total_size;
+            merged.included_in_totals |= stats.minified.included_in_totals;
+            merged
         })
     }
     
     /// Merge basic statistics
     pub fn merge_basic_stats(&self, stats_list: &[AggregatedStats]) -> Result<BasicStats> {
+        self.merge_basic(stats_list.iter().map(|stats| &stats.basic))
+    }
+    
+    fn merge_basic<'a>(&self, basic_list: impl IntoIterator<Item = &'a BasicStats>) -> Result<BasicStats> {
         let mut total_files = 0;
         let mut total_lines = 0;
         let mut code_lines = 0;
         let mut comment_lines = 0;
         let mut doc_lines = 0;
         let mut blank_lines = 0;
+        let mut inline_comment_lines = 0;
+        let mut logical_lines = 0;
+        let mut characters = 0;
+        let mut non_whitespace_characters = 0;
+        let mut tokens = 0;
+        let mut line_lengths = LineLengthStats::default();
         let mut total_size = 0;
-        let mut merged_extensions = HashMap::new();
+        let mut extension_entries = Vec::new();
         let mut all_file_sizes = Vec::new();
         
-        for stats in stats_list {
-            total_files += stats.basic.total_files;
-            total_lines += stats.basic.total_lines;
-            code_lines += stats.basic.code_lines;
-            comment_lines += stats.basic.comment_lines;
-            doc_lines += stats.basic.doc_lines;
-            blank_lines += stats.basic.blank_lines;
-            total_size += stats.basic.total_size;
+        for basic in basic_list {
+            total_files += basic.total_files;
+            total_lines += basic.total_lines;
+            code_lines += basic.code_lines;
+            comment_lines += basic.comment_lines;
+            doc_lines += basic.doc_lines;
+            blank_lines += basic.blank_lines;
+            inline_comment_lines += basic.inline_comment_lines;
+            logical_lines += basic.logical_lines;
+            characters += basic.characters;
+            non_whitespace_characters += basic.non_whitespace_characters;
+            tokens += basic.tokens;
+            line_lengths.merge(&basic.line_lengths);
+            total_size += basic.total_size;
             
             // Merge extension stats
-            for (ext, ext_stats) in &stats.basic.stats_by_extension {
-                let entry = merged_extensions.entry(ext.clone()).or_insert_with(|| {
-                    crate::core::stats::basic::ExtensionStats {
-                        file_count: 0,
-                        total_lines: 0,
-                        code_lines: 0,
-                        comment_lines: 0,
-                        doc_lines: 0,
-                        blank_lines: 0,
-                        total_size: 0,
-                        average_lines_per_file: 0.0,
-                        average_size_per_file: 0.0,
-                    }
-                });
-                
-                entry.file_count += ext_stats.file_count;
-                entry.total_lines += ext_stats.total_lines;
-                entry.code_lines += ext_stats.code_lines;
-                entry.comment_lines += ext_stats.comment_lines;
-                entry.doc_lines += ext_stats.doc_lines;
-                entry.blank_lines += ext_stats.blank_lines;
-                entry.total_size += ext_stats.total_size;
-            }
-            
-            all_file_sizes.push(stats.basic.largest_file_size);
-            all_file_sizes.push(stats.basic.smallest_file_size);
-        }
-        
-        // Recalculate averages for merged extensions
-        for ext_stats in merged_extensions.values_mut() {
-            ext_stats.average_lines_per_file = if ext_stats.file_count > 0 {
-                ext_stats.total_lines as f64 / ext_stats.file_count as f64
-            } else {
-                0.0
-            };
+            extension_entries.extend(basic.stats_by_extension.iter().map(|(ext, ext_stats)| (ext.clone(), ext_stats)));
             
-            ext_stats.average_size_per_file = if ext_stats.file_count > 0 {
-                ext_stats.total_size as f64 / ext_stats.file_count as f64
-            } else {
-                0.0
-            };
+            // An input without files has no smallest file
+            if basic.total_files > 0 {
+                all_file_sizes.push(basic.largest_file_size);
+                all_file_sizes.push(basic.smallest_file_size);
+            }
         }
         
         let largest_file_size = all_file_sizes.iter().max().copied().unwrap_or(0);
@@ -123,21 +263,91 @@ impl StatsMerger {
             comment_lines,
             doc_lines,
             blank_lines,
+            inline_comment_lines,
+            logical_lines,
+            characters,
+            non_whitespace_characters,
+            tokens,
+            line_lengths,
+            average_line_length: line_lengths.average(total_lines),
             total_size,
             average_file_size: if total_files > 0 { total_size as f64 / total_files as f64 } else { 0.0 },
             average_lines_per_file: if total_files > 0 { total_lines as f64 / total_files as f64 } else { 0.0 },
             largest_file_size,
             smallest_file_size,
-            stats_by_extension: merged_extensions,
+            stats_by_extension: Self::merge_extension_stats(extension_entries),
         })
     }
     
+    /// Sum the statistics of extensions sharing a key and recompute their averages
+    fn merge_extension_stats<'a>(entries: impl IntoIterator<Item = (String, &'a ExtensionStats)>) -> HashMap<String, ExtensionStats> {
+        let mut merged_extensions = HashMap::new();
+        
+        for (ext, ext_stats) in entries {
+            let entry = merged_extensions.entry(ext).or_insert_with(|| {
+                ExtensionStats {
+                    file_count: 0,
+                    total_lines: 0,
+                    code_lines: 0,
+                    comment_lines: 0,
+                    doc_lines: 0,
+                    blank_lines: 0,
+                    characters: 0,
+                    non_whitespace_characters: 0,
+                    tokens: 0,
+                    line_lengths: LineLengthStats::default(),
+                    average_line_length: 0.0,
+                    total_size: 0,
+                    average_lines_per_file: 0.0,
+                    average_size_per_file: 0.0,
+                    metrics: BTreeMap::new(),
+                }
+            });
+            
+            entry.file_count += ext_stats.file_count;
+            entry.total_lines += ext_stats.total_lines;
+            entry.code_lines += ext_stats.code_lines;
+            entry.comment_lines += ext_stats.comment_lines;
+            entry.doc_lines += ext_stats.doc_lines;
+            entry.blank_lines += ext_stats.blank_lines;
+            entry.characters += ext_stats.characters;
+            entry.non_whitespace_characters += ext_stats.non_whitespace_characters;
+            entry.tokens += ext_stats.tokens;
+            entry.line_lengths.merge(&ext_stats.line_lengths);
+            entry.total_size += ext_stats.total_size;
+            for (name, value) in &ext_stats.metrics {
+                *entry.metrics.entry(name.clone()).or_insert(0.0) += value;
+            }
+        }
+        
+        // Recalculate averages for merged extensions
+        for ext_stats in merged_extensions.values_mut() {
+            ext_stats.average_lines_per_file = if ext_stats.file_count > 0 {
+                ext_stats.total_lines as f64 / ext_stats.file_count as f64
+            } else {
+                0.0
+            };
+            
+            ext_stats.average_size_per_file = if ext_stats.file_count > 0 {
+                ext_stats.total_size as f64 / ext_stats.file_count as f64
+            } else {
+                0.0
+            };
+            
+            ext_stats.average_line_length = ext_stats.line_lengths.average(ext_stats.total_lines);
+        }
+        
+        merged_extensions
+    }
+    
     /// Merge complexity statistics
     pub fn merge_complexity_stats(&self, stats_list: &[AggregatedStats]) -> Result<ComplexityStats> {
         let mut total_functions = 0;
         let mut total_complexity = 0.0;
         let mut total_cognitive_complexity = 0.0;
+        // Like the files of a report, reports weigh by their code lines
         let mut total_maintainability = 0.0;
+        let mut maintainability_lines = 0;
         let mut total_function_lines = 0;
         let mut max_function_length = 0;
         let mut min_function_length = usize::MAX;
@@ -145,7 +355,20 @@ impl StatsMerger {
         let mut total_nesting_depth = 0.0;
         let mut total_parameters = 0;
         let mut max_parameters = 0;
-        let mut merged_complexity_by_extension = HashMap::new();
+        let mut total_return_paths = 0.0;
+        let mut max_return_paths = 0;
+        let mut long_parameter_functions = Vec::new();
+        let mut complex_functions = Vec::new();
+        let mut complexity_entries = Vec::new();
+        let mut structures = crate::core::stats::complexity::StructureDistribution {
+            classes: 0,
+            interfaces: 0,
+            traits: 0,
+            enums: 0,
+            structs: 0,
+            modules: 0,
+        };
+        let mut total_methods = 0.0;
         
         // Merge complexity distribution
         let mut merged_distribution = crate::core::stats::complexity::ComplexityDistribution {
@@ -160,14 +383,28 @@ impl StatsMerger {
             total_functions += stats.complexity.function_count;
             total_complexity += stats.complexity.cyclomatic_complexity * stats.complexity.function_count as f64;
             total_cognitive_complexity += stats.complexity.cognitive_complexity * stats.complexity.function_count as f64;
-            total_maintainability += stats.complexity.maintainability_index * stats.complexity.function_count as f64;
+            total_maintainability += stats.complexity.maintainability_index * stats.basic.code_lines as f64;
+            maintainability_lines += stats.basic.code_lines;
             total_function_lines += (stats.complexity.average_function_length * stats.complexity.function_count as f64) as usize;
             max_function_length = max_function_length.max(stats.complexity.max_function_length);
-            min_function_length = min_function_length.min(stats.complexity.min_function_length);
+            if stats.complexity.function_count > 0 {
+                min_function_length = min_function_length.min(stats.complexity.min_function_length);
+            }
             max_nesting_depth = max_nesting_depth.max(stats.complexity.max_nesting_depth);
             total_nesting_depth += stats.complexity.average_nesting_depth * stats.complexity.function_count as f64;
             total_parameters += (stats.complexity.average_parameters_per_function * stats.complexity.function_count as f64) as usize;
             max_parameters = max_parameters.max(stats.complexity.max_parameters_per_function);
+            total_return_paths += stats.complexity.average_return_paths_per_function * stats.complexity.function_count as f64;
+            max_return_paths = max_return_paths.max(stats.complexity.max_return_paths_per_function);
+            long_parameter_functions.extend(stats.complexity.long_parameter_functions.iter().cloned());
+            complex_functions.extend(stats.complexity.complex_functions.iter().cloned());
+            structures.classes += stats.complexity.class_count;
+            structures.interfaces += stats.complexity.interface_count;
+            structures.traits += stats.complexity.trait_count;
+            structures.enums += stats.complexity.enum_count;
+            structures.structs += stats.complexity.struct_count;
+            structures.modules += stats.complexity.module_count;
+            total_methods += stats.complexity.methods_per_class * stats.complexity.class_count as f64;
             
             // Merge complexity distribution
             merged_distribution.very_low_complexity += stats.complexity.complexity_distribution.very_low_complexity;
@@ -177,82 +414,7 @@ impl StatsMerger {
             merged_distribution.very_high_complexity += stats.complexity.complexity_distribution.very_high_complexity;
             
             // Merge extension complexity
-            for (ext, ext_complexity) in &stats.complexity.complexity_by_extension {
-                let entry = merged_complexity_by_extension.entry(ext.clone()).or_insert_with(|| {
-                    crate::core::stats::complexity::ExtensionComplexity {
-                        function_count: 0,
-                        class_count: 0,
-                        interface_count: 0,
-                        trait_count: 0,
-                        enum_count: 0,
-                        struct_count: 0,
-                        total_structures: 0,
-                        cyclomatic_complexity: 0.0,
-                        cognitive_complexity: 0.0,
-                        maintainability_index: 0.0,
-                        average_function_length: 0.0,
-                        max_nesting_depth: 0,
-                        average_nesting_depth: 0.0,
-                        methods_per_class: 0.0,
-                        average_parameters_per_function: 0.0,
-                        quality_score: 0.0,
-                    }
-                });
-                
-                let old_count = entry.function_count;
-                entry.function_count += ext_complexity.function_count;
-                
-                // Weighted average for complexity
-                entry.cyclomatic_complexity = if entry.function_count > 0 {
-                    (entry.cyclomatic_complexity * old_count as f64 + ext_complexity.cyclomatic_complexity * ext_complexity.function_count as f64) / entry.function_count as f64
-                } else {
-                    0.0
-                };
-                
-                // Weighted average for cognitive complexity
-                entry.cognitive_complexity = if entry.function_count > 0 {
-                    (entry.cognitive_complexity * old_count as f64 + ext_complexity.cognitive_complexity * ext_complexity.function_count as f64) / entry.function_count as f64
-                } else {
-                    0.0
-                };
-                
-                // Weighted average for maintainability
-                entry.maintainability_index = if entry.function_count > 0 {
-                    (entry.maintainability_index * old_count as f64 + ext_complexity.maintainability_index * ext_complexity.function_count as f64) / entry.function_count as f64
-                } else {
-                    0.0
-                };
-                
-                // Weighted average for function length
-                entry.average_function_length = if entry.function_count > 0 {
-                    (entry.average_function_length * old_count as f64 + ext_complexity.average_function_length * ext_complexity.function_count as f64) / entry.function_count as f64
-                } else {
-                    0.0
-                };
-                
-                entry.max_nesting_depth = entry.max_nesting_depth.max(ext_complexity.max_nesting_depth);
-                
-                // Weighted average for nesting depth
-                entry.average_nesting_depth = if entry.function_count > 0 {
-                    (entry.average_nesting_depth * old_count as f64 + ext_complexity.average_nesting_depth * ext_complexity.function_count as f64) / entry.function_count as f64
-                } else {
-                    0.0
-                };
-                
-                // Weighted average for parameters
-                entry.average_parameters_per_function = if entry.function_count > 0 {
-                    (entry.average_parameters_per_function * old_count as f64 + ext_complexity.average_parameters_per_function * ext_complexity.function_count as f64) / entry.function_count as f64
-                } else {
-                    0.0
-                };
-                
-                // Weighted average for quality score
-                entry.quality_score = if entry.function_count > 0 {
-                    (entry.quality_score * old_count as f64 + ext_complexity.quality_score * ext_complexity.function_count as f64) / entry.function_count as f64
-                } else {
-                    0.0
-                };
-            }
+            complexity_entries.extend(stats.complexity.complexity_by_extension.iter().map(|(ext, ext_complexity)| (ext.clone(), ext_complexity)));
         }
         
         // Merge quality metrics
@@ -268,63 +430,182 @@ impl StatsMerger {
         };
         
         if !stats_list.is_empty() {
+            // Each input weighs by its code lines, so a small shard doesn't count
+            // as much as a large one; inputs without code weigh the same
+            let total_code_lines: usize = stats_list.iter().map(|stats| stats.basic.code_lines).sum();
+            let weight = |stats: &AggregatedStats| if total_code_lines > 0 {
+                stats.basic.code_lines as f64 / total_code_lines as f64
+            } else {
+                1.0 / stats_list.len() as f64
+            };
+            
             for stats in stats_list {
-                merged_quality_metrics.code_health_score += stats.complexity.quality_metrics.code_health_score;
-                merged_quality_metrics.maintainability_index += stats.complexity.quality_metrics.maintainability_index;
-                merged_quality_metrics.documentation_coverage += stats.complexity.quality_metrics.documentation_coverage;
-                merged_quality_metrics.avg_complexity += stats.complexity.quality_metrics.avg_complexity;
-                merged_quality_metrics.function_size_health += stats.complexity.quality_metrics.function_size_health;
-                merged_quality_metrics.nesting_depth_health += stats.complexity.quality_metrics.nesting_depth_health;
-                merged_quality_metrics.code_duplication_ratio += stats.complexity.quality_metrics.code_duplication_ratio;
-                merged_quality_metrics.technical_debt_ratio += stats.complexity.quality_metrics.technical_debt_ratio;
+                let weight = weight(stats);
+                merged_quality_metrics.code_health_score += stats.complexity.quality_metrics.code_health_score * weight;
+                merged_quality_metrics.maintainability_index += stats.complexity.quality_metrics.maintainability_index * weight;
+                merged_quality_metrics.documentation_coverage += stats.complexity.quality_metrics.documentation_coverage * weight;
+                merged_quality_metrics.avg_complexity += stats.complexity.quality_metrics.avg_complexity * weight;
+                merged_quality_metrics.function_size_health += stats.complexity.quality_metrics.function_size_health * weight;
+                merged_quality_metrics.nesting_depth_health += stats.complexity.quality_metrics.nesting_depth_health * weight;
+                merged_quality_metrics.code_duplication_ratio += stats.complexity.quality_metrics.code_duplication_ratio * weight;
+                merged_quality_metrics.technical_debt_ratio += stats.complexity.quality_metrics.technical_debt_ratio * weight;
             }
+        }
+        
+        // Percentiles cannot be recombined exactly from summaries: keep the true
+        // extremes and weight the rest by function count
+        let mut length_distribution = crate::core::stats::complexity::FunctionLengthDistribution::default();
+        let weighted_stats: Vec<_> = stats_list.iter().filter(|stats| stats.complexity.function_count > 0).collect();
+        if !weighted_stats.is_empty() && total_functions > 0 {
+            length_distribution.min = weighted_stats.iter().map(|stats| stats.complexity.function_length_distribution.min).min().unwrap_or(0);
+            length_distribution.max = weighted_stats.iter().map(|stats| stats.complexity.function_length_distribution.max).max().unwrap_or(0);
             
-            let stats_count = stats_list.len() as f64;
-            merged_quality_metrics.code_health_score /= stats_count;
-            merged_quality_metrics.maintainability_index /= stats_count;
-            merged_quality_metrics.documentation_coverage /= stats_count;
-            merged_quality_metrics.avg_complexity /= stats_count;
-            merged_quality_metrics.function_size_health /= stats_count;
-            merged_quality_metrics.nesting_depth_health /= stats_count;
-            merged_quality_metrics.code_duplication_ratio /= stats_count;
-            merged_quality_metrics.technical_debt_ratio /= stats_count;
+            let weight = |stats: &AggregatedStats| stats.complexity.function_count as f64 / total_functions as f64;
+            let p50: f64 = weighted_stats.iter().map(|stats| stats.complexity.function_length_distribution.p50 as f64 * weight(stats)).sum();
+            let p90: f64 = weighted_stats.iter().map(|stats| stats.complexity.function_length_distribution.p90 as f64 * weight(stats)).sum();
+            length_distribution.p50 = p50.round() as usize;
+            length_distribution.p90 = p90.round() as usize;
+            length_distribution.top_5_percent_share = weighted_stats.iter().map(|stats| stats.complexity.function_length_distribution.top_5_percent_share * weight(stats)).sum();
+            length_distribution.gini_coefficient = weighted_stats.iter().map(|stats| stats.complexity.function_length_distribution.gini_coefficient * weight(stats)).sum();
         }
         
         Ok(ComplexityStats {
             function_count: total_functions,
-            class_count: 0,
-            interface_count: 0,
-            trait_count: 0,
-            enum_count: 0,
-            struct_count: 0,
-            module_count: 0,
-            total_structures: 0,
+            class_count: structures.classes,
+            interface_count: structures.interfaces,
+            trait_count: structures.traits,
+            enum_count: structures.enums,
+            struct_count: structures.structs,
+            module_count: structures.modules,
+            total_structures: stats_list.iter().map(|stats| stats.complexity.total_structures).sum(),
             cyclomatic_complexity: if total_functions > 0 { total_complexity / total_functions as f64 } else { 0.0 },
             cognitive_complexity: if total_functions > 0 { total_cognitive_complexity / total_functions as f64 } else { 0.0 },
-            maintainability_index: if total_functions > 0 { total_maintainability / total_functions as f64 } else { 100.0 },
+            maintainability_index: if maintainability_lines > 0 { total_maintainability / maintainability_lines as f64 } else { 100.0 },
             average_function_length: if total_functions > 0 { total_function_lines as f64 / total_functions as f64 } else { 0.0 },
             max_function_length,
             min_function_length: if min_function_length == usize::MAX { 0 } else { min_function_length },
             max_nesting_depth,
             average_nesting_depth: if total_functions > 0 { total_nesting_depth / total_functions as f64 } else { 0.0 },
-            methods_per_class: 0.0,
+            methods_per_class: if structures.classes > 0 { total_methods / structures.classes as f64 } else { 0.0 },
+            max_methods_per_class: stats_list.iter().map(|stats| stats.complexity.max_methods_per_class).max().unwrap_or(0),
             average_parameters_per_function: if total_functions > 0 { total_parameters as f64 / total_functions as f64 } else { 0.0 },
             max_parameters_per_function: max_parameters,
-            complexity_by_extension: merged_complexity_by_extension,
+            average_return_paths_per_function: if total_functions > 0 { total_return_paths / total_functions as f64 } else { 0.0 },
+            max_return_paths_per_function: max_return_paths,
+            complexity_by_extension: Self::merge_extension_complexity(complexity_entries),
             complexity_distribution: merged_distribution,
-            structure_distribution: crate::core::stats::complexity::StructureDistribution {
-                classes: 0,
-                interfaces: 0,
-                traits: 0,
-                enums: 0,
-                structs: 0,
-                modules: 0,
-            },
-            function_complexity_details: Vec::new(),
+            structure_distribution: structures,
+            // As many of the most complex functions as the longest list of an input
+            function_complexity_details: FunctionComplexityDetail::most_complex(
+                stats_list.iter().flat_map(|stats| stats.complexity.function_complexity_details.iter().cloned()).collect(),
+                stats_list.iter().map(|stats| stats.complexity.function_complexity_details.len()).max().unwrap_or(0),
+            ),
+            long_parameter_functions,
+            complex_functions,
+            function_thresholds: stats_list.first().map(|stats| stats.complexity.function_thresholds).unwrap_or_default(),
+            function_length_distribution: length_distribution,
             quality_metrics: merged_quality_metrics,
+            // As many of the lowest-scoring files as the longest list of an input
+            worst_files: FileQuality::worst(
+                stats_list.iter().flat_map(|stats| stats.complexity.worst_files.iter().cloned()).collect(),
+                stats_list.iter().map(|stats| stats.complexity.worst_files.len()).max().unwrap_or(0),
+            ),
         })
     }
     
+    /// Combine the complexity of extensions sharing a key, weighting averages by function count
+    fn merge_extension_complexity<'a>(entries: impl IntoIterator<Item = (String, &'a ExtensionComplexity)>) -> HashMap<String, ExtensionComplexity> {
+        let mut merged_complexity_by_extension: HashMap<String, ExtensionComplexity> = HashMap::new();
+        
+        for (ext, ext_complexity) in entries {
+            let entry = merged_complexity_by_extension.entry(ext).or_default();
+            
+            let old_count = entry.function_count;
+            entry.function_count += ext_complexity.function_count;
+            
+            // Methods per class weighted by classes
+            let old_classes = entry.class_count;
+            entry.class_count += ext_complexity.class_count;
+            entry.methods_per_class = if entry.class_count > 0 {
+                (entry.methods_per_class * old_classes as f64 + ext_complexity.methods_per_class * ext_complexity.class_count as f64) / entry.class_count as f64
+            } else {
+                0.0
+            };
+            entry.interface_count += ext_complexity.interface_count;
+            entry.trait_count += ext_complexity.trait_count;
+            entry.enum_count += ext_complexity.enum_count;
+            entry.struct_count += ext_complexity.struct_count;
+            entry.module_count += ext_complexity.module_count;
+            entry.max_methods_per_class = entry.max_methods_per_class.max(ext_complexity.max_methods_per_class);
+            entry.total_structures += ext_complexity.total_structures;
+            
+            // Weighted average for complexity
+            entry.cyclomatic_complexity = if entry.function_count > 0 {
+                (entry.cyclomatic_complexity * old_count as f64 + ext_complexity.cyclomatic_complexity * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+            
+            // Weighted average for cognitive complexity
+            entry.cognitive_complexity = if entry.function_count > 0 {
+                (entry.cognitive_complexity * old_count as f64 + ext_complexity.cognitive_complexity * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+            
+            // Weighted average for maintainability
+            entry.maintainability_index = if entry.function_count > 0 {
+                (entry.maintainability_index * old_count as f64 + ext_complexity.maintainability_index * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+            
+            // Weighted average for function length
+            entry.average_function_length = if entry.function_count > 0 {
+                (entry.average_function_length * old_count as f64 + ext_complexity.average_function_length * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+            
+            entry.max_nesting_depth = entry.max_nesting_depth.max(ext_complexity.max_nesting_depth);
+            
+            // Weighted average for nesting depth
+            entry.average_nesting_depth = if entry.function_count > 0 {
+                (entry.average_nesting_depth * old_count as f64 + ext_complexity.average_nesting_depth * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+            
+            // Weighted average for parameters
+            entry.average_parameters_per_function = if entry.function_count > 0 {
+                (entry.average_parameters_per_function * old_count as f64 + ext_complexity.average_parameters_per_function * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+            
+            entry.max_parameters_per_function = entry.max_parameters_per_function.max(ext_complexity.max_parameters_per_function);
+            
+            // Weighted average for return paths
+            entry.average_return_paths_per_function = if entry.function_count > 0 {
+                (entry.average_return_paths_per_function * old_count as f64 + ext_complexity.average_return_paths_per_function * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+            
+            entry.max_return_paths_per_function = entry.max_return_paths_per_function.max(ext_complexity.max_return_paths_per_function);
+            entry.long_parameter_function_count += ext_complexity.long_parameter_function_count;
+            
+            // Weighted average for quality score
+            entry.quality_score = if entry.function_count > 0 {
+                (entry.quality_score * old_count as f64 + ext_complexity.quality_score * ext_complexity.function_count as f64) / entry.function_count as f64
+            } else {
+                0.0
+            };
+        }
+        
+        merged_complexity_by_extension
+    }
+    
     /// Merge ratio statistics
     pub fn merge_ratio_stats(&self, stats_list: &[AggregatedStats]) -> Result<RatioStats> {
         // Calculate overall ratios from merged basic stats
@@ -357,6 +638,16 @@ impl StatsMerger {
                     blank_lines: 0,
                     file_size: 0,
                     doc_lines: 0,
+                    inline_comment_lines: 0,
+                    logical_lines: 0,
+                    characters: 0,
+                    non_whitespace_characters: 0,
+                    tokens: 0,
+                    line_lengths: LineLengthStats::default(),
+                    encoding: None,
+                    embedded: Default::default(),
+                    metrics: Default::default(),
+                    ..Default::default()
                 }));
                 
                 entry.0 += ext_stats.file_count;
@@ -366,6 +657,10 @@ impl StatsMerger {
                 entry.1.doc_lines += ext_stats.doc_lines;
                 entry.1.blank_lines += ext_stats.blank_lines;
                 entry.1.file_size += ext_stats.total_size;
+                entry.1.characters += ext_stats.characters;
+                entry.1.non_whitespace_characters += ext_stats.non_whitespace_characters;
+                entry.1.tokens += ext_stats.tokens;
+                entry.1.line_lengths.merge(&ext_stats.line_lengths);
             }
         }
         
@@ -377,12 +672,72 @@ impl StatsMerger {
             total_blank_lines: blank_lines,
             total_size: stats_list.iter().map(|s| s.basic.total_size).sum(),
             total_doc_lines: doc_lines,
+            total_inline_comment_lines: stats_list.iter().map(|s| s.basic.inline_comment_lines).sum(),
+            total_logical_lines: stats_list.iter().map(|s| s.basic.logical_lines).sum(),
+            total_characters: stats_list.iter().map(|s| s.basic.characters).sum(),
+            total_non_whitespace_characters: stats_list.iter().map(|s| s.basic.non_whitespace_characters).sum(),
+            total_tokens: stats_list.iter().map(|s| s.basic.tokens).sum(),
+            line_lengths: stats_list.iter().fold(LineLengthStats::default(), |mut merged, s| {
+                merged.merge(&s.basic.line_lengths);
+                merged
+            }),
             stats_by_extension: temp_stats_by_extension,
         };
         
         ratio_calculator.calculate_project_ratio_stats(&temp_code_stats)
     }
     
+    /// Regroup every per-extension breakdown under the report keys of `registry`,
+    /// so `ts` and `tsx` become one TypeScript row. Totals and scores don't change.
+    pub fn group_by_language(&self, stats: &mut AggregatedStats, registry: &LanguageRegistry) -> Result<()> {
+        let group_basic = |basic: &mut BasicStats| {
+            let by_extension = std::mem::take(&mut basic.stats_by_extension);
+            basic.stats_by_extension = Self::merge_extension_stats(by_extension.iter().map(|(ext, ext_stats)| (registry.key(ext), ext_stats)));
+        };
+        group_basic(&mut stats.basic);
+        if let Some(split) = &mut stats.test_split {
+            group_basic(&mut split.production);
+            group_basic(&mut split.test);
+        }
+        stats.per_path.iter_mut().chain(&mut stats.nested_projects).for_each(|path| group_basic(&mut path.basic));
+        stats.areas.iter_mut().for_each(|area| group_basic(&mut area.basic));
+        stats.categories.iter_mut().for_each(|category| group_basic(&mut category.basic));
+        
+        let complexity_by_extension = std::mem::take(&mut stats.complexity.complexity_by_extension);
+        stats.complexity.complexity_by_extension = Self::merge_extension_complexity(complexity_by_extension.iter().map(|(ext, complexity)| (registry.key(ext), complexity)));
+        
+        // Ratios and distributions follow the grouped line counts; the quality
+        // scores keep the values measured across extensions
+        let grouped_ratios = self.merge_ratio_stats(std::slice::from_ref(stats))?;
+        stats.ratios.ratios_by_extension = grouped_ratios.ratios_by_extension;
+        stats.ratios.language_distribution = grouped_ratios.language_distribution;
+        stats.ratios.file_distribution = grouped_ratios.file_distribution;
+        stats.ratios.size_distribution = grouped_ratios.size_distribution;
+        
+        // A language lacks complexity analysis only when none of its extensions has it
+        let metadata = &mut stats.metadata;
+        let analyzed: std::collections::HashSet<String> = metadata.languages_detected
+            .iter()
+            .filter(|ext| !metadata.complexity_not_analyzed.contains(ext))
+            .map(|ext| registry.key(ext))
+            .collect();
+        let mut complexity_not_analyzed: Vec<String> = metadata.complexity_not_analyzed
+            .iter()
+            .map(|ext| registry.key(ext))
+            .filter(|language| !analyzed.contains(language))
+            .collect();
+        complexity_not_analyzed.sort();
+        complexity_not_analyzed.dedup();
+        metadata.complexity_not_analyzed = complexity_not_analyzed;
+        
+        let mut languages_detected: Vec<String> = metadata.languages_detected.iter().map(|ext| registry.key(ext)).collect();
+        languages_detected.sort();
+        languages_detected.dedup();
+        metadata.languages_detected = languages_detected;
+        
+        Ok(())
+    }
+    
     /// Merge metadata
     pub fn merge_metadata(&self, stats_list: &[AggregatedStats]) -> Result<StatsMetadata> {
         let total_calculation_time = stats_list.iter().map(|s| s.metadata.calculation_time_ms).sum();
@@ -398,6 +753,12 @@ impl StatsMerger {
         
         let mut languages_detected: Vec<String> = all_languages.into_iter().collect();
         languages_detected.sort();
+        let mut complexity_not_analyzed: Vec<String> = stats_list
+            .iter()
+            .flat_map(|stats| stats.metadata.complexity_not_analyzed.iter().cloned())
+            .collect();
+        complexity_not_analyzed.sort();
+        complexity_not_analyzed.dedup();
         
         Ok(StatsMetadata {
             calculation_time_ms: total_calculation_time,
@@ -406,7 +767,11 @@ impl StatsMerger {
             file_count_analyzed: total_files,
             total_bytes_analyzed: total_bytes,
             languages_detected,
+            complexity_not_analyzed,
             analysis_depth: super::types::AnalysisDepth::Complete,
+            capabilities: AnalysisCapabilities::default(),
+            performance: None,
+            incomplete: stats_list.iter().find_map(|s| s.metadata.incomplete),
         })
     }
 }
@@ -9,6 +9,7 @@
 //! - `types` - Core data structures for aggregated statistics
 //! - `aggregator` - Main aggregator interface for combining statistics
 //! - `merging` - Logic for merging multiple statistics together
+//! - `schema` - The versioned layout of the `--output json` report
 //! 
 //! ## Usage
 //! 
@@ -23,11 +24,13 @@
 pub mod types;
 pub mod aggregator;
 pub mod merging;
+pub mod schema;
 
 // Re-export the main types and functionality
-pub use types::{AggregatedStats, StatsMetadata, AnalysisDepth};
+pub use types::{AggregatedStats, StatsMetadata, AnalysisCapabilities, AnalysisDepth, FileEncoding, FixtureStats, GeneratedStats, Interruption, MinifiedStats, PathStats, AreaStats, CategoryStats, RunPerformance, SkipReason, SkippedFile, TestSplitStats};
 pub use aggregator::StatsAggregator;
 pub use merging::StatsMerger;
+pub use schema::{JsonReport, SCHEMA_VERSION};
 
 // Convenience re-exports for common operations
 pub use aggregator::StatsAggregator as Aggregator;
@@ -38,4 +41,11 @@ pub use types::AggregatedStats as Stats;
 /// This is a convenience function that delegates to StatsAggregator::update_timing
 pub fn update_timing(stats: &mut AggregatedStats, start_time: std::time::Instant) {
     StatsAggregator::update_timing(stats, start_time);
+}
+
+/// Record which optional analyses produced data in the statistics
+///
+/// Call after attaching results computed outside the aggregator, such as ownership or churn
+pub fn update_capabilities(stats: &mut AggregatedStats) {
+    StatsAggregator::update_capabilities(stats);
 } 
\ No newline at end of file
@@ -0,0 +1,257 @@
+use super::types::AggregatedStats;
+use crate::utils::errors::{HowManyError, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// Version of the layout of the `--output json` report. It changes when a
+/// field is removed or renamed or changes type; new fields keep the version.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// JSON Schema of the `--output json` report, printed by `--schema`
+pub const REPORT_SCHEMA: &str = include_str!("report.schema.json");
+
+/// The report `--output json` prints: the statistics with the `schema_version` of their layout
+#[derive(Debug, Serialize)]
+pub struct JsonReport<'a> {
+    pub schema_version: u64,
+    #[serde(flatten)]
+    pub stats: &'a AggregatedStats,
+}
+
+impl<'a> JsonReport<'a> {
+    pub fn new(stats: &'a AggregatedStats) -> Self {
+        Self { schema_version: SCHEMA_VERSION, stats }
+    }
+}
+
+/// Statistics of a report written by `--output json`. Reports written before
+/// the layout was versioned are read as version 1; reports of a newer layout
+/// are refused rather than misread.
+pub fn read_report(path: &Path) -> Result<AggregatedStats> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| HowManyError::file_processing(format!("Cannot read report {}: {}", path.display(), e)))?;
+    let not_a_report = |e: serde_json::Error| HowManyError::file_processing(format!("{} is not a howmany JSON report: {}", path.display(), e));
+    let report: serde_json::Value = serde_json::from_str(&contents).map_err(not_a_report)?;
+
+    let version = report.get("schema_version").and_then(serde_json::Value::as_u64).unwrap_or(1);
+    if version > SCHEMA_VERSION {
+        return Err(HowManyError::file_processing(format!(
+            "{} has schema version {}, but this howmany reads up to version {}; upgrade howmany to read it",
+            path.display(),
+            version,
+            SCHEMA_VERSION
+        )));
+    }
+    serde_json::from_value(report).map_err(not_a_report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::budget::BudgetStats;
+    use crate::core::dependencies::{DependencyStats, Ecosystem, ManifestDependencies};
+    use crate::core::detector::patterns::category::FileCategory;
+    use crate::core::licenses::{LicenseCount, LicenseStats};
+    use crate::core::stats::aggregation::{AreaStats, CategoryStats, PathStats, RunPerformance, SkipReason, SkippedFile, TestSplitStats};
+    use crate::core::stats::complexity::FileQuality;
+    use crate::core::stats::StatsCalculator;
+    use crate::core::types::{CodeStats, LineLengthStats};
+    use crate::utils::config::BudgetConfig;
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    /// Report of version 1 as howmany 2.0.0 wrote it
+    const REPORT_V1: &str = include_str!("testdata/report-v1.json");
+
+    /// Where `value` breaks `schema`. Covers the keywords the report schema
+    /// uses; an object schema with `properties` but no `additionalProperties`
+    /// is closed, so fields missing from the schema are caught too.
+    fn violations(root: &Value, schema: &Value, value: &Value, at: &str, found: &mut Vec<String>) {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/$defs/");
+            return violations(root, &root["$defs"][name], value, at, found);
+        }
+        if schema.get("const").is_some_and(|expected| expected != value) {
+            found.push(format!("{}: expected {}", at, schema["const"]));
+        }
+        if schema.get("enum").and_then(Value::as_array).is_some_and(|allowed| !allowed.contains(value)) {
+            found.push(format!("{}: {} is not one of {}", at, value, schema["enum"]));
+        }
+        if let Some(types) = schema.get("type") {
+            let types: Vec<&str> = match types {
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                other => other.as_str().into_iter().collect(),
+            };
+            let matches = |name: &str| match name {
+                "object" => value.is_object(),
+                "array" => value.is_array(),
+                "string" => value.is_string(),
+                "boolean" => value.is_boolean(),
+                "null" => value.is_null(),
+                "number" => value.is_number(),
+                "integer" => value.is_u64() || value.is_i64(),
+                _ => false,
+            };
+            if !types.iter().any(|name| matches(name)) {
+                found.push(format!("{}: {} is not of type {:?}", at, value, types));
+                return;
+            }
+        }
+        if let (Some(minimum), Some(number)) = (schema.get("minimum").and_then(Value::as_f64), value.as_f64()) {
+            if number < minimum {
+                found.push(format!("{}: {} is below {}", at, number, minimum));
+            }
+        }
+
+        match value {
+            Value::Object(fields) => {
+                for required in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+                    if !fields.contains_key(required) {
+                        found.push(format!("{}: missing {}", at, required));
+                    }
+                }
+                let properties = schema.get("properties").and_then(Value::as_object);
+                for (name, field) in fields {
+                    let at = format!("{}.{}", at, name);
+                    match (properties.and_then(|properties| properties.get(name)), schema.get("additionalProperties")) {
+                        (Some(property), _) => violations(root, property, field, &at, found),
+                        (None, Some(additional)) if additional.is_object() => violations(root, additional, field, &at, found),
+                        (None, None) if properties.is_some() => found.push(format!("{}: not in the schema", at)),
+                        _ => {}
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        violations(root, item_schema, item, &format!("{}[{}]", at, index), found);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check(report: &Value) -> Vec<String> {
+        let schema: Value = serde_json::from_str(REPORT_SCHEMA).unwrap();
+        let mut found = Vec::new();
+        violations(&schema, &schema, report, "report", &mut found);
+        found
+    }
+
+    fn stats() -> AggregatedStats {
+        let code_stats = CodeStats {
+            total_files: 2,
+            total_lines: 30,
+            total_code_lines: 20,
+            total_comment_lines: 5,
+            total_blank_lines: 5,
+            total_size: 1024,
+            total_doc_lines: 0,
+            stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: LineLengthStats::default(),
+        };
+        let mut stats = StatsCalculator::new().calculate_project_stats(&code_stats, &[]).unwrap();
+        // Fill the optional sections so they are checked too
+        stats.metadata.performance = Some(RunPerformance { total_time_ms: 1500, ..RunPerformance::default() });
+        stats.skipped_files = vec![SkippedFile { path: "dump.sql".to_string(), reason: SkipReason::TooLarge, size: 1 << 30 }];
+        stats.per_path = vec![PathStats { path: "web".to_string(), basic: stats.basic.clone(), files: Vec::new() }];
+        stats.areas = vec![AreaStats { name: "frontend".to_string(), basic: stats.basic.clone() }];
+        stats.budgets = vec![BudgetStats::new("web/**", &BudgetConfig { max_code_lines: Some(10), ..BudgetConfig::default() }, &stats.basic)];
+        stats.categories = vec![CategoryStats { category: FileCategory::Docs, basic: stats.basic.clone(), scored: false }];
+        stats.test_split = Some(TestSplitStats { production: stats.basic.clone(), test: stats.basic.clone() });
+        stats.dependencies = Some(DependencyStats::new(
+            vec![ManifestDependencies { path: "Cargo.toml".to_string(), ecosystem: Ecosystem::Cargo, direct: 12, dev: 3 }],
+            vec!["web/package.json".to_string()],
+        ));
+        stats.metadata.capabilities.dependencies = true;
+        stats.licenses = Some(LicenseStats {
+            files: 3,
+            licensed: 2,
+            licenses: vec![LicenseCount { license: "MIT OR Apache-2.0".to_string(), files: 2 }],
+            missing: vec!["src/util.rs".to_string()],
+        });
+        stats.metadata.capabilities.licenses = true;
+        stats.complexity.worst_files = vec![FileQuality {
+            file_path: "src/parser.rs".to_string(),
+            score: 42.5,
+            code_lines: 900,
+            function_count: 30,
+            average_complexity: 8.2,
+            maintainability_index: 12.0,
+            documentation_coverage: 10.0,
+            duplication_ratio: 5.0,
+        }];
+        stats
+    }
+
+    #[test]
+    fn test_report_matches_schema() {
+        let stats = stats();
+        let report = serde_json::to_value(JsonReport::new(&stats)).unwrap();
+        assert_eq!(report["schema_version"], SCHEMA_VERSION);
+        assert_eq!(check(&report), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_schema_is_of_the_current_version() {
+        let schema: Value = serde_json::from_str(REPORT_SCHEMA).unwrap();
+        assert_eq!(schema["properties"]["schema_version"]["const"], SCHEMA_VERSION);
+    }
+
+    /// `--output yaml` and `--output toml` print this report in other syntaxes
+    #[test]
+    fn test_report_carries_over_to_yaml_and_toml() {
+        let stats = stats();
+        let report = serde_json::to_value(JsonReport::new(&stats)).unwrap();
+
+        let yaml: Value = serde_yaml::from_str(&serde_yaml::to_string(&JsonReport::new(&stats)).unwrap()).unwrap();
+        assert_eq!(yaml, report);
+
+        // TOML has no null, so fields without a value are left out
+        let toml: toml::Value = toml::from_str(&toml::to_string_pretty(&JsonReport::new(&stats)).unwrap()).unwrap();
+        assert_eq!(toml["schema_version"].as_integer(), Some(SCHEMA_VERSION as i64));
+        assert_eq!(toml["basic"]["total_files"].as_integer(), Some(2));
+        assert_eq!(toml["areas"][0]["name"].as_str(), Some("frontend"));
+    }
+
+    #[test]
+    fn test_report_carries_over_to_msgpack() {
+        let stats = stats();
+        let report = serde_json::to_value(JsonReport::new(&stats)).unwrap();
+        let msgpack = rmp_serde::to_vec_named(&JsonReport::new(&stats)).unwrap();
+        assert_eq!(rmp_serde::from_slice::<Value>(&msgpack).unwrap(), report);
+        assert!(msgpack.len() < serde_json::to_vec_pretty(&report).unwrap().len());
+    }
+
+    /// Reports of earlier releases must keep matching the schema and reading
+    /// back, or dashboards and `howmany merge` break on upgrade
+    #[test]
+    fn test_version_1_reports_stay_compatible() {
+        let report: Value = serde_json::from_str(REPORT_V1).unwrap();
+        assert_eq!(check(&report), Vec::<String>::new());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        std::fs::write(&path, REPORT_V1).unwrap();
+        let stats = read_report(&path).unwrap();
+        assert_eq!(stats.basic.total_files, 3);
+        assert_eq!(serde_json::to_value(JsonReport::new(&stats)).unwrap(), report);
+
+        // Reports from before the version was written
+        let mut unversioned = report.clone();
+        unversioned.as_object_mut().unwrap().remove("schema_version");
+        std::fs::write(&path, unversioned.to_string()).unwrap();
+        assert_eq!(read_report(&path).unwrap().basic.total_files, 3);
+
+        let mut newer = report;
+        newer["schema_version"] = Value::from(SCHEMA_VERSION + 1);
+        std::fs::write(&path, newer.to_string()).unwrap();
+        assert!(read_report(&path).unwrap_err().to_string().contains("upgrade howmany"));
+    }
+}
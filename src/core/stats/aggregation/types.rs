@@ -1,7 +1,17 @@
+use crate::core::budget::BudgetStats;
+use crate::core::dependencies::DependencyStats;
+use crate::core::licenses::LicenseStats;
+use crate::core::detector::patterns::category::FileCategory;
 use crate::core::stats::basic::BasicStats;
+use crate::core::types::FileStats;
 use crate::core::stats::complexity::ComplexityStats;
 use crate::core::stats::ratios::RatioStats;
+use crate::utils::churn::ChurnStats;
+use crate::utils::errors::HowManyError;
+use crate::utils::ownership::OwnershipStats;
+use crate::utils::paths::NormalizationConflict;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Aggregated statistics containing all types of statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +20,176 @@ pub struct AggregatedStats {
     pub complexity: ComplexityStats,
     pub ratios: RatioStats,
     pub metadata: StatsMetadata,
+    #[serde(default)]
+    pub fixtures: FixtureStats,
+    #[serde(default)]
+    pub generated: GeneratedStats,
+    #[serde(default)]
+    pub minified: MinifiedStats,
+    /// Files decoded from an encoding other than UTF-8
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub non_utf8_files: Vec<FileEncoding>,
+    /// Files left out of the counts, with the reason
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_files: Vec<SkippedFile>,
+    /// File names that differ only by Unicode normalization (NFC vs NFD)
+    #[serde(default)]
+    pub normalization_conflicts: Vec<NormalizationConflict>,
+    /// Per-root totals when several paths are analyzed with `--per-path`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub per_path: Vec<PathStats>,
+    /// Vendored crate checkouts kept out of the totals with `--nested-projects separate`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nested_projects: Vec<PathStats>,
+    /// Totals for the `[areas]` defined in the project config
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub areas: Vec<AreaStats>,
+    /// Consumption of the `[budget]` caps defined in the project config
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<BudgetStats>,
+    /// Totals for examples, benchmarks and docs, some of which may be left out of the quality score
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<CategoryStats>,
+    /// Line attribution from git blame, computed with `--owners`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ownership: Option<OwnershipStats>,
+    /// Change frequency and hotspots from recent git history, computed with `--churn`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<ChurnStats>,
+    /// Dependencies declared in the package manifests, computed with `--dependencies`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<DependencyStats>,
+    /// License headers of the counted files, computed with `--licenses`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub licenses: Option<LicenseStats>,
+    /// The totals split into production and test code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_split: Option<TestSplitStats>,
+}
+
+/// Production and test code totals. Test code is found by path conventions
+/// (`tests/`, `*_test.go`, `*.spec.ts`) and Rust `#[cfg(test)]` items; a Rust
+/// file with inline tests counts as a file in both buckets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestSplitStats {
+    pub production: BasicStats,
+    pub test: BasicStats,
+}
+
+impl TestSplitStats {
+    /// Test code lines per production code line
+    pub fn test_to_code_ratio(&self) -> f64 {
+        if self.production.code_lines > 0 {
+            self.test.code_lines as f64 / self.production.code_lines as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Basic totals for one analyzed root path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathStats {
+    pub path: String,
+    pub basic: BasicStats,
+    /// Individual file counts, kept when the run collects them (e.g. for the
+    /// interactive project switcher)
+    #[serde(skip)]
+    pub files: Vec<(String, FileStats)>,
+}
+
+/// Basic totals for one configured area
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AreaStats {
+    pub name: String,
+    pub basic: BasicStats,
+}
+
+/// Basic totals for the files of one directory category. They are part of the
+/// main totals; `scored` says whether they count toward the quality score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryStats {
+    pub category: FileCategory,
+    pub basic: BasicStats,
+    pub scored: bool,
+}
+
+/// Snapshot and golden-output test fixtures, kept out of the main totals and quality metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureStats {
+    pub file_count: usize,
+    pub total_lines: usize,
+    pub total_size: u64,
+    pub included_in_totals: bool,
+}
+
+/// Generated sources (protobuf stubs, `@generated` files, ...), kept out of the
+/// main totals and quality metrics unless `--include-generated` is set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GeneratedStats {
+    pub file_count: usize,
+    pub total_lines: usize,
+    pub code_lines: usize,
+    pub total_size: u64,
+    pub included_in_totals: bool,
+}
+
+/// Minified or bundled scripts and stylesheets (`*.min.js`, webpack output, ...),
+/// kept out of the main totals and quality metrics unless `--include-minified` is set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MinifiedStats {
+    pub file_count: usize,
+    pub total_lines: usize,
+    pub total_size: u64,
+    pub included_in_totals: bool,
+}
+
+/// A file read in a legacy or UTF-16 encoding, decoded before counting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEncoding {
+    pub path: String,
+    /// Encoding name, e.g. `windows-1252` or `UTF-16LE`
+    pub encoding: String,
+}
+
+/// A file the counter refused to read in full
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: SkipReason,
+    /// Size in bytes
+    pub size: u64,
+}
+
+impl SkippedFile {
+    /// The record of a file skipped with `error`, or `None` if the error is a real failure
+    pub fn from_error(path: String, error: &HowManyError) -> Option<Self> {
+        let (reason, size) = match error {
+            HowManyError::FileTooLarge { size, .. } => (SkipReason::TooLarge, *size),
+            HowManyError::BinaryContent { size, .. } => (SkipReason::Binary, *size),
+            _ => return None,
+        };
+        Some(Self { path, reason, size })
+    }
+}
+
+/// Why a file was left out of the counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Larger than `--max-file-size` (minified bundles, data dumps)
+    TooLarge,
+    /// Binary content under a source-like name
+    Binary,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLarge => write!(f, "too large"),
+            Self::Binary => write!(f, "binary content"),
+        }
+    }
 }
 
 /// Metadata about the statistics calculation
@@ -21,7 +201,79 @@ pub struct StatsMetadata {
     pub file_count_analyzed: usize,
     pub total_bytes_analyzed: u64,
     pub languages_detected: Vec<String>,
+    /// Detected extensions without a complexity analyzer, counted but left out of complexity metrics
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub complexity_not_analyzed: Vec<String>,
     pub analysis_depth: AnalysisDepth,
+    /// Optional analyses that produced data, so formatters can leave out the rest
+    #[serde(default)]
+    pub capabilities: AnalysisCapabilities,
+    /// Cache effectiveness and phase timings of the run, when produced by a directory analysis
+    #[serde(default)]
+    pub performance: Option<RunPerformance>,
+    /// Set when the analysis was stopped early, so the counts cover only part of the files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incomplete: Option<Interruption>,
+}
+
+/// Optional analyses that ran and produced data. Formatters consult these and
+/// leave out the sections of analyses that didn't run instead of reporting zeros.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisCapabilities {
+    /// Some counted language has a complexity analyzer, so function and health metrics were measured
+    pub complexity: bool,
+    /// Production and test code were split (`--split-tests`), so the test-to-code ratio was measured
+    pub test_split: bool,
+    /// Lines were attributed with git blame (`--owners`)
+    pub ownership: bool,
+    /// Change frequency was read from git history (`--churn`)
+    pub churn: bool,
+    /// Package manifests were inventoried (`--dependencies`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dependencies: bool,
+    /// File headers were checked for licenses (`--licenses`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub licenses: bool,
+}
+
+impl AnalysisCapabilities {
+    /// The capabilities with the flags of results attached to `stats` (test
+    /// split, ownership, churn, dependencies, licenses) read from it; `complexity` is set by the
+    /// calculator that ran the per-file analysis
+    pub fn of(stats: &AggregatedStats) -> Self {
+        Self {
+            complexity: stats.metadata.capabilities.complexity,
+            test_split: stats.test_split.is_some(),
+            ownership: stats.ownership.is_some(),
+            churn: stats.churn.is_some(),
+            dependencies: stats.dependencies.is_some(),
+            licenses: stats.licenses.is_some(),
+        }
+    }
+}
+
+/// Why an analysis stopped before every file was counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interruption {
+    Cancelled,
+    TimedOut,
+}
+
+/// Cache statistics and per-phase timings of an analysis run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunPerformance {
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    pub cache_hit_rate: f64,
+    /// Entries in the file cache after the run
+    pub cache_entries: usize,
+    /// Files with binary content under a source-like name, left out of the counts
+    #[serde(default)]
+    pub binary_files_skipped: usize,
+    pub total_time_ms: u64,
+    /// Wall-clock time per phase (`discovery`, `counting`, `statistics`), in milliseconds
+    pub phase_timings_ms: BTreeMap<String, u64>,
 }
 
 /// Depth of analysis performed
@@ -1,7 +1,7 @@
-use crate::core::types::{CodeStats, FileStats};
+use crate::core::types::{CodeStats, FileStats, LineLengthStats};
 use crate::utils::errors::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Basic statistics for a file or project
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +12,24 @@ pub struct BasicStats {
     pub comment_lines: usize,
     pub doc_lines: usize,
     pub blank_lines: usize,
+    /// Code lines that also carry a comment, counted in `code_lines` too
+    #[serde(default)]
+    pub inline_comment_lines: usize,
+    /// Statements (logical lines of code)
+    #[serde(default)]
+    pub logical_lines: usize,
+    #[serde(default)]
+    pub characters: usize,
+    #[serde(default)]
+    pub non_whitespace_characters: usize,
+    /// Approximate LLM tokens
+    #[serde(default)]
+    pub tokens: usize,
+    #[serde(default)]
+    pub line_lengths: LineLengthStats,
+    /// Average line length in characters
+    #[serde(default)]
+    pub average_line_length: f64,
     pub total_size: u64,
     pub average_file_size: f64,
     pub average_lines_per_file: f64,
@@ -29,9 +47,22 @@ pub struct ExtensionStats {
     pub comment_lines: usize,
     pub doc_lines: usize,
     pub blank_lines: usize,
+    #[serde(default)]
+    pub characters: usize,
+    #[serde(default)]
+    pub non_whitespace_characters: usize,
+    #[serde(default)]
+    pub tokens: usize,
+    #[serde(default)]
+    pub line_lengths: LineLengthStats,
+    #[serde(default)]
+    pub average_line_length: f64,
     pub total_size: u64,
     pub average_lines_per_file: f64,
     pub average_size_per_file: f64,
+    /// Plugin metrics summed over the files
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metrics: BTreeMap<String, f64>,
 }
 
 /// Calculator for basic statistics
@@ -51,6 +82,13 @@ impl BasicStatsCalculator {
             comment_lines: file_stats.comment_lines,
             doc_lines: file_stats.doc_lines,
             blank_lines: file_stats.blank_lines,
+            inline_comment_lines: file_stats.inline_comment_lines,
+            logical_lines: file_stats.logical_lines,
+            characters: file_stats.characters,
+            non_whitespace_characters: file_stats.non_whitespace_characters,
+            tokens: file_stats.tokens,
+            line_lengths: file_stats.line_lengths,
+            average_line_length: file_stats.line_lengths.average(file_stats.total_lines),
             total_size: file_stats.file_size,
             average_file_size: file_stats.file_size as f64,
             average_lines_per_file: file_stats.total_lines as f64,
@@ -73,6 +111,11 @@ impl BasicStatsCalculator {
                 comment_lines: file_stats.comment_lines,
                 doc_lines: file_stats.doc_lines,
                 blank_lines: file_stats.blank_lines,
+                characters: file_stats.characters,
+                non_whitespace_characters: file_stats.non_whitespace_characters,
+                tokens: file_stats.tokens,
+                line_lengths: file_stats.line_lengths,
+                average_line_length: file_stats.line_lengths.average(file_stats.total_lines),
                 total_size: file_stats.file_size,
                 average_lines_per_file: if *file_count > 0 {
                     file_stats.total_lines as f64 / *file_count as f64
@@ -84,6 +127,7 @@ impl BasicStatsCalculator {
                 } else {
                     0.0
                 },
+                metrics: file_stats.metrics.clone(),
             };
             
             stats_by_extension.insert(ext.clone(), ext_stats);
@@ -111,6 +155,13 @@ impl BasicStatsCalculator {
             comment_lines: code_stats.total_comment_lines,
             doc_lines: code_stats.total_doc_lines,
             blank_lines: code_stats.total_blank_lines,
+            inline_comment_lines: code_stats.total_inline_comment_lines,
+            logical_lines: code_stats.total_logical_lines,
+            characters: code_stats.total_characters,
+            non_whitespace_characters: code_stats.total_non_whitespace_characters,
+            tokens: code_stats.total_tokens,
+            line_lengths: code_stats.line_lengths,
+            average_line_length: code_stats.line_lengths.average(code_stats.total_lines),
             total_size: code_stats.total_size,
             average_file_size: if code_stats.total_files > 0 {
                 code_stats.total_size as f64 / code_stats.total_files as f64
@@ -210,6 +261,16 @@ mod tests {
             doc_lines: 5,
             blank_lines: 10,
             file_size: 2048,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
 
         let result = calculator.calculate_basic_stats(&file_stats).unwrap();
@@ -238,6 +299,16 @@ mod tests {
             doc_lines: 0,
             blank_lines: 0,
             file_size: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
 
         let result = calculator.calculate_basic_stats(&file_stats).unwrap();
@@ -268,6 +339,16 @@ mod tests {
             doc_lines: 10,
             blank_lines: 20,
             file_size: 3000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
         stats_by_extension.insert("py".to_string(), (1, FileStats {
             total_lines: 80,
@@ -276,6 +357,16 @@ mod tests {
             doc_lines: 3,
             blank_lines: 5,
             file_size: 1500,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
 
         let code_stats = CodeStats {
@@ -287,6 +378,12 @@ mod tests {
             total_blank_lines: 25,
             total_size: 4500,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_basic_stats(&code_stats).unwrap();
@@ -333,6 +430,12 @@ mod tests {
             total_blank_lines: 0,
             total_size: 0,
             stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_basic_stats(&code_stats).unwrap();
@@ -363,6 +466,16 @@ mod tests {
             doc_lines: 25,
             blank_lines: 50,
             file_size: 6000,  // This is the total size for all files of this extension
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
 
         let code_stats = CodeStats {
@@ -374,6 +487,12 @@ mod tests {
             total_blank_lines: 50,
             total_size: 6000,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_basic_stats(&code_stats).unwrap();
@@ -411,6 +530,12 @@ mod tests {
             total_size: 10000,
             average_lines_per_file: 100.0,
             average_size_per_file: 2000.0,
+            metrics: Default::default(),
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            average_line_length: 0.0,
         };
 
         assert_eq!(ext_stats.file_count, 5);
@@ -433,12 +558,19 @@ mod tests {
             comment_lines: 200,
             doc_lines: 50,
             blank_lines: 100,
+            inline_comment_lines: 0,
             total_size: 20000,
             average_file_size: 2000.0,
             average_lines_per_file: 100.0,
             largest_file_size: 5000,
             smallest_file_size: 500,
             stats_by_extension: HashMap::new(),
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            average_line_length: 0.0,
         };
 
         // Test serialization to JSON
@@ -470,6 +602,12 @@ mod tests {
             total_size: 6000,
             average_lines_per_file: 100.0,
             average_size_per_file: 2000.0,
+            metrics: Default::default(),
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            average_line_length: 0.0,
         };
 
         // Test serialization to JSON
@@ -499,6 +637,16 @@ mod tests {
             doc_lines: usize::MAX / 8,
             blank_lines: usize::MAX / 8,
             file_size: u64::MAX,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
 
         let result = calculator.calculate_basic_stats(&large_file_stats).unwrap();
@@ -528,6 +676,16 @@ mod tests {
             doc_lines: 5,
             blank_lines: 10,
             file_size: 2000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
 
         let code_stats = CodeStats {
@@ -539,6 +697,12 @@ mod tests {
             total_blank_lines: 20,
             total_size: 4000,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_basic_stats(&code_stats).unwrap();
@@ -1,54 +1,64 @@
+use crate::utils::cache::AnalysisCache;
+use crate::utils::encoding;
 use crate::utils::errors::Result;
-use super::types::{FunctionInfo, StructureInfo};
-use super::languages::get_language_analyzer;
+use super::halstead::HalsteadMetrics;
+use super::types::{FileAnalysis, FunctionInfo};
+use super::languages::{get_language_analyzer, resolve_extension};
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 /// Language-specific code analyzer
-pub struct CodeAnalyzer;
+pub struct CodeAnalyzer {
+    cache: Option<AnalysisCache>,
+}
 
 impl CodeAnalyzer {
     pub fn new() -> Self {
-        Self
+        Self { cache: None }
+    }
+
+    /// Reuse the analyses of files whose contents were analyzed before
+    pub fn with_cache(mut self, cache: Option<AnalysisCache>) -> Self {
+        self.cache = cache;
+        self
     }
 
-    /// Analyze structures in a file (classes, interfaces, etc.)
-    pub fn analyze_file_structures(&self, file_path: &str) -> Result<Vec<StructureInfo>> {
-        let file = fs::File::open(file_path)?;
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines().collect::<std::io::Result<Vec<_>>>()?;
+    /// Analyze the functions and structures of a file with the analyzer of `extension`
+    pub fn analyze_file_as(&self, file_path: &str, extension: &str) -> Result<FileAnalysis> {
+        let contents = fs::read(file_path)?;
+        let key = self.cache.as_ref().map(|cache| cache.key(extension, &contents));
+        if let Some(analysis) = self.cache.as_ref().zip(key.as_ref()).and_then(|(cache, key)| cache.get(key)) {
+            return Ok(analysis);
+        }
         
-        let extension = Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("unknown")
-            .to_lowercase();
+        let text = encoding::decode(&contents).text;
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        let analysis = match get_language_analyzer(resolve_extension(extension, lines.iter().map(String::as_str))) {
+            Some(analyzer) => FileAnalysis {
+                functions: analyzer.analyze_functions(&lines)?,
+                structures: analyzer.analyze_structures(&lines)?,
+                halstead: HalsteadMetrics::measure(&lines, analyzer.comment_scanner()),
+            },
+            None => FileAnalysis::default(), // Unsupported language
+        };
         
-        if let Some(analyzer) = get_language_analyzer(&extension) {
-            analyzer.analyze_structures(&lines)
-        } else {
-            Ok(Vec::new()) // Unsupported language
+        if let (Some(cache), Some(key)) = (&self.cache, key) {
+            cache.insert(key, analysis.clone());
         }
+        Ok(analysis)
     }
-    
+
     /// Analyze functions in a file for complexity metrics
     pub fn analyze_file_functions(&self, file_path: &str) -> Result<Vec<FunctionInfo>> {
-        let file = fs::File::open(file_path)?;
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines().collect::<std::io::Result<Vec<_>>>()?;
-        
-        let extension = Path::new(file_path)
+        Ok(self.analyze_file_as(file_path, &Self::extension_of(file_path))?.functions)
+    }
+    
+    fn extension_of(file_path: &str) -> String {
+        Path::new(file_path)
             .extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("unknown")
-            .to_lowercase();
-        
-        if let Some(analyzer) = get_language_analyzer(&extension) {
-            analyzer.analyze_functions(&lines)
-        } else {
-            Ok(Vec::new()) // Unsupported language
-        }
+            .to_lowercase()
     }
 }
 
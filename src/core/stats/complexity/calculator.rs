@@ -1,7 +1,11 @@
 use crate::core::types::{CodeStats, FileStats};
+use crate::utils::cache::AnalysisCache;
 use crate::utils::errors::Result;
-use super::types::{ComplexityStats, ComplexityDistribution, StructureDistribution, ExtensionComplexity, FunctionInfo, StructureInfo, StructureType};
+use super::types::{ComplexityStats, ComplexityDistribution, StructureDistribution, ExtensionComplexity, FileAnalysis, FunctionInfo, StructureInfo, StructureType, LongParameterListFinding, FunctionComplexityDetail, FileQuality, FunctionThresholds, FunctionLengthDistribution};
 use super::analyzer::CodeAnalyzer;
+use super::halstead::maintainability_index;
+use super::languages::has_language_analyzer;
+use super::overrides::AnalyzerOverrides;
 use super::quality::QualityCalculator;
 use std::collections::HashMap;
 use std::path::Path;
@@ -10,6 +14,14 @@ use std::path::Path;
 pub struct ComplexityCalculator {
     analyzer: CodeAnalyzer,
     quality_calculator: QualityCalculator,
+    thresholds: FunctionThresholds,
+    overrides: AnalyzerOverrides,
+    /// Most complex functions a project keeps in `function_complexity_details`
+    top_functions: usize,
+    /// Lowest-scoring files a project keeps in `worst_files`
+    worst_files: usize,
+    /// Whether the line counts are keyed by file extension rather than by language
+    by_extension: bool,
 }
 
 impl ComplexityCalculator {
@@ -17,13 +29,75 @@ impl ComplexityCalculator {
         Self {
             analyzer: CodeAnalyzer::new(),
             quality_calculator: QualityCalculator::new(),
+            thresholds: FunctionThresholds::default(),
+            overrides: AnalyzerOverrides::default(),
+            top_functions: 0,
+            worst_files: 0,
+            by_extension: false,
         }
     }
 
+    /// Use custom limits when selecting functions to report individually
+    pub fn with_thresholds(mut self, thresholds: FunctionThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Keep the `count` most complex functions of a project (`--top-functions`)
+    pub fn with_top_functions(mut self, count: usize) -> Self {
+        self.top_functions = count;
+        self
+    }
+
+    /// Keep the `count` lowest-scoring files of a project (`--worst`)
+    pub fn with_worst_files(mut self, count: usize) -> Self {
+        self.worst_files = count;
+        self
+    }
+
+    /// Skip files or pick their analyzer by path (`[complexity]`)
+    pub fn with_overrides(mut self, overrides: AnalyzerOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Match line counts keyed by file extension (`--by-extension`), where an
+    /// Objective-C `.m` file is counted under `m`
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.by_extension = by_extension;
+        self
+    }
+
+    /// Reuse functions and structures found in files with the same contents before
+    pub fn with_analysis_cache(mut self, cache: Option<AnalysisCache>) -> Self {
+        self.analyzer = self.analyzer.with_cache(cache);
+        self
+    }
+
+    /// Extension of the analyzer for a file, `None` when it is skipped
+    fn analyzer_extension(&self, file_path: &str) -> Option<String> {
+        self.overrides.analyzer_for(file_path, &extension_of(file_path))
+    }
+
+    /// Whether a file gets per-function analysis: it isn't skipped and its language has an analyzer
+    pub fn analyzes(&self, file_path: &str) -> bool {
+        self.analyzer_extension(file_path).is_some_and(|extension| has_language_analyzer(&extension))
+    }
+
+    /// Sum of the cyclomatic complexity of every function in a file
+    pub fn calculate_total_complexity(&self, file_path: &str) -> Result<usize> {
+        let functions = self.analyzer.analyze_file_functions(file_path)?;
+        Ok(functions.iter().map(|f| f.cyclomatic_complexity).sum())
+    }
+
     /// Calculate complexity statistics for a single file
     pub fn calculate_complexity_stats(&self, file_stats: &FileStats, file_path: &str) -> Result<ComplexityStats> {
-        let functions = self.analyzer.analyze_file_functions(file_path)?;
-        let structures = self.analyzer.analyze_file_structures(file_path)?;
+        let analysis = match self.analyzer_extension(file_path) {
+            Some(extension) => self.analyzer.analyze_file_as(file_path, &extension)?,
+            None => FileAnalysis::default(),
+        };
+        let maintainability_index = self.calculate_maintainability_index(&analysis, file_stats);
+        let FileAnalysis { functions, structures, .. } = analysis;
         
         let function_count = functions.len();
         
@@ -35,9 +109,6 @@ impl ComplexityCalculator {
         let total_cognitive = functions.iter().map(|f| f.cognitive_complexity as f64).sum::<f64>();
         let cognitive_complexity = if function_count > 0 { total_cognitive / function_count as f64 } else { 0.0 };
         
-        // Calculate maintainability index
-        let maintainability_index = self.calculate_maintainability_index(&functions, file_stats);
-        
         let average_function_length = if function_count > 0 {
             functions.iter().map(|f| f.line_count as f64).sum::<f64>() / function_count as f64
         } else {
@@ -61,6 +132,15 @@ impl ComplexityCalculator {
         
         let max_parameters_per_function = functions.iter().map(|f| f.parameter_count).max().unwrap_or(0);
         
+        let average_return_paths_per_function = if function_count > 0 {
+            functions.iter().map(|f| f.return_path_count as f64).sum::<f64>() / function_count as f64
+        } else {
+            0.0
+        };
+        
+        let max_return_paths_per_function = functions.iter().map(|f| f.return_path_count).max().unwrap_or(0);
+        let long_parameter_functions = self.find_long_parameter_functions(&functions, file_path);
+        
         let complexity_distribution = self.calculate_complexity_distribution(&functions);
         let structure_distribution = self.calculate_structure_distribution(&structures);
         
@@ -72,17 +152,17 @@ impl ComplexityCalculator {
         let module_count = structures.iter().filter(|s| s.structure_type == StructureType::Module || s.structure_type == StructureType::Namespace).count();
         let total_structures = structures.len();
         
+        let class_methods = class_method_counts(&structures, &functions);
         let methods_per_class = if class_count > 0 {
-            structures.iter()
-                .filter(|s| s.structure_type == StructureType::Class)
-                .map(|s| s.methods.len())
-                .sum::<usize>() as f64 / class_count as f64
+            class_methods.iter().sum::<usize>() as f64 / class_count as f64
         } else {
             0.0
         };
+        let max_methods_per_class = class_methods.iter().copied().max().unwrap_or(0);
         
         let function_complexity_details = self.quality_calculator.create_function_complexity_details(&functions, file_path);
-        let quality_metrics = self.quality_calculator.calculate_quality_metrics(&functions, file_stats, &structures);
+        let complex_functions = self.find_complex_functions(&functions, file_path);
+        let quality_metrics = self.quality_calculator.calculate_quality_metrics(&functions, file_stats, &structures, maintainability_index);
         
         Ok(ComplexityStats {
             function_count,
@@ -102,13 +182,21 @@ impl ComplexityCalculator {
             max_nesting_depth,
             average_nesting_depth,
             methods_per_class,
+            max_methods_per_class,
             average_parameters_per_function,
             max_parameters_per_function,
+            average_return_paths_per_function,
+            max_return_paths_per_function,
             complexity_by_extension: HashMap::new(),
             complexity_distribution,
             structure_distribution,
             function_complexity_details,
+            long_parameter_functions,
+            complex_functions,
+            function_thresholds: self.thresholds,
+            function_length_distribution: self.calculate_function_length_distribution(&functions),
             quality_metrics,
+            worst_files: Vec::new(),
         })
     }
     
@@ -120,75 +208,130 @@ impl ComplexityCalculator {
         let mut total_enums = 0;
         let mut total_structs = 0;
         let mut total_modules = 0;
+        let mut total_class_methods = 0;
+        let mut max_class_methods = 0;
         let mut total_complexity = 0.0;
         let mut total_function_lines = 0;
         let mut max_function_length = 0;
         let mut min_function_length = usize::MAX;
         let mut max_nesting_depth = 0;
         let mut total_nesting_depth = 0.0;
-        let mut complexity_by_extension = HashMap::new();
+        let mut complexity_by_extension: HashMap<String, ExtensionComplexity> = HashMap::new();
         let mut all_functions = Vec::new();
         let mut all_structures = Vec::new();
+        let mut long_parameter_functions = Vec::new();
+        let mut complex_functions = Vec::new();
+        let mut top_functions = Vec::new();
+        let mut worst_files = Vec::new();
+        // Maintainability indexes of the files, weighted by their code lines
+        let mut weighted_maintainability = 0.0;
+        let mut maintainability_lines = 0;
+        let mut maintainability_by_extension: HashMap<String, (f64, usize)> = HashMap::new();
+        
+        // Files of languages without an analyzer have no functions to find, and their
+        // lines would only dilute the line-based metrics below. Skipped files and
+        // files given another analyzer move their lines accordingly.
+        let mut analyzed_stats = analyzed_code_stats(code_stats);
+        let individual_files: Vec<_> = individual_files
+            .iter()
+            .filter_map(|(file_path, stats)| {
+                // A file counted as another language than its name's (an Objective-C `.m`) is analyzed as that one
+                let own_extension = stats.row_key(extension_of(file_path), self.by_extension);
+                let extension = self.overrides.analyzer_for(file_path, &stats.language_key(extension_of(file_path))).filter(|extension| has_language_analyzer(extension));
+                if extension.as_deref() != Some(own_extension.as_str()) {
+                    move_analyzed_lines(&mut analyzed_stats, stats, &own_extension, extension.as_deref());
+                }
+                Some((file_path, stats, extension?))
+            })
+            .collect();
         
         // Analyze individual files for detailed complexity metrics
-        for (file_path, _) in individual_files {
-            if let Ok(functions) = self.analyzer.analyze_file_functions(file_path) {
-                all_functions.extend(functions.clone());
+        for (file_path, file_stats, extension) in &individual_files {
+            let Ok(analysis) = self.analyzer.analyze_file_as(file_path, extension) else {
+                continue;
+            };
+            let file_maintainability = self.calculate_maintainability_index(&analysis, file_stats);
+            weighted_maintainability += file_maintainability * file_stats.code_lines as f64;
+            maintainability_lines += file_stats.code_lines;
+            let extension_maintainability = maintainability_by_extension.entry(extension.clone()).or_default();
+            extension_maintainability.0 += file_maintainability * file_stats.code_lines as f64;
+            extension_maintainability.1 += file_stats.code_lines;
+            let FileAnalysis { functions, structures, .. } = analysis;
+            
+            // Trimmed as it grows, like the most complex functions below
+            if self.worst_files > 0 {
+                let quality = self.quality_calculator.calculate_quality_metrics(&functions, file_stats, &structures, file_maintainability);
+                worst_files.push(self.quality_calculator.calculate_file_quality(file_path, &functions, file_stats, &quality));
+                if worst_files.len() > self.worst_files * 2 {
+                    worst_files = FileQuality::worst(worst_files, self.worst_files);
+                }
             }
             
-            if let Ok(structures) = self.analyzer.analyze_file_structures(file_path) {
-                all_structures.extend(structures.clone());
-                
-                total_classes += structures.iter().filter(|s| s.structure_type == StructureType::Class).count();
-                total_interfaces += structures.iter().filter(|s| s.structure_type == StructureType::Interface).count();
-                total_traits += structures.iter().filter(|s| s.structure_type == StructureType::Trait).count();
-                total_enums += structures.iter().filter(|s| s.structure_type == StructureType::Enum).count();
-                total_structs += structures.iter().filter(|s| s.structure_type == StructureType::Struct).count();
-                total_modules += structures.iter().filter(|s| s.structure_type == StructureType::Module || s.structure_type == StructureType::Namespace).count();
+            let distribution = self.calculate_structure_distribution(&structures);
+            total_classes += distribution.classes;
+            total_interfaces += distribution.interfaces;
+            total_traits += distribution.traits;
+            total_enums += distribution.enums;
+            total_structs += distribution.structs;
+            total_modules += distribution.modules;
+            
+            // Files declaring types but no functions still count toward their language
+            let class_methods = class_method_counts(&structures, &functions);
+            total_class_methods += class_methods.iter().sum::<usize>();
+            max_class_methods = max_class_methods.max(class_methods.iter().copied().max().unwrap_or(0));
+            if !structures.is_empty() {
+                let entry = complexity_by_extension.entry(extension.clone()).or_default();
+                let old_methods = entry.methods_per_class * entry.class_count as f64;
+                entry.class_count += distribution.classes;
+                entry.interface_count += distribution.interfaces;
+                entry.trait_count += distribution.traits;
+                entry.enum_count += distribution.enums;
+                entry.struct_count += distribution.structs;
+                entry.module_count += distribution.modules;
+                entry.total_structures += structures.len();
+                entry.methods_per_class = if entry.class_count > 0 { (old_methods + class_methods.iter().sum::<usize>() as f64) / entry.class_count as f64 } else { 0.0 };
+                entry.max_methods_per_class = entry.max_methods_per_class.max(class_methods.iter().copied().max().unwrap_or(0));
             }
+            all_structures.extend(structures);
             
-            if let Ok(functions) = self.analyzer.analyze_file_functions(file_path) {
-                let extension = Path::new(file_path)
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .unwrap_or("unknown")
-                    .to_lowercase();
+            let function_count = functions.len();
+            if function_count > 0 {
+                let ext_complexity = functions.iter().map(|f| f.cyclomatic_complexity as f64).sum::<f64>() / function_count as f64;
+                let ext_avg_length = functions.iter().map(|f| f.line_count as f64).sum::<f64>() / function_count as f64;
+                let ext_max_nesting = functions.iter().map(|f| f.nesting_depth).max().unwrap_or(0);
+                let ext_avg_nesting = functions.iter().map(|f| f.nesting_depth as f64).sum::<f64>() / function_count as f64;
+                let ext_avg_params = functions.iter().map(|f| f.parameter_count as f64).sum::<f64>() / function_count as f64;
+                let ext_max_params = functions.iter().map(|f| f.parameter_count).max().unwrap_or(0);
+                let ext_avg_returns = functions.iter().map(|f| f.return_path_count as f64).sum::<f64>() / function_count as f64;
+                let ext_max_returns = functions.iter().map(|f| f.return_path_count).max().unwrap_or(0);
+                let file_long_parameter_functions = self.find_long_parameter_functions(&functions, file_path);
                 
-                let function_count = functions.len();
-                if function_count > 0 {
-                    let ext_complexity = functions.iter().map(|f| f.cyclomatic_complexity as f64).sum::<f64>() / function_count as f64;
-                    let ext_avg_length = functions.iter().map(|f| f.line_count as f64).sum::<f64>() / function_count as f64;
-                    let ext_max_nesting = functions.iter().map(|f| f.nesting_depth).max().unwrap_or(0);
-                    let ext_avg_nesting = functions.iter().map(|f| f.nesting_depth as f64).sum::<f64>() / function_count as f64;
-                    
-                    let entry = complexity_by_extension.entry(extension).or_insert(ExtensionComplexity {
-                        function_count: 0,
-                        class_count: 0,
-                        interface_count: 0,
-                        trait_count: 0,
-                        enum_count: 0,
-                        struct_count: 0,
-                        total_structures: 0,
-                        cyclomatic_complexity: 0.0,
-                        cognitive_complexity: 0.0,
-                        maintainability_index: 0.0,
-                        average_function_length: 0.0,
-                        max_nesting_depth: 0,
-                        average_nesting_depth: 0.0,
-                        methods_per_class: 0.0,
-                        average_parameters_per_function: 0.0,
-                        quality_score: 0.0,
-                    });
-                    
-                    entry.function_count += function_count;
-                    entry.cyclomatic_complexity = (entry.cyclomatic_complexity * (entry.function_count - function_count) as f64 + ext_complexity * function_count as f64) / entry.function_count as f64;
-                    entry.average_function_length = (entry.average_function_length * (entry.function_count - function_count) as f64 + ext_avg_length * function_count as f64) / entry.function_count as f64;
-                    entry.max_nesting_depth = entry.max_nesting_depth.max(ext_max_nesting);
-                    entry.average_nesting_depth = (entry.average_nesting_depth * (entry.function_count - function_count) as f64 + ext_avg_nesting * function_count as f64) / entry.function_count as f64;
-                }
+                let entry = complexity_by_extension.entry(extension.clone()).or_default();
                 
-                all_functions.extend(functions);
+                entry.function_count += function_count;
+                entry.cyclomatic_complexity = (entry.cyclomatic_complexity * (entry.function_count - function_count) as f64 + ext_complexity * function_count as f64) / entry.function_count as f64;
+                entry.average_function_length = (entry.average_function_length * (entry.function_count - function_count) as f64 + ext_avg_length * function_count as f64) / entry.function_count as f64;
+                entry.max_nesting_depth = entry.max_nesting_depth.max(ext_max_nesting);
+                entry.average_nesting_depth = (entry.average_nesting_depth * (entry.function_count - function_count) as f64 + ext_avg_nesting * function_count as f64) / entry.function_count as f64;
+                entry.average_parameters_per_function = (entry.average_parameters_per_function * (entry.function_count - function_count) as f64 + ext_avg_params * function_count as f64) / entry.function_count as f64;
+                entry.max_parameters_per_function = entry.max_parameters_per_function.max(ext_max_params);
+                entry.average_return_paths_per_function = (entry.average_return_paths_per_function * (entry.function_count - function_count) as f64 + ext_avg_returns * function_count as f64) / entry.function_count as f64;
+                entry.max_return_paths_per_function = entry.max_return_paths_per_function.max(ext_max_returns);
+                entry.long_parameter_function_count += file_long_parameter_functions.len();
+                
+                long_parameter_functions.extend(file_long_parameter_functions);
+                complex_functions.extend(self.find_complex_functions(&functions, file_path));
+                
+                // Trimmed as it grows, so large projects don't hold every function
+                if self.top_functions > 0 {
+                    top_functions.extend(self.quality_calculator.create_function_complexity_details(&functions, file_path));
+                    if top_functions.len() > self.top_functions * 2 {
+                        top_functions = FunctionComplexityDetail::most_complex(top_functions, self.top_functions);
+                    }
+                }
             }
+            
+            all_functions.extend(functions);
         }
         
         // Calculate aggregate statistics
@@ -210,62 +353,26 @@ impl ComplexityCalculator {
         let average_parameters_per_function = if total_functions > 0 { total_parameters as f64 / total_functions as f64 } else { 0.0 };
         let max_parameters_per_function = all_functions.iter().map(|f| f.parameter_count).max().unwrap_or(0);
         
-        // Calculate maintainability index for the project
-        let maintainability_index = if total_functions > 0 {
-            let avg_complexity = total_complexity / total_functions as f64;
-            let avg_length = total_function_lines as f64 / total_functions as f64;
-            let avg_cognitive = cognitive_complexity;
-            let avg_params = average_parameters_per_function;
-            
-            // Simplified maintainability calculation
-            let length_score = (50.0 - avg_length).max(0.0);
-            let complexity_score = (30.0 - avg_complexity * 2.0).max(0.0);
-            let cognitive_score = (30.0 - avg_cognitive * 2.0).max(0.0);
-            let param_score = (20.0 - avg_params * 3.0).max(0.0);
-            
-            let base_score = (length_score + complexity_score + cognitive_score + param_score).min(100.0).max(0.0);
-            
-            // Apply file length penalty based on project file size distribution
-            let large_files_count = individual_files.iter()
-                .filter(|(_, stats)| stats.total_lines > 500)
-                .count();
-            let very_large_files_count = individual_files.iter()
-                .filter(|(_, stats)| stats.total_lines > 1000)
-                .count();
-            let extremely_large_files_count = individual_files.iter()
-                .filter(|(_, stats)| stats.total_lines > 2000)
-                .count();
-            
-            let total_files = individual_files.len().max(1);
-            let large_file_ratio = large_files_count as f64 / total_files as f64;
-            let very_large_file_ratio = very_large_files_count as f64 / total_files as f64;
-            let extremely_large_file_ratio = extremely_large_files_count as f64 / total_files as f64;
-            
-            // Progressive penalty based on proportion of large files
-            let file_size_penalty = (large_file_ratio * 10.0) + 
-                                   (very_large_file_ratio * 15.0) + 
-                                   (extremely_large_file_ratio * 20.0);
-            
-            (base_score - file_size_penalty.min(35.0)).max(0.0)
-        } else {
-            100.0
-        };
+        let total_return_paths = all_functions.iter().map(|f| f.return_path_count).sum::<usize>();
+        let average_return_paths_per_function = if total_functions > 0 { total_return_paths as f64 / total_functions as f64 } else { 0.0 };
+        let max_return_paths_per_function = all_functions.iter().map(|f| f.return_path_count).max().unwrap_or(0);
+        
+        // Files weigh by their code lines, as a file twice as long takes twice the upkeep
+        let maintainability_index = if maintainability_lines > 0 { weighted_maintainability / maintainability_lines as f64 } else { 100.0 };
+        for (extension, entry) in complexity_by_extension.iter_mut() {
+            if let Some(&(weighted, lines)) = maintainability_by_extension.get(extension) {
+                entry.maintainability_index = if lines > 0 { weighted / lines as f64 } else { 100.0 };
+            }
+        }
         
         let complexity_distribution = self.calculate_complexity_distribution(&all_functions);
         let structure_distribution = self.calculate_structure_distribution(&all_structures);
         
         let total_structures = all_structures.len();
-        let methods_per_class = if total_classes > 0 {
-            all_structures.iter()
-                .filter(|s| s.structure_type == StructureType::Class)
-                .map(|s| s.methods.len())
-                .sum::<usize>() as f64 / total_classes as f64
-        } else {
-            0.0
-        };
+        let methods_per_class = if total_classes > 0 { total_class_methods as f64 / total_classes as f64 } else { 0.0 };
         
         // Calculate quality metrics for the project
-        let quality_metrics = self.quality_calculator.calculate_project_quality_metrics(&all_functions, code_stats, &all_structures);
+        let quality_metrics = self.quality_calculator.calculate_project_quality_metrics(&all_functions, code_stats, &analyzed_stats, &all_structures, maintainability_index);
         
         Ok(ComplexityStats {
             function_count: total_functions,
@@ -285,16 +392,54 @@ impl ComplexityCalculator {
             max_nesting_depth,
             average_nesting_depth: if total_functions > 0 { total_nesting_depth / total_functions as f64 } else { 0.0 },
             methods_per_class,
+            max_methods_per_class: max_class_methods,
             average_parameters_per_function,
             max_parameters_per_function,
+            average_return_paths_per_function,
+            max_return_paths_per_function,
             complexity_by_extension,
             complexity_distribution,
             structure_distribution,
-            function_complexity_details: Vec::new(), // Will be populated by calling code if needed
+            function_complexity_details: FunctionComplexityDetail::most_complex(top_functions, self.top_functions),
+            long_parameter_functions,
+            complex_functions,
+            function_thresholds: self.thresholds,
+            function_length_distribution: self.calculate_function_length_distribution(&all_functions),
             quality_metrics,
+            worst_files: FileQuality::worst(worst_files, self.worst_files),
         })
     }
 
+    /// Collect functions whose parameter count exceeds the long parameter list threshold
+    fn find_long_parameter_functions(&self, functions: &[FunctionInfo], file_path: &str) -> Vec<LongParameterListFinding> {
+        let extension = Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("unknown")
+            .to_lowercase();
+        
+        functions.iter()
+            .filter(|f| f.parameter_count > self.thresholds.max_parameters)
+            .map(|f| LongParameterListFinding {
+                name: f.name.clone(),
+                file_path: file_path.to_string(),
+                extension: extension.clone(),
+                start_line: f.start_line,
+                parameter_count: f.parameter_count,
+            })
+            .collect()
+    }
+
+    /// Collect functions that exceed the cyclomatic, cognitive, nesting or length thresholds
+    fn find_complex_functions(&self, functions: &[FunctionInfo], file_path: &str) -> Vec<FunctionComplexityDetail> {
+        let flagged: Vec<FunctionInfo> = functions.iter()
+            .filter(|f| self.thresholds.is_exceeded_by(f))
+            .cloned()
+            .collect();
+        
+        self.quality_calculator.create_function_complexity_details(&flagged, file_path)
+    }
+
     /// Calculate complexity distribution
     fn calculate_complexity_distribution(&self, functions: &[FunctionInfo]) -> ComplexityDistribution {
         let mut distribution = ComplexityDistribution {
@@ -318,6 +463,12 @@ impl ComplexityCalculator {
         distribution
     }
 
+    /// Summarize how function lengths are spread
+    fn calculate_function_length_distribution(&self, functions: &[FunctionInfo]) -> FunctionLengthDistribution {
+        let lengths: Vec<usize> = functions.iter().map(|f| f.line_count).collect();
+        FunctionLengthDistribution::from_lengths(&lengths)
+    }
+
     /// Calculate structure distribution
     fn calculate_structure_distribution(&self, structures: &[StructureInfo]) -> StructureDistribution {
         StructureDistribution {
@@ -330,41 +481,11 @@ impl ComplexityCalculator {
         }
     }
 
-    /// Calculate maintainability index (simplified version)
-    fn calculate_maintainability_index(&self, functions: &[FunctionInfo], file_stats: &FileStats) -> f64 {
-        if functions.is_empty() {
-            return 100.0; // Perfect score for empty files
-        }
-
-        let mut total_score = 0.0;
-        
-        for func in functions {
-            // Simplified maintainability calculation based on:
-            // - Function length (shorter is better)
-            // - Cyclomatic complexity (lower is better)
-            // - Cognitive complexity (lower is better)
-            // - Parameter count (fewer is better)
-            
-            let length_score = (50.0 - func.line_count as f64).max(0.0);
-            let cyclomatic_score = (30.0 - func.cyclomatic_complexity as f64 * 2.0).max(0.0);
-            let cognitive_score = (30.0 - func.cognitive_complexity as f64 * 2.0).max(0.0);
-            let param_score = (20.0 - func.parameter_count as f64 * 3.0).max(0.0);
-            
-            total_score += length_score + cyclomatic_score + cognitive_score + param_score;
-        }
-        
-        let base_score = (total_score / functions.len() as f64).min(100.0).max(0.0);
-        
-        // Apply file length penalty - files over 500 lines are considered less maintainable
-        let file_length_penalty = if file_stats.total_lines > 500 {
-            // Progressive penalty: 0.5 points per 100 lines over 500, capped at 25 points
-            let excess_lines = file_stats.total_lines - 500;
-            ((excess_lines as f64 / 100.0) * 0.5).min(25.0)
-        } else {
-            0.0
-        };
-        
-        (base_score - file_length_penalty).max(0.0)
+    /// Maintainability index of one file, from its Halstead volume, the summed
+    /// cyclomatic complexity of its functions and its code lines
+    fn calculate_maintainability_index(&self, analysis: &FileAnalysis, file_stats: &FileStats) -> f64 {
+        let cyclomatic_complexity = analysis.functions.iter().map(|f| f.cyclomatic_complexity).sum::<usize>().max(1);
+        maintainability_index(analysis.halstead.volume(), cyclomatic_complexity, file_stats.code_lines)
     }
 
     /// Get complexity level description
@@ -394,4 +515,114 @@ impl Default for ComplexityCalculator {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+} 
+
+fn extension_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("unknown")
+        .to_lowercase()
+}
+
+/// Methods of each class among `structures`. Most analyzers leave a class's
+/// methods out of it, so they are also found as the functions declared in its
+/// lines, other than those nested in one of them.
+fn class_method_counts(structures: &[StructureInfo], functions: &[FunctionInfo]) -> Vec<usize> {
+    structures.iter()
+        .filter(|s| s.structure_type == StructureType::Class)
+        .map(|class| {
+            let inside: Vec<_> = functions.iter().filter(|f| f.start_line > class.start_line && f.end_line <= class.end_line).collect();
+            let declared = inside.iter()
+                .filter(|f| !inside.iter().any(|outer| outer.start_line < f.start_line && f.end_line <= outer.end_line))
+                .count();
+            declared.max(class.methods.len())
+        })
+        .collect()
+}
+
+/// Move a file's lines out of the analyzed totals of its own extension and,
+/// unless it is skipped, into those of the extension it is analyzed as
+fn move_analyzed_lines(analyzed: &mut CodeStats, stats: &FileStats, own_extension: &str, extension: Option<&str>) {
+    if let Some((file_count, totals)) = analyzed.stats_by_extension.get_mut(own_extension) {
+        *file_count = file_count.saturating_sub(1);
+        *totals = totals.saturating_sub(stats);
+        analyzed.total_files = analyzed.total_files.saturating_sub(1);
+        analyzed.total_lines = analyzed.total_lines.saturating_sub(stats.total_lines);
+        analyzed.total_code_lines = analyzed.total_code_lines.saturating_sub(stats.code_lines);
+        analyzed.total_comment_lines = analyzed.total_comment_lines.saturating_sub(stats.comment_lines);
+        analyzed.total_blank_lines = analyzed.total_blank_lines.saturating_sub(stats.blank_lines);
+        analyzed.total_doc_lines = analyzed.total_doc_lines.saturating_sub(stats.doc_lines);
+        analyzed.total_size = analyzed.total_size.saturating_sub(stats.file_size);
+    }
+    let Some(extension) = extension else {
+        return;
+    };
+    let (file_count, totals) = analyzed.stats_by_extension.entry(extension.to_string()).or_insert((0, FileStats::default()));
+    *file_count += 1;
+    totals.total_lines += stats.total_lines;
+    totals.code_lines += stats.code_lines;
+    totals.comment_lines += stats.comment_lines;
+    totals.blank_lines += stats.blank_lines;
+    totals.doc_lines += stats.doc_lines;
+    totals.file_size += stats.file_size;
+    analyzed.total_files += 1;
+    analyzed.total_lines += stats.total_lines;
+    analyzed.total_code_lines += stats.code_lines;
+    analyzed.total_comment_lines += stats.comment_lines;
+    analyzed.total_blank_lines += stats.blank_lines;
+    analyzed.total_doc_lines += stats.doc_lines;
+    analyzed.total_size += stats.file_size;
+}
+
+/// Totals of the extensions whose language has a complexity analyzer
+fn analyzed_code_stats(code_stats: &CodeStats) -> CodeStats {
+    let mut analyzed = CodeStats::default();
+    for (extension, (file_count, stats)) in &code_stats.stats_by_extension {
+        if !has_language_analyzer(extension) {
+            continue;
+        }
+        analyzed.total_files += file_count;
+        analyzed.total_lines += stats.total_lines;
+        analyzed.total_code_lines += stats.code_lines;
+        analyzed.total_comment_lines += stats.comment_lines;
+        analyzed.total_blank_lines += stats.blank_lines;
+        analyzed.total_doc_lines += stats.doc_lines;
+        analyzed.total_size += stats.file_size;
+        analyzed.stats_by_extension.insert(extension.clone(), (*file_count, stats.clone()));
+    }
+    analyzed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::counter::CodeCounter;
+
+    #[test]
+    fn test_worst_files_trimming_matches_full_sort() {
+        let dir = tempfile::tempdir().unwrap();
+        let counter = CodeCounter::new();
+        // Branches per file; the two files with 8 tie and are told apart by path
+        let mut individual_files = Vec::new();
+        for (name, branches) in [("a.py", 0), ("b.py", 8), ("c.py", 3), ("d.py", 5), ("e.py", 1), ("f.py", 8), ("g.py", 2)] {
+            let branches: String = (0..branches).map(|i| format!("    if x == {}:\n        return {}\n", i, i)).collect();
+            let path = dir.path().join(name);
+            std::fs::write(&path, format!("def pick(x):\n{}    return -1\n", branches)).unwrap();
+            individual_files.push((path.to_string_lossy().to_string(), counter.count_file(&path).unwrap()));
+        }
+        let code_stats = counter.aggregate_stats(individual_files.iter().map(|(_, stats)| ("py".to_string(), stats.clone())).collect());
+
+        let worst_files = |count: usize| -> Vec<(String, u64)> {
+            let stats = ComplexityCalculator::new().with_worst_files(count).calculate_project_complexity_stats(&code_stats, &individual_files).unwrap();
+            stats.worst_files.into_iter().map(|file| (file.file_path, file.score.to_bits())).collect()
+        };
+        // Seven files never reach the trimming threshold of twice the count
+        let all = worst_files(individual_files.len());
+        assert_eq!(all.len(), 7);
+        assert!(all[0].0.ends_with("b.py") && all[1].0.ends_with("f.py"));
+        for count in 1..=3 {
+            assert_eq!(worst_files(count), all[..count]);
+        }
+    }
+}
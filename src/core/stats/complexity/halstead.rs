@@ -0,0 +1,185 @@
+use super::languages::blocks::BraceScanner;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Operators of several characters, longest first so `===` isn't read as `==` and `=`
+const COMPOUND_OPERATORS: &[&str] = &[
+    "<<=", ">>=", "**=", "//=", "===", "!==", "<=>", ">>>", "...", "..=",
+    "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    "<<", ">>", "->", "=>", "::", "**", "//", "??", "?.", ":=", "..", "<-", "|>",
+];
+
+/// Keywords of the analyzed languages. They are operators: they act on
+/// operands, as `if` acts on its condition.
+const KEYWORDS: &[&str] = &[
+    "abstract", "and", "as", "async", "await", "begin", "break", "case", "catch", "class", "const", "continue",
+    "def", "default", "defer", "del", "delete", "do", "elif", "else", "elsif", "end", "enum", "except", "export",
+    "extends", "extern", "final", "finally", "fn", "for", "foreach", "from", "func", "function", "go", "goto",
+    "if", "impl", "implements", "import", "in", "instanceof", "interface", "is", "lambda", "let", "local",
+    "loop", "match", "mod", "module", "move", "mut", "namespace", "new", "not", "or", "package", "private",
+    "protected", "pub", "public", "raise", "rescue", "return", "select", "static", "struct", "switch", "then",
+    "throw", "throws", "trait", "try", "type", "typeof", "unless", "until", "use", "using", "val", "var",
+    "when", "where", "while", "with", "yield",
+];
+
+/// Halstead's counts of the operators and operands of a file, from which its
+/// volume follows. Keywords and punctuation are operators; names, numbers and
+/// literals are operands. A pair of brackets is one operator.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HalsteadMetrics {
+    /// Different operators (n1)
+    pub distinct_operators: usize,
+    /// Different operands (n2)
+    pub distinct_operands: usize,
+    /// Occurrences of operators (N1)
+    pub total_operators: usize,
+    /// Occurrences of operands (N2)
+    pub total_operands: usize,
+}
+
+impl HalsteadMetrics {
+    /// Count the operators and operands of `lines`, leaving out what `scanner` finds to be comments
+    pub fn measure(lines: &[String], mut scanner: BraceScanner) -> Self {
+        let mut operators = HashSet::new();
+        let mut operands = HashSet::new();
+        let mut metrics = Self::default();
+
+        for line in lines {
+            for token in tokenize(&scanner.source(line), scanner.has_single_quote_strings()) {
+                match token {
+                    Token::Operator(operator) => {
+                        metrics.total_operators += 1;
+                        operators.insert(operator);
+                    }
+                    Token::Operand(operand) => {
+                        metrics.total_operands += 1;
+                        operands.insert(operand);
+                    }
+                }
+            }
+        }
+
+        metrics.distinct_operators = operators.len();
+        metrics.distinct_operands = operands.len();
+        metrics
+    }
+
+    /// Different operators and operands (n = n1 + n2)
+    pub fn vocabulary(&self) -> usize {
+        self.distinct_operators + self.distinct_operands
+    }
+
+    /// Occurrences of operators and operands (N = N1 + N2)
+    pub fn length(&self) -> usize {
+        self.total_operators + self.total_operands
+    }
+
+    /// Bits needed to write the code down: `N × log2(n)`
+    pub fn volume(&self) -> f64 {
+        let vocabulary = self.vocabulary();
+        if vocabulary < 2 {
+            return 0.0;
+        }
+        self.length() as f64 * (vocabulary as f64).log2()
+    }
+}
+
+/// Maintainability index by the Microsoft variant of the SEI formula, on a
+/// scale of 0 to 100: `max(0, (171 - 5.2 ln(V) - 0.23 G - 16.2 ln(LOC)) * 100 / 171)`,
+/// with V the Halstead volume, G the cyclomatic complexity and LOC the code lines.
+/// Code without lines is fully maintainable.
+pub fn maintainability_index(volume: f64, cyclomatic_complexity: usize, code_lines: usize) -> f64 {
+    if code_lines == 0 {
+        return 100.0;
+    }
+    let index = 171.0 - 5.2 * volume.max(1.0).ln() - 0.23 * cyclomatic_complexity as f64 - 16.2 * (code_lines as f64).ln();
+    (index * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Operator(String),
+    Operand(String),
+}
+
+/// Operators and operands of a line of code with its comments left out. Unless
+/// `'` quotes strings, it only quotes character literals such as `'x'`; a
+/// lifetime or label such as `'a` is an operand.
+fn tokenize(code: &str, single_quote_strings: bool) -> Vec<Token> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let lifetime = c == '\'' && !single_quote_strings && chars.get(i + 1) != Some(&'\\') && chars.get(i + 2) != Some(&'\'');
+        if c.is_whitespace() || matches!(c, ')' | ']' | '}') {
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' || c == '$' || lifetime {
+            let start = i;
+            i += usize::from(lifetime);
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$' || (chars[i] == '.' && chars[start].is_ascii_digit())) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(if KEYWORDS.contains(&word.as_str()) { Token::Operator(word) } else { Token::Operand(word) });
+        } else if matches!(c, '"' | '\'' | '`') {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(chars.len());
+            tokens.push(Token::Operand(chars[start..i].iter().collect()));
+        } else if let Some(operator) = COMPOUND_OPERATORS.iter().find(|operator| chars[i..].starts_with(&operator.chars().collect::<Vec<_>>())) {
+            tokens.push(Token::Operator(operator.to_string()));
+            i += operator.len();
+        } else {
+            tokens.push(Token::Operator(c.to_string()));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(source: &str) -> Vec<String> {
+        source.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_operators_and_operands_counted() {
+        let source = "fn area(width: u32, height: u32) -> u32 {\n    // width times height\n    width * height\n}\n";
+        let metrics = HalsteadMetrics::measure(&lines(source), BraceScanner::new());
+
+        // fn ( : , : -> { *
+        assert_eq!(metrics.total_operators, 8);
+        assert_eq!(metrics.distinct_operators, 7);
+        // area width u32 height u32 u32 width height
+        assert_eq!(metrics.total_operands, 8);
+        assert_eq!(metrics.distinct_operands, 4);
+        assert!((metrics.volume() - 16.0 * 11f64.log2()).abs() < 1e-9);
+
+        // Lifetimes and character literals are operands: first 'a a 'a str c char char 'x'
+        let metrics = HalsteadMetrics::measure(&lines("fn first<'a>(a: &'a str, c: char) -> char { 'x' }"), BraceScanner::new());
+        assert_eq!(metrics.total_operands, 9);
+
+        // Literals are operands, with what they contain
+        let metrics = HalsteadMetrics::measure(&lines("print('a # b', x == 1.5)"), BraceScanner::new().with_line_comments(&["#"]).with_single_quote_strings());
+        assert_eq!(metrics.total_operands, 4);
+        assert_eq!(metrics.total_operators, 3);
+    }
+
+    #[test]
+    fn test_maintainability_index() {
+        assert_eq!(maintainability_index(0.0, 0, 0), 100.0);
+        // 171 - 5.2 ln(1000) - 0.23 * 10 - 16.2 ln(100) = 58.18, scaled by 100 / 171
+        assert!((maintainability_index(1000.0, 10, 100) - 34.02).abs() < 0.01);
+        // Huge, tangled files bottom out at zero
+        assert_eq!(maintainability_index(1e9, 2000, 100_000), 0.0);
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, Visibility};
+use super::blocks::BraceScanner;
+use super::LanguageAnalyzer;
+
+/// Condition suffixes of ARM (`bne`, `b.ge`) and RISC-V (`bltu`, `beqz`) conditional branches
+const BRANCH_CONDITIONS: &[&str] = &[
+    "eq", "ne", "cs", "cc", "hs", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le",
+    "ltu", "geu", "gtu", "leu", "eqz", "nez", "lez", "gez", "ltz", "gtz",
+];
+
+/// Assembly complexity analyzer for GNU as, NASM and MASM sources. Functions are
+/// the symbols a file exports (`.globl`, `global`, `.type name, @function`) or
+/// MASM `PROC`s, running to the next function; conditional branches are their decisions.
+/// Files that export nothing and have no `PROC`s have every non-local label as a function.
+pub struct AssemblyAnalyzer;
+
+impl AssemblyAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The line without its `;`, `//` or `#` comment
+    fn code<'a>(&self, line: &'a str) -> &'a str {
+        let mut end = line.len();
+        for marker in [";", "//"] {
+            if let Some(index) = line.find(marker) {
+                end = end.min(index);
+            }
+        }
+        // `#` starts GNU as comments, but also ARM immediates (`#4`) after a comma or space
+        if line.trim_start().starts_with('#') {
+            return "";
+        }
+        if let Some(index) = line.find(" #").filter(|index| !line[..*index].trim_end().ends_with(',')) {
+            end = end.min(index);
+        }
+        &line[..end]
+    }
+
+    /// Name of a label defined at the start of the line (`sum:`); local labels
+    /// (`.L3:`, `1:`) are jump targets inside a function
+    fn label<'a>(&self, line: &'a str) -> Option<&'a str> {
+        if line.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let (name, _) = self.code(line).split_once(':')?;
+        let local = name.starts_with('.') || name.starts_with(|c: char| c.is_ascii_digit());
+        let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.' | '@'));
+        (valid && !local).then_some(name)
+    }
+
+    /// Name of a MASM procedure (`sum PROC`)
+    fn procedure<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let mut words = self.code(line).split_whitespace();
+        let name = words.next()?;
+        words.next().filter(|word| word.eq_ignore_ascii_case("proc")).map(|_| name)
+    }
+
+    /// Symbols exported or typed as functions anywhere in the file
+    fn exported_symbols<'a>(&self, lines: &'a [String]) -> HashSet<&'a str> {
+        let mut symbols = HashSet::new();
+        for line in lines {
+            let mut words = self.code(line).split(|c: char| c.is_whitespace() || c == ',').filter(|word| !word.is_empty());
+            match words.next().map(str::to_lowercase).as_deref() {
+                Some(".globl" | ".global" | "global" | ".func" | "public") => symbols.extend(words),
+                Some(".type") => {
+                    if let (Some(name), Some(kind)) = (words.next(), words.next()) {
+                        if kind.ends_with("function") {
+                            symbols.insert(name);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        symbols
+    }
+
+    /// Whether the instruction on the line is a conditional branch: x86 `jcc`
+    /// and `loop`, ARM `bcc`/`b.cc`/`cbz`/`tbnz`, RISC-V `beq`/`bnez`
+    fn is_conditional_branch(&self, line: &str) -> bool {
+        let code = self.code(line);
+        // An instruction may follow a label on the same line
+        let instruction = code.rsplit_once(':').map_or(code, |(_, rest)| rest);
+        let Some(mnemonic) = instruction.split_whitespace().next().map(str::to_lowercase) else {
+            return false;
+        };
+
+        if let Some(condition) = mnemonic.strip_prefix('j') {
+            return condition != "mp" && !condition.is_empty();
+        }
+        if mnemonic.starts_with("loop") || matches!(mnemonic.as_str(), "cbz" | "cbnz" | "tbz" | "tbnz") {
+            return true;
+        }
+        mnemonic
+            .strip_prefix('b')
+            .map(|condition| condition.strip_prefix('.').unwrap_or(condition))
+            .is_some_and(|condition| BRANCH_CONDITIONS.contains(&condition))
+    }
+}
+
+impl LanguageAnalyzer for AssemblyAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let exported = self.exported_symbols(lines);
+        // Labels inside MASM procedures are jump targets
+        let has_procedures = lines.iter().any(|line| self.procedure(line).is_some());
+        let starts: Vec<(usize, &str)> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                if let Some(name) = self.procedure(line) {
+                    return Some((index, name));
+                }
+                self.label(line).filter(|name| !has_procedures && (exported.is_empty() || exported.contains(name))).map(|name| (index, name))
+            })
+            .collect();
+
+        let mut functions = Vec::new();
+        for (position, &(start, name)) in starts.iter().enumerate() {
+            let next_start = starts.get(position + 1).map_or(lines.len(), |(next, _)| *next);
+            // MASM procedures end at their ENDP, others at the last instruction before the next function
+            let endp = (start + 1..next_start).find(|index| {
+                let mut words = self.code(&lines[*index]).split_whitespace();
+                words.next() == Some(name) && words.next().is_some_and(|word| word.eq_ignore_ascii_case("endp"))
+            });
+            let end_line = endp.unwrap_or_else(|| {
+                (start..next_start).rev().find(|index| !self.code(&lines[*index]).trim().is_empty()).unwrap_or(start)
+            });
+            let complexity = 1 + lines[start..=end_line].iter().filter(|line| self.is_conditional_branch(line)).count();
+
+            functions.push(FunctionInfo {
+                name: name.to_string(),
+                line_count: end_line - start + 1,
+                cyclomatic_complexity: complexity,
+                cognitive_complexity: complexity,
+                nesting_depth: 0,
+                parameter_count: 0,
+                return_path_count: 1,
+                start_line: start + 1,
+                end_line: end_line + 1,
+                is_method: false,
+                parent_class: None,
+                local_variable_count: 0,
+                has_recursion: false,
+                has_exception_handling: false,
+                visibility: if exported.contains(name) { Visibility::Public } else { Visibility::Private },
+            });
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, _lines: &[String]) -> Result<Vec<StructureInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Assembly"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["s", "asm"]
+    }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&[";", "#"])
+    }
+}
+
+impl Default for AssemblyAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
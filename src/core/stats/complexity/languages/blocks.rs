@@ -0,0 +1,448 @@
+/// Keywords that open a parenthesized block without declaring a function
+const CONTROL_KEYWORDS: &[&str] = &[
+    "if", "else", "elif", "elseif", "for", "foreach", "while", "do", "switch", "match", "when",
+    "catch", "try", "using", "lock", "synchronized", "return", "throw", "new", "guard", "defer", "unless",
+];
+
+/// Whether a line starts a control-flow statement such as `if (x) {` or `} else if (y) {`,
+/// which declaration heuristics based on parentheses and braces would take for a function.
+/// A label in front of the statement, as in `outer: for (...) {`, is skipped.
+pub fn is_control_statement(line: &str) -> bool {
+    let statement = line.trim_start_matches(|c: char| c == '}' || c.is_whitespace());
+    let starts_control = |statement: &str| CONTROL_KEYWORDS.contains(&statement.split(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap_or(""));
+    if starts_control(statement) {
+        return true;
+    }
+
+    let label = statement.strip_prefix('\'').unwrap_or(statement);
+    let label_end = label.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(label.len());
+    let rest = &label[label_end..];
+    label_end > 0 && rest.starts_with(':') && !rest.starts_with("::") && starts_control(rest[1..].trim_start())
+}
+
+/// Occurrences of `keywords` in `line` as whole words, so `if` isn't found in `elif` or `modified`
+pub fn count_keywords(line: &str, keywords: &[&str]) -> usize {
+    line.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| keywords.contains(word))
+        .count()
+}
+
+/// Whether a declaration line leaves its signature or expression unfinished: parentheses
+/// are still open or the line ends with an operator, comma or opening bracket.
+/// Used where a declaration may have no braced body, such as Kotlin's `fun area() = w * h`.
+pub fn continues_on_next_line(line: &str) -> bool {
+    let code = line.split("//").next().unwrap_or(line).trim_end();
+    let open_parens = code.matches('(').count() + code.matches('[').count();
+    let close_parens = code.matches(')').count() + code.matches(']').count();
+
+    open_parens > close_parens
+        || ["(", "[", ",", "=", ":", "->", "=>", "+", "-", "*", "/", "&&", "||", "?", "."]
+            .iter()
+            .any(|suffix| code.ends_with(suffix))
+}
+
+/// Counts block braces line by line, ignoring braces inside string literals,
+/// character literals and comments. Block comments may span several lines.
+#[derive(Debug, Clone)]
+pub struct BraceScanner {
+    line_comments: &'static [&'static str],
+    block_comments: bool,
+    /// `'...'` is a string (JavaScript, PHP, Perl, R) rather than a character literal
+    single_quote_strings: bool,
+    in_block_comment: bool,
+}
+
+/// Braces found on one line, outside literals and comments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BraceCount {
+    pub open: usize,
+    pub close: usize,
+    /// The code on the line ends with `;`, e.g. a declaration without a body
+    pub ends_statement: bool,
+}
+
+impl BraceScanner {
+    /// C-family syntax: `//` and `/* */` comments, `'x'` character literals
+    pub fn new() -> Self {
+        Self {
+            line_comments: &["//"],
+            block_comments: true,
+            single_quote_strings: false,
+            in_block_comment: false,
+        }
+    }
+
+    pub fn with_line_comments(mut self, markers: &'static [&'static str]) -> Self {
+        self.line_comments = markers;
+        self
+    }
+
+    pub fn with_block_comments(mut self, enabled: bool) -> Self {
+        self.block_comments = enabled;
+        self
+    }
+
+    pub fn with_single_quote_strings(mut self) -> Self {
+        self.single_quote_strings = true;
+        self
+    }
+
+    pub fn scan(&mut self, line: &str) -> BraceCount {
+        let code = self.code(line);
+        BraceCount {
+            open: code.matches('{').count(),
+            close: code.matches('}').count(),
+            ends_statement: code.trim_end().ends_with(';'),
+        }
+    }
+
+    /// The code of a line with comments left out and string and character
+    /// literals emptied, so `"}"` becomes `""`
+    pub fn code(&mut self, line: &str) -> String {
+        self.strip_comments(line, false)
+    }
+
+    /// The code of a line with comments left out and literals kept whole
+    pub fn source(&mut self, line: &str) -> String {
+        self.strip_comments(line, true)
+    }
+
+    fn strip_comments(&mut self, line: &str, keep_literals: bool) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut code = String::with_capacity(line.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            if self.in_block_comment {
+                if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    self.in_block_comment = false;
+                    i += 1;
+                }
+                i += 1;
+                continue;
+            }
+
+            let c = chars[i];
+            if self.block_comments && c == '/' && chars.get(i + 1) == Some(&'*') {
+                self.in_block_comment = true;
+                i += 2;
+                continue;
+            }
+            if self.line_comments.iter().any(|marker| Self::starts_with(&chars[i..], marker)) {
+                break;
+            }
+
+            let end = match c {
+                '"' | '`' => Self::skip_string(&chars, i, c),
+                '\'' if self.single_quote_strings => Self::skip_string(&chars, i, c),
+                '\'' => Self::skip_char_literal(&chars, i),
+                _ => i,
+            };
+            if keep_literals {
+                code.extend(&chars[i..(end + 1).min(chars.len())]);
+            } else {
+                code.push(c);
+                if end > i {
+                    code.push(c);
+                }
+            }
+            i = end + 1;
+        }
+
+        code
+    }
+
+    /// Whether `'...'` is a string rather than a character literal
+    pub fn has_single_quote_strings(&self) -> bool {
+        self.single_quote_strings
+    }
+
+    /// Whether a block comment is still open after the last line
+    pub fn in_block_comment(&self) -> bool {
+        self.in_block_comment
+    }
+
+    fn starts_with(chars: &[char], marker: &str) -> bool {
+        let len = marker.chars().count();
+        chars.len() >= len && chars[..len].iter().copied().eq(marker.chars())
+    }
+
+    /// Index of the closing quote, or the end of the line for an unterminated string
+    fn skip_string(chars: &[char], start: usize, quote: char) -> usize {
+        let mut i = start + 1;
+        while i < chars.len() {
+            match chars[i] {
+                '\\' => i += 1,
+                c if c == quote => return i,
+                _ => {}
+            }
+            i += 1;
+        }
+        chars.len()
+    }
+
+    /// Skip `'x'` and `'\n'`; a lone quote such as a Rust lifetime is left alone
+    fn skip_char_literal(chars: &[char], start: usize) -> usize {
+        if chars.get(start + 1) == Some(&'\\') {
+            if let Some(offset) = chars[start + 2..].iter().take(10).position(|c| *c == '\'') {
+                return start + 2 + offset;
+            }
+        } else if chars.get(start + 2) == Some(&'\'') {
+            return start + 2;
+        }
+        start
+    }
+}
+
+impl Default for BraceScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Follows one brace-delimited body from its declaration to the closing brace
+#[derive(Debug, Clone)]
+pub struct BlockTracker {
+    scanner: BraceScanner,
+    depth: usize,
+    max_depth: usize,
+    opened: bool,
+}
+
+impl BlockTracker {
+    pub fn new(scanner: BraceScanner) -> Self {
+        Self {
+            scanner,
+            depth: 0,
+            max_depth: 0,
+            opened: false,
+        }
+    }
+
+    /// Feed the next line, starting with the declaration line.
+    /// Returns true when the block ends on this line: its closing brace was reached,
+    /// or the declaration ended with `;` before any body was opened.
+    pub fn feed(&mut self, line: &str) -> bool {
+        let code = self.scanner.code(line);
+        for c in code.chars() {
+            match c {
+                '{' => {
+                    self.depth += 1;
+                    self.max_depth = self.max_depth.max(self.depth);
+                    self.opened = true;
+                }
+                '}' => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        if self.opened {
+            self.depth == 0
+        } else {
+            code.trim_end().ends_with(';')
+        }
+    }
+
+    /// Open braces inside the block after the last fed line
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Deepest nesting of braces so far, the body's own braces being level 1.
+    /// Counted brace by brace, so `if (a) { b(); }` on one line nests too.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Whether the body's opening brace has been seen
+    pub fn is_opened(&self) -> bool {
+        self.opened
+    }
+}
+
+/// Follows a keyword-delimited body, such as Lua's `function ... end`, counting block
+/// keywords outside strings, comments and square brackets (Julia's `a[end]`)
+#[derive(Debug, Clone)]
+pub struct KeywordBlockTracker {
+    openers: &'static [&'static str],
+    closers: &'static [&'static str],
+    line_comment: &'static str,
+    /// Openers that only open a block at the start of a statement, such as Ruby's
+    /// `if`, which doesn't in `return if done`. A `do` after one on its line
+    /// belongs to it, as in `while busy do`.
+    leading_openers: &'static [&'static str],
+    /// Words inside parentheses are left out too, as MATLAB's `x(end)`
+    skip_parentheses: bool,
+    depth: usize,
+    max_depth: usize,
+    opened: bool,
+}
+
+impl KeywordBlockTracker {
+    pub fn new(openers: &'static [&'static str], closers: &'static [&'static str], line_comment: &'static str) -> Self {
+        Self {
+            openers,
+            closers,
+            line_comment,
+            leading_openers: &[],
+            skip_parentheses: false,
+            depth: 0,
+            max_depth: 0,
+            opened: false,
+        }
+    }
+
+    pub fn with_leading_openers(mut self, openers: &'static [&'static str]) -> Self {
+        self.leading_openers = openers;
+        self
+    }
+
+    pub fn with_parentheses_skipped(mut self) -> Self {
+        self.skip_parentheses = true;
+        self
+    }
+
+    /// Feed the next line, starting with the declaration line.
+    /// Returns true when the block ends on this line. A declaration line that opens
+    /// no block, such as Elixir's `def add(a, b), do: a + b`, ends right away.
+    pub fn feed(&mut self, line: &str) -> bool {
+        let mut leading_opened = false;
+        for (word, starts_statement) in Self::code_words(line, self.line_comment, self.skip_parentheses) {
+            let leading = starts_statement && self.leading_openers.contains(&word);
+            if word == "do" && leading_opened {
+                continue;
+            }
+            if leading || self.openers.contains(&word) {
+                self.depth += 1;
+                self.max_depth = self.max_depth.max(self.depth);
+                self.opened = true;
+                leading_opened |= leading;
+            } else if self.closers.contains(&word) {
+                self.depth = self.depth.saturating_sub(1);
+            }
+        }
+
+        !self.opened || self.depth == 0
+    }
+
+    /// Blocks open inside the block after the last fed line, the block itself included
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Deepest nesting of blocks so far, the declaration's own block being level 1
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Identifiers outside literals, comments and brackets, each with whether it
+    /// starts a statement: it comes first on the line or after `=`, `;` or `(`.
+    /// `do:` keyword arguments are skipped.
+    fn code_words<'a>(line: &'a str, line_comment: &str, skip_parentheses: bool) -> Vec<(&'a str, bool)> {
+        let code = match line.find(line_comment) {
+            Some(comment) if !line_comment.is_empty() => &line[..comment],
+            _ => line,
+        };
+        let bytes = code.as_bytes();
+        let mut words = Vec::new();
+        let mut bracket_depth = 0usize;
+        let mut statement_start = true;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                quote @ (b'"' | b'\'') => {
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != quote {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 };
+                    }
+                    statement_start = false;
+                }
+                b'[' => bracket_depth += 1,
+                b']' => bracket_depth = bracket_depth.saturating_sub(1),
+                b'(' if skip_parentheses => bracket_depth += 1,
+                b')' if skip_parentheses => bracket_depth = bracket_depth.saturating_sub(1),
+                b'=' | b';' | b'(' => statement_start = true,
+                c if c.is_ascii_alphabetic() || c == b'_' => {
+                    let start = i;
+                    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                        i += 1;
+                    }
+                    let keyword_argument = bytes.get(i) == Some(&b':');
+                    let member = start > 0 && bytes[start - 1] == b'.';
+                    if bracket_depth == 0 && !keyword_argument && !member {
+                        words.push((&code[start..i], statement_start));
+                    }
+                    statement_start = false;
+                    continue;
+                }
+                c if !c.is_ascii_whitespace() => statement_start = false,
+                _ => {}
+            }
+            i += 1;
+        }
+
+        words
+    }
+}
+
+/// Follows the nesting of an indentation-delimited body, such as a Python
+/// function's. A line indented past the block around it opens a level; lines
+/// continuing an open bracket or a line ending in `\` keep the level of the
+/// line they continue, and comment-only lines are skipped.
+#[derive(Debug, Clone)]
+pub struct IndentTracker {
+    scanner: BraceScanner,
+    /// Indentation of the declaration and of each block open inside it
+    levels: Vec<usize>,
+    open_brackets: usize,
+    continued: bool,
+    max_depth: usize,
+}
+
+impl IndentTracker {
+    /// Tracker for the body of a declaration indented by `indent` columns
+    pub fn new(scanner: BraceScanner, indent: usize) -> Self {
+        Self {
+            scanner,
+            levels: vec![indent],
+            open_brackets: 0,
+            continued: false,
+            max_depth: 0,
+        }
+    }
+
+    /// Feed the next line of the body
+    pub fn feed(&mut self, line: &str) {
+        let code = self.scanner.code(line);
+        if !code.trim().is_empty() && !self.continues() {
+            let indent = line.len() - line.trim_start().len();
+            while self.levels.len() > 1 && self.levels.last().is_some_and(|&level| level > indent) {
+                self.levels.pop();
+            }
+            if self.levels.last().is_some_and(|&level| indent > level) {
+                self.levels.push(indent);
+            }
+            self.max_depth = self.max_depth.max(self.levels.len() - 1);
+        }
+
+        for c in code.chars() {
+            match c {
+                '(' | '[' | '{' => self.open_brackets += 1,
+                ')' | ']' | '}' => self.open_brackets = self.open_brackets.saturating_sub(1),
+                _ => {}
+            }
+        }
+        self.continued = code.trim_end().ends_with('\\');
+    }
+
+    /// Whether the next line continues the last one, inside brackets or after a `\\`
+    pub fn continues(&self) -> bool {
+        self.open_brackets > 0 || self.continued
+    }
+
+    /// Deepest nesting of blocks so far, the body being level 1
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+}
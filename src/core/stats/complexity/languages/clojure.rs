@@ -1,7 +1,14 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::BraceScanner;
 use super::LanguageAnalyzer;
 
+/// Forms that branch, loop or open a function, nesting the code inside them
+const CONTROL_FORMS: &[&str] = &[
+    "if", "if-not", "if-let", "if-some", "when", "when-not", "when-let", "when-some", "when-first",
+    "cond", "condp", "cond->", "case", "loop", "doseq", "dotimes", "for", "while", "try", "fn",
+];
+
 /// Clojure language complexity analyzer
 pub struct ClojureAnalyzer;
 
@@ -144,30 +151,37 @@ impl ClojureAnalyzer {
         complexity
     }
     
-    /// Find the end of a function definition
-    fn find_function_end(&self, lines: &[String], start_line: usize) -> usize {
-        let mut paren_depth = 0;
-        let mut in_function = false;
+    /// Find the end of a function definition and the deepest nesting of the
+    /// control forms in it, the body being level 1
+    fn find_function_end(&self, lines: &[String], start_line: usize) -> (usize, usize) {
+        let mut scanner = self.comment_scanner();
+        // Whether each open form is a control form
+        let mut forms: Vec<bool> = Vec::new();
+        let mut deepest = 0;
         
         for (i, line) in lines.iter().enumerate().skip(start_line) {
-            let trimmed = line.trim();
-            
-            if trimmed.contains("(defn ") || trimmed.contains("(defn- ") || trimmed.contains("(def ") {
-                in_function = true;
-            }
-            
-            if in_function {
-                // Count parentheses
-                paren_depth += trimmed.matches('(').count();
-                paren_depth = paren_depth.saturating_sub(trimmed.matches(')').count());
-                
-                if paren_depth == 0 {
-                    return i;
+            let code: Vec<char> = scanner.code(line).chars().collect();
+            for (at, &c) in code.iter().enumerate() {
+                match c {
+                    '(' | '[' | '{' => {
+                        let head: String = code[at + 1..].iter().take_while(|c| !c.is_whitespace() && !"()[]{}".contains(**c)).collect();
+                        // `#(...)` is an anonymous function
+                        let control = c == '(' && (CONTROL_FORMS.contains(&head.as_str()) || (at > 0 && code[at - 1] == '#'));
+                        forms.push(control);
+                        deepest = deepest.max(forms.iter().filter(|&&control| control).count());
+                    }
+                    ')' | ']' | '}' => {
+                        let closed = forms.pop().is_some();
+                        if closed && forms.is_empty() {
+                            return (i, deepest + 1);
+                        }
+                    }
+                    _ => {}
                 }
             }
         }
         
-        lines.len().saturating_sub(1)
+        (lines.len().saturating_sub(1), deepest + 1)
     }
     
     /// Determine visibility of a function
@@ -201,7 +215,8 @@ impl LanguageAnalyzer for ClojureAnalyzer {
         
         for (i, line) in lines.iter().enumerate() {
             if let Some(func_name) = self.extract_function_name(line) {
-                let end_line = self.find_function_end(lines, i);
+                let (end_line, nesting_depth) = self.find_function_end(lines, i);
+                let parameter_count = self.count_parameters(lines, i, &func_name);
                 let complexity = self.calculate_cyclomatic_complexity(lines, i, end_line);
                 let _visibility = self.determine_visibility(line);
                 
@@ -210,8 +225,8 @@ impl LanguageAnalyzer for ClojureAnalyzer {
                     line_count: end_line.saturating_sub(i).max(1),
                     cyclomatic_complexity: complexity,
                     cognitive_complexity: complexity,
-                    nesting_depth: 0,
-                    parameter_count: self.count_parameters(line),
+                    nesting_depth,
+                    parameter_count,
                     return_path_count: 1,
                     start_line: i + 1,
                     end_line: end_line + 1,
@@ -261,24 +276,42 @@ impl LanguageAnalyzer for ClojureAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["clj", "cljs", "cljc", "edn"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&[";"]).with_block_comments(false)
+    }
 }
 
 impl ClojureAnalyzer {
-    /// Count parameters in a function definition
-    fn count_parameters(&self, line: &str) -> usize {
-        // Look for parameter vector [param1 param2 ...]
-        if let Some(start) = line.find('[') {
-            if let Some(end) = line.find(']') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
+    /// Count parameters in the first vector after the name, which may be on a later
+    /// line: `(defn greet "Says hi" [name & {:keys [loud]}]` has 2. A map or vector
+    /// destructuring an argument is one parameter, and `&` only marks the rest.
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        let mut scanner = self.comment_scanner();
+        let code: String = lines[index..].iter().take(16).map(|line| scanner.code(line) + "\n").collect();
+        let after_name = code.find(name).map_or(0, |at| at + name.len());
+        let Some(open) = code[after_name..].find('[').map(|open| after_name + open) else {
+            return 0;
+        };
+        
+        let mut items = Vec::new();
+        let mut item = String::new();
+        let mut depth = 0usize;
+        for c in code[open + 1..].chars() {
+            match c {
+                ']' | '}' | ')' if depth == 0 => break,
+                '[' | '{' | '(' => depth += 1,
+                ']' | '}' | ')' => depth -= 1,
+                c if depth == 0 && (c.is_whitespace() || c == ',') => {
+                    items.push(std::mem::take(&mut item));
+                    continue;
                 }
-                // Count parameters separated by whitespace
-                return params.split_whitespace().count();
+                _ => {}
             }
+            item.push(c);
         }
-        
-        0
+        items.push(item);
+        items.iter().filter(|item| !item.is_empty() && *item != "&").count()
     }
     
     /// Find the end of a structure definition
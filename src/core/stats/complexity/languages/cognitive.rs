@@ -0,0 +1,399 @@
+use super::blocks::BraceScanner;
+
+/// Keywords and operators that make a function harder to follow, after
+/// SonarSource's cognitive complexity. Structures add one plus their nesting
+/// level, continuations such as `else` add one, and every run of the same
+/// boolean operator adds one.
+#[derive(Debug)]
+pub struct CognitiveSyntax {
+    /// Structures that add one plus their nesting level and nest their body: `if`, loops, `switch`, `catch`
+    pub structures: &'static [&'static str],
+    /// Continuations of a structure that add one whatever their nesting and nest their body: `else`, `elif`
+    pub continuations: &'static [&'static str],
+    /// Jumps that add one when they name a label, such as `continue outer`
+    pub jumps: &'static [&'static str],
+    /// First character of a label, when labels are marked (Rust's `'outer`)
+    pub label_prefix: Option<char>,
+    /// Boolean operators; a run of the same operator adds one, each change of operator one more
+    pub logical_operators: &'static [&'static str],
+    /// Arrows of lambdas, whose braced body nests what it contains
+    pub lambda_arrows: &'static [&'static str],
+    /// `a ? b : c` is a conditional expression, adding like a structure
+    pub ternary: bool,
+    /// Loop whose condition follows its body, so the `while` closing it adds nothing
+    pub do_loop: Option<&'static str>,
+    /// `||` starts a closure without parameters unless it follows an operand (Rust)
+    pub closure_pipes: bool,
+    /// An `if` between a match pattern and its `=>` is a guard, which adds nothing (Rust)
+    pub match_guards: bool,
+    /// Structures may have a single statement for a body, ended by `;` rather than braces
+    pub braceless_bodies: bool,
+    /// Blocks are marked by indentation and a trailing `:` rather than braces (Python)
+    pub indentation: bool,
+}
+
+pub const RUST: CognitiveSyntax = CognitiveSyntax {
+    structures: &["if", "match", "for", "while", "loop"],
+    continuations: &["else"],
+    jumps: &["break", "continue"],
+    label_prefix: Some('\''),
+    logical_operators: &["&&", "||"],
+    lambda_arrows: &[],
+    ternary: false,
+    do_loop: None,
+    closure_pipes: true,
+    match_guards: true,
+    braceless_bodies: false,
+    indentation: false,
+};
+
+pub const JAVASCRIPT: CognitiveSyntax = CognitiveSyntax {
+    structures: &["if", "switch", "for", "while", "do", "catch"],
+    continuations: &["else"],
+    jumps: &["break", "continue"],
+    label_prefix: None,
+    logical_operators: &["&&", "||", "??"],
+    lambda_arrows: &["=>"],
+    ternary: true,
+    do_loop: Some("do"),
+    closure_pipes: false,
+    match_guards: false,
+    braceless_bodies: true,
+    indentation: false,
+};
+
+pub const JAVA: CognitiveSyntax = CognitiveSyntax {
+    structures: &["if", "switch", "for", "while", "do", "catch"],
+    continuations: &["else"],
+    jumps: &["break", "continue"],
+    label_prefix: None,
+    logical_operators: &["&&", "||"],
+    lambda_arrows: &["->"],
+    ternary: true,
+    do_loop: Some("do"),
+    closure_pipes: false,
+    match_guards: false,
+    braceless_bodies: true,
+    indentation: false,
+};
+
+pub const GO: CognitiveSyntax = CognitiveSyntax {
+    structures: &["if", "switch", "select", "for"],
+    continuations: &["else"],
+    jumps: &["break", "continue", "goto"],
+    label_prefix: None,
+    logical_operators: &["&&", "||"],
+    lambda_arrows: &[],
+    ternary: false,
+    do_loop: None,
+    closure_pipes: false,
+    match_guards: false,
+    braceless_bodies: false,
+    indentation: false,
+};
+
+pub const PYTHON: CognitiveSyntax = CognitiveSyntax {
+    structures: &["if", "for", "while", "except"],
+    continuations: &["elif", "else"],
+    jumps: &[],
+    label_prefix: None,
+    logical_operators: &["and", "or"],
+    lambda_arrows: &[],
+    ternary: false,
+    do_loop: None,
+    closure_pipes: false,
+    match_guards: false,
+    braceless_bodies: false,
+    indentation: true,
+};
+
+/// Operators of two characters, kept whole so `?.` isn't taken for a ternary
+const COMPOUND_OPERATORS: &[&str] = &["&&", "||", "??", "?.", "?:", "=>", "->", "::", "=="];
+
+/// Keywords of nested functions in Python, which nest their body without adding
+const PYTHON_NESTED_FUNCTIONS: &[&str] = &["def", "class"];
+
+/// A block open around the current line
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// Opened by a structure, continuation or lambda, so it adds to the nesting level
+    nests: bool,
+    /// Body of a `do` loop, whose closing `while` adds nothing
+    do_loop: bool,
+    /// Indentation of the line opening the block (Python)
+    indent: usize,
+}
+
+/// Cognitive complexity of one function, fed the lines of its body in order,
+/// starting with the declaration for brace languages. Like SonarSource's, the
+/// count starts at zero: a function without branches is not hard to follow.
+#[derive(Debug, Clone)]
+pub struct CognitiveCounter {
+    syntax: &'static CognitiveSyntax,
+    scanner: BraceScanner,
+    function_name: String,
+    complexity: usize,
+    frames: Vec<Frame>,
+    /// Block the next `{` (or trailing `:`) opens
+    pending: Option<Frame>,
+    paren_depth: usize,
+    last_operator: Option<&'static str>,
+    /// The previous token was `else`, so an `if` right after it is `else if`
+    after_else: bool,
+    /// A `do` body just closed, so the next `while` is its condition
+    after_do: bool,
+    lines_fed: usize,
+    /// Closing delimiter of the Python docstring the lines are in
+    docstring: Option<&'static str>,
+}
+
+impl CognitiveCounter {
+    pub fn new(syntax: &'static CognitiveSyntax, scanner: BraceScanner, function_name: &str) -> Self {
+        Self {
+            syntax,
+            scanner,
+            function_name: function_name.to_string(),
+            complexity: 0,
+            frames: Vec::new(),
+            pending: None,
+            paren_depth: 0,
+            last_operator: None,
+            after_else: false,
+            after_do: false,
+            lines_fed: 0,
+            docstring: None,
+        }
+    }
+
+    pub fn complexity(&self) -> usize {
+        self.complexity
+    }
+
+    /// Feed the next line. Lines that start with `*` continue a block comment
+    /// whose opening line the analyzer skipped.
+    pub fn feed(&mut self, line: &str) {
+        if self.syntax.indentation {
+            self.feed_indented(line);
+        } else if self.scanner.in_block_comment() || !line.trim_start().starts_with('*') {
+            let code = self.scanner.code(line);
+            let declaration = self.lines_fed == 0;
+            self.feed_braced(&tokenize(&code), declaration);
+        }
+        self.lines_fed += 1;
+    }
+
+    fn nesting(&self) -> usize {
+        self.frames.iter().filter(|frame| frame.nests).count()
+    }
+
+    /// Adds one plus the nesting level, for a structure or conditional expression
+    fn add_structure(&mut self) {
+        self.complexity += 1 + self.nesting();
+        self.last_operator = None;
+    }
+
+    fn add_operator(&mut self, operator: &'static str) {
+        if self.last_operator != Some(operator) {
+            self.complexity += 1;
+        }
+        self.last_operator = Some(operator);
+    }
+
+    fn feed_braced(&mut self, tokens: &[String], declaration: bool) {
+        let switch_rule = tokens.first().is_some_and(|token| token == "case" || token == "default");
+
+        for (index, token) in tokens.iter().enumerate() {
+            let previous = index.checked_sub(1).map(|previous| tokens[previous].as_str());
+            let next = tokens.get(index + 1).map(String::as_str);
+            let after_else = std::mem::take(&mut self.after_else);
+            let after_do = std::mem::take(&mut self.after_do);
+
+            match token.as_str() {
+                "{" => {
+                    let frame = self.pending.take().unwrap_or(Frame { nests: false, do_loop: false, indent: 0 });
+                    self.frames.push(frame);
+                    self.last_operator = None;
+                }
+                "}" => {
+                    self.after_do = self.frames.pop().is_some_and(|frame| frame.do_loop);
+                    self.last_operator = None;
+                }
+                ";" => {
+                    if self.syntax.braceless_bodies && self.paren_depth == 0 {
+                        self.pending = None;
+                    }
+                    self.last_operator = None;
+                }
+                "(" => self.paren_depth += 1,
+                ")" => self.paren_depth = self.paren_depth.saturating_sub(1),
+                "," => self.last_operator = None,
+                "?" if self.syntax.ternary && !matches!(previous, Some("<" | ",")) => self.add_structure(),
+                "if" if self.syntax.match_guards && is_match_guard(&tokens[index + 1..]) => {}
+                "|" if self.syntax.closure_pipes && next == Some("{") => {
+                    self.pending = Some(Frame { nests: true, do_loop: false, indent: 0 });
+                }
+                "||" if self.syntax.closure_pipes && !is_operand(previous) => {
+                    if next == Some("{") {
+                        self.pending = Some(Frame { nests: true, do_loop: false, indent: 0 });
+                    }
+                }
+                arrow if self.syntax.lambda_arrows.contains(&arrow) => {
+                    if next == Some("{") && !switch_rule {
+                        self.pending = Some(Frame { nests: true, do_loop: false, indent: 0 });
+                    }
+                }
+                operator => {
+                    if let Some(operator) = self.syntax.logical_operators.iter().find(|candidate| **candidate == operator) {
+                        self.add_operator(operator);
+                    } else {
+                        self.feed_word(token, next, after_else, after_do, declaration);
+                    }
+                }
+            }
+        }
+    }
+
+    fn feed_word(&mut self, word: &str, next: Option<&str>, after_else: bool, after_do: bool, declaration: bool) {
+        if word == "while" && after_do {
+            return;
+        }
+        if let Some(structure) = self.syntax.structures.iter().find(|structure| **structure == word) {
+            // `else if` adds one, for the `else`
+            if !(after_else && word == "if") {
+                self.add_structure();
+            }
+            self.pending = Some(Frame { nests: true, do_loop: self.syntax.do_loop == Some(*structure), indent: 0 });
+        } else if self.syntax.continuations.contains(&word) {
+            self.complexity += 1;
+            self.last_operator = None;
+            self.pending = Some(Frame { nests: true, do_loop: false, indent: 0 });
+            self.after_else = true;
+        } else if self.syntax.jumps.contains(&word) {
+            if next.is_some_and(|label| self.is_label(label)) {
+                self.complexity += 1;
+            }
+        } else if word == self.function_name && next == Some("(") && !declaration {
+            // Recursion
+            self.complexity += 1;
+        }
+    }
+
+    fn is_label(&self, token: &str) -> bool {
+        let mut chars = token.chars();
+        match (self.syntax.label_prefix, chars.next()) {
+            (Some(prefix), Some(first)) => first == prefix,
+            (None, Some(first)) => first.is_alphabetic() || first == '_',
+            (_, None) => false,
+        }
+    }
+
+    fn feed_indented(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if let Some(closing) = self.docstring {
+            if trimmed.contains(closing) {
+                self.docstring = None;
+            }
+            return;
+        }
+        if let Some(quote) = ["\"\"\"", "'''"].into_iter().find(|quote| trimmed.starts_with(quote) && trimmed.matches(quote).count() == 1) {
+            self.docstring = Some(quote);
+            return;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        let continued = self.paren_depth > 0;
+        if !continued {
+            while self.frames.last().is_some_and(|frame| frame.indent >= indent) {
+                self.frames.pop();
+            }
+            self.last_operator = None;
+        }
+
+        let code = self.scanner.code(line);
+        let tokens = tokenize(&code);
+        let leading = tokens.iter().position(|token| token != "async").map(|index| tokens[index].as_str());
+
+        for (index, token) in tokens.iter().enumerate() {
+            let starts_statement = !continued && Some(token.as_str()) == leading && tokens[..index].iter().all(|token| token == "async");
+            match token.as_str() {
+                "(" | "[" | "{" => self.paren_depth += 1,
+                ")" | "]" | "}" => self.paren_depth = self.paren_depth.saturating_sub(1),
+                "if" if !starts_statement => {
+                    // A conditional expression or a comprehension filter
+                    self.add_structure();
+                }
+                word if starts_statement && self.syntax.structures.contains(&word) => {
+                    self.add_structure();
+                    self.pending = Some(Frame { nests: true, do_loop: false, indent });
+                }
+                word if starts_statement && self.syntax.continuations.contains(&word) => {
+                    self.complexity += 1;
+                    self.pending = Some(Frame { nests: true, do_loop: false, indent });
+                }
+                word if starts_statement && PYTHON_NESTED_FUNCTIONS.contains(&word) => {
+                    self.pending = Some(Frame { nests: true, do_loop: false, indent });
+                }
+                word => {
+                    if let Some(operator) = self.syntax.logical_operators.iter().find(|operator| **operator == word) {
+                        self.add_operator(operator);
+                    } else if word == self.function_name && tokens.get(index + 1).is_some_and(|next| next == "(") {
+                        // Recursion
+                        self.complexity += 1;
+                    }
+                }
+            }
+        }
+
+        // The block opens with the `:` ending its header, which may span several lines
+        if self.paren_depth == 0 && code.trim_end().ends_with(':') {
+            let frame = self.pending.take().unwrap_or(Frame { nests: false, do_loop: false, indent });
+            self.frames.push(frame);
+        }
+    }
+}
+
+/// Whether a token ends an operand, so a `||` after it is an operator rather than a closure
+fn is_operand(token: Option<&str>) -> bool {
+    match token {
+        Some(")" | "]" | "?") => true,
+        Some("move" | "return" | "in") => false,
+        Some(token) => token.chars().next().is_some_and(|first| first.is_alphanumeric() || first == '_' || first == '\'' || first == '"'),
+        None => false,
+    }
+}
+
+/// Whether the tokens after an `if` reach a match arm's `=>` before any block opens
+fn is_match_guard(rest: &[String]) -> bool {
+    rest.iter().find(|token| *token == "=>" || *token == "{").is_some_and(|token| token == "=>")
+}
+
+/// Words, compound operators and single punctuation of a line of code
+fn tokenize(code: &str) -> Vec<String> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let starts_word = c.is_alphanumeric() || c == '_' || c == '$'
+            || (c == '\'' && chars.get(i + 1).is_some_and(|next| next.is_alphabetic() || *next == '_'));
+        if c.is_whitespace() {
+            i += 1;
+        } else if starts_word {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else if let Some(operator) = COMPOUND_OPERATORS.iter().find(|operator| chars[i..].starts_with(&operator.chars().collect::<Vec<_>>())) {
+            tokens.push(operator.to_string());
+            i += 2;
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+
+    tokens
+}
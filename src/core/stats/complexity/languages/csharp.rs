@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{is_control_statement, BlockTracker, BraceScanner};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// C# language complexity analyzer
@@ -151,7 +153,12 @@ impl CSharpAnalyzer {
         }
         
         // Must contain parentheses for parameters
-        if !trimmed.contains('(') {
+        let Some(paren) = trimmed.find('(') else {
+            return false;
+        };
+        
+        // Control statements and assignments such as `var task = Task.Run(async () => ...)`
+        if is_control_statement(trimmed) || trimmed[..paren].contains('=') {
             return false;
         }
         
@@ -173,34 +180,8 @@ impl CSharpAnalyzer {
     }
     
     /// Count parameters in method signature
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.rfind(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                
-                // Count parameters by commas, but be careful with generics
-                let mut paren_depth = 0;
-                let mut angle_depth = 0;
-                let mut param_count = 1;
-                
-                for ch in params.chars() {
-                    match ch {
-                        '(' => paren_depth += 1,
-                        ')' => paren_depth -= 1,
-                        '<' => angle_depth += 1,
-                        '>' => angle_depth -= 1,
-                        ',' if paren_depth == 0 && angle_depth == 0 => param_count += 1,
-                        _ => {}
-                    }
-                }
-                
-                return param_count;
-            }
-        }
-        0
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, BraceScanner::new(), &parameters::C_FAMILY)
     }
     
     /// Determine structure type from declaration
@@ -262,10 +243,8 @@ impl CSharpAnalyzer {
 impl LanguageAnalyzer for CSharpAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut brace_count = 0;
-        let mut in_function = false;
-        let mut nesting_level: usize = 0;
+        // Functions whose body is still open, innermost last
+        let mut open_functions: Vec<(FunctionInfo, BlockTracker)> = Vec::new();
         let mut in_comment_block = false;
         
         for (line_num, line) in lines.iter().enumerate() {
@@ -291,11 +270,11 @@ impl LanguageAnalyzer for CSharpAnalyzer {
             // Method declaration detection
             if self.is_method_declaration(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     let is_static = self.is_static(trimmed);
                     let _is_async = self.is_async(trimmed);
                     
-                    current_function = Some(FunctionInfo {
+                    open_functions.push((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
@@ -310,77 +289,64 @@ impl LanguageAnalyzer for CSharpAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    brace_count = 0;
-                    nesting_level = 0;
+                        visibility: Visibility::Public,
+                    }, BlockTracker::new(BraceScanner::new())));
                 }
             }
             
-            if in_function {
-                // Count braces to track function scope
-                brace_count += line.matches('{').count() as i32;
-                brace_count -= line.matches('}').count() as i32;
+            if let Some((func, tracker)) = open_functions.last_mut() {
+                // Count braces to track function scope, ignoring strings and comments
+                let closed = tracker.feed(line);
+                let nesting_level = tracker.depth();
+                
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                func.nesting_depth = tracker.max_depth();
                 
-                // Track nesting level
-                if line.contains('{') {
-                    nesting_level += 1;
+                // Add complexity from keywords
+                let keyword_complexity = self.count_complexity_keywords(trimmed);
+                func.cyclomatic_complexity += keyword_complexity;
+                
+                // Add cognitive complexity
+                let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
+                func.cognitive_complexity += cognitive_complexity;
+                
+                // Count return statements
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
                 }
-                if line.contains('}') {
-                    nesting_level = nesting_level.saturating_sub(1);
+                
+                // Check for recursion
+                if trimmed.contains(&func.name) && trimmed.contains('(') {
+                    func.has_recursion = true;
                 }
                 
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    func.nesting_depth = func.nesting_depth.max(nesting_level);
-                    
-                    // Add complexity from keywords
-                    let keyword_complexity = self.count_complexity_keywords(trimmed);
-                    func.cyclomatic_complexity += keyword_complexity;
-                    
-                    // Add cognitive complexity
-                    let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
-                    func.cognitive_complexity += cognitive_complexity;
-                    
-                    // Count return statements
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && trimmed.contains('(') {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for exception handling
-                    if trimmed.contains("try") || trimmed.contains("catch") || 
-                       trimmed.contains("finally") || trimmed.contains("throw") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Count local variables
-                    if (trimmed.contains("var ") || trimmed.contains("int ") || 
-                        trimmed.contains("string ") || trimmed.contains("bool ") ||
-                        trimmed.contains("double ") || trimmed.contains("float ") ||
-                        trimmed.contains("decimal ") || trimmed.contains("object ")) &&
-                       !trimmed.contains("public") && !trimmed.contains("private") &&
-                       !trimmed.contains("protected") && !trimmed.contains("internal") {
-                        func.local_variable_count += 1;
-                    }
+                // Check for exception handling
+                if trimmed.contains("try") || trimmed.contains("catch") || 
+                   trimmed.contains("finally") || trimmed.contains("throw") {
+                    func.has_exception_handling = true;
+                }
+                
+                // Count local variables
+                if (trimmed.contains("var ") || trimmed.contains("int ") || 
+                    trimmed.contains("string ") || trimmed.contains("bool ") ||
+                    trimmed.contains("double ") || trimmed.contains("float ") ||
+                    trimmed.contains("decimal ") || trimmed.contains("object ")) &&
+                   !trimmed.contains("public") && !trimmed.contains("private") &&
+                   !trimmed.contains("protected") && !trimmed.contains("internal") {
+                    func.local_variable_count += 1;
                 }
                 
                 // End of function
-                if brace_count <= 0 && in_function {
-                    if let Some(func) = current_function.take() {
+                if closed {
+                    if let Some((func, _)) = open_functions.pop() {
                         functions.push(func);
                     }
-                    in_function = false;
-                    nesting_level = 0;
                 }
             }
         }
         
+        functions.sort_by_key(|func| func.start_line);
         Ok(functions)
     }
     
@@ -481,7 +447,7 @@ impl LanguageAnalyzer for CSharpAnalyzer {
             
             if self.is_method_declaration(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     let is_static = self.is_static(trimmed);
                     
                     let method_info = FunctionInfo {
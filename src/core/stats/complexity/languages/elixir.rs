@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, KeywordBlockTracker};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Elixir language complexity analyzer
@@ -122,38 +124,18 @@ impl ElixirAnalyzer {
         complexity
     }
     
-    /// Find the end of a function definition
-    fn find_function_end(&self, lines: &[String], start_line: usize) -> usize {
-        let mut depth = 0;
-        let mut in_function = false;
+    /// Find the end of a function definition and the deepest nesting of its body
+    fn find_function_end(&self, lines: &[String], start_line: usize) -> (usize, usize) {
+        // Block keywords in strings and comments are ignored; one-line `def f(x), do: x` clauses open no block
+        let mut tracker = KeywordBlockTracker::new(&["do", "fn"], &["end"], "#");
         
         for (i, line) in lines.iter().enumerate().skip(start_line) {
-            let trimmed = line.trim();
-            
-            if trimmed.contains("def ") || trimmed.contains("defp ") {
-                in_function = true;
-            }
-            
-            if in_function {
-                // Count do/end blocks
-                if trimmed.contains(" do") || trimmed.ends_with(" do") {
-                    depth += 1;
-                }
-                if trimmed == "end" || trimmed.starts_with("end ") {
-                    depth -= 1;
-                    if depth == 0 {
-                        return i;
-                    }
-                }
-                
-                // Handle single-line functions
-                if trimmed.contains(", do:") && depth == 0 {
-                    return i;
-                }
+            if tracker.feed(line) {
+                return (i, tracker.max_depth());
             }
         }
         
-        lines.len().saturating_sub(1)
+        (lines.len().saturating_sub(1), tracker.max_depth())
     }
     
     /// Determine visibility of a function
@@ -189,7 +171,8 @@ impl LanguageAnalyzer for ElixirAnalyzer {
         
         for (i, line) in lines.iter().enumerate() {
             if let Some(func_name) = self.extract_function_name(line) {
-                let end_line = self.find_function_end(lines, i);
+                let (end_line, nesting_depth) = self.find_function_end(lines, i);
+                let parameter_count = self.count_parameters(lines, i, &func_name);
                 let complexity = self.calculate_cyclomatic_complexity(lines, i, end_line);
                 let _visibility = self.determine_visibility(line);
                 
@@ -198,8 +181,8 @@ impl LanguageAnalyzer for ElixirAnalyzer {
                     line_count: end_line.saturating_sub(i).max(1),
                     cyclomatic_complexity: complexity,
                     cognitive_complexity: complexity, // Use same value for now
-                    nesting_depth: 0, // Calculate if needed
-                    parameter_count: self.count_parameters(line),
+                    nesting_depth,
+                    parameter_count,
                     return_path_count: 1, // Default value
                     start_line: i + 1,
                     end_line: end_line + 1,
@@ -249,48 +232,16 @@ impl LanguageAnalyzer for ElixirAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["ex", "exs"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false)
+    }
 }
 
 impl ElixirAnalyzer {
-    /// Count parameters in a function definition
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                return params.split(',').count();
-            }
-        }
-        
-        // Handle functions without parentheses
-        if line.contains("def ") || line.contains("defp ") {
-            let after_def = if let Some(pos) = line.find("def ") {
-                &line[pos + 4..]
-            } else if let Some(pos) = line.find("defp ") {
-                &line[pos + 5..]
-            } else {
-                return 0;
-            };
-            
-            // Count arguments separated by commas before "do" or "when"
-            let args_part = if let Some(do_pos) = after_def.find(" do") {
-                &after_def[..do_pos]
-            } else if let Some(when_pos) = after_def.find(" when") {
-                &after_def[..when_pos]
-            } else {
-                after_def
-            };
-            
-            if args_part.trim().is_empty() {
-                return 0;
-            }
-            
-            return args_part.split(',').count();
-        }
-        
-        0
+    /// Count parameters in a function definition, whose parentheses are optional
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count_bare(lines, index, name, self.comment_scanner(), &["do", "when"])
     }
     
     /// Find the end of a structure definition
@@ -340,7 +291,8 @@ impl ElixirAnalyzer {
         
         for i in start_line..=end_line.min(lines.len().saturating_sub(1)) {
             if let Some(func_name) = self.extract_function_name(&lines[i]) {
-                let func_end_line = self.find_function_end(lines, i);
+                let (func_end_line, nesting_depth) = self.find_function_end(lines, i);
+                let parameter_count = self.count_parameters(lines, i, &func_name);
                 let complexity = self.calculate_cyclomatic_complexity(lines, i, func_end_line);
                 
                 methods.push(FunctionInfo {
@@ -348,8 +300,8 @@ impl ElixirAnalyzer {
                     line_count: func_end_line.saturating_sub(i).max(1),
                     cyclomatic_complexity: complexity,
                     cognitive_complexity: complexity,
-                    nesting_depth: 0,
-                    parameter_count: self.count_parameters(&lines[i]),
+                    nesting_depth,
+                    parameter_count,
                     return_path_count: 1,
                     start_line: i + 1,
                     end_line: func_end_line + 1,
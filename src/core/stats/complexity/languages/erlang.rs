@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, KeywordBlockTracker};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Erlang language complexity analyzer
@@ -166,39 +168,27 @@ impl ErlangAnalyzer {
     }
     
     /// Count parameters in function signature
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                
-                // Count parameters by commas, handling nested structures
-                let mut param_count = 1;
-                let mut paren_depth = 0;
-                let mut bracket_depth = 0;
-                let mut brace_depth = 0;
-                
-                for ch in params.chars() {
-                    match ch {
-                        '(' => paren_depth += 1,
-                        ')' => paren_depth -= 1,
-                        '[' => bracket_depth += 1,
-                        ']' => bracket_depth -= 1,
-                        '{' => brace_depth += 1,
-                        '}' => brace_depth -= 1,
-                        ',' if paren_depth == 0 && bracket_depth == 0 && brace_depth == 0 => {
-                            param_count += 1;
-                        }
-                        _ => {}
-                    }
-                }
-                
-                return param_count;
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, self.comment_scanner(), &parameters::UNTYPED)
+    }
+    
+    /// Tracker of the blocks closed by `end` inside a function
+    fn block_tracker(&self) -> KeywordBlockTracker {
+        KeywordBlockTracker::new(&["case", "if", "receive", "try", "begin", "maybe", "fun"], &["end"], "%")
+    }
+    
+    /// The line with references such as `fun handle/2` masked, since only an
+    /// anonymous `fun (...)` opens a block closed by `end`
+    fn mask_function_references(&self, line: &str) -> String {
+        let mut masked = line.to_string();
+        let mut from = 0;
+        while let Some(at) = masked[from..].find("fun ").map(|at| from + at) {
+            if masked[at + 4..].trim_start().starts_with(|c: char| c.is_ascii_lowercase()) {
+                masked.replace_range(at..at + 3, "ref");
             }
+            from = at + 4;
         }
-        0
+        masked
     }
     
     /// Determine visibility (all functions are public in Erlang unless unexported)
@@ -273,7 +263,7 @@ impl LanguageAnalyzer for ErlangAnalyzer {
         let mut functions = Vec::new();
         let mut current_function: Option<FunctionInfo> = None;
         let mut in_function = false;
-        let mut nesting_level: usize = 0;
+        let mut tracker = self.block_tracker();
         let mut function_end_patterns = 0;
         
         for (line_num, line) in lines.iter().enumerate() {
@@ -284,10 +274,16 @@ impl LanguageAnalyzer for ErlangAnalyzer {
                 continue;
             }
             
-            // Function declaration detection
-            if self.is_function_declaration(trimmed) {
-                if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+            // Function declaration detection. Clauses start at the top level; `Pattern -> call(X)`
+            // lines inside `case` are indented, and later clauses of the current function continue it.
+            if !line.starts_with(char::is_whitespace) && self.is_function_declaration(trimmed) {
+                let func_name = self.extract_function_name(trimmed).filter(|func_name| {
+                    let next_clause = in_function && function_end_patterns == 0 &&
+                        current_function.as_ref().is_some_and(|func| &func.name == func_name);
+                    !next_clause
+                });
+                if let Some(func_name) = func_name {
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     let _clause_count = self.count_function_clauses(lines, line_num, &func_name);
                     
                     current_function = Some(FunctionInfo {
@@ -307,7 +303,7 @@ impl LanguageAnalyzer for ErlangAnalyzer {
                         has_exception_handling: false,
                         visibility: Visibility::Public,});
                     in_function = true;
-                    nesting_level = 0;
+                    tracker = self.block_tracker();
                     function_end_patterns = 0;
                 }
             }
@@ -317,16 +313,10 @@ impl LanguageAnalyzer for ErlangAnalyzer {
                     func.line_count += 1;
                     func.end_line = line_num + 1;
                     
-                    // Track nesting level
-                    if trimmed.contains("case ") || trimmed.contains("if ") || 
-                       trimmed.contains("receive") || trimmed.contains("try ") {
-                        nesting_level += 1;
-                    }
-                    if trimmed.contains("end") {
-                        nesting_level = nesting_level.saturating_sub(1);
-                    }
-                    
-                    func.nesting_depth = func.nesting_depth.max(nesting_level);
+                    // Track blocks by their keywords; the clause bodies are level 1
+                    tracker.feed(&self.mask_function_references(trimmed));
+                    let nesting_level = tracker.depth();
+                    func.nesting_depth = tracker.max_depth() + 1;
                     
                     // Add complexity from keywords
                     let keyword_complexity = self.count_complexity_keywords(trimmed);
@@ -375,7 +365,6 @@ impl LanguageAnalyzer for ErlangAnalyzer {
                         functions.push(func);
                     }
                     in_function = false;
-                    nesting_level = 0;
                     function_end_patterns = 0;
                 }
             }
@@ -445,7 +434,7 @@ impl LanguageAnalyzer for ErlangAnalyzer {
                 
                 if self.is_function_declaration(trimmed) {
                     if let Some(func_name) = self.extract_function_name(trimmed) {
-                        let param_count = self.count_parameters(trimmed);
+                        let param_count = self.count_parameters(lines, line_num, &func_name);
                         
                         let method_info = FunctionInfo {
                             name: func_name,
@@ -482,6 +471,10 @@ impl LanguageAnalyzer for ErlangAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["erl", "hrl"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["%"]).with_block_comments(false)
+    }
 }
 
 impl Default for ErlangAnalyzer {
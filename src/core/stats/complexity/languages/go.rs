@@ -1,5 +1,8 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BlockTracker, BraceScanner};
+use super::cognitive::{self, CognitiveCounter};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Go language complexity analyzer
@@ -67,36 +70,6 @@ impl GoAnalyzer {
         keywords.iter().map(|&keyword| line.matches(keyword).count()).sum()
     }
     
-    /// Count cognitive complexity for Go code
-    fn count_cognitive_complexity(&self, line: &str, nesting_level: usize) -> usize {
-        let mut complexity = 0;
-        let nesting_multiplier = nesting_level.max(1);
-        
-        // Basic control structures
-        if line.contains("if ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("else if") { complexity += 1; }
-        if line.contains("else") && !line.contains("else if") { complexity += 1; }
-        if line.contains("for ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("switch ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("case ") { complexity += 1; }
-        if line.contains("select ") { complexity += 1 * nesting_multiplier; }
-        
-        // Logical operators
-        complexity += line.matches("&&").count() * nesting_multiplier;
-        complexity += line.matches("||").count() * nesting_multiplier;
-        
-        // Go-specific complexity
-        if line.contains("go ") { complexity += 1; } // Goroutines add complexity
-        if line.contains("defer ") { complexity += 1; } // Defer statements
-        if line.contains("panic(") { complexity += 2; } // Panic adds significant complexity
-        if line.contains("recover(") { complexity += 1; } // Recovery handling
-        
-        // Channel operations
-        if line.contains("<-") { complexity += 1; } // Channel send/receive
-        
-        complexity
-    }
-    
     /// Check if line contains a function declaration
     fn is_function_declaration(&self, line: &str) -> bool {
         let trimmed = line.trim();
@@ -118,32 +91,8 @@ impl GoAnalyzer {
     }
     
     /// Count parameters in function signature
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                
-                // Go parameters can be grouped: func(a, b int, c string)
-                // Count parameter groups by looking for type keywords
-                let param_str = params.trim();
-                if param_str.is_empty() {
-                    return 0;
-                }
-                
-                // Simple heuristic: count commas + 1, but account for grouped params
-                let comma_count = param_str.matches(',').count();
-                if comma_count == 0 {
-                    return 1;
-                }
-                
-                // More sophisticated counting would require parsing Go syntax
-                return comma_count + 1;
-            }
-        }
-        0
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, BraceScanner::new(), &parameters::UNTYPED)
     }
     
     /// Determine structure type from declaration
@@ -198,10 +147,8 @@ impl GoAnalyzer {
 impl LanguageAnalyzer for GoAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut brace_count = 0;
-        let mut in_function = false;
-        let mut nesting_level: usize = 0;
+        // Functions whose body is still open, innermost last
+        let mut open_functions: Vec<(FunctionInfo, BlockTracker, CognitiveCounter)> = Vec::new();
         let mut in_comment_block = false;
         
         for (line_num, line) in lines.iter().enumerate() {
@@ -227,7 +174,7 @@ impl LanguageAnalyzer for GoAnalyzer {
             // Function declaration detection
             if self.is_function_declaration(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     let is_method = self.is_method(trimmed);
                     let parent_class = if is_method {
                         self.extract_receiver_type(trimmed)
@@ -235,11 +182,12 @@ impl LanguageAnalyzer for GoAnalyzer {
                         None
                     };
                     
-                    current_function = Some(FunctionInfo {
+                    let counter = CognitiveCounter::new(&cognitive::GO, BraceScanner::new(), &func_name);
+                    open_functions.push((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
-                        cognitive_complexity: 1, // Base cognitive complexity
+                        cognitive_complexity: 0,
                         nesting_depth: 0,
                         parameter_count: param_count,
                         return_path_count: 0,
@@ -250,73 +198,59 @@ impl LanguageAnalyzer for GoAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    brace_count = 0;
-                    nesting_level = 0;
+                        visibility: Visibility::Public,
+                    }, BlockTracker::new(BraceScanner::new()), counter));
                 }
             }
             
-            if in_function {
-                // Count braces to track function scope
-                brace_count += line.matches('{').count() as i32;
-                brace_count -= line.matches('}').count() as i32;
+            if let Some((func, tracker, counter)) = open_functions.last_mut() {
+                // Count braces to track function scope, ignoring strings and comments
+                let closed = tracker.feed(line);
+                
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                func.nesting_depth = tracker.max_depth();
+                
+                // Add complexity from keywords
+                let keyword_complexity = self.count_complexity_keywords(trimmed);
+                func.cyclomatic_complexity += keyword_complexity;
                 
-                // Track nesting level
-                if line.contains('{') {
-                    nesting_level += 1;
+                // Add cognitive complexity
+                counter.feed(line);
+                func.cognitive_complexity = counter.complexity();
+                
+                // Count return statements
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
                 }
-                if line.contains('}') {
-                    nesting_level = nesting_level.saturating_sub(1);
+                
+                // Check for recursion
+                if trimmed.contains(&func.name) && trimmed.contains('(') {
+                    func.has_recursion = true;
                 }
                 
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    func.nesting_depth = func.nesting_depth.max(nesting_level);
-                    
-                    // Add complexity from keywords
-                    let keyword_complexity = self.count_complexity_keywords(trimmed);
-                    func.cyclomatic_complexity += keyword_complexity;
-                    
-                    // Add cognitive complexity
-                    let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
-                    func.cognitive_complexity += cognitive_complexity;
-                    
-                    // Count return statements
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && trimmed.contains('(') {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for error handling (Go's way of exception handling)
-                    if trimmed.contains("panic(") || trimmed.contains("recover(") || 
-                       trimmed.contains("if err != nil") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Rough estimate of local variables
-                    if trimmed.contains(":=") || 
-                       (trimmed.contains("var ") && !trimmed.contains("func")) {
-                        func.local_variable_count += 1;
-                    }
+                // Check for error handling (Go's way of exception handling)
+                if trimmed.contains("panic(") || trimmed.contains("recover(") || 
+                   trimmed.contains("if err != nil") {
+                    func.has_exception_handling = true;
+                }
+                
+                // Rough estimate of local variables
+                if trimmed.contains(":=") || 
+                   (trimmed.contains("var ") && !trimmed.contains("func")) {
+                    func.local_variable_count += 1;
                 }
                 
                 // End of function
-                if brace_count <= 0 && in_function {
-                    if let Some(func) = current_function.take() {
+                if closed {
+                    if let Some((func, _, _)) = open_functions.pop() {
                         functions.push(func);
                     }
-                    in_function = false;
-                    nesting_level = 0;
                 }
             }
         }
         
+        functions.sort_by_key(|func| func.start_line);
         Ok(functions)
     }
     
@@ -426,7 +360,7 @@ impl LanguageAnalyzer for GoAnalyzer {
             if self.is_function_declaration(trimmed) && self.is_method(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
                     if let Some(receiver_type) = self.extract_receiver_type(trimmed) {
-                        let param_count = self.count_parameters(trimmed);
+                        let param_count = self.count_parameters(lines, line_num, &func_name);
                         
                         let method_info = FunctionInfo {
                             name: func_name,
@@ -0,0 +1,172 @@
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BlockTracker, BraceScanner};
+use super::LanguageAnalyzer;
+
+/// GraphQL complexity analyzer: operations and fragments are the functions,
+/// with their variables as parameters, selection-set depth as nesting and
+/// `@include`/`@skip` directives as branches; schema types are the structures
+pub struct GraphqlAnalyzer;
+
+impl GraphqlAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Keyword and name of a top-level definition (`query GetUser(...)`,
+    /// `fragment UserFields on User`, `extend type Query`)
+    fn definition<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str)> {
+        if line.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let mut words = line.trim().split(|c: char| c.is_whitespace() || c == '(' || c == '{').filter(|word| !word.is_empty());
+        let mut keyword = words.next()?;
+        if keyword == "extend" {
+            keyword = words.next()?;
+        }
+        if !matches!(keyword, "query" | "mutation" | "subscription" | "fragment" | "type" | "interface" | "enum" | "input") {
+            return None;
+        }
+        let name = words.next()?;
+        name.chars().all(|c| c.is_alphanumeric() || c == '_').then_some((keyword, name))
+    }
+
+    fn scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false)
+    }
+
+    fn find_end(&self, lines: &[String], start_line: usize) -> usize {
+        let mut tracker = BlockTracker::new(self.scanner());
+        for (index, line) in lines.iter().enumerate().skip(start_line) {
+            if tracker.feed(line) {
+                return index;
+            }
+        }
+        lines.len().saturating_sub(1)
+    }
+
+    /// Variables declared by an operation, up to the opening of its selection set
+    fn count_variables(&self, lines: &[String], start_line: usize) -> usize {
+        let mut variables = 0;
+        for line in &lines[start_line..] {
+            let header = line.split('{').next().unwrap_or("");
+            variables += header.matches('$').count();
+            if line.contains('{') {
+                break;
+            }
+        }
+        variables
+    }
+}
+
+impl LanguageAnalyzer for GraphqlAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some((keyword, name)) = self.definition(line) else {
+                continue;
+            };
+            if !matches!(keyword, "query" | "mutation" | "subscription" | "fragment") {
+                continue;
+            }
+
+            let mut tracker = BlockTracker::new(self.scanner());
+            let mut end_line = lines.len().saturating_sub(1);
+            let mut complexity = 1;
+            let mut nesting_depth = 0;
+            for (line_index, body_line) in lines.iter().enumerate().skip(index) {
+                let ended = tracker.feed(body_line);
+                let code = body_line.split('#').next().unwrap_or("");
+                complexity += code.matches("@include").count() + code.matches("@skip").count();
+                nesting_depth = nesting_depth.max(tracker.depth().saturating_sub(1));
+                if ended {
+                    end_line = line_index;
+                    break;
+                }
+            }
+
+            functions.push(FunctionInfo {
+                name: name.to_string(),
+                line_count: end_line - index + 1,
+                cyclomatic_complexity: complexity,
+                cognitive_complexity: complexity + nesting_depth,
+                nesting_depth,
+                parameter_count: self.count_variables(lines, index),
+                return_path_count: 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                is_method: false,
+                parent_class: None,
+                local_variable_count: 0,
+                has_recursion: false,
+                has_exception_handling: false,
+                visibility: Visibility::Public,
+            });
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, lines: &[String]) -> Result<Vec<StructureInfo>> {
+        let mut structures = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some((keyword, name)) = self.definition(line) else {
+                continue;
+            };
+            let structure_type = match keyword {
+                "type" => StructureType::Class,
+                "interface" => StructureType::Interface,
+                "enum" => StructureType::Enum,
+                "input" => StructureType::Struct,
+                _ => continue,
+            };
+
+            let end_line = self.find_end(lines, index);
+            // Fields or enum values: the lines of the body that aren't descriptions or comments
+            let properties = lines[index + 1..end_line.max(index + 1)]
+                .iter()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('"') && !line.starts_with('}'))
+                .count();
+            // `type User implements Node & Entity`
+            let interface_count = line
+                .split_once(" implements ")
+                .map_or(0, |(_, interfaces)| interfaces.split('{').next().unwrap_or("").split(['&', ',']).filter(|name| !name.trim().is_empty()).count());
+
+            structures.push(StructureInfo {
+                name: name.to_string(),
+                structure_type,
+                line_count: end_line - index + 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                methods: Vec::new(),
+                properties,
+                visibility: Visibility::Public,
+                inheritance_depth: 0,
+                interface_count,
+            });
+        }
+
+        Ok(structures)
+    }
+
+    fn language_name(&self) -> &'static str {
+        "GraphQL"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["graphql", "gql"]
+    }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        self.scanner()
+    }
+}
+
+impl Default for GraphqlAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
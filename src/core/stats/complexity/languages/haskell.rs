@@ -1,5 +1,6 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, IndentTracker};
 use super::LanguageAnalyzer;
 
 /// Haskell language complexity analyzer
@@ -162,32 +163,26 @@ impl HaskellAnalyzer {
     }
     
     /// Find the end of a function definition
-    fn find_function_end(&self, lines: &[String], start_line: usize) -> usize {
-        let mut in_function = false;
-        let mut base_indentation = None;
+    fn find_function_end(&self, lines: &[String], start_line: usize, func_name: &str) -> usize {
+        let mut end_line = start_line;
         
-        for (i, line) in lines.iter().enumerate().skip(start_line) {
+        // The type signature and every equation of the function belong to it; the next
+        // top-level line for another name ends it. Trailing blanks and comments are excluded.
+        for (i, line) in lines.iter().enumerate().skip(start_line + 1) {
             let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with("--") {
+                continue;
+            }
             
-            if !trimmed.is_empty() {
-                let current_indentation = line.len() - line.trim_start().len();
-                
-                if !in_function {
-                    // First non-empty line defines the function
-                    in_function = true;
-                    base_indentation = Some(current_indentation);
-                } else {
-                    // Check if we've moved to a new top-level definition
-                    if current_indentation <= base_indentation.unwrap_or(0) && 
-                       (trimmed.contains("::") || trimmed.contains("=")) &&
-                       !trimmed.starts_with("--") {
-                        return i.saturating_sub(1);
-                    }
-                }
+            let top_level = !line.starts_with(char::is_whitespace);
+            let same_function = trimmed.split_whitespace().next() == Some(func_name);
+            if top_level && !same_function {
+                break;
             }
+            end_line = i;
         }
         
-        lines.len().saturating_sub(1)
+        end_line
     }
     
     /// Determine visibility of a function
@@ -220,10 +215,24 @@ impl HaskellAnalyzer {
 impl LanguageAnalyzer for HaskellAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
+        let mut covered_until = None;
         
         for (i, line) in lines.iter().enumerate() {
+            // Definitions are top-level; indented `where` and `let` bindings belong to their function
+            if line.starts_with(char::is_whitespace) || covered_until.is_some_and(|end| i <= end) {
+                continue;
+            }
+            
             if let Some(func_name) = self.extract_function_name(line) {
-                let end_line = self.find_function_end(lines, i);
+                let end_line = self.find_function_end(lines, i, &func_name);
+                covered_until = Some(end_line);
+                let parameter_count = self.count_parameters(lines, i, end_line, &func_name);
+                
+                // Nesting follows the indentation of guards, `where` and `let` bindings, `case` alternatives and `do` blocks
+                let mut tracker = IndentTracker::new(self.comment_scanner(), 0);
+                for line in &lines[i..=end_line] {
+                    tracker.feed(line);
+                }
                 let complexity = self.calculate_cyclomatic_complexity(lines, i, end_line);
                 let _visibility = self.determine_visibility(line);
                 
@@ -232,8 +241,8 @@ impl LanguageAnalyzer for HaskellAnalyzer {
                     line_count: end_line.saturating_sub(i).max(1),
                     cyclomatic_complexity: complexity,
                     cognitive_complexity: complexity,
-                    nesting_depth: 0,
-                    parameter_count: self.count_parameters(line),
+                    nesting_depth: tracker.max_depth(),
+                    parameter_count,
                     return_path_count: 1,
                     start_line: i + 1,
                     end_line: end_line + 1,
@@ -283,31 +292,62 @@ impl LanguageAnalyzer for HaskellAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["hs", "lhs"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["--"]).with_block_comments(false)
+    }
 }
 
 impl HaskellAnalyzer {
-    /// Count parameters in a function definition
-    fn count_parameters(&self, line: &str) -> usize {
-        // For type signatures, count arrows to estimate parameters
-        if line.contains("::") {
-            let parts: Vec<&str> = line.split("::").collect();
-            if let Some(type_part) = parts.get(1) {
-                return type_part.matches("->").count();
-            }
-        }
+    /// Count parameters from the type signature, as the arrows outside brackets
+    /// after any constraint: `mapM :: Monad m => (a -> m b) -> [a] -> m [b]` has 2.
+    /// Without a signature, the patterns before the `=` or guard of the first equation.
+    fn count_parameters(&self, lines: &[String], start_line: usize, end_line: usize, name: &str) -> usize {
+        let mut scanner = self.comment_scanner();
+        let code: Vec<String> = lines[start_line..=end_line].iter().map(|line| scanner.code(line)).collect();
+        let after_name = |line: &String| line.strip_prefix(name).filter(|rest| rest.starts_with(char::is_whitespace)).map(str::to_string);
         
-        // For function definitions, count arguments before =
-        if line.contains('=') && !line.contains("::") {
-            let parts: Vec<&str> = line.split('=').collect();
-            if let Some(first_part) = parts.first() {
-                let args: Vec<&str> = first_part.trim().split_whitespace().collect();
-                if args.len() > 1 {
-                    return args.len() - 1; // Subtract 1 for the function name
+        if let Some(start) = code.iter().position(|line| after_name(line).is_some_and(|rest| rest.trim_start().starts_with("::"))) {
+            // The signature may continue on indented lines
+            let continuation = code[start + 1..].iter().take_while(|line| line.starts_with(char::is_whitespace));
+            let signature = std::iter::once(&code[start]).chain(continuation).cloned().collect::<Vec<_>>().join(" ");
+            let signature = signature.split_once("::").map_or("", |(_, signature)| signature);
+            let mut depth = 0usize;
+            let mut arrows = 0;
+            let chars: Vec<char> = signature.chars().collect();
+            for (at, &c) in chars.iter().enumerate() {
+                match c {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                    '-' if depth == 0 && chars.get(at + 1) == Some(&'>') => arrows += 1,
+                    // Arrows so far belonged to the constraint
+                    '=' if depth == 0 && chars.get(at + 1) == Some(&'>') => arrows = 0,
+                    _ => {}
                 }
             }
+            return arrows;
         }
         
-        0
+        let Some(equation) = code.iter().find_map(after_name) else {
+            return 0;
+        };
+        let mut depth = 0usize;
+        let mut patterns = 0;
+        let mut in_pattern = false;
+        for c in equation.chars() {
+            match c {
+                '=' | '|' if depth == 0 => break,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            let separator = depth == 0 && c.is_whitespace();
+            if !separator && !in_pattern {
+                patterns += 1;
+            }
+            in_pattern = !separator;
+        }
+        patterns
     }
     
     /// Extract return type from function signature
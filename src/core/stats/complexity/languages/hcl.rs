@@ -0,0 +1,149 @@
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, Visibility};
+use super::blocks::{count_keywords, BlockTracker, BraceScanner};
+use super::LanguageAnalyzer;
+
+/// HCL (Terraform) complexity analyzer. HCL has no functions, so each top-level
+/// block (`resource`, `module`, `data`, `locals`, ...) is analyzed as one, with
+/// conditional and `for` expressions, `dynamic` blocks and `count`/`for_each`
+/// as its branches.
+pub struct HclAnalyzer;
+
+impl HclAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Terraform address of a top-level block from its opening line:
+    /// `aws_instance.web` for a resource, `module.vpc`, `data.aws_ami.ubuntu`, `locals`
+    fn block_address(&self, line: &str) -> Option<String> {
+        if line.starts_with(char::is_whitespace) || line.starts_with('#') || line.starts_with("//") {
+            return None;
+        }
+        let header = line[..line.find('{')?].trim();
+        if header.is_empty() || header.contains('=') {
+            return None;
+        }
+
+        let mut words = header.split_whitespace().map(|word| word.trim_matches('"'));
+        let block_type = words.next()?;
+        if !block_type.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            return None;
+        }
+        let mut parts: Vec<&str> = if block_type == "resource" { Vec::new() } else { vec![block_type] };
+        parts.extend(words);
+        Some(parts.join("."))
+    }
+
+    fn scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#", "//"])
+    }
+
+    /// Branches on one line of a block, outside string literals
+    fn count_branches(&self, line: &str) -> usize {
+        let code = without_strings(line);
+        let trimmed = code.trim();
+        let attribute = trimmed.split('=').next().unwrap_or("").trim();
+
+        code.matches(" ? ").count()
+            + code.matches("&&").count()
+            + code.matches("||").count()
+            + count_keywords(&code, &["for"])
+            + usize::from(trimmed.starts_with("dynamic "))
+            + usize::from(trimmed.contains('=') && (attribute == "count" || attribute == "for_each"))
+    }
+}
+
+/// The line with the contents of its `"..."` strings removed, so words and
+/// operators inside descriptions and URLs aren't taken for code
+fn without_strings(line: &str) -> String {
+    let mut code = String::with_capacity(line.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        if in_string {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => in_string = false,
+                _ => escaped = false,
+            }
+            continue;
+        }
+        if c == '"' {
+            in_string = true;
+        }
+        code.push(c);
+    }
+    code
+}
+
+impl LanguageAnalyzer for HclAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let mut index = 0;
+
+        while index < lines.len() {
+            let Some(address) = self.block_address(&lines[index]) else {
+                index += 1;
+                continue;
+            };
+
+            let mut tracker = BlockTracker::new(self.scanner());
+            let mut end_line = lines.len().saturating_sub(1);
+            let mut complexity = 1;
+            let mut nesting_depth = 0;
+            for (line_index, line) in lines.iter().enumerate().skip(index) {
+                let ended = tracker.feed(line);
+                complexity += self.count_branches(line);
+                nesting_depth = nesting_depth.max(tracker.depth().saturating_sub(1));
+                if ended {
+                    end_line = line_index;
+                    break;
+                }
+            }
+
+            functions.push(FunctionInfo {
+                name: address,
+                line_count: end_line - index + 1,
+                cyclomatic_complexity: complexity,
+                cognitive_complexity: complexity + nesting_depth,
+                nesting_depth,
+                parameter_count: 0,
+                return_path_count: 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                is_method: false,
+                parent_class: None,
+                local_variable_count: 0,
+                has_recursion: false,
+                has_exception_handling: false,
+                visibility: Visibility::Public,
+            });
+            index = end_line + 1;
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, _lines: &[String]) -> Result<Vec<StructureInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn language_name(&self) -> &'static str {
+        "HCL"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["tf", "hcl"]
+    }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        self.scanner()
+    }
+}
+
+impl Default for HclAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
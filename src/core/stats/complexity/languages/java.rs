@@ -1,5 +1,8 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{is_control_statement, BlockTracker, BraceScanner};
+use super::cognitive::{self, CognitiveCounter};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Java language complexity analyzer
@@ -64,36 +67,6 @@ impl JavaAnalyzer {
         keywords.iter().map(|&keyword| line.matches(keyword).count()).sum()
     }
     
-    /// Count cognitive complexity for Java code
-    fn count_cognitive_complexity(&self, line: &str, nesting_level: usize) -> usize {
-        let mut complexity = 0;
-        let nesting_multiplier = nesting_level.max(1);
-        
-        // Basic control structures
-        if line.contains("if ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("else if") { complexity += 1; }
-        if line.contains("else") && !line.contains("else if") { complexity += 1; }
-        if line.contains("while ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("for ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("do ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("switch ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("case ") { complexity += 1; }
-        if line.contains("catch ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("finally") { complexity += 1; }
-        
-        // Logical operators
-        complexity += line.matches("&&").count() * nesting_multiplier;
-        complexity += line.matches("||").count() * nesting_multiplier;
-        
-        // Ternary operator
-        complexity += line.matches("?").count() * nesting_multiplier;
-        
-        // Lambda expressions add complexity
-        if line.contains("->") { complexity += 1; }
-        
-        complexity
-    }
-    
     /// Check if line contains a method declaration
     fn is_method_declaration(&self, line: &str) -> bool {
         let trimmed = line.trim();
@@ -104,7 +77,12 @@ impl JavaAnalyzer {
         }
         
         // Must contain parentheses for parameters
-        if !trimmed.contains('(') {
+        let Some(paren) = trimmed.find('(') else {
+            return false;
+        };
+        
+        // Control statements and assignments such as `int total = sum(values);`
+        if is_control_statement(trimmed) || trimmed[..paren].contains('=') {
             return false;
         }
         
@@ -131,32 +109,8 @@ impl JavaAnalyzer {
     }
     
     /// Count parameters in method signature
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                // Count commas + 1, but be careful of generics
-                let mut paren_depth = 0;
-                let mut angle_depth = 0;
-                let mut param_count = 1;
-                
-                for ch in params.chars() {
-                    match ch {
-                        '(' => paren_depth += 1,
-                        ')' => paren_depth -= 1,
-                        '<' => angle_depth += 1,
-                        '>' => angle_depth -= 1,
-                        ',' if paren_depth == 0 && angle_depth == 0 => param_count += 1,
-                        _ => {}
-                    }
-                }
-                return param_count;
-            }
-        }
-        0
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, BraceScanner::new(), &parameters::C_FAMILY)
     }
     
     /// Determine structure type from declaration
@@ -189,10 +143,8 @@ impl JavaAnalyzer {
 impl LanguageAnalyzer for JavaAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut brace_count = 0;
-        let mut in_function = false;
-        let mut nesting_level: usize = 0;
+        // Methods whose body is still open, innermost last (anonymous and local classes nest)
+        let mut open_functions: Vec<(FunctionInfo, BlockTracker, CognitiveCounter)> = Vec::new();
         let mut current_class: Option<String> = None;
         
         for (line_num, line) in lines.iter().enumerate() {
@@ -208,17 +160,19 @@ impl LanguageAnalyzer for JavaAnalyzer {
                 current_class = self.extract_class_name(trimmed);
             }
             
-            // Method declaration detection
-            if self.is_method_declaration(trimmed) {
+            // Method declaration detection; inside a method only a nested body can start one
+            let nested = !open_functions.is_empty();
+            if self.is_method_declaration(trimmed) && (!nested || trimmed.ends_with('{')) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     let is_method = current_class.is_some();
                     
-                    current_function = Some(FunctionInfo {
+                    let counter = CognitiveCounter::new(&cognitive::JAVA, BraceScanner::new(), &func_name);
+                    open_functions.push((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
-                        cognitive_complexity: 1, // Base cognitive complexity
+                        cognitive_complexity: 0,
                         nesting_depth: 0,
                         parameter_count: param_count,
                         return_path_count: 0,
@@ -229,73 +183,59 @@ impl LanguageAnalyzer for JavaAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    brace_count = 0;
-                    nesting_level = 0;
+                        visibility: Visibility::Public,
+                    }, BlockTracker::new(BraceScanner::new()), counter));
                 }
             }
             
-            if in_function {
-                // Count braces to track function scope
-                brace_count += line.matches('{').count() as i32;
-                brace_count -= line.matches('}').count() as i32;
+            if let Some((func, tracker, counter)) = open_functions.last_mut() {
+                // Count braces to track method scope, ignoring strings and comments
+                let closed = tracker.feed(line);
+                
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                func.nesting_depth = tracker.max_depth();
+                
+                // Add complexity from keywords
+                let keyword_complexity = self.count_complexity_keywords(trimmed);
+                func.cyclomatic_complexity += keyword_complexity;
                 
-                // Track nesting level
-                if line.contains('{') {
-                    nesting_level += 1;
+                // Add cognitive complexity
+                counter.feed(line);
+                func.cognitive_complexity = counter.complexity();
+                
+                // Count return statements
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
                 }
-                if line.contains('}') {
-                    nesting_level = nesting_level.saturating_sub(1);
+                
+                // Check for recursion
+                if trimmed.contains(&func.name) && trimmed.contains('(') {
+                    func.has_recursion = true;
                 }
                 
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    func.nesting_depth = func.nesting_depth.max(nesting_level);
-                    
-                    // Add complexity from keywords
-                    let keyword_complexity = self.count_complexity_keywords(trimmed);
-                    func.cyclomatic_complexity += keyword_complexity;
-                    
-                    // Add cognitive complexity
-                    let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
-                    func.cognitive_complexity += cognitive_complexity;
-                    
-                    // Count return statements
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && trimmed.contains('(') {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for exception handling
-                    if trimmed.contains("try") || trimmed.contains("catch") || trimmed.contains("throw") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Rough estimate of local variables
-                    if trimmed.contains("int ") || trimmed.contains("String ") || 
-                       trimmed.contains("boolean ") || trimmed.contains("double ") ||
-                       trimmed.contains("float ") || trimmed.contains("long ") {
-                        func.local_variable_count += 1;
-                    }
+                // Check for exception handling
+                if trimmed.contains("try") || trimmed.contains("catch") || trimmed.contains("throw") {
+                    func.has_exception_handling = true;
+                }
+                
+                // Rough estimate of local variables
+                if trimmed.contains("int ") || trimmed.contains("String ") || 
+                   trimmed.contains("boolean ") || trimmed.contains("double ") ||
+                   trimmed.contains("float ") || trimmed.contains("long ") {
+                    func.local_variable_count += 1;
                 }
                 
-                // End of function
-                if brace_count <= 0 && in_function {
-                    if let Some(func) = current_function.take() {
+                // End of method; abstract and interface methods end at their `;`
+                if closed {
+                    if let Some((func, _, _)) = open_functions.pop() {
                         functions.push(func);
                     }
-                    in_function = false;
-                    nesting_level = 0;
                 }
             }
         }
         
+        functions.sort_by_key(|func| func.start_line);
         Ok(functions)
     }
     
@@ -355,7 +295,7 @@ impl LanguageAnalyzer for JavaAnalyzer {
                     // Count method declarations within the structure
                     if self.is_method_declaration(trimmed) {
                         if let Some(func_name) = self.extract_function_name(trimmed) {
-                            let param_count = self.count_parameters(trimmed);
+                            let param_count = self.count_parameters(lines, line_num, &func_name);
                             
                             let method_info = FunctionInfo {
                                 name: func_name,
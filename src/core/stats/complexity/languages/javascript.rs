@@ -1,5 +1,8 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{is_control_statement, BlockTracker, BraceScanner};
+use super::cognitive::{self, CognitiveCounter};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// JavaScript/TypeScript language complexity analyzer
@@ -12,9 +15,13 @@ impl JavaScriptAnalyzer {
     
     /// Check if line is a JavaScript function declaration
     fn is_function_declaration(&self, line: &str) -> bool {
+        if is_control_statement(line) {
+            return false;
+        }
+        
         line.contains("function ") || 
-        line.contains("=> ") || 
-        (line.contains("(") && line.contains(")") && line.contains("{"))
+        line.contains("=>") || 
+        (line.contains(')') && line.trim_end().ends_with('{'))
     }
     
     /// Extract function name from JavaScript function declaration
@@ -31,7 +38,8 @@ impl JavaScriptAnalyzer {
         // Handle arrow functions and method declarations
         if let Some(arrow_pos) = line.find("=>") {
             let before_arrow = &line[..arrow_pos];
-            if let Some(equals_pos) = before_arrow.rfind('=') {
+            // The first `=` assigns the function; later ones are parameter defaults
+            if let Some(equals_pos) = before_arrow.find('=') {
                 let name_part = &before_arrow[..equals_pos];
                 if let Some(name) = name_part.split_whitespace().last() {
                     return Some(name.to_string());
@@ -39,6 +47,16 @@ impl JavaScriptAnalyzer {
             }
         }
         
+        // Method shorthand such as `async area(scale) {`
+        if !line.contains("=>") {
+            if let Some(paren) = line.find('(') {
+                let name = line[..paren].split_whitespace().last().unwrap_or("");
+                if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+                    return Some(name.to_string());
+                }
+            }
+        }
+        
         Some("anonymous".to_string())
     }
     
@@ -48,60 +66,17 @@ impl JavaScriptAnalyzer {
         keywords.iter().map(|&keyword| line.matches(keyword).count()).sum()
     }
     
-    /// Count cognitive complexity for JavaScript code
-    fn count_cognitive_complexity(&self, line: &str, nesting_level: i32) -> usize {
-        let mut complexity = 0;
-        let nesting_multiplier = (nesting_level as usize).max(1);
-        
-        // Basic control structures
-        if line.contains("if") { complexity += 1 * nesting_multiplier; }
-        if line.contains("else") { complexity += 1; }
-        if line.contains("while") { complexity += 1 * nesting_multiplier; }
-        if line.contains("for") { complexity += 1 * nesting_multiplier; }
-        if line.contains("switch") { complexity += 1 * nesting_multiplier; }
-        if line.contains("case") { complexity += 1; }
-        if line.contains("catch") { complexity += 1; }
-        if line.contains("finally") { complexity += 1; }
-        
-        // Logical operators
-        complexity += line.matches("&&").count() * nesting_multiplier;
-        complexity += line.matches("||").count() * nesting_multiplier;
-        
-        // Ternary operator
-        complexity += line.matches("?").count() * nesting_multiplier;
-        
-        // Recursion penalty
-        if line.contains("(") && line.contains(")") { 
-            // Simple heuristic for recursive calls
-            complexity += line.matches("()").count().min(1);
-        }
-        
-        complexity
-    }
-    
-    /// Count function parameters
-    fn count_function_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.rfind(')') {
-                if end > start {
-                    let params_str = &line[start + 1..end];
-                    if params_str.trim().is_empty() {
-                        return 0;
-                    }
-                    
-                    // Simple parameter counting (split by comma)
-                    let param_count = params_str.split(',').count();
-                    
-                    // Adjust for common patterns
-                    if params_str.contains("this") {
-                        return param_count.saturating_sub(1);
-                    }
-                    
-                    return param_count;
-                }
+    /// Count function parameters; an arrow function's single parameter may go
+    /// without parentheses, as in `x => x * 2`
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        if let Some((head, _)) = lines[index].split_once("=>") {
+            let head = head.trim_end();
+            let last = head.rsplit(|c: char| c.is_whitespace() || c == '=' || c == '(' || c == ',').next().unwrap_or("");
+            if !last.is_empty() && last.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+                return 1;
             }
         }
-        0
+        parameters::count(lines, index, name, BraceScanner::new().with_single_quote_strings(), &parameters::JAVASCRIPT)
     }
     
     /// Detect JavaScript/TypeScript structure type and name
@@ -169,9 +144,8 @@ impl JavaScriptAnalyzer {
 impl LanguageAnalyzer for JavaScriptAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut brace_count = 0;
-        let mut in_function = false;
+        // Functions whose body is still open, innermost last
+        let mut open_functions: Vec<(FunctionInfo, BlockTracker, CognitiveCounter)> = Vec::new();
         
         for (line_num, line) in lines.iter().enumerate() {
             let trimmed = line.trim();
@@ -182,15 +156,23 @@ impl LanguageAnalyzer for JavaScriptAnalyzer {
             }
             
             // Function declaration detection
+            let mut expression_body = false;
             if self.is_function_declaration(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    current_function = Some(FunctionInfo {
+                    // An arrow function with an expression body ends on its own line
+                    expression_body = trimmed.split_once("=>").is_some_and(|(_, body)| {
+                        let body = body.trim();
+                        !body.is_empty() && !body.starts_with('{')
+                    });
+                    let counter = CognitiveCounter::new(&cognitive::JAVASCRIPT, BraceScanner::new().with_single_quote_strings(), &func_name);
+                    let parameter_count = self.count_parameters(lines, line_num, &func_name);
+                    open_functions.push((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
-                        cognitive_complexity: 1, // Base cognitive complexity
+                        cognitive_complexity: 0,
                         nesting_depth: 0,
-                        parameter_count: 0,
+                        parameter_count,
                         return_path_count: 0,
                         start_line: line_num + 1,
                         end_line: line_num + 1,
@@ -199,59 +181,51 @@ impl LanguageAnalyzer for JavaScriptAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    brace_count = 0;
+                        visibility: Visibility::Public,
+                    }, BlockTracker::new(BraceScanner::new().with_single_quote_strings()), counter));
                 }
             }
             
-            if in_function {
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    
-                    // Count braces for nesting depth
-                    let open_braces = trimmed.matches('{').count();
-                    let close_braces = trimmed.matches('}').count();
-                    brace_count += open_braces as i32 - close_braces as i32;
-                    func.nesting_depth = func.nesting_depth.max(brace_count.max(0) as usize);
-                    
-                    // Calculate cyclomatic complexity
-                    func.cyclomatic_complexity += self.count_complexity_keywords(trimmed);
-                    
-                    // Calculate cognitive complexity
-                    func.cognitive_complexity += self.count_cognitive_complexity(trimmed, brace_count);
-                    
-                    // Count parameters
-                    if trimmed.contains('(') && func.parameter_count == 0 {
-                        func.parameter_count = self.count_function_parameters(trimmed);
-                    }
-                    
-                    // Count return paths
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && !trimmed.starts_with("function ") {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for exception handling
-                    if trimmed.contains("try") || trimmed.contains("catch") || trimmed.contains("throw") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Function end detection
-                    if brace_count <= 0 && close_braces > 0 {
-                        functions.push(func.clone());
-                        current_function = None;
-                        in_function = false;
+            if let Some((func, tracker, counter)) = open_functions.last_mut() {
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                
+                // Braces in strings, template literals and comments are not counted
+                let closed = tracker.feed(trimmed) || (expression_body && !tracker.is_opened());
+                func.nesting_depth = tracker.max_depth();
+                
+                // Calculate cyclomatic complexity
+                func.cyclomatic_complexity += self.count_complexity_keywords(trimmed);
+                
+                // Calculate cognitive complexity
+                counter.feed(trimmed);
+                func.cognitive_complexity = counter.complexity();
+                
+                // Count return paths
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
+                }
+                
+                // Check for recursion
+                if trimmed.contains(&func.name) && !trimmed.starts_with("function ") {
+                    func.has_recursion = true;
+                }
+                
+                // Check for exception handling
+                if trimmed.contains("try") || trimmed.contains("catch") || trimmed.contains("throw") {
+                    func.has_exception_handling = true;
+                }
+                
+                // Function end detection
+                if closed {
+                    if let Some((func, _, _)) = open_functions.pop() {
+                        functions.push(func);
                     }
                 }
             }
         }
         
+        functions.sort_by_key(|func| func.start_line);
         Ok(functions)
     }
     
@@ -323,6 +297,10 @@ impl LanguageAnalyzer for JavaScriptAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["js", "jsx", "ts", "tsx"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_single_quote_strings()
+    }
 }
 
 impl Default for JavaScriptAnalyzer {
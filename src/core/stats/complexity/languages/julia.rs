@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, KeywordBlockTracker};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Julia language complexity analyzer
@@ -111,40 +113,18 @@ impl JuliaAnalyzer {
         complexity
     }
     
-    /// Find the end of a function definition
-    fn find_function_end(&self, lines: &[String], start_line: usize) -> usize {
-        let mut depth = 0;
-        let mut in_function = false;
+    /// Find the end of a function definition and the deepest nesting of its body
+    fn find_function_end(&self, lines: &[String], start_line: usize) -> (usize, usize) {
+        // Block keywords in strings and comments are ignored; `a[end]` indexing is not a block end
+        let mut tracker = KeywordBlockTracker::new(&["function", "begin", "let", "for", "while", "if", "try", "struct", "macro", "quote", "do", "module"], &["end"], "#");
         
         for (i, line) in lines.iter().enumerate().skip(start_line) {
-            let trimmed = line.trim();
-            
-            if trimmed.contains("function ") {
-                in_function = true;
-                depth += 1;
-            }
-            
-            if in_function {
-                // Count begin/end blocks
-                if trimmed.contains("begin") || trimmed.contains("let") || trimmed.contains("for") || 
-                   trimmed.contains("while") || trimmed.contains("if") || trimmed.contains("try") {
-                    depth += 1;
-                }
-                if trimmed == "end" || trimmed.starts_with("end ") {
-                    depth -= 1;
-                    if depth == 0 {
-                        return i;
-                    }
-                }
-                
-                // Handle single-line functions
-                if trimmed.contains("=") && !trimmed.contains("function") && depth == 1 {
-                    return i;
-                }
+            if tracker.feed(line) {
+                return (i, tracker.max_depth());
             }
         }
         
-        lines.len().saturating_sub(1)
+        (lines.len().saturating_sub(1), tracker.max_depth())
     }
     
     /// Determine visibility of a function
@@ -178,7 +158,8 @@ impl LanguageAnalyzer for JuliaAnalyzer {
         
         for (i, line) in lines.iter().enumerate() {
             if let Some(func_name) = self.extract_function_name(line) {
-                let end_line = self.find_function_end(lines, i);
+                let (end_line, nesting_depth) = self.find_function_end(lines, i);
+                let parameter_count = self.count_parameters(lines, i, &func_name);
                 let complexity = self.calculate_cyclomatic_complexity(lines, i, end_line);
                 let _visibility = self.determine_visibility(line);
                 
@@ -187,8 +168,8 @@ impl LanguageAnalyzer for JuliaAnalyzer {
                     line_count: end_line.saturating_sub(i).max(1),
                     cyclomatic_complexity: complexity,
                     cognitive_complexity: complexity,
-                    nesting_depth: 0,
-                    parameter_count: self.count_parameters(line),
+                    nesting_depth,
+                    parameter_count,
                     return_path_count: 1,
                     start_line: i + 1,
                     end_line: end_line + 1,
@@ -238,22 +219,16 @@ impl LanguageAnalyzer for JuliaAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["jl"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false)
+    }
 }
 
 impl JuliaAnalyzer {
     /// Count parameters in a function definition
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                return params.split(',').count();
-            }
-        }
-        
-        0
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, self.comment_scanner(), &parameters::UNTYPED)
     }
     
     /// Find the end of a structure definition
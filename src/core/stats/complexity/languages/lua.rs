@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, KeywordBlockTracker};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Lua language complexity analyzer
@@ -120,42 +122,18 @@ impl LuaAnalyzer {
         complexity
     }
     
-    /// Find the end of a function definition
-    fn find_function_end(&self, lines: &[String], start_line: usize) -> usize {
-        let mut depth = 0;
-        let mut in_function = false;
+    /// Find the end of a function definition and the deepest nesting of its body
+    fn find_function_end(&self, lines: &[String], start_line: usize) -> (usize, usize) {
+        // Block keywords in strings and comments are ignored; `while`/`for` loops open their block with `do`
+        let mut tracker = KeywordBlockTracker::new(&["function", "do", "if", "repeat"], &["end", "until"], "--");
         
         for (i, line) in lines.iter().enumerate().skip(start_line) {
-            let trimmed = line.trim();
-            
-            if trimmed.contains("function") {
-                in_function = true;
-                depth += 1;
-            }
-            
-            if in_function {
-                // Count function/do/if/for/while/repeat blocks
-                if trimmed.contains("function") || trimmed.contains("do") || 
-                   trimmed.contains("if") || trimmed.contains("for") || 
-                   trimmed.contains("while") || trimmed.contains("repeat") {
-                    depth += 1;
-                }
-                if trimmed == "end" || trimmed.starts_with("end ") {
-                    depth -= 1;
-                    if depth == 0 {
-                        return i;
-                    }
-                }
-                if trimmed.contains("until") {
-                    depth -= 1;
-                    if depth == 0 {
-                        return i;
-                    }
-                }
+            if tracker.feed(line) {
+                return (i, tracker.max_depth());
             }
         }
         
-        lines.len().saturating_sub(1)
+        (lines.len().saturating_sub(1), tracker.max_depth())
     }
     
     /// Determine visibility of a function
@@ -187,7 +165,8 @@ impl LanguageAnalyzer for LuaAnalyzer {
         
         for (i, line) in lines.iter().enumerate() {
             if let Some(func_name) = self.extract_function_name(line) {
-                let end_line = self.find_function_end(lines, i);
+                let (end_line, nesting_depth) = self.find_function_end(lines, i);
+                let parameter_count = self.count_parameters(lines, i, &func_name);
                 let complexity = self.calculate_cyclomatic_complexity(lines, i, end_line);
                 let _visibility = self.determine_visibility(line);
                 
@@ -196,8 +175,8 @@ impl LanguageAnalyzer for LuaAnalyzer {
                     line_count: end_line.saturating_sub(i).max(1),
                     cyclomatic_complexity: complexity,
                     cognitive_complexity: complexity,
-                    nesting_depth: 0,
-                    parameter_count: self.count_parameters(line),
+                    nesting_depth,
+                    parameter_count,
                     return_path_count: 1,
                     start_line: i + 1,
                     end_line: end_line + 1,
@@ -247,22 +226,16 @@ impl LanguageAnalyzer for LuaAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["lua"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["--"]).with_block_comments(false).with_single_quote_strings()
+    }
 }
 
 impl LuaAnalyzer {
     /// Count parameters in a function definition
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                return params.split(',').count();
-            }
-        }
-        
-        0
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, self.comment_scanner(), &parameters::UNTYPED)
     }
     
     /// Find the end of a structure definition
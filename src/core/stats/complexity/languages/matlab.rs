@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, KeywordBlockTracker};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// MATLAB language complexity analyzer
@@ -132,39 +134,8 @@ impl MatlabAnalyzer {
     }
     
     /// Count parameters in function signature
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(paren_start) = line.find('(') {
-            if let Some(paren_end) = line.find(')') {
-                let params = &line[paren_start + 1..paren_end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                
-                // Count parameters by commas
-                let mut param_count = 1;
-                let mut paren_depth = 0;
-                let mut bracket_depth = 0;
-                let mut brace_depth = 0;
-                
-                for ch in params.chars() {
-                    match ch {
-                        '(' => paren_depth += 1,
-                        ')' => paren_depth -= 1,
-                        '[' => bracket_depth += 1,
-                        ']' => bracket_depth -= 1,
-                        '{' => brace_depth += 1,
-                        '}' => brace_depth -= 1,
-                        ',' if paren_depth == 0 && bracket_depth == 0 && brace_depth == 0 => {
-                            param_count += 1;
-                        }
-                        _ => {}
-                    }
-                }
-                
-                return param_count;
-            }
-        }
-        0
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, self.comment_scanner(), &parameters::UNTYPED)
     }
     
     /// Determine visibility (MATLAB doesn't have formal visibility)
@@ -230,10 +201,7 @@ impl MatlabAnalyzer {
 impl LanguageAnalyzer for MatlabAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut in_function = false;
-        let mut nesting_level: usize = 0;
-        let mut function_end_keywords = 0;
+        let mut current_function: Option<(FunctionInfo, KeywordBlockTracker)> = None;
         
         for (line_num, line) in lines.iter().enumerate() {
             let trimmed = line.trim();
@@ -243,12 +211,22 @@ impl LanguageAnalyzer for MatlabAnalyzer {
                 continue;
             }
             
-            // Function declaration detection
-            if self.is_function_declaration(trimmed) {
+            // Functions of a file may go without `end`, each ending where the next one starts
+            if self.is_function_declaration(trimmed) && current_function.as_ref().is_some_and(|(_, tracker)| tracker.depth() == 1) {
+                if let Some((func, _)) = current_function.take() {
+                    functions.push(func);
+                }
+            }
+            
+            // Function declaration detection; a function nested in another is part of it
+            if current_function.is_none() && self.is_function_declaration(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     
-                    current_function = Some(FunctionInfo {
+                    // `end` also indexes the last element, as in `x(end)`
+                    let tracker = KeywordBlockTracker::new(&["function", "if", "for", "parfor", "while", "switch", "try", "spmd"], &["end"], "%")
+                        .with_parentheses_skipped();
+                    current_function = Some((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
@@ -263,77 +241,64 @@ impl LanguageAnalyzer for MatlabAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    nesting_level = 0;
-                    function_end_keywords = 0;
+                        visibility: Visibility::Public,}, tracker));
                 }
             }
             
-            if in_function {
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    
-                    // Track nesting level
-                    if trimmed.contains("if ") || trimmed.contains("for ") || 
-                       trimmed.contains("while ") || trimmed.contains("switch ") ||
-                       trimmed.contains("try") {
-                        nesting_level += 1;
-                    }
-                    if trimmed == "end" {
-                        if nesting_level > 0 {
-                            nesting_level -= 1;
-                        } else {
-                            function_end_keywords += 1;
-                        }
-                    }
-                    
-                    func.nesting_depth = func.nesting_depth.max(nesting_level);
-                    
-                    // Add complexity from keywords
-                    let keyword_complexity = self.count_complexity_keywords(trimmed);
-                    func.cyclomatic_complexity += keyword_complexity;
-                    
-                    // Add cognitive complexity
-                    let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
-                    func.cognitive_complexity += cognitive_complexity;
-                    
-                    // Count return statements
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && trimmed.contains('(') {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for exception handling
-                    if trimmed.contains("try") || trimmed.contains("catch") || 
-                       trimmed.contains("error(") || trimmed.contains("warning(") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Count local variables (assignments)
-                    if trimmed.contains("=") && !trimmed.contains("==") &&
-                       !trimmed.contains("~=") && !trimmed.contains("<=") && 
-                       !trimmed.contains(">=") && !trimmed.contains("function ") {
-                        func.local_variable_count += 1;
-                    }
+            if let Some((func, tracker)) = current_function.as_mut() {
+                // Track blocks by their keywords, ignoring strings, comments and indexing
+                let closed = tracker.feed(trimmed);
+                let nesting_level = tracker.depth().saturating_sub(1);
+                
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                func.nesting_depth = tracker.max_depth();
+                
+                // Add complexity from keywords
+                let keyword_complexity = self.count_complexity_keywords(trimmed);
+                func.cyclomatic_complexity += keyword_complexity;
+                
+                // Add cognitive complexity
+                let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
+                func.cognitive_complexity += cognitive_complexity;
+                
+                // Count return statements
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
                 }
                 
-                // End of function detection (MATLAB functions end with "end")
-                if function_end_keywords > 0 && nesting_level == 0 {
-                    if let Some(func) = current_function.take() {
+                // Check for recursion
+                if trimmed.contains(&func.name) && trimmed.contains('(') {
+                    func.has_recursion = true;
+                }
+                
+                // Check for exception handling
+                if trimmed.contains("try") || trimmed.contains("catch") || 
+                   trimmed.contains("error(") || trimmed.contains("warning(") {
+                    func.has_exception_handling = true;
+                }
+                
+                // Count local variables (assignments)
+                if trimmed.contains("=") && !trimmed.contains("==") &&
+                   !trimmed.contains("~=") && !trimmed.contains("<=") && 
+                   !trimmed.contains(">=") && !trimmed.contains("function ") {
+                    func.local_variable_count += 1;
+                }
+                
+                // End of function
+                if closed {
+                    if let Some((func, _)) = current_function.take() {
                         functions.push(func);
                     }
-                    in_function = false;
-                    function_end_keywords = 0;
                 }
             }
         }
         
+        // A function without `end` runs to the end of the file
+        if let Some((func, _)) = current_function {
+            functions.push(func);
+        }
+        
         Ok(functions)
     }
     
@@ -399,7 +364,7 @@ impl LanguageAnalyzer for MatlabAnalyzer {
             // Add functions to the script structure
             if self.is_function_declaration(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     
                     let method_info = FunctionInfo {
                         name: func_name,
@@ -439,6 +404,10 @@ impl LanguageAnalyzer for MatlabAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["m"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["%"]).with_block_comments(false).with_single_quote_strings()
+    }
 }
 
 impl Default for MatlabAnalyzer {
@@ -1,5 +1,10 @@
 use crate::utils::errors::Result;
 use super::types::{FunctionInfo, StructureInfo};
+use blocks::BraceScanner;
+
+pub mod blocks;
+pub mod cognitive;
+pub mod parameters;
 
 // Language-specific modules
 pub mod rust;
@@ -24,6 +29,14 @@ pub mod lua;
 pub mod zig;
 pub mod clojure;
 pub mod haskell;
+pub mod hcl;
+pub mod protobuf;
+pub mod graphql;
+pub mod nix;
+pub mod assembly;
+pub mod verilog;
+pub mod vhdl;
+pub mod objc;
 
 /// Common trait for all language-specific complexity analyzers
 pub trait LanguageAnalyzer {
@@ -38,6 +51,29 @@ pub trait LanguageAnalyzer {
     
     /// Get the file extensions this analyzer supports
     fn supported_extensions(&self) -> Vec<&'static str>;
+    
+    /// Scanner that leaves the comments out of a line, for Halstead's measures.
+    /// C-family comments unless the language has its own.
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new()
+    }
+}
+
+/// Whether functions and structures of an extension's language are analyzed.
+/// Languages without an analyzer (SQL, YAML, HTML, ...) are still counted.
+pub fn has_language_analyzer(extension: &str) -> bool {
+    get_language_analyzer(&extension.to_lowercase()).is_some()
+}
+
+/// Extension whose comment patterns and analyzer apply to a file, for extensions
+/// shared by several languages: a `.m` file is MATLAB unless its lines are
+/// Objective-C, which is handled as `mm`
+pub fn resolve_extension<'a, 'l>(extension: &'a str, lines: impl IntoIterator<Item = &'l str>) -> &'a str {
+    if extension == "m" && objc::is_objective_c(lines) {
+        "mm"
+    } else {
+        extension
+    }
 }
 
 /// Factory function to get the appropriate language analyzer
@@ -47,7 +83,7 @@ pub fn get_language_analyzer(extension: &str) -> Option<Box<dyn LanguageAnalyzer
         "py" => Some(Box::new(python::PythonAnalyzer::new())),
         "js" | "jsx" | "ts" | "tsx" => Some(Box::new(javascript::JavaScriptAnalyzer::new())),
         "java" => Some(Box::new(java::JavaAnalyzer::new())),
-        "cpp" | "cc" | "cxx" | "c" | "h" | "hpp" => Some(Box::new(cpp::CppAnalyzer::new())),
+        "cpp" | "cc" | "cxx" | "c" | "h" | "hpp" | "cu" | "cuh" => Some(Box::new(cpp::CppAnalyzer::new())),
         "go" => Some(Box::new(go::GoAnalyzer::new())),
         "cs" => Some(Box::new(csharp::CSharpAnalyzer::new())),
         "php" => Some(Box::new(php::PhpAnalyzer::new())),
@@ -65,6 +101,16 @@ pub fn get_language_analyzer(extension: &str) -> Option<Box<dyn LanguageAnalyzer
         "zig" => Some(Box::new(zig::ZigAnalyzer::new())),
         "clj" | "cljs" | "cljc" | "edn" => Some(Box::new(clojure::ClojureAnalyzer::new())),
         "hs" | "lhs" => Some(Box::new(haskell::HaskellAnalyzer::new())),
-        _ => None,
+        "tf" | "hcl" => Some(Box::new(hcl::HclAnalyzer::new())),
+        "proto" => Some(Box::new(protobuf::ProtobufAnalyzer::new())),
+        "graphql" | "gql" => Some(Box::new(graphql::GraphqlAnalyzer::new())),
+        "nix" => Some(Box::new(nix::NixAnalyzer::new())),
+        "s" | "asm" => Some(Box::new(assembly::AssemblyAnalyzer::new())),
+        "v" | "vh" | "sv" | "svh" => Some(Box::new(verilog::VerilogAnalyzer::new())),
+        "vhd" | "vhdl" => Some(Box::new(vhdl::VhdlAnalyzer::new())),
+        "mm" => Some(Box::new(objc::ObjectiveCAnalyzer::new())),
+        _ => crate::core::plugins::analyzer(extension),
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,146 @@
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, Visibility};
+use super::blocks::{count_keywords, BraceScanner};
+use super::LanguageAnalyzer;
+
+/// Nix complexity analyzer: attributes bound to a lambda (`mkApp = { pname, version }: ...;`)
+/// are the functions, with `if`, `assert`, `or` defaults and boolean operators as branches.
+/// Nix has no structures of its own; attribute sets are values.
+pub struct NixAnalyzer;
+
+impl NixAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Name and parameter count of an attribute bound to a lambda, from its
+    /// binding line; curried lambdas (`add = a: b: a + b;`) take several parameters
+    fn lambda_binding<'a>(&self, line: &'a str) -> Option<(&'a str, usize)> {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            return None;
+        }
+        let (name, mut value) = trimmed.split_once(" = ").or_else(|| trimmed.split_once('='))?;
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '\'' | '"')) {
+            return None;
+        }
+
+        let mut parameters = 0;
+        loop {
+            value = value.trim_start();
+            // `args@{ ... }:` names the whole argument set too
+            let (binding, rest) = value.split_at(value.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '\'')).unwrap_or(value.len()));
+            let rest = rest.strip_prefix('@').unwrap_or(rest).trim_start();
+
+            if let Some(formals) = rest.strip_prefix('{') {
+                let Some((formals, after)) = formals.split_once('}') else {
+                    break;
+                };
+                let after = after.trim_start();
+                let after = after.strip_prefix('@').map_or(after, |named| named.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_').trim_start());
+                let Some(body) = after.strip_prefix(':') else {
+                    break;
+                };
+                parameters += formals.split(',').map(|formal| formal.trim()).filter(|formal| !formal.is_empty() && *formal != "...").count();
+                value = body;
+            } else if !binding.is_empty() && rest.starts_with(':') && rest[1..].chars().next().is_none_or(char::is_whitespace) {
+                parameters += 1;
+                value = &rest[1..];
+            } else {
+                break;
+            }
+        }
+
+        (parameters > 0).then_some((name, parameters))
+    }
+
+    /// Line of the `;` ending a binding, outside any braces and `let ... in`,
+    /// and the deepest nesting of those in it, the binding being level 1.
+    /// The `;` of `assert cond;` and `with pkgs;` only start the body.
+    fn find_binding_end(&self, lines: &[String], start_line: usize) -> (usize, usize) {
+        let mut scanner = BraceScanner::new().with_line_comments(&["#"]);
+        let mut depth = 0usize;
+        let mut lets = 0usize;
+        let mut prefixes = 0usize;
+        let mut deepest = 0;
+
+        for (index, line) in lines.iter().enumerate().skip(start_line) {
+            let count = scanner.scan(line);
+            depth = (depth + count.open).saturating_sub(count.close);
+            let code = line.split('#').next().unwrap_or("");
+            lets = (lets + count_keywords(code, &["let"])).saturating_sub(count_keywords(code, &["in"]));
+            prefixes += count_keywords(code, &["assert", "with"]);
+            deepest = deepest.max(depth + lets);
+            if depth == 0 && lets == 0 && count.ends_statement {
+                if prefixes == 0 {
+                    return (index, deepest + 1);
+                }
+                prefixes -= 1;
+            }
+        }
+
+        (lines.len().saturating_sub(1), deepest + 1)
+    }
+
+    fn count_branches(&self, line: &str) -> usize {
+        let code = line.split('#').next().unwrap_or("");
+        count_keywords(code, &["if", "assert", "or"]) + code.matches("&&").count() + code.matches("||").count()
+    }
+}
+
+impl LanguageAnalyzer for NixAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some((name, parameter_count)) = self.lambda_binding(line) else {
+                continue;
+            };
+            let (end_line, nesting_depth) = self.find_binding_end(lines, index);
+            let complexity = 1 + lines[index..=end_line].iter().map(|line| self.count_branches(line)).sum::<usize>();
+
+            functions.push(FunctionInfo {
+                name: name.trim_matches('"').to_string(),
+                line_count: end_line - index + 1,
+                cyclomatic_complexity: complexity,
+                cognitive_complexity: complexity,
+                nesting_depth,
+                parameter_count,
+                return_path_count: 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                is_method: false,
+                parent_class: None,
+                local_variable_count: 0,
+                has_recursion: false,
+                has_exception_handling: false,
+                visibility: Visibility::Public,
+            });
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, _lines: &[String]) -> Result<Vec<StructureInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Nix"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["nix"]
+    }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#"]).with_single_quote_strings()
+    }
+}
+
+impl Default for NixAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,257 @@
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{count_keywords, is_control_statement, BlockTracker, BraceScanner};
+use super::LanguageAnalyzer;
+
+/// Line starts that only Objective-C sources have: preprocessor directives,
+/// `@` directives and C comments. MATLAB comments start with `%` instead.
+const OBJC_MARKERS: &[&str] = &[
+    "#import", "#include", "#define", "#if", "#pragma", "@interface", "@implementation", "@protocol",
+    "@end", "@property", "@class", "@synthesize", "//", "/*",
+];
+
+/// Whether the lines of a `.m` file are Objective-C rather than MATLAB. The
+/// first line that only one of the languages could start decides; files with
+/// none, like a MATLAB script without comments, are MATLAB.
+pub fn is_objective_c<'l>(lines: impl IntoIterator<Item = &'l str>) -> bool {
+    for line in lines {
+        let trimmed = line.trim_start();
+        if OBJC_MARKERS.iter().any(|marker| trimmed.starts_with(marker)) || is_method_start(trimmed) {
+            return true;
+        }
+        let word = trimmed.split(|c: char| !c.is_alphanumeric()).next().unwrap_or("");
+        if trimmed.starts_with('%') || matches!(word, "function" | "classdef") {
+            return false;
+        }
+    }
+    false
+}
+
+/// `- (void)run {` or `+ (instancetype)shared;`
+fn is_method_start(trimmed: &str) -> bool {
+    trimmed.strip_prefix(['-', '+']).is_some_and(|rest| rest.trim_start().starts_with('('))
+}
+
+/// Objective-C complexity analyzer: methods and C functions are the functions;
+/// `@implementation`s and `@protocol`s the structures
+pub struct ObjectiveCAnalyzer;
+
+impl ObjectiveCAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn code<'a>(&self, line: &'a str) -> &'a str {
+        line.split("//").next().unwrap_or("")
+    }
+
+    /// Selector and parameter count of a method signature:
+    /// `- (void)move:(int)x to:(int)y {` is `move:to:` with 2 parameters
+    fn selector(&self, line: &str) -> Option<(String, usize)> {
+        let trimmed = line.trim_start();
+        if !is_method_start(trimmed) {
+            return None;
+        }
+        let signature = trimmed[1..].split(['{', ';']).next().unwrap_or("");
+        let mut selector = String::new();
+        let mut parameters = 0;
+        let mut depth = 0usize;
+        let mut part = String::new();
+        // Words outside the `(type)`s alternate between selector parts and parameter names
+        for c in signature.chars() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ':' if depth == 0 => {
+                    selector.push_str(part.trim());
+                    selector.push(':');
+                    part.clear();
+                    parameters += 1;
+                }
+                _ if depth == 0 && c.is_whitespace() && parameters > 0 => part.clear(),
+                _ if depth == 0 && (c.is_alphanumeric() || c == '_' || c.is_whitespace()) => part.push(c),
+                _ => {}
+            }
+        }
+        if parameters == 0 {
+            selector = part.trim().to_string();
+        }
+        (!selector.is_empty()).then_some((selector, parameters))
+    }
+
+    /// Name and parameter count of a C function definition at the top level (`static int clamp(int v) {`)
+    fn c_function(&self, line: &str) -> Option<(String, usize)> {
+        let code = self.code(line).trim();
+        if code.starts_with(['#', '@', '-', '+', '}', '/', '*']) || code.ends_with(';') || code.contains('=') || is_control_statement(code) {
+            return None;
+        }
+        let (before, after) = code.split_once('(')?;
+        // A return type comes before the name
+        let mut words = before.split_whitespace();
+        let name = words.next_back()?.trim_start_matches('*');
+        if words.next().is_none() || name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        let arguments = after.rsplit_once(')').map_or(after, |(arguments, _)| arguments).trim();
+        let parameters = if arguments.is_empty() || arguments == "void" { 0 } else { arguments.split(',').count() };
+        Some((name.to_string(), parameters))
+    }
+
+    /// Name of an `@implementation`, `@interface` or `@protocol` opened on the
+    /// line, with categories named `Class(Category)`
+    fn container(&self, line: &str, directive: &str) -> Option<String> {
+        let rest = self.code(line).trim().strip_prefix(directive)?;
+        if !rest.starts_with(char::is_whitespace) || rest.trim_end().ends_with(';') {
+            return None;
+        }
+        let header = rest.split(['{', ':', '<']).next().unwrap_or(rest);
+        let name: String = header.chars().filter(|c| !c.is_whitespace()).collect();
+        // Class extensions (`@interface Shape ()`) belong to the class
+        let name = name.strip_suffix("()").map_or(name.clone(), str::to_string);
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Line of the `@end` closing a container opened on `start_line`
+    fn find_end_directive(&self, lines: &[String], start_line: usize) -> usize {
+        (start_line + 1..lines.len())
+            .find(|index| self.code(&lines[*index]).trim() == "@end")
+            .unwrap_or(lines.len().saturating_sub(1))
+    }
+
+    /// Last line of a body starting at `start_line`, and its deepest nesting,
+    /// the body's own braces being level 1. `None` for declarations without a body.
+    fn find_body_end(&self, lines: &[String], start_line: usize) -> Option<(usize, usize)> {
+        let mut tracker = BlockTracker::new(BraceScanner::new());
+        for (index, line) in lines.iter().enumerate().skip(start_line) {
+            if tracker.feed(line) {
+                return tracker.is_opened().then_some((index, tracker.max_depth()));
+            }
+        }
+        None
+    }
+
+    fn count_branches(&self, line: &str) -> usize {
+        let code = self.code(line);
+        count_keywords(code, &["if", "for", "while", "case", "catch"])
+            + code.matches('?').count()
+            + code.matches("&&").count()
+            + code.matches("||").count()
+    }
+
+    fn function_info(&self, lines: &[String], index: usize, name: String, parameter_count: usize, parent: Option<&str>) -> Option<FunctionInfo> {
+        let (end_line, nesting_depth) = self.find_body_end(lines, index)?;
+        let body = &lines[index..=end_line];
+        let complexity = 1 + body.iter().map(|line| self.count_branches(line)).sum::<usize>();
+        let is_static = self.code(&lines[index]).trim_start().starts_with("static ");
+        Some(FunctionInfo {
+            name,
+            line_count: end_line - index + 1,
+            cyclomatic_complexity: complexity,
+            cognitive_complexity: complexity + nesting_depth.saturating_sub(1),
+            nesting_depth,
+            parameter_count,
+            return_path_count: body.iter().map(|line| count_keywords(self.code(line), &["return"])).sum::<usize>().max(1),
+            start_line: index + 1,
+            end_line: end_line + 1,
+            is_method: parent.is_some(),
+            parent_class: parent.map(str::to_string),
+            local_variable_count: 0,
+            has_recursion: false,
+            has_exception_handling: body.iter().any(|line| line.contains("@try")),
+            visibility: if is_static { Visibility::Private } else { Visibility::Public },
+        })
+    }
+}
+
+impl LanguageAnalyzer for ObjectiveCAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let mut implementation: Option<(String, usize)> = None;
+        let mut index = 0;
+
+        while index < lines.len() {
+            let line = &lines[index];
+            if let Some(name) = self.container(line, "@implementation") {
+                implementation = Some((name, self.find_end_directive(lines, index)));
+            }
+            let parent = implementation.as_ref().filter(|(_, end)| index <= *end).map(|(name, _)| name.as_str());
+
+            // Method declarations of an `@interface` have no body, so only implemented methods are found
+            let declaration = self
+                .selector(line)
+                .map(|(name, parameters)| (name, parameters, parent))
+                .or_else(|| self.c_function(line).map(|(name, parameters)| (name, parameters, None)));
+            match declaration.and_then(|(name, parameters, parent)| self.function_info(lines, index, name, parameters, parent)) {
+                // Blocks and nested functions inside a body are part of it
+                Some(function) => {
+                    index = function.end_line;
+                    functions.push(function);
+                }
+                None => index += 1,
+            }
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, lines: &[String]) -> Result<Vec<StructureInfo>> {
+        let functions = self.analyze_functions(lines)?;
+        let mut structures = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let (name, structure_type) = if let Some(name) = self.container(line, "@implementation") {
+                (name, StructureType::Class)
+            } else if let Some(name) = self.container(line, "@protocol") {
+                (name, StructureType::Interface)
+            } else {
+                continue;
+            };
+            let end_line = self.find_end_directive(lines, index);
+
+            // Properties, superclass and protocols are declared by the class's `@interface`s
+            // in the same file, such as a class extension, or by the protocol itself
+            let headers: Vec<usize> = (0..lines.len())
+                .filter(|start| *start == index || self.container(&lines[*start], "@interface").as_deref() == Some(name.as_str()))
+                .collect();
+            let properties = headers
+                .iter()
+                .map(|start| lines[*start..=self.find_end_directive(lines, *start)].iter().filter(|line| line.trim_start().starts_with("@property")).count())
+                .sum();
+            let header_code: Vec<&str> = headers.iter().map(|start| self.code(&lines[*start])).collect();
+            let protocols = header_code
+                .iter()
+                .filter_map(|code| code.split_once('<'))
+                .map(|(_, list)| list.split('>').next().unwrap_or("").split(',').filter(|protocol| !protocol.trim().is_empty()).count())
+                .sum();
+
+            structures.push(StructureInfo {
+                methods: functions.iter().filter(|function| function.parent_class.as_deref() == Some(name.as_str())).cloned().collect(),
+                name,
+                structure_type,
+                line_count: end_line - index + 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                properties,
+                visibility: Visibility::Public,
+                inheritance_depth: usize::from(header_code.iter().any(|code| code.split('<').next().unwrap_or("").contains(':'))),
+                interface_count: protocols,
+            });
+        }
+
+        Ok(structures)
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Objective-C"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["m", "mm"]
+    }
+}
+
+impl Default for ObjectiveCAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,256 @@
+use super::blocks::BraceScanner;
+
+/// Lines a parameter list may run over before the declaration is taken to have none
+const MAX_SIGNATURE_LINES: usize = 64;
+
+/// How a language writes the parameter list of a declaration
+#[derive(Debug)]
+pub struct ParameterSyntax {
+    /// `<...>` holds type parameters and arguments, whose commas don't separate parameters
+    pub generics: bool,
+    /// Receivers written in the list, which aren't parameters: `self`, `this`
+    pub receivers: &'static [&'static str],
+    /// Entries that mark the kind of the parameters around them rather than
+    /// being one, such as Python's bare `*` and C's `(void)`
+    pub markers: &'static [&'static str],
+    /// `{...}` and `[...]` group named and optional parameters rather than
+    /// destructure one (Dart)
+    pub optional_groups: bool,
+    /// The list directly follows the name; otherwise it is the first list
+    /// opened after it, as in `name = function(a, b)`
+    pub adjacent: bool,
+}
+
+pub const C_FAMILY: ParameterSyntax = ParameterSyntax {
+    generics: true,
+    receivers: &[],
+    markers: &["void"],
+    optional_groups: false,
+    adjacent: false,
+};
+
+pub const RUST: ParameterSyntax = ParameterSyntax {
+    generics: true,
+    receivers: &["self"],
+    markers: &[],
+    optional_groups: false,
+    adjacent: false,
+};
+
+pub const PYTHON: ParameterSyntax = ParameterSyntax {
+    generics: false,
+    receivers: &["self", "cls"],
+    markers: &["*", "/"],
+    optional_groups: false,
+    adjacent: true,
+};
+
+pub const JAVASCRIPT: ParameterSyntax = ParameterSyntax {
+    generics: true,
+    // TypeScript's `this: Window` types the receiver
+    receivers: &["this"],
+    markers: &[],
+    optional_groups: false,
+    adjacent: false,
+};
+
+pub const DART: ParameterSyntax = ParameterSyntax {
+    generics: true,
+    receivers: &[],
+    markers: &[],
+    optional_groups: true,
+    adjacent: false,
+};
+
+/// Languages without type arguments in their parameter lists: Go, PHP, R,
+/// MATLAB, Lua, Julia, Zig, Erlang
+pub const UNTYPED: ParameterSyntax = ParameterSyntax {
+    generics: false,
+    receivers: &[],
+    markers: &[],
+    optional_groups: false,
+    adjacent: false,
+};
+
+/// Perl, whose methods take the invocant as their first parameter
+pub const PERL: ParameterSyntax = ParameterSyntax {
+    generics: false,
+    receivers: &["$self", "$class"],
+    markers: &[],
+    optional_groups: false,
+    adjacent: false,
+};
+
+/// Ruby and Elixir, whose parentheses are optional: a list must follow the name
+pub const BARE: ParameterSyntax = ParameterSyntax {
+    generics: false,
+    receivers: &[],
+    markers: &[],
+    optional_groups: false,
+    adjacent: true,
+};
+
+/// Parameters of the declaration of `name` on `lines[index]`, whose list may
+/// run over the following lines. The list opens on the declaration line, after
+/// `name` when the line contains it. `scanner` leaves out comments and empties
+/// literals, so commas in default values such as `sep=", "` don't count.
+/// A declaration without a list has no parameters.
+pub fn count(lines: &[String], index: usize, name: &str, mut scanner: BraceScanner, syntax: &ParameterSyntax) -> usize {
+    let mut code = String::new();
+    for line in lines.iter().skip(index).take(MAX_SIGNATURE_LINES) {
+        code.push_str(&scanner.code(line));
+        code.push('\n');
+    }
+
+    let declaration_end = code.find('\n').unwrap_or(code.len());
+    let after_name = find_word(&code[..declaration_end], name).map_or(0, |at| at + name.len());
+    let Some(open) = list_start(&code[after_name..declaration_end], syntax) else {
+        return 0;
+    };
+    count_list(&code[after_name + open + 1..], syntax)
+}
+
+/// Parameters of a declaration whose list may go without parentheses, as Ruby's
+/// `def greet name, greeting` and Elixir's `def add a, b do`: without them, the
+/// entries after `name` on its line up to the first of the words in `ends`
+pub fn count_bare(lines: &[String], index: usize, name: &str, scanner: BraceScanner, ends: &[&str]) -> usize {
+    let code = scanner.clone().code(&lines[index]);
+    let Some(at) = find_word(&code, name) else {
+        return 0;
+    };
+    let rest = &code[at + name.len()..];
+    if rest.trim_start().starts_with('(') {
+        return count(lines, index, name, scanner, &BARE);
+    }
+    let end = ends.iter().filter_map(|end| find_word(rest, end)).min().unwrap_or(rest.len());
+    count_list(&rest[..end], &BARE)
+}
+
+/// Parameters in `list`, up to the bracket closing it or its end: the entries
+/// between top-level commas, other than receivers and markers
+pub fn count_list(list: &str, syntax: &ParameterSyntax) -> usize {
+    let chars: Vec<char> = list.chars().collect();
+    let mut entries = Vec::new();
+    let mut entry = String::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            // Arrows of function types and lambdas, not closing angle brackets
+            '-' | '=' if chars.get(i + 1) == Some(&'>') => {
+                entry.push(c);
+                entry.push('>');
+                i += 2;
+                continue;
+            }
+            '{' | '[' | '}' | ']' if syntax.optional_groups && depth == 0 => {
+                i += 1;
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            '<' if syntax.generics => depth += 1,
+            ')' | ']' | '}' if depth == 0 => break,
+            ')' | ']' | '}' => depth -= 1,
+            '>' if syntax.generics && depth > 0 => depth -= 1,
+            ',' if depth == 0 => {
+                entries.push(std::mem::take(&mut entry));
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        entry.push(c);
+        i += 1;
+    }
+    entries.push(entry);
+
+    entries.iter().filter(|entry| is_parameter(entry, syntax)).count()
+}
+
+/// Offset of the bracket opening the list in the declaration after the name,
+/// skipping type parameters such as Rust's `fn parse<T: FromStr>(`
+fn list_start(declaration: &str, syntax: &ParameterSyntax) -> Option<usize> {
+    let mut rest = declaration.trim_start();
+    if syntax.generics && rest.starts_with('<') {
+        let mut depth = 0;
+        let close = rest.char_indices().find(|&(_, c)| {
+            depth += match c {
+                '<' => 1,
+                '>' => -1,
+                _ => 0,
+            };
+            depth == 0
+        })?;
+        rest = rest[close.0 + 1..].trim_start();
+    }
+
+    let skipped = declaration.len() - rest.len();
+    if rest.starts_with('(') {
+        Some(skipped)
+    } else if syntax.adjacent {
+        None
+    } else {
+        rest.find('(').map(|open| skipped + open)
+    }
+}
+
+/// Whether an entry of a list declares a parameter
+fn is_parameter(entry: &str, syntax: &ParameterSyntax) -> bool {
+    let entry = entry.trim();
+    if entry.is_empty() || syntax.markers.contains(&entry) {
+        return false;
+    }
+    // `&self`, `&'a mut self`, `mut self: Box<Self>`
+    let receiver = entry
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '&' || c == '*')
+        .find(|word| !word.is_empty() && *word != "mut" && !word.starts_with('\''));
+    !receiver.is_some_and(|word| syntax.receivers.contains(&word))
+}
+
+/// Byte offset of `word` in `code` where it isn't part of a longer identifier
+fn find_word(code: &str, word: &str) -> Option<usize> {
+    if word.is_empty() {
+        return None;
+    }
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    code.match_indices(word).map(|(at, _)| at).find(|&at| {
+        !code[..at].chars().next_back().is_some_and(is_identifier) && !code[at + word.len()..].chars().next().is_some_and(is_identifier)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(source: &str, name: &str, syntax: &ParameterSyntax) -> usize {
+        let lines: Vec<String> = source.lines().map(String::from).collect();
+        count(&lines, 0, name, BraceScanner::new().with_single_quote_strings(), syntax)
+    }
+
+    #[test]
+    fn test_lists_over_several_lines() {
+        let source = "pub fn connect<T: Into<String>>(\n    &mut self,\n    host: T, // (primary)\n    ports: HashMap<u16, Vec<u8>>,\n    retry: impl Fn(u32, u32) -> bool,\n) -> Result<(), Error> {";
+        assert_eq!(parameters(source, "connect", &RUST), 3);
+        assert_eq!(parameters("fn area(&self) -> f64 {", "area", &RUST), 0);
+        assert_eq!(parameters("fn main() {\n    run(a, b);\n}", "main", &RUST), 0);
+    }
+
+    #[test]
+    fn test_defaults_receivers_and_markers() {
+        assert_eq!(parameters("def join(self, parts, sep=', ', *, strict=(True, False), **kw):", "join", &PYTHON), 4);
+        assert_eq!(parameters("int main(void) {", "main", &C_FAMILY), 0);
+        assert_eq!(parameters("func (s *Server) Serve(l net.Listener, opts ...Option) (int, error) {", "Serve", &UNTYPED), 2);
+        assert_eq!(parameters("const render = async ({ title, body }, [first], ...rest) => {", "render", &JAVASCRIPT), 3);
+        assert_eq!(parameters("void paint(Canvas c, {Color? fill, double width = 1}) {", "paint", &DART), 3);
+        // Parentheses are optional in Ruby, so a call after the name isn't the list
+        assert_eq!(parameters("def reset", "reset", &BARE), 0);
+
+        let lines = |source: &str| vec![source.to_string()];
+        assert_eq!(count_bare(&lines("def greet name, greeting = \"hi, you\""), 0, "greet", BraceScanner::new(), &[]), 2);
+        assert_eq!(count_bare(&lines("def add a, b when is_integer(a) do"), 0, "add", BraceScanner::new(), &["do", "when"]), 2);
+        assert_eq!(count_bare(&lines("def reset, do: :ok"), 0, "reset", BraceScanner::new(), &["do", "when"]), 0);
+        assert_eq!(count_bare(&lines("def reset() do"), 0, "reset", BraceScanner::new(), &["do", "when"]), 0);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BlockTracker, BraceScanner};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Perl language complexity analyzer
@@ -150,46 +152,22 @@ impl PerlAnalyzer {
     }
     
     /// Count parameters in function signature
-    fn count_parameters(&self, line: &str) -> usize {
-        // Perl functions often use @_ for parameters, but we can try to count declared params
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                
-                // Count parameters by commas
-                let mut param_count = 1;
-                let mut paren_depth = 0;
-                let mut bracket_depth = 0;
-                let mut brace_depth = 0;
-                
-                for ch in params.chars() {
-                    match ch {
-                        '(' => paren_depth += 1,
-                        ')' => paren_depth -= 1,
-                        '[' => bracket_depth += 1,
-                        ']' => bracket_depth -= 1,
-                        '{' => brace_depth += 1,
-                        '}' => brace_depth -= 1,
-                        ',' if paren_depth == 0 && bracket_depth == 0 && brace_depth == 0 => {
-                            param_count += 1;
-                        }
-                        _ => {}
-                    }
-                }
-                
-                return param_count;
-            }
-        }
-        
-        // If no parentheses, check for @_ usage (common in Perl)
-        if line.contains("@_") {
-            return 1; // Assume at least one parameter
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count(lines, index, name, BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false).with_single_quote_strings(), &parameters::PERL)
+    }
+    
+    /// Count parameters a sub without a signature unpacks from `@_`, as in
+    /// `my ($self, $path) = @_;` or `my $path = shift;`
+    fn count_unpacked_parameters(&self, line: &str) -> usize {
+        let Some((names, source)) = line.strip_prefix("my ").and_then(|rest| rest.split_once('=')) else {
+            return 0;
+        };
+        match source.trim().trim_end_matches(';').trim() {
+            // `my $count = @_;` takes the number of arguments
+            "@_" => names.trim().strip_prefix('(').map_or(0, |list| parameters::count_list(list, &parameters::PERL)),
+            "shift" | "shift @_" | "shift(@_)" if !matches!(names.trim(), "$self" | "$class") => 1,
+            _ => 0,
         }
-        
-        0
     }
     
     /// Determine visibility (Perl doesn't have formal visibility)
@@ -232,10 +210,8 @@ impl PerlAnalyzer {
 impl LanguageAnalyzer for PerlAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut brace_count = 0;
-        let mut in_function = false;
-        let mut nesting_level: usize = 0;
+        // Functions whose body is still open, innermost last
+        let mut open_functions: Vec<(FunctionInfo, BlockTracker)> = Vec::new();
         
         for (line_num, line) in lines.iter().enumerate() {
             let trimmed = line.trim();
@@ -248,10 +224,10 @@ impl LanguageAnalyzer for PerlAnalyzer {
             // Function declaration detection
             if self.is_function_declaration(trimmed) {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &func_name);
                     let _visibility = self.get_visibility(trimmed);
                     
-                    current_function = Some(FunctionInfo {
+                    open_functions.push((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
@@ -266,73 +242,62 @@ impl LanguageAnalyzer for PerlAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    brace_count = 0;
-                    nesting_level = 0;
+                        visibility: Visibility::Public,
+                    }, BlockTracker::new(BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false).with_single_quote_strings())));
                 }
             }
             
-            if in_function {
-                // Count braces to track function scope
-                brace_count += line.matches('{').count() as i32;
-                brace_count -= line.matches('}').count() as i32;
+            if let Some((func, tracker)) = open_functions.last_mut() {
+                // Count braces to track function scope, ignoring strings and comments
+                let closed = tracker.feed(line);
+                let nesting_level = tracker.depth();
+                
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                func.nesting_depth = tracker.max_depth();
+                
+                // Add complexity from keywords
+                let keyword_complexity = self.count_complexity_keywords(trimmed);
+                func.cyclomatic_complexity += keyword_complexity;
+                
+                // Add cognitive complexity
+                let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
+                func.cognitive_complexity += cognitive_complexity;
                 
-                // Track nesting level
-                if line.contains('{') {
-                    nesting_level += 1;
+                // Count return statements
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
                 }
-                if line.contains('}') {
-                    nesting_level = nesting_level.saturating_sub(1);
+                
+                // Check for recursion
+                if trimmed.contains(&func.name) && trimmed.contains('(') {
+                    func.has_recursion = true;
                 }
                 
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    func.nesting_depth = func.nesting_depth.max(nesting_level);
-                    
-                    // Add complexity from keywords
-                    let keyword_complexity = self.count_complexity_keywords(trimmed);
-                    func.cyclomatic_complexity += keyword_complexity;
-                    
-                    // Add cognitive complexity
-                    let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
-                    func.cognitive_complexity += cognitive_complexity;
-                    
-                    // Count return statements
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && trimmed.contains('(') {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for exception handling
-                    if trimmed.contains("eval") || trimmed.contains("die") || 
-                       trimmed.contains("warn") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Count local variables (my, our, local declarations)
-                    if trimmed.contains("my ") || trimmed.contains("our ") || 
-                       trimmed.contains("local ") {
-                        func.local_variable_count += 1;
-                    }
+                // Check for exception handling
+                if trimmed.contains("eval") || trimmed.contains("die") || 
+                   trimmed.contains("warn") {
+                    func.has_exception_handling = true;
+                }
+                
+                func.parameter_count += self.count_unpacked_parameters(trimmed);
+                
+                // Count local variables (my, our, local declarations)
+                if trimmed.contains("my ") || trimmed.contains("our ") || 
+                   trimmed.contains("local ") {
+                    func.local_variable_count += 1;
                 }
                 
                 // End of function
-                if brace_count <= 0 && in_function {
-                    if let Some(func) = current_function.take() {
+                if closed {
+                    if let Some((func, _)) = open_functions.pop() {
                         functions.push(func);
                     }
-                    in_function = false;
-                    nesting_level = 0;
                 }
             }
         }
         
+        functions.sort_by_key(|func| func.start_line);
         Ok(functions)
     }
     
@@ -391,7 +356,7 @@ impl LanguageAnalyzer for PerlAnalyzer {
                 
                 if self.is_function_declaration(trimmed) {
                     if let Some(func_name) = self.extract_function_name(trimmed) {
-                        let param_count = self.count_parameters(trimmed);
+                        let param_count = self.count_parameters(lines, line_num, &func_name);
                         let _visibility = self.get_visibility(trimmed);
                         
                         let method_info = FunctionInfo {
@@ -440,7 +405,7 @@ impl LanguageAnalyzer for PerlAnalyzer {
                 
                 if self.is_function_declaration(trimmed) {
                     if let Some(func_name) = self.extract_function_name(trimmed) {
-                        let param_count = self.count_parameters(trimmed);
+                        let param_count = self.count_parameters(lines, line_num, &func_name);
                         let _visibility = self.get_visibility(trimmed);
                         
                         let method_info = FunctionInfo {
@@ -478,6 +443,10 @@ impl LanguageAnalyzer for PerlAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["pl", "pm", "perl"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false).with_single_quote_strings()
+    }
 }
 
 impl Default for PerlAnalyzer {
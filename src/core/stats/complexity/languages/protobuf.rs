@@ -0,0 +1,154 @@
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BlockTracker, BraceScanner};
+use super::LanguageAnalyzer;
+
+/// Protocol Buffers complexity analyzer: `rpc` methods are the functions, and
+/// messages, enums and services the structures
+pub struct ProtobufAnalyzer;
+
+impl ProtobufAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Keyword and name of a declaration (`message User {`, `rpc GetUser (GetUserRequest) ...`)
+    fn declaration<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str)> {
+        let mut words = line.trim().splitn(2, char::is_whitespace);
+        let keyword = words.next()?;
+        if !matches!(keyword, "message" | "enum" | "service" | "rpc") {
+            return None;
+        }
+        let name = words.next()?.trim_start().split(|c: char| !c.is_alphanumeric() && c != '_').next()?;
+        (!name.is_empty()).then_some((keyword, name))
+    }
+
+    /// Line where a declaration's body closes, or the declaration itself when it has none
+    fn find_end(&self, lines: &[String], start_line: usize) -> usize {
+        let mut tracker = BlockTracker::new(BraceScanner::new().with_single_quote_strings());
+        for (index, line) in lines.iter().enumerate().skip(start_line) {
+            if tracker.feed(line) {
+                return index;
+            }
+        }
+        lines.len().saturating_sub(1)
+    }
+
+    /// Fields of a message or values of an enum: the statements directly in its body
+    fn count_members(&self, lines: &[String], start_line: usize, end_line: usize) -> usize {
+        let mut scanner = BraceScanner::new().with_single_quote_strings();
+        let mut depth = 0;
+        let mut members = 0;
+
+        for line in &lines[start_line..=end_line] {
+            let trimmed = line.trim();
+            let count = scanner.scan(line);
+            let first_word = trimmed.split_whitespace().next().unwrap_or("");
+            if depth == 1 && count.ends_statement && trimmed.contains('=') && !matches!(first_word, "option" | "reserved" | "extensions") {
+                members += 1;
+            }
+            depth = (depth + count.open).saturating_sub(count.close);
+        }
+
+        members
+    }
+
+    fn rpc_info(&self, lines: &[String], index: usize, name: &str, service: Option<&str>) -> FunctionInfo {
+        let end_line = self.find_end(lines, index);
+        FunctionInfo {
+            name: name.to_string(),
+            line_count: end_line - index + 1,
+            cyclomatic_complexity: 1,
+            cognitive_complexity: 1,
+            nesting_depth: 0,
+            // One request message
+            parameter_count: 1,
+            return_path_count: 1,
+            start_line: index + 1,
+            end_line: end_line + 1,
+            is_method: service.is_some(),
+            parent_class: service.map(str::to_string),
+            local_variable_count: 0,
+            has_recursion: false,
+            has_exception_handling: false,
+            visibility: Visibility::Public,
+        }
+    }
+}
+
+impl LanguageAnalyzer for ProtobufAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let mut service: Option<(&str, usize)> = None;
+
+        for (index, line) in lines.iter().enumerate() {
+            match self.declaration(line) {
+                Some(("service", name)) => service = Some((name, self.find_end(lines, index))),
+                Some(("rpc", name)) => {
+                    let parent = service.filter(|(_, end)| index <= *end).map(|(service, _)| service);
+                    functions.push(self.rpc_info(lines, index, name, parent));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, lines: &[String]) -> Result<Vec<StructureInfo>> {
+        let mut structures = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some((keyword, name)) = self.declaration(line) else {
+                continue;
+            };
+            let structure_type = match keyword {
+                "message" => StructureType::Struct,
+                "enum" => StructureType::Enum,
+                "service" => StructureType::Interface,
+                _ => continue,
+            };
+
+            let end_line = self.find_end(lines, index);
+            let methods = if keyword == "service" {
+                (index + 1..=end_line)
+                    .filter_map(|rpc| match self.declaration(&lines[rpc]) {
+                        Some(("rpc", rpc_name)) => Some(self.rpc_info(lines, rpc, rpc_name, Some(name))),
+                        _ => None,
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            structures.push(StructureInfo {
+                name: name.to_string(),
+                structure_type,
+                line_count: end_line - index + 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                methods,
+                properties: self.count_members(lines, index, end_line),
+                visibility: Visibility::Public,
+                inheritance_depth: 0,
+                interface_count: 0,
+            });
+        }
+
+        Ok(structures)
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Protocol Buffers"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["proto"]
+    }
+}
+
+impl Default for ProtobufAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
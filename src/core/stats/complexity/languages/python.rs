@@ -1,5 +1,8 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, IndentTracker};
+use super::cognitive::{self, CognitiveCounter};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Python language complexity analyzer
@@ -30,58 +33,6 @@ impl PythonAnalyzer {
         keywords.iter().map(|&keyword| line.matches(keyword).count()).sum()
     }
     
-    /// Count cognitive complexity for Python code
-    fn count_cognitive_complexity(&self, line: &str, nesting_level: usize) -> usize {
-        let mut complexity = 0;
-        let nesting_multiplier = nesting_level.max(1);
-        
-        // Basic control structures
-        if line.contains("if ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("elif ") { complexity += 1; }
-        if line.contains("else:") { complexity += 1; }
-        if line.contains("while ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("for ") { complexity += 1 * nesting_multiplier; }
-        if line.contains("try:") { complexity += 1 * nesting_multiplier; }
-        if line.contains("except") { complexity += 1; }
-        if line.contains("finally") { complexity += 1; }
-        
-        // Logical operators
-        complexity += line.matches(" and ").count() * nesting_multiplier;
-        complexity += line.matches(" or ").count() * nesting_multiplier;
-        
-        // Comprehensions add complexity
-        if line.contains(" for ") && (line.contains("[") || line.contains("{")) {
-            complexity += 1;
-        }
-        
-        complexity
-    }
-    
-    /// Count function parameters
-    fn count_function_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.rfind(')') {
-                if end > start {
-                    let params_str = &line[start + 1..end];
-                    if params_str.trim().is_empty() {
-                        return 0;
-                    }
-                    
-                    // Simple parameter counting (split by comma)
-                    let param_count = params_str.split(',').count();
-                    
-                    // Adjust for common patterns
-                    if params_str.contains("self") {
-                        return param_count.saturating_sub(1);
-                    }
-                    
-                    return param_count;
-                }
-            }
-        }
-        0
-    }
-    
     /// Detect Python structure type and name
     fn detect_structure(&self, line: &str) -> Option<(StructureType, String)> {
         if line.starts_with("class ") {
@@ -119,7 +70,7 @@ impl PythonAnalyzer {
 impl LanguageAnalyzer for PythonAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
+        let mut current_function: Option<(FunctionInfo, CognitiveCounter, IndentTracker)> = None;
         let mut function_indent = 0;
         
         for (line_num, line) in lines.iter().enumerate() {
@@ -137,17 +88,20 @@ impl LanguageAnalyzer for PythonAnalyzer {
             if trimmed.starts_with("def ") {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
                     // Save previous function if exists
-                    if let Some(func) = current_function.take() {
+                    if let Some((func, _, _)) = current_function.take() {
                         functions.push(func);
                     }
                     
-                    current_function = Some(FunctionInfo {
+                    let scanner = BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false).with_single_quote_strings();
+                    let parameter_count = parameters::count(lines, line_num, &func_name, scanner.clone(), &parameters::PYTHON);
+                    let counter = CognitiveCounter::new(&cognitive::PYTHON, scanner.clone(), &func_name);
+                    current_function = Some((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
-                        cognitive_complexity: 1, // Base cognitive complexity
+                        cognitive_complexity: 0,
                         nesting_depth: 0,
-                        parameter_count: 0,
+                        parameter_count,
                         return_path_count: 0,
                         start_line: line_num + 1,
                         end_line: line_num + 1,
@@ -156,38 +110,35 @@ impl LanguageAnalyzer for PythonAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
+                        visibility: Visibility::Public,}, counter, IndentTracker::new(scanner, current_indent)));
                     function_indent = current_indent;
                 }
             }
             
-            if let Some(ref mut func) = current_function {
-                // Check if we're still in the function
-                if current_indent <= function_indent && line_num > func.start_line - 1 && !trimmed.is_empty() {
+            if let Some((ref mut func, ref mut counter, ref mut tracker)) = current_function {
+                // Check if we're still in the function; the closing `):` of a
+                // signature over several lines doesn't end it
+                if current_indent <= function_indent && line_num > func.start_line - 1 && !tracker.continues() {
                     // Function ended
                     functions.push(func.clone());
                     current_function = None;
                     continue;
                 }
                 
+                // Track nesting by the indentation of the blocks, whatever its width
+                tracker.feed(line);
+                func.nesting_depth = tracker.max_depth();
+                
                 if current_indent > function_indent {
                     func.line_count += 1;
                     func.end_line = line_num + 1;
                     
-                    // Calculate nesting depth
-                    let relative_indent = (current_indent - function_indent) / 4; // Assuming 4-space indentation
-                    func.nesting_depth = func.nesting_depth.max(relative_indent);
-                    
                     // Calculate cyclomatic complexity
                     func.cyclomatic_complexity += self.count_complexity_keywords(trimmed);
                     
                     // Calculate cognitive complexity
-                    func.cognitive_complexity += self.count_cognitive_complexity(trimmed, relative_indent);
-                    
-                    // Count parameters
-                    if trimmed.contains('(') && func.parameter_count == 0 {
-                        func.parameter_count = self.count_function_parameters(trimmed);
-                    }
+                    counter.feed(line);
+                    func.cognitive_complexity = counter.complexity();
                     
                     // Count return paths
                     if trimmed.contains("return") {
@@ -208,7 +159,7 @@ impl LanguageAnalyzer for PythonAnalyzer {
         }
         
         // Add the last function if exists
-        if let Some(func) = current_function {
+        if let Some((func, _, _)) = current_function {
             functions.push(func);
         }
         
@@ -289,6 +240,10 @@ impl LanguageAnalyzer for PythonAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["py"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false).with_single_quote_strings()
+    }
 }
 
 impl Default for PythonAnalyzer {
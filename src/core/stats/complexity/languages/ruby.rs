@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BraceScanner, KeywordBlockTracker};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Ruby language complexity analyzer
@@ -148,31 +150,9 @@ impl RubyAnalyzer {
         })
     }
     
-    /// Count parameters in method signature
-    fn count_parameters(&self, line: &str) -> usize {
-        // Ruby methods can have parameters without parentheses
-        if let Some(def_pos) = line.find("def ") {
-            let after_def = &line[def_pos + 4..];
-            
-            // Check if there are parentheses
-            if let Some(paren_start) = after_def.find('(') {
-                if let Some(paren_end) = after_def.find(')') {
-                    let params = &after_def[paren_start + 1..paren_end];
-                    if params.trim().is_empty() {
-                        return 0;
-                    }
-                    return params.split(',').count();
-                }
-            } else {
-                // No parentheses, check for parameters after method name
-                let parts: Vec<&str> = after_def.split_whitespace().collect();
-                if parts.len() > 1 {
-                    // Simple heuristic: count space-separated parts after method name
-                    return parts.len() - 1;
-                }
-            }
-        }
-        0
+    /// Count parameters in method signature, whose parentheses are optional
+    fn count_parameters(&self, lines: &[String], index: usize, name: &str) -> usize {
+        parameters::count_bare(lines, index, name, self.comment_scanner(), &[])
     }
     
     /// Determine structure type from declaration
@@ -226,10 +206,7 @@ impl RubyAnalyzer {
 impl LanguageAnalyzer for RubyAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut block_depth = 0;
-        let mut in_function = false;
-        let mut nesting_level: usize = 0;
+        let mut current_function: Option<(FunctionInfo, KeywordBlockTracker)> = None;
         let mut in_comment_block = false;
         
         for (line_num, line) in lines.iter().enumerate() {
@@ -253,14 +230,17 @@ impl LanguageAnalyzer for RubyAnalyzer {
                 continue;
             }
             
-            // Method definition detection
-            if self.is_method_definition(trimmed) {
+            // Method definition detection; a method defined inside another is part of it
+            if current_function.is_none() && self.is_method_definition(trimmed) {
                 if let Some(method_name) = self.extract_method_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &method_name);
                     let is_class_method = self.is_class_method(trimmed);
                     let _is_special = self.is_special_method(&method_name);
                     
-                    current_function = Some(FunctionInfo {
+                    // Modifiers such as `return if done` open no block
+                    let tracker = KeywordBlockTracker::new(&["def", "class", "module", "do"], &["end"], "#")
+                        .with_leading_openers(&["if", "unless", "while", "until", "for", "case", "begin"]);
+                    current_function = Some((FunctionInfo {
                         name: method_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
@@ -275,72 +255,55 @@ impl LanguageAnalyzer for RubyAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    block_depth = 0;
-                    nesting_level = 0;
+                        visibility: Visibility::Public,}, tracker));
                 }
             }
             
-            if in_function {
-                // Track block depth (def/class/module/if/while/etc. increase, end decreases)
-                if trimmed.starts_with("def ") || trimmed.starts_with("class ") || 
-                   trimmed.starts_with("module ") || trimmed.contains("if ") || 
-                   trimmed.contains("while ") || trimmed.contains("for ") ||
-                   trimmed.contains("case ") || trimmed.contains("begin ") {
-                    block_depth += 1;
-                    nesting_level += 1;
+            if let Some((func, tracker)) = current_function.as_mut() {
+                // Track blocks by their keywords, ignoring strings and comments
+                let closed = tracker.feed(trimmed);
+                let nesting_level = tracker.depth();
+                
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                func.nesting_depth = tracker.max_depth();
+                
+                // Add complexity from keywords
+                let keyword_complexity = self.count_complexity_keywords(trimmed);
+                func.cyclomatic_complexity += keyword_complexity;
+                
+                // Add cognitive complexity
+                let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
+                func.cognitive_complexity += cognitive_complexity;
+                
+                // Count return statements (Ruby has implicit returns)
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
                 }
                 
-                if self.is_block_end(trimmed) {
-                    block_depth -= 1;
-                    nesting_level = nesting_level.saturating_sub(1);
+                // Check for recursion
+                if trimmed.contains(&func.name) && 
+                   (trimmed.contains('(') || trimmed.contains(' ')) {
+                    func.has_recursion = true;
                 }
                 
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    func.nesting_depth = func.nesting_depth.max(nesting_level);
-                    
-                    // Add complexity from keywords
-                    let keyword_complexity = self.count_complexity_keywords(trimmed);
-                    func.cyclomatic_complexity += keyword_complexity;
-                    
-                    // Add cognitive complexity
-                    let cognitive_complexity = self.count_cognitive_complexity(trimmed, nesting_level);
-                    func.cognitive_complexity += cognitive_complexity;
-                    
-                    // Count return statements (Ruby has implicit returns)
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && 
-                       (trimmed.contains('(') || trimmed.contains(' ')) {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for exception handling
-                    if trimmed.contains("begin") || trimmed.contains("rescue") || 
-                       trimmed.contains("ensure") || trimmed.contains("raise") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Count local variables (rough estimate)
-                    if trimmed.contains(" = ") && !trimmed.contains("def ") &&
-                       !trimmed.contains("class ") && !trimmed.contains("module ") {
-                        func.local_variable_count += 1;
-                    }
+                // Check for exception handling
+                if trimmed.contains("begin") || trimmed.contains("rescue") || 
+                   trimmed.contains("ensure") || trimmed.contains("raise") {
+                    func.has_exception_handling = true;
+                }
+                
+                // Count local variables (rough estimate)
+                if trimmed.contains(" = ") && !trimmed.contains("def ") &&
+                   !trimmed.contains("class ") && !trimmed.contains("module ") {
+                    func.local_variable_count += 1;
                 }
                 
                 // End of method
-                if block_depth <= 0 && in_function && self.is_block_end(trimmed) {
-                    if let Some(func) = current_function.take() {
+                if closed {
+                    if let Some((func, _)) = current_function.take() {
                         functions.push(func);
                     }
-                    in_function = false;
-                    nesting_level = 0;
                 }
             }
         }
@@ -471,7 +434,7 @@ impl LanguageAnalyzer for RubyAnalyzer {
             
             if self.is_method_definition(trimmed) {
                 if let Some(method_name) = self.extract_method_name(trimmed) {
-                    let param_count = self.count_parameters(trimmed);
+                    let param_count = self.count_parameters(lines, line_num, &method_name);
                     let is_class_method = self.is_class_method(trimmed);
                     
                     let method_info = FunctionInfo {
@@ -514,6 +477,10 @@ impl LanguageAnalyzer for RubyAnalyzer {
     fn supported_extensions(&self) -> Vec<&'static str> {
         vec!["rb", "rbw", "rake", "gemspec"]
     }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["#"]).with_block_comments(false).with_single_quote_strings()
+    }
 }
 
 impl Default for RubyAnalyzer {
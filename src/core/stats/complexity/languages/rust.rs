@@ -1,5 +1,8 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BlockTracker, BraceScanner};
+use super::cognitive::{self, CognitiveCounter};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Rust language complexity analyzer
@@ -30,54 +33,6 @@ impl RustAnalyzer {
         keywords.iter().map(|&keyword| line.matches(keyword).count()).sum()
     }
     
-    /// Count cognitive complexity for Rust code
-    fn count_cognitive_complexity(&self, line: &str, nesting_level: i32) -> usize {
-        let mut complexity = 0;
-        let nesting_multiplier = (nesting_level as usize).max(1);
-        
-        // Basic control structures
-        if line.contains("if") { complexity += 1 * nesting_multiplier; }
-        if line.contains("else") { complexity += 1; }
-        if line.contains("match") { complexity += 1 * nesting_multiplier; }
-        if line.contains("while") { complexity += 1 * nesting_multiplier; }
-        if line.contains("for") { complexity += 1 * nesting_multiplier; }
-        if line.contains("loop") { complexity += 1 * nesting_multiplier; }
-        
-        // Logical operators
-        complexity += line.matches("&&").count() * nesting_multiplier;
-        complexity += line.matches("||").count() * nesting_multiplier;
-        
-        // Recursion penalty
-        if line.contains("self.") && line.contains("(") { complexity += 1; }
-        
-        complexity
-    }
-    
-    /// Count function parameters
-    fn count_function_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.rfind(')') {
-                if end > start {
-                    let params_str = &line[start + 1..end];
-                    if params_str.trim().is_empty() {
-                        return 0;
-                    }
-                    
-                    // Simple parameter counting (split by comma)
-                    let param_count = params_str.split(',').count();
-                    
-                    // Adjust for common patterns
-                    if params_str.contains("self") {
-                        return param_count.saturating_sub(1);
-                    }
-                    
-                    return param_count;
-                }
-            }
-        }
-        0
-    }
-    
     /// Detect Rust structure type and name
     fn detect_structure(&self, line: &str) -> Option<(StructureType, String, Visibility)> {
         let visibility = if line.starts_with("pub ") {
@@ -139,9 +94,8 @@ impl RustAnalyzer {
 impl LanguageAnalyzer for RustAnalyzer {
     fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let mut current_function: Option<FunctionInfo> = None;
-        let mut brace_count = 0;
-        let mut in_function = false;
+        // Functions whose body is still open, innermost last
+        let mut open_functions: Vec<(FunctionInfo, BlockTracker, CognitiveCounter)> = Vec::new();
         
         for (line_num, line) in lines.iter().enumerate() {
             let trimmed = line.trim();
@@ -154,13 +108,15 @@ impl LanguageAnalyzer for RustAnalyzer {
             // Function declaration detection
             if trimmed.starts_with("fn ") || trimmed.contains(" fn ") {
                 if let Some(func_name) = self.extract_function_name(trimmed) {
-                    current_function = Some(FunctionInfo {
+                    let counter = CognitiveCounter::new(&cognitive::RUST, BraceScanner::new(), &func_name);
+                    let parameter_count = parameters::count(lines, line_num, &func_name, BraceScanner::new(), &parameters::RUST);
+                    open_functions.push((FunctionInfo {
                         name: func_name,
                         line_count: 0,
                         cyclomatic_complexity: 1, // Base complexity
-                        cognitive_complexity: 1, // Base cognitive complexity
+                        cognitive_complexity: 0,
                         nesting_depth: 0,
-                        parameter_count: 0,
+                        parameter_count,
                         return_path_count: 0,
                         start_line: line_num + 1,
                         end_line: line_num + 1,
@@ -169,59 +125,51 @@ impl LanguageAnalyzer for RustAnalyzer {
                         local_variable_count: 0,
                         has_recursion: false,
                         has_exception_handling: false,
-                        visibility: Visibility::Public,});
-                    in_function = true;
-                    brace_count = 0;
+                        visibility: Visibility::Public,
+                    }, BlockTracker::new(BraceScanner::new()), counter));
                 }
             }
             
-            if in_function {
-                if let Some(ref mut func) = current_function {
-                    func.line_count += 1;
-                    func.end_line = line_num + 1;
-                    
-                    // Count braces for nesting depth
-                    let open_braces = trimmed.matches('{').count();
-                    let close_braces = trimmed.matches('}').count();
-                    brace_count += open_braces as i32 - close_braces as i32;
-                    func.nesting_depth = func.nesting_depth.max(brace_count.max(0) as usize);
-                    
-                    // Calculate cyclomatic complexity
-                    func.cyclomatic_complexity += self.count_complexity_keywords(trimmed);
-                    
-                    // Calculate cognitive complexity
-                    func.cognitive_complexity += self.count_cognitive_complexity(trimmed, brace_count);
-                    
-                    // Count parameters
-                    if trimmed.contains('(') && func.parameter_count == 0 {
-                        func.parameter_count = self.count_function_parameters(trimmed);
-                    }
-                    
-                    // Count return paths
-                    if trimmed.contains("return") {
-                        func.return_path_count += 1;
-                    }
-                    
-                    // Check for recursion
-                    if trimmed.contains(&func.name) && !trimmed.starts_with("fn ") {
-                        func.has_recursion = true;
-                    }
-                    
-                    // Check for exception handling
-                    if trimmed.contains("try") || trimmed.contains("catch") || trimmed.contains("?") {
-                        func.has_exception_handling = true;
-                    }
-                    
-                    // Function end detection
-                    if brace_count <= 0 && close_braces > 0 {
-                        functions.push(func.clone());
-                        current_function = None;
-                        in_function = false;
+            if let Some((func, tracker, counter)) = open_functions.last_mut() {
+                func.line_count += 1;
+                func.end_line = line_num + 1;
+                
+                // Braces in strings, character literals and comments are not counted
+                let closed = tracker.feed(trimmed);
+                func.nesting_depth = tracker.max_depth();
+                
+                // Calculate cyclomatic complexity
+                func.cyclomatic_complexity += self.count_complexity_keywords(trimmed);
+                
+                // Calculate cognitive complexity
+                counter.feed(trimmed);
+                func.cognitive_complexity = counter.complexity();
+                
+                // Count return paths
+                if trimmed.contains("return") {
+                    func.return_path_count += 1;
+                }
+                
+                // Check for recursion
+                if trimmed.contains(&func.name) && !trimmed.starts_with("fn ") {
+                    func.has_recursion = true;
+                }
+                
+                // Check for exception handling
+                if trimmed.contains("try") || trimmed.contains("catch") || trimmed.contains("?") {
+                    func.has_exception_handling = true;
+                }
+                
+                // Function end detection; a trait method without a body ends at its `;`
+                if closed {
+                    if let Some((func, _, _)) = open_functions.pop() {
+                        functions.push(func);
                     }
                 }
             }
         }
         
+        functions.sort_by_key(|func| func.start_line);
         Ok(functions)
     }
     
@@ -0,0 +1,583 @@
+use super::blocks::{BlockTracker, BraceScanner, KeywordBlockTracker};
+use super::super::types::FunctionThresholds;
+use super::{get_language_analyzer, resolve_extension};
+
+/// `(name, start_line, end_line)` of each function found in `source`, with 1-based lines
+fn spans(extension: &str, source: &str) -> Vec<(String, usize, usize)> {
+    let lines: Vec<String> = source.lines().map(String::from).collect();
+    let analyzer = get_language_analyzer(extension).unwrap();
+    analyzer
+        .analyze_functions(&lines)
+        .unwrap()
+        .into_iter()
+        .map(|func| (func.name, func.start_line, func.end_line))
+        .collect()
+}
+
+fn expected(spans: &[(&str, usize, usize)]) -> Vec<(String, usize, usize)> {
+    spans.iter().map(|(name, start, end)| (name.to_string(), *start, *end)).collect()
+}
+
+#[test]
+fn test_rust_spans() {
+    let source = r#"/// Adds numbers
+pub fn add(a: i32, b: i32) -> i32 {
+    let s = "}";
+    // closing } in a comment
+    if a > b {
+        return a;
+    }
+
+    a + b
+}
+
+fn outer() {
+    fn inner() -> u8 { 1 }
+    inner();
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+"#;
+
+    assert_eq!(
+        spans("rs", source),
+        expected(&[("add", 2, 10), ("outer", 12, 15), ("inner", 13, 13), ("area", 18, 18)])
+    );
+}
+
+#[test]
+fn test_python_spans_exclude_trailing_blank_lines() {
+    let source = r#"def add(a, b):
+    s = "}"
+    if a > b:
+        return a
+
+    return a + b
+
+
+class Shape:
+    def area(self):
+        return 0
+    # trailing comment
+
+x = 1
+"#;
+
+    assert_eq!(spans("py", source), expected(&[("add", 1, 6), ("area", 10, 11)]));
+}
+
+#[test]
+fn test_javascript_spans() {
+    let source = r#"function add(a, b) {
+  const s = "}";
+  if (a > b) {
+    return a;
+  }
+  return a + b;
+}
+
+const mul = (a, b) => {
+  return a * b;
+};
+
+class Shape {
+  area() {
+    return 0;
+  }
+}
+"#;
+
+    assert_eq!(spans("js", source), expected(&[("add", 1, 7), ("mul", 9, 11), ("area", 14, 16)]));
+}
+
+#[test]
+fn test_java_spans() {
+    let source = r#"public class Shape {
+    public int add(int a, int b) {
+        String s = "}";
+        if (a > b) {
+            return a;
+        }
+        return a + b;
+    }
+
+    private void noop() {
+    }
+}
+"#;
+
+    assert_eq!(spans("java", source), expected(&[("add", 2, 8), ("noop", 10, 11)]));
+}
+
+#[test]
+fn test_go_spans() {
+    let source = "package main\n\nfunc add(a int, b int) int {\n\ts := \"}\"\n\tif a > b {\n\t\treturn a\n\t}\n\treturn a + b\n}\n\nfunc (s *Shape) Area() float64 {\n\treturn 0\n}\n";
+
+    assert_eq!(spans("go", source), expected(&[("add", 3, 9), ("Area", 11, 13)]));
+}
+
+#[test]
+fn test_csharp_spans_with_allman_braces() {
+    let source = r#"public class Shape
+{
+    public int Add(int a, int b)
+    {
+        var s = "}";
+        if (a > b)
+        {
+            return a;
+        }
+        return a + b;
+    }
+}
+"#;
+
+    assert_eq!(spans("cs", source), expected(&[("Add", 3, 11)]));
+}
+
+#[test]
+fn test_keyword_delimited_spans() {
+    let lua = "function add(a, b)\n  if a > b then\n    return a\n  end\n  return a + b\nend\n\nlocal function noop()\nend\n";
+    assert_eq!(spans("lua", lua), expected(&[("add", 1, 6), ("noop", 8, 9)]));
+
+    let elixir = "defmodule Math do\n  def add(a, b) do\n    if a > b do\n      a\n    else\n      a + b\n    end\n  end\nend\n";
+    assert_eq!(spans("ex", elixir), expected(&[("add", 2, 8)]));
+
+    let ruby = "def add(a, b)\n  if a > b\n    return a\n  end\n  a + b\nend\n\nclass Shape\n  def area\n    0\n  end\nend\n";
+    assert_eq!(spans("rb", ruby), expected(&[("add", 1, 6), ("area", 9, 11)]));
+}
+
+#[test]
+fn test_multi_clause_spans() {
+    let erlang = "-module(math).\nadd(A, B) when A > B ->\n    A;\nadd(A, B) ->\n    A + B.\n\nnoop() ->\n    ok.\n";
+    assert_eq!(spans("erl", erlang), expected(&[("add", 2, 5), ("noop", 7, 8)]));
+
+    // The type signature and every equation belong to one function
+    let haskell = "add :: Int -> Int -> Int\nadd a b\n  | a > b = a\n  | otherwise = a + b\n\nnoop :: ()\nnoop = ()\n";
+    assert_eq!(spans("hs", haskell), expected(&[("add", 1, 4), ("noop", 6, 7)]));
+}
+
+#[test]
+fn test_configuration_and_schema_spans() {
+    // Each top-level block is named by its Terraform address
+    let hcl = "resource \"aws_instance\" \"web\" {\n  count = var.enabled ? 1 : 0\n  tags = {\n    Name = \"web {x}\"\n  }\n}\n\nlocals {\n  region = \"eu\"\n}\n";
+    assert_eq!(spans("tf", hcl), expected(&[("aws_instance.web", 1, 6), ("locals", 8, 10)]));
+
+    let proto = "syntax = \"proto3\";\n\nservice Users {\n  rpc GetUser (GetUserRequest) returns (User);\n  rpc Watch (WatchRequest) returns (stream User) {\n    option deprecated = true;\n  }\n}\n";
+    assert_eq!(spans("proto", proto), expected(&[("GetUser", 4, 4), ("Watch", 5, 7)]));
+
+    let graphql = "query GetUser($id: ID!, $full: Boolean!) {\n  user(id: $id) {\n    name\n    bio @include(if: $full)\n  }\n}\n\nfragment UserFields on User {\n  name\n}\n";
+    assert_eq!(spans("graphql", graphql), expected(&[("GetUser", 1, 6), ("UserFields", 8, 10)]));
+
+    // Bindings of lambdas end at their `;`, past nested sets and `let ... in`
+    let nix = "{\n  add = a: b: a + b;\n  mkApp = { pname, version ? \"1.0\", ... }:\n    let\n      name = \"${pname}-${version}\";\n    in {\n      inherit name;\n    };\n  answer = 42;\n}\n";
+    assert_eq!(spans("nix", nix), expected(&[("add", 2, 2), ("mkApp", 3, 8)]));
+}
+
+#[test]
+fn test_configuration_and_schema_metrics() {
+    let lines = |source: &str| source.lines().map(String::from).collect::<Vec<_>>();
+
+    let hcl = lines("resource \"aws_instance\" \"web\" {\n  for_each = var.hosts\n  ami = var.ami != \"\" ? var.ami : data.aws_ami.default.id\n}\n");
+    let block = &get_language_analyzer("tf").unwrap().analyze_functions(&hcl).unwrap()[0];
+    assert_eq!(block.cyclomatic_complexity, 3);
+
+    let proto = lines("message User {\n  string name = 1;\n  reserved 2;\n  message Address {\n    string city = 1;\n  }\n  Address address = 3;\n}\n");
+    let structures = get_language_analyzer("proto").unwrap().analyze_structures(&proto).unwrap();
+    assert_eq!(structures.iter().map(|s| (s.name.as_str(), s.properties)).collect::<Vec<_>>(), vec![("User", 2), ("Address", 1)]);
+
+    let graphql = lines("query GetUser($id: ID!, $full: Boolean!) {\n  user(id: $id) {\n    bio @include(if: $full)\n  }\n}\n\ntype User implements Node & Entity {\n  \"\"\"Display name\"\"\"\n  name: String!\n  bio: String\n}\n");
+    let analyzer = get_language_analyzer("graphql").unwrap();
+    let query = &analyzer.analyze_functions(&graphql).unwrap()[0];
+    assert_eq!((query.parameter_count, query.cyclomatic_complexity, query.nesting_depth), (2, 2, 1));
+    let user = &analyzer.analyze_structures(&graphql).unwrap()[0];
+    assert_eq!((user.properties, user.interface_count), (2, 2));
+
+    let nix = lines("{\n  mkApp = { pname, version ? \"1.0\", ... }:\n    assert pname != \"\";\n    if version == \"\" then null else pname;\n}\n");
+    let function = &get_language_analyzer("nix").unwrap().analyze_functions(&nix).unwrap()[0];
+    assert_eq!((function.parameter_count, function.cyclomatic_complexity), (2, 3));
+}
+
+#[test]
+fn test_hardware_and_assembly_spans() {
+    // Only exported symbols are functions; local labels are jump targets
+    let gas = "    .globl sum\n    .type sum, @function\nsum:\n    xorl %eax, %eax  # total\n.L2:\n    cmpq %rsi, %rdi\n    jge .L3\n    addl (%rdi), %eax\n    jmp .L2\n.L3:\n    ret\nhelper:\n    ret\n";
+    assert_eq!(spans("s", gas), expected(&[("sum", 3, 13)]));
+    let nasm = "section .text\nglobal main\nmain:\n    mov eax, 1\n    ret\n";
+    assert_eq!(spans("asm", nasm), expected(&[("main", 3, 5)]));
+    let masm = "sum PROC\n    cmp ecx, 0\n    je done\ndone:\n    ret\nsum ENDP\n";
+    assert_eq!(spans("asm", masm), expected(&[("sum", 1, 6)]));
+
+    let verilog = "module alu #(parameter W = 8) (\n  input  logic [W-1:0] a,\n  output logic [W-1:0] y\n);\n  function automatic logic [W-1:0] add(input logic [W-1:0] x, input logic [W-1:0] z);\n    return x + z;\n  endfunction\n  assign y = a;\nendmodule\n";
+    assert_eq!(spans("sv", verilog), expected(&[("add", 5, 7)]));
+
+    // Processes and subprograms end at their own `end`, past `end if`
+    let vhdl = "architecture rtl of counter is\n  function parity(v : bit_vector) return bit is\n  begin\n    return '0';\n  end function;\nbegin\n  tick : process (clk)\n  begin\n    if rising_edge(clk) then\n      count <= count + 1;\n    end if;\n  end process tick;\nend architecture rtl;\n";
+    assert_eq!(spans("vhd", vhdl), expected(&[("parity", 2, 5), ("tick", 7, 12)]));
+
+    let cuda = "__global__ void scale(float *x, float a, int n) {\n    int i = blockIdx.x * blockDim.x + threadIdx.x;\n    if (i < n) { x[i] *= a; }\n}\n";
+    assert_eq!(spans("cu", cuda), expected(&[("scale", 1, 4)]));
+}
+
+#[test]
+fn test_hardware_and_assembly_metrics() {
+    let lines = |source: &str| source.lines().map(String::from).collect::<Vec<_>>();
+
+    let arm = lines(".global clamp\nclamp:\n    cmp x0, #0\n    b.ge 1f\n    mov x0, #0\n1:  cbz x1, 2f\n    bl helper\n2:  ret\n");
+    let clamp = &get_language_analyzer("s").unwrap().analyze_functions(&arm).unwrap()[0];
+    assert_eq!(clamp.cyclomatic_complexity, 3);
+
+    let verilog = lines("module alu (\n  input  logic a,\n  input  logic b,\n  output logic y\n);\n  always_comb y = a && b ? 1'b1 : 1'b0;\nendmodule\n");
+    let alu = &get_language_analyzer("v").unwrap().analyze_structures(&verilog).unwrap()[0];
+    assert_eq!((alu.name.as_str(), alu.end_line, alu.properties), ("alu", 7, 3));
+
+    let vhdl = lines("package body util is\n  procedure step(a, b : in integer; c : out integer) is\n  begin\n    case a is\n      when 0 => c := b;\n      when others => c := 0;\n    end case;\n  end procedure;\nend package body util;\n");
+    let analyzer = get_language_analyzer("vhdl").unwrap();
+    let step = &analyzer.analyze_functions(&vhdl).unwrap()[0];
+    assert_eq!((step.parameter_count, step.cyclomatic_complexity, step.parent_class.as_deref()), (3, 2, Some("util")));
+    let util = &analyzer.analyze_structures(&vhdl).unwrap()[0];
+    assert_eq!((util.end_line, util.methods.len()), (9, 1));
+}
+
+#[test]
+fn test_objective_c_spans_and_metrics() {
+    let source = r#"#import "Shape.h"
+
+@interface Shape () <NSCopying>
+@property (nonatomic) double width;
+- (double)area;
+@end
+
+static double clamp(double value, double limit) {
+    return value > limit ? limit : value;
+}
+
+@implementation Shape
+- (double)area {
+    return self.width * self.width;
+}
+
+- (void)resizeTo:(double)width animated:(BOOL)animated
+{
+    if (animated && width > 0) {
+        [UIView animateWithDuration:0.2 animations:^{
+            self.width = width;
+        }];
+    }
+}
+@end
+"#;
+    assert_eq!(
+        spans("mm", source),
+        expected(&[("clamp", 8, 10), ("area", 13, 15), ("resizeTo:animated:", 17, 24)])
+    );
+
+    let lines: Vec<String> = source.lines().map(String::from).collect();
+    let analyzer = get_language_analyzer("mm").unwrap();
+    let functions = analyzer.analyze_functions(&lines).unwrap();
+    let resize = &functions[2];
+    assert_eq!((resize.parameter_count, resize.cyclomatic_complexity, resize.parent_class.as_deref()), (2, 3, Some("Shape")));
+    assert!(!functions[0].is_method);
+    let shape = &analyzer.analyze_structures(&lines).unwrap()[0];
+    assert_eq!((shape.name.as_str(), shape.methods.len(), shape.properties, shape.interface_count), ("Shape", 2, 1, 1));
+}
+
+#[test]
+fn test_m_files_resolve_by_contents() {
+    assert_eq!(resolve_extension("m", ["// Greeter.m", "#import <Foundation/Foundation.h>"]), "mm");
+    assert_eq!(resolve_extension("m", ["", "@implementation Greeter"]), "mm");
+    assert_eq!(resolve_extension("m", ["%% Section", "#import is not MATLAB"]), "m");
+    assert_eq!(resolve_extension("m", ["function y = square(x)", "y = x.^2;"]), "m");
+    assert_eq!(resolve_extension("m", ["x = 1;"]), "m");
+    assert_eq!(resolve_extension("h", ["#import <Foundation/Foundation.h>"]), "h");
+}
+
+#[test]
+fn test_brace_scanner_ignores_literals_and_comments() {
+    let mut scanner = BraceScanner::new();
+
+    assert_eq!(scanner.scan(r#"let s = "{"; let c = '}'; // }"#).open, 0);
+    assert_eq!(scanner.scan("fn main() { /* { spans").open, 1);
+    // Still inside the block comment
+    let count = scanner.scan("lines } */ }");
+    assert_eq!((count.open, count.close), (0, 1));
+    assert!(scanner.scan("fn area(&self) -> f64;").ends_statement);
+}
+
+#[test]
+fn test_block_tracker_waits_for_body() {
+    let mut tracker = BlockTracker::new(BraceScanner::new());
+
+    // Allman style: the signature line opens nothing yet
+    assert!(!tracker.feed("int add(int a, int b)"));
+    assert!(!tracker.feed("{"));
+    assert!(!tracker.feed("    if (a) { return a; }"));
+    assert!(tracker.feed("}"));
+}
+
+#[test]
+fn test_keyword_block_tracker() {
+    let mut tracker = KeywordBlockTracker::new(&["function", "if", "for"], &["end"], "#");
+
+    assert!(!tracker.feed("function last(a)"));
+    // `end` inside brackets, strings and comments does not close the block
+    assert!(!tracker.feed("    x = a[end] * \"end\" # end"));
+    assert!(!tracker.feed("    if x > 0 x end"));
+    assert!(tracker.feed("end"));
+}
+
+/// `(name, cognitive_complexity)` of each function found in `source`
+fn cognitive(extension: &str, source: &str) -> Vec<(String, usize)> {
+    let lines: Vec<String> = source.lines().map(String::from).collect();
+    let analyzer = get_language_analyzer(extension).unwrap();
+    analyzer
+        .analyze_functions(&lines)
+        .unwrap()
+        .into_iter()
+        .map(|func| (func.name, func.cognitive_complexity))
+        .collect()
+}
+
+/// The examples of SonarSource's cognitive complexity white paper
+#[test]
+fn test_java_cognitive_complexity() {
+    let source = r#"class Numbers {
+    int sumOfPrimes(int max) {
+        int total = 0;
+        OUT: for (int i = 1; i <= max; ++i) {
+            for (int j = 2; j < i; ++j) {
+                if (i % j == 0) {
+                    continue OUT;
+                }
+            }
+            total += i;
+        }
+        return total;
+    }
+
+    String getWords(int number) {
+        switch (number) {
+            case 1:
+                return "one";
+            default:
+                return "lots";
+        }
+    }
+
+    boolean valid(String s) {
+        if (s == null || s.isEmpty() || "if".equals(s)) {
+            return false;
+        } else if (s.length() > 10 && s.startsWith("x") || s.endsWith("y")) {
+            return true;
+        } else {
+            return s.length() > 2 ? true : false;
+        }
+    }
+}
+"#;
+
+    assert_eq!(
+        cognitive("java", source),
+        vec![("sumOfPrimes".to_string(), 7), ("getWords".to_string(), 1), ("valid".to_string(), 8)]
+    );
+}
+
+#[test]
+fn test_rust_cognitive_complexity() {
+    let source = r#"fn find(items: &[Item], key: &str) -> Option<usize> {
+    'outer: for (i, item) in items.iter().enumerate() {
+        match item.kind {
+            Kind::Leaf if item.key == key => return Some(i),
+            _ => {
+                while let Some(child) = item.next() {
+                    if child.skip || child.hidden {
+                        continue 'outer;
+                    }
+                }
+            }
+        }
+    }
+    items.first().and_then(|item| find(&item.children, key)).or_else(|| None)
+}
+"#;
+
+    // for +1, match +2, while +3, if +4, || +1, continue 'outer +1, recursion +1
+    assert_eq!(cognitive("rs", source), vec![("find".to_string(), 13)]);
+}
+
+#[test]
+fn test_javascript_cognitive_complexity() {
+    let source = r#"function visit(node) {
+    if (!node) {
+        return 0;
+    }
+    let total = node.value ?? 0;
+    do {
+        total += node.left ? visit(node.left) : 0;
+    } while (node.next && node.next.ready);
+    try {
+        total += parse(node);
+    } catch (e) {
+        if (e.fatal) throw e;
+    }
+    return total;
+}
+"#;
+
+    // if +1, ?? +1, do +1, ternary +2, recursion +1, && +1, catch +1, if +2
+    assert_eq!(cognitive("js", source), vec![("visit".to_string(), 10)]);
+}
+
+#[test]
+fn test_go_cognitive_complexity() {
+    let source = r#"func (s *Server) route(path string) int {
+	switch {
+	case path == "":
+		return 0
+	case strings.HasPrefix(path, "/api") && !s.closed:
+		for i := 0; i < 3; i++ {
+			if s.try(path) {
+				goto done
+			} else {
+				continue
+			}
+		}
+	}
+done:
+	return 1
+}
+"#;
+
+    // switch +1, && +1, for +2, if +3, goto +1, else +1
+    assert_eq!(cognitive("go", source), vec![("route".to_string(), 9)]);
+}
+
+#[test]
+fn test_python_cognitive_complexity() {
+    let source = r#"def walk(tree, depth=0):
+    """Visit every node; if a node is hidden, skip it."""
+    if tree is None:
+        return []
+    nodes = []
+    for child in tree.children:
+        if child.hidden and not child.forced or child.pinned:
+            continue
+        elif child.leaf:
+            nodes.append(child)
+        else:
+            nodes.extend(walk(child, depth + 1))
+    try:
+        tree.close()
+    except OSError:
+        pass
+    return [n for n in nodes if n.ok] if nodes else []
+"#;
+
+    // if +1, for +1, if +2, and/or +2, elif +1, else +1, recursion +1,
+    // except +1, comprehension filter +1, conditional expression +1
+    assert_eq!(cognitive("py", source), vec![("walk".to_string(), 12)]);
+}
+
+/// `(name, nesting_depth, parameter_count)` of each function found in `source`
+fn shapes(extension: &str, source: &str) -> Vec<(String, usize, usize)> {
+    let lines: Vec<String> = source.lines().map(String::from).collect();
+    let analyzer = get_language_analyzer(extension).unwrap();
+    analyzer
+        .analyze_functions(&lines)
+        .unwrap()
+        .into_iter()
+        .map(|func| (func.name, func.nesting_depth, func.parameter_count))
+        .collect()
+}
+
+fn expected_shapes(shapes: &[(&str, usize, usize)]) -> Vec<(String, usize, usize)> {
+    shapes.iter().map(|(name, nesting, parameters)| (name.to_string(), *nesting, *parameters)).collect()
+}
+
+/// Nesting counts the body as level 1; parameter lists may run over several lines
+#[test]
+fn test_nesting_depth_and_parameters() {
+    let java = r#"class Tree {
+    void walk(Node node,
+              Map<String, List<Node>> index,
+              Visitor visitor) {
+        for (Node child : node.children) {
+            if (child.visible) { visitor.visit(child, "}"); }
+        }
+    }
+
+    int size() { return 0; }
+}
+"#;
+    assert_eq!(shapes("java", java), expected_shapes(&[("walk", 3, 3), ("size", 1, 0)]));
+
+    let go = "func (s *Server) Serve(l net.Listener, opts ...Option) error {\n\tfor {\n\t\tif s.closed {\n\t\t\treturn nil\n\t\t}\n\t}\n}\n";
+    assert_eq!(shapes("go", go), expected_shapes(&[("Serve", 3, 2)]));
+
+    let javascript = "const render = async ({ title, body }, options = {}) => {\n  if (title) {\n    return body;\n  }\n};\nconst double = x => x * 2;\n";
+    assert_eq!(shapes("js", javascript), expected_shapes(&[("render", 2, 2), ("double", 0, 1)]));
+
+    // Indentation of any width, with the continuation lines of a signature and a call left out
+    let python = r#"class Tree:
+    def walk(self,
+             tree,
+             sep=", "):
+      for node in tree:
+          total = sum(
+                  node.sizes)
+          if node:
+              return total
+"#;
+    assert_eq!(shapes("py", python), expected_shapes(&[("walk", 3, 2)]));
+
+    // Modifiers open no block; parentheses are optional
+    let ruby = "def walk tree, depth = 0\n  tree.each do |node|\n    return if node.nil?\n    while node.busy do\n      node.wait\n    end\n  end\nend\n\ndef reset\n  @nodes = []\nend\n";
+    assert_eq!(shapes("rb", ruby), expected_shapes(&[("walk", 3, 2), ("reset", 1, 0)]));
+
+    let lua = "function walk(tree, depth)\n  for _, node in ipairs(tree) do\n    if node then node:run() end\n  end\nend\n";
+    assert_eq!(shapes("lua", lua), expected_shapes(&[("walk", 3, 2)]));
+
+    // `end` also indexes the last element
+    let matlab = "function total = walk(tree, depth)\n  total = 0;\n  for i = 1:numel(tree)\n    if tree(end) > 0\n      total = total + tree(i);\n    end\n  end\nend\n";
+    assert_eq!(shapes("m", matlab), expected_shapes(&[("walk", 3, 2)]));
+
+    let erlang = "walk(Tree, Depth) ->\n    lists:foreach(fun (Node) ->\n        case Node of\n            leaf -> ok\n        end\n    end, Tree),\n    lists:map(fun visit/1, Tree).\n";
+    assert_eq!(shapes("erl", erlang), expected_shapes(&[("walk", 3, 2)]));
+
+    let haskell = "walk :: Ord a => (a -> Bool) -> [a] -> [a]\nwalk keep (x:xs)\n  | keep x = x : rest\n  | otherwise = rest\n  where\n    rest = walk keep xs\n";
+    assert_eq!(shapes("hs", haskell), expected_shapes(&[("walk", 2, 2)]));
+
+    let clojure = "(defn walk\n  \"Visits [every] node\"\n  [tree & {:keys [depth]}]\n  (doseq [node tree]\n    (when (:visible node)\n      (visit node))))\n";
+    assert_eq!(shapes("clj", clojure), expected_shapes(&[("walk", 3, 2)]));
+
+    let perl = "sub walk {\n    my ($self, $tree, $depth) = @_;\n    for my $node (@$tree) {\n        next unless $node;\n    }\n}\n";
+    assert_eq!(shapes("pl", perl), expected_shapes(&[("walk", 2, 2)]));
+
+    let verilog = "function automatic int walk;\n  input int tree, depth;\n  begin\n    case (tree)\n      0: walk = 4'hF;\n      default: begin walk = depth; end\n    endcase\n  end\nendfunction\n";
+    assert_eq!(shapes("v", verilog), expected_shapes(&[("walk", 4, 2)]));
+}
+
+/// Functions nested past the limit are reported in every language, whatever delimits their blocks
+#[test]
+fn test_deeply_nested_functions_are_flagged() {
+    let sources = [
+        ("rs", "fn walk(tree: &Tree) {\n    for node in tree {\n        if node.visible {\n            while node.busy {\n                match node.kind {\n                    Kind::Leaf => {\n                        node.run();\n                    }\n                    _ => {}\n                }\n            }\n        }\n    }\n}\n"),
+        ("kt", "fun walk(tree: Tree) {\n    for (node in tree) {\n        if (node.visible) {\n            while (node.busy) {\n                when (node.kind) {\n                    Kind.LEAF -> {\n                        node.run()\n                    }\n                }\n            }\n        }\n    }\n}\n"),
+        ("py", "def walk(tree):\n    for node in tree:\n        if node.visible:\n            while node.busy:\n                with node.lock:\n                    try:\n                        node.run()\n                    except OSError:\n                        pass\n"),
+        ("rb", "def walk(tree)\n  tree.each do |node|\n    if node.visible\n      while node.busy\n        case node.kind\n        when :leaf\n          begin\n            node.run\n          rescue IOError\n            retry\n          end\n        end\n      end\n    end\n  end\nend\n"),
+        ("lua", "function walk(tree)\n  for _, node in ipairs(tree) do\n    if node.visible then\n      while node.busy do\n        repeat\n          if node.ready then node:run() end\n        until node.done\n      end\n    end\n  end\nend\n"),
+        ("ex", "defmodule Tree do\n  def walk(tree) do\n    Enum.each(tree, fn node ->\n      if node.visible do\n        case node.kind do\n          :leaf ->\n            with {:ok, _} <- run(node) do\n              if node.done do\n                :ok\n              end\n            end\n        end\n      end\n    end)\n  end\nend\n"),
+        ("vhd", "architecture rtl of walker is\nbegin\n  walk : process (clk)\n  begin\n    if rising_edge(clk) then\n      for i in 0 to 7 loop\n        case state is\n          when idle =>\n            if start = '1' then\n              while busy loop\n                null;\n              end loop;\n            end if;\n          when others => null;\n        end case;\n      end loop;\n    end if;\n  end process walk;\nend architecture rtl;\n"),
+    ];
+    let thresholds = FunctionThresholds::default();
+
+    for (extension, source) in sources {
+        let lines: Vec<String> = source.lines().map(String::from).collect();
+        let functions = get_language_analyzer(extension).unwrap().analyze_functions(&lines).unwrap();
+        let walk = functions.iter().find(|func| func.name == "walk").unwrap();
+        assert_eq!(walk.nesting_depth, 6, "{}", extension);
+        assert!(thresholds.is_exceeded_by(walk), "{}", extension);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{count_keywords, BraceScanner, KeywordBlockTracker};
+use super::parameters;
+use super::LanguageAnalyzer;
+
+/// Verilog and SystemVerilog complexity analyzer: functions and tasks are the
+/// functions; modules, interfaces, packages and classes the structures
+pub struct VerilogAnalyzer;
+
+impl VerilogAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn code<'a>(&self, line: &'a str) -> &'a str {
+        line.split("//").next().unwrap_or("")
+    }
+
+    /// Keyword and name of a declaration opening a `function`/`task` or a
+    /// design unit (`module counter #(...) (`, `function automatic logic [7:0] add(`)
+    fn declaration<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str)> {
+        let code = self.code(line);
+        let mut words = code.split(|c: char| c.is_whitespace() || c == '(' || c == ';' || c == '#').filter(|word| !word.is_empty());
+        let mut keyword = words.next()?;
+        // Prototypes (`pure virtual function`, `extern task`) have no body, so they stop at their first word
+        while matches!(keyword, "virtual" | "static" | "protected" | "local") {
+            keyword = words.next()?;
+        }
+        if !matches!(keyword, "function" | "task" | "module" | "macromodule" | "interface" | "package" | "class" | "program") {
+            return None;
+        }
+        if keyword == "interface" && code.trim_start().starts_with("interface class") {
+            return None;
+        }
+
+        // The name is the last identifier before the port or argument list
+        let header = code.split(['(', ';']).next().unwrap_or(code);
+        let header = header.split('#').next().unwrap_or(header);
+        let header = header.split(" extends ").next().unwrap_or(header);
+        let name = header.split_whitespace().last()?;
+        let name = name.rsplit(']').next().unwrap_or(name);
+        let name = name.rsplit("::").next().unwrap_or(name);
+        let valid = name != keyword && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+        (valid && !name.is_empty()).then_some((keyword, name))
+    }
+
+    /// Last line of the declaration and the deepest nesting of its blocks,
+    /// the declaration's own being level 1
+    fn find_end(&self, lines: &[String], start_line: usize, keyword: &str) -> (usize, usize) {
+        let mut tracker = match keyword {
+            "function" => KeywordBlockTracker::new(&["function", "begin", "case", "casez", "casex", "fork"], &["endfunction", "end", "endcase", "join", "join_any", "join_none"], "//"),
+            "task" => KeywordBlockTracker::new(&["task", "begin", "case", "casez", "casex", "fork"], &["endtask", "end", "endcase", "join", "join_any", "join_none"], "//"),
+            "module" | "macromodule" => KeywordBlockTracker::new(&["module", "macromodule"], &["endmodule"], "//"),
+            "interface" => KeywordBlockTracker::new(&["interface"], &["endinterface"], "//"),
+            "package" => KeywordBlockTracker::new(&["package"], &["endpackage"], "//"),
+            "class" => KeywordBlockTracker::new(&["class"], &["endclass"], "//"),
+            _ => KeywordBlockTracker::new(&["program"], &["endprogram"], "//"),
+        };
+        for (index, line) in lines.iter().enumerate().skip(start_line) {
+            // `'` only marks sized literals such as `4'hF`, never strings
+            if tracker.feed(&line.replace('\'', " ")) {
+                return (index, tracker.max_depth());
+            }
+        }
+        (lines.len().saturating_sub(1), tracker.max_depth())
+    }
+
+    fn count_branches(&self, line: &str) -> usize {
+        let code = self.code(line);
+        count_keywords(code, &["if", "for", "foreach", "while", "repeat", "casez", "casex", "case"])
+            + code.matches('?').count()
+            + code.matches("&&").count()
+            + code.matches("||").count()
+    }
+
+    /// Arguments in the parenthesized list of the declaration, or in the older
+    /// style in the `input`, `output` and `inout` declarations of its body
+    fn count_parameters(&self, lines: &[String], index: usize, end_line: usize, name: &str) -> usize {
+        if self.code(&lines[index]).contains('(') {
+            return parameters::count(lines, index, name, BraceScanner::new(), &parameters::UNTYPED);
+        }
+        lines[index + 1..=end_line]
+            .iter()
+            .filter_map(|line| ["input ", "output ", "inout ", "ref "].iter().find_map(|direction| self.code(line).trim().strip_prefix(direction)))
+            .map(|declared| declared.trim_end_matches(';').split(',').count())
+            .sum()
+    }
+
+    fn function_info(&self, lines: &[String], index: usize, name: &str, keyword: &str, parent: Option<&str>) -> FunctionInfo {
+        let (end_line, nesting_depth) = self.find_end(lines, index, keyword);
+        let complexity = 1 + lines[index..=end_line].iter().map(|line| self.count_branches(line)).sum::<usize>();
+        FunctionInfo {
+            name: name.to_string(),
+            line_count: end_line - index + 1,
+            cyclomatic_complexity: complexity,
+            cognitive_complexity: complexity,
+            nesting_depth,
+            parameter_count: self.count_parameters(lines, index, end_line, name),
+            return_path_count: 1,
+            start_line: index + 1,
+            end_line: end_line + 1,
+            is_method: parent.is_some(),
+            parent_class: parent.map(str::to_string),
+            local_variable_count: 0,
+            has_recursion: false,
+            has_exception_handling: false,
+            visibility: Visibility::Public,
+        }
+    }
+}
+
+impl LanguageAnalyzer for VerilogAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let mut unit: Option<(&str, usize)> = None;
+
+        for (index, line) in lines.iter().enumerate() {
+            match self.declaration(line) {
+                Some((keyword @ ("function" | "task"), name)) => {
+                    let parent = unit.filter(|(_, end)| index <= *end).map(|(unit, _)| unit);
+                    functions.push(self.function_info(lines, index, name, keyword, parent));
+                }
+                Some((keyword, name)) => unit = Some((name, self.find_end(lines, index, keyword).0)),
+                None => {}
+            }
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, lines: &[String]) -> Result<Vec<StructureInfo>> {
+        let mut structures = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some((keyword, name)) = self.declaration(line) else {
+                continue;
+            };
+            let structure_type = match keyword {
+                "module" | "macromodule" | "program" => StructureType::Module,
+                "interface" => StructureType::Interface,
+                "package" => StructureType::Namespace,
+                "class" => StructureType::Class,
+                _ => continue,
+            };
+
+            let (end_line, _) = self.find_end(lines, index, keyword);
+            let mut methods = Vec::new();
+            let mut properties = 0;
+            for member in index + 1..end_line {
+                match self.declaration(&lines[member]) {
+                    Some((member_keyword @ ("function" | "task"), member_name)) => {
+                        methods.push(self.function_info(lines, member, member_name, member_keyword, Some(name)));
+                    }
+                    // Ports and signals, one declaration per line
+                    _ => {
+                        let declaration = self.code(&lines[member]).split('=').next().unwrap_or("");
+                        if count_keywords(declaration, &["input", "output", "inout", "wire", "reg", "logic"]) > 0 {
+                            properties += 1;
+                        }
+                    }
+                }
+            }
+
+            structures.push(StructureInfo {
+                name: name.to_string(),
+                structure_type,
+                line_count: end_line - index + 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                methods,
+                properties,
+                visibility: Visibility::Public,
+                inheritance_depth: usize::from(keyword == "class" && line.contains(" extends ")),
+                interface_count: 0,
+            });
+        }
+
+        Ok(structures)
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Verilog"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["v", "vh", "sv", "svh"]
+    }
+}
+
+impl Default for VerilogAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,244 @@
+use crate::utils::errors::Result;
+use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{count_keywords, BraceScanner};
+use super::LanguageAnalyzer;
+
+/// Constructs closed by `end <keyword>` inside a subprogram, process or design unit
+const INNER_BLOCKS: &[&str] = &["if", "loop", "case", "generate", "block", "record", "component", "units", "protected", "for"];
+
+/// VHDL complexity analyzer: functions, procedures and processes are the
+/// functions; entities, architectures and packages the structures.
+/// VHDL is case-insensitive, so lines are compared in lowercase.
+pub struct VhdlAnalyzer;
+
+impl VhdlAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Lowercased words of the line's code, without its `--` comment
+    fn words(&self, line: &str) -> Vec<String> {
+        let code = line.split("--").next().unwrap_or("").to_lowercase();
+        code.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Kind and name of a subprogram body or process starting on the line:
+    /// `function parity(v : bits) return bit is`, `sync : process (clk)`.
+    /// Subprogram declarations without `is` have no body.
+    fn subprogram(&self, line: &str) -> Option<(&'static str, String)> {
+        let words = self.words(line);
+        let code = line.split("--").next().unwrap_or("").to_lowercase();
+
+        if let Some(position) = words.iter().position(|word| word == "process") {
+            if position > 0 && words[position - 1] == "end" {
+                return None;
+            }
+            // An optional label before the colon names the process
+            let label = code.split_once(':').map(|(label, _)| label.trim()).filter(|label| !label.is_empty() && !label.contains(' '));
+            return Some(("process", label.unwrap_or("process").to_string()));
+        }
+
+        let mut words = words.iter().map(String::as_str).skip_while(|word| matches!(*word, "pure" | "impure"));
+        let kind = match words.next()? {
+            "function" => "function",
+            "procedure" => "procedure",
+            _ => return None,
+        };
+        let name = words.next()?.to_string();
+        // The body starts at `is`, possibly on a later line of a long parameter list
+        (!code.trim_end().ends_with(';')).then_some((kind, name))
+    }
+
+    /// Kind and name of an entity, architecture (named `entity(architecture)`) or package
+    fn design_unit(&self, line: &str) -> Option<(&'static str, String)> {
+        let words = self.words(line);
+        match words.as_slice() {
+            [entity, name, is, ..] if entity == "entity" && is == "is" => Some(("entity", name.clone())),
+            [architecture, name, of, entity, ..] if architecture == "architecture" && of == "of" => Some(("architecture", format!("{}({})", entity, name))),
+            [package, body, name, ..] if package == "package" && body == "body" => Some(("package", name.clone())),
+            [package, name, is, ..] if package == "package" && is == "is" => Some(("package", name.clone())),
+            _ => None,
+        }
+    }
+
+    /// Line closing the construct opened on `start_line`: `end process`,
+    /// `end function parity;`, `end architecture;` or a bare `end;`. The
+    /// `end if`/`end loop` of statements inside it don't close it, and neither
+    /// do the ends of the processes and subprograms it contains.
+    fn find_end(&self, lines: &[String], start_line: usize) -> usize {
+        let mut nested = 0usize;
+        for (index, line) in lines.iter().enumerate().skip(start_line + 1) {
+            if self.subprogram(line).is_some() {
+                nested += 1;
+                continue;
+            }
+            let words = self.words(line);
+            let Some(end) = words.iter().position(|word| word == "end") else {
+                continue;
+            };
+            if words.get(end + 1).is_some_and(|word| INNER_BLOCKS.contains(&word.as_str())) {
+                continue;
+            }
+            if nested == 0 {
+                return index;
+            }
+            nested -= 1;
+        }
+        lines.len().saturating_sub(1)
+    }
+
+    fn count_branches(&self, line: &str) -> usize {
+        let code = line.split("--").next().unwrap_or("").to_lowercase();
+        let choices = count_keywords(&code, &["when"]) - code.matches("when others").count();
+        count_keywords(&code, &["if", "elsif", "for", "while"]) - code.matches("end if").count() + choices
+    }
+
+    /// Deepest nesting of the `if`, `case` and loop statements in a body, the body
+    /// itself being level 1. Each closes at its own `end if`, `end case` or `end loop`.
+    fn nesting_depth(&self, lines: &[String], start_line: usize, end_line: usize) -> usize {
+        let mut depth = 0usize;
+        let mut deepest = 0;
+        for line in &lines[start_line..=end_line] {
+            let words = self.words(line);
+            for (position, word) in words.iter().enumerate() {
+                if !matches!(word.as_str(), "if" | "case" | "loop") {
+                    continue;
+                }
+                if position > 0 && words[position - 1] == "end" {
+                    depth = depth.saturating_sub(1);
+                } else {
+                    depth += 1;
+                    deepest = deepest.max(depth);
+                }
+            }
+        }
+        deepest + 1
+    }
+
+    /// Parameters of a subprogram: every name in its interface list (`a, b : in bit; c : out bit` has 3)
+    fn count_parameters(&self, lines: &[String], start_line: usize) -> usize {
+        let mut header = String::new();
+        for line in &lines[start_line..] {
+            header.push_str(line.split("--").next().unwrap_or(""));
+            header.push(' ');
+            if line.to_lowercase().contains(" is") || line.contains(';') && !line.contains('(') {
+                break;
+            }
+        }
+        let Some((_, list)) = header.split_once('(') else {
+            return 0;
+        };
+        let list = list.rsplit_once(')').map_or(list, |(list, _)| list);
+        list.split(';')
+            .filter_map(|declaration| declaration.split_once(':'))
+            .map(|(names, _)| names.split(',').filter(|name| !name.trim().is_empty()).count())
+            .sum()
+    }
+
+    fn function_info(&self, lines: &[String], index: usize, kind: &str, name: String, parent: Option<&str>) -> FunctionInfo {
+        let end_line = self.find_end(lines, index);
+        let complexity = 1 + lines[index..=end_line].iter().map(|line| self.count_branches(line)).sum::<usize>();
+        FunctionInfo {
+            name,
+            line_count: end_line - index + 1,
+            cyclomatic_complexity: complexity,
+            cognitive_complexity: complexity,
+            nesting_depth: self.nesting_depth(lines, index, end_line),
+            parameter_count: if kind == "process" { 0 } else { self.count_parameters(lines, index) },
+            return_path_count: 1,
+            start_line: index + 1,
+            end_line: end_line + 1,
+            is_method: parent.is_some(),
+            parent_class: parent.map(str::to_string),
+            local_variable_count: 0,
+            has_recursion: false,
+            has_exception_handling: false,
+            visibility: Visibility::Public,
+        }
+    }
+}
+
+impl LanguageAnalyzer for VhdlAnalyzer {
+    fn analyze_functions(&self, lines: &[String]) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let mut unit: Option<(String, usize)> = None;
+
+        for (index, line) in lines.iter().enumerate() {
+            if let Some((_, name)) = self.design_unit(line) {
+                unit = Some((name, self.find_end(lines, index)));
+            } else if let Some((kind, name)) = self.subprogram(line) {
+                let parent = unit.as_ref().filter(|(_, end)| index <= *end).map(|(unit, _)| unit.as_str());
+                functions.push(self.function_info(lines, index, kind, name, parent));
+            }
+        }
+
+        Ok(functions)
+    }
+
+    fn analyze_structures(&self, lines: &[String]) -> Result<Vec<StructureInfo>> {
+        let mut structures = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some((kind, name)) = self.design_unit(line) else {
+                continue;
+            };
+            let end_line = self.find_end(lines, index);
+
+            let mut methods = Vec::new();
+            let mut properties = 0;
+            for member in index + 1..end_line {
+                if let Some((member_kind, member_name)) = self.subprogram(&lines[member]) {
+                    methods.push(self.function_info(lines, member, member_kind, member_name, Some(&name)));
+                    continue;
+                }
+                // Ports, signals, constants and variables, one declaration per line
+                let words = self.words(&lines[member]);
+                let object = matches!(words.first().map(String::as_str), Some("signal" | "constant" | "variable"));
+                let port = words.iter().any(|word| matches!(word.as_str(), "in" | "out" | "inout" | "buffer"));
+                if lines[member].contains(':') && (object || port) {
+                    properties += 1;
+                }
+            }
+
+            structures.push(StructureInfo {
+                structure_type: match kind {
+                    "entity" => StructureType::Interface,
+                    "package" => StructureType::Namespace,
+                    _ => StructureType::Module,
+                },
+                name,
+                line_count: end_line - index + 1,
+                start_line: index + 1,
+                end_line: end_line + 1,
+                methods,
+                properties,
+                visibility: Visibility::Public,
+                inheritance_depth: 0,
+                interface_count: usize::from(kind == "architecture"),
+            });
+        }
+
+        Ok(structures)
+    }
+
+    fn language_name(&self) -> &'static str {
+        "VHDL"
+    }
+
+    fn supported_extensions(&self) -> Vec<&'static str> {
+        vec!["vhd", "vhdl"]
+    }
+
+    fn comment_scanner(&self) -> BraceScanner {
+        BraceScanner::new().with_line_comments(&["--"]).with_block_comments(false)
+    }
+}
+
+impl Default for VhdlAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
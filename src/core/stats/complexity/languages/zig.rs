@@ -1,5 +1,7 @@
 use crate::utils::errors::Result;
 use super::super::types::{FunctionInfo, StructureInfo, StructureType, Visibility};
+use super::blocks::{BlockTracker, BraceScanner};
+use super::parameters;
 use super::LanguageAnalyzer;
 
 /// Zig language complexity analyzer
@@ -107,30 +109,18 @@ impl ZigAnalyzer {
         complexity
     }
     
-    /// Find the end of a function definition
-    fn find_function_end(&self, lines: &[String], start_line: usize) -> usize {
-        let mut depth = 0;
-        let mut in_function = false;
+    /// Find the end of a function definition and the deepest nesting of its body
+    fn find_function_end(&self, lines: &[String], start_line: usize) -> (usize, usize) {
+        // Braces in strings and comments are ignored; `extern fn` declarations end at their `;`
+        let mut tracker = BlockTracker::new(BraceScanner::new().with_block_comments(false));
         
         for (i, line) in lines.iter().enumerate().skip(start_line) {
-            let trimmed = line.trim();
-            
-            if trimmed.contains("fn ") {
-                in_function = true;
-            }
-            
-            if in_function {
-                // Count braces
-                depth += trimmed.matches('{').count();
-                depth = depth.saturating_sub(trimmed.matches('}').count());
-                
-                if depth == 0 && trimmed.contains('}') {
-                    return i;
-                }
+            if tracker.feed(line) {
+                return (i, tracker.max_depth());
             }
         }
         
-        lines.len().saturating_sub(1)
+        (lines.len().saturating_sub(1), tracker.max_depth())
     }
     
     /// Determine visibility of a function
@@ -164,7 +154,8 @@ impl LanguageAnalyzer for ZigAnalyzer {
         
         for (i, line) in lines.iter().enumerate() {
             if let Some(func_name) = self.extract_function_name(line) {
-                let end_line = self.find_function_end(lines, i);
+                let (end_line, nesting_depth) = self.find_function_end(lines, i);
+                let parameter_count = parameters::count(lines, i, &func_name, BraceScanner::new().with_block_comments(false), &parameters::UNTYPED);
                 let complexity = self.calculate_cyclomatic_complexity(lines, i, end_line);
                 let _visibility = self.determine_visibility(line);
                 
@@ -173,8 +164,8 @@ impl LanguageAnalyzer for ZigAnalyzer {
                     line_count: end_line.saturating_sub(i).max(1),
                     cyclomatic_complexity: complexity,
                     cognitive_complexity: complexity,
-                    nesting_depth: 0,
-                    parameter_count: self.count_parameters(line),
+                    nesting_depth,
+                    parameter_count,
                     return_path_count: 1,
                     start_line: i + 1,
                     end_line: end_line + 1,
@@ -227,21 +218,6 @@ impl LanguageAnalyzer for ZigAnalyzer {
 }
 
 impl ZigAnalyzer {
-    /// Count parameters in a function definition
-    fn count_parameters(&self, line: &str) -> usize {
-        if let Some(start) = line.find('(') {
-            if let Some(end) = line.find(')') {
-                let params = &line[start + 1..end];
-                if params.trim().is_empty() {
-                    return 0;
-                }
-                return params.split(',').count();
-            }
-        }
-        
-        0
-    }
-    
     /// Extract return type from function definition
     fn extract_return_type(&self, line: &str) -> Option<String> {
         // Look for return type after closing parenthesis
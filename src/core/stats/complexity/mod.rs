@@ -1,15 +1,20 @@
 use crate::core::types::{CodeStats, FileStats};
+use crate::utils::cache::AnalysisCache;
 use crate::utils::errors::Result;
 
 // Re-export all public types
 pub use types::*;
+pub use overrides::AnalyzerOverrides;
+pub use halstead::{maintainability_index, HalsteadMetrics};
 
 // Internal modules
 mod types;
 mod analyzer;
 mod quality;
+mod halstead;
 mod calculator;
-mod languages;
+mod overrides;
+pub(crate) mod languages;
 
 // Main interface - this is the public API that other modules will use
 pub struct ComplexityStatsCalculator {
@@ -23,11 +28,57 @@ impl ComplexityStatsCalculator {
         }
     }
     
+    /// Use custom limits when selecting functions to report individually
+    pub fn with_thresholds(mut self, thresholds: FunctionThresholds) -> Self {
+        self.calculator = self.calculator.with_thresholds(thresholds);
+        self
+    }
+    
+    /// Keep the `count` most complex functions of a project (`--top-functions`)
+    pub fn with_top_functions(mut self, count: usize) -> Self {
+        self.calculator = self.calculator.with_top_functions(count);
+        self
+    }
+    
+    /// Keep the `count` lowest-scoring files of a project (`--worst`)
+    pub fn with_worst_files(mut self, count: usize) -> Self {
+        self.calculator = self.calculator.with_worst_files(count);
+        self
+    }
+    
+    /// Skip files or pick their analyzer by path (`[complexity]`)
+    pub fn with_overrides(mut self, overrides: AnalyzerOverrides) -> Self {
+        self.calculator = self.calculator.with_overrides(overrides);
+        self
+    }
+    
+    /// Match line counts keyed by file extension (`--by-extension`)
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.calculator = self.calculator.with_by_extension(by_extension);
+        self
+    }
+    
+    /// Reuse functions and structures found in files with the same contents before
+    pub fn with_analysis_cache(mut self, cache: Option<AnalysisCache>) -> Self {
+        self.calculator = self.calculator.with_analysis_cache(cache);
+        self
+    }
+    
     /// Calculate complexity statistics for a single file
     pub fn calculate_complexity_stats(&self, file_stats: &FileStats, file_path: &str) -> Result<ComplexityStats> {
         self.calculator.calculate_complexity_stats(file_stats, file_path)
     }
     
+    /// Whether a file gets per-function analysis: it isn't skipped and its language has an analyzer
+    pub fn analyzes(&self, file_path: &str) -> bool {
+        self.calculator.analyzes(file_path)
+    }
+    
+    /// Sum of the cyclomatic complexity of every function in a file
+    pub fn calculate_total_complexity(&self, file_path: &str) -> Result<usize> {
+        self.calculator.calculate_total_complexity(file_path)
+    }
+    
     /// Calculate complexity statistics for a project
     pub fn calculate_project_complexity_stats(&self, code_stats: &CodeStats, individual_files: &[(String, FileStats)]) -> Result<ComplexityStats> {
         self.calculator.calculate_project_complexity_stats(code_stats, individual_files)
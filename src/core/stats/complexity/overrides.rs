@@ -0,0 +1,91 @@
+use crate::core::filters::glob::GlobPatterns;
+use crate::utils::config::ComplexityConfig;
+use crate::utils::errors::{HowManyError, Result};
+use super::languages::has_language_analyzer;
+use std::path::{Path, PathBuf};
+
+/// Per-path choice of complexity analyzer from `[complexity]`: files left out
+/// of the analysis (huge generated parsers) and files analyzed as another
+/// language than their extension says (`scripts/*.jsm` as JavaScript)
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzerOverrides {
+    skip: Option<GlobPatterns>,
+    analyzers: Vec<(GlobPatterns, String)>,
+    /// Analyzed roots, so globs match paths relative to them
+    roots: Vec<PathBuf>,
+}
+
+impl AnalyzerOverrides {
+    /// Overrides from the project config; fails on a glob that doesn't parse or
+    /// an analyzer extension no analyzer handles
+    pub fn new(config: &ComplexityConfig) -> Result<Self> {
+        let skip = if config.skip.is_empty() {
+            None
+        } else {
+            Some(GlobPatterns::new(&config.skip)?)
+        };
+
+        let analyzers = config.analyzers
+            .iter()
+            .map(|(pattern, extension)| {
+                let extension = extension.trim_start_matches('.').to_lowercase();
+                if !has_language_analyzer(&extension) {
+                    return Err(HowManyError::invalid_config(format!(
+                        "Complexity analyzer for \"{}\" is \"{}\", which is not an extension with a complexity analyzer",
+                        pattern, extension
+                    )));
+                }
+                Ok((GlobPatterns::new(std::slice::from_ref(pattern))?, extension))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { skip, analyzers, roots: Vec::new() })
+    }
+
+    /// Match globs relative to these analyzed roots
+    pub fn with_roots(mut self, roots: &[PathBuf]) -> Self {
+        self.roots = roots.to_vec();
+        self
+    }
+
+    /// Extension of the analyzer for `file_path`, given its own extension:
+    /// the configured one, its own, or `None` when it is skipped
+    pub fn analyzer_for(&self, file_path: &str, extension: &str) -> Option<String> {
+        let path = self.relative(Path::new(file_path));
+        if self.skip.as_ref().is_some_and(|skip| skip.is_match(path)) {
+            return None;
+        }
+        let forced = self.analyzers.iter().find(|(patterns, _)| patterns.is_match(path));
+        Some(forced.map_or(extension, |(_, extension)| extension.as_str()).to_string())
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        let path = self.roots.iter().find_map(|root| path.strip_prefix(root).ok()).unwrap_or(path);
+        path.strip_prefix(".").unwrap_or(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyzer_overrides() {
+        let config = ComplexityConfig {
+            skip: vec!["src/parser/generated/**".to_string()],
+            analyzers: [("scripts/*.jsm".to_string(), "js".to_string())].into_iter().collect(),
+        };
+        let overrides = AnalyzerOverrides::new(&config).unwrap().with_roots(&[PathBuf::from("/work/app")]);
+
+        assert_eq!(overrides.analyzer_for("/work/app/src/parser/generated/grammar.rs", "rs"), None);
+        assert_eq!(overrides.analyzer_for("/work/app/scripts/build.jsm", "jsm"), Some("js".to_string()));
+        assert_eq!(overrides.analyzer_for("./src/main.rs", "rs"), Some("rs".to_string()));
+        assert_eq!(AnalyzerOverrides::default().analyzer_for("src/parser/generated/grammar.rs", "rs"), Some("rs".to_string()));
+
+        let config = ComplexityConfig {
+            analyzers: [("scripts/**".to_string(), "txt".to_string())].into_iter().collect(),
+            ..ComplexityConfig::default()
+        };
+        assert!(AnalyzerOverrides::new(&config).is_err());
+    }
+}
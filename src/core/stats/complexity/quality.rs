@@ -1,5 +1,5 @@
 use crate::core::types::{CodeStats, FileStats};
-use super::types::{QualityMetrics, FunctionInfo, StructureInfo, ComplexityLevel, FunctionComplexityDetail};
+use super::types::{QualityMetrics, FunctionInfo, StructureInfo, ComplexityLevel, FunctionComplexityDetail, FileQuality, LONG_PARAMETER_LIST_THRESHOLD};
 
 /// Quality metrics calculator
 pub struct QualityCalculator;
@@ -9,10 +9,10 @@ impl QualityCalculator {
         Self
     }
 
-    /// Calculate code health metrics for practical developer insights
-    pub fn calculate_quality_metrics(&self, functions: &[FunctionInfo], file_stats: &FileStats, _structures: &[StructureInfo]) -> QualityMetrics {
-        let code_health_score = self.calculate_code_health_score(functions, file_stats);
-        let maintainability_index = self.calculate_maintainability_index(functions, file_stats);
+    /// Calculate code health metrics for practical developer insights, given the
+    /// maintainability index the complexity calculator found
+    pub fn calculate_quality_metrics(&self, functions: &[FunctionInfo], file_stats: &FileStats, _structures: &[StructureInfo], maintainability_index: f64) -> QualityMetrics {
+        let code_health_score = self.calculate_code_health_score(functions, file_stats, maintainability_index);
         let documentation_coverage = self.calculate_documentation_coverage(file_stats);
         let avg_complexity = self.calculate_average_complexity(functions);
         let function_size_health = self.calculate_function_size_health(functions, file_stats);
@@ -33,8 +33,7 @@ impl QualityCalculator {
     }
     
     /// Calculate overall code health score based on practical metrics
-    fn calculate_code_health_score(&self, functions: &[FunctionInfo], file_stats: &FileStats) -> f64 {
-        let maintainability = self.calculate_maintainability_index(functions, file_stats);
+    fn calculate_code_health_score(&self, functions: &[FunctionInfo], file_stats: &FileStats, maintainability: f64) -> f64 {
         let documentation = self.calculate_documentation_coverage(file_stats);
         let complexity = 100.0 - (self.calculate_average_complexity(functions) * 10.0).min(100.0); // Invert complexity for score
         let function_size = self.calculate_function_size_health(functions, file_stats);
@@ -44,84 +43,6 @@ impl QualityCalculator {
         (maintainability * 0.3 + documentation * 0.2 + complexity * 0.25 + function_size * 0.15 + nesting_depth * 0.1).min(100.0).max(0.0)
     }
     
-    /// Calculate industry-standard maintainability index
-    fn calculate_maintainability_index(&self, functions: &[FunctionInfo], file_stats: &FileStats) -> f64 {
-        // If no functions detected, estimate based on file characteristics
-        if functions.is_empty() {
-            let mut score = 85.0; // Start with good baseline
-            
-            // Apply progressive file length penalty
-            let file_length_penalty = if file_stats.total_lines > 500 {
-                if file_stats.total_lines > 2000 {
-                    // Very large files: significant penalty
-                    ((file_stats.total_lines - 500) as f64 / 100.0).min(40.0)
-                } else if file_stats.total_lines > 1000 {
-                    // Large files: moderate penalty
-                    ((file_stats.total_lines - 500) as f64 / 150.0).min(25.0)
-                } else {
-                    // Medium files: small penalty
-                    ((file_stats.total_lines - 500) as f64 / 200.0).min(15.0)
-                }
-            } else {
-                0.0
-            };
-            
-            score -= file_length_penalty;
-            
-            // Reward good documentation
-            let doc_ratio = (file_stats.comment_lines + file_stats.doc_lines) as f64 / file_stats.code_lines.max(1) as f64;
-            if doc_ratio > 0.2 {
-                score += 10.0;
-            } else if doc_ratio < 0.05 {
-                score -= 15.0;
-            }
-            
-            // Penalize files with very little code (likely config files)
-            if file_stats.code_lines < 10 {
-                score -= 20.0;
-            }
-            
-            return score.min(100.0).max(0.0);
-        }
-
-        let mut total_score = 0.0;
-        
-        for func in functions {
-            // Simplified maintainability calculation based on:
-            // - Function length (shorter is better)
-            // - Cyclomatic complexity (lower is better)
-            // - Cognitive complexity (lower is better)
-            // - Parameter count (fewer is better)
-            
-            let length_score = (50.0 - func.line_count as f64).max(0.0);
-            let cyclomatic_score = (30.0 - func.cyclomatic_complexity as f64 * 2.0).max(0.0);
-            let cognitive_score = (30.0 - func.cognitive_complexity as f64 * 2.0).max(0.0);
-            let param_score = (20.0 - func.parameter_count as f64 * 3.0).max(0.0);
-            
-            total_score += length_score + cyclomatic_score + cognitive_score + param_score;
-        }
-        
-        let base_score = (total_score / functions.len() as f64).min(100.0).max(0.0);
-        
-        // Apply file length penalty - files over 500 lines are considered less maintainable
-        let file_length_penalty = if file_stats.total_lines > 500 {
-            if file_stats.total_lines > 2000 {
-                // Very large files: significant penalty
-                ((file_stats.total_lines - 500) as f64 / 100.0).min(40.0)
-            } else if file_stats.total_lines > 1000 {
-                // Large files: moderate penalty
-                ((file_stats.total_lines - 500) as f64 / 150.0).min(25.0)
-            } else {
-                // Medium files: small penalty
-                ((file_stats.total_lines - 500) as f64 / 200.0).min(15.0)
-            }
-        } else {
-            0.0
-        };
-        
-        (base_score - file_length_penalty).max(0.0)
-    }
-    
     /// Calculate readability score
     fn calculate_readability_score(&self, functions: &[FunctionInfo], file_stats: &FileStats) -> f64 {
         let mut score = 100.0;
@@ -280,26 +201,21 @@ impl QualityCalculator {
         score.min(100.0).max(0.0)
     }
     
-    /// Calculate code health metrics for the entire project
-    pub fn calculate_project_quality_metrics(&self, functions: &[FunctionInfo], code_stats: &CodeStats, _structures: &[StructureInfo]) -> QualityMetrics {
-        // Create a synthetic FileStats for project-level calculations
-        let project_file_stats = FileStats {
-            total_lines: code_stats.total_lines,
-            code_lines: code_stats.total_code_lines,
-            comment_lines: code_stats.total_comment_lines,
-            doc_lines: code_stats.total_doc_lines,
-            blank_lines: code_stats.total_blank_lines,
-            file_size: code_stats.total_size,
-        };
-        
-        let code_health_score = self.calculate_code_health_score(functions, &project_file_stats);
-        let maintainability_index = self.calculate_maintainability_index(functions, &project_file_stats);
+    /// Calculate code health metrics for the entire project. Complexity-based
+    /// metrics only see `analyzed_stats`, the lines of languages whose functions
+    /// were analyzed; documentation and duplication cover all of `code_stats`.
+    pub fn calculate_project_quality_metrics(&self, functions: &[FunctionInfo], code_stats: &CodeStats, analyzed_stats: &CodeStats, _structures: &[StructureInfo], maintainability_index: f64) -> QualityMetrics {
+        // Create synthetic FileStats for project-level calculations
+        let project_file_stats = Self::project_file_stats(code_stats);
+        let analyzed_file_stats = Self::project_file_stats(analyzed_stats);
+        
+        let code_health_score = self.calculate_code_health_score(functions, &analyzed_file_stats, maintainability_index);
         let documentation_coverage = self.calculate_documentation_coverage(&project_file_stats);
         let avg_complexity = self.calculate_average_complexity(functions);
-        let function_size_health = self.calculate_function_size_health(functions, &project_file_stats);
-        let nesting_depth_health = self.calculate_nesting_depth_health(functions, &project_file_stats);
+        let function_size_health = self.calculate_function_size_health(functions, &analyzed_file_stats);
+        let nesting_depth_health = self.calculate_nesting_depth_health(functions, &analyzed_file_stats);
         let code_duplication_ratio = self.estimate_project_code_duplication(code_stats);
-        let technical_debt_ratio = self.calculate_technical_debt_ratio(functions, &project_file_stats);
+        let technical_debt_ratio = self.calculate_technical_debt_ratio(functions, &analyzed_file_stats);
         
         QualityMetrics {
             code_health_score,
@@ -313,6 +229,27 @@ impl QualityCalculator {
         }
     }
     
+    fn project_file_stats(code_stats: &CodeStats) -> FileStats {
+        FileStats {
+            total_lines: code_stats.total_lines,
+            code_lines: code_stats.total_code_lines,
+            comment_lines: code_stats.total_comment_lines,
+            doc_lines: code_stats.total_doc_lines,
+            inline_comment_lines: code_stats.total_inline_comment_lines,
+            logical_lines: code_stats.total_logical_lines,
+            characters: code_stats.total_characters,
+            non_whitespace_characters: code_stats.total_non_whitespace_characters,
+            tokens: code_stats.total_tokens,
+            line_lengths: code_stats.line_lengths,
+            blank_lines: code_stats.total_blank_lines,
+            file_size: code_stats.total_size,
+            encoding: None,
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
+        }
+    }
+    
     /// Estimate code duplication for the entire project
     fn estimate_project_code_duplication(&self, code_stats: &CodeStats) -> f64 {
         let total_lines = code_stats.total_lines;
@@ -356,7 +293,7 @@ impl QualityCalculator {
             concerns.push("High cognitive complexity".to_string());
         }
         
-        if func.parameter_count > 5 {
+        if func.parameter_count > LONG_PARAMETER_LIST_THRESHOLD {
             concerns.push("Too many parameters".to_string());
         }
         
@@ -375,6 +312,23 @@ impl QualityCalculator {
         concerns
     }
 
+    /// Score one file from its quality metrics: 30% complexity, 30% function
+    /// size, 20% documentation and 20% the absence of duplication
+    pub fn calculate_file_quality(&self, file_path: &str, functions: &[FunctionInfo], file_stats: &FileStats, quality: &QualityMetrics) -> FileQuality {
+        let score = quality.complexity_score() * 0.3 + quality.function_size_health * 0.3 + quality.documentation_coverage * 0.2 + quality.uniqueness_score() * 0.2;
+        
+        FileQuality {
+            file_path: file_path.to_string(),
+            score: score.clamp(0.0, 100.0),
+            code_lines: file_stats.code_lines,
+            function_count: functions.len(),
+            average_complexity: quality.avg_complexity,
+            maintainability_index: quality.maintainability_index,
+            documentation_coverage: quality.documentation_coverage,
+            duplication_ratio: quality.code_duplication_ratio,
+        }
+    }
+
     /// Create detailed complexity information for functions
     pub fn create_function_complexity_details(&self, functions: &[FunctionInfo], file_path: &str) -> Vec<FunctionComplexityDetail> {
         functions.iter().map(|func| {
@@ -567,4 +521,41 @@ impl Default for QualityCalculator {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(avg_complexity: f64, function_size_health: f64, documentation_coverage: f64, code_duplication_ratio: f64) -> QualityMetrics {
+        QualityMetrics {
+            code_health_score: 0.0,
+            maintainability_index: 0.0,
+            documentation_coverage,
+            avg_complexity,
+            function_size_health,
+            nesting_depth_health: 0.0,
+            code_duplication_ratio,
+            technical_debt_ratio: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_file_quality_score() {
+        let calculator = QualityCalculator::new();
+        let file_stats = FileStats { code_lines: 40, ..FileStats::default() };
+
+        // 30% of 80 (complexity 2), 30% of 50, 20% of 100 and 20% of 80 (5% duplication)
+        let quality = calculator.calculate_file_quality("src/app.py", &[], &file_stats, &metrics(2.0, 50.0, 100.0, 5.0));
+        assert!((quality.score - 75.0).abs() < 1e-9);
+        assert_eq!((quality.file_path.as_str(), quality.code_lines, quality.function_count), ("src/app.py", 40, 0));
+        assert_eq!((quality.average_complexity, quality.documentation_coverage, quality.duplication_ratio), (2.0, 100.0, 5.0));
+
+        // Complexity and duplication bottom out at zero points
+        let quality = calculator.calculate_file_quality("src/app.py", &[], &file_stats, &metrics(40.0, 100.0, 100.0, 60.0));
+        assert!((quality.score - 50.0).abs() < 1e-9);
+
+        // Out-of-range inputs are clamped to the 0-100 scale
+        assert_eq!(calculator.calculate_file_quality("a.py", &[], &file_stats, &metrics(0.0, 500.0, 300.0, 0.0)).score, 100.0);
+        assert_eq!(calculator.calculate_file_quality("a.py", &[], &file_stats, &metrics(10.0, -400.0, 0.0, 25.0)).score, 0.0);
+    }
+}
@@ -3,9 +3,53 @@
 // This module contains only the type definitions to avoid circular dependencies
 
 
+use super::halstead::HalsteadMetrics;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Functions taking more parameters than this are reported as long parameter lists
+pub const LONG_PARAMETER_LIST_THRESHOLD: usize = 5;
+/// Functions above this cyclomatic complexity are reported individually
+pub const HIGH_CYCLOMATIC_COMPLEXITY_THRESHOLD: usize = 15;
+/// Functions above this cognitive complexity are reported individually
+pub const HIGH_COGNITIVE_COMPLEXITY_THRESHOLD: usize = 20;
+/// Functions nested deeper than this are reported individually
+pub const DEEP_NESTING_THRESHOLD: usize = 5;
+/// Functions longer than this many lines are reported individually
+pub const LONG_FUNCTION_THRESHOLD: usize = 50;
+
+/// Per-function limits above which a function is reported as a finding
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FunctionThresholds {
+    pub max_cyclomatic_complexity: usize,
+    pub max_cognitive_complexity: usize,
+    pub max_nesting_depth: usize,
+    pub max_function_length: usize,
+    pub max_parameters: usize,
+}
+
+impl FunctionThresholds {
+    /// Whether a function exceeds any of the complexity, nesting or length limits
+    pub fn is_exceeded_by(&self, function: &FunctionInfo) -> bool {
+        function.cyclomatic_complexity > self.max_cyclomatic_complexity
+            || function.cognitive_complexity > self.max_cognitive_complexity
+            || function.nesting_depth > self.max_nesting_depth
+            || function.line_count > self.max_function_length
+    }
+}
+
+impl Default for FunctionThresholds {
+    fn default() -> Self {
+        Self {
+            max_cyclomatic_complexity: HIGH_CYCLOMATIC_COMPLEXITY_THRESHOLD,
+            max_cognitive_complexity: HIGH_COGNITIVE_COMPLEXITY_THRESHOLD,
+            max_nesting_depth: DEEP_NESTING_THRESHOLD,
+            max_function_length: LONG_FUNCTION_THRESHOLD,
+            max_parameters: LONG_PARAMETER_LIST_THRESHOLD,
+        }
+    }
+}
+
 /// Complexity statistics for a file or project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplexityStats {
@@ -26,13 +70,31 @@ pub struct ComplexityStats {
     pub max_nesting_depth: usize,
     pub average_nesting_depth: f64,
     pub methods_per_class: f64,
+    /// Methods of the class with the most of them
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub max_methods_per_class: usize,
     pub average_parameters_per_function: f64,
     pub max_parameters_per_function: usize,
+    pub average_return_paths_per_function: f64,
+    pub max_return_paths_per_function: usize,
     pub complexity_by_extension: HashMap<String, ExtensionComplexity>,
     pub complexity_distribution: ComplexityDistribution,
     pub structure_distribution: StructureDistribution,
+    /// Every function of a file; for a project, the `--top-functions` most complex ones
     pub function_complexity_details: Vec<FunctionComplexityDetail>,
+    pub long_parameter_functions: Vec<LongParameterListFinding>,
+    /// Functions exceeding the cyclomatic, cognitive, nesting or length thresholds
+    #[serde(default)]
+    pub complex_functions: Vec<FunctionComplexityDetail>,
+    /// Limits used to select `complex_functions` and `long_parameter_functions`
+    #[serde(default)]
+    pub function_thresholds: FunctionThresholds,
+    #[serde(default)]
+    pub function_length_distribution: FunctionLengthDistribution,
     pub quality_metrics: QualityMetrics,
+    /// The `--worst` lowest-scoring files of a project, worst first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worst_files: Vec<FileQuality>,
 }
 
 /// Code health metrics for practical developer insights
@@ -48,6 +110,18 @@ pub struct QualityMetrics {
     pub technical_debt_ratio: f64,     // Estimated technical debt ratio (0-100)
 }
 
+impl QualityMetrics {
+    /// Score of the average function complexity (0-100): ten points off per unit
+    pub fn complexity_score(&self) -> f64 {
+        100.0 - (self.avg_complexity * 10.0).min(100.0)
+    }
+
+    /// Score of the estimated duplication (0-100); the estimate tops out at 25%
+    pub fn uniqueness_score(&self) -> f64 {
+        100.0 - (self.code_duplication_ratio * 4.0).min(100.0)
+    }
+}
+
 /// Detailed complexity information for individual functions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionComplexityDetail {
@@ -70,6 +144,50 @@ pub struct FunctionComplexityDetail {
     pub maintainability_concerns: Vec<String>,
 }
 
+impl FunctionComplexityDetail {
+    /// The `count` most complex functions: highest cyclomatic complexity first,
+    /// then cognitive complexity, then length
+    pub fn most_complex(mut functions: Vec<Self>, count: usize) -> Vec<Self> {
+        functions.sort_by(|a, b| {
+            b.cyclomatic_complexity.cmp(&a.cyclomatic_complexity)
+                .then(b.cognitive_complexity.cmp(&a.cognitive_complexity))
+                .then(b.line_count.cmp(&a.line_count))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then(a.start_line.cmp(&b.start_line))
+        });
+        functions.truncate(count);
+        functions
+    }
+}
+
+/// Quality of one file, scored from 0 to 100 from the complexity and size of
+/// its functions, its documentation and its estimated duplication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileQuality {
+    pub file_path: String,
+    pub score: f64,
+    pub code_lines: usize,
+    pub function_count: usize,
+    /// Average cyclomatic complexity of its functions
+    pub average_complexity: f64,
+    pub maintainability_index: f64,
+    pub documentation_coverage: f64,
+    pub duplication_ratio: f64,
+}
+
+impl FileQuality {
+    /// The `count` lowest-scoring files, worst first; larger files first among equal scores
+    pub fn worst(mut files: Vec<Self>, count: usize) -> Vec<Self> {
+        files.sort_by(|a, b| {
+            a.score.total_cmp(&b.score)
+                .then(b.code_lines.cmp(&a.code_lines))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+        files.truncate(count);
+        files
+    }
+}
+
 /// Complexity level classification
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ComplexityLevel {
@@ -80,6 +198,12 @@ pub enum ComplexityLevel {
     VeryHigh,   // 51+
 }
 
+/// Counts added after version 1 of the report are left out when zero, so
+/// older reports read back unchanged
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
 /// Distribution of different structure types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructureDistribution {
@@ -92,7 +216,7 @@ pub struct StructureDistribution {
 }
 
 /// Complexity statistics for a specific file extension
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExtensionComplexity {
     pub function_count: usize,
     pub class_count: usize,
@@ -100,6 +224,9 @@ pub struct ExtensionComplexity {
     pub trait_count: usize,
     pub enum_count: usize,
     pub struct_count: usize,
+    /// Modules and namespaces
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub module_count: usize,
     pub total_structures: usize,
     pub cyclomatic_complexity: f64,
     pub cognitive_complexity: f64,
@@ -108,10 +235,80 @@ pub struct ExtensionComplexity {
     pub max_nesting_depth: usize,
     pub average_nesting_depth: f64,
     pub methods_per_class: f64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub max_methods_per_class: usize,
     pub average_parameters_per_function: f64,
+    pub max_parameters_per_function: usize,
+    pub average_return_paths_per_function: f64,
+    pub max_return_paths_per_function: usize,
+    pub long_parameter_function_count: usize,
     pub quality_score: f64,
 }
 
+/// A function whose parameter count exceeds `FunctionThresholds::max_parameters`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LongParameterListFinding {
+    pub name: String,
+    pub file_path: String,
+    pub extension: String,
+    pub start_line: usize,
+    pub parameter_count: usize,
+}
+
+/// Spread of function lengths in lines, for spotting codebases where a few
+/// very long functions hold most of the code
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionLengthDistribution {
+    pub min: usize,
+    pub p50: usize,
+    pub p90: usize,
+    pub max: usize,
+    /// Share of all function lines (0-1) held by the longest 5% of functions
+    pub top_5_percent_share: f64,
+    /// 0 when every function has the same length, approaching 1 when one function holds all lines
+    pub gini_coefficient: f64,
+}
+
+impl FunctionLengthDistribution {
+    /// Summarize a set of function lengths; empty input gives all zeros
+    pub fn from_lengths(lengths: &[usize]) -> Self {
+        if lengths.is_empty() {
+            return Self::default();
+        }
+        
+        let mut sorted = lengths.to_vec();
+        sorted.sort_unstable();
+        let count = sorted.len();
+        let total: usize = sorted.iter().sum();
+        
+        // Nearest-rank percentile
+        let percentile = |p: usize| sorted[((p * count).div_ceil(100)).max(1) - 1];
+        
+        let top_count = (count * 5).div_ceil(100).max(1);
+        let top_lines: usize = sorted[count - top_count..].iter().sum();
+        
+        let (top_5_percent_share, gini_coefficient) = if total > 0 {
+            let weighted: f64 = sorted.iter()
+                .enumerate()
+                .map(|(i, &length)| (i + 1) as f64 * length as f64)
+                .sum();
+            let gini = 2.0 * weighted / (count as f64 * total as f64) - (count + 1) as f64 / count as f64;
+            (top_lines as f64 / total as f64, gini.max(0.0))
+        } else {
+            (0.0, 0.0)
+        };
+        
+        Self {
+            min: sorted[0],
+            p50: percentile(50),
+            p90: percentile(90),
+            max: sorted[count - 1],
+            top_5_percent_share,
+            gini_coefficient,
+        }
+    }
+}
+
 /// Distribution of complexity levels
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplexityDistribution {
@@ -123,7 +320,7 @@ pub struct ComplexityDistribution {
 }
 
 /// Enhanced function information for complexity analysis
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionInfo {
     pub name: String,
     pub line_count: usize,
@@ -143,7 +340,7 @@ pub struct FunctionInfo {
 }
 
 /// Structure information (classes, interfaces, enums, etc.)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructureInfo {
     pub name: String,
     pub structure_type: StructureType,
@@ -158,7 +355,7 @@ pub struct StructureInfo {
 }
 
 /// Type of code structure
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StructureType {
     Class,
     Interface,
@@ -170,11 +367,79 @@ pub enum StructureType {
 }
 
 /// Visibility of code structure
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Visibility {
     Public,
     Private,
     Protected,
     Internal,
     Unknown,
-} 
\ No newline at end of file
+}
+
+/// Functions and structures an analyzer found in one file, with the
+/// Halstead counts of its code, as the file cache keeps them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileAnalysis {
+    pub functions: Vec<FunctionInfo>,
+    pub structures: Vec<StructureInfo>,
+    #[serde(default)]
+    pub halstead: HalsteadMetrics,
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quality(file_path: &str, score: f64, code_lines: usize) -> FileQuality {
+        FileQuality {
+            file_path: file_path.to_string(),
+            score,
+            code_lines,
+            function_count: 1,
+            average_complexity: 1.0,
+            maintainability_index: 100.0,
+            documentation_coverage: 0.0,
+            duplication_ratio: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_worst_files_order() {
+        let files = vec![
+            quality("src/a.rs", 50.0, 10),
+            quality("src/d.rs", 20.0, 5),
+            quality("src/e.rs", 90.0, 500),
+            quality("src/c.rs", 50.0, 30),
+            quality("src/b.rs", 20.0, 5),
+        ];
+
+        // Lowest score first, then the larger file, then by path
+        let worst: Vec<_> = FileQuality::worst(files.clone(), 4).into_iter().map(|file| file.file_path).collect();
+        assert_eq!(worst, ["src/b.rs", "src/d.rs", "src/c.rs", "src/a.rs"]);
+        assert_eq!(FileQuality::worst(files.clone(), 10).len(), 5);
+        assert!(FileQuality::worst(files, 0).is_empty());
+    }
+
+    #[test]
+    fn test_function_length_distribution() {
+        assert_eq!(FunctionLengthDistribution::from_lengths(&[]), FunctionLengthDistribution::default());
+
+        let single = FunctionLengthDistribution::from_lengths(&[42]);
+        assert_eq!((single.min, single.p50, single.p90, single.max), (42, 42, 42, 42));
+        assert_eq!((single.top_5_percent_share, single.gini_coefficient), (1.0, 0.0));
+
+        let equal = FunctionLengthDistribution::from_lengths(&[10, 10, 10, 10]);
+        assert_eq!((equal.min, equal.p50, equal.max), (10, 10, 10));
+        assert_eq!(equal.top_5_percent_share, 0.25);
+        assert!(equal.gini_coefficient.abs() < 1e-9);
+
+        // One function holds almost every line
+        let skewed = FunctionLengthDistribution::from_lengths(&[97, 1, 1, 1, 1]);
+        assert_eq!((skewed.min, skewed.p50, skewed.p90, skewed.max), (1, 1, 97, 97));
+        assert!((skewed.top_5_percent_share - 97.0 / 101.0).abs() < 1e-9);
+        assert!((skewed.gini_coefficient - 0.7604).abs() < 1e-4, "{}", skewed.gini_coefficient);
+
+        // Empty functions have no lines to share out
+        let empty = FunctionLengthDistribution::from_lengths(&[0, 0]);
+        assert_eq!((empty.top_5_percent_share, empty.gini_coefficient), (0.0, 0.0));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::core::stats::aggregation::AggregatedStats;
 use crate::utils::errors::Result;
+use crate::utils::format;
 use serde::{Deserialize, Serialize};
 
 
@@ -63,15 +64,11 @@ impl Default for FormattingOptions {
 }
 
 /// Formatter for statistics display
-pub struct StatFormatter {
-    size_units: Vec<&'static str>,
-}
+pub struct StatFormatter;
 
 impl StatFormatter {
     pub fn new() -> Self {
-        Self {
-            size_units: vec!["B", "KB", "MB", "GB", "TB"],
-        }
+        Self
     }
     
     /// Format statistics according to options
@@ -156,6 +153,7 @@ impl StatFormatter {
             output.push_str(&format!("Documentation: {:.1}/100\n", stats.ratios.quality_metrics.documentation_score));
             output.push_str(&format!("Maintainability: {:.1}/100\n", stats.ratios.quality_metrics.maintainability_score));
             output.push_str(&format!("Readability: {:.1}/100\n", stats.ratios.quality_metrics.readability_score));
+            output.push_str(&format!("Style: {:.1}/100\n", stats.ratios.quality_metrics.style_score));
         }
         
         Ok(output)
@@ -361,35 +359,12 @@ impl StatFormatter {
     
     /// Format a number with thousand separators
     pub fn format_number(&self, num: usize) -> String {
-        let num_str = num.to_string();
-        let mut result = String::new();
-        let chars: Vec<char> = num_str.chars().collect();
-        
-        for (i, ch) in chars.iter().enumerate() {
-            if i > 0 && (chars.len() - i) % 3 == 0 {
-                result.push(',');
-            }
-            result.push(*ch);
-        }
-        
-        result
+        format::format_number(num)
     }
     
     /// Format file size in human-readable format
     pub fn format_size(&self, size: u64) -> String {
-        let mut size = size as f64;
-        let mut unit_index = 0;
-        
-        while size >= 1024.0 && unit_index < self.size_units.len() - 1 {
-            size /= 1024.0;
-            unit_index += 1;
-        }
-        
-        if unit_index == 0 {
-            format!("{} {}", size as u64, self.size_units[unit_index])
-        } else {
-            format!("{:.1} {}", size, self.size_units[unit_index])
-        }
+        format::format_size(size)
     }
     
     /// Format percentage
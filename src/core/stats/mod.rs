@@ -7,11 +7,11 @@ pub mod visualization;
 
 // Re-export commonly used types
 pub use basic::{BasicStats, BasicStatsCalculator};
-pub use complexity::{ComplexityStats, ComplexityStatsCalculator};
-pub use ratios::{RatioStats, RatioStatsCalculator};
+pub use complexity::{AnalyzerOverrides, ComplexityStats, ComplexityStatsCalculator, FunctionThresholds};
+pub use ratios::{QualityWeights, RatioStats, RatioStatsCalculator};
 pub use formatting::{StatFormatter, FormattingOptions, OutputFormat, SortBy};
-pub use aggregation::{StatsAggregator, AggregatedStats, StatsMetadata, AnalysisDepth};
-pub use visualization::{VisualizationGenerator, PieChartData, ChartConfig, ColorScheme};
+pub use aggregation::{StatsAggregator, AggregatedStats, StatsMetadata, AnalysisCapabilities, AnalysisDepth};
+pub use visualization::{VisualizationGenerator, DirectoryTreeNode, TreeNodeKind, PieChartData, ChartConfig, ColorScheme, TreemapData, TreemapNode, SunburstData, StackedBarData, BarDataset};
 
 
 
@@ -26,6 +26,7 @@ pub struct StatsCalculator {
     formatter: StatFormatter,
     aggregator: StatsAggregator,
     visualization_generator: VisualizationGenerator,
+    quality_weights: QualityWeights,
 }
 
 impl StatsCalculator {
@@ -37,20 +38,69 @@ impl StatsCalculator {
             formatter: StatFormatter::new(),
             aggregator: StatsAggregator::new(),
             visualization_generator: VisualizationGenerator::new(),
+            quality_weights: QualityWeights::default(),
         }
     }
     
+    /// Use custom limits when selecting functions to report individually
+    pub fn with_function_thresholds(mut self, thresholds: FunctionThresholds) -> Self {
+        self.complexity_calculator = self.complexity_calculator.with_thresholds(thresholds);
+        self
+    }
+    
+    /// List the `count` most complex functions of a project (`--top-functions`)
+    pub fn with_top_functions(mut self, count: usize) -> Self {
+        self.complexity_calculator = self.complexity_calculator.with_top_functions(count);
+        self
+    }
+    
+    /// List the `count` lowest-scoring files of a project (`--worst`)
+    pub fn with_worst_files(mut self, count: usize) -> Self {
+        self.complexity_calculator = self.complexity_calculator.with_worst_files(count);
+        self
+    }
+    
+    /// Skip files or pick their complexity analyzer by path (`[complexity]`)
+    pub fn with_analyzer_overrides(mut self, overrides: AnalyzerOverrides) -> Self {
+        self.complexity_calculator = self.complexity_calculator.with_overrides(overrides);
+        self
+    }
+    
+    /// Keep the functions and structures found in each file in the file cache,
+    /// so unchanged files aren't parsed again
+    pub fn with_analysis_cache(mut self, cache: Option<crate::utils::cache::AnalysisCache>) -> Self {
+        self.complexity_calculator = self.complexity_calculator.with_analysis_cache(cache);
+        self
+    }
+    
+    /// Weigh the components of the overall quality score (`[scoring.weights]`)
+    pub fn with_quality_weights(mut self, weights: QualityWeights) -> Self {
+        self.quality_weights = weights;
+        self
+    }
+    
+    /// Line counts are keyed by file extension rather than by language, and
+    /// per-file charts break down the same way
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.complexity_calculator = self.complexity_calculator.with_by_extension(by_extension);
+        self.visualization_generator = self.visualization_generator.with_by_extension(by_extension);
+        self
+    }
+    
     /// Calculate comprehensive statistics for a single file
     pub fn calculate_file_stats(&self, file_stats: &FileStats, file_path: &str) -> Result<AggregatedStats> {
         let basic_stats = self.basic_calculator.calculate_basic_stats(file_stats)?;
         let complexity_stats = self.complexity_calculator.calculate_complexity_stats(file_stats, file_path)?;
         let ratio_stats = self.ratio_calculator.calculate_ratio_stats(file_stats)?;
         
-        Ok(self.aggregator.aggregate_file_stats(
+        let mut stats = self.aggregator.aggregate_file_stats(
             basic_stats,
             complexity_stats,
             ratio_stats,
-        ))
+        );
+        stats.metadata.capabilities.complexity = self.complexity_calculator.analyzes(file_path);
+        self.score_quality(&mut stats);
+        Ok(stats)
     }
     
     /// Calculate comprehensive statistics for a collection of files
@@ -59,11 +109,22 @@ impl StatsCalculator {
         let complexity_stats = self.complexity_calculator.calculate_project_complexity_stats(code_stats, individual_files)?;
         let ratio_stats = self.ratio_calculator.calculate_project_ratio_stats(code_stats)?;
         
-        Ok(self.aggregator.aggregate_project_stats(
+        let mut stats = self.aggregator.aggregate_project_stats(
             basic_stats,
             complexity_stats,
             ratio_stats,
-        ))
+        );
+        // Functions are found per file, so without individual files there are only line counts
+        stats.metadata.capabilities.complexity = individual_files.iter().any(|(file_path, _)| self.complexity_calculator.analyzes(file_path));
+        self.score_quality(&mut stats);
+        Ok(stats)
+    }
+    
+    /// Weigh the overall quality score, counting the complexity components
+    /// only when functions were analyzed
+    fn score_quality(&self, stats: &mut AggregatedStats) {
+        let complexity = stats.metadata.capabilities.complexity.then_some(&stats.complexity.quality_metrics);
+        stats.ratios.quality_metrics.overall_quality_score = self.quality_weights.overall_score(&stats.ratios.quality_metrics, complexity);
     }
     
     /// Get formatted statistics for display
@@ -76,6 +137,11 @@ impl StatsCalculator {
         self.visualization_generator.generate_language_distribution(stats, config)
     }
     
+    /// Generate per-language code/comment/doc line breakdown
+    pub fn generate_language_line_breakdown(&self, stats: &AggregatedStats, config: &ChartConfig) -> StackedBarData {
+        self.visualization_generator.generate_language_line_breakdown(stats, config)
+    }
+    
     /// Generate file count distribution pie chart data
     pub fn generate_file_count_distribution(&self, stats: &AggregatedStats, config: &ChartConfig) -> PieChartData {
         self.visualization_generator.generate_file_count_distribution(stats, config)
@@ -96,6 +162,16 @@ impl StatsCalculator {
         self.visualization_generator.generate_line_type_distribution(stats, config)
     }
     
+    /// Generate directory treemap data from individual file statistics
+    pub fn generate_directory_treemap(&self, individual_files: &[(String, FileStats)], config: &ChartConfig) -> TreemapData {
+        self.visualization_generator.generate_directory_treemap(individual_files, config)
+    }
+    
+    /// Generate language/directory sunburst data from individual file statistics
+    pub fn generate_language_sunburst(&self, individual_files: &[(String, FileStats)], config: &ChartConfig) -> SunburstData {
+        self.visualization_generator.generate_language_sunburst(individual_files, config)
+    }
+    
     /// Convert pie chart data to Chart.js format
     pub fn to_chartjs_format(&self, data: &PieChartData, config: &ChartConfig) -> serde_json::Value {
         self.visualization_generator.to_chartjs_format(data, config)
@@ -141,6 +217,12 @@ impl StatsCalculator {
             total_blank_lines: aggregated_stats.basic.blank_lines,
             total_size: aggregated_stats.basic.total_size,
             total_doc_lines: aggregated_stats.basic.doc_lines,
+            total_inline_comment_lines: aggregated_stats.basic.inline_comment_lines,
+            total_logical_lines: aggregated_stats.basic.logical_lines,
+            total_characters: aggregated_stats.basic.characters,
+            total_non_whitespace_characters: aggregated_stats.basic.non_whitespace_characters,
+            total_tokens: aggregated_stats.basic.tokens,
+            line_lengths: aggregated_stats.basic.line_lengths,
             stats_by_extension: aggregated_stats.basic.stats_by_extension.iter()
                 .map(|(ext, ext_stats)| {
                     (ext.clone(), (ext_stats.file_count, FileStats {
@@ -150,6 +232,16 @@ impl StatsCalculator {
                         blank_lines: ext_stats.blank_lines,
                         file_size: ext_stats.total_size,
                         doc_lines: ext_stats.doc_lines,
+                        inline_comment_lines: 0,
+                        logical_lines: 0,
+                        characters: ext_stats.characters,
+                        non_whitespace_characters: ext_stats.non_whitespace_characters,
+                        tokens: ext_stats.tokens,
+                        line_lengths: ext_stats.line_lengths,
+                        encoding: None,
+                        embedded: Default::default(),
+                        metrics: ext_stats.metrics.clone(),
+                        ..Default::default()
                     }))
                 })
                 .collect(),
@@ -71,10 +71,19 @@ impl RatioStatsCalculator {
             0.0
         };
         
-        let quality_metrics = self.quality_calculator.calculate_quality_metrics(
+        let comment_density = if total_lines > 0.0 {
+            round_to_2_decimals((file_stats.comment_lines + file_stats.inline_comment_lines) as f64 / total_lines)
+        } else {
+            0.0
+        };
+        
+        let mut quality_metrics = self.quality_calculator.calculate_quality_metrics(
             code_ratio, comment_ratio, doc_ratio, blank_ratio,
             comment_to_code_ratio, doc_to_code_ratio, &HashMap::new()
         );
+        quality_metrics.style_score = round_to_2_decimals(
+            self.quality_calculator.calculate_style_score(&file_stats.line_lengths, file_stats.total_lines)
+        );
         
         Ok(RatioStats {
             code_ratio,
@@ -83,6 +92,8 @@ impl RatioStatsCalculator {
             blank_ratio,
             comment_to_code_ratio,
             doc_to_code_ratio,
+            comment_density,
+            test_to_code_ratio: 0.0,
             ratios_by_extension: HashMap::new(),
             language_distribution: HashMap::new(),
             file_distribution: HashMap::new(),
@@ -129,6 +140,12 @@ impl RatioStatsCalculator {
             0.0
         };
         
+        let comment_density = if total_lines > 0.0 {
+            round_to_2_decimals((code_stats.total_comment_lines + code_stats.total_inline_comment_lines) as f64 / total_lines)
+        } else {
+            0.0
+        };
+        
         // Calculate per-extension ratios
         let mut ratios_by_extension = HashMap::new();
         
@@ -178,10 +195,13 @@ impl RatioStatsCalculator {
         let file_distribution = self.calculate_file_distribution(code_stats);
         let size_distribution = self.calculate_size_distribution(code_stats);
         
-        let quality_metrics = self.quality_calculator.calculate_quality_metrics(
+        let mut quality_metrics = self.quality_calculator.calculate_quality_metrics(
             code_ratio, comment_ratio, doc_ratio, blank_ratio,
             comment_to_code_ratio, doc_to_code_ratio, &ratios_by_extension
         );
+        quality_metrics.style_score = round_to_2_decimals(
+            self.quality_calculator.calculate_style_score(&code_stats.line_lengths, code_stats.total_lines)
+        );
         
         Ok(RatioStats {
             code_ratio,
@@ -190,6 +210,8 @@ impl RatioStatsCalculator {
             blank_ratio,
             comment_to_code_ratio,
             doc_to_code_ratio,
+            comment_density,
+            test_to_code_ratio: 0.0,
             ratios_by_extension,
             language_distribution,
             file_distribution,
@@ -274,7 +296,7 @@ impl Default for RatioStatsCalculator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::stats::ratios::types::QualityMetrics;
+    use crate::core::stats::ratios::types::{QualityMetrics, QualityWeights};
     use crate::testing::test_utils::TestProject;
     use std::collections::HashMap;
 
@@ -309,6 +331,16 @@ mod tests {
             doc_lines: 10,
             blank_lines: 10,
             file_size: 2048,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
 
         let result = calculator.calculate_ratio_stats(&file_stats).unwrap();
@@ -346,6 +378,16 @@ mod tests {
             doc_lines: 0,
             blank_lines: 0,
             file_size: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
 
         let result = calculator.calculate_ratio_stats(&file_stats).unwrap();
@@ -377,6 +419,16 @@ mod tests {
             doc_lines: 20,
             blank_lines: 20,
             file_size: 4000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
         stats_by_extension.insert("py".to_string(), (1, FileStats {
             total_lines: 100,
@@ -385,6 +437,16 @@ mod tests {
             doc_lines: 5,
             blank_lines: 10,
             file_size: 2000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
 
         let code_stats = CodeStats {
@@ -396,6 +458,12 @@ mod tests {
             total_blank_lines: 30,
             total_size: 6000,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_ratio_stats(&code_stats).unwrap();
@@ -458,6 +526,12 @@ mod tests {
             total_blank_lines: 0,
             total_size: 0,
             stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_ratio_stats(&code_stats).unwrap();
@@ -487,6 +561,16 @@ mod tests {
             doc_lines: 20,
             blank_lines: 40,
             file_size: 6000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
 
         let code_stats = CodeStats {
@@ -498,6 +582,12 @@ mod tests {
             total_blank_lines: 40,
             total_size: 6000,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_ratio_stats(&code_stats).unwrap();
@@ -560,6 +650,8 @@ mod tests {
             blank_ratio: 0.05,
             comment_to_code_ratio: 0.29,
             doc_to_code_ratio: 0.07,
+            comment_density: 0.2,
+            test_to_code_ratio: 0.0,
             ratios_by_extension: HashMap::new(),
             language_distribution: HashMap::new(),
             file_distribution: HashMap::new(),
@@ -569,6 +661,7 @@ mod tests {
                 maintainability_score: 80.0,
                 readability_score: 70.0,
                 consistency_score: 85.0,
+                style_score: 100.0,
                 overall_quality_score: 77.5,
             },
         };
@@ -624,6 +717,7 @@ mod tests {
             maintainability_score: 90.0,
             readability_score: 80.0,
             consistency_score: 95.0,
+            style_score: 90.0,
             overall_quality_score: 87.5,
         };
 
@@ -650,6 +744,16 @@ mod tests {
             doc_lines: 0,
             blank_lines: 0,
             file_size: 2000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
 
         let result = calculator.calculate_ratio_stats(&code_only_stats).unwrap();
@@ -668,6 +772,16 @@ mod tests {
             doc_lines: 0,
             blank_lines: 0,
             file_size: 2000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
 
         let result = calculator.calculate_ratio_stats(&comments_only_stats).unwrap();
@@ -700,6 +814,16 @@ mod tests {
             doc_lines: 20,
             blank_lines: 30,
             file_size: 4000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
         stats_by_extension.insert("py".to_string(), (1, FileStats {
             total_lines: 100,
@@ -708,6 +832,16 @@ mod tests {
             doc_lines: 5,
             blank_lines: 10,
             file_size: 2000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
         stats_by_extension.insert("js".to_string(), (1, FileStats {
             total_lines: 120,
@@ -716,6 +850,16 @@ mod tests {
             doc_lines: 10,
             blank_lines: 15,
             file_size: 2400,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         }));
 
         let code_stats = CodeStats {
@@ -727,6 +871,12 @@ mod tests {
             total_blank_lines: 55,
             total_size: 8400,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
 
         let result = calculator.calculate_project_ratio_stats(&code_stats).unwrap();
@@ -758,6 +908,20 @@ mod tests {
         assert!(result.quality_metrics.consistency_score > 0.0);
     }
 
+    #[test]
+    fn test_style_score() {
+        let calculator = RatioStatsCalculator::new();
+        let mut file_stats = FileStats { total_lines: 100, code_lines: 100, ..FileStats::default() };
+        assert_eq!(calculator.calculate_ratio_stats(&file_stats).unwrap().quality_metrics.style_score, 100.0);
+
+        // Four lines in a hundred over 100 characters
+        file_stats.line_lengths.over_100 = 4;
+        assert_eq!(calculator.calculate_ratio_stats(&file_stats).unwrap().quality_metrics.style_score, 80.0);
+
+        file_stats.line_lengths.over_100 = 40;
+        assert_eq!(calculator.calculate_ratio_stats(&file_stats).unwrap().quality_metrics.style_score, 0.0);
+    }
+
     #[test]
     fn test_quality_thresholds_default() {
         let thresholds = QualityThresholds::default();
@@ -767,4 +931,43 @@ mod tests {
         assert_eq!(thresholds.ideal_comment_to_code, 0.20);
         assert_eq!(thresholds.ideal_doc_to_code, 0.15);
     }
+
+    #[test]
+    fn test_quality_weights() {
+        let ratios = QualityMetrics {
+            documentation_score: 40.0,
+            maintainability_score: 80.0,
+            readability_score: 60.0,
+            consistency_score: 100.0,
+            style_score: 100.0,
+            overall_quality_score: 0.0,
+        };
+        let complexity = crate::core::stats::complexity::QualityMetrics {
+            code_health_score: 0.0,
+            maintainability_index: 0.0,
+            documentation_coverage: 0.0,
+            avg_complexity: 3.0,
+            function_size_health: 50.0,
+            nesting_depth_health: 0.0,
+            code_duplication_ratio: 10.0,
+            technical_debt_ratio: 0.0,
+        };
+
+        // 40 * 0.25 + 80 * 0.35 + 60 * 0.25 + 100 * 0.15
+        let weights = QualityWeights::default();
+        assert!((weights.overall_score(&ratios, Some(&complexity)) - 68.0).abs() < 1e-9);
+
+        // Weights needn't sum to 1, and a weight of 0 leaves a component out
+        let weights = QualityWeights { documentation: 1.0, maintainability: 0.0, readability: 0.0, consistency: 0.0, complexity: 2.0, size: 1.0, duplication: 0.0 };
+        assert!((weights.overall_score(&ratios, Some(&complexity)) - (40.0 + 70.0 * 2.0 + 50.0) / 4.0).abs() < 1e-9);
+        // Without complexity analysis only the documentation counts
+        assert_eq!(weights.overall_score(&ratios, None), 40.0);
+        // Nothing weighted can be scored, so the default weights apply
+        let complexity_only = QualityWeights { documentation: 0.0, maintainability: 0.0, readability: 0.0, consistency: 0.0, ..weights };
+        assert!((complexity_only.overall_score(&ratios, None) - 68.0).abs() < 1e-9);
+
+        assert!(weights.validate().is_ok());
+        assert!(QualityWeights { size: -1.0, ..QualityWeights::default() }.validate().unwrap_err().to_string().contains("size"));
+        assert!(QualityWeights { documentation: 0.0, maintainability: 0.0, readability: 0.0, consistency: 0.0, ..QualityWeights::default() }.validate().is_err());
+    }
 } 
\ No newline at end of file
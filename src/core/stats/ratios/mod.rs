@@ -5,7 +5,7 @@ pub mod insights;
 pub mod manager;
 
 // Re-export the main types and structs for easy access
-pub use types::{RatioStats, ExtensionRatios, QualityMetrics, QualityThresholds};
+pub use types::{RatioStats, ExtensionRatios, QualityMetrics, QualityThresholds, QualityWeights};
 pub use calculator::RatioStatsCalculator;
 pub use quality::QualityCalculator;
 pub use insights::InsightsAnalyzer;
@@ -1,4 +1,5 @@
-use super::types::{QualityMetrics, QualityThresholds, ExtensionRatios};
+use super::types::{QualityMetrics, QualityThresholds, QualityWeights, ExtensionRatios};
+use crate::core::types::LineLengthStats;
 use std::collections::HashMap;
 
 /// Quality metrics calculator
@@ -34,16 +35,29 @@ impl QualityCalculator {
         // Consistency score (0-100)
         let consistency_score = self.calculate_consistency_score(ratios_by_extension);
         
-        // Overall quality score (weighted average)
-        let overall_quality_score = (doc_score * 0.25) + (maintainability_score * 0.35) + (readability_score * 0.25) + (consistency_score * 0.15);
-        
-        QualityMetrics {
+        let mut metrics = QualityMetrics {
             documentation_score: doc_score,
             maintainability_score,
             readability_score,
             consistency_score,
-            overall_quality_score,
+            style_score: 100.0,
+            overall_quality_score: 0.0,
+        };
+        
+        // Overall quality score (weighted average)
+        metrics.overall_quality_score = QualityWeights::default().overall_score(&metrics, None);
+        metrics
+    }
+    
+    /// Calculate style score: five points off per percent of lines longer than
+    /// 100 characters. Not part of the overall score.
+    pub fn calculate_style_score(&self, line_lengths: &LineLengthStats, total_lines: usize) -> f64 {
+        if total_lines == 0 {
+            return 100.0;
         }
+        
+        let long_line_ratio = line_lengths.over_100 as f64 / total_lines as f64;
+        (100.0 - long_line_ratio * 500.0).max(0.0)
     }
     
     /// Calculate documentation score
@@ -1,3 +1,5 @@
+use crate::core::stats::complexity;
+use crate::utils::errors::{HowManyError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,6 +12,10 @@ pub struct RatioStats {
     pub blank_ratio: f64,          // blank lines / total lines
     pub comment_to_code_ratio: f64, // comment lines / code lines
     pub doc_to_code_ratio: f64,    // doc lines / code lines
+    #[serde(default)]
+    pub comment_density: f64,      // (comment lines + code lines with an inline comment) / total lines
+    #[serde(default)]
+    pub test_to_code_ratio: f64,   // test code lines / production code lines
     pub ratios_by_extension: HashMap<String, ExtensionRatios>,
     pub language_distribution: HashMap<String, f64>, // percentage of total lines by language
     pub file_distribution: HashMap<String, f64>,     // percentage of total files by language
@@ -37,9 +43,94 @@ pub struct QualityMetrics {
     pub maintainability_score: f64, // 0-100 based on various factors
     pub readability_score: f64,     // 0-100 based on comment density
     pub consistency_score: f64,     // 0-100 based on ratio consistency across files
+    #[serde(default)]
+    pub style_score: f64,           // 0-100 based on the share of lines over 100 characters
     pub overall_quality_score: f64, // 0-100 weighted average
 }
 
+/// Weights of the components of the overall quality score
+/// (`[scoring.weights] documentation = 0.4`). A weight of 0 leaves its
+/// component out; the others are scaled to sum to 1. Complexity, size and
+/// duplication come from the functions found in the files, so they only
+/// count when complexity was analyzed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QualityWeights {
+    pub documentation: f64,
+    pub maintainability: f64,
+    pub readability: f64,
+    pub consistency: f64,
+    /// Average cyclomatic complexity of the functions
+    pub complexity: f64,
+    /// Share of short functions
+    pub size: f64,
+    /// Estimated duplicated code
+    pub duplication: f64,
+}
+
+impl Default for QualityWeights {
+    fn default() -> Self {
+        Self {
+            documentation: 0.25,
+            maintainability: 0.35,
+            readability: 0.25,
+            consistency: 0.15,
+            complexity: 0.0,
+            size: 0.0,
+            duplication: 0.0,
+        }
+    }
+}
+
+impl QualityWeights {
+    fn named(&self) -> [(&'static str, f64); 7] {
+        [
+            ("documentation", self.documentation),
+            ("maintainability", self.maintainability),
+            ("readability", self.readability),
+            ("consistency", self.consistency),
+            ("complexity", self.complexity),
+            ("size", self.size),
+            ("duplication", self.duplication),
+        ]
+    }
+
+    /// Refuse negative weights, and weights that leave out every component
+    pub fn validate(&self) -> Result<()> {
+        if let Some((name, weight)) = self.named().into_iter().find(|(_, weight)| !weight.is_finite() || *weight < 0.0) {
+            return Err(HowManyError::invalid_config(format!("Quality weight {} must be a number of 0 or more, not {}", name, weight)));
+        }
+        if self.named().iter().all(|(_, weight)| *weight == 0.0) {
+            return Err(HowManyError::invalid_config("At least one quality weight must be above 0"));
+        }
+        Ok(())
+    }
+
+    /// Overall quality score (0-100): the weighted average of the ratio-based
+    /// scores and, given the complexity metrics, of the complexity, size and
+    /// duplication scores. When no weighted component can be scored, the
+    /// default weights apply.
+    pub fn overall_score(&self, ratios: &QualityMetrics, complexity: Option<&complexity::QualityMetrics>) -> f64 {
+        let mut components = vec![
+            (self.documentation, ratios.documentation_score),
+            (self.maintainability, ratios.maintainability_score),
+            (self.readability, ratios.readability_score),
+            (self.consistency, ratios.consistency_score),
+        ];
+        if let Some(complexity) = complexity {
+            components.push((self.complexity, complexity.complexity_score()));
+            components.push((self.size, complexity.function_size_health));
+            components.push((self.duplication, complexity.uniqueness_score()));
+        }
+        
+        let total_weight: f64 = components.iter().map(|(weight, _)| weight).sum();
+        if total_weight <= 0.0 {
+            return Self::default().overall_score(ratios, complexity);
+        }
+        components.iter().map(|(weight, score)| weight * score).sum::<f64>() / total_weight
+    }
+}
+
 /// Thresholds for quality assessment
 #[derive(Debug, Clone)]
 pub struct QualityThresholds {
@@ -1,5 +1,12 @@
+use crate::core::detector::extension_key;
 use crate::core::stats::aggregation::AggregatedStats;
+use crate::core::stats::basic::ExtensionStats;
+use crate::core::types::FileStats;
+use crate::utils::languages::LanguageRegistry;
+use crate::utils::linguist;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 /// Pie chart data for visualization
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +17,126 @@ pub struct PieChartData {
     pub total: f64,
 }
 
+/// One series of a bar chart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarDataset {
+    pub label: String,
+    pub values: Vec<f64>,
+    pub color: String,
+}
+
+/// Stacked bar chart data: one bar per label, one segment per dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackedBarData {
+    pub labels: Vec<String>,
+    pub datasets: Vec<BarDataset>,
+}
+
+/// A single directory rectangle in the treemap chart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreemapNode {
+    pub path: String,
+    pub group: String,
+    pub value: f64,
+    pub files: usize,
+}
+
+/// Treemap data for directory sizes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreemapData {
+    pub nodes: Vec<TreemapNode>,
+    pub total: f64,
+}
+
+/// Sunburst data: languages on the inner ring, their directories on the outer ring.
+/// Outer segments are ordered so each one sits under its parent language.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SunburstData {
+    pub languages: PieChartData,
+    pub directories: PieChartData,
+}
+
+/// A directory or file of the JSON tree, with the totals of everything below it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryTreeNode {
+    pub name: String,
+    /// Path relative to the tree's root, `.` for the root itself
+    pub path: String,
+    pub kind: TreeNodeKind,
+    pub files: usize,
+    pub total_lines: usize,
+    pub code_lines: usize,
+    pub comment_lines: usize,
+    pub doc_lines: usize,
+    pub blank_lines: usize,
+    pub file_size: u64,
+    /// Code lines per language, or per extension with `--by-extension`
+    pub languages: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DirectoryTreeNode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeNodeKind {
+    Directory,
+    File,
+}
+
+impl DirectoryTreeNode {
+    fn new(name: String, path: String, kind: TreeNodeKind) -> Self {
+        Self {
+            name,
+            path,
+            kind,
+            files: 0,
+            total_lines: 0,
+            code_lines: 0,
+            comment_lines: 0,
+            doc_lines: 0,
+            blank_lines: 0,
+            file_size: 0,
+            languages: BTreeMap::new(),
+            children: Vec::new(),
+        }
+    }
+    
+    /// Add a file's totals, with its code lines split by language as in `languages`
+    fn add(&mut self, file_stats: &FileStats, languages: &[(String, usize)]) {
+        self.files += 1;
+        self.total_lines += file_stats.total_lines;
+        self.code_lines += file_stats.code_lines;
+        self.comment_lines += file_stats.comment_lines;
+        self.doc_lines += file_stats.doc_lines;
+        self.blank_lines += file_stats.blank_lines;
+        self.file_size += file_stats.file_size;
+        for (language, code_lines) in languages {
+            *self.languages.entry(language.clone()).or_insert(0) += code_lines;
+        }
+    }
+    
+    /// Child named `name`, created when missing
+    fn child(&mut self, name: &str, kind: TreeNodeKind) -> &mut DirectoryTreeNode {
+        let index = match self.children.iter().position(|child| child.name == name && child.kind == kind) {
+            Some(index) => index,
+            None => {
+                let path = if self.path == "." { name.to_string() } else { format!("{}/{}", self.path, name) };
+                self.children.push(DirectoryTreeNode::new(name.to_string(), path, kind));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+    
+    /// Directories before files, each by name
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+        for child in &mut self.children {
+            child.sort();
+        }
+    }
+}
+
 /// Chart configuration options
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChartConfig {
@@ -18,6 +145,7 @@ pub struct ChartConfig {
     pub show_values: bool,
     pub color_scheme: ColorScheme,
     pub min_slice_percentage: f64, // Minimum percentage to show a slice
+    pub max_directory_depth: usize, // Directory levels kept when grouping files for treemap/sunburst
 }
 
 /// Color schemes for charts
@@ -38,16 +166,33 @@ impl Default for ChartConfig {
             show_values: false,
             color_scheme: ColorScheme::LanguageSpecific,
             min_slice_percentage: 1.0, // Only show slices >= 1%
+            max_directory_depth: 2,
         }
     }
 }
 
+/// Per-directory totals used by the treemap and sunburst charts
+#[derive(Debug, Default)]
+struct DirectoryTotals {
+    files: usize,
+    code_lines: usize,
+    code_lines_by_extension: HashMap<String, usize>,
+}
+
 /// Visualization generator for statistics
-pub struct VisualizationGenerator;
+pub struct VisualizationGenerator {
+    by_extension: bool,
+}
 
 impl VisualizationGenerator {
     pub fn new() -> Self {
-        Self
+        Self { by_extension: false }
+    }
+    
+    /// Break per-file charts down by file extension rather than by language
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.by_extension = by_extension;
+        self
     }
     
     /// Generate language distribution pie chart data
@@ -55,24 +200,25 @@ impl VisualizationGenerator {
         let mut data = Vec::new();
         let total_lines = stats.basic.total_lines as f64;
         
+        // Keep every language here; slices below the threshold are folded into "Others" below
         for (ext, ext_stats) in &stats.basic.stats_by_extension {
-            let percentage = (ext_stats.total_lines as f64 / total_lines) * 100.0;
-            if percentage >= config.min_slice_percentage {
-                data.push((ext.clone(), ext_stats.total_lines as f64, percentage));
-            }
+            let percentage = if total_lines > 0.0 { (ext_stats.total_lines as f64 / total_lines) * 100.0 } else { 0.0 };
+            data.push((ext.clone(), ext_stats.total_lines as f64, percentage));
         }
         
-        // Sort by value (descending)
-        data.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        // Sort by value (descending), then name for a stable order
+        data.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
         
         // Group small slices into "Others" if needed
         let mut labels = Vec::new();
+        let mut extensions = Vec::new();
         let mut values = Vec::new();
         let mut others_value = 0.0;
         
         for (ext, value, percentage) in data {
             if percentage >= config.min_slice_percentage && labels.len() < 10 {
                 labels.push(self.format_language_label(&ext));
+                extensions.push(ext);
                 values.push(value);
             } else {
                 others_value += value;
@@ -81,10 +227,11 @@ impl VisualizationGenerator {
         
         if others_value > 0.0 {
             labels.push("Others".to_string());
+            extensions.push(String::new());
             values.push(others_value);
         }
         
-        let colors = self.generate_colors(&labels, &config.color_scheme);
+        let colors = self.generate_language_colors(&extensions, &config.color_scheme);
         
         PieChartData {
             labels: labels.clone(),
@@ -94,6 +241,30 @@ impl VisualizationGenerator {
         }
     }
     
+    /// Generate per-language code/comment/doc line breakdown for a stacked bar chart
+    pub fn generate_language_line_breakdown(&self, stats: &AggregatedStats, _config: &ChartConfig) -> StackedBarData {
+        const MAX_LANGUAGES: usize = 10;
+        
+        let mut extensions: Vec<_> = stats.basic.stats_by_extension.iter().collect();
+        extensions.sort_by(|a, b| b.1.total_lines.cmp(&a.1.total_lines).then_with(|| a.0.cmp(b.0)));
+        extensions.truncate(MAX_LANGUAGES);
+        
+        let labels = extensions.iter().map(|(ext, _)| self.format_language_label(ext)).collect();
+        let dataset = |label: &str, color: &str, lines: fn(&ExtensionStats) -> usize| BarDataset {
+            label: label.to_string(),
+            values: extensions.iter().map(|(_, ext_stats)| lines(ext_stats) as f64).collect(),
+            color: color.to_string(),
+        };
+        
+        let datasets = vec![
+            dataset("Code", "#3b82f6", |ext_stats| ext_stats.code_lines),
+            dataset("Comments", "#8b5cf6", |ext_stats| ext_stats.comment_lines),
+            dataset("Documentation", "#10b981", |ext_stats| ext_stats.doc_lines),
+        ];
+        
+        StackedBarData { labels, datasets }
+    }
+    
     /// Generate file count distribution pie chart data
     pub fn generate_file_count_distribution(&self, stats: &AggregatedStats, config: &ChartConfig) -> PieChartData {
         let mut data = Vec::new();
@@ -110,12 +281,14 @@ impl VisualizationGenerator {
         data.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         
         let mut labels = Vec::new();
+        let mut extensions = Vec::new();
         let mut values = Vec::new();
         let mut others_value = 0.0;
         
         for (ext, value, percentage) in data {
             if percentage >= config.min_slice_percentage && labels.len() < 10 {
                 labels.push(self.format_language_label(&ext));
+                extensions.push(ext);
                 values.push(value);
             } else {
                 others_value += value;
@@ -124,10 +297,11 @@ impl VisualizationGenerator {
         
         if others_value > 0.0 {
             labels.push("Others".to_string());
+            extensions.push(String::new());
             values.push(others_value);
         }
         
-        let colors = self.generate_colors(&labels, &config.color_scheme);
+        let colors = self.generate_language_colors(&extensions, &config.color_scheme);
         
         PieChartData {
             labels: labels.clone(),
@@ -273,6 +447,219 @@ impl VisualizationGenerator {
         }
     }
     
+    /// Generate directory treemap data, sized by code lines
+    pub fn generate_directory_treemap(&self, individual_files: &[(String, FileStats)], config: &ChartConfig) -> TreemapData {
+        let directories = self.aggregate_by_directory(individual_files, config.max_directory_depth);
+        
+        let mut nodes: Vec<TreemapNode> = directories.into_iter()
+            .filter(|(_, totals)| totals.code_lines > 0)
+            .map(|(path, totals)| TreemapNode {
+                group: path.split('/').next().unwrap_or(".").to_string(),
+                path,
+                value: totals.code_lines as f64,
+                files: totals.files,
+            })
+            .collect();
+        
+        nodes.sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(std::cmp::Ordering::Equal));
+        let total = nodes.iter().map(|node| node.value).sum();
+        
+        TreemapData { nodes, total }
+    }
+    
+    /// Generate language sunburst data: code lines per language, split by directory
+    pub fn generate_language_sunburst(&self, individual_files: &[(String, FileStats)], config: &ChartConfig) -> SunburstData {
+        const MAX_LANGUAGES: usize = 10;
+        const MAX_DIRECTORIES_PER_LANGUAGE: usize = 6;
+        
+        let directories = self.aggregate_by_directory(individual_files, config.max_directory_depth);
+        
+        // Pivot to language -> directory -> code lines
+        let mut by_language: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        for (dir, totals) in &directories {
+            for (ext, lines) in &totals.code_lines_by_extension {
+                if *lines > 0 {
+                    by_language.entry(ext.clone()).or_default().push((dir.clone(), *lines));
+                }
+            }
+        }
+        
+        let mut languages = by_language.into_iter()
+            .map(|(ext, mut dirs)| {
+                dirs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                let total: usize = dirs.iter().map(|(_, lines)| lines).sum();
+                (ext, total, dirs)
+            })
+            .collect::<Vec<_>>();
+        languages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        
+        let total: f64 = languages.iter().map(|(_, lines, _)| *lines as f64).sum();
+        let mut language_labels = Vec::new();
+        let mut language_extensions = Vec::new();
+        let mut language_values = Vec::new();
+        let mut directory_labels = Vec::new();
+        let mut directory_values = Vec::new();
+        let mut directory_colors = Vec::new();
+        let mut others_value = 0.0;
+        
+        for (ext, lines, dirs) in &languages {
+            if language_labels.len() >= MAX_LANGUAGES {
+                others_value += *lines as f64;
+                continue;
+            }
+            
+            let label = self.format_language_label(ext);
+            let color = self.generate_language_colors(std::slice::from_ref(ext), &config.color_scheme)
+                .pop()
+                .unwrap_or_else(|| linguist::DEFAULT_COLOR.to_string());
+            
+            let mut remaining = 0;
+            for (index, (dir, dir_lines)) in dirs.iter().enumerate() {
+                if index < MAX_DIRECTORIES_PER_LANGUAGE {
+                    directory_labels.push(format!("{} ({})", dir, label));
+                    directory_values.push(*dir_lines as f64);
+                    directory_colors.push(color.clone());
+                } else {
+                    remaining += dir_lines;
+                }
+            }
+            if remaining > 0 {
+                directory_labels.push(format!("other directories ({})", label));
+                directory_values.push(remaining as f64);
+                directory_colors.push(color.clone());
+            }
+            
+            language_labels.push(label);
+            language_extensions.push(ext.clone());
+            language_values.push(*lines as f64);
+        }
+        
+        if others_value > 0.0 {
+            language_labels.push("Others".to_string());
+            language_values.push(others_value);
+            directory_labels.push("Others".to_string());
+            directory_values.push(others_value);
+            directory_colors.push(linguist::DEFAULT_COLOR.to_string());
+        }
+        
+        let mut language_colors = self.generate_language_colors(&language_extensions, &config.color_scheme);
+        if others_value > 0.0 {
+            language_colors.push(linguist::DEFAULT_COLOR.to_string());
+        }
+        
+        SunburstData {
+            languages: PieChartData {
+                labels: language_labels,
+                values: language_values,
+                colors: language_colors,
+                total,
+            },
+            directories: PieChartData {
+                labels: directory_labels,
+                values: directory_values,
+                colors: directory_colors,
+                total,
+            },
+        }
+    }
+    
+    /// Nested tree of every directory and file relative to the files' common root,
+    /// each node carrying the totals of the files below it
+    pub fn generate_directory_tree(&self, individual_files: &[(String, FileStats)]) -> DirectoryTreeNode {
+        let (parents, common_len) = self.parent_directories(individual_files);
+        let root_name = parents.first()
+            .and_then(|parent| parent.get(..common_len))
+            .and_then(|common| common.last())
+            .cloned()
+            .unwrap_or_else(|| ".".to_string());
+        let mut root = DirectoryTreeNode::new(root_name, ".".to_string(), TreeNodeKind::Directory);
+        
+        for ((file_path, file_stats), parent) in individual_files.iter().zip(parents.iter()) {
+            let path = Path::new(file_path);
+            let languages = self.language_code_lines(path, file_stats);
+            let file_name = path.file_name().map_or_else(|| file_path.clone(), |name| name.to_string_lossy().into_owned());
+            
+            let mut node = &mut root;
+            node.add(file_stats, &languages);
+            for dir in parent.iter().skip(common_len) {
+                node = node.child(dir, TreeNodeKind::Directory);
+                node.add(file_stats, &languages);
+            }
+            node.child(&file_name, TreeNodeKind::File).add(file_stats, &languages);
+        }
+        
+        root.sort();
+        root
+    }
+    
+    /// Directory components of each file's parent, and the length of the prefix shared by all of them
+    fn parent_directories(&self, individual_files: &[(String, FileStats)]) -> (Vec<Vec<String>>, usize) {
+        let parents: Vec<Vec<String>> = individual_files.iter()
+            .map(|(file_path, _)| {
+                Path::new(file_path)
+                    .parent()
+                    .map(|parent| parent.components()
+                        .filter_map(|c| match c {
+                            std::path::Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                            _ => None,
+                        })
+                        .collect())
+                    .unwrap_or_default()
+            })
+            .collect();
+        
+        // Length of the directory prefix shared by every file
+        let common_len = parents.iter().skip(1).fold(parents.first().map_or(0, |p| p.len()), |len, parent| {
+            parents[0].iter().zip(parent.iter()).take(len).take_while(|(a, b)| a == b).count()
+        });
+        
+        (parents, common_len)
+    }
+    
+    /// Group files by directory relative to their common root, keeping at most `max_depth` levels
+    fn aggregate_by_directory(&self, individual_files: &[(String, FileStats)], max_depth: usize) -> BTreeMap<String, DirectoryTotals> {
+        let (parents, common_len) = self.parent_directories(individual_files);
+        
+        let mut directories: BTreeMap<String, DirectoryTotals> = BTreeMap::new();
+        for ((file_path, file_stats), parent) in individual_files.iter().zip(parents.iter()) {
+            let relative: Vec<&str> = parent.iter()
+                .skip(common_len)
+                .take(max_depth.max(1))
+                .map(|s| s.as_str())
+                .collect();
+            let dir = if relative.is_empty() { ".".to_string() } else { relative.join("/") };
+            
+            let totals = directories.entry(dir).or_default();
+            totals.files += 1;
+            totals.code_lines += file_stats.code_lines;
+            for (language, code_lines) in self.language_code_lines(Path::new(file_path), file_stats) {
+                *totals.code_lines_by_extension.entry(language).or_insert(0) += code_lines;
+            }
+        }
+        
+        directories
+    }
+    
+    /// Row key of an extension, matching the keys of the aggregated statistics
+    fn language_key(&self, extension: &str) -> String {
+        let extension = extension.to_lowercase();
+        if self.by_extension {
+            extension
+        } else {
+            LanguageRegistry::new().key(&extension)
+        }
+    }
+    
+    /// A file's code lines by row key: embedded blocks (a Vue file's script, a
+    /// README's fenced code) count toward their own language, as in the aggregated statistics
+    fn language_code_lines(&self, path: &Path, file_stats: &FileStats) -> Vec<(String, usize)> {
+        let extension = file_stats.row_key(extension_key(path).unwrap_or_else(|| "no_ext".to_string()), self.by_extension);
+        let embedded_lines: usize = file_stats.embedded.values().map(|embedded| embedded.code_lines).sum();
+        std::iter::once((self.language_key(&extension), file_stats.code_lines.saturating_sub(embedded_lines)))
+            .chain(file_stats.embedded.iter().map(|(extension, embedded)| (self.language_key(extension), embedded.code_lines)))
+            .collect()
+    }
+    
     /// Format language label with emoji and proper name
     fn format_language_label(&self, ext: &str) -> String {
         let (emoji, name) = self.get_language_info(ext);
@@ -280,6 +667,9 @@ impl VisualizationGenerator {
     }
     /// Get language icon and proper name
     fn get_language_info(&self, ext: &str) -> (&'static str, &'static str) {
+        if let Some(language) = LanguageRegistry::new().named(ext) {
+            return ("●", language.name);
+        }
         match ext {
             "rs" => ("●", "Rust"),
             "py" => ("●", "Python"),
@@ -320,6 +710,21 @@ impl VisualizationGenerator {
             "lua" => ("●", "Lua"),
             "pl" | "pm" | "pod" => ("●", "Perl"),
             "zig" => ("●", "Zig"),
+            "cob" | "cbl" | "cpy" => ("●", "COBOL"),
+            "f" | "for" | "f77" | "ftn" | "f90" | "f95" | "f03" | "f08" => ("●", "Fortran"),
+            "adb" | "ads" | "ada" => ("●", "Ada"),
+            "pas" | "dpr" | "lpr" => ("●", "Pascal"),
+            "tf" | "tfvars" | "hcl" => ("●", "HCL"),
+            "proto" => ("●", "Protocol Buffers"),
+            "graphql" | "gql" => ("●", "GraphQL"),
+            "nix" => ("●", "Nix"),
+            "s" | "asm" => ("●", "Assembly"),
+            "v" | "vh" | "sv" | "svh" => ("●", "Verilog"),
+            "vhd" | "vhdl" => ("●", "VHDL"),
+            "cu" | "cuh" => ("●", "CUDA"),
+            "makefile" => ("●", "Makefile"),
+            "dockerfile" => ("●", "Dockerfile"),
+            "cmake" => ("●", "CMake"),
             "clj" | "cljs" | "cljc" => ("●", "Clojure"),
             "ps1" | "psm1" | "psd1" => ("●", "PowerShell"),
             "bat" | "cmd" => ("●", "Batch"),
@@ -332,52 +737,22 @@ impl VisualizationGenerator {
     /// Generate colors for chart based on color scheme
     fn generate_colors(&self, labels: &[String], scheme: &ColorScheme) -> Vec<String> {
         match scheme {
-            ColorScheme::LanguageSpecific => self.generate_language_specific_colors(labels),
-            ColorScheme::Default => self.generate_default_colors(labels.len()),
+            // Charts that aren't split by language have no language colors to use
+            ColorScheme::LanguageSpecific | ColorScheme::Default => self.generate_default_colors(labels.len()),
             ColorScheme::Pastel => self.generate_pastel_colors(labels.len()),
             ColorScheme::Vibrant => self.generate_vibrant_colors(labels.len()),
             ColorScheme::Monochrome => self.generate_monochrome_colors(labels.len()),
         }
     }
     
-    /// Generate language-specific colors
-    fn generate_language_specific_colors(&self, labels: &[String]) -> Vec<String> {
-        labels.iter().map(|label| {
-            // Extract extension from formatted label
-            let ext = if label.contains("Rust") { "rs" }
-            else if label.contains("Python") { "py" }
-            else if label.contains("JavaScript") { "js" }
-            else if label.contains("TypeScript") { "ts" }
-            else if label.contains("HTML") { "html" }
-            else if label.contains("CSS") { "css" }
-            else if label.contains("Java") && !label.contains("JavaScript") { "java" }
-            else if label.contains("C++") { "cpp" }
-            else if label.contains("Go") { "go" }
-            else if label.contains("PHP") { "php" }
-            else if label.contains("Ruby") { "rb" }
-            else if label.contains("Swift") { "swift" }
-            else { "default" };
-            
-            self.get_language_color(ext).to_string()
-        }).collect()
-    }
-    
-    /// Get language-specific color
-    fn get_language_color(&self, ext: &str) -> &'static str {
-        match ext {
-            "rs" => "#dea584",      // Rust orange
-            "py" => "#3776ab",      // Python blue
-            "js" => "#f7df1e",      // JavaScript yellow
-            "ts" => "#3178c6",      // TypeScript blue
-            "html" => "#e34f26",    // HTML orange-red
-            "css" => "#1572b6",     // CSS blue
-            "java" => "#ed8b00",    // Java orange
-            "cpp" => "#00599c",     // C++ blue
-            "go" => "#00add8",      // Go cyan
-            "php" => "#777bb4",     // PHP purple
-            "rb" => "#cc342d",      // Ruby red
-            "swift" => "#fa7343",   // Swift orange
-            _ => "#6c757d",         // Default gray
+    /// Generate colors for language slices, one per extension (empty for "Others").
+    /// The language-specific scheme uses GitHub linguist's language colors.
+    fn generate_language_colors(&self, extensions: &[String], scheme: &ColorScheme) -> Vec<String> {
+        match scheme {
+            ColorScheme::LanguageSpecific => extensions.iter()
+                .map(|ext| linguist::language_color_or_default(ext).to_string())
+                .collect(),
+            _ => self.generate_colors(extensions, scheme),
         }
     }
     
@@ -469,4 +844,127 @@ impl Default for VisualizationGenerator {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(code_lines: usize) -> FileStats {
+        FileStats {
+            total_lines: code_lines + 1,
+            code_lines,
+            comment_lines: 1,
+            blank_lines: 0,
+            file_size: 100,
+            doc_lines: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_directory_tree() {
+        let files = vec![
+            ("./app/src/main.rs".to_string(), file(10)),
+            ("./app/src/util/mod.rs".to_string(), file(5)),
+            ("./app/build.py".to_string(), file(3)),
+        ];
+        let tree = VisualizationGenerator::new().generate_directory_tree(&files);
+
+        assert_eq!((tree.name.as_str(), tree.path.as_str()), ("app", "."));
+        assert_eq!((tree.files, tree.code_lines, tree.file_size), (3, 18, 300));
+        assert_eq!(tree.languages.get("Rust"), Some(&15));
+        let by_extension = VisualizationGenerator::new().with_by_extension(true).generate_directory_tree(&files);
+        assert_eq!(by_extension.languages.get("rs"), Some(&15));
+
+        // Directories first, then files
+        let names: Vec<_> = tree.children.iter().map(|child| child.name.as_str()).collect();
+        assert_eq!(names, ["src", "build.py"]);
+        let src = &tree.children[0];
+        assert_eq!((src.kind, src.files, src.code_lines), (TreeNodeKind::Directory, 2, 15));
+        assert_eq!(src.children[0].path, "src/util");
+        assert_eq!(src.children[1].path, "src/main.rs");
+        assert_eq!(src.children[1].kind, TreeNodeKind::File);
+        assert!(src.children[1].children.is_empty());
+
+        // Embedded blocks count toward their own language, as in the aggregated statistics
+        let mut vue = file(12);
+        vue.embedded.insert("ts".to_string(), file(8));
+        let mut files = files;
+        files.push(("./app/src/App.vue".to_string(), vue));
+        let tree = VisualizationGenerator::new().generate_directory_tree(&files);
+        assert_eq!(tree.code_lines, 30);
+        assert_eq!((tree.languages.get("Vue"), tree.languages.get("TypeScript")), (Some(&4), Some(&8)));
+        let app = &tree.children[0].children[1];
+        assert_eq!(app.name, "App.vue");
+        assert_eq!(app.languages.values().sum::<usize>(), app.code_lines);
+    }
+
+    #[test]
+    fn test_directory_treemap() {
+        let files = vec![
+            ("./app/src/parser/lexer/token.rs".to_string(), file(40)),
+            ("./app/src/lib.rs".to_string(), file(10)),
+            ("./app/tests/cli.rs".to_string(), file(20)),
+            ("./app/build.rs".to_string(), file(5)),
+            ("./app/empty.rs".to_string(), file(0)),
+        ];
+        let generator = VisualizationGenerator::new();
+        let nodes = |config: &ChartConfig| -> Vec<(String, String, f64, usize)> {
+            generator.generate_directory_treemap(&files, config).nodes.into_iter().map(|node| (node.path, node.group, node.value, node.files)).collect()
+        };
+
+        // Paths are relative to the common root and cut after two levels; files
+        // at the root share "." and directories without code are left out
+        let treemap = generator.generate_directory_treemap(&files, &ChartConfig::default());
+        assert_eq!(treemap.total, 75.0);
+        assert_eq!(nodes(&ChartConfig::default()), [
+            ("src/parser".to_string(), "src".to_string(), 40.0, 1),
+            ("tests".to_string(), "tests".to_string(), 20.0, 1),
+            ("src".to_string(), "src".to_string(), 10.0, 1),
+            (".".to_string(), ".".to_string(), 5.0, 2),
+        ]);
+
+        let shallow = ChartConfig { max_directory_depth: 1, ..ChartConfig::default() };
+        assert_eq!(nodes(&shallow)[0], ("src".to_string(), "src".to_string(), 50.0, 2));
+    }
+
+    #[test]
+    fn test_language_sunburst() {
+        // Twelve languages, the two smallest folded into "Others"
+        let extensions = ["rs", "py", "js", "ts", "go", "java", "c", "rb", "php", "swift", "kt", "lua"];
+        let mut files: Vec<_> = extensions.iter().enumerate()
+            .map(|(index, extension)| (format!("./app/lib/main.{}", extension), file(120 - index * 10)))
+            .collect();
+        // Rust spread over nine directories with `lib`, the three smallest folded into "other directories"
+        for (index, lines) in [80, 70, 60, 50, 40, 30, 20, 10].into_iter().enumerate() {
+            files.push((format!("./app/crate{}/lib.rs", index), file(lines)));
+        }
+
+        let sunburst = VisualizationGenerator::new().generate_language_sunburst(&files, &ChartConfig::default());
+        let languages = &sunburst.languages;
+        assert_eq!(languages.labels.len(), 11);
+        assert_eq!((languages.labels[0].as_str(), languages.values[0]), ("● Rust", 480.0));
+        assert_eq!((languages.labels[10].as_str(), languages.values[10]), ("Others", 30.0));
+        assert_eq!(languages.colors.len(), 11);
+        assert_eq!(languages.total, 1140.0);
+
+        let directories = &sunburst.directories;
+        let rust: Vec<_> = directories.labels.iter().zip(&directories.values).take_while(|(label, _)| label.ends_with("(● Rust)")).collect();
+        assert_eq!(rust.len(), 7);
+        assert_eq!((rust[0].0.as_str(), *rust[0].1), ("lib (● Rust)", 120.0));
+        assert_eq!((rust[1].0.as_str(), *rust[1].1), ("crate0 (● Rust)", 80.0));
+        assert_eq!((rust[6].0.as_str(), *rust[6].1), ("other directories (● Rust)", 60.0));
+        assert_eq!((directories.labels.last().unwrap().as_str(), *directories.values.last().unwrap()), ("Others", 30.0));
+        assert_eq!(directories.values.iter().sum::<f64>(), 1140.0);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use serde::{Deserialize, Serialize};
 
 /// Statistics for a single file
@@ -10,6 +10,36 @@ pub struct FileStats {
     pub blank_lines: usize,
     pub file_size: u64,
     pub doc_lines: usize, // Documentation content
+    /// Code lines that also carry a comment (`let x = 5; // why`), counted in `code_lines` too
+    #[serde(default)]
+    pub inline_comment_lines: usize,
+    /// Statements (logical lines of code), estimated from terminators and block openers
+    #[serde(default)]
+    pub logical_lines: usize,
+    /// Characters (Unicode scalar values), not bytes
+    #[serde(default)]
+    pub characters: usize,
+    #[serde(default)]
+    pub non_whitespace_characters: usize,
+    /// Approximate LLM tokens, for estimating how much context a file takes
+    #[serde(default)]
+    pub tokens: usize,
+    #[serde(default)]
+    pub line_lengths: LineLengthStats,
+    /// Encoding the file was decoded from, `None` for UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Extension the contents were counted as when it isn't the one of the
+    /// file's name: `mm` for an Objective-C `.m` file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Counts of the blocks in other languages (a Vue file's `<script lang="ts">`,
+    /// a README's fenced Rust), by extension. They are part of the counts above.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub embedded: BTreeMap<String, FileStats>,
+    /// Metrics measured by plugins, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metrics: BTreeMap<String, f64>,
 }
 
 impl Default for FileStats {
@@ -21,10 +51,164 @@ impl Default for FileStats {
             blank_lines: 0,
             file_size: 0,
             doc_lines: 0,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: LineLengthStats::default(),
+            encoding: None,
+            language: None,
+            embedded: BTreeMap::new(),
+            metrics: BTreeMap::new(),
         }
     }
 }
 
+impl FileStats {
+    /// Key the file's counts belong under: its language's extension if the
+    /// contents resolved one, else `extension`, the key of its name
+    pub fn language_key(&self, extension: String) -> String {
+        self.language.clone().unwrap_or(extension)
+    }
+
+    /// Row the file's counts go in: `extension` when rows are by extension,
+    /// so an Objective-C `.m` stays under `m`, else `language_key`
+    pub fn row_key(&self, extension: String, by_extension: bool) -> String {
+        if by_extension {
+            extension
+        } else {
+            self.language_key(extension)
+        }
+    }
+
+    /// These counts with `part` taken out, e.g. a file's inline test module
+    pub fn saturating_sub(&self, part: &FileStats) -> FileStats {
+        FileStats {
+            total_lines: self.total_lines.saturating_sub(part.total_lines),
+            code_lines: self.code_lines.saturating_sub(part.code_lines),
+            comment_lines: self.comment_lines.saturating_sub(part.comment_lines),
+            blank_lines: self.blank_lines.saturating_sub(part.blank_lines),
+            file_size: self.file_size.saturating_sub(part.file_size),
+            doc_lines: self.doc_lines.saturating_sub(part.doc_lines),
+            inline_comment_lines: self.inline_comment_lines.saturating_sub(part.inline_comment_lines),
+            logical_lines: self.logical_lines.saturating_sub(part.logical_lines),
+            characters: self.characters.saturating_sub(part.characters),
+            non_whitespace_characters: self.non_whitespace_characters.saturating_sub(part.non_whitespace_characters),
+            tokens: self.tokens.saturating_sub(part.tokens),
+            line_lengths: self.line_lengths.saturating_sub(&part.line_lengths),
+            encoding: self.encoding.clone(),
+            language: self.language.clone(),
+            embedded: BTreeMap::new(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// Line lengths in characters, without the line ending, and how many lines
+/// pass common style limits
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct LineLengthStats {
+    pub max: usize,
+    /// Sum of all line lengths, for the average
+    pub total: usize,
+    pub over_80: usize,
+    pub over_100: usize,
+    pub over_120: usize,
+}
+
+impl LineLengthStats {
+    pub fn add_line(&mut self, length: usize) {
+        self.max = self.max.max(length);
+        self.total += length;
+        self.over_80 += usize::from(length > 80);
+        self.over_100 += usize::from(length > 100);
+        self.over_120 += usize::from(length > 120);
+    }
+
+    /// Add the lines of another file or extension
+    pub fn merge(&mut self, other: &LineLengthStats) {
+        self.max = self.max.max(other.max);
+        self.total += other.total;
+        self.over_80 += other.over_80;
+        self.over_100 += other.over_100;
+        self.over_120 += other.over_120;
+    }
+
+    /// These lengths with `part` taken out; the longest line is kept, as it
+    /// may not be part of `part`
+    pub fn saturating_sub(&self, part: &LineLengthStats) -> LineLengthStats {
+        LineLengthStats {
+            max: self.max,
+            total: self.total.saturating_sub(part.total),
+            over_80: self.over_80.saturating_sub(part.over_80),
+            over_100: self.over_100.saturating_sub(part.over_100),
+            over_120: self.over_120.saturating_sub(part.over_120),
+        }
+    }
+
+    /// Average length of `lines` lines
+    pub fn average(&self, lines: usize) -> f64 {
+        if lines > 0 {
+            self.total as f64 / lines as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// What a single line of a file counts as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineKind {
+    Code,
+    Comment,
+    Doc,
+    Blank,
+}
+
+impl LineKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineKind::Code => "code",
+            LineKind::Comment => "comment",
+            LineKind::Doc => "doc",
+            LineKind::Blank => "blank",
+        }
+    }
+}
+
+/// One line of a file with its classification, from `CodeCounter::classify_file`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassifiedLine {
+    /// 1-based line number
+    pub number: usize,
+    pub kind: LineKind,
+    /// A code line that also carries a comment (`let x = 5; // why`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inline_comment: bool,
+    pub text: String,
+}
+
+/// Lightweight report for `--fast-json`: line counts only, without
+/// complexity, quality, ratio or time estimate analysis
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FastReport {
+    pub total_files: usize,
+    pub totals: FileStats,
+    pub files: Vec<FastFileStats>,
+    pub elapsed_ms: u64,
+}
+
+/// Per-file entry of a [`FastReport`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FastFileStats {
+    pub path: String,
+    #[serde(flatten)]
+    pub stats: FileStats,
+}
+
 /// Aggregated statistics for a project
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CodeStats {
@@ -35,6 +219,18 @@ pub struct CodeStats {
     pub total_blank_lines: usize,
     pub total_size: u64,
     pub total_doc_lines: usize, // Documentation content
+    #[serde(default)]
+    pub total_inline_comment_lines: usize,
+    #[serde(default)]
+    pub total_logical_lines: usize,
+    #[serde(default)]
+    pub total_characters: usize,
+    #[serde(default)]
+    pub total_non_whitespace_characters: usize,
+    #[serde(default)]
+    pub total_tokens: usize,
+    #[serde(default)]
+    pub line_lengths: LineLengthStats,
     pub stats_by_extension: HashMap<String, (usize, FileStats)>, // (file_count, aggregated_stats)
 }
 
@@ -48,6 +244,12 @@ impl Default for CodeStats {
             total_blank_lines: 0,
             total_size: 0,
             total_doc_lines: 0,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: LineLengthStats::default(),
             stats_by_extension: HashMap::new(),
         }
     }
@@ -6,6 +6,14 @@ pub mod core {
     pub mod filters;
     pub mod stats;
     pub mod patterns;
+    pub mod history;
+    pub mod gate;
+    pub mod budget;
+    pub mod analyzer;
+    pub mod plugins;
+    pub mod explain;
+    pub mod dependencies;
+    pub mod licenses;
 }
 
 // User interface modules
@@ -14,16 +22,36 @@ pub mod ui {
     pub mod interactive;
     pub mod html;
     pub mod sarif;
+    pub mod prometheus;
+    pub mod csv;
+    pub mod compat;
     pub mod filters;
 }
 
 // Utility modules
 pub mod utils {
     pub mod errors;
+    pub mod encoding;
     pub mod config;
     pub mod progress;
     pub mod cache;
     pub mod metrics;
+    pub mod format;
+    pub mod compression;
+    pub mod archive;
+    pub mod churn;
+    pub mod hashing;
+    pub mod linguist;
+    pub mod languages;
+    pub mod checkpoint;
+    pub mod ownership;
+    pub mod paths;
+    pub mod pathspec;
+    pub mod remote;
+    pub mod shard;
+    pub mod shared_cache;
+    pub mod warnings;
+    pub mod doctor;
 }
 
 // Testing utilities (only available in test builds)
@@ -37,12 +65,13 @@ pub use core::counter::CodeCounter;
 pub use core::filters::FileFilter;
 pub use core::stats::StatsCalculator;
 pub use core::patterns::PatternMatcher;
+pub use core::analyzer::{Analyzer, AnalysisHandle};
 
 pub use ui::cli::Config;
 pub use ui::interactive::InteractiveDisplay;
 pub use ui::html::HtmlReporter;
 pub use ui::sarif::SarifReporter;
-pub use utils::errors::{HowManyError, Result};
+pub use utils::errors::{ErrorCategory, HowManyError, Result};
 pub use utils::config::HowManyConfig;
 pub use utils::progress::ProgressReporter;
 pub use utils::cache::FileCache;
@@ -1,37 +1,173 @@
-use howmany::{FileDetector, FileFilter, Config, InteractiveDisplay, Result};
-use howmany::ui::cli::{OutputFormat, SortBy};
+use howmany::core::filters::GlobPatterns;
+use howmany::core::detector::extension_key;
+use howmany::core::detector::patterns::category::FileCategory;
+use howmany::core::detector::patterns::generated::GeneratedPatterns;
+use howmany::core::explain::{self, Exclusion};
+use howmany::{FileDetector, FileFilter, Config, HowManyConfig, HowManyError, InteractiveDisplay, Result};
+use howmany::ui::cli::dry_run::DryRunReport;
+use howmany::ui::cli::{summary, CacheCommand, Command, ConfigCommand, DoctorArgs, GateArgs, HistoryArgs, MergeArgs, NestedProjects, OutputFormat, SortBy};
 use howmany::ui::filters::{FilterOptions, FileFilter as FileStatsFilter, FilteredOutputFormatter};
-use howmany::core::types::{CodeStats, FileStats};
-use howmany::core::stats::{StatsCalculator, AggregatedStats};
-use howmany::core::counter::CachedCodeCounter;
+use howmany::core::types::{CodeStats, FastFileStats, FastReport, FileStats};
+use howmany::core::stats::{StatsCalculator, AggregatedStats, VisualizationGenerator};
+use howmany::core::stats::{BasicStats, BasicStatsCalculator};
+use howmany::core::stats::basic::ExtensionStats;
+use howmany::core::stats::aggregation::schema::{self, JsonReport};
+use howmany::core::stats::aggregation::{AreaStats, CategoryStats, StatsMerger, FileEncoding, FixtureStats, GeneratedStats, MinifiedStats, PathStats, SkipReason, SkippedFile, TestSplitStats, update_capabilities};
+use howmany::core::stats::complexity::{AnalyzerOverrides, ComplexityStatsCalculator, FunctionThresholds};
+use howmany::ui::prometheus::PrometheusExporter;
+use howmany::ui::csv::CsvExporter;
+use howmany::ui::compat::CompatExporter;
+use howmany::ui::sarif::SarifRuleSet;
+use howmany::core::counter::{CachedCodeCounter, CodeCounter};
+use howmany::core::budget::{self, BudgetStats, BudgetStatus};
+use howmany::core::dependencies::{self, DependencyScanner, Ecosystem};
+use howmany::core::licenses::LicenseScanner;
+use howmany::core::gate::{DiffGate, GateReport, GateViolation};
+use howmany::core::history::{HistoryAnalyzer, HistoryReport};
+use howmany::core::plugins;
 use howmany::utils::metrics::MetricsCollector;
-use std::path::Path;
+use howmany::utils::progress::ProgressReporter;
+use howmany::utils::archive;
+use howmany::utils::checkpoint::RunCheckpoint;
+use howmany::utils::churn::{ChurnAnalyzer, ChurnStats};
+use howmany::utils::compression::{self, DEFAULT_MAX_DECOMPRESSED_SIZE};
+use howmany::utils::config::{BudgetConfig, DocumentationConfig, ScoringConfig};
+use howmany::utils::doctor;
+use howmany::utils::format;
+use howmany::utils::cache::{self, CacheKey};
+use howmany::utils::shared_cache::SharedCache;
+use howmany::utils::hashing::HashAlgorithm;
+use howmany::utils::languages::LanguageRegistry;
+use howmany::utils::ownership::OwnershipAnalyzer;
+use howmany::utils::paths::{find_normalization_conflicts, NestedProjectFinder};
+use howmany::utils::pathspec::GitPathspec;
+use howmany::utils::shard::Shard;
+use howmany::utils::remote::{ensure_work_tree, RepoCheckout, RepoSpec};
+use howmany::utils::warnings::FailedFiles;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::process;
 
+/// File the HTML report is written to
+const HTML_REPORT_FILE: &str = "howmany-report.html";
+const HISTORY_REPORT_FILE: &str = "howmany-history.html";
+
 fn main() {
     let mut config = Config::parse_args();
     
     // Apply presets and shortcuts before processing
+    config.apply_target_command();
     config.apply_output_preset();
     config.apply_advanced_filter_shortcuts();
     
+    // Wrappers parsing the JSON output get errors as JSON too
+    let json_errors = config.fast_json || matches!(config.primary_format(), OutputFormat::Json | OutputFormat::JsonTree | OutputFormat::ClocJson);
     if let Err(e) = run(config) {
-        eprintln!("Error: {}", e);
-        process::exit(1);
+        if json_errors {
+            eprintln!("{}", e.to_json());
+        } else {
+            eprintln!("Error: {}", e);
+        }
+        process::exit(e.category().exit_code());
     }
 }
 
 fn run(config: Config) -> Result<()> {
-    let path = config.path.as_deref().unwrap_or_else(|| Path::new("."));
+    // The doctor reports invalid options among its findings
+    if let Some(Command::Doctor(args)) = &config.command {
+        return run_doctor(&config, args);
+    }
+    config.validate()?;
+    
+    if config.schema {
+        print!("{}", schema::REPORT_SCHEMA);
+        return Ok(());
+    }
+    
+    match &config.command {
+        Some(Command::History(args)) => return run_history(&config, args),
+        Some(Command::Diff(args)) => return run_gate(&config, args),
+        Some(Command::Merge(args)) => return run_merge(&config, args),
+        Some(Command::Config(ConfigCommand::Show { path })) => return print_effective_config(&config, path),
+        Some(Command::Config(ConfigCommand::Init { path, force })) => {
+            println!("Wrote {}", HowManyConfig::init_project(path, *force)?.display());
+            return Ok(());
+        }
+        Some(Command::Cache(command)) => return run_cache(command),
+        Some(Command::Completions { shell }) => return print_completions(*shell),
+        Some(Command::Man) => return print_man_page(),
+        Some(Command::Count(_) | Command::Report(_) | Command::Doctor(_)) | None => {}
+    }
+    
+    // The checkout must outlive the analysis: dropping it deletes a temporary clone
+    let checkout = match &config.repo {
+        Some(repo) => {
+            let spec = RepoSpec::parse(repo)?;
+            if config.primary_format() == &OutputFormat::Text {
+                eprintln!("Fetching repository: {}", repo);
+            }
+            // `--churn` reads history, so clone as many commits as it mines
+            Some(RepoCheckout::open_with_depth(&spec, config.churn.unwrap_or(1))?)
+        }
+        None => None,
+    };
+    let roots = match &checkout {
+        Some(checkout) => vec![checkout.path().to_path_buf()],
+        None => config.roots(),
+    };
+    if let Some(missing) = roots.iter().find(|root| !root.exists()) {
+        return Err(HowManyError::FileNotFound(missing.display().to_string()));
+    }
+    // Project settings come from the first path's `.howmany.toml`
+    let file_config = HowManyConfig::load_for_project(&roots[0])?;
+    plugins::discover(&roots[0])?;
+    let sarif_rules = SarifRuleSet::from_config(&file_config.sarif)?;
+    let mut walk = WalkOptions::from_config(&config, &file_config, sarif_rules.function_thresholds())?;
+    walk.ownership = walk.ownership
+        .map(|analyzer| analyzer.with_concentration_threshold(sarif_rules.ownership_concentration_threshold()));
+    if walk.ownership.is_some() {
+        for root in &roots {
+            OwnershipAnalyzer::ensure_repository(root)?;
+        }
+    }
+    if walk.churn.is_some() {
+        for root in &roots {
+            ChurnAnalyzer::ensure_repository(root)?;
+        }
+    }
+    if !config.pathspecs.is_empty() {
+        walk.pathspec = Some(GitPathspec::resolve(&roots, &config.pathspecs)?);
+    }
+    
+    if config.print_config {
+        return print_effective_config(&config, &roots[0]);
+    }
+    
+    if let Some(path) = &config.classify_file {
+        return classify_file_output(path, &walk, config.primary_format());
+    }
+    
+    if config.dry_run {
+        return dry_run_output(&config, &roots, &walk);
+    }
+    
+    if let Some(path) = &config.explain {
+        return explain_output(&roots, &walk, path.as_deref(), config.primary_format());
+    }
+    
+    // Editor integrations want basic counts as fast as possible
+    if config.fast_json {
+        return fast_json_output(&roots, &walk);
+    }
     
     // Handle quiet mode - suppress most output except essential results
     if config.quiet && !config.cli_mode {
         return quiet_output(
-            path,
-            config.max_depth,
-            config.include_hidden,
-            config.get_ignore_patterns(),
-            config.get_extensions(),
+            &roots,
+            &walk,
             config.get_filter_options(),
         );
     }
@@ -39,30 +175,26 @@ fn run(config: Config) -> Result<()> {
     // Simple CLI mode - just show basic counts
     if config.cli_mode {
         return simple_cli_output(
-            path,
-            config.max_depth,
-            config.include_hidden,
-            config.get_ignore_patterns(),
-            config.get_extensions(),
+            &roots,
+            &walk,
             config.get_filter_options(),
         );
     }
     
     // Interactive mode (default unless --no-interactive is passed or specific output format is requested)
-    if config.interactive() && matches!(config.format, OutputFormat::Text) && !config.quiet {
+    if config.interactive() && config.formats == [OutputFormat::Text] && !config.quiet {
+        // Several paths become member projects in the project switcher
+        walk.per_path |= roots.len() > 1;
         let (aggregated_stats, individual_files) = analyze_code_comprehensive(
-            path,
-            config.max_depth,
-            config.include_hidden,
-            config.get_ignore_patterns(),
-            config.get_extensions(),
+            &roots,
+            &walk,
             true, // Always collect individual files for interactive mode to enable real-time analysis
-            &config.format,
+            config.primary_format(),
         )?;
         
         let mut display = InteractiveDisplay::new();
         display.show_welcome()?;
-        let pb = display.show_scanning_progress(&path.display().to_string())?;
+        let pb = display.show_scanning_progress(&display_roots(&roots))?;
         pb.finish_and_clear();
         return display.show_comprehensive_results(&aggregated_stats, &individual_files).map_err(|e| {
             howmany::utils::errors::HowManyError::display(format!("Interactive display error: {}", e))
@@ -72,124 +204,936 @@ fn run(config: Config) -> Result<()> {
     // List files mode
     if config.list_files {
         return list_files(
-            path,
-            config.max_depth,
-            config.include_hidden,
-            config.get_ignore_patterns(),
-            config.get_extensions(),
-            &config.format,
+            &roots,
+            &walk,
+            config.primary_format(),
         );
     }
     
-    // Regular counting mode with comprehensive analysis
+    // Regular counting mode with comprehensive analysis.
+    // HTML reports always need per-file data for the directory charts, and
+    // SARIF reports need it to attach findings to file locations, and the JSON
+    // tree is built from the file paths.
     let (aggregated_stats, individual_files) = analyze_code_comprehensive(
-        path,
-        config.max_depth,
-        config.include_hidden,
-        config.get_ignore_patterns(),
-        config.get_extensions(),
-        config.show_files,
-        &config.format,
+        &roots,
+        &walk,
+        config.show_files || config.csv_per_file || config.top_functions.is_some() || config.worst.is_some() || config.has_format(&OutputFormat::Html) || config.has_format(&OutputFormat::Sarif) || config.has_format(&OutputFormat::JsonTree),
+        config.primary_format(),
     )?;
     
-    output_comprehensive_results(
-        &aggregated_stats,
-        &individual_files,
-        config.format.clone(),
-        config.sort_by.clone(),
-        config.descending,
-        config.verbose,
-        &config,
-    )
+    for format in &config.formats {
+        output_comprehensive_results(
+            &aggregated_stats,
+            &individual_files,
+            format.clone(),
+            &config,
+            &sarif_rules,
+        )?;
+    }
+    
+    // In CI, end the log with the key takeaways and a link to the full HTML report
+    if config.has_format(&OutputFormat::Text) && config.has_format(&OutputFormat::Html) && summary::is_ci_environment() {
+        let base_url = summary::resolve_report_base_url(config.report_url.as_deref());
+        let report_link = summary::report_location(Path::new(HTML_REPORT_FILE), base_url.as_deref());
+        let findings = summary::top_findings(&aggregated_stats, &individual_files, summary::CI_SUMMARY_FINDINGS);
+        print!("{}", summary::render_ci_footer(&aggregated_stats, &report_link, &findings));
+    }
+    
+    Ok(())
 }
 
-/// Comprehensive code analysis using the full stats pipeline
-fn analyze_code_comprehensive(
-    path: &Path,
+/// `howmany history`: count sampled past commits straight from git and report the growth
+fn run_history(config: &Config, args: &HistoryArgs) -> Result<()> {
+    ensure_work_tree(&args.path, "history")?;
+    let file_config = HowManyConfig::load_for_project(&args.path)?;
+    plugins::discover(&args.path)?;
+    let walk = WalkOptions::from_config(config, &file_config, FunctionThresholds::default())?;
+    let should_print = config.primary_format() == &OutputFormat::Text;
+    let accept = walk.git_path_filter()?;
+    // Cached snapshots are only reused with the same filters
+    let settings = format!(
+        "ext={:?};ignore={:?};include={:?};fixtures={};generated={};minified={};docs={:?};by_extension={}",
+        config.get_extensions(), walk.ignore_patterns, walk.include_patterns, walk.include_fixtures, walk.include_generated, walk.include_minified, walk.documentation, walk.by_extension
+    );
+    
+    let analyzer = HistoryAnalyzer::new(&args.path)
+        .with_revision(&args.revision)
+        .with_samples(args.samples)
+        .with_tags(args.tags)
+        .with_by_extension(walk.by_extension)
+        .with_cache(!config.no_cache);
+    let mut report = analyzer.analyze(&CodeCounter::new().with_documentation(walk.documentation.clone()), &settings, accept, |revision| {
+        if should_print {
+            println!("Counting {} ({})...", revision.label, revision.commit);
+        }
+    })?;
+    if !config.by_extension {
+        report.group_by_language(&LanguageRegistry::new());
+    }
+    
+    for format in &config.formats {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            OutputFormat::Html => {
+                let output_path = Path::new(HISTORY_REPORT_FILE);
+                howmany::ui::html::HtmlReporter::new().generate_history_report(&report, output_path)?;
+                note_report_file(config, format!("HTML history report generated: {}", output_path.display()));
+            }
+            _ => output_history_text(&report),
+        }
+    }
+    
+    Ok(())
+}
+
+/// `howmany diff`: fail when a changed file newly crosses a size or complexity limit
+fn run_gate(config: &Config, args: &GateArgs) -> Result<()> {
+    ensure_work_tree(&args.path, "diff")?;
+    let file_config = HowManyConfig::load_for_project(&args.path)?;
+    plugins::discover(&args.path)?;
+    let sarif_rules = SarifRuleSet::from_config(&file_config.sarif)?;
+    let walk = WalkOptions::from_config(config, &file_config, sarif_rules.function_thresholds())?;
+    
+    let counter = CodeCounter::new().with_documentation(walk.documentation.clone()).with_max_file_bytes(walk.max_file_size);
+    let report = DiffGate::new(&args.path, &args.base, sarif_rules.gate_thresholds()).check(&counter, walk.git_path_filter()?)?;
+    
+    for format in &config.formats {
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            _ => output_gate_text(&report),
+        }
+    }
+    
+    if report.passed() {
+        Ok(())
+    } else {
+        Err(HowManyError::gate_failed(report.violations.len()))
+    }
+}
+
+/// `howmany cache`: where the caches are, how much they hold, or delete them.
+/// Only the files howmany writes are touched, so a shared `$HOWMANY_CACHE_DIR` is safe to clear.
+fn run_cache(command: &CacheCommand) -> Result<()> {
+    let dir = cache::cache_dir()?;
+    match command {
+        CacheCommand::Path => println!("{}", dir.display()),
+        CacheCommand::Clear => {
+            let mut freed = 0;
+            for name in cache::CACHE_ENTRIES {
+                let path = dir.join(name);
+                freed += disk_usage(&path);
+                let removed = if path.is_dir() { std::fs::remove_dir_all(&path) } else { std::fs::remove_file(&path) };
+                match removed {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+            }
+            println!("Cleared {} from {}", format::format_size(freed), dir.display());
+        }
+        CacheCommand::Stats => {
+            let project_caches = howmany::FileCache::project_caches()?;
+            let file_caches: Vec<_> = std::iter::once(howmany::FileCache::cache_path()?)
+                .chain(project_caches.iter().cloned())
+                .map(|path| howmany::FileCache::inspect_file(&path).unwrap_or_default())
+                .collect();
+            let files: usize = file_caches.iter().map(|file_cache| file_cache.size()).sum();
+            let contents: usize = file_caches.iter().map(|file_cache| file_cache.stored_stats()).sum();
+            let analyses: usize = file_caches.iter().map(|file_cache| file_cache.stored_analyses()).sum();
+            println!("Cache directory: {}", dir.display());
+            println!("Cached files: {} ({} distinct contents) in {} projects", format::format_number(files), format::format_number(contents), project_caches.len());
+            println!("Cached complexity analyses: {}", format::format_number(analyses));
+            for name in cache::CACHE_ENTRIES {
+                println!("  {}: {}", name, format::format_size(disk_usage(&dir.join(name))));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `howmany doctor`: print the findings of `doctor::diagnose`, failing when any is an error
+fn run_doctor(config: &Config, args: &DoctorArgs) -> Result<()> {
+    let report = doctor::diagnose(config, &args.path, |file_config, thresholds| {
+        WalkOptions::from_config(config, file_config, thresholds).map(drop)
+    });
+    
+    if config.primary_format() == &OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.render());
+    }
+    report.outcome()
+}
+
+/// Bytes a file, or a directory with everything in it, takes; 0 when missing
+fn disk_usage(path: &Path) -> u64 {
+    match std::fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| disk_usage(&entry.path())).sum(),
+        Err(_) => std::fs::metadata(path).map_or(0, |metadata| metadata.len()),
+    }
+}
+
+/// `--print-config` and `howmany config show`: the configuration a run over
+/// `project_root` uses, with the command-line options that override the file applied
+fn print_effective_config(config: &Config, project_root: &Path) -> Result<()> {
+    let (effective, source) = effective_config(config, project_root)?;
+    println!("# Effective configuration from {}", source);
+    print!("{}", effective.to_toml()?);
+    Ok(())
+}
+
+/// Built-in defaults merged with the project's config file and the
+/// command-line options, with where they came from
+fn effective_config(config: &Config, project_root: &Path) -> Result<(HowManyConfig, String)> {
+    let file_config = HowManyConfig::load_for_project(project_root)?;
+    let sarif_rules = SarifRuleSet::from_config(&file_config.sarif)?;
+    // Surface invalid globs and settings the same way an analysis would
+    WalkOptions::from_config(config, &file_config, sarif_rules.function_thresholds())?;
+    
+    let effective = HowManyConfig {
+        default_max_depth: config.max_depth,
+        default_include_hidden: config.include_hidden,
+        custom_ignore_patterns: config.resolve_ignore_patterns(&file_config),
+        custom_include_patterns: config.resolve_include_patterns(&file_config),
+        sarif: sarif_rules.to_config(),
+        ..file_config
+    };
+    
+    let source = match HowManyConfig::source_for_project(project_root) {
+        Some(path) => format!("built-in defaults, {}, command-line options", path.display()),
+        None => "built-in defaults, command-line options".to_string(),
+    };
+    Ok((effective, source))
+}
+
+/// `--dry-run`: the configuration a run would use, the filters it would apply
+/// and how many files it would find, without reading any of them
+fn dry_run_output(config: &Config, roots: &[PathBuf], walk: &WalkOptions) -> Result<()> {
+    let (effective, source) = effective_config(config, &roots[0])?;
+    
+    // Only the rules of the walk apply: binary, generated and minified files
+    // found by their contents are left out when they are read
+    let detector = walk.build_detector();
+    let filter = walk.build_filter()?;
+    let mut nested_finder = NestedProjectFinder::new(roots);
+    let estimated_files = discover_files(roots, &filter, |entry_path, relative_path| {
+        walk.discovery_exclusion(&detector, &mut nested_finder, entry_path, relative_path).is_none() && walk.counts_file(&detector, relative_path)
+    })
+    .len();
+    
+    let report = DryRunReport::new(config, roots, effective, source, estimated_files);
+    if config.primary_format() == &OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.render_text()?);
+    }
+    Ok(())
+}
+
+/// `howmany completions SHELL`: a completion script generated from the command-line definition
+fn print_completions(shell: Shell) -> Result<()> {
+    clap_complete::generate(shell, &mut Config::command(), "howmany", &mut std::io::stdout());
+    Ok(())
+}
+
+/// `howmany man`: the man page, generated from the command-line definition
+fn print_man_page() -> Result<()> {
+    clap_mangen::Man::new(Config::command()).render(&mut std::io::stdout())?;
+    Ok(())
+}
+
+/// `howmany merge`: combine JSON reports of separately analyzed subtrees into one report
+fn run_merge(config: &Config, args: &MergeArgs) -> Result<()> {
+    // Reports only carry totals, not the per-file data these formats are built from
+    let per_file_formats = [(OutputFormat::Html, "html"), (OutputFormat::Sarif, "sarif"), (OutputFormat::JsonTree, "json-tree")];
+    if let Some((_, name)) = per_file_formats.iter().find(|(format, _)| config.has_format(format)) {
+        return Err(HowManyError::invalid_config(format!("{} output needs per-file data, which merged reports don't have", name)));
+    }
+    if config.csv_per_file {
+        return Err(HowManyError::invalid_config("--csv-per-file needs per-file data, which merged reports don't have"));
+    }
+    
+    let merged = merge_reports(&args.reports, config.by_extension)?;
+    for format in &config.formats {
+        output_comprehensive_results(&merged, &[], format.clone(), config, &SarifRuleSet::default())?;
+    }
+    
+    Ok(())
+}
+
+/// The JSON reports at `paths` combined into one, grouped by language unless `by_extension`
+fn merge_reports(paths: &[PathBuf], by_extension: bool) -> Result<AggregatedStats> {
+    let reports = paths.iter().map(|path| schema::read_report(path)).collect::<Result<Vec<_>>>()?;
+    let merger = StatsMerger::new();
+    let mut merged = merger.merge_stats(reports)?;
+    if !by_extension {
+        merger.group_by_language(&mut merged, &LanguageRegistry::new())?;
+    }
+    Ok(merged)
+}
+
+fn output_gate_text(report: &GateReport) {
+    let describe = |violation: &GateViolation| {
+        let before = violation.before.map_or_else(|| "new".to_string(), |before| before.to_string());
+        format!("{}: {} {} → {} (limit {})", violation.path, violation.metric, before, violation.after, violation.limit)
+    };
+    
+    println!();
+    println!("=== Diff Gate: changes since {} ({}) ===", report.base, &report.merge_base[..report.merge_base.len().min(12)]);
+    println!("Changed files checked: {}", report.files_checked);
+    
+    if !report.legacy.is_empty() {
+        println!();
+        println!("Already over a limit before the change (allowed):");
+        for violation in &report.legacy {
+            println!("  {}", describe(violation));
+        }
+    }
+    
+    println!();
+    if report.passed() {
+        println!("✅ Gate passed: no changed file crossed a limit");
+    } else {
+        println!("❌ Newly over a limit:");
+        for violation in &report.violations {
+            println!("  {}", describe(violation));
+        }
+    }
+}
+
+fn output_history_text(report: &HistoryReport) {
+    const BAR_WIDTH: usize = 40;
+    
+    println!();
+    println!("=== Code Growth: {} ({}) ===", report.repository, report.revision);
+    if report.cached_snapshots > 0 {
+        println!("{} of {} samples read from the history cache", report.cached_snapshots, report.snapshots.len());
+    }
+    println!();
+    
+    let max_code_lines = report.snapshots.iter().map(|snapshot| snapshot.code_lines).max().unwrap_or(0).max(1);
+    let mut previous_code_lines = None;
+    for snapshot in &report.snapshots {
+        let change = match previous_code_lines {
+            Some(previous) => format!("{:+}", snapshot.code_lines as i64 - previous as i64),
+            None => String::new(),
+        };
+        let bar = "█".repeat((snapshot.code_lines * BAR_WIDTH).div_ceil(max_code_lines));
+        println!("{}  {:<10} {:>6} files {:>9} code {:>8}  health {:>5.1}  {}",
+            snapshot.date.format("%Y-%m-%d"), snapshot.label, snapshot.files, snapshot.code_lines, change,
+            snapshot.code_health_score, bar);
+        previous_code_lines = Some(snapshot.code_lines);
+    }
+    
+    if let (Some(first), Some(last)) = (report.snapshots.first(), report.snapshots.last()) {
+        println!();
+        println!("Languages (code lines, first → latest sample):");
+        let mut languages: Vec<_> = last.languages.iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (language, lines) in languages.into_iter().take(10) {
+            println!("  {}: {} → {}", language, first.languages.get(language).copied().unwrap_or(0), lines);
+        }
+    }
+}
+
+/// File discovery and analysis settings shared by every analysis mode
+struct WalkOptions {
     max_depth: Option<usize>,
     include_hidden: bool,
     ignore_patterns: Vec<String>,
-    extensions: Vec<String>,
-    show_files: bool,
-    output_format: &OutputFormat,
-) -> Result<(AggregatedStats, Vec<(String, FileStats)>)> {
-    // Only print messages for text output format
-    let should_print = matches!(output_format, OutputFormat::Text);
+    ignore_files: Vec<PathBuf>,
+    include_patterns: Vec<String>,
+    extensions: Option<GlobPatterns>,
+    include_fixtures: bool,
+    /// Count generated sources in the totals (`--include-generated`)
+    include_generated: bool,
+    /// Skip generated sources without reporting them (`--exclude-generated`)
+    exclude_generated: bool,
+    /// Count minified scripts and stylesheets in the totals (`--include-minified`)
+    include_minified: bool,
+    /// Keep the production and test buckets in the report (`--split-tests`); the
+    /// test-to-code ratio is always reported
+    split_tests: bool,
+    decompress: bool,
+    /// Files over `--max-file-size` are skipped by the counter and reported
+    max_file_size: Option<u64>,
+    hash_algorithm: HashAlgorithm,
+    cache_key: CacheKey,
+    /// Off with `--no-cache`
+    caching: bool,
+    max_cache_entries: usize,
+    /// `[cache.remote]`, pulled into the local cache before counting
+    shared_cache: Option<SharedCache>,
+    /// Progress bars while scanning and counting; off with `--quiet` or `show_progress = false`, and when stdout isn't a terminal
+    progress: bool,
+    function_thresholds: FunctionThresholds,
+    /// Most complex functions to list (`--top-functions`), none when 0
+    top_functions: usize,
+    /// Lowest-scoring files to list (`--worst`), none when 0
+    worst_files: usize,
+    per_path: bool,
+    nested_projects: NestedProjects,
+    areas: Vec<(String, GlobPatterns)>,
+    /// `[budget]` globs with their caps
+    budgets: Vec<(String, GlobPatterns, BudgetConfig)>,
+    /// Set with `--owners` to attribute counted files with git blame
+    ownership: Option<OwnershipAnalyzer>,
+    /// Set with `--churn` to rank counted files by change frequency × complexity
+    churn: Option<ChurnAnalyzer>,
+    /// Set with `--dependencies` to count the dependencies of the package manifests below the roots
+    dependencies: Option<DependencyScanner>,
+    /// Set with `--licenses` to check the counted files for a license header
+    licenses: Option<LicenseScanner>,
+    /// Set with `--resume` to the settings an interrupted run must share to be continued
+    resume: Option<String>,
+    /// Generated-code patterns with the header markers of `[generated]`
+    generated_patterns: GeneratedPatterns,
+    /// How prose files add to the documentation totals (`[documentation]`)
+    documentation: DocumentationConfig,
+    /// Directory categories left out of the quality score (`[scoring]`)
+    scoring: ScoringConfig,
+    /// Complexity analyzer choices by path (`[complexity]`)
+    analyzer_overrides: AnalyzerOverrides,
+    /// Files selected by the git pathspecs after `--`, resolved once the roots are known
+    pathspec: Option<GitPathspec>,
+    /// Part of the files this run analyzes (`--shard`)
+    shard: Option<Shard>,
+    /// Print every unreadable file (`--verbose-warnings`) rather than a summary
+    verbose_warnings: bool,
+    /// Keep a row per file extension (`--by-extension`) instead of grouping them by language
+    by_extension: bool,
+}
+
+impl WalkOptions {
+    fn from_config(config: &Config, file_config: &HowManyConfig, function_thresholds: FunctionThresholds) -> Result<Self> {
+        // Extensions are matched as globs too (e.g. `--ext 'c*,h'`), case-insensitively
+        let extensions: Vec<String> = config.get_extensions().iter().map(|ext| ext.to_lowercase()).collect();
+        let extensions = if extensions.is_empty() {
+            None
+        } else {
+            Some(GlobPatterns::new(&extensions)?)
+        };
+        
+        let areas = file_config.areas
+            .iter()
+            .map(|(name, patterns)| Ok((name.clone(), GlobPatterns::new(patterns)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let budgets = file_config.budget
+            .iter()
+            .map(|(pattern, budget)| {
+                budget::validate(pattern, budget)?;
+                Ok((pattern.clone(), GlobPatterns::new(std::slice::from_ref(pattern))?, budget.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        file_config.scoring.weights.validate()?;
+        
+        Ok(Self {
+            max_depth: config.max_depth,
+            include_hidden: config.include_hidden,
+            ignore_patterns: config.resolve_ignore_patterns(file_config),
+            ignore_files: config.ignore_files.clone(),
+            include_patterns: config.resolve_include_patterns(file_config),
+            extensions,
+            include_fixtures: config.include_fixtures,
+            include_generated: config.include_generated,
+            exclude_generated: config.exclude_generated,
+            include_minified: config.include_minified,
+            split_tests: config.split_tests,
+            decompress: config.decompress,
+            max_file_size: config.get_max_file_size(),
+            hash_algorithm: file_config.hashing.algorithm.ensure_available()?,
+            cache_key: file_config.hashing.cache_key,
+            caching: !config.no_cache,
+            max_cache_entries: file_config.cache.max_entries,
+            shared_cache: file_config.cache.remote.as_ref().map(SharedCache::new).transpose()?,
+            progress: file_config.output_preferences.show_progress && !config.quiet && atty::is(atty::Stream::Stdout),
+            function_thresholds,
+            top_functions: config.top_functions.unwrap_or(0),
+            worst_files: config.worst.unwrap_or(0),
+            per_path: config.per_path,
+            nested_projects: config.nested_projects,
+            areas,
+            budgets,
+            ownership: config.owners.then(|| OwnershipAnalyzer::new().with_teams(file_config.teams.clone()).with_by_extension(config.by_extension)),
+            churn: config.churn.map(ChurnAnalyzer::new),
+            dependencies: config.dependencies.then(DependencyScanner::new),
+            licenses: config.licenses.then(LicenseScanner::new),
+            resume: config.resume.then(|| format!(
+                "ext={:?} ignore={:?} ignore_files={:?} include={:?} hidden={} depth={:?} fixtures={} generated={}/{} markers={:?} minified={} decompress={} max_size={:?} nested={:?} pathspecs={:?} shard={:?}",
+                config.get_extensions(),
+                config.resolve_ignore_patterns(file_config),
+                config.ignore_files,
+                config.resolve_include_patterns(file_config),
+                config.include_hidden,
+                config.max_depth,
+                config.include_fixtures,
+                config.include_generated,
+                config.exclude_generated,
+                file_config.generated,
+                config.include_minified,
+                config.decompress,
+                config.get_max_file_size(),
+                config.nested_projects,
+                config.pathspecs,
+                config.shard,
+            )),
+            generated_patterns: file_config.generated.patterns()?,
+            documentation: file_config.documentation.clone(),
+            scoring: file_config.scoring.clone(),
+            analyzer_overrides: AnalyzerOverrides::new(&file_config.complexity)?,
+            pathspec: None,
+            shard: config.shard,
+            verbose_warnings: config.verbose_warnings,
+            by_extension: config.by_extension,
+        })
+    }
     
-    if should_print {
-        println!("Analyzing directory: {}", path.display());
+    /// Checkpoint key for a `--resume` run over `roots`
+    fn resume_settings(&self, roots: &[PathBuf]) -> Option<String> {
+        let settings = self.resume.as_ref()?;
+        let roots: Vec<_> = roots.iter().map(|root| root.canonicalize().unwrap_or_else(|_| root.clone())).collect();
+        Some(format!("roots={:?} {}", roots, settings))
     }
     
-    let detector = FileDetector::new();
-    let mut filter = FileFilter::new()
-        .respect_hidden(!include_hidden)
-        .respect_gitignore(true);
+    /// File classifier using the configured generated-code markers
+    fn build_detector(&self) -> FileDetector {
+        FileDetector::new().with_generated_patterns(self.generated_patterns.clone())
+    }
     
-    if let Some(depth) = max_depth {
-        filter = filter.with_max_depth(depth);
+    /// Build the directory walker filter for these settings
+    fn build_filter(&self) -> Result<FileFilter> {
+        let mut filter = FileFilter::new()
+            .respect_hidden(!self.include_hidden)
+            .respect_gitignore(true);
+        
+        if let Some(depth) = self.max_depth {
+            filter = filter.with_max_depth(depth);
+        }
+        
+        // Add custom ignore patterns
+        if !self.ignore_patterns.is_empty() {
+            filter = filter.with_custom_ignores(self.ignore_patterns.clone())?;
+        }
+        
+        // Restrict the walk to include patterns
+        if !self.include_patterns.is_empty() {
+            filter = filter.with_include_patterns(self.include_patterns.clone())?;
+        }
+        
+        if !self.ignore_files.is_empty() {
+            filter = filter.with_ignore_files(self.ignore_files.clone())?;
+        }
+        
+        Ok(filter)
     }
     
-    // Add custom ignore patterns
-    if !ignore_patterns.is_empty() {
-        filter = filter.with_custom_ignores(ignore_patterns);
+    /// Path used for classification: the inner file for `.gz` sources when `--decompress` is set
+    fn logical_path<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        match compression::decompressed_path(path) {
+            Some(inner_path) if self.decompress => Cow::Owned(inner_path),
+            _ => Cow::Borrowed(path),
+        }
     }
     
-    if should_print {
-        println!("Scanning for user-created code files...");
+    /// Whether a discovered file belongs in the main totals.
+    /// Test fixtures only count when `--include-fixtures` is set, generated
+    /// sources (by name only) when `--include-generated` is, and minified
+    /// files (by name only) when `--include-minified` is.
+    fn counts_file(&self, detector: &FileDetector, path: &Path) -> bool {
+        let path = self.logical_path(path);
+        if detector.is_test_fixture(&path) {
+            return self.include_fixtures;
+        }
+        if detector.is_generated_code(&path) {
+            return self.include_generated;
+        }
+        if detector.is_minified_name(&path) {
+            return self.include_minified && detector.is_user_created_file(&path);
+        }
+        detector.is_user_created_file(&path)
     }
     
-    // Collect all file paths first
-    let file_paths: Vec<_> = filter.walk_directory(path)
-        .filter_map(|entry| {
+    /// Filter for paths read from git rather than walked, relative to the
+    /// analyzed directory. They are filtered like the entries of an archive.
+    fn git_path_filter(&self) -> Result<impl Fn(&Path) -> bool + '_> {
+        let detector = self.build_detector();
+        let ignores = GlobPatterns::new(&self.ignore_patterns)?;
+        let includes = GlobPatterns::new(&self.include_patterns)?;
+        Ok(move |path: &Path| {
+            let relative_path = Path::new(".").join(path);
+            !ignores.is_match(path)
+                && (includes.is_empty() || includes.is_match(path))
+                && self.counts_file(&detector, &relative_path)
+                && self.matches_extension(&relative_path)
+        })
+    }
+    
+    /// Statistics calculator with the function thresholds and the complexity
+    /// analyzer choices, whose globs match paths relative to `roots`
+    fn build_stats_calculator(&self, roots: &[PathBuf]) -> StatsCalculator {
+        StatsCalculator::new()
+            .with_function_thresholds(self.function_thresholds)
+            .with_top_functions(self.top_functions)
+            .with_worst_files(self.worst_files)
+            .with_quality_weights(self.scoring.weights.clone())
+            .with_analyzer_overrides(self.analyzer_overrides.clone().with_roots(roots))
+            .with_by_extension(self.by_extension)
+    }
+    
+    /// Line counter honoring the size limits, the configured content hash and the prose policy,
+    /// caching in the file cache of the project at `project_root`. A shared cache that can't
+    /// be reached leaves the local cache to work alone.
+    fn build_counter(&self, project_root: &Path) -> CachedCodeCounter {
+        let mut counter = CachedCodeCounter::for_project(project_root)
+            .with_max_decompressed_size(self.max_file_size.unwrap_or(DEFAULT_MAX_DECOMPRESSED_SIZE))
+            .with_max_file_bytes(self.max_file_size)
+            .with_hash_algorithm(self.hash_algorithm)
+            .with_cache_key(self.cache_key)
+            .with_caching(self.caching)
+            .with_max_cache_entries(Some(self.max_cache_entries))
+            .with_documentation(self.documentation.clone());
+        
+        if let Some(shared_cache) = &self.shared_cache {
+            if let Err(e) = counter.pull_shared_cache(shared_cache.clone().for_project(project_root)) {
+                eprintln!("Warning: {}; using the local cache only", e);
+            }
+        }
+        counter
+    }
+    
+    /// Rule that keeps a walked file out of the analysis, or `None` for the
+    /// files it reads. `relative_path` is below the analyzed root.
+    fn discovery_exclusion(&self, detector: &FileDetector, nested_finder: &mut NestedProjectFinder, entry_path: &Path, relative_path: &Path) -> Option<Exclusion> {
+        // Shards split the files by their path below the analyzed root
+        if !self.in_shard(relative_path) {
+            return Some(Exclusion::Shard);
+        }
+        
+        // A separately reported checkout is classified from its own root, so
+        // a crate under `vendor/` isn't dropped as a third-party dependency
+        let nested_path = (self.nested_projects == NestedProjects::Separate)
+            .then(|| nested_finder.project_of(entry_path))
+            .flatten()
+            .map(|project| project_path(&project, entry_path));
+        let relative_path = nested_path.as_deref().unwrap_or(relative_path);
+        
+        // User-created files, test fixtures and source maps are read (and bucketed separately)
+        if let Some(exclusion) = detector.exclusion(&self.logical_path(relative_path)) {
+            return Some(exclusion);
+        }
+        
+        if !self.matches_extension(relative_path) {
+            Some(Exclusion::ExtensionFilter)
+        } else if !self.matches_pathspec(entry_path) {
+            Some(Exclusion::Pathspec)
+        } else {
+            None
+        }
+    }
+    
+    /// Rule that keeps a discovered file out of the totals once it is counted:
+    /// the counter's size and binary checks, then the fixture, generated and
+    /// minified buckets as `analyze_roots` applies them
+    fn counting_exclusion(&self, detector: &FileDetector, counter: &mut CachedCodeCounter, file_path: &Path, logical_path: &Path) -> Option<Exclusion> {
+        let stats = match counter.count_file(file_path) {
+            Ok(stats) => stats,
+            Err(e) => {
+                return Some(match SkippedFile::from_error(file_path.to_string_lossy().to_string(), &e).map(|skipped| skipped.reason) {
+                    Some(SkipReason::TooLarge) => Exclusion::TooLarge,
+                    Some(SkipReason::Binary) => Exclusion::BinaryContent,
+                    None => Exclusion::Unreadable,
+                });
+            }
+        };
+        
+        if detector.is_test_fixture(logical_path) && !self.include_fixtures {
+            Some(Exclusion::Fixture)
+        } else if (detector.is_generated_code(logical_path) || detector.has_generated_header(file_path)) && (self.exclude_generated || !self.include_generated) {
+            Some(Exclusion::Generated)
+        } else if !self.include_minified && detector.is_minified(logical_path, &stats, || std::fs::read(file_path).ok()) {
+            Some(Exclusion::Minified)
+        } else {
+            None
+        }
+    }
+    
+    /// Check a walked file against the git pathspecs (always true when none were given)
+    fn matches_pathspec(&self, path: &Path) -> bool {
+        self.pathspec.as_ref().is_none_or(|pathspec| pathspec.matches(path))
+    }
+    
+    /// Check a root-relative path against `--shard` (always true when not sharded)
+    fn in_shard(&self, relative_path: &Path) -> bool {
+        self.shard.is_none_or(|shard| shard.contains(relative_path))
+    }
+    
+    /// Check a path against the `--ext` filter (always true when no filter is set)
+    fn matches_extension(&self, path: &Path) -> bool {
+        let extensions = match &self.extensions {
+            Some(extensions) => extensions,
+            None => return true,
+        };
+        
+        match extension_key(&self.logical_path(path)) {
+            Some(ext) => extensions.is_match(Path::new(&ext.to_lowercase())),
+            None => false,
+        }
+    }
+}
+
+/// Production and test parts of the counted files, turned into `TestSplitStats`
+#[derive(Default)]
+struct TestSplit {
+    production: Vec<(String, FileStats)>,
+    test: Vec<(String, FileStats)>,
+}
+
+impl TestSplit {
+    /// Files at test paths go to the test bucket whole; other files give up their
+    /// inline test items, if `inline_tests` finds any
+    fn add(
+        &mut self,
+        extension: &str,
+        stats: &FileStats,
+        is_test_path: bool,
+        inline_tests: impl FnOnce() -> Result<Option<FileStats>>,
+    ) {
+        if is_test_path {
+            self.test.push((extension.to_string(), stats.clone()));
+            return;
+        }
+        
+        match inline_tests() {
+            Ok(Some(tests)) => {
+                self.production.push((extension.to_string(), stats.saturating_sub(&tests)));
+                self.test.push((extension.to_string(), tests));
+            }
+            _ => self.production.push((extension.to_string(), stats.clone())),
+        }
+    }
+    
+    /// Store the test-to-code ratio, and the buckets with `keep_buckets`, in `aggregated_stats`
+    fn finish(self, counter: &CachedCodeCounter, keep_buckets: bool, aggregated_stats: &mut AggregatedStats) -> Result<()> {
+        let calculator = BasicStatsCalculator::new();
+        let split = TestSplitStats {
+            production: calculator.calculate_project_basic_stats(&counter.aggregate_stats(self.production))?,
+            test: calculator.calculate_project_basic_stats(&counter.aggregate_stats(self.test))?,
+        };
+        aggregated_stats.ratios.test_to_code_ratio = split.test_to_code_ratio();
+        aggregated_stats.test_split = keep_buckets.then_some(split);
+        Ok(())
+    }
+}
+
+/// Files grouped by directory category, and the files the quality score is computed from
+#[derive(Default)]
+struct ScoringScope {
+    categories: BTreeMap<FileCategory, Vec<(String, FileStats)>>,
+    scored_file_stats: Vec<(String, FileStats)>,
+    scored_files: Vec<(String, FileStats)>,
+    unscored_count: usize,
+}
+
+impl ScoringScope {
+    /// Record a counted file; `path` is given when individual files are collected
+    fn add(&mut self, category: Option<FileCategory>, scoring: &ScoringConfig, extension: &str, stats: &FileStats, path: Option<&str>) {
+        if let Some(category) = category {
+            self.categories.entry(category).or_default().push((extension.to_string(), stats.clone()));
+        }
+        
+        if !scoring.is_scored(category) {
+            self.unscored_count += 1;
+            return;
+        }
+        self.scored_file_stats.push((extension.to_string(), stats.clone()));
+        if let Some(path) = path {
+            self.scored_files.push((path.to_string(), stats.clone()));
+        }
+    }
+    
+    /// Store the category totals in `aggregated_stats` and, when files were left
+    /// out of scoring, replace its quality metrics with those of the scored files
+    fn finish(
+        self,
+        counter: &CachedCodeCounter,
+        calculator: &StatsCalculator,
+        scoring: &ScoringConfig,
+        aggregated_stats: &mut AggregatedStats,
+    ) -> Result<()> {
+        let basic_calculator = BasicStatsCalculator::new();
+        for (category, files) in self.categories {
+            aggregated_stats.categories.push(CategoryStats {
+                category,
+                basic: basic_calculator.calculate_project_basic_stats(&counter.aggregate_stats(files))?,
+                scored: scoring.is_scored(Some(category)),
+            });
+        }
+        
+        if self.unscored_count == 0 {
+            return Ok(());
+        }
+        let scored = calculator.calculate_project_stats(&counter.aggregate_stats(self.scored_file_stats), &self.scored_files)?;
+        aggregated_stats.ratios.quality_metrics = scored.ratios.quality_metrics;
+        aggregated_stats.complexity.quality_metrics = scored.complexity.quality_metrics;
+        Ok(())
+    }
+}
+
+/// Directory of a file analyzed on its own, whose project cache it uses
+fn containing_dir(path: &Path) -> &Path {
+    path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."))
+}
+
+/// Roots as shown in progress messages
+fn display_roots(roots: &[PathBuf]) -> String {
+    roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Path of a discovered file as seen from its root (`./src/main.rs`), so that
+/// directories above the root, such as `/tmp` or `~/.cache`, never affect classification
+fn project_path(root: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(root) {
+        Ok(relative) if !relative.as_os_str().is_empty() => Path::new(".").join(relative),
+        // The root itself is a file
+        _ => match path.file_name() {
+            Some(name) => Path::new(".").join(name),
+            None => path.to_path_buf(),
+        },
+    }
+}
+
+/// Walk every root and collect the files accepted by `accept`, each paired with
+/// the index of its root. `accept` gets the file's path and its `project_path`.
+/// Files reachable from several roots are kept once, under the first root listed.
+fn discover_files(
+    roots: &[PathBuf],
+    filter: &FileFilter,
+    mut accept: impl FnMut(&Path, &Path) -> bool,
+) -> Vec<(usize, PathBuf)> {
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    
+    for (root_index, root) in roots.iter().enumerate() {
+        for entry in filter.walk_directory(root) {
             let entry_path = entry.path();
             
-            if !entry_path.is_file() {
-                return None;
+            if !entry_path.is_file() || !accept(entry_path, &project_path(root, entry_path)) {
+                continue;
             }
             
-            // Check if it's a user-created file
-            if !detector.is_user_created_file(entry_path) {
-                return None;
+            // `.` and `src` reach the same files by different paths
+            let key = std::fs::canonicalize(entry_path).unwrap_or_else(|_| entry_path.to_path_buf());
+            if seen.insert(key) {
+                files.push((root_index, entry_path.to_path_buf()));
             }
-            
-            // Check extension filter if specified
-            if !extensions.is_empty() {
-                if let Some(ext) = entry_path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    if !extensions.iter().any(|e| e.to_lowercase() == ext_str) {
-                        return None;
-                    }
-                } else {
-                    return None;
-                }
+        }
+    }
+    
+    files
+}
+
+/// Comprehensive code analysis using the full stats pipeline
+fn analyze_code_comprehensive(
+    roots: &[PathBuf],
+    walk: &WalkOptions,
+    show_files: bool,
+    output_format: &OutputFormat,
+) -> Result<(AggregatedStats, Vec<(String, FileStats)>)> {
+    let (mut aggregated_stats, individual_files) = analyze_roots(roots, walk, show_files, output_format)?;
+    if !walk.by_extension {
+        StatsMerger::new().group_by_language(&mut aggregated_stats, &LanguageRegistry::new())?;
+    }
+    Ok((aggregated_stats, individual_files))
+}
+
+/// Count and analyze the files below `roots`, with a row per extension
+fn analyze_roots(
+    roots: &[PathBuf],
+    walk: &WalkOptions,
+    show_files: bool,
+    output_format: &OutputFormat,
+) -> Result<(AggregatedStats, Vec<(String, FileStats)>)> {
+    // Only print messages for text output format
+    let should_print = matches!(output_format, OutputFormat::Text);
+    
+    // A single file is analyzed directly instead of walked, and an archive is read in memory
+    if let [root] = roots {
+        if root.is_file() && archive::is_archive(root) {
+            return analyze_archive(root, walk, should_print);
+        }
+        if root.is_file() {
+            return analyze_single_file(root, walk, should_print);
+        }
+    }
+    
+    if should_print {
+        if roots.len() == 1 {
+            println!("Analyzing directory: {}", roots[0].display());
+        } else {
+            println!("Analyzing directories: {}", display_roots(roots));
+        }
+    }
+    
+    let detector = walk.build_detector();
+    let filter = walk.build_filter()?;
+    let mut metrics = MetricsCollector::new();
+    
+    // With `--resume`, an interrupted run with the same roots and settings
+    // continues from its checkpointed file list instead of walking again
+    let resume_settings = walk.resume_settings(roots);
+    let mut checkpoint = match &resume_settings {
+        Some(settings) => RunCheckpoint::load(settings, walk.hash_algorithm)?,
+        None => None,
+    };
+    
+    if should_print {
+        match &checkpoint {
+            Some(checkpoint) => println!(
+                "Resuming interrupted analysis: {} of {} files already analyzed",
+                checkpoint.analyzed_count(),
+                checkpoint.files().len()
+            ),
+            None => println!("Scanning for user-created code files..."),
+        }
+    }
+    
+    // Collect all file paths first
+    let progress = ProgressReporter::new().with_visible(should_print && walk.progress);
+    let discovery_timer = metrics.create_timer("discovery");
+    let scanning = progress.create_spinner("Walking directories...");
+    let mut scanned = 0;
+    let separate_nested = walk.nested_projects == NestedProjects::Separate;
+    let mut nested_finder = NestedProjectFinder::new(roots);
+    let discovered = match &checkpoint {
+        Some(checkpoint) => checkpoint.files(),
+        None => discover_files(roots, &filter, |entry_path, relative_path| {
+            scanned += 1;
+            if scanned % 64 == 0 {
+                scanning.set_message(format!("Walking directories... {} paths checked", format::format_number(scanned)));
             }
-            
-            Some(entry_path.to_path_buf())
-        })
-        .collect();
+            walk.discovery_exclusion(&detector, &mut nested_finder, entry_path, relative_path).is_none()
+        }),
+    };
+    // Persist the scan result before the long analysis pass starts
+    if let (Some(settings), None, false) = (&resume_settings, &checkpoint, discovered.is_empty()) {
+        checkpoint = Some(RunCheckpoint::create(settings, discovered.clone(), walk.hash_algorithm)?);
+    }
+    let file_paths: Vec<_> = discovered.iter().map(|(_, file_path)| file_path.clone()).collect();
+    scanning.finish_and_clear();
+    let (phase, elapsed) = discovery_timer.finish();
+    metrics.add_phase_timing(&phase, elapsed);
     
     if file_paths.is_empty() {
         if should_print {
             println!("No files found matching the criteria.");
         }
         let empty_stats = StatsCalculator::new().calculate_project_stats(
-            &CodeStats {
-                total_files: 0,
-                total_lines: 0,
-                total_code_lines: 0,
-                total_comment_lines: 0,
-                total_blank_lines: 0,
-                total_size: 0,
-                total_doc_lines: 0,
-                stats_by_extension: std::collections::HashMap::new(),
-            },
+            &CodeStats::default(),
             &[],
         )?;
         return Ok((empty_stats, Vec::new()));
     }
     
-    let mut counter = CachedCodeCounter::new();
-    let mut metrics = MetricsCollector::new();
+    let mut counter = walk.build_counter(&roots[0]);
     
     if should_print {
         println!("Processing {} files...", file_paths.len());
@@ -197,39 +1141,256 @@ fn analyze_code_comprehensive(
     
     // Process files sequentially to enable caching
     let mut file_stats = Vec::new();
+    let mut root_file_stats: Vec<Vec<(String, FileStats)>> = vec![Vec::new(); roots.len()];
+    let mut root_files: Vec<Vec<(String, FileStats)>> = vec![Vec::new(); roots.len()];
+    let mut area_file_stats: Vec<Vec<(String, FileStats)>> = vec![Vec::new(); walk.areas.len()];
+    let mut budget_file_stats: Vec<Vec<(String, FileStats)>> = vec![Vec::new(); walk.budgets.len()];
+    let mut nested_file_stats: BTreeMap<PathBuf, Vec<(String, FileStats)>> = BTreeMap::new();
+    let mut nested_files: BTreeMap<PathBuf, Vec<(String, FileStats)>> = BTreeMap::new();
     let mut individual_files = Vec::new();
+    let mut counted_paths = Vec::new();
+    let mut fixtures = FixtureStats {
+        included_in_totals: walk.include_fixtures,
+        ..FixtureStats::default()
+    };
+    let mut generated = GeneratedStats {
+        included_in_totals: walk.include_generated,
+        ..GeneratedStats::default()
+    };
+    let mut minified = MinifiedStats {
+        included_in_totals: walk.include_minified,
+        ..MinifiedStats::default()
+    };
+    let mut test_split = TestSplit::default();
+    let mut scoring_scope = ScoringScope::default();
+    let mut non_utf8_files = Vec::new();
+    let mut skipped_files = Vec::new();
+    let mut failed_files = FailedFiles::new(walk.verbose_warnings);
     
-    for file_path in &file_paths {
-        match counter.count_file(file_path) {
+    let counting_timer = metrics.create_timer("counting");
+    progress.set_total_files(discovered.len() as u64);
+    for (root_index, file_path) in &discovered {
+        progress.set_message(&project_path(&roots[*root_index], file_path).display().to_string());
+        progress.increment();
+        
+        // Files analyzed before an interruption are reused unless they changed since the scan
+        let result = match checkpoint.as_ref().and_then(|checkpoint| checkpoint.analyzed(file_path)) {
+            Some(stats) => Ok(stats.clone()),
+            None => {
+                let result = counter.count_file(file_path);
+                if let (Some(checkpoint), Ok(stats)) = (checkpoint.as_mut(), &result) {
+                    checkpoint.record(file_path, stats)?;
+                }
+                result
+            }
+        };
+        match result {
             Ok(stats) => {
+                let nested_project = separate_nested.then(|| nested_finder.project_of(file_path)).flatten();
+                let relative_path = project_path(nested_project.as_ref().unwrap_or(&roots[*root_index]), file_path);
+                let logical_path = walk.logical_path(&relative_path);
+                if detector.is_test_fixture(&logical_path) {
+                    fixtures.file_count += 1;
+                    fixtures.total_lines += stats.total_lines;
+                    fixtures.total_size += stats.file_size;
+                    
+                    if !walk.include_fixtures {
+                        continue;
+                    }
+                }
+                
+                // Generated by name, or by a marker comment at the top of the file
+                if detector.is_generated_code(&logical_path) || detector.has_generated_header(file_path) {
+                    if walk.exclude_generated {
+                        continue;
+                    }
+                    generated.file_count += 1;
+                    generated.total_lines += stats.total_lines;
+                    generated.code_lines += stats.code_lines;
+                    generated.total_size += stats.file_size;
+                    
+                    if !walk.include_generated {
+                        continue;
+                    }
+                }
+                
+                // Minified by name, or by long lines of packed code
+                if detector.is_minified(&logical_path, &stats, || std::fs::read(file_path).ok()) {
+                    minified.file_count += 1;
+                    minified.total_lines += stats.total_lines;
+                    minified.total_size += stats.file_size;
+                    
+                    if !walk.include_minified {
+                        continue;
+                    }
+                }
+                
                 // Record metrics
                 metrics.record_file_processed(stats.total_lines, stats.file_size);
+                if let Some(encoding) = &stats.encoding {
+                    non_utf8_files.push(FileEncoding { path: file_path.to_string_lossy().to_string(), encoding: encoding.clone() });
+                }
                 
-                let extension = file_path
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .unwrap_or("no_ext")
-                    .to_string();
+                let extension = stats.row_key(extension_key(&logical_path).unwrap_or_else(|| "no_ext".to_string()), walk.by_extension);
+                
+                // Vendored checkouts get their own totals instead of adding to the parent's
+                if let Some(project) = nested_project {
+                    if show_files {
+                        nested_files.entry(project.clone()).or_default().push((file_path.to_string_lossy().to_string(), stats.clone()));
+                    }
+                    nested_file_stats.entry(project).or_default().push((extension, stats));
+                    continue;
+                }
+                
+                if walk.per_path {
+                    root_file_stats[*root_index].push((extension.clone(), stats.clone()));
+                    if show_files {
+                        root_files[*root_index].push((file_path.to_string_lossy().to_string(), stats.clone()));
+                    }
+                }
+                
+                // Area globs are matched relative to the analyzed root
+                let area_path = relative_path.strip_prefix(".").unwrap_or(&relative_path);
+                for (area_index, (_, patterns)) in walk.areas.iter().enumerate() {
+                    if patterns.is_match(area_path) {
+                        area_file_stats[area_index].push((extension.clone(), stats.clone()));
+                    }
+                }
+                for (budget_index, (_, patterns, _)) in walk.budgets.iter().enumerate() {
+                    if patterns.is_match(area_path) {
+                        budget_file_stats[budget_index].push((extension.clone(), stats.clone()));
+                    }
+                }
+                let is_test_path = detector.is_test_code(&logical_path) || detector.is_test_fixture(&logical_path);
+                test_split.add(&extension, &stats, is_test_path, || counter.count_inline_tests(file_path));
+                let path = file_path.to_string_lossy();
+                scoring_scope.add(detector.file_category(&logical_path), &walk.scoring, &extension, &stats, show_files.then_some(&*path));
                 file_stats.push((extension, stats.clone()));
+                if walk.ownership.is_some() || walk.churn.is_some() || walk.licenses.is_some() {
+                    counted_paths.push(file_path.clone());
+                }
                 
                 if show_files {
                     individual_files.push((file_path.to_string_lossy().to_string(), stats));
                 }
             }
-            Err(e) => {
-                if show_files && should_print {
-                    eprintln!("Warning: Failed to process {}: {}", file_path.display(), e);
+            Err(e) => match SkippedFile::from_error(file_path.to_string_lossy().to_string(), &e) {
+                Some(skipped) => {
+                    if skipped.reason == SkipReason::Binary {
+                        metrics.record_binary_skipped();
+                    }
+                    skipped_files.push(skipped);
                 }
-            }
+                None => failed_files.add(file_path, &e),
+            },
         }
     }
+    progress.finish_and_clear();
+    
+    // With `--verbose-warnings` every failure was already printed
+    if let Some(summary) = failed_files.summary().filter(|_| should_print && !walk.verbose_warnings) {
+        eprintln!("Warning: {}", summary);
+    }
+    
+    let (phase, elapsed) = counting_timer.finish();
+    metrics.add_phase_timing(&phase, elapsed);
     
     // Create basic aggregated stats
+    let statistics_timer = metrics.create_timer("statistics");
+    let calculating = progress.create_spinner("Calculating statistics...");
     let basic_code_stats = counter.aggregate_stats(file_stats);
     
     // Use comprehensive stats calculator
-    let stats_calculator = StatsCalculator::new();
-    let aggregated_stats = stats_calculator.calculate_project_stats(&basic_code_stats, &individual_files)?;
+    let stats_calculator = walk.build_stats_calculator(roots).with_analysis_cache(counter.analysis_cache());
+    let mut aggregated_stats = stats_calculator.calculate_project_stats(&basic_code_stats, &individual_files)?;
+    aggregated_stats.fixtures = fixtures;
+    aggregated_stats.generated = generated;
+    aggregated_stats.minified = minified;
+    aggregated_stats.non_utf8_files = non_utf8_files;
+    aggregated_stats.skipped_files = skipped_files;
+    test_split.finish(&counter, walk.split_tests, &mut aggregated_stats)?;
+    scoring_scope.finish(&counter, &stats_calculator, &walk.scoring, &mut aggregated_stats)?;
+    aggregated_stats.normalization_conflicts = find_normalization_conflicts(&file_paths);
+    
+    let basic_calculator = BasicStatsCalculator::new();
+    if walk.per_path {
+        for ((root, root_stats), files) in roots.iter().zip(root_file_stats).zip(root_files) {
+            aggregated_stats.per_path.push(PathStats {
+                path: root.display().to_string(),
+                basic: basic_calculator.calculate_project_basic_stats(&counter.aggregate_stats(root_stats))?,
+                files,
+            });
+        }
+    }
+    
+    for (project, project_stats) in nested_file_stats {
+        aggregated_stats.nested_projects.push(PathStats {
+            path: project.display().to_string(),
+            basic: basic_calculator.calculate_project_basic_stats(&counter.aggregate_stats(project_stats))?,
+            files: nested_files.remove(&project).unwrap_or_default(),
+        });
+    }
+    
+    for ((name, _), area_stats) in walk.areas.iter().zip(area_file_stats) {
+        aggregated_stats.areas.push(AreaStats {
+            name: name.clone(),
+            basic: basic_calculator.calculate_project_basic_stats(&counter.aggregate_stats(area_stats))?,
+        });
+    }
+    for ((pattern, _, budget), budget_stats) in walk.budgets.iter().zip(budget_file_stats) {
+        let basic = basic_calculator.calculate_project_basic_stats(&counter.aggregate_stats(budget_stats))?;
+        aggregated_stats.budgets.push(BudgetStats::new(pattern, budget, &basic));
+    }
+    calculating.finish_and_clear();
+    let (phase, elapsed) = statistics_timer.finish();
+    metrics.add_phase_timing(&phase, elapsed);
+    
+    if let Some(analyzer) = &walk.ownership {
+        if should_print {
+            println!("Running git blame on {} files...", counted_paths.len());
+        }
+        let ownership_timer = metrics.create_timer("ownership");
+        aggregated_stats.ownership = Some(analyzer.analyze(&counted_paths));
+        let (phase, elapsed) = ownership_timer.finish();
+        metrics.add_phase_timing(&phase, elapsed);
+    }
+    
+    if let Some(analyzer) = &walk.churn {
+        if should_print {
+            println!("Reading git history for churn...");
+        }
+        let churn_timer = metrics.create_timer("churn");
+        aggregated_stats.churn = Some(analyze_churn(analyzer, roots, &counted_paths)?);
+        let (phase, elapsed) = churn_timer.finish();
+        metrics.add_phase_timing(&phase, elapsed);
+    }
+    
+    if let Some(scanner) = &walk.dependencies {
+        let dependencies_timer = metrics.create_timer("dependencies");
+        // Manifests aren't code, so they are walked for separately; vendored packages bring their own
+        let manifests: Vec<_> = discover_files(roots, &filter, |entry_path, relative_path| {
+            Ecosystem::of_manifest(entry_path).is_some() && !dependencies::is_vendored(relative_path)
+        })
+        .into_iter()
+        .map(|(_, manifest)| manifest)
+        .collect();
+        aggregated_stats.dependencies = Some(scanner.scan(&manifests));
+        let (phase, elapsed) = dependencies_timer.finish();
+        metrics.add_phase_timing(&phase, elapsed);
+    }
+    
+    if let Some(scanner) = &walk.licenses {
+        let licenses_timer = metrics.create_timer("licenses");
+        aggregated_stats.licenses = Some(scanner.scan(&counted_paths));
+        let (phase, elapsed) = licenses_timer.finish();
+        metrics.add_phase_timing(&phase, elapsed);
+    }
+    update_capabilities(&mut aggregated_stats);
+    
+    // The run completed, so there is nothing left to resume
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.remove()?;
+    }
     
     // Save cache and cleanup
     counter.cleanup_cache();
@@ -238,15 +1399,26 @@ fn analyze_code_comprehensive(
             eprintln!("Warning: Failed to save cache: {}", e);
         }
     }
+    if let Err(e) = counter.push_shared_cache() {
+        eprintln!("Warning: {}", e);
+    }
     
-    // Show performance metrics only for text output
-    let final_metrics = metrics.finish();
     let (cache_hits, cache_misses) = counter.cache_stats();
+    metrics.record_cache_stats(cache_hits, cache_misses);
+    let final_metrics = metrics.finish();
+    
+    // Machine-readable outputs carry the cache and timing figures in their metadata
+    aggregated_stats.metadata.performance = Some(final_metrics.run_performance(counter.cache_size()));
+    
+    // Show performance metrics only for text output
     
     if final_metrics.files_processed > 0 && should_print {
         println!("📊 Performance Summary:");
         println!("   • Files processed: {}", final_metrics.files_processed);
-        println!("   • Processing time: {:.2}s", final_metrics.total_duration.as_secs_f64());
+        println!("   • Processing time: {}", format::format_duration(final_metrics.total_duration));
+        if final_metrics.binary_files_skipped > 0 {
+            println!("   • Binary files skipped: {}", final_metrics.binary_files_skipped);
+        }
         
         if cache_hits + cache_misses > 0 {
             println!("   • Cache hit rate: {:.1}%", counter.cache_hit_rate() * 100.0);
@@ -259,57 +1431,269 @@ fn analyze_code_comprehensive(
     Ok((aggregated_stats, individual_files))
 }
 
-fn list_files(
+/// Full analysis of one file passed as PATH, bypassing discovery filters
+fn analyze_single_file(
     path: &Path,
-    max_depth: Option<usize>,
-    include_hidden: bool,
-    ignore_patterns: Vec<String>,
-    extensions: Vec<String>,
+    walk: &WalkOptions,
+    should_print: bool,
+) -> Result<(AggregatedStats, Vec<(String, FileStats)>)> {
+    if should_print {
+        println!("Analyzing file: {}", path.display());
+    }
+    
+    let mut metrics = MetricsCollector::new();
+    let mut counter = walk.build_counter(containing_dir(path));
+    
+    let counting_timer = metrics.create_timer("counting");
+    let stats = counter.count_file(path)?;
+    metrics.record_file_processed(stats.total_lines, stats.file_size);
+    let (phase, elapsed) = counting_timer.finish();
+    metrics.add_phase_timing(&phase, elapsed);
+    
+    let statistics_timer = metrics.create_timer("statistics");
+    let file_path = path.to_string_lossy().to_string();
+    let stats_calculator = walk.build_stats_calculator(&[]).with_analysis_cache(counter.analysis_cache());
+    let mut aggregated_stats = stats_calculator.calculate_file_stats(&stats, &file_path)?;
+    if let Some(encoding) = &stats.encoding {
+        aggregated_stats.non_utf8_files.push(FileEncoding { path: file_path.clone(), encoding: encoding.clone() });
+    }
+    
+    // The single-file pipeline leaves the language breakdown empty; fill in the one extension
+    let extension = stats.row_key(extension_key(&walk.logical_path(path)).unwrap_or_else(|| "no_ext".to_string()), walk.by_extension);
+    aggregated_stats.basic.stats_by_extension.insert(extension.clone(), ExtensionStats {
+        file_count: 1,
+        total_lines: stats.total_lines,
+        code_lines: stats.code_lines,
+        comment_lines: stats.comment_lines,
+        doc_lines: stats.doc_lines,
+        blank_lines: stats.blank_lines,
+        characters: stats.characters,
+        non_whitespace_characters: stats.non_whitespace_characters,
+        tokens: stats.tokens,
+        line_lengths: stats.line_lengths,
+        average_line_length: stats.line_lengths.average(stats.total_lines),
+        total_size: stats.file_size,
+        average_lines_per_file: stats.total_lines as f64,
+        average_size_per_file: stats.file_size as f64,
+        metrics: stats.metrics.clone(),
+    });
+    let detector = walk.build_detector();
+    let mut test_split = TestSplit::default();
+    test_split.add(&extension, &stats, detector.is_test_code(path), || counter.count_inline_tests(path));
+    test_split.finish(&counter, walk.split_tests, &mut aggregated_stats)?;
+    aggregated_stats.metadata.languages_detected = vec![extension];
+    let (phase, elapsed) = statistics_timer.finish();
+    metrics.add_phase_timing(&phase, elapsed);
+    
+    if let Some(analyzer) = &walk.ownership {
+        let ownership_timer = metrics.create_timer("ownership");
+        aggregated_stats.ownership = Some(analyzer.analyze(&[path.to_path_buf()]));
+        let (phase, elapsed) = ownership_timer.finish();
+        metrics.add_phase_timing(&phase, elapsed);
+    }
+    
+    if let Some(analyzer) = &walk.churn {
+        let churn_timer = metrics.create_timer("churn");
+        let file = [path.to_path_buf()];
+        aggregated_stats.churn = Some(analyze_churn(analyzer, &file, &file)?);
+        let (phase, elapsed) = churn_timer.finish();
+        metrics.add_phase_timing(&phase, elapsed);
+    }
+    
+    if let Some(scanner) = &walk.dependencies {
+        aggregated_stats.dependencies = Some(scanner.scan(&[path.to_path_buf()]));
+    }
+    
+    if let Some(scanner) = &walk.licenses {
+        aggregated_stats.licenses = Some(scanner.scan(&[path.to_path_buf()]));
+    }
+    update_capabilities(&mut aggregated_stats);
+    
+    if let Err(e) = counter.save_cache() {
+        if should_print {
+            eprintln!("Warning: Failed to save cache: {}", e);
+        }
+    }
+    if let Err(e) = counter.push_shared_cache() {
+        eprintln!("Warning: {}", e);
+    }
+    
+    let (cache_hits, cache_misses) = counter.cache_stats();
+    metrics.record_cache_stats(cache_hits, cache_misses);
+    aggregated_stats.metadata.performance = Some(metrics.finish().run_performance(counter.cache_size()));
+    
+    Ok((aggregated_stats, vec![(file_path, stats)]))
+}
+
+/// Rank counted files by change frequency in the roots' history times their total complexity
+fn analyze_churn(analyzer: &ChurnAnalyzer, roots: &[PathBuf], files: &[PathBuf]) -> Result<ChurnStats> {
+    let complexity = ComplexityStatsCalculator::new();
+    analyzer.analyze(roots, files, |file| {
+        complexity.calculate_total_complexity(&file.to_string_lossy()).unwrap_or(0)
+    })
+}
+
+/// Analysis of a `.zip`, `.tar`, `.tar.gz` or `.crate` file, streaming its
+/// entries through the counter without extracting them
+fn analyze_archive(
+    path: &Path,
+    walk: &WalkOptions,
+    should_print: bool,
+) -> Result<(AggregatedStats, Vec<(String, FileStats)>)> {
+    if should_print {
+        println!("Analyzing archive: {}", path.display());
+    }
+    
+    let detector = walk.build_detector();
+    let counter = walk.build_counter(containing_dir(path));
+    let ignores = GlobPatterns::new(&walk.ignore_patterns)?;
+    let includes = GlobPatterns::new(&walk.include_patterns)?;
+    let mut metrics = MetricsCollector::new();
+    
+    let mut file_stats = Vec::new();
+    let mut individual_files = Vec::new();
+    let mut fixtures = FixtureStats {
+        included_in_totals: walk.include_fixtures,
+        ..FixtureStats::default()
+    };
+    let mut generated = GeneratedStats {
+        included_in_totals: walk.include_generated,
+        ..GeneratedStats::default()
+    };
+    let mut minified = MinifiedStats {
+        included_in_totals: walk.include_minified,
+        ..MinifiedStats::default()
+    };
+    let mut test_split = TestSplit::default();
+    let mut scoring_scope = ScoringScope::default();
+    let mut non_utf8_files = Vec::new();
+    let mut skipped_files = Vec::new();
+    
+    let counting_timer = metrics.create_timer("counting");
+    let max_entry_size = walk.max_file_size.unwrap_or(DEFAULT_MAX_DECOMPRESSED_SIZE);
+    let skipped = archive::for_each_entry(path, max_entry_size, |entry_path, contents| {
+        // Entries are classified like walked files, relative to the archive root
+        let relative_path = Path::new(".").join(entry_path);
+        if ignores.is_match(entry_path) || (!includes.is_empty() && !includes.is_match(entry_path)) {
+            return Ok(());
+        }
+        if !detector.is_user_created_file(&relative_path) && !detector.is_test_fixture(&relative_path) && !detector.is_source_map(&relative_path) {
+            return Ok(());
+        }
+        if !walk.matches_extension(&relative_path) || !walk.in_shard(&relative_path) {
+            return Ok(());
+        }
+        
+        let stats = match counter.count_contents(entry_path, contents) {
+            Ok(stats) => stats,
+            Err(e) => {
+                let skipped = SkippedFile::from_error(format!("{}/{}", path.display(), entry_path.display()), &e).ok_or(e)?;
+                if skipped.reason == SkipReason::Binary {
+                    metrics.record_binary_skipped();
+                }
+                skipped_files.push(skipped);
+                return Ok(());
+            }
+        };
+        if detector.is_test_fixture(&relative_path) {
+            fixtures.file_count += 1;
+            fixtures.total_lines += stats.total_lines;
+            fixtures.total_size += stats.file_size;
+            
+            if !walk.include_fixtures {
+                return Ok(());
+            }
+        }
+        
+        if detector.is_generated_code(&relative_path) || detector.has_generated_marker(contents) {
+            if walk.exclude_generated {
+                return Ok(());
+            }
+            generated.file_count += 1;
+            generated.total_lines += stats.total_lines;
+            generated.code_lines += stats.code_lines;
+            generated.total_size += stats.file_size;
+            
+            if !walk.include_generated {
+                return Ok(());
+            }
+        }
+        
+        if detector.is_minified(&relative_path, &stats, || Some(contents.to_vec())) {
+            minified.file_count += 1;
+            minified.total_lines += stats.total_lines;
+            minified.total_size += stats.file_size;
+            
+            if !walk.include_minified {
+                return Ok(());
+            }
+        }
+        
+        metrics.record_file_processed(stats.total_lines, stats.file_size);
+        if let Some(encoding) = &stats.encoding {
+            non_utf8_files.push(FileEncoding { path: format!("{}/{}", path.display(), entry_path.display()), encoding: encoding.clone() });
+        }
+        let extension = stats.row_key(extension_key(entry_path).unwrap_or_else(|| "no_ext".to_string()), walk.by_extension);
+        let is_test_path = detector.is_test_code(&relative_path) || detector.is_test_fixture(&relative_path);
+        test_split.add(&extension, &stats, is_test_path, || counter.count_inline_tests_in(entry_path, contents));
+        scoring_scope.add(detector.file_category(&relative_path), &walk.scoring, &extension, &stats, None);
+        file_stats.push((extension, stats.clone()));
+        individual_files.push((format!("{}/{}", path.display(), entry_path.display()), stats));
+        Ok(())
+    })?;
+    let (phase, elapsed) = counting_timer.finish();
+    metrics.add_phase_timing(&phase, elapsed);
+    
+    if skipped > 0 && should_print {
+        eprintln!("Warning: Skipped {} archive entries larger than {}", skipped, format::format_size(max_entry_size));
+    }
+    
+    // Entries exist only in memory, so the per-function analysis (which reads files) is skipped
+    let statistics_timer = metrics.create_timer("statistics");
+    let code_stats = counter.aggregate_stats(file_stats);
+    let stats_calculator = walk.build_stats_calculator(&[]);
+    let mut aggregated_stats = stats_calculator.calculate_project_stats(&code_stats, &[])?;
+    aggregated_stats.fixtures = fixtures;
+    aggregated_stats.generated = generated;
+    aggregated_stats.minified = minified;
+    aggregated_stats.non_utf8_files = non_utf8_files;
+    aggregated_stats.skipped_files = skipped_files;
+    test_split.finish(&counter, walk.split_tests, &mut aggregated_stats)?;
+    scoring_scope.finish(&counter, &stats_calculator, &walk.scoring, &mut aggregated_stats)?;
+    update_capabilities(&mut aggregated_stats);
+    let (phase, elapsed) = statistics_timer.finish();
+    metrics.add_phase_timing(&phase, elapsed);
+    
+    aggregated_stats.metadata.performance = Some(metrics.finish().run_performance(0));
+    
+    Ok((aggregated_stats, individual_files))
+}
+
+fn list_files(
+    roots: &[PathBuf],
+    walk: &WalkOptions,
     output_format: &OutputFormat,
 ) -> Result<()> {
     let should_print = matches!(output_format, OutputFormat::Text);
     
-    let detector = FileDetector::new();
-    let mut filter = FileFilter::new()
-        .respect_hidden(!include_hidden)
-        .respect_gitignore(true);
-    
-    if let Some(depth) = max_depth {
-        filter = filter.with_max_depth(depth);
-    }
-    
-    // Add custom ignore patterns
-    if !ignore_patterns.is_empty() {
-        filter = filter.with_custom_ignores(ignore_patterns);
-    }
+    let detector = walk.build_detector();
+    let filter = walk.build_filter()?;
     
     if should_print {
         println!("Files that would be counted:");
     }
     
-    for entry in filter.walk_directory(path) {
-        let entry_path = entry.path();
-        
-        if entry_path.is_file() {
-            // Check if it's a user-created file
-            if !detector.is_user_created_file(entry_path) {
-                continue;
-            }
-            
-            // Check extension filter if specified
-            if !extensions.is_empty() {
-                if let Some(ext) = entry_path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    if !extensions.iter().any(|e| e.to_lowercase() == ext_str) {
-                        continue;
-                    }
-                } else {
-                    continue;
-                }
-            }
-            
-            println!("  {}", entry_path.display());
-        }
+    let files = discover_files(roots, &filter, |entry_path, relative_path| {
+        // Check if it's a user-created file, then the extension filter if specified
+        walk.counts_file(&detector, relative_path)
+            && walk.matches_extension(relative_path)
+            && walk.matches_pathspec(entry_path)
+            && walk.in_shard(relative_path)
+    });
+    
+    // Binaries under source-like names are skipped by the counter, so they aren't listed
+    for (_, file_path) in files.into_iter().filter(|(_, file_path)| !detector.has_binary_content(file_path)) {
+        println!("  {}", file_path.display());
     }
     
     Ok(())
@@ -319,17 +1703,22 @@ fn output_comprehensive_results(
     aggregated_stats: &AggregatedStats,
     individual_files: &[(String, FileStats)],
     format: OutputFormat,
-    sort_by: SortBy,
-    descending: bool,
-    verbose: bool,
     config: &Config,
+    sarif_rules: &SarifRuleSet,
 ) -> Result<()> {
     match format {
-        OutputFormat::Text => output_text(aggregated_stats, individual_files, sort_by, descending, verbose, config),
+        OutputFormat::Text => output_text(aggregated_stats, individual_files, config.sort_by, config.descending, config.verbose, config),
         OutputFormat::Json => output_json(aggregated_stats, individual_files),
-        OutputFormat::Csv => output_csv(aggregated_stats, individual_files),
-        OutputFormat::Html => output_html(aggregated_stats, individual_files),
-        OutputFormat::Sarif => output_sarif(aggregated_stats, individual_files),
+        OutputFormat::JsonTree => output_json_tree(individual_files, config.by_extension),
+        OutputFormat::Csv => output_csv(aggregated_stats, individual_files, config),
+        OutputFormat::Html => output_html(aggregated_stats, individual_files, config),
+        OutputFormat::Sarif => output_sarif(aggregated_stats, individual_files, sarif_rules, config),
+        OutputFormat::Prometheus => output_prometheus(aggregated_stats),
+        OutputFormat::Yaml => output_yaml(aggregated_stats),
+        OutputFormat::Toml => output_toml(aggregated_stats),
+        OutputFormat::Msgpack => output_msgpack(aggregated_stats),
+        OutputFormat::ClocJson => output_cloc_json(aggregated_stats),
+        OutputFormat::Tokei => output_tokei(aggregated_stats),
     }
 }
 
@@ -360,18 +1749,65 @@ fn output_text(
     println!("=== Code Statistics ===");
     
     // Basic stats
-    println!("Total files: {}", format_number(aggregated_stats.basic.total_files, use_color));
-    println!("Total lines: {}", format_number(aggregated_stats.basic.total_lines, use_color));
-    println!("Code lines: {}", format_number(aggregated_stats.basic.code_lines, use_color));
-    println!("Comment lines: {}", format_number(aggregated_stats.basic.comment_lines, use_color));
-    println!("Documentation lines: {}", format_number(aggregated_stats.basic.doc_lines, use_color));
-    println!("Blank lines: {}", format_number(aggregated_stats.basic.blank_lines, use_color));
+    if let Some(split) = &aggregated_stats.test_split {
+        print_test_split_totals(&aggregated_stats.basic, split);
+        println!("Test-to-code ratio: {:.2}", aggregated_stats.ratios.test_to_code_ratio);
+    } else {
+        println!("Total files: {}", format_number(aggregated_stats.basic.total_files, use_color));
+        println!("Total lines: {}", format_number(aggregated_stats.basic.total_lines, use_color));
+        println!("Code lines: {}", format_number(aggregated_stats.basic.code_lines, use_color));
+        println!("Logical lines (statements): {}", format_number(aggregated_stats.basic.logical_lines, use_color));
+        println!("Comment lines: {}", format_number(aggregated_stats.basic.comment_lines, use_color));
+        println!("Documentation lines: {}", format_number(aggregated_stats.basic.doc_lines, use_color));
+        println!("Blank lines: {}", format_number(aggregated_stats.basic.blank_lines, use_color));
+    }
+    
+    if aggregated_stats.fixtures.file_count > 0 && !aggregated_stats.fixtures.included_in_totals {
+        println!("Test fixtures (excluded): {} files, {} lines",
+            format_number(aggregated_stats.fixtures.file_count, use_color),
+            format_number(aggregated_stats.fixtures.total_lines, use_color));
+    }
+    
+    if aggregated_stats.generated.file_count > 0 && !aggregated_stats.generated.included_in_totals {
+        println!("Generated code (excluded): {} files, {} lines",
+            format_number(aggregated_stats.generated.file_count, use_color),
+            format_number(aggregated_stats.generated.total_lines, use_color));
+    }
+    
+    if aggregated_stats.minified.file_count > 0 && !aggregated_stats.minified.included_in_totals {
+        println!("Minified files (excluded): {} files, {} lines",
+            format_number(aggregated_stats.minified.file_count, use_color),
+            format_number(aggregated_stats.minified.total_lines, use_color));
+    }
+    
+    if !aggregated_stats.non_utf8_files.is_empty() {
+        let mut by_encoding: BTreeMap<&str, usize> = BTreeMap::new();
+        for file in &aggregated_stats.non_utf8_files {
+            *by_encoding.entry(&file.encoding).or_default() += 1;
+        }
+        let summary: Vec<String> = by_encoding.iter().map(|(encoding, count)| format!("{}: {}", encoding, count)).collect();
+        println!("Non-UTF-8 files (decoded): {} ({})", aggregated_stats.non_utf8_files.len(), summary.join(", "));
+    }
+    
+    if !aggregated_stats.skipped_files.is_empty() {
+        println!("Skipped files: {}", aggregated_stats.skipped_files.len());
+        for file in &aggregated_stats.skipped_files {
+            println!("  {} ({}, {})", file.path, file.reason, format::format_size(file.size));
+        }
+    }
+    
+    if !aggregated_stats.normalization_conflicts.is_empty() {
+        println!("Warning: {} file name(s) have variants differing only by Unicode normalization (NFC/NFD):",
+            aggregated_stats.normalization_conflicts.len());
+        for conflict in &aggregated_stats.normalization_conflicts {
+            println!("  {}", conflict.variants.join(", "));
+        }
+    }
     
     if config.show_size {
-        let size_mb = aggregated_stats.basic.total_size as f64 / (1024.0 * 1024.0);
-        println!("Total size: {} bytes ({:.2} MB)", 
+        println!("Total size: {} bytes ({})", 
             format_number(aggregated_stats.basic.total_size as usize, use_color), 
-            size_mb
+            format::format_size(aggregated_stats.basic.total_size)
         );
     }
     
@@ -382,26 +1818,135 @@ fn output_text(
         
         // Simple time estimation based on lines of code
         let hours = (aggregated_stats.basic.code_lines as f64 * 0.5) / 60.0; // ~30 seconds per line
-        let days = hours / 8.0;
-        
-        if days >= 1.0 {
-            println!("Estimated development time: {:.1} days ({:.1} hours)", days, hours);
-        } else {
-            println!("Estimated development time: {:.1} hours", hours);
-        }
+        println!("Estimated development time: {}", format::format_work_hours(hours));
     }
     
-    // Enhanced stats from comprehensive analysis
-    if config.show_complexity && aggregated_stats.complexity.function_count > 0 {
+    // Enhanced stats from comprehensive analysis; left out when no file had a complexity analyzer
+    if config.show_complexity && aggregated_stats.metadata.capabilities.complexity {
         println!();
         println!("=== Complexity Analysis ===");
         println!("Functions: {}", format_number(aggregated_stats.complexity.function_count, use_color));
-        println!("Average complexity: {:.1}", aggregated_stats.complexity.cyclomatic_complexity);
-        println!("Max nesting depth: {}", aggregated_stats.complexity.max_nesting_depth);
+        if !aggregated_stats.metadata.complexity_not_analyzed.is_empty() {
+            println!("Complexity not analyzed for: {}", aggregated_stats.metadata.complexity_not_analyzed.join(", "));
+        }
+        
+        if aggregated_stats.complexity.function_count > 0 {
+            println!("Average complexity: {:.1}", aggregated_stats.complexity.cyclomatic_complexity);
+            println!("Max nesting depth: {}", aggregated_stats.complexity.max_nesting_depth);
+            
+            let lengths = &aggregated_stats.complexity.function_length_distribution;
+            println!("Function length: min {}, median {}, p90 {}, max {} lines",
+                lengths.min, lengths.p50, lengths.p90, lengths.max);
+            println!("Longest 5% of functions: {:.1}% of function lines (Gini {:.2})",
+                lengths.top_5_percent_share * 100.0, lengths.gini_coefficient);
+            
+            if config.show_function_details {
+                println!("Average function length: {:.1} lines", aggregated_stats.complexity.average_function_length);
+                println!("Average parameters: {:.1} (max {})",
+                    aggregated_stats.complexity.average_parameters_per_function,
+                    aggregated_stats.complexity.max_parameters_per_function);
+                println!("Average return paths: {:.1} (max {})",
+                    aggregated_stats.complexity.average_return_paths_per_function,
+                    aggregated_stats.complexity.max_return_paths_per_function);
+                
+                let long_parameter_functions = &aggregated_stats.complexity.long_parameter_functions;
+                if !long_parameter_functions.is_empty() {
+                    println!("Functions with more than {} parameters: {}", aggregated_stats.complexity.function_thresholds.max_parameters, long_parameter_functions.len());
+                }
+                
+                // Per-language parameter and return path breakdown
+                let mut languages: Vec<_> = aggregated_stats.complexity.complexity_by_extension.iter()
+                    .filter(|(_, ext_complexity)| ext_complexity.function_count > 0)
+                    .collect();
+                languages.sort_by(|a, b| a.0.cmp(b.0));
+                
+                for (ext, ext_complexity) in languages {
+                    println!("  {}: {:.1} params (max {}), {:.1} return paths (max {}), {} long parameter lists",
+                        ext,
+                        ext_complexity.average_parameters_per_function,
+                        ext_complexity.max_parameters_per_function,
+                        ext_complexity.average_return_paths_per_function,
+                        ext_complexity.max_return_paths_per_function,
+                        ext_complexity.long_parameter_function_count);
+                }
+            }
+        }
+    }
+    
+    // Classes, interfaces, traits, enums, structs and modules, per language
+    let complexity = &aggregated_stats.complexity;
+    if config.show_complexity && aggregated_stats.metadata.capabilities.complexity && complexity.total_structures > 0 {
+        println!();
+        println!("=== Structures ===");
+        println!("Classes: {}, interfaces: {}, traits: {}, enums: {}, structs: {}, modules: {}",
+            complexity.class_count, complexity.interface_count, complexity.trait_count,
+            complexity.enum_count, complexity.struct_count, complexity.module_count);
+        if complexity.class_count > 0 {
+            println!("Methods per class: {:.1} (max {})", complexity.methods_per_class, complexity.max_methods_per_class);
+        }
+        
+        let mut languages: Vec<_> = complexity.complexity_by_extension.iter()
+            .filter(|(_, ext_complexity)| ext_complexity.total_structures > 0)
+            .collect();
+        languages.sort_by(|a, b| b.1.total_structures.cmp(&a.1.total_structures).then_with(|| a.0.cmp(b.0)));
         
-        if config.show_function_details {
-            println!("Average function length: {:.1} lines", aggregated_stats.complexity.average_function_length);
-            println!("Methods per class: {:.1}", aggregated_stats.complexity.methods_per_class);
+        println!("{:<12} {:>8} {:>10} {:>7} {:>6} {:>8} {:>8} {:>14}", "Language", "Classes", "Interfaces", "Traits", "Enums", "Structs", "Modules", "Methods/class");
+        for (ext, ext_complexity) in languages {
+            let methods_per_class = if ext_complexity.class_count > 0 {
+                format!("{:.1} (max {})", ext_complexity.methods_per_class, ext_complexity.max_methods_per_class)
+            } else {
+                "-".to_string()
+            };
+            println!("{:<12} {:>8} {:>10} {:>7} {:>6} {:>8} {:>8} {:>14}",
+                ext,
+                ext_complexity.class_count,
+                ext_complexity.interface_count,
+                ext_complexity.trait_count,
+                ext_complexity.enum_count,
+                ext_complexity.struct_count,
+                ext_complexity.module_count,
+                methods_per_class);
+        }
+    }
+    
+    // Most complex functions, with --top-functions
+    let top_functions = &aggregated_stats.complexity.function_complexity_details;
+    if config.top_functions.is_some() && !top_functions.is_empty() {
+        println!();
+        println!("=== Most Complex Functions ===");
+        println!("{:>10} {:>9} {:>6} {:>6}  Function", "Cyclomatic", "Cognitive", "Lines", "Params");
+        for function in top_functions {
+            let name = match &function.parent_class {
+                Some(parent) => format!("{}::{}", parent, function.name),
+                None => function.name.clone(),
+            };
+            println!("{:>10} {:>9} {:>6} {:>6}  {} ({}:{})",
+                function.cyclomatic_complexity,
+                function.cognitive_complexity,
+                function.line_count,
+                function.parameter_count,
+                name,
+                function.file_path,
+                function.start_line);
+        }
+    }
+    
+    // Lowest-scoring files, with --worst
+    let worst_files = &aggregated_stats.complexity.worst_files;
+    if config.worst.is_some() && !worst_files.is_empty() {
+        println!();
+        println!("=== Files to Refactor First ===");
+        println!("{:>5} {:>6} {:>9} {:>10} {:>5} {:>5}  File", "Score", "MI", "Functions", "Complexity", "Docs", "Dup");
+        for file in worst_files {
+            println!("{:>5.1} {:>6.1} {:>9} {:>10.1} {:>4.0}% {:>4.0}%  {} ({} code lines)",
+                file.score,
+                file.maintainability_index,
+                file.function_count,
+                file.average_complexity,
+                file.documentation_coverage,
+                file.duplication_ratio,
+                file.file_path,
+                file.code_lines);
         }
     }
     
@@ -421,6 +1966,10 @@ fn output_text(
         println!("Overall quality score: {}{:.1}/100{}", quality_color, quality_score, reset);
         println!("Documentation score: {:.1}/100", aggregated_stats.ratios.quality_metrics.documentation_score);
         println!("Maintainability score: {:.1}/100", aggregated_stats.ratios.quality_metrics.maintainability_score);
+        let line_lengths = &aggregated_stats.basic.line_lengths;
+        println!("Style score: {:.1}/100 (longest line {}, average {:.1} characters; {} over 80, {} over 100, {} over 120)",
+            aggregated_stats.ratios.quality_metrics.style_score, line_lengths.max, aggregated_stats.basic.average_line_length,
+            line_lengths.over_80, line_lengths.over_100, line_lengths.over_120);
     }
     
     // Code ratios
@@ -429,12 +1978,174 @@ fn output_text(
         println!("=== Code Ratios ===");
         println!("Code ratio: {:.1}%", aggregated_stats.ratios.code_ratio * 100.0);
         println!("Comment ratio: {:.1}%", aggregated_stats.ratios.comment_ratio * 100.0);
+        println!("Comment density (incl. {} inline): {:.1}%",
+            aggregated_stats.basic.inline_comment_lines, aggregated_stats.ratios.comment_density * 100.0);
         println!("Documentation ratio: {:.1}%", aggregated_stats.ratios.doc_ratio * 100.0);
+        println!("Test-to-code ratio: {:.2}", aggregated_stats.ratios.test_to_code_ratio);
+    }
+    
+    if !aggregated_stats.per_path.is_empty() {
+        println!();
+        println!("=== Breakdown by Path ===");
+        
+        for path_stats in &aggregated_stats.per_path {
+            println!("  {}: {} files, {} lines ({} code, {} docs, {} comments)",
+                path_stats.path, path_stats.basic.total_files, path_stats.basic.total_lines,
+                path_stats.basic.code_lines, path_stats.basic.doc_lines, path_stats.basic.comment_lines);
+        }
+    }
+    
+    if !aggregated_stats.nested_projects.is_empty() {
+        println!();
+        println!("=== Nested Projects (not in totals) ===");
+        
+        for project in &aggregated_stats.nested_projects {
+            println!("  {}: {} files, {} lines ({} code, {} docs, {} comments)",
+                project.path, project.basic.total_files, project.basic.total_lines,
+                project.basic.code_lines, project.basic.doc_lines, project.basic.comment_lines);
+        }
+    }
+    
+    if !aggregated_stats.areas.is_empty() {
+        println!();
+        println!("=== Breakdown by Area ===");
+        
+        for area in &aggregated_stats.areas {
+            println!("  {}: {} files, {} lines ({} code, {} docs, {} comments)",
+                area.name, area.basic.total_files, area.basic.total_lines,
+                area.basic.code_lines, area.basic.doc_lines, area.basic.comment_lines);
+        }
+    }
+    
+    if !aggregated_stats.budgets.is_empty() {
+        println!();
+        println!("=== Budgets ===");
+        
+        for budget in &aggregated_stats.budgets {
+            let usage: Vec<String> = budget.usage
+                .iter()
+                .map(|usage| format!("{} {}/{} ({:.1}%)", usage.metric, usage.used, usage.limit, usage.percent))
+                .collect();
+            let status = match budget.status {
+                BudgetStatus::Ok => "",
+                BudgetStatus::Warning => " - warning: approaching its cap",
+                BudgetStatus::Exceeded => " - over budget",
+            };
+            println!("  {}: {}{}", budget.pattern, usage.join(", "), status);
+        }
+    }
+    
+    if !aggregated_stats.categories.is_empty() {
+        println!();
+        println!("=== Examples, Benchmarks and Docs ===");
+        
+        for category in &aggregated_stats.categories {
+            println!("  {}: {} files, {} lines ({} code, {} docs, {} comments){}",
+                category.category, category.basic.total_files, category.basic.total_lines,
+                category.basic.code_lines, category.basic.doc_lines, category.basic.comment_lines,
+                if category.scored { "" } else { ", not in quality score" });
+        }
+    }
+    
+    if let Some(ownership) = &aggregated_stats.ownership {
+        println!();
+        println!("=== Ownership ===");
+        println!("Blamed lines: {} across {} files ({} skipped)",
+            ownership.total_lines, ownership.files_blamed, ownership.files_skipped);
+        println!("Bus factor: {} (top author owns {:.1}%)", ownership.bus_factor, ownership.top_author_share * 100.0);
+        println!("Single-author files: {}", ownership.single_author_files);
+        
+        for author in ownership.authors.iter().take(10) {
+            let last_touched = author.last_touched
+                .map(|time| time.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            println!("  {} <{}>: {} lines ({:.1}%), {} files, last touched {}",
+                author.name, author.email, author.lines, author.share * 100.0, author.files, last_touched);
+        }
+        if ownership.authors.len() > 10 {
+            println!("  ... and {} more authors", ownership.authors.len() - 10);
+        }
+        
+        for team in &ownership.teams {
+            println!("  Team {}: {} lines ({:.1}%), {} authors", team.name, team.lines, team.share * 100.0, team.authors);
+        }
+        
+        if !ownership.languages.is_empty() {
+            println!("By language:");
+            for area in &ownership.languages {
+                println!("  {}: top author {} owns {:.1}% of {} lines, {} authors",
+                    area.name, area.top_author, area.top_author_share * 100.0, area.lines, area.authors);
+            }
+        }
+        
+        if let Some(threshold) = ownership.concentration_threshold {
+            let concentrated: Vec<_> = ownership.directories.iter()
+                .map(|area| (area.name.clone(), area))
+                .chain(ownership.languages.iter().map(|area| (format!("{} files", area.name), area)))
+                .filter(|(_, area)| area.concentrated)
+                .collect();
+            println!("Concentrated areas (one author owns more than {}%): {}", threshold, concentrated.len());
+            for (name, area) in concentrated {
+                println!("  {}: {} owns {:.1}% of {} lines", name, area.top_author, area.top_author_share * 100.0, area.lines);
+            }
+        }
+    }
+    
+    if let Some(churn) = &aggregated_stats.churn {
+        println!();
+        println!("=== Hotspots ===");
+        println!("Commits analyzed: {} of the last {} requested", churn.commits_analyzed, churn.commit_window);
+        println!("Files changed: {}", churn.files_changed);
+        
+        if churn.hotspots.is_empty() {
+            println!("  No changed files with functions");
+        }
+        for hotspot in churn.hotspots.iter().take(10) {
+            println!("  {}: score {} ({} changes × complexity {})",
+                hotspot.path, hotspot.score, hotspot.changes, hotspot.complexity);
+        }
+    }
+    
+    if let Some(dependencies) = &aggregated_stats.dependencies {
+        println!();
+        println!("=== Dependencies ===");
+        println!("Direct: {}, dev: {}, in {} manifests", dependencies.direct, dependencies.dev, dependencies.manifests.len());
+        for ecosystem in &dependencies.ecosystems {
+            println!("  {}: {} direct, {} dev ({} manifests)",
+                ecosystem.ecosystem.name(), ecosystem.direct, ecosystem.dev, ecosystem.manifests);
+        }
+        if verbose {
+            for manifest in &dependencies.manifests {
+                println!("  {}: {} direct, {} dev", manifest.path, manifest.direct, manifest.dev);
+            }
+        }
+        if !dependencies.unreadable.is_empty() {
+            println!("Unreadable manifests: {}", dependencies.unreadable.join(", "));
+        }
+    }
+    
+    if let Some(licenses) = &aggregated_stats.licenses {
+        println!();
+        println!("=== Licenses ===");
+        println!("Licensed files: {} of {} ({:.1}%)", licenses.licensed, licenses.files, licenses.coverage());
+        for count in &licenses.licenses {
+            println!("  {}: {} files", count.license, count.files);
+        }
+        if !licenses.missing.is_empty() {
+            println!("Missing a license header: {} files", licenses.missing.len());
+            let shown = if verbose { licenses.missing.len() } else { 20 };
+            for path in licenses.missing.iter().take(shown) {
+                println!("  {}", path);
+            }
+            if licenses.missing.len() > shown {
+                println!("  ... and {} more files (-v lists all)", licenses.missing.len() - shown);
+            }
+        }
     }
     
     if verbose || !aggregated_stats.basic.stats_by_extension.is_empty() {
         println!();
-        println!("=== Breakdown by Extension ===");
+        println!("=== Breakdown by {} ===", if config.by_extension { "Extension" } else { "Language" });
         
         let mut extensions: Vec<_> = aggregated_stats.basic.stats_by_extension.iter().collect();
         
@@ -470,9 +2181,27 @@ fn output_text(
         }
         
         for (ext, ext_stats) in extensions {
-            println!("  {}: {} files, {} lines ({} code, {} docs, {} comments)",
+            let split = aggregated_stats.test_split.as_ref().map(|split| {
+                let code_lines = |basic: &BasicStats| basic.stats_by_extension.get(ext).map_or(0, |stats| stats.code_lines);
+                format!(" [{} production, {} test code]", code_lines(&split.production), code_lines(&split.test))
+            });
+            println!("  {}: {} files, {} lines ({} code, {} docs, {} comments){}",
                 ext, ext_stats.file_count, ext_stats.total_lines, ext_stats.code_lines,
-                ext_stats.doc_lines, ext_stats.comment_lines);
+                ext_stats.doc_lines, ext_stats.comment_lines, split.unwrap_or_default());
+        }
+    }
+    
+    let mut plugin_metrics: BTreeMap<&str, f64> = BTreeMap::new();
+    for ext_stats in aggregated_stats.basic.stats_by_extension.values() {
+        for (name, value) in &ext_stats.metrics {
+            *plugin_metrics.entry(name).or_insert(0.0) += value;
+        }
+    }
+    if !plugin_metrics.is_empty() {
+        println!();
+        println!("=== Plugin Metrics ===");
+        for (name, value) in plugin_metrics {
+            println!("  {}: {}", name, value);
         }
     }
     
@@ -488,13 +2217,34 @@ fn output_text(
         }
         
         for (file_path, file_stats) in files {
-            println!("  {}: {} lines ({} code)", file_path, file_stats.total_lines, file_stats.code_lines);
+            match &file_stats.encoding {
+                Some(encoding) => println!("  {}: {} lines ({} code) [{}]", file_path, file_stats.total_lines, file_stats.code_lines, encoding),
+                None => println!("  {}: {} lines ({} code)", file_path, file_stats.total_lines, file_stats.code_lines),
+            }
         }
     }
     
     Ok(())
 }
 
+/// Totals in Total / Production / Test columns, for `--split-tests`
+fn print_test_split_totals(basic: &BasicStats, split: &TestSplitStats) {
+    let columns = [basic, &split.production, &split.test];
+    let rows = [
+        ("Files", columns.map(|stats| stats.total_files)),
+        ("Lines", columns.map(|stats| stats.total_lines)),
+        ("Code lines", columns.map(|stats| stats.code_lines)),
+        ("Comment lines", columns.map(|stats| stats.comment_lines)),
+        ("Documentation lines", columns.map(|stats| stats.doc_lines)),
+        ("Blank lines", columns.map(|stats| stats.blank_lines)),
+    ];
+    
+    println!("{:<20} {:>12} {:>12} {:>12}", "", "Total", "Production", "Test");
+    for (label, [total, production, test]) in rows {
+        println!("{:<20} {:>12} {:>12} {:>12}", label, total, production, test);
+    }
+}
+
 /// Print summary-only output
 fn print_summary_only(aggregated_stats: &AggregatedStats, config: &Config) {
     println!("Summary: {} files, {} lines ({} code, {} comments)", 
@@ -536,72 +2286,132 @@ fn output_json(
     aggregated_stats: &AggregatedStats,
     _individual_files: &[(String, FileStats)],
 ) -> Result<()> {
-    // Use the comprehensive stats serialization
-    let json_output = serde_json::to_string_pretty(aggregated_stats)?;
+    // Use the comprehensive stats serialization, under the version of its layout
+    let json_output = serde_json::to_string_pretty(&JsonReport::new(aggregated_stats))?;
     println!("{}", json_output);
     Ok(())
 }
 
+/// The JSON report as YAML, for snapshots kept next to other YAML config
+fn output_yaml(aggregated_stats: &AggregatedStats) -> Result<()> {
+    let yaml_output = serde_yaml::to_string(&JsonReport::new(aggregated_stats))
+        .map_err(|e| HowManyError::display(format!("Failed to write YAML: {}", e)))?;
+    print!("{}", yaml_output);
+    Ok(())
+}
+
+/// The JSON report as TOML. Fields without a value are left out, as TOML has no null.
+fn output_toml(aggregated_stats: &AggregatedStats) -> Result<()> {
+    let toml_output = toml::to_string_pretty(&JsonReport::new(aggregated_stats))
+        .map_err(|e| HowManyError::display(format!("Failed to write TOML: {}", e)))?;
+    print!("{}", toml_output);
+    Ok(())
+}
+
+/// The JSON report as MessagePack, with field names kept so any decoder can read
+/// it without a schema. Refuses a terminal, which would show the bytes as garbage.
+fn output_msgpack(aggregated_stats: &AggregatedStats) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+    
+    let mut stdout = std::io::stdout().lock();
+    if stdout.is_terminal() {
+        return Err(HowManyError::display("msgpack output is binary; redirect it to a file or pipe it to a program"));
+    }
+    let msgpack_output = rmp_serde::to_vec_named(&JsonReport::new(aggregated_stats))
+        .map_err(|e| HowManyError::display(format!("Failed to write MessagePack: {}", e)))?;
+    stdout.write_all(&msgpack_output)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn output_json_tree(individual_files: &[(String, FileStats)], by_extension: bool) -> Result<()> {
+    let tree = VisualizationGenerator::new().with_by_extension(by_extension).generate_directory_tree(individual_files);
+    println!("{}", serde_json::to_string_pretty(&tree)?);
+    Ok(())
+}
+
+fn output_cloc_json(aggregated_stats: &AggregatedStats) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&CompatExporter::new().cloc_json(aggregated_stats))?);
+    Ok(())
+}
+
+fn output_tokei(aggregated_stats: &AggregatedStats) -> Result<()> {
+    print!("{}", CompatExporter::new().tokei_table(aggregated_stats));
+    Ok(())
+}
+
+fn output_prometheus(aggregated_stats: &AggregatedStats) -> Result<()> {
+    print!("{}", PrometheusExporter::new().render(aggregated_stats));
+    Ok(())
+}
+
 fn output_csv(
     aggregated_stats: &AggregatedStats,
-    _individual_files: &[(String, FileStats)],
+    individual_files: &[(String, FileStats)],
+    config: &Config,
 ) -> Result<()> {
-    println!("Extension,Files,Total Lines,Code Lines,Comment Lines,Doc Lines,Blank Lines,Size (bytes)");
-    
-    for (ext, ext_stats) in &aggregated_stats.basic.stats_by_extension {
-        println!("{},{},{},{},{},{},{},{}",
-            ext,
-            ext_stats.file_count,
-            ext_stats.total_lines,
-            ext_stats.code_lines,
-            ext_stats.comment_lines,
-            ext_stats.doc_lines,
-            ext_stats.blank_lines,
-            ext_stats.total_size);
+    let exporter = CsvExporter::new().with_delimiter(config.csv_delimiter).with_by_extension(config.by_extension);
+    if config.csv_per_file {
+        print!("{}", exporter.render_files(aggregated_stats, individual_files)?);
+    } else {
+        print!("{}", exporter.render(aggregated_stats)?);
     }
-    
     Ok(())
 }
 
 fn output_html(
     aggregated_stats: &AggregatedStats,
     individual_files: &[(String, FileStats)],
+    config: &Config,
 ) -> Result<()> {
     use howmany::ui::html::HtmlReporter;
     
-    let reporter = HtmlReporter::new();
-    let output_path = Path::new("howmany-report.html");
+    let reporter = HtmlReporter::new().with_by_extension(config.by_extension);
+    let reporter = match &config.html_template {
+        Some(template) => reporter.with_template_file(template)?,
+        None => reporter,
+    };
+    let output_path = Path::new(HTML_REPORT_FILE);
     
     // Use comprehensive report generation with real AggregatedStats
     reporter.generate_comprehensive_report(aggregated_stats, individual_files, output_path)?;
-    println!("HTML report generated: {}", output_path.display());
+    note_report_file(config, format!("HTML report generated: {}", output_path.display()));
     
     Ok(())
 }
 
+/// Tell where a report file was written; on stderr when standard output
+/// carries a JSON, CSV or other document the note would break
+fn note_report_file(config: &Config, note: String) {
+    if config.stdout_is_document() {
+        eprintln!("{}", note);
+    } else {
+        println!("{}", note);
+    }
+}
+
 fn output_sarif(
     aggregated_stats: &AggregatedStats,
     individual_files: &[(String, FileStats)],
+    sarif_rules: &SarifRuleSet,
+    config: &Config,
 ) -> Result<()> {
     use howmany::ui::sarif::SarifReporter;
     
-    let reporter = SarifReporter::new();
+    let reporter = SarifReporter::new().with_rules(sarif_rules.clone());
     let output_path = Path::new("howmany-report.sarif");
     
     // Use comprehensive report generation with AggregatedStats
     reporter.generate_comprehensive_report(aggregated_stats, individual_files, output_path)?;
-    println!("SARIF report generated: {}", output_path.display());
+    note_report_file(config, format!("SARIF report generated: {}", output_path.display()));
     
     Ok(())
 }
 
 /// Simple CLI output showing just basic file and line counts
 fn simple_cli_output(
-    path: &Path,
-    max_depth: Option<usize>,
-    include_hidden: bool,
-    ignore_patterns: Vec<String>,
-    extensions: Vec<String>,
+    roots: &[PathBuf],
+    walk: &WalkOptions,
     filter_options: FilterOptions,
 ) -> Result<()> {
     // Check if we need enhanced output (requires full analysis)
@@ -612,11 +2422,8 @@ fn simple_cli_output(
     if needs_enhanced_output {
         // Run full analysis for enhanced output
         let (mut aggregated_stats, individual_files) = analyze_code_comprehensive(
-            path,
-            max_depth,
-            include_hidden,
-            ignore_patterns.clone(),
-            extensions.clone(),
+            roots,
+            walk,
             false, // Don't need individual files for CLI output
             &OutputFormat::Text,
         )?;
@@ -680,57 +2487,41 @@ fn simple_cli_output(
         return Ok(());
     }
     
-    // Simple counting for basic output
-    let detector = FileDetector::new();
-    let mut filter = FileFilter::new()
-        .respect_hidden(!include_hidden)
-        .respect_gitignore(true);
-    
-    if let Some(depth) = max_depth {
-        filter = filter.with_max_depth(depth);
+    // Archives have no files to walk; count their entries in memory
+    // (a non-text format keeps progress messages out of the one-line output)
+    if let [root] = roots {
+        if root.is_file() && archive::is_archive(root) {
+            let (aggregated_stats, _) = analyze_code_comprehensive(roots, walk, false, &OutputFormat::Json)?;
+            println!("{} files, {} lines", aggregated_stats.basic.total_files, aggregated_stats.basic.total_lines);
+            return Ok(());
+        }
     }
     
-    // Add custom ignore patterns
-    if !ignore_patterns.is_empty() {
-        filter = filter.with_custom_ignores(ignore_patterns);
-    }
+    // Simple counting for basic output
+    let detector = walk.build_detector();
+    let filter = walk.build_filter()?;
     
     // Collect and filter files
     let file_stats_filter = FileStatsFilter::new(filter_options.clone());
     let mut filtered_files = Vec::new();
     let mut total_lines = 0;
-    let mut counter = CachedCodeCounter::new();
+    let mut counter = walk.build_counter(&roots[0]);
     
-    for entry in filter.walk_directory(path) {
-        let entry_path = entry.path();
-        
-        if !entry_path.is_file() {
-            continue;
-        }
-        
-        // Check if it's a user-created file
-        if !detector.is_user_created_file(entry_path) {
-            continue;
-        }
-        
-        // Check extension filter if specified
-        if !extensions.is_empty() {
-            if let Some(ext) = entry_path.extension() {
-                let ext_str = ext.to_string_lossy().to_lowercase();
-                if !extensions.iter().any(|e| e.to_lowercase() == ext_str) {
-                    continue;
-                }
-            } else {
-                continue;
-            }
-        }
-        
+    let files = discover_files(roots, &filter, |entry_path, relative_path| {
+        // Check if it's a user-created file, then the extension filter if specified
+        walk.counts_file(&detector, relative_path)
+            && walk.matches_extension(relative_path)
+            && walk.matches_pathspec(entry_path)
+            && walk.in_shard(relative_path)
+    });
+    
+    for (_, file_path) in files {
         // Count lines for this file
-        if let Ok(stats) = counter.count_file(entry_path) {
+        if let Ok(stats) = counter.count_file(&file_path) {
             // Apply filters
-            if file_stats_filter.passes_filter(&entry_path.to_string_lossy(), &stats) {
-                filtered_files.push(entry_path.to_path_buf());
+            if file_stats_filter.passes_filter(&file_path.to_string_lossy(), &stats) {
                 total_lines += stats.total_lines;
+                filtered_files.push(file_path);
             }
         }
     }
@@ -741,8 +2532,7 @@ fn simple_cli_output(
             .filter_map(|path| std::fs::metadata(path).ok())
             .map(|metadata| metadata.len())
             .sum();
-        let size_mb = total_size as f64 / (1024.0 * 1024.0);
-        println!("{} files, {} lines, {:.1} MB", filtered_files.len(), total_lines, size_mb);
+        println!("{} files, {} lines, {}", filtered_files.len(), total_lines, format::format_size(total_size));
     } else {
         println!("{} files, {} lines", filtered_files.len(), total_lines);
     }
@@ -750,21 +2540,118 @@ fn simple_cli_output(
     Ok(())
 }
 
+/// Each line of one file with its classification, as a table or a JSON array
+fn classify_file_output(path: &Path, walk: &WalkOptions, format: &OutputFormat) -> Result<()> {
+    let lines = walk.build_counter(containing_dir(path)).classify_file(path)?;
+    
+    if format == &OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&lines)?);
+        return Ok(());
+    }
+    
+    let width = lines.len().to_string().len();
+    for line in &lines {
+        let kind = if line.inline_comment { "code+comment" } else { line.kind.name() };
+        let row = format!("{:>width$}  {:<12}  {}", line.number, kind, line.text, width = width);
+        println!("{}", row.trim_end());
+    }
+    Ok(())
+}
+
+/// Whether each path below `roots` is counted, or the rule that excludes it:
+/// a table of exclusion reasons, or the verdict on `path` alone
+fn explain_output(roots: &[PathBuf], walk: &WalkOptions, path: Option<&Path>, format: &OutputFormat) -> Result<()> {
+    let detector = walk.build_detector();
+    let filter = walk.build_filter()?;
+    let mut counter = walk.build_counter(&roots[0]);
+    let mut nested_finder = NestedProjectFinder::new(roots);
+    let query = path.map(|path| explain::explained_path(roots, path)).transpose()?;
+    
+    let report = explain::explain_walk(roots, &filter, |root, entry_path| {
+        let exclusion = walk.discovery_exclusion(&detector, &mut nested_finder, entry_path, &project_path(root, entry_path));
+        // Files are only read to explain the whole walk or the file asked about
+        if exclusion.is_some() || query.as_deref().is_some_and(|query| query != entry_path) {
+            return exclusion;
+        }
+        let nested_project = (walk.nested_projects == NestedProjects::Separate).then(|| nested_finder.project_of(entry_path)).flatten();
+        let relative_path = project_path(nested_project.as_deref().unwrap_or(root), entry_path);
+        walk.counting_exclusion(&detector, &mut counter, entry_path, &walk.logical_path(&relative_path))
+    });
+    // A failed cache write only costs speed on the next run
+    let _ = counter.save_cache();
+    
+    match (query, format == &OutputFormat::Json) {
+        (Some(query), true) => println!("{}", serde_json::to_string_pretty(&report.verdict(&query))?),
+        (Some(query), false) => println!("{}", report.verdict(&query)),
+        (None, true) => println!("{}", serde_json::to_string_pretty(&report)?),
+        (None, false) => print!("{}", report.render_text(roots)),
+    }
+    Ok(())
+}
+
+/// Fast JSON output - cached per-file line counts without the stats pipeline
+fn fast_json_output(roots: &[PathBuf], walk: &WalkOptions) -> Result<()> {
+    let started = std::time::Instant::now();
+    let detector = walk.build_detector();
+    let filter = walk.build_filter()?;
+    let mut counter = walk.build_counter(&roots[0]);
+    
+    let files = discover_files(roots, &filter, |entry_path, relative_path| {
+        walk.counts_file(&detector, relative_path)
+            && walk.matches_extension(relative_path)
+            && walk.matches_pathspec(entry_path)
+            && walk.in_shard(relative_path)
+    });
+    
+    let mut totals = FileStats::default();
+    let mut file_entries = Vec::with_capacity(files.len());
+    
+    for (_, file_path) in files {
+        // Unchanged files are served from the cache without being read
+        if let Ok(stats) = counter.count_file(&file_path) {
+            totals.total_lines += stats.total_lines;
+            totals.code_lines += stats.code_lines;
+            totals.comment_lines += stats.comment_lines;
+            totals.doc_lines += stats.doc_lines;
+            totals.blank_lines += stats.blank_lines;
+            totals.inline_comment_lines += stats.inline_comment_lines;
+            totals.logical_lines += stats.logical_lines;
+            totals.characters += stats.characters;
+            totals.non_whitespace_characters += stats.non_whitespace_characters;
+            totals.tokens += stats.tokens;
+            totals.line_lengths.merge(&stats.line_lengths);
+            totals.file_size += stats.file_size;
+            file_entries.push(FastFileStats {
+                path: file_path.to_string_lossy().to_string(),
+                stats,
+            });
+        }
+    }
+    
+    // A failed cache write only costs speed on the next run
+    let _ = counter.save_cache();
+    let _ = counter.push_shared_cache();
+    
+    let report = FastReport {
+        total_files: file_entries.len(),
+        totals,
+        files: file_entries,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+    println!("{}", serde_json::to_string(&report)?);
+    
+    Ok(())
+}
+
 /// Quiet mode output - minimal information only
 fn quiet_output(
-    path: &Path,
-    max_depth: Option<usize>,
-    include_hidden: bool,
-    ignore_patterns: Vec<String>,
-    extensions: Vec<String>,
+    roots: &[PathBuf],
+    walk: &WalkOptions,
     _filter_options: FilterOptions,
 ) -> Result<()> {
     let (aggregated_stats, _) = analyze_code_comprehensive(
-        path,
-        max_depth,
-        include_hidden,
-        ignore_patterns,
-        extensions,
+        roots,
+        walk,
         false,
         &OutputFormat::Text,
     )?;
@@ -778,4 +2665,149 @@ fn quiet_output(
     Ok(())
 }
 
- 
\ No newline at end of file
+ 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn walk_options(args: &[&str], file_config: &HowManyConfig) -> WalkOptions {
+        let config = Config::try_parse_from(["howmany", "--no-cache"].iter().chain(args)).unwrap();
+        WalkOptions::from_config(&config, file_config, FunctionThresholds::default()).unwrap()
+    }
+    
+    fn analyze(args: &[&str], roots: &[PathBuf]) -> AggregatedStats {
+        analyze_roots(roots, &walk_options(args, &HowManyConfig::default()), false, &OutputFormat::Json).unwrap().0
+    }
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("build.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {\n    println!(\"hi\");\n}\n").unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_overlapping_roots_count_files_once() {
+        let dir = project();
+        let root = dir.path().to_path_buf();
+        let once = analyze(&[], std::slice::from_ref(&root));
+        assert_eq!((once.basic.total_files, once.basic.code_lines), (3, 7));
+
+        // `src` again, and the project through a `.` component: nothing is counted twice
+        let overlapping = analyze(&[], &[root.clone(), root.join("src"), root.join(".")]);
+        assert_eq!((overlapping.basic.total_files, overlapping.basic.code_lines), (3, 7));
+    }
+
+    #[test]
+    fn test_objective_c_m_files_by_language_and_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Greeter.m"), "#import <Foundation/Foundation.h>\n\n@implementation Greeter\n- (void)greet {\n    NSLog(@\"hi\");\n}\n@end\n").unwrap();
+        std::fs::write(dir.path().join("square.m"), "% Squares\nfunction y = square(x)\n    y = x.^2;\nend\n").unwrap();
+        let roots = [dir.path().to_path_buf()];
+        
+        let stats = analyze(&["--by-extension"], &roots);
+        let rows: Vec<_> = stats.basic.stats_by_extension.iter().map(|(key, row)| (key.as_str(), row.file_count, row.code_lines)).collect();
+        assert_eq!(rows, vec![("m", 2, 9)]);
+        
+        let (stats, _) = analyze_code_comprehensive(&roots, &walk_options(&[], &HowManyConfig::default()), false, &OutputFormat::Json).unwrap();
+        let registry = LanguageRegistry::new();
+        let by_language = &stats.basic.stats_by_extension;
+        assert_eq!(by_language.len(), 2);
+        assert_eq!((by_language[&registry.key("mm")].file_count, by_language[&registry.key("mm")].code_lines), (1, 6));
+        assert_eq!((by_language[&registry.key("m")].file_count, by_language[&registry.key("m")].code_lines), (1, 3));
+    }
+    
+    #[test]
+    fn test_files_over_max_file_size_are_reported() {
+        let dir = project();
+        std::fs::write(dir.path().join("src/data.rs"), "// generated\n".repeat(100)).unwrap();
+        let roots = [dir.path().to_path_buf()];
+        
+        let stats = analyze(&["--max-file-size", "1KB"], &roots);
+        assert_eq!(stats.basic.total_files, 3);
+        let skipped: Vec<_> = stats.skipped_files.iter().map(|file| (Path::new(&file.path).ends_with("src/data.rs"), file.reason, file.size)).collect();
+        assert_eq!(skipped, vec![(true, SkipReason::TooLarge, 1300)]);
+        
+        // Under the default limit, or with none
+        for args in [&[][..], &["--max-file-size", "0"]] {
+            let stats = analyze(args, &roots);
+            assert_eq!((stats.basic.total_files, stats.skipped_files.len()), (4, 0));
+        }
+    }
+    
+    #[test]
+    fn test_per_path_totals() {
+        let dir = project();
+        let root = dir.path().to_path_buf();
+
+        let single = analyze(&["--per-path"], std::slice::from_ref(&root));
+        assert_eq!(single.per_path.len(), 1);
+        assert_eq!(single.per_path[0].basic.total_files, 3);
+
+        // The files of `src` stay with the root listed first
+        let stats = analyze(&["--per-path"], &[root.clone(), root.join("src")]);
+        let totals: Vec<_> = stats.per_path.iter().map(|path| (path.path.clone(), path.basic.total_files)).collect();
+        assert_eq!(totals, vec![
+            (root.display().to_string(), 3),
+            (root.join("src").display().to_string(), 0),
+        ]);
+
+        let stats = analyze(&["--per-path"], &[root.join("src"), root.clone()]);
+        let totals: Vec<_> = stats.per_path.iter().map(|path| path.basic.total_files).collect();
+        assert_eq!(totals, vec![2, 1]);
+    }
+    
+    #[test]
+    fn test_merged_shards_match_the_full_run() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("tests")).unwrap();
+        for index in 0..6 {
+            let mut module = format!("pub fn value_{index}() -> usize {{\n    {index}\n}}\n");
+            if index % 2 == 0 {
+                module.push_str(&format!("\n#[cfg(test)]\nmod tests {{\n    #[test]\n    fn value() {{\n        assert_eq!(super::value_{index}(), {index});\n    }}\n}}\n"));
+            }
+            std::fs::write(dir.path().join(format!("src/module_{index}.rs")), module).unwrap();
+            std::fs::write(dir.path().join(format!("tests/module_{index}.rs")), "#[test]\nfn value() {\n    assert!(true);\n}\n").unwrap();
+        }
+        std::fs::write(dir.path().join("build.py"), "print('build')\n").unwrap();
+        let file_config = HowManyConfig {
+            areas: BTreeMap::from([("sources".to_string(), vec!["src/**".to_string()])]),
+            ..HowManyConfig::default()
+        };
+        let roots = [dir.path().to_path_buf()];
+        let run = |args: &[&str]| analyze_code_comprehensive(&roots, &walk_options(args, &file_config), false, &OutputFormat::Json).unwrap().0;
+        
+        // Reports as `--output json` writes them, without `--split-tests`
+        let full = run(&[]);
+        let reports_dir = tempfile::tempdir().unwrap();
+        let reports: Vec<PathBuf> = ["1/2", "2/2"]
+            .iter()
+            .enumerate()
+            .map(|(index, shard)| {
+                let stats = run(&["--shard", shard]);
+                assert!(stats.basic.total_files > 0 && stats.basic.total_files < full.basic.total_files);
+                let path = reports_dir.path().join(format!("shard-{}.json", index + 1));
+                std::fs::write(&path, serde_json::to_string(&JsonReport::new(&stats)).unwrap()).unwrap();
+                path
+            })
+            .collect();
+        let merged = merge_reports(&reports, false).unwrap();
+        
+        let rows = |stats: &AggregatedStats| {
+            let mut rows: Vec<_> = stats.basic.stats_by_extension.iter().map(|(key, row)| (key.clone(), row.file_count, row.code_lines)).collect();
+            rows.sort();
+            rows
+        };
+        assert_eq!((merged.basic.total_files, merged.basic.code_lines), (full.basic.total_files, full.basic.code_lines));
+        assert_eq!(rows(&merged), rows(&full));
+        assert!(full.ratios.test_to_code_ratio > 0.0);
+        assert!((merged.ratios.test_to_code_ratio - full.ratios.test_to_code_ratio).abs() < 1e-9);
+        let areas = |stats: &AggregatedStats| stats.areas.iter().map(|area| (area.name.clone(), area.basic.total_files, area.basic.code_lines)).collect::<Vec<_>>();
+        assert_eq!(areas(&merged), areas(&full));
+        assert_eq!(areas(&full), vec![("sources".to_string(), 6, 39)]);
+    }
+}
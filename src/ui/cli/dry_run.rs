@@ -0,0 +1,211 @@
+use super::{summary, Config, NestedProjects};
+use crate::core::filters::HOWMANYIGNORE_FILE;
+use crate::utils::cache;
+use crate::utils::config::HowManyConfig;
+use crate::utils::errors::Result;
+use crate::utils::format;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Environment variables that change what a run does, shown when set
+const ENVIRONMENT: [&str; 3] = [cache::CACHE_DIR_ENV, summary::REPORT_URL_ENV, "CI"];
+
+/// What a run would do (`--dry-run`): the configuration it would use, the
+/// filters it would apply and how many files it would find
+#[derive(Debug, Serialize)]
+pub struct DryRunReport {
+    pub roots: Vec<PathBuf>,
+    pub configuration_sources: String,
+    pub configuration: HowManyConfig,
+    pub environment: BTreeMap<String, String>,
+    /// Filter rules by name, in the order `describe_filters` gives them
+    #[serde(serialize_with = "filters_by_name")]
+    pub filters: Vec<(String, String)>,
+    pub output_formats: Vec<String>,
+    pub output_preset: Option<String>,
+    pub estimated_files: usize,
+}
+
+impl DryRunReport {
+    /// Report on a run of `config` over `roots` with the `effective` configuration from `sources`
+    pub fn new(config: &Config, roots: &[PathBuf], effective: HowManyConfig, sources: String, estimated_files: usize) -> Self {
+        Self {
+            roots: roots.to_vec(),
+            configuration_sources: sources,
+            filters: describe_filters(config, &effective),
+            configuration: effective,
+            environment: ENVIRONMENT
+                .into_iter()
+                .filter_map(|name| std::env::var(name).ok().map(|value| (name.to_string(), value)))
+                .collect(),
+            output_formats: config.formats.iter().map(ToString::to_string).collect(),
+            output_preset: config.output_preset.clone(),
+            estimated_files,
+        }
+    }
+
+    pub fn render_text(&self) -> Result<String> {
+        let roots: Vec<_> = self.roots.iter().map(|root| root.display().to_string()).collect();
+        let mut output = format!("Dry run of {}: nothing is analyzed\n\n", roots.join(", "));
+        output.push_str(&format!("# Effective configuration from {}\n", self.configuration_sources));
+        output.push_str(&self.configuration.to_toml()?);
+        output.push('\n');
+        if !self.environment.is_empty() {
+            output.push_str("Environment:\n");
+            for (name, value) in &self.environment {
+                output.push_str(&format!("  {}={}\n", name, value));
+            }
+            output.push('\n');
+        }
+        let width = self.filters.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        output.push_str("Filters:\n");
+        for (name, value) in &self.filters {
+            output.push_str(&format!("  {:<width$}  {}\n", name, value, width = width));
+        }
+        output.push('\n');
+        match &self.output_preset {
+            Some(preset) => output.push_str(&format!("Output: {} (preset {})\n", self.output_formats.join(", "), preset)),
+            None => output.push_str(&format!("Output: {}\n", self.output_formats.join(", "))),
+        }
+        output.push_str(&format!("Estimated files: {}\n", format::format_number(self.estimated_files)));
+        Ok(output)
+    }
+}
+
+fn filters_by_name<S: Serializer>(filters: &[(String, String)], serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    filters.iter().cloned().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// The rules deciding which files are counted, by name, with the ignore and
+/// include patterns of the `effective` configuration. Filters applied to the
+/// results after counting come last.
+pub fn describe_filters(config: &Config, effective: &HowManyConfig) -> Vec<(String, String)> {
+    let join = |values: &[String]| values.join(", ");
+    let mut ignore_files = vec![".gitignore".to_string(), HOWMANYIGNORE_FILE.to_string()];
+    ignore_files.extend(config.ignore_files.iter().map(|path| path.display().to_string()));
+    let bucket = |counted: bool| if counted { "counted" } else { "reported separately" };
+
+    let mut filters = vec![
+        ("ignore files".to_string(), join(&ignore_files)),
+        ("hidden paths".to_string(), if config.include_hidden { "walked" } else { "skipped" }.to_string()),
+    ];
+    let mut add = |name: &str, value: String| filters.push((name.to_string(), value));
+    if let Some(depth) = config.max_depth {
+        add("max depth", depth.to_string());
+    }
+    if !effective.custom_ignore_patterns.is_empty() {
+        add("ignore", join(&effective.custom_ignore_patterns));
+    }
+    if !effective.custom_include_patterns.is_empty() {
+        add("include", join(&effective.custom_include_patterns));
+    }
+    let extensions = config.get_extensions();
+    if !extensions.is_empty() {
+        add("extensions", join(&extensions));
+    }
+    if let Some(size) = config.get_max_file_size() {
+        add("max file size", format::format_size(size));
+    }
+    add("test fixtures", bucket(config.include_fixtures).to_string());
+    add("generated code", if config.exclude_generated { "skipped" } else { bucket(config.include_generated) }.to_string());
+    add("minified files", bucket(config.include_minified).to_string());
+    if config.decompress {
+        add("compressed files", "decompressed".to_string());
+    }
+    if config.nested_projects == NestedProjects::Separate {
+        add("nested projects", "separate".to_string());
+    }
+    if let Some(shard) = config.shard {
+        add("shard", shard.to_string());
+    }
+    if !config.pathspecs.is_empty() {
+        add("pathspecs", config.pathspecs.join(" "));
+    }
+
+    let options = config.get_filter_options();
+    let limits = [
+        ("min lines", options.min_lines.map(|value| value.to_string())),
+        ("max lines", options.max_lines.map(|value| value.to_string())),
+        ("min size", options.min_size_bytes.map(format::format_size)),
+        ("max size", options.max_size_bytes.map(format::format_size)),
+        ("min complexity", options.min_complexity.map(|value| value.to_string())),
+        ("max complexity", options.max_complexity.map(|value| value.to_string())),
+        ("min functions", options.min_functions.map(|value| value.to_string())),
+        ("max functions", options.max_functions.map(|value| value.to_string())),
+        ("min quality", options.min_quality_score.map(|value| value.to_string())),
+        ("max quality", options.max_quality_score.map(|value| value.to_string())),
+        ("min doc ratio", options.min_doc_ratio.map(|value| value.to_string())),
+        ("max doc ratio", options.max_doc_ratio.map(|value| value.to_string())),
+    ];
+    for (name, value) in limits {
+        if let Some(value) = value {
+            add(name, value);
+        }
+    }
+    if !options.include_languages.is_empty() {
+        add("languages", join(&options.include_languages));
+    }
+    if !options.exclude_languages.is_empty() {
+        add("excluded languages", join(&options.exclude_languages));
+    }
+    filters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn parse(args: &[&str]) -> Config {
+        Config::try_parse_from(std::iter::once("howmany").chain(args.iter().copied())).unwrap()
+    }
+
+    fn filter<'a>(filters: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        filters.iter().find(|(filter, _)| filter == name).map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn test_describe_filters() {
+        let config = parse(&["--depth", "3", "--ext", "rs,py", "--include-fixtures", "--exclude-generated", "--min-lines", "5"]);
+        let effective = HowManyConfig {
+            custom_ignore_patterns: vec!["*.log".to_string()],
+            ..HowManyConfig::default()
+        };
+        let filters = describe_filters(&config, &effective);
+
+        assert_eq!(filters[0], ("ignore files".to_string(), ".gitignore, .howmanyignore".to_string()));
+        assert_eq!(filter(&filters, "hidden paths"), Some("skipped"));
+        assert_eq!(filter(&filters, "max depth"), Some("3"));
+        assert_eq!(filter(&filters, "ignore"), Some("*.log"));
+        assert_eq!(filter(&filters, "include"), None);
+        assert_eq!(filter(&filters, "extensions"), Some("rs, py"));
+        assert_eq!(filter(&filters, "max file size"), Some(format::format_size(super::super::DEFAULT_MAX_FILE_SIZE).as_str()));
+        assert_eq!(filter(&filters, "test fixtures"), Some("counted"));
+        assert_eq!(filter(&filters, "generated code"), Some("skipped"));
+        assert_eq!(filter(&filters, "minified files"), Some("reported separately"));
+        // Result filters come after the walk's rules
+        assert_eq!(filters.last().unwrap(), &("min lines".to_string(), "5".to_string()));
+
+        let filters = describe_filters(&parse(&["--max-file-size", "0"]), &HowManyConfig::default());
+        assert_eq!(filter(&filters, "max file size"), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let config = parse(&["-o", "json,sarif", "--depth", "2"]);
+        let report = DryRunReport::new(&config, &[PathBuf::from("src")], HowManyConfig::default(), "built-in defaults".to_string(), 1234);
+
+        let text = report.render_text().unwrap();
+        assert!(text.starts_with("Dry run of src: nothing is analyzed\n\n# Effective configuration from built-in defaults\n"));
+        assert!(text.contains("\nFilters:\n  ignore files    .gitignore, .howmanyignore\n"));
+        assert!(text.contains("\nOutput: json, sarif\n"));
+        assert!(text.ends_with(&format!("Estimated files: {}\n", format::format_number(1234))));
+
+        // JSON lists the filters by name
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["filters"]["max depth"], "2");
+        assert_eq!(json["estimated_files"], 1234);
+        assert_eq!(json["roots"], serde_json::json!(["src"]));
+    }
+}
@@ -1,198 +1,498 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
+use clap_complete::Shell;
+use crate::utils::shard::Shard;
 use std::path::PathBuf;
 
+pub mod dry_run;
+pub mod summary;
+
+/// Size over which files are skipped and reported when `--max-file-size` isn't given
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+
 #[derive(Parser)]
 #[command(name = "howmany")]
 #[command(about = "Count files and lines of code in your projects")]
 #[command(version = "2.0.0")]
 pub struct Config {
-    /// Directory to analyze (defaults to current directory)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    
+    /// Directories to analyze, merged into one report (defaults to current directory)
     #[arg(value_name = "PATH")]
-    pub path: Option<PathBuf>,
+    pub paths: Vec<PathBuf>,
+    
+    /// Git pathspecs after `--` (e.g. `-- ':!vendor' ':(glob)src/**/*.rs'`), matched by git inside each path
+    #[arg(last = true, value_name = "PATHSPEC")]
+    pub pathspecs: Vec<String>,
+    
+    /// Clone and analyze a git repository (URL[@REF]); the clone is removed afterwards
+    #[arg(long = "repo", value_name = "URL[@REF]", global = true)]
+    pub repo: Option<String>,
+    
+    /// Also show totals for each analyzed path
+    #[arg(long = "per-path", global = true)]
+    pub per_path: bool,
+    
+    /// Vendored crate checkouts (Cargo.toml plus .git) below a path: merge into the totals, or report them separately
+    #[arg(long = "nested-projects", value_name = "MODE", default_value = "merge", global = true)]
+    pub nested_projects: NestedProjects,
+    
+    /// Attribute lines to authors and teams with git blame (slow on large repositories)
+    #[arg(long = "owners", global = true)]
+    pub owners: bool,
+    
+    /// Mine the last N commits (default 500) for change frequency and rank hotspots by churn × complexity
+    #[arg(long = "churn", value_name = "N", num_args = 0..=1, default_missing_value = "500", global = true)]
+    pub churn: Option<usize>,
+    
+    /// Count the dependencies declared in Cargo.toml, package.json, go.mod, requirements.txt, pyproject.toml and pom.xml
+    #[arg(long = "dependencies", global = true)]
+    pub dependencies: bool,
     
-    /// Output format: text, json, csv, html, or sarif
-    #[arg(short = 'o', long = "output", default_value = "text")]
-    pub format: OutputFormat,
+    /// Check file headers for SPDX identifiers and license boilerplate, and list files without one
+    #[arg(long = "licenses", global = true)]
+    pub licenses: bool,
+    
+    /// Checkpoint the analysis so an interrupted run over the same paths continues where it stopped
+    #[arg(long = "resume", conflicts_with = "repo", global = true)]
+    pub resume: bool,
+    
+    /// Only analyze part K of N of the files (e.g. 2/8), for CI jobs whose JSON reports `howmany merge` combines
+    #[arg(long = "shard", value_name = "K/N", global = true)]
+    pub shard: Option<Shard>,
+    
+    /// Output format: text, json, json-tree, csv, html, sarif, prometheus, yaml, toml, msgpack, cloc-json, or tokei (comma-separated for several, e.g. text,html; only html and sarif combine with others)
+    #[arg(short = 'o', long = "output", default_value = "text", value_delimiter = ',', global = true)]
+    pub formats: Vec<OutputFormat>,
+    
+    /// Report every file extension on its own row (`ts` apart from `tsx`) instead of grouping extensions by language
+    #[arg(long = "by-extension", global = true)]
+    pub by_extension: bool,
+    
+    /// Count every file (or history sample) instead of reusing cached results, and leave the cache untouched
+    #[arg(long = "no-cache", global = true)]
+    pub no_cache: bool,
+    
+    /// Custom template for HTML reports (may extend the built-in "comprehensive.html")
+    #[arg(long = "html-template", value_name = "FILE", global = true)]
+    pub html_template: Option<PathBuf>,
+    
+    /// Base URL where the HTML report is published, linked from the CI summary footer
+    #[arg(long = "report-url", value_name = "URL", global = true)]
+    pub report_url: Option<String>,
     
     /// Show individual file statistics
-    #[arg(short = 'f', long = "files")]
+    #[arg(short = 'f', long = "files", global = true)]
     pub show_files: bool,
     
+    /// Write one CSV row per file instead of one per language
+    #[arg(long = "csv-per-file", global = true)]
+    pub csv_per_file: bool,
+    
+    /// Field delimiter of CSV output, e.g. ';' for spreadsheets that use decimal commas
+    #[arg(long = "csv-delimiter", value_name = "CHAR", default_value_t = ',', global = true)]
+    pub csv_delimiter: char,
+    
     /// Simple CLI mode - show only basic file and line counts
-    #[arg(long = "cli")]
+    #[arg(long = "cli", global = true)]
     pub cli_mode: bool,
     
     /// Disable interactive mode (interactive mode is enabled by default)
-    #[arg(long = "no-interactive")]
+    #[arg(long = "no-interactive", global = true)]
     pub no_interactive: bool,
     
     /// Show detailed breakdown by file extension
-    #[arg(short = 'v', long = "verbose")]
+    #[arg(short = 'v', long = "verbose", global = true)]
     pub verbose: bool,
     
+    /// Print a warning for every file that could not be read instead of one summary by cause
+    #[arg(long = "verbose-warnings", global = true)]
+    pub verbose_warnings: bool,
+    
     /// Maximum directory depth to traverse
-    #[arg(short = 'd', long = "depth")]
+    #[arg(short = 'd', long = "depth", global = true)]
     pub max_depth: Option<usize>,
     
     /// Only count specific file extensions (comma-separated: rs,py,js)
-    #[arg(short = 'e', long = "ext")]
+    #[arg(short = 'e', long = "ext", global = true)]
     pub extensions: Option<String>,
     
     /// Include hidden files and directories
-    #[arg(long = "hidden")]
+    #[arg(long = "hidden", global = true)]
     pub include_hidden: bool,
     
+    /// Count snapshot and golden-output test fixtures as regular code
+    #[arg(long = "include-fixtures", global = true)]
+    pub include_fixtures: bool,
+    
+    /// Count generated sources (protobuf stubs, `@generated` or `DO NOT EDIT` files) as regular code
+    #[arg(long = "include-generated", global = true, conflicts_with = "exclude_generated")]
+    pub include_generated: bool,
+    
+    /// Skip generated sources entirely instead of reporting them separately
+    #[arg(long = "exclude-generated", global = true)]
+    pub exclude_generated: bool,
+    
+    /// Count minified scripts and stylesheets (`*.min.js`, bundles with very long lines) as regular code
+    #[arg(long = "include-minified", global = true)]
+    pub include_minified: bool,
+    
+    /// Split every count into production and test code (tests/, *_test.go, *.spec.ts, Rust #[cfg(test)] items)
+    #[arg(long = "split-tests", global = true)]
+    pub split_tests: bool,
+    
+    /// Decompress single-file gzip sources (e.g. dump.sql.gz) and count their lines
+    #[arg(long = "decompress", global = true)]
+    pub decompress: bool,
+    
+    /// Skip files larger than this and list them as skipped, also limits decompressed .gz contents (e.g. 10MB; default 50MB, 0 for no limit)
+    #[arg(long = "max-file-size", global = true)]
+    pub max_file_size: Option<String>,
+    
     /// Sort results by: files, lines, code, comments, size, complexity, quality, functions
-    #[arg(short = 's', long = "sort", default_value = "files")]
+    #[arg(short = 's', long = "sort", default_value = "files", global = true)]
     pub sort_by: SortBy,
     
     /// Sort in descending order
-    #[arg(long = "desc")]
+    #[arg(long = "desc", global = true)]
     pub descending: bool,
     
-    /// Additional patterns to ignore (comma-separated: node_modules,target,dist)
-    #[arg(long = "ignore")]
+    /// Additional globs to ignore, `!` re-includes (comma-separated: node_modules,**/*.gen.rs,!keep.gen.rs)
+    #[arg(long = "ignore", global = true)]
     pub ignore_patterns: Option<String>,
     
+    /// Extra ignore file with gitignore syntax, in addition to .gitignore and .howmanyignore (repeatable)
+    #[arg(long = "ignore-file", value_name = "PATH", global = true)]
+    pub ignore_files: Vec<PathBuf>,
+    
+    /// Only analyze paths matching these globs, `!` excludes (comma-separated: src/**,crates/*/src/**)
+    #[arg(long = "include", visible_alias = "include-pattern", global = true)]
+    pub include_patterns: Option<String>,
+    
     /// List files that would be counted (useful for debugging)
-    #[arg(short = 'l', long = "list")]
+    #[arg(short = 'l', long = "list", global = true)]
     pub list_files: bool,
     
     // Filter options
     /// Minimum lines per file to include
-    #[arg(long = "min-lines")]
+    #[arg(long = "min-lines", global = true)]
     pub min_lines: Option<usize>,
     
     /// Maximum lines per file to include
-    #[arg(long = "max-lines")]
+    #[arg(long = "max-lines", global = true)]
     pub max_lines: Option<usize>,
     
     /// Minimum file size to include (e.g., 1KB, 500MB)
-    #[arg(long = "min-size")]
+    #[arg(long = "min-size", global = true)]
     pub min_size: Option<String>,
     
     /// Maximum file size to include (e.g., 1KB, 500MB)
-    #[arg(long = "max-size")]
+    #[arg(long = "max-size", global = true)]
     pub max_size: Option<String>,
     
-    /// Include only these languages (comma-separated: rs,py,js)
-    #[arg(long = "only")]
+    /// Include only these languages (comma-separated extensions or names: rs,py,TypeScript)
+    #[arg(long = "only", global = true)]
     pub only_languages: Option<String>,
     
-    /// Exclude these languages (comma-separated: rs,py,js)
-    #[arg(long = "exclude")]
+    /// Exclude these languages (comma-separated extensions or names: rs,py,TypeScript)
+    #[arg(long = "exclude", global = true)]
     pub exclude_languages: Option<String>,
     
     // Enhanced CLI output options
     /// Show complexity information in CLI mode
-    #[arg(long = "show-complexity")]
+    #[arg(long = "show-complexity", global = true)]
     pub show_complexity: bool,
     
     /// Show quality scores in CLI mode
-    #[arg(long = "show-quality")]
+    #[arg(long = "show-quality", global = true)]
     pub show_quality: bool,
     
     /// Show code ratios in CLI mode
-    #[arg(long = "show-ratios")]
+    #[arg(long = "show-ratios", global = true)]
     pub show_ratios: bool,
     
     /// Show size information in CLI mode
-    #[arg(long = "show-size")]
+    #[arg(long = "show-size", global = true)]
     pub show_size: bool,
     
     // Advanced filtering options
     /// Minimum complexity score to include (0.0-100.0)
-    #[arg(long = "min-complexity")]
+    #[arg(long = "min-complexity", global = true)]
     pub min_complexity: Option<f64>,
     
     /// Maximum complexity score to include (0.0-100.0)
-    #[arg(long = "max-complexity")]
+    #[arg(long = "max-complexity", global = true)]
     pub max_complexity: Option<f64>,
     
     /// Minimum functions per file to include
-    #[arg(long = "min-functions")]
+    #[arg(long = "min-functions", global = true)]
     pub min_functions: Option<usize>,
     
     /// Maximum functions per file to include
-    #[arg(long = "max-functions")]
+    #[arg(long = "max-functions", global = true)]
     pub max_functions: Option<usize>,
     
     /// Minimum quality score to include (0-100)
-    #[arg(long = "min-quality")]
+    #[arg(long = "min-quality", global = true)]
     pub min_quality_score: Option<f64>,
     
     /// Maximum quality score to include (0-100)
-    #[arg(long = "max-quality")]
+    #[arg(long = "max-quality", global = true)]
     pub max_quality_score: Option<f64>,
     
     /// Minimum documentation ratio to include (0.0-1.0)
-    #[arg(long = "min-doc-ratio")]
+    #[arg(long = "min-doc-ratio", global = true)]
     pub min_doc_ratio: Option<f64>,
     
     /// Maximum documentation ratio to include (0.0-1.0)
-    #[arg(long = "max-doc-ratio")]
+    #[arg(long = "max-doc-ratio", global = true)]
     pub max_doc_ratio: Option<f64>,
     
     // Advanced filter shortcuts
     /// Only show files with high complexity (complexity > 10)
-    #[arg(long = "high-complexity")]
+    #[arg(long = "high-complexity", global = true)]
     pub high_complexity_only: bool,
     
     /// Only show files with low quality scores (quality < 60)
-    #[arg(long = "low-quality")]
+    #[arg(long = "low-quality", global = true)]
     pub low_quality_only: bool,
     
     /// Only show files with poor documentation (doc ratio < 0.1)
-    #[arg(long = "undocumented")]
+    #[arg(long = "undocumented", global = true)]
     pub undocumented_only: bool,
     
     // Output enhancement options
     /// Show time estimates in CLI mode
-    #[arg(long = "show-time")]
+    #[arg(long = "show-time", global = true)]
     pub show_time_estimates: bool,
     
     /// Compact output mode
-    #[arg(long = "compact")]
+    #[arg(long = "compact", global = true)]
     pub compact_output: bool,
     
     /// Show only summary (no per-extension breakdown)
-    #[arg(long = "summary-only")]
+    #[arg(long = "summary-only", global = true)]
     pub summary_only: bool,
     
     /// Show top N results only
-    #[arg(long = "top")]
+    #[arg(long = "top", global = true)]
     pub top_n: Option<usize>,
     
     /// Show file-level complexity details
-    #[arg(long = "show-functions")]
+    #[arg(long = "show-functions", global = true)]
     pub show_function_details: bool,
     
+    /// List the N most complex functions with their file, line, complexity, length and parameters
+    #[arg(long = "top-functions", value_name = "N", global = true)]
+    pub top_functions: Option<usize>,
+    
+    /// List the N files with the lowest quality score, to refactor first
+    #[arg(long = "worst", value_name = "N", global = true)]
+    pub worst: Option<usize>,
+    
     // Format options
     /// Disable colors in output
-    #[arg(long = "no-color")]
+    #[arg(long = "no-color", global = true)]
     pub no_color: bool,
     
     /// Output preset (compact, detailed, minimal)
-    #[arg(long = "preset")]
+    #[arg(long = "preset", global = true)]
     pub output_preset: Option<String>,
     
     // Developer experience
     /// Quiet mode - minimal output
-    #[arg(short = 'q', long = "quiet")]
+    #[arg(short = 'q', long = "quiet", global = true)]
     pub quiet: bool,
     
-    /// Explain why files were included/excluded
-    #[arg(long = "explain")]
-    pub explain_filtering: bool,
+    /// Fast JSON for editor integrations - per-file line counts only, no complexity or quality analysis
+    #[arg(long = "fast-json", global = true)]
+    pub fast_json: bool,
+    
+    /// Print the classification (code, comment, doc or blank) of every line of FILE
+    #[arg(long = "classify-file", value_name = "FILE", global = true)]
+    pub classify_file: Option<PathBuf>,
+    
+    /// Print the effective configuration (defaults, config file and command-line options) as TOML instead of analyzing
+    #[arg(long = "print-config", global = true)]
+    pub print_config: bool,
+    
+    /// Print the resolved configuration, the filters that would apply and an estimate of the files to count, without analyzing
+    #[arg(long = "dry-run", global = true)]
+    pub dry_run: bool,
+    
+    /// Explain why files are counted or excluded: a table of exclusion reasons, or the verdict on one PATH
+    #[arg(long = "explain", value_name = "PATH", num_args = 0..=1, global = true)]
+    pub explain: Option<Option<PathBuf>>,
+    
+    /// Print the JSON Schema of the `--output json` report and exit
+    #[arg(long = "schema", global = true)]
+    pub schema: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Count and analyze the files below PATHs and print the results, without the interactive display
+    Count(TargetArgs),
+    /// Analyze the files below PATHs and write the HTML report, or the reports chosen with --output
+    Report(TargetArgs),
+    /// Fail when a changed file crosses a complexity or size limit it satisfied at a base revision
+    #[command(alias = "gate")]
+    Diff(GateArgs),
+    /// Chart how lines of code, languages and quality grew over past commits
+    History(HistoryArgs),
+    /// Combine the JSON reports of separate runs, such as sharded CI jobs, into one report
+    Merge(MergeArgs),
+    /// Inspect the configuration, or write a commented one to start from
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Check the configuration, ignore patterns and caches of a project, and the options given with it
+    Doctor(DoctorArgs),
+    /// Inspect or clear the cache of file counts, history samples and run checkpoints
+    #[command(subcommand)]
+    Cache(CacheCommand),
+    /// Print the completion script of a shell (bash, zsh, fish, powershell or elvish)
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Print the man page in roff format
+    Man,
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Delete every cache howmany keeps
+    Clear,
+    /// Show how many files are cached and how much space the caches take
+    Stats,
+    /// Print the cache directory
+    Path,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective configuration of a project as TOML: the built-in defaults merged with its config file
+    Show {
+        /// Project directory whose `.howmany.toml` is read
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+    },
+    /// Write a commented `.howmany.toml` with the default ignore patterns and examples of every section
+    Init {
+        /// Project directory the file is written to
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+        
+        /// Replace an existing `.howmany.toml`
+        #[arg(long = "force")]
+        force: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Project directory whose configuration and tree are checked
+    #[arg(value_name = "PATH", default_value = ".")]
+    pub path: PathBuf,
+}
+
+#[derive(Args)]
+pub struct HistoryArgs {
+    /// Repository, or a directory inside one, whose history is read
+    #[arg(value_name = "PATH", default_value = ".")]
+    pub path: PathBuf,
+    
+    /// Number of commits (or tags) to sample, spread evenly from the first to the latest
+    #[arg(long = "samples", value_name = "N", default_value_t = crate::core::history::DEFAULT_HISTORY_SAMPLES)]
+    pub samples: usize,
+    
+    /// Sample release tags instead of commits
+    #[arg(long = "tags")]
+    pub tags: bool,
+    
+    /// Revision whose first-parent history is sampled
+    #[arg(long = "rev", value_name = "REV", default_value = "HEAD", conflicts_with = "tags")]
+    pub revision: String,
+    
 }
 
-#[derive(Clone)]
+/// What `count` and `report` analyze, as a bare `howmany` takes it
+#[derive(Args)]
+pub struct TargetArgs {
+    /// Directories to analyze, merged into one report (defaults to current directory)
+    #[arg(value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+    
+    /// Git pathspecs after `--`, matched by git inside each path
+    #[arg(last = true, value_name = "PATHSPEC")]
+    pub pathspecs: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct GateArgs {
+    /// Directory inside a git repository whose changes are gated
+    #[arg(value_name = "PATH", default_value = ".")]
+    pub path: PathBuf,
+    
+    /// Revision the changes are compared with, usually the pull request's target branch
+    #[arg(long = "base", value_name = "REV")]
+    pub base: String,
+}
+
+#[derive(Args)]
+pub struct MergeArgs {
+    /// Reports written by `howmany --output json`
+    #[arg(value_name = "REPORT", required = true)]
+    pub reports: Vec<PathBuf>,
+}
+
+#[derive(Clone, PartialEq)]
 pub enum OutputFormat {
     Text,
     Json,
+    /// Nested directory tree with the totals of each directory, for treemaps and sunbursts
+    JsonTree,
     Csv,
     Html,
     Sarif,
+    Prometheus,
+    /// The `json` report as YAML
+    Yaml,
+    /// The `json` report as TOML
+    Toml,
+    /// The `json` report as MessagePack, a compact binary encoding
+    Msgpack,
+    /// The JSON document of `cloc --json`
+    ClocJson,
+    /// The terminal table of tokei
+    Tokei,
+}
+
+impl OutputFormat {
+    /// Whether the format is written to a report file instead of standard output
+    pub fn writes_file(&self) -> bool {
+        matches!(self, OutputFormat::Html | OutputFormat::Sarif)
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::JsonTree => "json-tree",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Html => "html",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::Prometheus => "prometheus",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Toml => "toml",
+            OutputFormat::Msgpack => "msgpack",
+            OutputFormat::ClocJson => "cloc-json",
+            OutputFormat::Tokei => "tokei",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -202,14 +502,39 @@ impl std::str::FromStr for OutputFormat {
         match s.to_lowercase().as_str() {
             "text" | "txt" => Ok(OutputFormat::Text),
             "json" => Ok(OutputFormat::Json),
+            "json-tree" => Ok(OutputFormat::JsonTree),
             "csv" => Ok(OutputFormat::Csv),
             "html" => Ok(OutputFormat::Html),
             "sarif" => Ok(OutputFormat::Sarif),
+            "prometheus" | "prom" => Ok(OutputFormat::Prometheus),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "toml" => Ok(OutputFormat::Toml),
+            "msgpack" | "messagepack" => Ok(OutputFormat::Msgpack),
+            "cloc-json" | "cloc" => Ok(OutputFormat::ClocJson),
+            "tokei" => Ok(OutputFormat::Tokei),
             _ => Err(format!("Invalid output format: {}", s)),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NestedProjects {
+    Merge,
+    Separate,
+}
+
+impl std::str::FromStr for NestedProjects {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "merge" => Ok(NestedProjects::Merge),
+            "separate" => Ok(NestedProjects::Separate),
+            _ => Err(format!("Invalid nested projects mode: {} (expected merge or separate)", s)),
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum SortBy {
     Files,
@@ -243,8 +568,37 @@ impl std::str::FromStr for SortBy {
 }
 
 impl Config {
+    /// Parse the command line. Usage errors exit with the status of a config
+    /// error, as clap's own status 2 is a threshold failure to howmany.
     pub fn parse_args() -> Self {
-        Self::parse()
+        Self::try_parse().unwrap_or_else(|e| {
+            if e.use_stderr() {
+                let _ = e.print();
+                std::process::exit(crate::utils::errors::ErrorCategory::Config.exit_code());
+            }
+            e.exit()
+        })
+    }
+    
+    /// Fold `count` and `report` into the options of a bare invocation. Both
+    /// skip the interactive display, and `report` writes the HTML report
+    /// unless `--output` asks for other formats.
+    pub fn apply_target_command(&mut self) {
+        let (target, report) = match self.command.take() {
+            Some(Command::Count(target)) => (target, false),
+            Some(Command::Report(target)) => (target, true),
+            command => {
+                self.command = command;
+                return;
+            }
+        };
+        
+        self.paths.extend(target.paths);
+        self.pathspecs.extend(target.pathspecs);
+        self.no_interactive = true;
+        if report && self.formats == [OutputFormat::Text] {
+            self.formats = vec![OutputFormat::Html];
+        }
     }
     
     /// Check if interactive mode should be enabled (default true, unless --no-interactive is passed)
@@ -252,6 +606,31 @@ impl Config {
         !self.no_interactive
     }
     
+    /// Paths to analyze, defaulting to the current directory
+    pub fn roots(&self) -> Vec<PathBuf> {
+        if self.paths.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            self.paths.clone()
+        }
+    }
+    
+    /// The first requested output format, which drives progress messages and list mode
+    pub fn primary_format(&self) -> &OutputFormat {
+        self.formats.first().unwrap_or(&OutputFormat::Text)
+    }
+    
+    /// Check whether an output format was requested
+    pub fn has_format(&self, format: &OutputFormat) -> bool {
+        self.formats.contains(format)
+    }
+    
+    /// Whether standard output carries a machine-readable document, which
+    /// notes about written report files would corrupt
+    pub fn stdout_is_document(&self) -> bool {
+        self.formats.iter().any(|format| !format.writes_file() && format != &OutputFormat::Text)
+    }
+    
     /// Convert comma-separated extensions string to Vec
     pub fn get_extensions(&self) -> Vec<String> {
         self.extensions
@@ -260,6 +639,15 @@ impl Config {
             .unwrap_or_default()
     }
     
+    /// Parse `--max-file-size` into bytes, `DEFAULT_MAX_FILE_SIZE` when it isn't
+    /// given and `None` when it is 0 (no limit)
+    pub fn get_max_file_size(&self) -> Option<u64> {
+        match &self.max_file_size {
+            Some(size) => crate::ui::filters::FilterParser::parse_size(size).filter(|size| *size > 0),
+            None => Some(DEFAULT_MAX_FILE_SIZE),
+        }
+    }
+    
     /// Convert comma-separated ignore patterns string to Vec
     pub fn get_ignore_patterns(&self) -> Vec<String> {
         self.ignore_patterns
@@ -268,6 +656,149 @@ impl Config {
             .unwrap_or_default()
     }
     
+    /// Convert comma-separated include patterns string to Vec
+    pub fn get_include_patterns(&self) -> Vec<String> {
+        self.include_patterns
+            .as_ref()
+            .map(|s| s.split(',').map(|pattern| pattern.trim().to_string()).filter(|p| !p.is_empty()).collect())
+            .unwrap_or_default()
+    }
+    
+    /// Ignore patterns from the config file followed by the ones given on the CLI
+    pub fn resolve_ignore_patterns(&self, file_config: &crate::utils::config::HowManyConfig) -> Vec<String> {
+        file_config.custom_ignore_patterns
+            .iter()
+            .cloned()
+            .chain(self.get_ignore_patterns())
+            .collect()
+    }
+    
+    /// Include patterns from the CLI, falling back to the config file when none were given
+    pub fn resolve_include_patterns(&self, file_config: &crate::utils::config::HowManyConfig) -> Vec<String> {
+        let cli_patterns = self.get_include_patterns();
+        if cli_patterns.is_empty() {
+            file_config.custom_include_patterns.clone()
+        } else {
+            cli_patterns
+        }
+    }
+    
+    /// Reject contradictory or malformed option combinations, listing every problem at once
+    pub fn validate(&self) -> crate::utils::errors::Result<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::utils::errors::HowManyError::invalid_config(format!(
+                "conflicting or invalid options:\n  - {}",
+                problems.join("\n  - ")
+            )))
+        }
+    }
+    
+    /// Contradictory or malformed option combinations, one sentence each
+    pub fn problems(&self) -> Vec<String> {
+        use crate::ui::filters::FilterParser;
+        
+        let mut problems = Vec::new();
+        
+        fn check_range<T: PartialOrd + std::fmt::Display>(problems: &mut Vec<String>, name: &str, min: Option<T>, max: Option<T>) {
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    problems.push(format!("--min-{name} ({min}) is greater than --max-{name} ({max})"));
+                }
+            }
+        }
+        
+        check_range(&mut problems, "lines", self.min_lines, self.max_lines);
+        check_range(&mut problems, "complexity", self.min_complexity, self.max_complexity);
+        check_range(&mut problems, "functions", self.min_functions, self.max_functions);
+        check_range(&mut problems, "quality", self.min_quality_score, self.max_quality_score);
+        check_range(&mut problems, "doc-ratio", self.min_doc_ratio, self.max_doc_ratio);
+        
+        let mut parse_size = |flag: &str, value: &Option<String>| match value {
+            Some(value) => match FilterParser::parse_size(value) {
+                Some(size) => Some(size),
+                None => {
+                    problems.push(format!("{flag} '{value}' is not a size (e.g. 500B, 10KB, 2MB)"));
+                    None
+                }
+            },
+            None => None,
+        };
+        let min_size = parse_size("--min-size", &self.min_size);
+        let max_size = parse_size("--max-size", &self.max_size);
+        parse_size("--max-file-size", &self.max_file_size);
+        check_range(&mut problems, "size", min_size, max_size);
+        
+        for (flag, value) in [("--min-doc-ratio", self.min_doc_ratio), ("--max-doc-ratio", self.max_doc_ratio)] {
+            if let Some(value) = value.filter(|value| !(0.0..=1.0).contains(value)) {
+                problems.push(format!("{flag} ({value}) must be between 0.0 and 1.0"));
+            }
+        }
+        for (flag, value) in [("--min-quality", self.min_quality_score), ("--max-quality", self.max_quality_score)] {
+            if let Some(value) = value.filter(|value| !(0.0..=100.0).contains(value)) {
+                problems.push(format!("{flag} ({value}) must be between 0 and 100"));
+            }
+        }
+        
+        if let Some(Command::History(history)) = &self.command {
+            if history.samples == 0 {
+                problems.push("history --samples needs at least one commit".to_string());
+            }
+            if self.formats.iter().any(|format| !matches!(format, OutputFormat::Text | OutputFormat::Json | OutputFormat::Html)) {
+                problems.push("history only supports text, json and html output".to_string());
+            }
+        }
+        if self.top_functions == Some(0) {
+            problems.push("--top-functions needs at least one function".to_string());
+        }
+        if self.worst == Some(0) {
+            problems.push("--worst needs at least one file".to_string());
+        }
+        if self.classify_file.is_some() && self.formats.iter().any(|format| !matches!(format, OutputFormat::Text | OutputFormat::Json)) {
+            problems.push("--classify-file only supports text and json output".to_string());
+        }
+        if matches!(self.command, Some(Command::Doctor(_))) && self.formats.iter().any(|format| !matches!(format, OutputFormat::Text | OutputFormat::Json)) {
+            problems.push("doctor only supports text and json output".to_string());
+        }
+        if (self.csv_per_file || self.csv_delimiter != ',') && !self.has_format(&OutputFormat::Csv) {
+            problems.push("--csv-per-file and --csv-delimiter only apply to csv output".to_string());
+        }
+        if !self.csv_delimiter.is_ascii() || matches!(self.csv_delimiter, '"' | '\n' | '\r') {
+            problems.push(format!("--csv-delimiter {:?} must be a single ASCII character other than a quote or line break", self.csv_delimiter));
+        }
+        let stdout_formats: Vec<String> = self.formats.iter().filter(|format| !format.writes_file()).map(OutputFormat::to_string).collect();
+        if stdout_formats.len() > 1 {
+            problems.push(format!("{} all write to standard output; pick one of them (html and sarif write files and combine with any)", stdout_formats.join(", ")));
+        }
+        if self.has_format(&OutputFormat::Msgpack) && self.formats.len() > 1 {
+            problems.push("msgpack output is binary and can't be combined with other formats".to_string());
+        }
+        if self.explain.is_some() && self.formats.iter().any(|format| !matches!(format, OutputFormat::Text | OutputFormat::Json)) {
+            problems.push("--explain only supports text and json output".to_string());
+        }
+        if self.repo.is_some() && !self.paths.is_empty() {
+            problems.push("--repo analyzes a clone of the repository, so it takes no paths".to_string());
+        }
+        if self.churn == Some(0) {
+            problems.push("--churn needs at least one commit".to_string());
+        }
+        if self.quiet && self.verbose {
+            problems.push("--quiet and --verbose cannot be used together".to_string());
+        }
+        if self.summary_only && self.show_files {
+            problems.push("--summary-only hides the per-file listing requested by --files".to_string());
+        }
+        if let Some(preset) = &self.output_preset {
+            if !matches!(preset.to_lowercase().as_str(), "compact" | "detailed" | "minimal") {
+                problems.push(format!("Unknown preset '{preset}' (expected compact, detailed or minimal)"));
+            }
+        }
+        
+        problems
+    }
+    
     /// Apply advanced filter shortcuts to set specific filter values
     pub fn apply_advanced_filter_shortcuts(&mut self) {
         if self.high_complexity_only {
@@ -344,4 +875,31 @@ impl Config {
             compact_output: self.compact_output,
         }
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn parse(args: &[&str]) -> Config {
+        Config::try_parse_from(std::iter::once("howmany").chain(args.iter().copied())).unwrap()
+    }
+    
+    #[test]
+    fn test_one_format_on_stdout() {
+        for formats in ["text,html", "json,sarif", "csv,html,sarif", "json"] {
+            assert!(parse(&["-o", formats]).validate().is_ok(), "{formats}");
+        }
+        for formats in ["json,csv", "text,json", "json,json"] {
+            let problems = parse(&["-o", formats]).problems();
+            assert_eq!(problems.len(), 1, "{formats}");
+            assert!(problems[0].contains("write to standard output"), "{formats}");
+        }
+    }
+    
+    #[test]
+    fn test_report_notes_stay_off_documents() {
+        assert!(!parse(&["-o", "text,html"]).stdout_is_document());
+        assert!(!parse(&["-o", "html,sarif"]).stdout_is_document());
+        assert!(parse(&["-o", "json,sarif"]).stdout_is_document());
+    }
+}
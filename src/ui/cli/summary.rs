@@ -0,0 +1,239 @@
+use crate::core::stats::AggregatedStats;
+use crate::core::types::FileStats;
+use std::cmp::Reverse;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Number of findings listed in the CI summary footer
+pub const CI_SUMMARY_FINDINGS: usize = 3;
+
+/// Environment variable used when `--report-url` is not given
+pub const REPORT_URL_ENV: &str = "HOWMANY_REPORT_URL";
+
+/// Files above this many lines are reported as oversized (same threshold as SARIF rule HM001)
+const LARGE_FILE_LINES: usize = 1000;
+
+/// Detect a CI environment via the `CI` variable set by GitHub Actions, GitLab CI, CircleCI, etc.
+pub fn is_ci_environment() -> bool {
+    env::var("CI")
+        .map(|value| !value.is_empty() && value != "false" && value != "0")
+        .unwrap_or(false)
+}
+
+/// Base URL for published reports, from the CLI or the `HOWMANY_REPORT_URL` environment variable
+pub fn resolve_report_base_url(cli_url: Option<&str>) -> Option<String> {
+    cli_url
+        .map(str::to_string)
+        .or_else(|| env::var(REPORT_URL_ENV).ok())
+        .filter(|url| !url.trim().is_empty())
+}
+
+/// Link to a report artifact: a URL under `base_url` if given, otherwise its absolute path
+pub fn report_location(report_path: &Path, base_url: Option<&str>) -> String {
+    match base_url {
+        Some(base) => {
+            let file_name = report_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            format!("{}/{}", base.trim_end_matches('/'), file_name)
+        }
+        None => fs::canonicalize(report_path)
+            .unwrap_or_else(|_| report_path.to_path_buf())
+            .display()
+            .to_string(),
+    }
+}
+
+/// Pick the most important findings, taking the worst of each kind before repeating a kind
+pub fn top_findings(
+    aggregated_stats: &AggregatedStats,
+    individual_files: &[(String, FileStats)],
+    limit: usize,
+) -> Vec<String> {
+    // Functions can also be collected for nesting or length, only rank the ones over the complexity limit
+    let max_complexity = aggregated_stats.complexity.function_thresholds.max_cyclomatic_complexity;
+    let mut complex_functions: Vec<_> = aggregated_stats.complexity.complex_functions
+        .iter()
+        .filter(|f| f.cyclomatic_complexity > max_complexity)
+        .collect();
+    complex_functions.sort_by_key(|f| Reverse(f.cyclomatic_complexity));
+    let complex_functions: Vec<String> = complex_functions
+        .into_iter()
+        .map(|f| format!("{}:{} `{}` has cyclomatic complexity {}", f.file_path, f.start_line, f.name, f.cyclomatic_complexity))
+        .collect();
+
+    let mut large_files: Vec<_> = individual_files
+        .iter()
+        .filter(|(_, stats)| stats.total_lines > LARGE_FILE_LINES)
+        .collect();
+    large_files.sort_by_key(|(_, stats)| Reverse(stats.total_lines));
+    let large_files: Vec<String> = large_files
+        .into_iter()
+        .map(|(path, stats)| format!("{} has {} lines", path, stats.total_lines))
+        .collect();
+
+    let mut long_parameter_lists: Vec<_> = aggregated_stats.complexity.long_parameter_functions.iter().collect();
+    long_parameter_lists.sort_by_key(|f| Reverse(f.parameter_count));
+    let long_parameter_lists: Vec<String> = long_parameter_lists
+        .into_iter()
+        .map(|f| format!("{}:{} `{}` takes {} parameters", f.file_path, f.start_line, f.name, f.parameter_count))
+        .collect();
+
+    let mut groups = [
+        complex_functions.into_iter(),
+        large_files.into_iter(),
+        long_parameter_lists.into_iter(),
+    ];
+
+    let mut findings = Vec::new();
+    while findings.len() < limit {
+        let before = findings.len();
+        for group in groups.iter_mut() {
+            if findings.len() == limit {
+                break;
+            }
+            if let Some(finding) = group.next() {
+                findings.push(finding);
+            }
+        }
+        if findings.len() == before {
+            break;
+        }
+    }
+
+    findings
+}
+
+/// Render the short footer printed at the end of CI logs
+pub fn render_ci_footer(aggregated_stats: &AggregatedStats, report_link: &str, findings: &[String]) -> String {
+    let mut footer = String::new();
+    footer.push_str("\n=== HowMany Summary ===\n");
+    footer.push_str(&format!(
+        "{} files, {} lines of code, code health {:.1}/100\n",
+        aggregated_stats.basic.total_files,
+        aggregated_stats.basic.code_lines,
+        aggregated_stats.complexity.quality_metrics.code_health_score
+    ));
+    footer.push_str(&format!("Full report: {}\n", report_link));
+
+    if findings.is_empty() {
+        footer.push_str("No notable findings\n");
+    } else {
+        footer.push_str("Top findings:\n");
+        for (index, finding) in findings.iter().enumerate() {
+            footer.push_str(&format!("  {}. {}\n", index + 1, finding));
+        }
+    }
+
+    footer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::counter::CodeCounter;
+    use crate::core::stats::complexity::{ComplexityLevel, FunctionComplexityDetail, LongParameterListFinding};
+    use crate::core::stats::StatsCalculator;
+
+    fn function(name: &str, cyclomatic_complexity: usize) -> FunctionComplexityDetail {
+        FunctionComplexityDetail {
+            name: name.to_string(),
+            file_path: "src/lib.rs".to_string(),
+            start_line: cyclomatic_complexity,
+            end_line: cyclomatic_complexity + 10,
+            line_count: 10,
+            cyclomatic_complexity,
+            cognitive_complexity: cyclomatic_complexity,
+            parameter_count: 2,
+            return_path_count: 1,
+            nesting_depth: 1,
+            is_method: false,
+            parent_class: None,
+            local_variable_count: 0,
+            has_recursion: false,
+            has_exception_handling: false,
+            complexity_level: ComplexityLevel::High,
+            maintainability_concerns: Vec::new(),
+        }
+    }
+
+    fn file(total_lines: usize) -> FileStats {
+        FileStats {
+            total_lines,
+            code_lines: total_lines,
+            ..FileStats::default()
+        }
+    }
+
+    fn stats() -> AggregatedStats {
+        let code_stats = CodeCounter::new().aggregate_stats(vec![("rs".to_string(), file(1500)), ("rs".to_string(), file(3000))]);
+        let mut stats = StatsCalculator::new().calculate_project_stats(&code_stats, &[]).unwrap();
+        stats.complexity.complex_functions = vec![function("parse", 20), function("render", 40), function("nested", 10)];
+        stats.complexity.long_parameter_functions = vec![LongParameterListFinding {
+            name: "build".to_string(),
+            file_path: "src/build.rs".to_string(),
+            extension: "rs".to_string(),
+            start_line: 3,
+            parameter_count: 7,
+        }];
+        stats
+    }
+
+    fn individual_files() -> Vec<(String, FileStats)> {
+        vec![
+            ("src/small.rs".to_string(), file(200)),
+            ("src/big.rs".to_string(), file(1500)),
+            ("src/huge.rs".to_string(), file(3000)),
+        ]
+    }
+
+    #[test]
+    fn test_top_findings() {
+        let stats = stats();
+        let files = individual_files();
+
+        // The worst of each kind comes first
+        assert_eq!(top_findings(&stats, &files, CI_SUMMARY_FINDINGS), vec![
+            "src/lib.rs:40 `render` has cyclomatic complexity 40",
+            "src/huge.rs has 3000 lines",
+            "src/build.rs:3 `build` takes 7 parameters",
+        ]);
+
+        // Then the next of each kind; functions within the complexity limit are left out
+        let findings = top_findings(&stats, &files, 10);
+        assert_eq!(&findings[3..], [
+            "src/lib.rs:20 `parse` has cyclomatic complexity 20",
+            "src/big.rs has 1500 lines",
+        ]);
+        assert!(top_findings(&stats, &files, 0).is_empty());
+    }
+
+    #[test]
+    fn test_report_location() {
+        let report = Path::new("out/howmany-report.html");
+        assert_eq!(report_location(report, Some("https://ci.example.com/artifacts/")), "https://ci.example.com/artifacts/howmany-report.html");
+        assert_eq!(report_location(report, Some("https://ci.example.com/artifacts")), "https://ci.example.com/artifacts/howmany-report.html");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("howmany-report.html");
+        fs::write(&path, "<html></html>").unwrap();
+        let location = report_location(&path, None);
+        assert!(Path::new(&location).is_absolute());
+        assert_eq!(Path::new(&location), fs::canonicalize(&path).unwrap());
+    }
+
+    #[test]
+    fn test_ci_footer() {
+        let stats = stats();
+        let findings = top_findings(&stats, &individual_files(), CI_SUMMARY_FINDINGS);
+        let footer = render_ci_footer(&stats, "https://ci.example.com/report.html", &findings);
+        assert!(footer.contains("2 files, 4500 lines of code, code health "));
+        assert!(footer.contains("Full report: https://ci.example.com/report.html\n"));
+        assert!(footer.ends_with("Top findings:\n  1. src/lib.rs:40 `render` has cyclomatic complexity 40\n  2. src/huge.rs has 3000 lines\n  3. src/build.rs:3 `build` takes 7 parameters\n"));
+
+        let footer = render_ci_footer(&stats, "/tmp/report.html", &[]);
+        assert!(footer.ends_with("No notable findings\n"));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::core::stats::AggregatedStats;
+use crate::core::stats::basic::ExtensionStats;
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+
+/// Width of the tokei table, including the leading space of each row
+const TOKEI_WIDTH: usize = 79;
+
+/// Renders analysis results in the layouts of cloc and tokei, so dashboards
+/// and diff scripts written against those tools keep working. Like both
+/// tools, comments include documentation comments.
+pub struct CompatExporter;
+
+impl CompatExporter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The document `cloc --json` prints: a `header`, an object per language
+    /// with `nFiles`, `blank`, `comment` and `code`, and their `SUM`
+    pub fn cloc_json(&self, stats: &AggregatedStats) -> Value {
+        let basic = &stats.basic;
+        let elapsed_seconds = stats.metadata.performance.as_ref().map_or(0.0, |performance| performance.total_time_ms as f64 / 1000.0);
+        let per_second = |count: usize| if elapsed_seconds > 0.0 { count as f64 / elapsed_seconds } else { 0.0 };
+
+        let mut document = Map::new();
+        document.insert("header".to_string(), json!({
+            "cloc_url": env!("CARGO_PKG_REPOSITORY"),
+            "cloc_version": env!("CARGO_PKG_VERSION"),
+            "elapsed_seconds": elapsed_seconds,
+            "n_files": basic.total_files,
+            "n_lines": basic.total_lines,
+            "files_per_second": per_second(basic.total_files),
+            "lines_per_second": per_second(basic.total_lines),
+        }));
+        for (language, ext_stats) in Self::languages(stats) {
+            document.insert(language.clone(), json!({
+                "nFiles": ext_stats.file_count,
+                "blank": ext_stats.blank_lines,
+                "comment": ext_stats.comment_lines + ext_stats.doc_lines,
+                "code": ext_stats.code_lines,
+            }));
+        }
+        document.insert("SUM".to_string(), json!({
+            "blank": basic.blank_lines,
+            "comment": basic.comment_lines + basic.doc_lines,
+            "code": basic.code_lines,
+            "nFiles": basic.total_files,
+        }));
+        Value::Object(document)
+    }
+
+    /// The table tokei prints by default: a row per language and a total
+    pub fn tokei_table(&self, stats: &AggregatedStats) -> String {
+        let rule = "=".repeat(TOKEI_WIDTH);
+        let mut output = String::new();
+        let _ = writeln!(output, "{}", rule);
+        let _ = writeln!(output, " {:<18} {:>6} {:>12} {:>12} {:>12} {:>12}", "Language", "Files", "Lines", "Code", "Comments", "Blanks");
+        let _ = writeln!(output, "{}", rule);
+        for (language, ext_stats) in Self::languages(stats) {
+            let _ = writeln!(
+                output,
+                " {:<18} {:>6} {:>12} {:>12} {:>12} {:>12}",
+                Self::truncate(language),
+                ext_stats.file_count,
+                ext_stats.total_lines,
+                ext_stats.code_lines,
+                ext_stats.comment_lines + ext_stats.doc_lines,
+                ext_stats.blank_lines
+            );
+        }
+        let basic = &stats.basic;
+        let _ = writeln!(output, "{}", rule);
+        let _ = writeln!(
+            output,
+            " {:<18} {:>6} {:>12} {:>12} {:>12} {:>12}",
+            "Total",
+            basic.total_files,
+            basic.total_lines,
+            basic.code_lines,
+            basic.comment_lines + basic.doc_lines,
+            basic.blank_lines
+        );
+        let _ = writeln!(output, "{}", rule);
+        output
+    }
+
+    /// Languages by name, as both tools list them
+    fn languages(stats: &AggregatedStats) -> Vec<(&String, &ExtensionStats)> {
+        let mut languages: Vec<_> = stats.basic.stats_by_extension.iter().collect();
+        languages.sort_by(|a, b| a.0.cmp(b.0));
+        languages
+    }
+
+    /// Language names that don't fit their column end in `~`, as in tokei
+    fn truncate(language: &str) -> String {
+        if language.chars().count() <= 18 {
+            return language.to_string();
+        }
+        let mut truncated: String = language.chars().take(17).collect();
+        truncated.push('~');
+        truncated
+    }
+}
+
+impl Default for CompatExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::stats::aggregation::RunPerformance;
+    use crate::core::stats::StatsCalculator;
+    use crate::core::types::CodeStats;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn language(file_count: usize, code_lines: usize, comment_lines: usize, doc_lines: usize, blank_lines: usize) -> ExtensionStats {
+        ExtensionStats {
+            file_count,
+            total_lines: code_lines + comment_lines + doc_lines + blank_lines,
+            code_lines,
+            comment_lines,
+            doc_lines,
+            blank_lines,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            average_line_length: 0.0,
+            total_size: 0,
+            average_lines_per_file: 0.0,
+            average_size_per_file: 0.0,
+            metrics: BTreeMap::new(),
+        }
+    }
+
+    fn stats() -> AggregatedStats {
+        let code_stats = CodeStats {
+            total_files: 3,
+            total_lines: 32,
+            total_code_lines: 23,
+            total_comment_lines: 1,
+            total_blank_lines: 6,
+            total_size: 515,
+            total_doc_lines: 2,
+            stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
+        };
+        let mut stats = StatsCalculator::new().calculate_project_stats(&code_stats, &[]).unwrap();
+        stats.basic.stats_by_extension.insert("Rust".to_string(), language(2, 15, 0, 2, 2));
+        stats.basic.stats_by_extension.insert("Python".to_string(), language(1, 8, 1, 0, 4));
+        stats.metadata.performance = Some(RunPerformance { total_time_ms: 500, ..RunPerformance::default() });
+        stats
+    }
+
+    #[test]
+    fn test_cloc_json_layout() {
+        let document = CompatExporter::new().cloc_json(&stats());
+
+        assert_eq!(document["header"]["n_files"], 3);
+        assert_eq!(document["header"]["elapsed_seconds"], 0.5);
+        assert_eq!(document["header"]["lines_per_second"], 64.0);
+        assert_eq!(document["Rust"], json!({"nFiles": 2, "blank": 2, "comment": 2, "code": 15}));
+        assert_eq!(document["SUM"], json!({"nFiles": 3, "blank": 6, "comment": 3, "code": 23}));
+    }
+
+    #[test]
+    fn test_tokei_table_layout() {
+        let table = CompatExporter::new().tokei_table(&stats());
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines[1], " Language            Files        Lines         Code     Comments       Blanks");
+        assert_eq!(lines[3], " Python                  1           13            8            1            4");
+        assert_eq!(lines[4], " Rust                    2           19           15            2            2");
+        assert_eq!(lines[6], " Total                   3           32           23            3            6");
+        assert!(lines.iter().all(|line| line.chars().count() <= TOKEI_WIDTH));
+        assert_eq!(CompatExporter::truncate("Visual Studio Solution File"), "Visual Studio Sol~");
+    }
+}
@@ -0,0 +1,200 @@
+use crate::core::detector::extension_key;
+use crate::core::stats::basic::BasicStats;
+use crate::core::stats::AggregatedStats;
+use crate::core::types::FileStats;
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::languages::LanguageRegistry;
+use std::path::Path;
+
+/// Count columns of the CSV tables, after the name column
+const COUNT_HEADERS: [&str; 9] = ["Total Lines", "Code Lines", "Comment Lines", "Doc Lines", "Blank Lines", "Size (bytes)", "Characters", "Non-Whitespace Characters", "Tokens"];
+
+/// Renders analysis results as CSV tables: one row per language (or per file)
+/// closed by a totals row, then the configured areas
+pub struct CsvExporter {
+    delimiter: char,
+    by_extension: bool,
+}
+
+impl CsvExporter {
+    pub fn new() -> Self {
+        Self {
+            delimiter: ',',
+            by_extension: false,
+        }
+    }
+
+    /// Field delimiter, e.g. ';' for spreadsheets that use decimal commas; must be ASCII
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Name rows by extension instead of language
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.by_extension = by_extension;
+        self
+    }
+
+    fn name_header(&self) -> &'static str {
+        if self.by_extension { "Extension" } else { "Language" }
+    }
+
+    /// A row per language, with production and test code lines when the
+    /// totals were split, closed by the totals; configured areas follow as a second table
+    pub fn render(&self, stats: &AggregatedStats) -> Result<String> {
+        let basic = &stats.basic;
+        let split = stats.test_split.as_ref();
+        let mut headers: Vec<String> = [self.name_header(), "Files"].into_iter().chain(COUNT_HEADERS).map(str::to_string).collect();
+        if split.is_some() {
+            headers.extend(["Production Code Lines".to_string(), "Test Code Lines".to_string()]);
+        }
+
+        let mut extensions: Vec<_> = basic.stats_by_extension.iter().collect();
+        extensions.sort_by(|a, b| a.0.cmp(b.0));
+        let mut rows = Vec::new();
+        for (ext, ext_stats) in extensions {
+            let counts = [ext_stats.file_count, ext_stats.total_lines, ext_stats.code_lines, ext_stats.comment_lines, ext_stats.doc_lines, ext_stats.blank_lines, ext_stats.total_size as usize, ext_stats.characters, ext_stats.non_whitespace_characters, ext_stats.tokens];
+            let mut row: Vec<String> = std::iter::once(ext.clone()).chain(counts.map(|count| count.to_string())).collect();
+            if let Some(split) = split {
+                let code_lines = |basic: &BasicStats| basic.stats_by_extension.get(ext).map_or(0, |stats| stats.code_lines);
+                row.extend([code_lines(&split.production).to_string(), code_lines(&split.test).to_string()]);
+            }
+            rows.push(row);
+        }
+
+        let mut total: Vec<String> = ["Total".to_string(), basic.total_files.to_string()].into_iter().chain(total_counts(basic).map(|count| count.to_string())).collect();
+        if let Some(split) = split {
+            total.extend([split.production.code_lines.to_string(), split.test.code_lines.to_string()]);
+        }
+        rows.push(total);
+        let mut output = self.table(headers, rows)?;
+
+        if !stats.areas.is_empty() {
+            output.push('\n');
+            let headers = ["Area", "Files"].into_iter().chain(COUNT_HEADERS).map(str::to_string).collect();
+            let rows = stats.areas
+                .iter()
+                .map(|area| {
+                    let basic = &area.basic;
+                    std::iter::once(area.name.clone())
+                        .chain(std::iter::once(basic.total_files.to_string()))
+                        .chain(total_counts(basic).map(|count| count.to_string()))
+                        .collect()
+                })
+                .collect();
+            output.push_str(&self.table(headers, rows)?);
+        }
+
+        Ok(output)
+    }
+
+    /// A sheet of the files by path, closed by the totals
+    pub fn render_files(&self, stats: &AggregatedStats, individual_files: &[(String, FileStats)]) -> Result<String> {
+        let registry = LanguageRegistry::new();
+        let mut files: Vec<_> = individual_files.iter().collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut rows = Vec::new();
+        for (path, file_stats) in files {
+            let extension = file_stats.row_key(extension_key(Path::new(path)).unwrap_or_else(|| "no_ext".to_string()), self.by_extension).to_lowercase();
+            let language = if self.by_extension { extension } else { registry.key(&extension) };
+            let counts = [file_stats.total_lines, file_stats.code_lines, file_stats.comment_lines, file_stats.doc_lines, file_stats.blank_lines, file_stats.file_size as usize, file_stats.characters, file_stats.non_whitespace_characters, file_stats.tokens];
+            rows.push([path.clone(), language].into_iter().chain(counts.map(|count| count.to_string())).collect());
+        }
+        rows.push(["Total".to_string(), String::new()].into_iter().chain(total_counts(&stats.basic).map(|count| count.to_string())).collect());
+
+        let headers = ["Path", self.name_header()].into_iter().chain(COUNT_HEADERS).map(str::to_string).collect();
+        self.table(headers, rows)
+    }
+
+    /// One CSV table, with fields quoted where they contain the delimiter, quotes or line breaks
+    fn table(&self, headers: Vec<String>, rows: Vec<Vec<String>>) -> Result<String> {
+        let failed = |e: csv::Error| HowManyError::display(format!("Failed to write CSV: {}", e));
+        let mut writer = csv::WriterBuilder::new().delimiter(self.delimiter as u8).from_writer(Vec::new());
+        writer.write_record(&headers).map_err(failed)?;
+        for row in &rows {
+            writer.write_record(row).map_err(failed)?;
+        }
+        let table = writer.into_inner().map_err(|e| HowManyError::display(format!("Failed to write CSV: {}", e)))?;
+        Ok(String::from_utf8_lossy(&table).into_owned())
+    }
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The counts of `COUNT_HEADERS` for a set of files
+fn total_counts(basic: &BasicStats) -> [usize; 9] {
+    [basic.total_lines, basic.code_lines, basic.comment_lines, basic.doc_lines, basic.blank_lines, basic.total_size as usize, basic.characters, basic.non_whitespace_characters, basic.tokens]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::counter::CodeCounter;
+    use crate::core::stats::StatsCalculator;
+
+    fn file(code_lines: usize, comment_lines: usize) -> FileStats {
+        FileStats {
+            total_lines: code_lines + comment_lines,
+            code_lines,
+            comment_lines,
+            file_size: 100,
+            ..FileStats::default()
+        }
+    }
+
+    fn files() -> Vec<(String, FileStats)> {
+        vec![
+            ("src/main.rs".to_string(), file(10, 2)),
+            ("src/a, \"quoted\"\nname.py".to_string(), file(3, 1)),
+        ]
+    }
+
+    fn stats(files: &[(String, FileStats)]) -> AggregatedStats {
+        let code_stats = CodeCounter::new().aggregate_stats(files.iter().map(|(path, stats)| (extension_key(Path::new(path)).unwrap(), stats.clone())).collect());
+        StatsCalculator::new().calculate_project_stats(&code_stats, &[]).unwrap()
+    }
+
+    #[test]
+    fn test_fields_are_quoted() {
+        let files = files();
+        let output = CsvExporter::new().render_files(&stats(&files), &files).unwrap();
+        // The delimiter, quotes and line breaks only appear inside a quoted field, with quotes doubled
+        assert!(output.contains("\"src/a, \"\"quoted\"\"\nname.py\",Python,4,3,1,0,0,100,0,0,0\n"));
+        assert!(output.contains("\nsrc/main.rs,Rust,12,10,2,0,0,100,0,0,0\n"));
+    }
+
+    #[test]
+    fn test_other_delimiters() {
+        let files = files();
+        let stats = stats(&files);
+
+        let output = CsvExporter::new().with_delimiter(';').with_by_extension(true).render(&stats).unwrap();
+        assert!(output.starts_with("Extension;Files;Total Lines;Code Lines;"));
+        assert!(output.contains("\nrs;1;12;10;2;0;0;100;0;0;0\n"));
+
+        let output = CsvExporter::new().with_delimiter('\t').render_files(&stats, &files).unwrap();
+        assert!(output.starts_with("Path\tLanguage\tTotal Lines\t"));
+        // A comma needs no quoting when it isn't the delimiter, the line break still does
+        assert!(output.contains("\"src/a, \"\"quoted\"\"\nname.py\"\tPython\t4\t"));
+    }
+
+    #[test]
+    fn test_totals_row() {
+        let files = files();
+        let stats = stats(&files);
+
+        let output = CsvExporter::new().with_by_extension(true).render(&stats).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[3], "Total,2,16,13,3,0,0,200,0,0,0");
+
+        let output = CsvExporter::new().render_files(&stats, &files).unwrap();
+        assert!(output.ends_with("\nTotal,,16,13,3,0,0,200,0,0,0\n"));
+    }
+}
@@ -1,6 +1,9 @@
+use crate::core::detector::extension_key;
+use crate::utils::languages::LanguageRegistry;
 use crate::core::types::FileStats;
 use crate::core::stats::aggregation::AggregatedStats;
 use crate::core::stats::basic::ExtensionStats;
+use crate::utils::format::format_size;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -63,6 +66,14 @@ impl Default for FilterOptions {
     }
 }
 
+/// Whether an `--only` or `--exclude` entry selects a statistics key. Extensions match
+/// themselves and language names match every extension of the language, so
+/// `rs`, `Rust` and `rust` all select the Rust row.
+fn matches_language(filter: &str, key: &str) -> bool {
+    let registry = LanguageRegistry::new();
+    filter.eq_ignore_ascii_case(key) || registry.covers(filter, key) || registry.covers(key, filter)
+}
+
 /// Filter for individual files
 pub struct FileFilter {
     options: FilterOptions,
@@ -101,20 +112,16 @@ impl FileFilter {
         }
         
         // Language/extension filters
-        let extension = std::path::Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("no_ext")
-            .to_lowercase();
+        let extension = file_stats.language_key(extension_key(std::path::Path::new(file_path)).unwrap_or_else(|| "no_ext".to_string())).to_lowercase();
         
         if !self.options.include_languages.is_empty() {
-            if !self.options.include_languages.iter().any(|lang| lang.to_lowercase() == extension) {
+            if !self.options.include_languages.iter().any(|lang| matches_language(lang, &extension)) {
                 return false;
             }
         }
         
         if !self.options.exclude_languages.is_empty() {
-            if self.options.exclude_languages.iter().any(|lang| lang.to_lowercase() == extension) {
+            if self.options.exclude_languages.iter().any(|lang| matches_language(lang, &extension)) {
                 return false;
             }
         }
@@ -163,13 +170,13 @@ impl ProjectFilter {
         for (ext, stats) in stats_by_extension {
             // Language filters
             if !self.options.include_languages.is_empty() {
-                if !self.options.include_languages.iter().any(|lang| lang.to_lowercase() == ext.to_lowercase()) {
+                if !self.options.include_languages.iter().any(|lang| matches_language(lang, ext)) {
                     continue;
                 }
             }
             
             if !self.options.exclude_languages.is_empty() {
-                if self.options.exclude_languages.iter().any(|lang| lang.to_lowercase() == ext.to_lowercase()) {
+                if self.options.exclude_languages.iter().any(|lang| matches_language(lang, ext)) {
                     continue;
                 }
             }
@@ -257,8 +264,7 @@ impl FilteredOutputFormatter {
         ));
         
         if options.show_size_info {
-            let size_mb = aggregated_stats.basic.total_size as f64 / (1024.0 * 1024.0);
-            output.push_str(&format!(", {:.1} MB", size_mb));
+            output.push_str(&format!(", {}", format_size(aggregated_stats.basic.total_size)));
         }
         
         if options.show_complexity && aggregated_stats.complexity.function_count > 0 {
@@ -0,0 +1,198 @@
+use crate::utils::errors::{HowManyError, Result};
+use minijinja::{Environment, Value};
+use std::fs;
+use std::path::Path;
+
+/// Name of the built-in report rendered from basic `CodeStats`
+pub const STANDARD_TEMPLATE: &str = "standard.html";
+/// Name of the built-in comprehensive report; user templates can `{% extends %}` it
+pub const COMPREHENSIVE_TEMPLATE: &str = "comprehensive.html";
+/// Name of the built-in growth report of `howmany history`
+pub const HISTORY_TEMPLATE: &str = "history.html";
+/// Name of the partial with the light/dark palette, theme toggle and print
+/// handling shared by the built-in reports; user templates can `{% include %}` it
+pub const THEME_PARTIAL: &str = "theme.html";
+/// Name a user-supplied template is registered under
+pub const CUSTOM_TEMPLATE: &str = "custom.html";
+
+/// Runtime template engine backing the HTML reports.
+///
+/// The built-in templates are embedded at compile time; a user template can be
+/// loaded from disk and either replace the comprehensive report entirely or
+/// extend it and override individual blocks.
+pub struct ReportTemplateEngine {
+    env: Environment<'static>,
+    has_custom_template: bool,
+}
+
+impl ReportTemplateEngine {
+    pub fn new() -> Self {
+        let mut env = Environment::new();
+        env.add_template(THEME_PARTIAL, include_str!("assets/theme.html"))
+            .expect("built-in theme partial is valid");
+        env.add_template(STANDARD_TEMPLATE, include_str!("assets/standard.html"))
+            .expect("built-in standard template is valid");
+        env.add_template(COMPREHENSIVE_TEMPLATE, include_str!("assets/comprehensive.html"))
+            .expect("built-in comprehensive template is valid");
+        env.add_template(HISTORY_TEMPLATE, include_str!("assets/history.html"))
+            .expect("built-in history template is valid");
+        env.add_filter("quality_class", quality_class);
+        env.add_filter("progress_class", progress_class);
+
+        Self {
+            env,
+            has_custom_template: false,
+        }
+    }
+
+    /// Load a user template from disk to be used for comprehensive reports
+    pub fn with_template_file(mut self, path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path).map_err(|e| {
+            HowManyError::template(format!("Failed to read template '{}': {}", path.display(), e))
+        })?;
+        self.env
+            .add_template_owned(CUSTOM_TEMPLATE, source)
+            .map_err(|e| HowManyError::template(format!("Invalid template '{}': {}", path.display(), e)))?;
+        self.has_custom_template = true;
+        Ok(self)
+    }
+
+    pub fn has_custom_template(&self) -> bool {
+        self.has_custom_template
+    }
+
+    /// Name of the template used for comprehensive reports
+    pub fn comprehensive_template_name(&self) -> &'static str {
+        if self.has_custom_template {
+            CUSTOM_TEMPLATE
+        } else {
+            COMPREHENSIVE_TEMPLATE
+        }
+    }
+
+    pub fn render(&self, name: &str, context: Value) -> Result<String> {
+        let template = self
+            .env
+            .get_template(name)
+            .map_err(|e| HowManyError::template(e.to_string()))?;
+        template
+            .render(context)
+            .map_err(|e| HowManyError::template(format!("Failed to render '{}': {}", name, e)))
+    }
+}
+
+impl Default for ReportTemplateEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn quality_class(score: f64) -> String {
+    if score >= 80.0 {
+        "score-excellent"
+    } else if score >= 60.0 {
+        "score-good"
+    } else {
+        "score-poor"
+    }
+    .to_string()
+}
+
+fn progress_class(score: f64) -> String {
+    if score >= 80.0 {
+        "progress-excellent"
+    } else if score >= 60.0 {
+        "progress-good"
+    } else {
+        "progress-poor"
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::counter::CodeCounter;
+    use crate::core::stats::StatsCalculator;
+    use crate::core::types::FileStats;
+    use crate::ui::html::standard_report::StandardReportGenerator;
+    use crate::utils::errors::ErrorCategory;
+
+    fn files() -> Vec<(String, FileStats)> {
+        let file = |code_lines: usize, comment_lines: usize| FileStats {
+            total_lines: code_lines + comment_lines,
+            code_lines,
+            comment_lines,
+            file_size: 100,
+            ..FileStats::default()
+        };
+        vec![
+            ("src/main.rs".to_string(), file(10, 2)),
+            ("scripts/build.py".to_string(), file(3, 1)),
+        ]
+    }
+
+    fn write_template(dir: &tempfile::TempDir, source: &str) -> std::path::PathBuf {
+        let path = dir.path().join("report.html");
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    fn code_stats(files: &[(String, FileStats)]) -> crate::core::types::CodeStats {
+        CodeCounter::new().aggregate_stats(files.iter().map(|(path, stats)| (path.rsplit('.').next().unwrap().to_string(), stats.clone())).collect())
+    }
+
+    #[test]
+    fn test_built_in_templates() {
+        let files = files();
+        let code_stats = code_stats(&files);
+        let generator = StandardReportGenerator::new();
+
+        let standard = generator.create_html_content(&code_stats, &files).unwrap();
+        assert!(standard.starts_with("<!DOCTYPE html>"));
+        assert!(standard.contains("src/main.rs"));
+
+        let stats = StatsCalculator::new().calculate_project_stats(&code_stats, &files).unwrap();
+        let comprehensive = generator.create_comprehensive_html_content(&stats, &files).unwrap();
+        assert!(comprehensive.starts_with("<!DOCTYPE html>"));
+        assert!(comprehensive.contains("scripts/build.py"));
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_template(&dir, "{% extends \"comprehensive.html\" %}{% block title %}{{ stats.basic.total_files }} files{% endblock %}");
+        let files = files();
+        let stats = StatsCalculator::new().calculate_project_stats(&code_stats(&files), &files).unwrap();
+        let generator = StandardReportGenerator::new().with_template_file(&path).unwrap();
+        let report = generator.create_comprehensive_html_content(&stats, &files).unwrap();
+        assert!(report.contains("<title>2 files</title>"));
+        assert!(report.contains("src/main.rs"));
+
+        let path = write_template(&dir, "{{ stats.basic.code_lines }} lines");
+        let engine = ReportTemplateEngine::new().with_template_file(&path).unwrap();
+        let context = minijinja::context! { stats => minijinja::context! { basic => minijinja::context! { code_lines => 13 } } };
+        assert_eq!(engine.render(CUSTOM_TEMPLATE, context).unwrap(), "13 lines");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Syntax errors surface when the template is loaded
+        let path = write_template(&dir, "{% for file in files %}{{ file.path }}");
+        let error = ReportTemplateEngine::new().with_template_file(&path).err().unwrap();
+        assert!(error.to_string().contains("Invalid template"), "{}", error);
+        assert_eq!(error.category(), ErrorCategory::Config);
+
+        // Lookups on undefined variables when it is rendered
+        let path = write_template(&dir, "{{ stat.basic.code_lines }}");
+        let engine = ReportTemplateEngine::new().with_template_file(&path).unwrap();
+        let error = engine.render(CUSTOM_TEMPLATE, minijinja::context! { stats => 1 }).unwrap_err();
+        assert!(error.to_string().contains("Failed to render"), "{}", error);
+        assert_eq!(error.category().exit_code(), 4);
+
+        let error = ReportTemplateEngine::new().with_template_file(&dir.path().join("missing.html")).err().unwrap();
+        assert_eq!(error.category(), ErrorCategory::Config);
+    }
+}
@@ -2,6 +2,7 @@ pub mod reporter;
 pub mod standard_report;
 pub mod insights;
 pub mod templates;
+pub mod engine;
 pub mod utils;
 
 pub use reporter::HtmlReporter; 
\ No newline at end of file
@@ -1,3 +1,4 @@
+use crate::core::history::HistoryReport;
 use crate::core::types::{CodeStats, FileStats};
 use crate::core::stats::AggregatedStats;
 use crate::utils::errors::Result;
@@ -18,6 +19,22 @@ impl HtmlReporter {
         }
     }
     
+    /// Use a user-supplied template for comprehensive reports.
+    ///
+    /// The template may stand alone or `{% extends "comprehensive.html" %}` and
+    /// override individual blocks of the built-in report.
+    pub fn with_template_file(mut self, path: &Path) -> Result<Self> {
+        self.standard_generator = self.standard_generator.with_template_file(path)?;
+        Ok(self)
+    }
+    
+    /// Break per-file charts down by file extension rather than by language,
+    /// matching statistics kept per extension with `--by-extension`
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.standard_generator = self.standard_generator.with_by_extension(by_extension);
+        self
+    }
+    
     /// Generate report from basic CodeStats (backward compatibility)
     pub fn generate_report(&self, stats: &CodeStats, individual_files: &[(String, FileStats)], output_path: &Path) -> Result<()> {
         let html_content = self.standard_generator.create_html_content(stats, individual_files)?;
@@ -32,7 +49,12 @@ impl HtmlReporter {
         Ok(())
     }
     
-
+    /// Generate the growth report of `howmany history`
+    pub fn generate_history_report(&self, report: &HistoryReport, output_path: &Path) -> Result<()> {
+        let html_content = self.standard_generator.create_history_html_content(report)?;
+        fs::write(output_path, html_content)?;
+        Ok(())
+    }
     
     /// Auto-detect and generate the best possible report
     pub fn generate_auto_report(&self, stats: Option<&CodeStats>, aggregated_stats: Option<&AggregatedStats>, individual_files: &[(String, FileStats)], output_path: &Path) -> Result<()> {
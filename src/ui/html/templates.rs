@@ -2,6 +2,7 @@ use crate::core::types::{CodeStats, FileStats};
 use crate::core::stats::basic::BasicStats;
 use crate::core::stats::complexity::ComplexityStatsCalculator;
 use crate::core::stats::aggregation::AggregatedStats;
+use crate::utils::languages::LanguageRegistry;
 use super::utils::FileUtils;
 
 use std::fmt::Write;
@@ -55,7 +56,9 @@ impl TemplateGenerator {
         rows
     }
     
-    /// Generate extension rows using real complexity analysis from AggregatedStats
+    /// Generate extension rows using real complexity analysis from AggregatedStats.
+    /// The function and complexity cells are left out when complexity wasn't
+    /// analyzed at all, and show a dash for languages without an analyzer.
     pub fn generate_extension_rows_with_real_analysis(&self, aggregated_stats: &AggregatedStats) -> String {
         let extensions_count = aggregated_stats.basic.stats_by_extension.len();
         let mut rows = String::with_capacity(extensions_count * 300); // Better pre-allocation
@@ -64,14 +67,25 @@ impl TemplateGenerator {
         extensions.sort_by(|a, b| b.1.total_lines.cmp(&a.1.total_lines));
         
         for (ext, ext_stats) in extensions {
-            let complexity_data = aggregated_stats.complexity.complexity_by_extension.get(ext);
-            let complexity_score = complexity_data.map(|c| c.cyclomatic_complexity).unwrap_or(0.0);
-            let function_count = complexity_data.map(|c| c.function_count).unwrap_or(0);
-            let complexity_class = self.get_complexity_class_for_score(complexity_score);
+            let complexity_cells = if !aggregated_stats.metadata.capabilities.complexity {
+                String::new()
+            } else if aggregated_stats.metadata.complexity_not_analyzed.contains(ext) {
+                "<td>–</td><td>–</td>".to_string()
+            } else {
+                let complexity_data = aggregated_stats.complexity.complexity_by_extension.get(ext);
+                let complexity_score = complexity_data.map(|c| c.cyclomatic_complexity).unwrap_or(0.0);
+                let function_count = complexity_data.map(|c| c.function_count).unwrap_or(0);
+                format!(
+                    "<td>{}</td><td><span class=\"complexity-badge {}\">{:.1}</span></td>",
+                    function_count,
+                    self.get_complexity_class_for_score(complexity_score),
+                    complexity_score
+                )
+            };
             
             // Use format! directly instead of write! for better performance in this case
             rows.push_str(&format!(
-                "<tr><td>{} {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td><span class=\"complexity-badge {}\">{:.1}</span></td><td>{}</td></tr>",
+                "<tr><td>{} {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>{}<td>{}</td></tr>",
                 self.file_utils.get_file_emoji(ext),
                 ext,
                 ext_stats.file_count,
@@ -79,9 +93,7 @@ impl TemplateGenerator {
                 ext_stats.code_lines,
                 ext_stats.comment_lines,
                 ext_stats.doc_lines,
-                function_count,
-                complexity_class,
-                complexity_score,
+                complexity_cells,
                 self.file_utils.format_size(ext_stats.total_size)
             ));
         }
@@ -692,22 +704,7 @@ impl TemplateGenerator {
     }
     
     fn get_language_name(&self, ext: &str) -> &'static str {
-        match ext {
-            "rs" => "Rust",
-            "py" => "Python",
-            "js" => "JavaScript",
-            "ts" => "TypeScript",
-            "java" => "Java",
-            "cpp" | "cc" | "cxx" => "C++",
-            "c" => "C",
-            "go" => "Go",
-            "rb" => "Ruby",
-            "php" => "PHP",
-            "cs" => "C#",
-            "swift" => "Swift",
-            "kt" => "Kotlin",
-            _ => "Unknown",
-        }
+        LanguageRegistry::new().resolve(ext).map_or("Unknown", |language| language.name)
     }
     
     fn shorten_path(&self, path: &str) -> String {
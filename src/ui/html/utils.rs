@@ -1,4 +1,6 @@
 use crate::core::types::FileStats;
+use crate::utils::format;
+use crate::utils::languages::LanguageRegistry;
 
 pub struct FileUtils;
 
@@ -8,24 +10,12 @@ impl FileUtils {
     }
     
     pub fn format_size(&self, size: u64) -> String {
-        const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
-        let mut size = size as f64;
-        let mut unit_index = 0;
-        
-        while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-            size /= 1024.0;
-            unit_index += 1;
-        }
-        
-        if unit_index == 0 {
-            format!("{} {}", size as u64, UNITS[unit_index])
-        } else {
-            format!("{:.1} {}", size, UNITS[unit_index])
-        }
+        format::format_size(size)
     }
     
+    /// Emoji of a file extension or language name
     pub fn get_file_emoji(&self, ext: &str) -> &str {
-        match ext {
+        match LanguageRegistry::new().extension(ext) {
             "js" | "jsx" => "🟨",
             "ts" | "tsx" => "🔷",
             "py" => "🐍",
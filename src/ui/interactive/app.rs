@@ -7,6 +7,8 @@ use std::fs;
 use std::path::Path;
 use crate::ui::html::HtmlReporter;
 use crate::utils::errors::Result;
+use crate::utils::languages::LanguageRegistry;
+use crate::core::detector::extension_key;
 use serde_json;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -85,6 +87,14 @@ impl Default for SearchState {
     }
 }
 
+/// One entry of the project switcher: the whole workspace or one of its member projects
+#[derive(Debug, Clone)]
+pub struct ProjectView {
+    pub name: String,
+    pub stats: CodeStats,
+    pub individual_files: Vec<(String, FileStats)>,
+}
+
 #[derive(Debug)]
 pub struct InteractiveApp {
     pub mode: AppMode,
@@ -106,6 +116,9 @@ pub struct InteractiveApp {
     pub filtered_extensions: Vec<String>,
     pub language_stats: std::collections::HashMap<String, (crate::ui::interactive::utils::LanguageInfo, usize, FileStats)>,
     pub show_code_health: bool,
+
+    pub projects: Vec<ProjectView>,
+    pub selected_project: usize,
 }
 
 impl Default for InteractiveApp {
@@ -130,6 +143,9 @@ impl Default for InteractiveApp {
             filtered_extensions: Vec::new(),
             language_stats: std::collections::HashMap::new(),
             show_code_health: false,
+
+            projects: Vec::new(),
+            selected_project: 0,
         }
     }
 }
@@ -147,6 +163,40 @@ impl InteractiveApp {
         self.update_filtered_extensions();
         self.update_language_stats(&stats);
     }
+
+    /// Show the first project and list all of them in the project sidebar
+    pub fn set_projects(&mut self, projects: Vec<ProjectView>) {
+        self.projects = projects;
+        self.select_project(0);
+    }
+
+    /// Whether there are member projects to switch between
+    pub fn has_projects(&self) -> bool {
+        self.projects.len() > 1
+    }
+
+    fn select_project(&mut self, index: usize) {
+        let Some(project) = self.projects.get(index).cloned() else {
+            return;
+        };
+        self.selected_project = index;
+        self.set_data(project.stats, project.individual_files);
+        self.table_state.select(None);
+        self.export_state = ExportState::default();
+    }
+
+    fn next_project(&mut self) {
+        if self.has_projects() {
+            self.select_project((self.selected_project + 1) % self.projects.len());
+        }
+    }
+
+    fn prev_project(&mut self) {
+        if self.has_projects() {
+            let len = self.projects.len();
+            self.select_project((self.selected_project + len - 1) % len);
+        }
+    }
     
 
 
@@ -215,9 +265,9 @@ impl InteractiveApp {
                 if let Some(ref stats) = self.stats {
                     for (ext, _) in &stats.stats_by_extension {
                         if ext.to_lowercase().contains(&query) {
-                            // Find files with this extension
+                            // Find files counted under this extension or language
                             for (file_path, file_stats) in &self.individual_files {
-                                if file_path.ends_with(&format!(".{}", ext)) {
+                                if counted_under(ext, file_path) {
                                     results.push(SearchResult {
                                         file_path: file_path.clone(),
                                         line_count: file_stats.total_lines,
@@ -344,7 +394,7 @@ impl InteractiveApp {
         if let Some(ref stats) = self.stats {
             self.filtered_extensions = stats.stats_by_extension.keys()
                 .filter(|ext| {
-                    self.filtered_files.iter().any(|(path, _)| path.ends_with(&format!(".{}", ext)))
+                    self.filtered_files.iter().any(|(path, _)| counted_under(ext, path))
                 })
                 .cloned()
                 .collect();
@@ -416,6 +466,14 @@ impl InteractiveApp {
                 self.prev_tab();
                 return; // Immediate tab switch
             },
+            KeyCode::Char(']') => {
+                self.next_project();
+                return;
+            },
+            KeyCode::Char('[') => {
+                self.prev_project();
+                return;
+            },
             _ => {}
         }
 
@@ -790,4 +848,9 @@ impl InteractiveApp {
     }
 
 
-} 
\ No newline at end of file
+} 
+
+/// Whether a file's lines are counted under a statistics key, a language name or an extension
+fn counted_under(key: &str, file_path: &str) -> bool {
+    extension_key(Path::new(file_path)).is_some_and(|extension| LanguageRegistry::new().covers(key, &extension))
+}
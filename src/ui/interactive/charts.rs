@@ -1,6 +1,10 @@
 use crate::core::stats::aggregation::AggregatedStats;
 
 use crate::core::stats::visualization::{PieChartData, ChartConfig};
+use crate::ui::interactive::utils::parse_hex_color;
+use crate::utils::format::format_number;
+use crate::utils::languages::LanguageRegistry;
+use crate::utils::linguist;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -448,8 +452,15 @@ pub fn render_function_complexity_table(f: &mut ratatui::Frame, area: Rect, stat
     f.render_widget(insight_list, chunks[1]);
 }
 
-/// Get language info (emoji and name)
+/// Get language info (emoji and name) of a language name or file extension
 fn get_language_info(ext: &str) -> (&'static str, &'static str) {
+    match LanguageRegistry::new().named(ext) {
+        Some(language) => (extension_info(language.extensions[0]).0, language.name),
+        None => extension_info(ext),
+    }
+}
+
+fn extension_info(ext: &str) -> (&'static str, &'static str) {
     match ext {
         "rs" => ("🦀", "Rust"),
         "py" => ("🐍", "Python"),
@@ -484,6 +495,21 @@ fn get_language_info(ext: &str) -> (&'static str, &'static str) {
         "lua" => ("🌙", "Lua"),
         "pl" | "pm" | "pod" => ("🐪", "Perl"),
         "zig" => ("⚡", "Zig"),
+        "cob" | "cbl" | "cpy" => ("🏦", "COBOL"),
+        "f" | "for" | "f77" | "ftn" | "f90" | "f95" | "f03" | "f08" => ("🧮", "Fortran"),
+        "adb" | "ads" | "ada" => ("🛡️", "Ada"),
+        "pas" | "dpr" | "lpr" => ("📐", "Pascal"),
+        "tf" | "tfvars" | "hcl" => ("🏗️", "HCL"),
+        "proto" => ("📦", "Protocol Buffers"),
+        "graphql" | "gql" => ("◈", "GraphQL"),
+        "nix" => ("❄️", "Nix"),
+        "s" | "asm" => ("🔩", "Assembly"),
+        "v" | "vh" | "sv" | "svh" => ("🔌", "Verilog"),
+        "vhd" | "vhdl" => ("🔌", "VHDL"),
+        "cu" | "cuh" => ("🟩", "CUDA"),
+        "makefile" => ("🛠️", "Makefile"),
+        "dockerfile" => ("🐳", "Dockerfile"),
+        "cmake" => ("🛠️", "CMake"),
         "clj" | "cljs" | "cljc" => ("🔄", "Clojure"),
         "ps1" | "psm1" | "psd1" => ("⚡", "PowerShell"),
         "bat" | "cmd" => ("⚙️", "Batch"),
@@ -494,27 +520,9 @@ fn get_language_info(ext: &str) -> (&'static str, &'static str) {
     }
 }
 
+/// Linguist's color for the language, as shown in GitHub's language bar
 fn get_language_color(ext: &str) -> Color {
-    match ext {
-        "rs" => Color::Red,
-        "py" => Color::Blue,
-        "js" => Color::Yellow,
-        "ts" => Color::Blue,
-        "jsx" | "tsx" => Color::Cyan,
-        "java" => Color::Red,
-        "cpp" | "cc" | "cxx" | "c" => Color::Blue,
-        "go" => Color::Cyan,
-        "cs" => Color::Blue,
-        "php" => Color::Magenta,
-        "rb" => Color::Red,
-        "swift" => Color::Red,
-        "kt" => Color::Magenta,
-        "html" => Color::Red,
-        "css" | "scss" => Color::Blue,
-        "sql" => Color::Yellow,
-        "sh" => Color::Green,
-        _ => Color::Gray,
-    }
+    parse_hex_color(linguist::language_color_or_default(ext))
 }
 
 fn get_structure_color(structure_type: &str) -> Color {
@@ -796,22 +804,6 @@ fn render_second_metrics_row(f: &mut ratatui::Frame, area: Rect, stats: &Aggrega
     f.render_widget(lang_block, chunks[3]);
 }
 
-/// Helper function to format numbers with commas
-fn format_number(num: usize) -> String {
-    let num_str = num.to_string();
-    let mut result = String::new();
-    let chars: Vec<char> = num_str.chars().collect();
-    
-    for (i, ch) in chars.iter().enumerate() {
-        if i > 0 && (chars.len() - i) % 3 == 0 {
-            result.push(',');
-        }
-        result.push(*ch);
-    }
-    
-    result
-}
-
 /// Render code breakdown with progress bars
 fn render_code_breakdown_bars(f: &mut ratatui::Frame, area: Rect, stats: &AggregatedStats) {
     let chunks = Layout::default()
@@ -1103,11 +1095,13 @@ fn generate_complexity_insights(stats: &AggregatedStats) -> Vec<ComplexityInsigh
 
 /// Advanced language visualizer with detailed statistics and visual charts
 pub fn render_advanced_language_visualizer(f: &mut ratatui::Frame, area: Rect, stats: &AggregatedStats) {
+    let worst_files = &stats.complexity.worst_files;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(12), // Language distribution with advanced metrics
             Constraint::Min(10),    // Detailed language analysis table
+            Constraint::Length(if worst_files.is_empty() { 0 } else { worst_files.len() as u16 + 2 }), // Files to refactor first
         ])
         .split(area);
     
@@ -1116,6 +1110,38 @@ pub fn render_advanced_language_visualizer(f: &mut ratatui::Frame, area: Rect, s
     
     // Detailed language analysis table
     render_detailed_language_analysis(f, chunks[1], stats);
+    
+    if !worst_files.is_empty() {
+        render_worst_files(f, chunks[2], stats);
+    }
+}
+
+/// Render the files with the lowest quality score, worst first
+fn render_worst_files(f: &mut ratatui::Frame, area: Rect, stats: &AggregatedStats) {
+    let items: Vec<ListItem> = stats.complexity.worst_files.iter().map(|file| {
+        let color = if file.score >= 80.0 {
+            Color::Green
+        } else if file.score >= 60.0 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{:>5.1} ", file.score), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("MI {:>5.1}  CC {:>4.1}  ", file.maintainability_index, file.average_complexity), Style::default().fg(Color::Gray)),
+            Span::styled(file.file_path.clone(), Style::default().fg(Color::White)),
+        ]))
+    }).collect();
+    
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(" 🔧 Files to Refactor First ")
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(Color::Red))
+        );
+    
+    f.render_widget(list, area);
 }
 
 /// Render advanced language distribution with enhanced metrics
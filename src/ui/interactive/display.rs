@@ -1,6 +1,6 @@
 use crate::core::types::{CodeStats, FileStats};
-use crate::ui::interactive::app::InteractiveApp;
-use crate::ui::interactive::rendering::{render_footer, render_header, render_main_content, render_help, render_welcome};
+use crate::ui::interactive::app::{InteractiveApp, ProjectView};
+use crate::ui::interactive::rendering::{render_footer, render_header, render_main_content, render_help, render_project_sidebar, render_welcome};
 use anyhow::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
@@ -73,6 +73,14 @@ impl ModernInteractiveDisplay {
         rt.block_on(self.run_interactive_async())
     }
 
+    /// Interactive mode with a sidebar for switching between member projects
+    pub fn run_workspace_mode(&mut self, projects: Vec<ProjectView>) -> Result<()> {
+        self.app.set_projects(projects);
+
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.run_interactive_async())
+    }
+
     async fn run_interactive_async(&mut self) -> Result<()> {
         let mut animation_interval = interval(Duration::from_millis(100));
         let mut redraw_needed = true;
@@ -171,10 +179,20 @@ impl ModernInteractiveDisplay {
 
             render_header(f, chunks[0], app);
             
+            let mut content_area = chunks[1];
+            if app.has_projects() {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(32), Constraint::Min(0)])
+                    .split(chunks[1]);
+                render_project_sidebar(f, columns[0], app);
+                content_area = columns[1];
+            }
+            
             if app.show_help {
-                render_help(f, chunks[1]);
+                render_help(f, content_area);
             } else {
-                render_main_content(f, chunks[1], app);
+                render_main_content(f, content_area, app);
             }
             
             render_footer(f, chunks[2], app);
@@ -1,5 +1,6 @@
 use crate::core::types::{CodeStats, FileStats};
 use crate::ui::interactive::display::ModernInteractiveDisplay;
+use crate::utils::format::format_size;
 use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
 use std::{io, time::Duration};
@@ -67,7 +68,7 @@ impl InteractiveDisplay {
         println!("💬 Comment Lines: {}", stats.total_comment_lines.to_string().bright_magenta());
         println!("📚 Documentation Lines: {}", stats.total_doc_lines.to_string().bright_cyan());
         println!("⬜ Blank Lines: {}", stats.total_blank_lines.to_string().bright_black());
-        println!("💾 Total Size: {}", Self::format_size_fallback(stats.total_size).bright_cyan());
+        println!("💾 Total Size: {}", format_size(stats.total_size).bright_cyan());
 
         if !individual_files.is_empty() {
             println!("\n{}", "📄 INDIVIDUAL FILES".bright_green());
@@ -84,21 +85,4 @@ impl InteractiveDisplay {
         
         Ok(())
     }
-
-    fn format_size_fallback(size: u64) -> String {
-        const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
-        let mut size = size as f64;
-        let mut unit_index = 0;
-        
-        while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-            size /= 1024.0;
-            unit_index += 1;
-        }
-        
-        if unit_index == 0 {
-            format!("{} {}", size as u64, UNITS[unit_index])
-        } else {
-            format!("{:.1} {}", size, UNITS[unit_index])
-        }
-    }
 } 
\ No newline at end of file
@@ -7,7 +7,10 @@ pub mod legacy;
 
 use crate::core::types::{CodeStats, FileStats};
 use crate::core::stats::AggregatedStats;
+use crate::core::stats::BasicStats;
 use crate::utils::errors::Result;
+use crate::utils::format::format_size;
+use app::ProjectView;
 use display::ModernInteractiveDisplay;
 use legacy::InteractiveDisplay as LegacyDisplay;
 
@@ -56,28 +59,23 @@ impl InteractiveDisplay {
     
     pub fn show_comprehensive_results(&mut self, aggregated_stats: &AggregatedStats, individual_files: &[(String, FileStats)]) -> Result<()> {
         if let Some(ref mut modern) = self.modern_display {
-            // Convert AggregatedStats back to CodeStats for compatibility
-            let code_stats = CodeStats {
-                total_files: aggregated_stats.basic.total_files,
-                total_lines: aggregated_stats.basic.total_lines,
-                total_code_lines: aggregated_stats.basic.code_lines,
-                total_comment_lines: aggregated_stats.basic.comment_lines,
-                total_blank_lines: aggregated_stats.basic.blank_lines,
-                total_size: aggregated_stats.basic.total_size,
-                total_doc_lines: aggregated_stats.basic.doc_lines,
-                stats_by_extension: aggregated_stats.basic.stats_by_extension.iter()
-                    .map(|(ext, ext_stats)| {
-                        (ext.clone(), (ext_stats.file_count, crate::core::types::FileStats {
-                            total_lines: ext_stats.total_lines,
-                            code_lines: ext_stats.code_lines,
-                            comment_lines: ext_stats.comment_lines,
-                            blank_lines: ext_stats.blank_lines,
-                            file_size: ext_stats.total_size,
-                            doc_lines: ext_stats.doc_lines,
-                        }))
-                    })
-                    .collect(),
-            };
+            let code_stats = code_stats_from_basic(&aggregated_stats.basic);
+            
+            // Several analyzed paths or separately reported nested projects get a project switcher
+            let members: Vec<_> = aggregated_stats.per_path.iter().chain(&aggregated_stats.nested_projects).collect();
+            if !members.is_empty() {
+                let mut projects = vec![ProjectView {
+                    name: "Workspace".to_string(),
+                    stats: code_stats,
+                    individual_files: individual_files.to_vec(),
+                }];
+                projects.extend(members.into_iter().map(|member| ProjectView {
+                    name: member.path.clone(),
+                    stats: code_stats_from_basic(&member.basic),
+                    individual_files: member.files.clone(),
+                }));
+                return modern.run_workspace_mode(projects).map_err(|e| crate::utils::errors::HowManyError::display(e.to_string()));
+            }
             
             // Run with async support for better responsiveness
             modern.run_interactive_mode(code_stats, individual_files.to_vec()).map_err(|e| crate::utils::errors::HowManyError::display(e.to_string()))
@@ -100,7 +98,7 @@ impl InteractiveDisplay {
         println!("💬 Comment Lines: {}", aggregated_stats.basic.comment_lines.to_string().bright_magenta());
         println!("📚 Documentation Lines: {}", aggregated_stats.basic.doc_lines.to_string().bright_cyan());
         println!("⬜ Blank Lines: {}", aggregated_stats.basic.blank_lines.to_string().bright_black());
-        println!("💾 Total Size: {}", self.format_size_fallback(aggregated_stats.basic.total_size).bright_cyan());
+        println!("💾 Total Size: {}", format_size(aggregated_stats.basic.total_size).bright_cyan());
         
         // Enhanced stats
         if aggregated_stats.complexity.function_count > 0 {
@@ -136,22 +134,46 @@ impl InteractiveDisplay {
         
         Ok(())
     }
-    
-    fn format_size_fallback(&self, size: u64) -> String {
-        const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-        let mut size = size as f64;
-        let mut unit_index = 0;
-        
-        while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-            size /= 1024.0;
-            unit_index += 1;
-        }
-        
-        if unit_index == 0 {
-            format!("{} {}", size as u64, UNITS[unit_index])
-        } else {
-            format!("{:.1} {}", size, UNITS[unit_index])
-        }
+}
+
+/// Convert AggregatedStats totals back to CodeStats for the interactive views
+fn code_stats_from_basic(basic: &BasicStats) -> CodeStats {
+    CodeStats {
+        total_files: basic.total_files,
+        total_lines: basic.total_lines,
+        total_code_lines: basic.code_lines,
+        total_comment_lines: basic.comment_lines,
+        total_blank_lines: basic.blank_lines,
+        total_size: basic.total_size,
+        total_doc_lines: basic.doc_lines,
+        total_inline_comment_lines: basic.inline_comment_lines,
+        total_logical_lines: basic.logical_lines,
+        total_characters: basic.characters,
+        total_non_whitespace_characters: basic.non_whitespace_characters,
+        total_tokens: basic.tokens,
+        line_lengths: basic.line_lengths,
+        stats_by_extension: basic.stats_by_extension.iter()
+            .map(|(ext, ext_stats)| {
+                (ext.clone(), (ext_stats.file_count, FileStats {
+                    total_lines: ext_stats.total_lines,
+                    code_lines: ext_stats.code_lines,
+                    comment_lines: ext_stats.comment_lines,
+                    blank_lines: ext_stats.blank_lines,
+                    file_size: ext_stats.total_size,
+                    doc_lines: ext_stats.doc_lines,
+                    inline_comment_lines: 0,
+                    logical_lines: 0,
+                    characters: ext_stats.characters,
+                    non_whitespace_characters: ext_stats.non_whitespace_characters,
+                    tokens: ext_stats.tokens,
+                    line_lengths: ext_stats.line_lengths,
+                    encoding: None,
+                    embedded: Default::default(),
+                    metrics: ext_stats.metrics.clone(),
+                    ..Default::default()
+                }))
+            })
+            .collect(),
     }
 }
 
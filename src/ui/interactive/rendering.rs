@@ -1,7 +1,7 @@
 use crate::core::types::CodeStats;
 
 use crate::ui::interactive::app::{AppMode, InteractiveApp, ExportFormat, SearchMode};
-use crate::ui::interactive::utils::{centered_rect, format_size, get_file_icon, shorten_path};
+use crate::ui::interactive::utils::{centered_rect, format_size, get_file_icon, parse_hex_color, shorten_path};
 use crate::ui::interactive::charts::{render_enhanced_overview, render_advanced_language_visualizer};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -24,6 +24,34 @@ pub fn render_header(f: &mut ratatui::Frame, area: Rect, app: &InteractiveApp) {
     f.render_widget(tabs, area);
 }
 
+/// Member projects with their file and code line counts, the shown one highlighted
+pub fn render_project_sidebar(f: &mut ratatui::Frame, area: Rect, app: &InteractiveApp) {
+    let name_width = area.width.saturating_sub(6) as usize;
+    let items: Vec<ListItem> = app.projects
+        .iter()
+        .map(|project| {
+            ListItem::new(vec![
+                Line::from(Span::styled(
+                    format!("📦 {}", shorten_path(&project.name, name_width)),
+                    Style::default().fg(Color::White),
+                )),
+                Line::from(Span::styled(
+                    format!("   {} files · {} code",
+                        crate::utils::format::format_number(project.stats.total_files),
+                        crate::utils::format::format_number(project.stats.total_code_lines)),
+                    Style::default().fg(Color::Gray),
+                )),
+            ])
+        })
+        .collect();
+    
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(" Projects [ ] "))
+        .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let mut state = ListState::default().with_selected(Some(app.selected_project));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
 pub fn render_main_content(f: &mut ratatui::Frame, area: Rect, app: &mut InteractiveApp) {
     if app.search_state.is_active {
         render_search(f, area, app);
@@ -143,10 +171,13 @@ pub fn render_overview(f: &mut ratatui::Frame, area: Rect, app: &InteractiveApp)
     }
 }
 
+/// Files the language analysis lists as most in need of refactoring
+const WORST_FILES_SHOWN: usize = 5;
+
 pub fn render_code_health(f: &mut ratatui::Frame, area: Rect, app: &InteractiveApp) {
     if let Some(ref stats) = app.stats {
         // Create comprehensive aggregated stats with real-time tracking
-        let stats_calculator = crate::core::stats::StatsCalculator::new();
+        let stats_calculator = crate::core::stats::StatsCalculator::new().with_worst_files(WORST_FILES_SHOWN);
         let aggregated_stats = stats_calculator.calculate_project_stats(stats, &app.individual_files)
             .unwrap_or_else(|_| {
                 // Fallback to basic aggregated stats if comprehensive calculation fails
@@ -166,9 +197,9 @@ pub fn render_code_health(f: &mut ratatui::Frame, area: Rect, app: &InteractiveA
 
 // Helper function to create aggregated stats from basic stats
 fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::aggregation::AggregatedStats {
-    use crate::core::stats::aggregation::AggregatedStats;
+    use crate::core::stats::aggregation::{AggregatedStats, FixtureStats, GeneratedStats, MinifiedStats};
     use crate::core::stats::basic::BasicStats;
-    use crate::core::stats::complexity::{ComplexityStats, ComplexityDistribution, StructureDistribution, QualityMetrics};
+    use crate::core::stats::complexity::{ComplexityStats, ComplexityDistribution, StructureDistribution, QualityMetrics, FunctionThresholds};
     use crate::core::stats::ratios::RatioStats;
     use crate::core::stats::aggregation::StatsMetadata;
     use std::collections::HashMap;
@@ -181,6 +212,13 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
         comment_lines: stats.total_comment_lines,
         blank_lines: stats.total_blank_lines,
         doc_lines: stats.total_doc_lines,
+        inline_comment_lines: stats.total_inline_comment_lines,
+        logical_lines: stats.total_logical_lines,
+        characters: stats.total_characters,
+        non_whitespace_characters: stats.total_non_whitespace_characters,
+        tokens: stats.total_tokens,
+        line_lengths: stats.line_lengths,
+        average_line_length: stats.line_lengths.average(stats.total_lines),
         total_size: stats.total_size,
         average_file_size: if stats.total_files > 0 { stats.total_size as f64 / stats.total_files as f64 } else { 0.0 },
         average_lines_per_file: if stats.total_files > 0 { stats.total_lines as f64 / stats.total_files as f64 } else { 0.0 },
@@ -195,9 +233,15 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
                     comment_lines: file_stats.comment_lines,
                     blank_lines: file_stats.blank_lines,
                     doc_lines: file_stats.doc_lines,
+                    characters: file_stats.characters,
+                    non_whitespace_characters: file_stats.non_whitespace_characters,
+                    tokens: file_stats.tokens,
+                    line_lengths: file_stats.line_lengths,
+                    average_line_length: file_stats.line_lengths.average(file_stats.total_lines),
                     total_size: file_stats.file_size,
                     average_lines_per_file: if *count > 0 { file_stats.total_lines as f64 / *count as f64 } else { 0.0 },
                     average_size_per_file: if *count > 0 { file_stats.file_size as f64 / *count as f64 } else { 0.0 },
+                    metrics: file_stats.metrics.clone(),
                 })
             })
             .collect(),
@@ -215,15 +259,18 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
         total_structures: 0,
         cyclomatic_complexity: 0.0,
         cognitive_complexity: 0.0,
-        maintainability_index: 85.0,
+        maintainability_index: 100.0, // As for a project without analyzed code
         average_function_length: 0.0,
         max_function_length: 0,
         min_function_length: 0,
         max_nesting_depth: 0,
         average_nesting_depth: 0.0,
         methods_per_class: 0.0,
+        max_methods_per_class: 0,
         average_parameters_per_function: 0.0,
         max_parameters_per_function: 0,
+        average_return_paths_per_function: 0.0,
+        max_return_paths_per_function: 0,
         complexity_by_extension: HashMap::new(),
         complexity_distribution: ComplexityDistribution {
             very_low_complexity: 0,
@@ -241,9 +288,13 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
             modules: 0,
         },
         function_complexity_details: Vec::new(),
+        long_parameter_functions: Vec::new(),
+        complex_functions: Vec::new(),
+        function_thresholds: FunctionThresholds::default(),
+        function_length_distribution: Default::default(),
         quality_metrics: QualityMetrics {
             code_health_score: 85.0,
-            maintainability_index: 85.0,
+            maintainability_index: 100.0,
             documentation_coverage: 80.0,
             avg_complexity: 0.0,
             function_size_health: 90.0,
@@ -251,6 +302,7 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
             code_duplication_ratio: 5.0,
             technical_debt_ratio: 10.0,
         },
+        worst_files: Vec::new(),
     };
     
     // Create placeholder ratio stats
@@ -261,6 +313,8 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
         blank_ratio: if stats.total_lines > 0 { stats.total_blank_lines as f64 / stats.total_lines as f64 } else { 0.0 },
         comment_to_code_ratio: if stats.total_code_lines > 0 { stats.total_comment_lines as f64 / stats.total_code_lines as f64 } else { 0.0 },
         doc_to_code_ratio: if stats.total_code_lines > 0 { stats.total_doc_lines as f64 / stats.total_code_lines as f64 } else { 0.0 },
+        comment_density: if stats.total_lines > 0 { (stats.total_comment_lines + stats.total_inline_comment_lines) as f64 / stats.total_lines as f64 } else { 0.0 },
+        test_to_code_ratio: 0.0,
         ratios_by_extension: HashMap::new(),
         language_distribution: HashMap::new(),
         file_distribution: HashMap::new(),
@@ -273,6 +327,7 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
             maintainability_score: 85.0,
             readability_score: 80.0,
             consistency_score: 75.0,
+            style_score: 100.0,
         },
     };
     
@@ -284,7 +339,11 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
         file_count_analyzed: stats.total_files,
         total_bytes_analyzed: stats.total_size,
         languages_detected: stats.stats_by_extension.keys().cloned().collect(),
+        complexity_not_analyzed: Vec::new(),
         analysis_depth: crate::core::stats::aggregation::AnalysisDepth::Basic,
+        capabilities: crate::core::stats::aggregation::AnalysisCapabilities::default(),
+        performance: None,
+        incomplete: None,
     };
     
     AggregatedStats {
@@ -292,6 +351,22 @@ fn create_aggregated_stats_from_basic(stats: &CodeStats) -> crate::core::stats::
         complexity: complexity_stats,
         ratios: ratio_stats,
         metadata,
+        fixtures: FixtureStats::default(),
+        generated: GeneratedStats::default(),
+        minified: MinifiedStats::default(),
+        non_utf8_files: Vec::new(),
+        skipped_files: Vec::new(),
+        normalization_conflicts: Vec::new(),
+        per_path: Vec::new(),
+        nested_projects: Vec::new(),
+        areas: Vec::new(),
+        budgets: Vec::new(),
+        categories: Vec::new(),
+        ownership: None,
+        churn: None,
+        dependencies: None,
+        licenses: None,
+        test_split: None,
     }
 }
 
@@ -307,9 +382,15 @@ fn convert_to_extension_stats(stats_by_extension: &std::collections::HashMap<Str
             comment_lines: file_stats.comment_lines,
             doc_lines: file_stats.doc_lines,
             blank_lines: file_stats.blank_lines,
+            characters: file_stats.characters,
+            non_whitespace_characters: file_stats.non_whitespace_characters,
+            tokens: file_stats.tokens,
+            line_lengths: file_stats.line_lengths,
+            average_line_length: file_stats.line_lengths.average(file_stats.total_lines),
             total_size: file_stats.file_size,
             average_lines_per_file: if *file_count > 0 { file_stats.total_lines as f64 / *file_count as f64 } else { 0.0 },
             average_size_per_file: if *file_count > 0 { file_stats.file_size as f64 / *file_count as f64 } else { 0.0 },
+            metrics: file_stats.metrics.clone(),
         };
         (ext.clone(), extension_stats)
     }).collect()
@@ -777,19 +858,6 @@ fn render_language_stats_summary(f: &mut ratatui::Frame, area: Rect, app: &Inter
     f.render_widget(summary_paragraph, area);
 }
 
-fn parse_hex_color(hex: &str) -> Color {
-    if hex.starts_with('#') && hex.len() == 7 {
-        if let (Ok(r), Ok(g), Ok(b)) = (
-            u8::from_str_radix(&hex[1..3], 16),
-            u8::from_str_radix(&hex[3..5], 16),
-            u8::from_str_radix(&hex[5..7], 16),
-        ) {
-            return Color::Rgb(r, g, b);
-        }
-    }
-    Color::White
-}
-
 fn render_language_details_table(f: &mut ratatui::Frame, area: Rect, app: &mut InteractiveApp) {
     let header = Row::new(vec![
         Cell::from("Language"),
@@ -868,6 +936,7 @@ pub fn render_help(f: &mut ratatui::Frame, area: Rect) {
         Line::from("  ↑/↓ or j/k        - Scroll up/down"),
         Line::from("  Page Up/Down      - Scroll by page"),
         Line::from("  Home/End          - Go to top/bottom"),
+        Line::from("  [ / ]             - Previous/next project (several paths or nested projects)"),
         Line::from(""),
         Line::from(vec![
             Span::styled("Search:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
@@ -932,6 +1001,14 @@ pub fn render_footer(f: &mut ratatui::Frame, area: Rect, app: &InteractiveApp) {
         Span::styled(" to search", Style::default().fg(Color::White)),
     ];
     
+    if app.has_projects() {
+        footer_spans.extend(vec![
+            Span::styled(", ", Style::default().fg(Color::White)),
+            Span::styled("[ ]", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled(" to switch projects", Style::default().fg(Color::White)),
+        ]);
+    }
+    
     if app.search_state.is_active {
         footer_spans = vec![
             Span::styled("Search Mode: ", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
@@ -1,23 +1,11 @@
+use crate::utils::languages::LanguageRegistry;
+use crate::utils::linguist;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
+    style::Color,
 };
 
-pub fn format_size(size: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
-    let mut size = size as f64;
-    let mut unit_index = 0;
-    
-    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size /= 1024.0;
-        unit_index += 1;
-    }
-    
-    if unit_index == 0 {
-        format!("{} {}", size as u64, UNITS[unit_index])
-    } else {
-        format!("{:.1} {}", size, UNITS[unit_index])
-    }
-}
+pub use crate::utils::format::format_size;
 
 pub fn get_extension_icon(ext: &str) -> &'static str {
     match ext {
@@ -76,277 +64,392 @@ pub struct LanguageInfo {
     pub extensions: Vec<String>,
 }
 
-/// Map file extension to programming language
+/// Parse a `#rrggbb` color into a terminal RGB color
+pub fn parse_hex_color(hex: &str) -> Color {
+    if hex.starts_with('#') && hex.len() == 7 {
+        if let (Ok(r), Ok(g), Ok(b)) = (
+            u8::from_str_radix(&hex[1..3], 16),
+            u8::from_str_radix(&hex[3..5], 16),
+            u8::from_str_radix(&hex[5..7], 16),
+        ) {
+            return Color::Rgb(r, g, b);
+        }
+    }
+    Color::White
+}
+
+/// Map a statistics key, a language name or a file extension, to its language
 pub fn get_language_from_extension(ext: &str) -> LanguageInfo {
+    match LanguageRegistry::new().named(ext) {
+        Some(language) => LanguageInfo {
+            name: language.name.to_string(),
+            extensions: language.extensions.iter().map(|extension| extension.to_string()).collect(),
+            ..extension_info(language.extensions[0])
+        },
+        None => extension_info(ext),
+    }
+}
+
+fn extension_info(ext: &str) -> LanguageInfo {
     match ext {
         "rs" => LanguageInfo {
             name: "Rust".to_string(),
             icon: "🦀".to_string(),
-            color: "#dea584".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["rs".to_string()],
         },
         "py" => LanguageInfo {
             name: "Python".to_string(),
             icon: "🐍".to_string(),
-            color: "#3776ab".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["py".to_string()],
         },
         "js" => LanguageInfo {
             name: "JavaScript".to_string(),
             icon: "📜".to_string(),
-            color: "#f7df1e".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["js".to_string()],
         },
         "jsx" => LanguageInfo {
             name: "React JSX".to_string(),
             icon: "⚛️".to_string(),
-            color: "#61dafb".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["jsx".to_string()],
         },
         "ts" => LanguageInfo {
             name: "TypeScript".to_string(),
             icon: "📘".to_string(),
-            color: "#3178c6".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["ts".to_string()],
         },
         "tsx" => LanguageInfo {
             name: "React TSX".to_string(),
             icon: "⚛️".to_string(),
-            color: "#61dafb".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["tsx".to_string()],
         },
         "html" => LanguageInfo {
             name: "HTML".to_string(),
             icon: "🌐".to_string(),
-            color: "#e34f26".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["html".to_string()],
         },
         "css" => LanguageInfo {
             name: "CSS".to_string(),
             icon: "🎨".to_string(),
-            color: "#1572b6".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["css".to_string()],
         },
         "scss" | "sass" => LanguageInfo {
             name: "Sass".to_string(),
             icon: "🎨".to_string(),
-            color: "#cf649a".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["scss".to_string(), "sass".to_string()],
         },
         "java" => LanguageInfo {
             name: "Java".to_string(),
             icon: "☕".to_string(),
-            color: "#ed8b00".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["java".to_string()],
         },
         "c" => LanguageInfo {
             name: "C".to_string(),
             icon: "⚡".to_string(),
-            color: "#00599c".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["c".to_string()],
         },
         "cpp" | "cc" | "cxx" => LanguageInfo {
             name: "C++".to_string(),
             icon: "⚡".to_string(),
-            color: "#00599c".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["cpp".to_string(), "cc".to_string(), "cxx".to_string()],
         },
         "h" | "hpp" => LanguageInfo {
             name: "C/C++ Header".to_string(),
             icon: "📎".to_string(),
-            color: "#00599c".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["h".to_string(), "hpp".to_string()],
         },
         "go" => LanguageInfo {
             name: "Go".to_string(),
             icon: "🐹".to_string(),
-            color: "#00add8".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["go".to_string()],
         },
         "php" => LanguageInfo {
             name: "PHP".to_string(),
             icon: "🐘".to_string(),
-            color: "#777bb4".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["php".to_string()],
         },
         "rb" => LanguageInfo {
             name: "Ruby".to_string(),
             icon: "💎".to_string(),
-            color: "#cc342d".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["rb".to_string()],
         },
         "swift" => LanguageInfo {
             name: "Swift".to_string(),
             icon: "🍎".to_string(),
-            color: "#fa7343".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["swift".to_string()],
         },
         "kt" => LanguageInfo {
             name: "Kotlin".to_string(),
             icon: "🎯".to_string(),
-            color: "#7f52ff".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["kt".to_string()],
         },
         "scala" => LanguageInfo {
             name: "Scala".to_string(),
             icon: "🎭".to_string(),
-            color: "#dc322f".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["scala".to_string()],
         },
         "cs" => LanguageInfo {
             name: "C#".to_string(),
             icon: "🔷".to_string(),
-            color: "#239120".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["cs".to_string()],
         },
         "sh" | "bash" | "zsh" => LanguageInfo {
             name: "Shell".to_string(),
             icon: "🐚".to_string(),
-            color: "#89e051".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["sh".to_string(), "bash".to_string(), "zsh".to_string()],
         },
         "json" => LanguageInfo {
             name: "JSON".to_string(),
             icon: "📋".to_string(),
-            color: "#000000".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["json".to_string()],
         },
         "xml" => LanguageInfo {
             name: "XML".to_string(),
             icon: "📄".to_string(),
-            color: "#e37933".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["xml".to_string()],
         },
         "yaml" | "yml" => LanguageInfo {
             name: "YAML".to_string(),
             icon: "⚙️".to_string(),
-            color: "#cb171e".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["yaml".to_string(), "yml".to_string()],
         },
         "toml" => LanguageInfo {
             name: "TOML".to_string(),
             icon: "🔧".to_string(),
-            color: "#9c4221".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["toml".to_string()],
         },
         "md" => LanguageInfo {
             name: "Markdown".to_string(),
             icon: "📝".to_string(),
-            color: "#083fa1".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["md".to_string()],
         },
         "txt" => LanguageInfo {
             name: "Text".to_string(),
             icon: "📄".to_string(),
-            color: "#6c757d".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["txt".to_string()],
         },
         "sql" => LanguageInfo {
             name: "SQL".to_string(),
             icon: "🗃️".to_string(),
-            color: "#e38c00".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["sql".to_string()],
         },
         "r" => LanguageInfo {
             name: "R".to_string(),
             icon: "📊".to_string(),
-            color: "#198ce7".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["r".to_string()],
         },
         "dart" => LanguageInfo {
             name: "Dart".to_string(),
             icon: "🎯".to_string(),
-            color: "#0175c2".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["dart".to_string()],
         },
         "hs" | "lhs" | "hsc" => LanguageInfo {
             name: "Haskell".to_string(),
             icon: "λ".to_string(),
-            color: "#5e5086".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["hs".to_string(), "lhs".to_string(), "hsc".to_string()],
         },
         "ex" | "exs" | "eex" => LanguageInfo {
             name: "Elixir".to_string(),
             icon: "💧".to_string(),
-            color: "#6e4a7e".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["ex".to_string(), "exs".to_string(), "eex".to_string()],
         },
         "erl" | "hrl" => LanguageInfo {
             name: "Erlang".to_string(),
             icon: "📞".to_string(),
-            color: "#b83998".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["erl".to_string(), "hrl".to_string()],
         },
         "jl" => LanguageInfo {
             name: "Julia".to_string(),
             icon: "🔬".to_string(),
-            color: "#9558b2".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["jl".to_string()],
         },
         "lua" => LanguageInfo {
             name: "Lua".to_string(),
             icon: "🌙".to_string(),
-            color: "#000080".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["lua".to_string()],
         },
         "pl" | "pm" | "pod" => LanguageInfo {
             name: "Perl".to_string(),
             icon: "🐪".to_string(),
-            color: "#0298c3".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["pl".to_string(), "pm".to_string(), "pod".to_string()],
         },
         "m" => LanguageInfo {
             name: "MATLAB".to_string(),
             icon: "📊".to_string(),
-            color: "#e16737".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["m".to_string()],
         },
         "zig" => LanguageInfo {
             name: "Zig".to_string(),
             icon: "⚡".to_string(),
-            color: "#ec915c".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["zig".to_string()],
         },
+        "cob" | "cbl" | "cpy" => LanguageInfo {
+            name: "COBOL".to_string(),
+            icon: "🏦".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["cob".to_string(), "cbl".to_string(), "cpy".to_string()],
+        },
+        "f" | "for" | "f77" | "ftn" | "f90" | "f95" | "f03" | "f08" => LanguageInfo {
+            name: "Fortran".to_string(),
+            icon: "🧮".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["f".to_string(), "for".to_string(), "f77".to_string(), "ftn".to_string(), "f90".to_string(), "f95".to_string(), "f03".to_string(), "f08".to_string()],
+        },
+        "adb" | "ads" | "ada" => LanguageInfo {
+            name: "Ada".to_string(),
+            icon: "🛡️".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["adb".to_string(), "ads".to_string(), "ada".to_string()],
+        },
+        "pas" | "dpr" | "lpr" => LanguageInfo {
+            name: "Pascal".to_string(),
+            icon: "📐".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["pas".to_string(), "dpr".to_string(), "lpr".to_string()],
+        },
+        "tf" | "tfvars" | "hcl" => LanguageInfo {
+            name: "HCL".to_string(),
+            icon: "🏗️".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["tf".to_string(), "tfvars".to_string(), "hcl".to_string()],
+        },
+        "proto" => LanguageInfo {
+            name: "Protocol Buffers".to_string(),
+            icon: "📦".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["proto".to_string()],
+        },
+        "graphql" | "gql" => LanguageInfo {
+            name: "GraphQL".to_string(),
+            icon: "◈".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["graphql".to_string(), "gql".to_string()],
+        },
+        "nix" => LanguageInfo {
+            name: "Nix".to_string(),
+            icon: "❄️".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["nix".to_string()],
+        },
+        "s" | "asm" => LanguageInfo {
+            name: "Assembly".to_string(),
+            icon: "🔩".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["s".to_string(), "asm".to_string()],
+        },
+        "v" | "vh" | "sv" | "svh" => LanguageInfo {
+            name: "Verilog".to_string(),
+            icon: "🔌".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["v".to_string(), "vh".to_string(), "sv".to_string(), "svh".to_string()],
+        },
+        "vhd" | "vhdl" => LanguageInfo {
+            name: "VHDL".to_string(),
+            icon: "🔌".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["vhd".to_string(), "vhdl".to_string()],
+        },
+        "cu" | "cuh" => LanguageInfo {
+            name: "CUDA".to_string(),
+            icon: "🟩".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["cu".to_string(), "cuh".to_string()],
+        },
+        "makefile" => LanguageInfo {
+            name: "Makefile".to_string(),
+            icon: "🛠️".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["makefile".to_string()],
+        },
+        "dockerfile" => LanguageInfo {
+            name: "Dockerfile".to_string(),
+            icon: "🐳".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["dockerfile".to_string()],
+        },
+        "cmake" => LanguageInfo {
+            name: "CMake".to_string(),
+            icon: "🛠️".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
+            extensions: vec!["cmake".to_string()],
+        },
         "clj" | "cljs" | "cljc" => LanguageInfo {
             name: "Clojure".to_string(),
             icon: "🔄".to_string(),
-            color: "#db5855".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["clj".to_string(), "cljs".to_string(), "cljc".to_string()],
         },
         "ps1" | "psm1" | "psd1" => LanguageInfo {
             name: "PowerShell".to_string(),
             icon: "⚡".to_string(),
-            color: "#012456".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["ps1".to_string(), "psm1".to_string(), "psd1".to_string()],
         },
         "bat" | "cmd" => LanguageInfo {
             name: "Batch".to_string(),
             icon: "⚙️".to_string(),
-            color: "#c1f12e".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["bat".to_string(), "cmd".to_string()],
         },
         "vb" | "vbs" => LanguageInfo {
             name: "Visual Basic".to_string(),
             icon: "🔷".to_string(),
-            color: "#945db7".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["vb".to_string(), "vbs".to_string()],
         },
         "mlx" => LanguageInfo {
             name: "MATLAB".to_string(),
             icon: "📊".to_string(),
-            color: "#e16737".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["m".to_string(), "mlx".to_string()],
         },
         "rmd" | "Rmd" => LanguageInfo {
             name: "R Markdown".to_string(),
             icon: "📊".to_string(),
-            color: "#198ce7".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec!["r".to_string(), "R".to_string(), "rmd".to_string(), "Rmd".to_string()],
         },
         _ => LanguageInfo {
             name: "Unknown".to_string(),
             icon: "📄".to_string(),
-            color: "#6c757d".to_string(),
+            color: linguist::language_color_or_default(ext).to_string(),
             extensions: vec![ext.to_string()],
         },
     }
@@ -367,6 +470,11 @@ pub fn group_extensions_by_language(stats_by_extension: &std::collections::HashM
             existing_stats.code_lines += file_stats.code_lines;
             existing_stats.comment_lines += file_stats.comment_lines;
             existing_stats.doc_lines += file_stats.doc_lines;
+            existing_stats.inline_comment_lines += file_stats.inline_comment_lines;
+            existing_stats.logical_lines += file_stats.logical_lines;
+            existing_stats.characters += file_stats.characters;
+            existing_stats.non_whitespace_characters += file_stats.non_whitespace_characters;
+            existing_stats.tokens += file_stats.tokens;
             existing_stats.blank_lines += file_stats.blank_lines;
             existing_stats.file_size += file_stats.file_size;
             
@@ -2,6 +2,8 @@ pub mod cli;
 pub mod interactive;
 pub mod html;
 pub mod sarif;
+pub mod prometheus;
+pub mod compat;
 pub mod filters;
 
 // Re-export commonly used types  
@@ -0,0 +1,243 @@
+use crate::core::stats::AggregatedStats;
+use std::fmt::Write;
+
+/// Renders analysis results in the Prometheus text exposition format,
+/// suitable for a node_exporter textfile collector or a Pushgateway.
+pub struct PrometheusExporter;
+
+impl PrometheusExporter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, stats: &AggregatedStats) -> String {
+        let mut output = String::new();
+
+        Self::write_metric(&mut output, "howmany_files", "gauge", "Number of files analyzed", &[
+            (String::new(), stats.basic.total_files as f64),
+        ]);
+
+        Self::write_metric(&mut output, "howmany_lines", "gauge", "Lines by kind", &[
+            ("kind=\"code\"".to_string(), stats.basic.code_lines as f64),
+            ("kind=\"comment\"".to_string(), stats.basic.comment_lines as f64),
+            ("kind=\"doc\"".to_string(), stats.basic.doc_lines as f64),
+            ("kind=\"blank\"".to_string(), stats.basic.blank_lines as f64),
+        ]);
+
+        let mut languages: Vec<_> = stats.basic.stats_by_extension.iter().collect();
+        languages.sort_by(|a, b| a.0.cmp(b.0));
+        let language_lines: Vec<_> = languages
+            .iter()
+            .map(|(ext, ext_stats)| (format!("extension=\"{}\"", Self::escape(ext)), ext_stats.code_lines as f64))
+            .collect();
+        Self::write_metric(&mut output, "howmany_extension_code_lines", "gauge", "Code lines per file extension", &language_lines);
+
+        let area_files: Vec<_> = stats.areas
+            .iter()
+            .map(|area| (format!("area=\"{}\"", Self::escape(&area.name)), area.basic.total_files as f64))
+            .collect();
+        Self::write_metric(&mut output, "howmany_area_files", "gauge", "Files per configured area", &area_files);
+        
+        let area_lines: Vec<_> = stats.areas
+            .iter()
+            .map(|area| (format!("area=\"{}\"", Self::escape(&area.name)), area.basic.code_lines as f64))
+            .collect();
+        Self::write_metric(&mut output, "howmany_area_code_lines", "gauge", "Code lines per configured area", &area_lines);
+
+        let budget_usage: Vec<_> = stats.budgets
+            .iter()
+            .flat_map(|budget| budget.usage.iter().map(move |usage| (
+                format!("budget=\"{}\",metric=\"{}\"", Self::escape(&budget.pattern), usage.metric.name()),
+                usage.percent,
+            )))
+            .collect();
+        Self::write_metric(&mut output, "howmany_budget_used_percent", "gauge", "Share of each configured budget cap in use", &budget_usage);
+
+        if let Some(split) = &stats.test_split {
+            Self::write_metric(&mut output, "howmany_role_code_lines", "gauge", "Code lines in production and test code", &[
+                ("role=\"production\"".to_string(), split.production.code_lines as f64),
+                ("role=\"test\"".to_string(), split.test.code_lines as f64),
+            ]);
+        }
+        Self::write_metric(&mut output, "howmany_test_to_code_ratio", "gauge", "Test code lines per production code line", &[
+            (String::new(), stats.ratios.test_to_code_ratio),
+        ]);
+
+        if stats.metadata.capabilities.complexity {
+            Self::write_metric(&mut output, "howmany_functions", "gauge", "Functions found by complexity analysis", &[
+                (String::new(), stats.complexity.function_count as f64),
+            ]);
+            Self::write_metric(&mut output, "howmany_code_health_score", "gauge", "Overall code health score (0-100)", &[
+                (String::new(), stats.complexity.quality_metrics.code_health_score),
+            ]);
+        }
+
+        if let Some(performance) = &stats.metadata.performance {
+            Self::write_metric(&mut output, "howmany_cache_hits", "gauge", "File cache hits during the run", &[
+                (String::new(), performance.cache_hits as f64),
+            ]);
+            Self::write_metric(&mut output, "howmany_cache_misses", "gauge", "File cache misses during the run", &[
+                (String::new(), performance.cache_misses as f64),
+            ]);
+            Self::write_metric(&mut output, "howmany_cache_hit_ratio", "gauge", "Share of files served from the cache (0-1)", &[
+                (String::new(), performance.cache_hit_rate),
+            ]);
+            Self::write_metric(&mut output, "howmany_cache_entries", "gauge", "Entries in the file cache after the run", &[
+                (String::new(), performance.cache_entries as f64),
+            ]);
+            Self::write_metric(&mut output, "howmany_binary_files_skipped", "gauge", "Files skipped for binary content", &[
+                (String::new(), performance.binary_files_skipped as f64),
+            ]);
+            Self::write_metric(&mut output, "howmany_analysis_duration_seconds", "gauge", "Total analysis time", &[
+                (String::new(), performance.total_time_ms as f64 / 1000.0),
+            ]);
+
+            let phases: Vec<_> = performance.phase_timings_ms
+                .iter()
+                .map(|(phase, ms)| (format!("phase=\"{}\"", Self::escape(phase)), *ms as f64 / 1000.0))
+                .collect();
+            Self::write_metric(&mut output, "howmany_phase_duration_seconds", "gauge", "Analysis time per phase", &phases);
+        }
+
+        output
+    }
+
+    fn write_metric(output: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let _ = writeln!(output, "# HELP {} {}", name, help);
+        let _ = writeln!(output, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            if labels.is_empty() {
+                let _ = writeln!(output, "{} {}", name, value);
+            } else {
+                let _ = writeln!(output, "{}{{{}}} {}", name, labels, value);
+            }
+        }
+    }
+
+    /// Escape a label value as required by the exposition format
+    fn escape(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+    }
+}
+
+impl Default for PrometheusExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::stats::aggregation::{AreaStats, RunPerformance};
+    use crate::core::stats::StatsCalculator;
+    use crate::core::types::CodeStats;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_render_includes_cache_metrics() {
+        let code_stats = CodeStats {
+            total_files: 2,
+            total_lines: 30,
+            total_code_lines: 20,
+            total_comment_lines: 5,
+            total_blank_lines: 5,
+            total_size: 1024,
+            total_doc_lines: 0,
+            stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
+        };
+        let mut stats = StatsCalculator::new().calculate_project_stats(&code_stats, &[]).unwrap();
+        stats.metadata.performance = Some(RunPerformance {
+            cache_hits: 9,
+            cache_misses: 1,
+            cache_hit_rate: 0.9,
+            cache_entries: 10,
+            binary_files_skipped: 0,
+            total_time_ms: 1500,
+            phase_timings_ms: [("counting".to_string(), 250)].into_iter().collect(),
+        });
+
+        let output = PrometheusExporter::new().render(&stats);
+
+        assert!(output.contains("# TYPE howmany_files gauge\nhowmany_files 2\n"));
+        assert!(output.contains("howmany_lines{kind=\"code\"} 20\n"));
+        assert!(output.contains("howmany_cache_hits 9\n"));
+        assert!(output.contains("howmany_cache_hit_ratio 0.9\n"));
+        assert!(output.contains("howmany_analysis_duration_seconds 1.5\n"));
+        assert!(output.contains("howmany_phase_duration_seconds{phase=\"counting\"} 0.25\n"));
+        // No extensions means no per-extension samples, and no dangling HELP line
+        assert!(!output.contains("howmany_extension_code_lines"));
+    }
+
+    #[test]
+    fn test_render_area_metrics() {
+        let code_stats = CodeStats {
+            total_files: 3,
+            total_lines: 40,
+            total_code_lines: 30,
+            total_comment_lines: 5,
+            total_blank_lines: 5,
+            total_size: 2048,
+            total_doc_lines: 0,
+            stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
+        };
+        let mut stats = StatsCalculator::new().calculate_project_stats(&code_stats, &[]).unwrap();
+        stats.areas.push(AreaStats {
+            name: "frontend".to_string(),
+            basic: stats.basic.clone(),
+        });
+
+        let output = PrometheusExporter::new().render(&stats);
+
+        assert!(output.contains("howmany_area_files{area=\"frontend\"} 3\n"));
+        assert!(output.contains("howmany_area_code_lines{area=\"frontend\"} 30\n"));
+    }
+
+    #[test]
+    fn test_render_omits_analyses_that_did_not_run() {
+        let code_stats = CodeStats {
+            total_files: 1,
+            total_lines: 10,
+            total_code_lines: 10,
+            total_comment_lines: 0,
+            total_blank_lines: 0,
+            total_size: 100,
+            total_doc_lines: 0,
+            stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
+        };
+        // Without individual files no function was analyzed
+        let mut stats = StatsCalculator::new().calculate_project_stats(&code_stats, &[]).unwrap();
+        assert!(!stats.metadata.capabilities.complexity);
+
+        let output = PrometheusExporter::new().render(&stats);
+        assert!(!output.contains("howmany_code_health_score"));
+        assert!(!output.contains("howmany_functions"));
+
+        stats.metadata.capabilities.complexity = true;
+        let output = PrometheusExporter::new().render(&stats);
+        assert!(output.contains("howmany_functions 0\n"));
+        assert!(output.contains("# TYPE howmany_code_health_score gauge\n"));
+    }
+}
@@ -1,18 +1,25 @@
+use crate::core::detector::extension_key;
 use crate::core::types::{CodeStats, FileStats};
 use crate::core::stats::AggregatedStats;
+use crate::core::stats::complexity::{FunctionComplexityDetail, FunctionLengthDistribution};
 use crate::utils::errors::Result;
+use crate::utils::format::format_number;
+use super::rules::{SarifRule, SarifRuleSet, SIZE_OUTLIER_MIN_FILES, SIZE_OUTLIER_MIN_LINES};
 use serde_sarif::sarif::{
     Sarif, Run, Tool, ToolComponent, Result as SarifResult, 
     Location, PhysicalLocation, ArtifactLocation, Region,
     Message, ReportingDescriptor, MultiformatMessageString,
-    RunAutomationDetails, ResultKind, ResultLevel
+    RunAutomationDetails, ResultKind
 };
 use serde_json::Value;
 use chrono::Utc;
+use std::collections::HashMap;
+use std::path::Path;
 
 pub struct SarifConverter {
     tool_name: String,
     tool_version: String,
+    rules: SarifRuleSet,
 }
 
 impl SarifConverter {
@@ -20,9 +27,21 @@ impl SarifConverter {
         Self {
             tool_name: "howmany".to_string(),
             tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            rules: SarifRuleSet::new(),
         }
     }
 
+    /// Use a custom rule set (thresholds, severities, enabled rules)
+    pub fn with_rules(mut self, rules: SarifRuleSet) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Get the active rule set
+    pub fn rules(&self) -> &SarifRuleSet {
+        &self.rules
+    }
+
     /// Get the tool name
     pub fn tool_name(&self) -> &str {
         &self.tool_name
@@ -48,6 +67,7 @@ impl SarifConverter {
                 results.extend(quality_results);
             }
         }
+        results.extend(self.analyze_size_outliers(individual_files));
 
         // Add project-level summary results
         results.extend(self.create_project_summary_results(stats));
@@ -63,11 +83,11 @@ impl SarifConverter {
     ) -> Result<Sarif> {
         let mut results = Vec::new();
 
-        // Generate detailed complexity and quality results
+        // Generate detailed file-level quality results
         for (file_path, file_stats) in individual_files {
-            // Complexity analysis results
-            if let Some(complexity_results) = self.analyze_file_complexity(file_path, file_stats, aggregated_stats) {
-                results.extend(complexity_results);
+            // Oversized, undocumented and empty files
+            if let Some(quality_results) = self.analyze_file_quality(file_path, file_stats) {
+                results.extend(quality_results);
             }
 
             // Quality metrics results
@@ -75,6 +95,49 @@ impl SarifConverter {
                 results.extend(quality_results);
             }
         }
+        results.extend(self.analyze_size_outliers(individual_files));
+
+        // Function-level complexity findings
+        for function in &aggregated_stats.complexity.complex_functions {
+            results.extend(self.analyze_function_complexity(function));
+        }
+
+        // Function-level long parameter list findings
+        let max_parameters = self.rules.threshold("HM104");
+        for finding in &aggregated_stats.complexity.long_parameter_functions {
+            if finding.parameter_count as f64 <= max_parameters {
+                continue;
+            }
+            results.push(self.create_result(
+                "HM104",
+                &format!("Function '{}' takes {} parameters (threshold {}), consider grouping related parameters",
+                    finding.name, finding.parameter_count, max_parameters),
+                &finding.file_path,
+                Some(self.create_line_region(finding.start_line)),
+            ));
+        }
+
+        // Areas written almost entirely by one author
+        if let Some(ownership) = &aggregated_stats.ownership {
+            for area in ownership.directories.iter().filter(|area| area.concentrated) {
+                results.push(self.create_result(
+                    "HM501",
+                    &format!("{:.1}% of the {} lines in this directory were written by {}",
+                        area.top_author_share * 100.0, area.lines, area.top_author),
+                    &area.name,
+                    None,
+                ));
+            }
+            for area in ownership.languages.iter().filter(|area| area.concentrated) {
+                results.push(self.create_result(
+                    "HM501",
+                    &format!("{:.1}% of the {} lines of .{} code were written by {}",
+                        area.top_author_share * 100.0, area.lines, area.name, area.top_author),
+                    "project://ownership",
+                    None,
+                ));
+            }
+        }
 
         // Add comprehensive project-level results
         results.extend(self.create_comprehensive_project_results(aggregated_stats));
@@ -87,14 +150,12 @@ impl SarifConverter {
         let mut results = Vec::new();
 
         // Large file warning
-        if file_stats.total_lines > 1000 {
+        if file_stats.total_lines as f64 > self.rules.threshold("HM001") {
             results.push(self.create_result(
                 "HM001",
-                "Large File",
-                &format!("File has {} lines, consider breaking it into smaller modules", file_stats.total_lines),
-                "warning",
+                &format!("File has {} lines, consider breaking it into smaller modules", format_number(file_stats.total_lines)),
                 file_path,
-                None,
+                Some(self.create_line_region(1)),
             ));
         }
 
@@ -105,14 +166,12 @@ impl SarifConverter {
             0.0
         };
 
-        if doc_ratio < 0.1 && file_stats.code_lines > 50 {
+        if doc_ratio < self.rules.threshold("HM002") && file_stats.code_lines > 50 {
             results.push(self.create_result(
                 "HM002",
-                "Low Documentation",
                 &format!("Documentation ratio is {:.1}%, consider adding more comments and documentation", doc_ratio * 100.0),
-                "info",
                 file_path,
-                None,
+                Some(self.create_line_region(1)),
             ));
         }
 
@@ -120,72 +179,106 @@ impl SarifConverter {
         if file_stats.code_lines == 0 {
             results.push(self.create_result(
                 "HM003",
-                "Empty File",
                 "File contains no code lines, consider removing if unused",
-                "note",
                 file_path,
-                None,
+                Some(self.create_line_region(1)),
             ));
         }
 
         if results.is_empty() { None } else { Some(results) }
     }
 
-    /// Analyze file complexity using AggregatedStats
-    fn analyze_file_complexity(
-        &self,
-        file_path: &str,
-        _file_stats: &FileStats,
-        aggregated_stats: &AggregatedStats,
-    ) -> Option<Vec<SarifResult>> {
-        let mut results = Vec::new();
-
-        // Get file extension for complexity analysis
-        let extension = std::path::Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("unknown");
+    /// Files many times the median length of the files of their language, so
+    /// what counts as large follows each language's norms in the project
+    fn analyze_size_outliers(&self, individual_files: &[(String, FileStats)]) -> Vec<SarifResult> {
+        let extension_of = |file_path: &str, file_stats: &FileStats| {
+            file_stats.language_key(extension_key(Path::new(file_path)).unwrap_or_default()).to_lowercase()
+        };
 
-        // Check if we have complexity data for this extension
-        if let Some(complexity_data) = aggregated_stats.complexity.complexity_by_extension.get(extension) {
-            // High complexity warning
-            if complexity_data.cyclomatic_complexity > 15.0 {
-                results.push(self.create_result(
-                    "HM101",
-                    "High Complexity",
-                    &format!("Average cyclomatic complexity is {:.1}, consider refactoring", complexity_data.cyclomatic_complexity),
-                    "warning",
-                    file_path,
-                    None,
-                ));
+        let mut lengths_by_extension: HashMap<String, Vec<usize>> = HashMap::new();
+        for (file_path, file_stats) in individual_files {
+            lengths_by_extension.entry(extension_of(file_path, file_stats)).or_default().push(file_stats.total_lines);
+        }
+        let medians: HashMap<String, usize> = lengths_by_extension
+            .into_iter()
+            .filter(|(_, lengths)| lengths.len() >= SIZE_OUTLIER_MIN_FILES)
+            // The same nearest-rank median as the function length distribution
+            .map(|(extension, lengths)| (extension, FunctionLengthDistribution::from_lengths(&lengths).p50))
+            .collect();
+
+        let ratio = self.rules.threshold("HM004");
+        let mut results = Vec::new();
+        for (file_path, file_stats) in individual_files {
+            let extension = extension_of(file_path, file_stats);
+            let Some(&median) = medians.get(&extension) else {
+                continue;
+            };
+            if median == 0 || file_stats.total_lines < SIZE_OUTLIER_MIN_LINES {
+                continue;
             }
-
-            // Cognitive complexity issues
-            if complexity_data.cognitive_complexity > 20.0 {
+            let times_median = file_stats.total_lines as f64 / median as f64;
+            if times_median >= ratio {
                 results.push(self.create_result(
-                    "HM102",
-                    "High Cognitive Complexity",
-                    &format!("Cognitive complexity is {:.1}, may be difficult to understand", complexity_data.cognitive_complexity),
-                    "warning",
+                    "HM004",
+                    &format!("File has {} lines, {:.0}x the median of {} lines for .{} files in this project",
+                        format_number(file_stats.total_lines), times_median, format_number(median), extension),
                     file_path,
-                    None,
+                    Some(self.create_line_region(1)),
                 ));
             }
+        }
+        results
+    }
 
-            // Deep nesting warning
-            if complexity_data.max_nesting_depth > 5 {
-                results.push(self.create_result(
-                    "HM103",
-                    "Deep Nesting",
-                    &format!("Maximum nesting depth is {}, consider extracting nested logic", complexity_data.max_nesting_depth),
-                    "info",
-                    file_path,
-                    None,
-                ));
-            }
+    /// Create results for a single function exceeding complexity thresholds
+    fn analyze_function_complexity(&self, function: &FunctionComplexityDetail) -> Vec<SarifResult> {
+        let mut results = Vec::new();
+
+        let max_cyclomatic = self.rules.threshold("HM101");
+        if function.cyclomatic_complexity as f64 > max_cyclomatic {
+            results.push(self.create_result(
+                "HM101",
+                &format!("Function '{}' has cyclomatic complexity {} (threshold {}), consider refactoring",
+                    function.name, function.cyclomatic_complexity, max_cyclomatic),
+                &function.file_path,
+                Some(self.create_function_region(function)),
+            ));
         }
 
-        if results.is_empty() { None } else { Some(results) }
+        let max_cognitive = self.rules.threshold("HM102");
+        if function.cognitive_complexity as f64 > max_cognitive {
+            results.push(self.create_result(
+                "HM102",
+                &format!("Function '{}' has cognitive complexity {} (threshold {}), may be difficult to understand",
+                    function.name, function.cognitive_complexity, max_cognitive),
+                &function.file_path,
+                Some(self.create_function_region(function)),
+            ));
+        }
+
+        let max_nesting = self.rules.threshold("HM103");
+        if function.nesting_depth as f64 > max_nesting {
+            results.push(self.create_result(
+                "HM103",
+                &format!("Function '{}' is nested {} levels deep (threshold {}), consider extracting nested logic",
+                    function.name, function.nesting_depth, max_nesting),
+                &function.file_path,
+                Some(self.create_function_region(function)),
+            ));
+        }
+
+        let max_length = self.rules.threshold("HM105");
+        if function.line_count as f64 > max_length {
+            results.push(self.create_result(
+                "HM105",
+                &format!("Function '{}' is {} lines long (threshold {}), consider splitting it up",
+                    function.name, function.line_count, max_length),
+                &function.file_path,
+                Some(self.create_function_region(function)),
+            ));
+        }
+
+        results
     }
 
     /// Analyze comprehensive quality metrics
@@ -198,26 +291,22 @@ impl SarifConverter {
         let mut results = Vec::new();
 
         // Maintainability issues
-        if aggregated_stats.complexity.quality_metrics.maintainability_index < 50.0 {
+        if aggregated_stats.complexity.quality_metrics.maintainability_index < self.rules.threshold("HM201") {
             results.push(self.create_result(
                 "HM201",
-                "Low Maintainability",
                 &format!("Maintainability index is {:.1}/100, consider refactoring", 
                     aggregated_stats.complexity.quality_metrics.maintainability_index),
-                "warning",
                 file_path,
                 None,
             ));
         }
 
         // Code health issues
-        if aggregated_stats.complexity.quality_metrics.code_health_score < 60.0 {
+        if aggregated_stats.complexity.quality_metrics.code_health_score < self.rules.threshold("HM202") {
             results.push(self.create_result(
                 "HM202",
-                "Poor Code Health",
                 &format!("Code health score is {:.1}/100, review code quality practices", 
                     aggregated_stats.complexity.quality_metrics.code_health_score),
-                "info",
                 file_path,
                 None,
             ));
@@ -233,21 +322,17 @@ impl SarifConverter {
         // Project size summary
         results.push(self.create_result(
             "HM000",
-            "Project Summary",
             &format!("Project contains {} files with {} total lines ({} code, {} comments)", 
                 stats.total_files, stats.total_lines, stats.total_code_lines, stats.total_comment_lines),
-            "note",
             "project://summary",
             None,
         ));
 
         // Large project warning
-        if stats.total_files > 1000 {
+        if stats.total_files as f64 > self.rules.threshold("HM301") {
             results.push(self.create_result(
                 "HM301",
-                "Large Project",
                 &format!("Project has {} files, consider modularization strategies", stats.total_files),
-                "info",
                 "project://summary",
                 None,
             ));
@@ -263,25 +348,21 @@ impl SarifConverter {
         // Comprehensive project summary
         results.push(self.create_result(
             "HM000",
-            "Comprehensive Project Summary",
             &format!("Project: {} files, {} functions, {:.1} avg complexity, {:.1}/100 quality score", 
                 aggregated_stats.basic.total_files,
                 aggregated_stats.complexity.function_count,
                 aggregated_stats.complexity.cyclomatic_complexity,
                 aggregated_stats.complexity.quality_metrics.code_health_score),
-            "note",
             "project://summary",
             None,
         ));
 
         // Technical debt indicators
-        if aggregated_stats.complexity.quality_metrics.code_health_score < 70.0 {
+        if aggregated_stats.complexity.quality_metrics.code_health_score < self.rules.threshold("HM401") {
             results.push(self.create_result(
                 "HM401",
-                "Technical Debt Alert",
                 &format!("Overall code health is {:.1}/100. Consider prioritizing refactoring efforts", 
                     aggregated_stats.complexity.quality_metrics.code_health_score),
-                "warning",
                 "project://technical-debt",
                 None,
             ));
@@ -290,12 +371,10 @@ impl SarifConverter {
         // Quality assessment insights
         results.push(self.create_result(
             "HM402",
-            "Quality Assessment Insights",
             &format!("Overall code quality: {:.1}/100 (Maintainability: {:.1}/100, Code Health: {:.1}/100)", 
                 aggregated_stats.ratios.quality_metrics.overall_quality_score,
                 aggregated_stats.complexity.quality_metrics.maintainability_index,
                 aggregated_stats.complexity.quality_metrics.code_health_score),
-            "note",
             "project://quality-insights",
             None,
         ));
@@ -303,29 +382,19 @@ impl SarifConverter {
         results
     }
 
-    /// Create a SARIF result object
+    /// Create a SARIF result object, using the severity configured for the rule
     fn create_result(
         &self,
         rule_id: &str,
-        _rule_name: &str,
         message_text: &str,
-        level: &str,
         file_path: &str,
         region: Option<Region>,
     ) -> SarifResult {
-        let result_level = match level {
-            "error" => ResultLevel::Error,
-            "warning" => ResultLevel::Warning,
-            "note" => ResultLevel::Note,
-            "info" => ResultLevel::None, // Use None as fallback since Info doesn't exist
-            _ => ResultLevel::None,
-        };
-
         let result = SarifResult {
             rule_id: Some(rule_id.to_string()),
             rule_index: None,
             kind: Some(ResultKind::Review),
-            level: Some(result_level),
+            level: Some(self.rules.level(rule_id).to_result_level()),
             message: Message {
                 text: Some(message_text.to_string()),
                 markdown: None,
@@ -393,8 +462,38 @@ impl SarifConverter {
         }
     }
 
+    /// Create a region pointing at a single source line
+    fn create_line_region(&self, line: usize) -> Region {
+        Region {
+            start_line: Some(line as i64),
+            start_column: None,
+            end_line: None,
+            end_column: None,
+            char_offset: None,
+            char_length: None,
+            byte_offset: None,
+            byte_length: None,
+            snippet: None,
+            message: None,
+            source_language: None,
+            properties: None,
+        }
+    }
+
+    /// Create a region spanning a function's body
+    fn create_function_region(&self, function: &FunctionComplexityDetail) -> Region {
+        let start_line = function.start_line.max(1);
+        Region {
+            end_line: Some(function.end_line.max(start_line) as i64),
+            ..self.create_line_region(start_line)
+        }
+    }
+
     /// Create the complete SARIF log structure
-    fn create_sarif_log(&self, results: Vec<SarifResult>) -> Result<Sarif> {
+    fn create_sarif_log(&self, mut results: Vec<SarifResult>) -> Result<Sarif> {
+        // Drop results of rules disabled in the configuration
+        results.retain(|result| result.rule_id.as_deref().is_some_and(|id| self.rules.is_enabled(id)));
+
         let sarif_log = Sarif {
             version: Value::String("2.1.0".to_string()),
             schema: Some("https://docs.oasis-open.org/sarif/sarif/v2.1.0/errata01/os/schemas/sarif-schema-2.1.0.json".to_string()),
@@ -493,70 +592,53 @@ impl SarifConverter {
         }
     }
 
-    /// Create rule definitions for all howmany rules
+    /// Create rule definitions for all enabled howmany rules
     pub fn create_rule_definitions(&self) -> Vec<ReportingDescriptor> {
-        vec![
-            self.create_rule("HM000", "Project Summary", "Provides an overview of project statistics"),
-            self.create_rule("HM001", "Large File", "Detects files that may be too large and should be split"),
-            self.create_rule("HM002", "Low Documentation", "Identifies files with insufficient documentation"),
-            self.create_rule("HM003", "Empty File", "Detects files with no code content"),
-            self.create_rule("HM101", "High Complexity", "Identifies functions or files with high cyclomatic complexity"),
-            self.create_rule("HM102", "High Cognitive Complexity", "Detects code that may be difficult to understand"),
-            self.create_rule("HM103", "Deep Nesting", "Identifies deeply nested code structures"),
-            self.create_rule("HM201", "Low Maintainability", "Detects code with low maintainability scores"),
-            self.create_rule("HM202", "Poor Code Health", "Identifies overall code health issues"),
-            self.create_rule("HM301", "Large Project", "Warns about projects that may benefit from modularization"),
-            self.create_rule("HM401", "Technical Debt Alert", "Highlights significant technical debt indicators"),
-            self.create_rule("HM402", "Quality Assessment Insights", "Provides overall code quality insights"),
-        ]
+        self.rules
+            .rules()
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(|rule| self.create_rule(rule))
+            .collect()
     }
 
     /// Create a single rule definition
-    fn create_rule(&self, id: &str, name: &str, description: &str) -> ReportingDescriptor {
+    fn create_rule(&self, rule: &SarifRule) -> ReportingDescriptor {
+        let full_description = match rule.threshold {
+            Some(threshold) => format!("{} Threshold: {}. Level: {}.", rule.help, threshold, rule.level.as_str()),
+            None => format!("{} Level: {}.", rule.help, rule.level.as_str()),
+        };
+
         ReportingDescriptor {
-            id: id.to_string(),
+            id: rule.id.to_string(),
             deprecated_ids: None,
             deprecated_names: None,
             deprecated_guids: None,
             guid: None,
-            name: Some(name.to_string()),
+            name: Some(rule.name.to_string()),
             short_description: Some(MultiformatMessageString {
-                text: description.to_string(),
+                text: rule.description.to_string(),
                 markdown: None,
                 properties: None,
             }),
             full_description: Some(MultiformatMessageString {
-                text: self.get_rule_help_text(id),
+                text: full_description,
                 markdown: None,
                 properties: None,
             }),
             message_strings: None,
             default_configuration: None,
-            help_uri: Some(format!("https://github.com/GriffinCanCode/howmany/blob/main/docs/rules/{}.md", id)),
-            help: None,
+            help_uri: Some(rule.help_uri()),
+            help: Some(MultiformatMessageString {
+                text: rule.help.to_string(),
+                markdown: None,
+                properties: None,
+            }),
             relationships: None,
             properties: None,
         }
     }
 
-    /// Get detailed help text for rules
-    fn get_rule_help_text(&self, rule_id: &str) -> String {
-        match rule_id {
-            "HM001" => "Large files can be difficult to maintain and understand. Consider breaking them into smaller, more focused modules.".to_string(),
-            "HM002" => "Well-documented code is easier to maintain. Consider adding comments explaining complex logic and public APIs.".to_string(),
-            "HM003" => "Empty files may indicate incomplete implementation or files that can be removed to clean up the codebase.".to_string(),
-            "HM101" => "High cyclomatic complexity indicates code that may be difficult to test and maintain. Consider refactoring into smaller functions.".to_string(),
-            "HM102" => "High cognitive complexity makes code harder to understand. Consider simplifying control flow and reducing nested conditions.".to_string(),
-            "HM103" => "Deeply nested code is harder to read and maintain. Consider extracting nested logic into separate functions.".to_string(),
-            "HM201" => "Low maintainability scores indicate code that may be expensive to modify. Focus on improving code structure and reducing complexity.".to_string(),
-            "HM202" => "Poor code health affects long-term project sustainability. Review coding standards and consider refactoring efforts.".to_string(),
-            "HM301" => "Large projects benefit from modular architecture. Consider organizing code into logical modules or packages.".to_string(),
-            "HM401" => "Technical debt accumulation can slow development. Prioritize refactoring efforts to improve code quality.".to_string(),
-            "HM402" => "Quality assessment insights provide an overall view of code quality metrics.".to_string(),
-            _ => format!("Rule {} provides code quality insights from HowMany analysis.", rule_id),
-        }
-    }
-
     /// Normalize file paths for SARIF output
     pub fn normalize_file_path(&self, file_path: &str) -> String {
         // Convert to forward slashes and ensure proper URI format
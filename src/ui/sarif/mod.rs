@@ -1,6 +1,8 @@
 mod reporter;
 mod converter;
+mod rules;
 mod tests;
 
 pub use reporter::SarifReporter;
-pub use converter::SarifConverter; 
\ No newline at end of file
+pub use converter::SarifConverter;
+pub use rules::{RuleLevel, SarifRule, SarifRuleSet};
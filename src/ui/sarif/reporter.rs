@@ -2,6 +2,7 @@ use crate::core::types::{CodeStats, FileStats};
 use crate::core::stats::AggregatedStats;
 use crate::utils::errors::Result;
 use super::converter::SarifConverter;
+use super::rules::SarifRuleSet;
 use std::fs;
 use std::path::Path;
 
@@ -16,6 +17,12 @@ impl SarifReporter {
         }
     }
 
+    /// Use a custom rule set (thresholds, severities, enabled rules)
+    pub fn with_rules(mut self, rules: SarifRuleSet) -> Self {
+        self.converter = self.converter.with_rules(rules);
+        self
+    }
+
     /// Generate SARIF report from basic CodeStats
     pub fn generate_report(
         &self,
@@ -0,0 +1,273 @@
+use crate::core::stats::complexity::{
+    FunctionThresholds, DEEP_NESTING_THRESHOLD, HIGH_COGNITIVE_COMPLEXITY_THRESHOLD,
+    HIGH_CYCLOMATIC_COMPLEXITY_THRESHOLD, LONG_FUNCTION_THRESHOLD, LONG_PARAMETER_LIST_THRESHOLD,
+};
+use crate::core::gate::GateThresholds;
+
+/// How many times the median size of its language's files a file must be to
+/// be a size outlier (HM004)
+pub const SIZE_OUTLIER_RATIO: f64 = 10.0;
+
+/// Files of a language needed for its median size to say what is usual
+pub const SIZE_OUTLIER_MIN_FILES: usize = 5;
+
+/// Files shorter than this are never size outliers, however small the median
+pub const SIZE_OUTLIER_MIN_LINES: usize = 100;
+use crate::utils::config::{SarifConfig, SarifRuleConfig};
+use crate::utils::ownership::DEFAULT_CONCENTRATION_THRESHOLD;
+use crate::utils::errors::{HowManyError, Result};
+use serde_sarif::sarif::ResultLevel;
+
+/// Severity attached to results of a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleLevel {
+    Error,
+    Warning,
+    Note,
+    None,
+}
+
+impl RuleLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleLevel::Error => "error",
+            RuleLevel::Warning => "warning",
+            RuleLevel::Note => "note",
+            RuleLevel::None => "none",
+        }
+    }
+
+    pub fn to_result_level(self) -> ResultLevel {
+        match self {
+            RuleLevel::Error => ResultLevel::Error,
+            RuleLevel::Warning => ResultLevel::Warning,
+            RuleLevel::Note => ResultLevel::Note,
+            RuleLevel::None => ResultLevel::None,
+        }
+    }
+}
+
+impl std::str::FromStr for RuleLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(RuleLevel::Error),
+            "warning" => Ok(RuleLevel::Warning),
+            "note" => Ok(RuleLevel::Note),
+            "none" | "info" => Ok(RuleLevel::None),
+            _ => Err(format!("Invalid SARIF level: {} (expected error, warning, note or none)", s)),
+        }
+    }
+}
+
+/// A SARIF rule with its effective severity and threshold
+#[derive(Debug, Clone)]
+pub struct SarifRule {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub help: &'static str,
+    pub level: RuleLevel,
+    /// Limit the rule compares against; `None` for purely informational rules
+    pub threshold: Option<f64>,
+    pub enabled: bool,
+}
+
+impl SarifRule {
+    fn new(id: &'static str, name: &'static str, description: &'static str, help: &'static str, level: RuleLevel) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            help,
+            level,
+            threshold: None,
+            enabled: true,
+        }
+    }
+
+    fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    pub fn help_uri(&self) -> String {
+        format!("https://github.com/GriffinCanCode/howmany/blob/main/docs/rules/{}.md", self.id)
+    }
+}
+
+/// The set of rules used by the SARIF reporter, optionally tuned from `.howmany.toml`
+#[derive(Debug, Clone)]
+pub struct SarifRuleSet {
+    rules: Vec<SarifRule>,
+}
+
+impl SarifRuleSet {
+    /// Built-in rules with their default thresholds and severities
+    pub fn new() -> Self {
+        let rules = vec![
+            SarifRule::new("HM000", "Project Summary", "Provides an overview of project statistics",
+                "Project summary results give an overview of the analyzed codebase.", RuleLevel::Note),
+            SarifRule::new("HM001", "Large File", "Detects files that may be too large and should be split",
+                "Large files can be difficult to maintain and understand. Consider breaking them into smaller, more focused modules.", RuleLevel::Warning)
+                .with_threshold(1000.0),
+            SarifRule::new("HM002", "Low Documentation", "Identifies files with insufficient documentation",
+                "Well-documented code is easier to maintain. Consider adding comments explaining complex logic and public APIs.", RuleLevel::None)
+                .with_threshold(0.1),
+            SarifRule::new("HM003", "Empty File", "Detects files with no code content",
+                "Empty files may indicate incomplete implementation or files that can be removed to clean up the codebase.", RuleLevel::Note),
+            SarifRule::new("HM004", "Size Outlier", "Detects files many times the median size of their language's files in the project",
+                "A file far larger than is usual for its language in this project often holds several responsibilities. Unlike Large File, the limit is relative, so verbose languages aren't flagged for being verbose.", RuleLevel::Note)
+                .with_threshold(SIZE_OUTLIER_RATIO),
+            SarifRule::new("HM101", "High Complexity", "Identifies functions with high cyclomatic complexity",
+                "High cyclomatic complexity indicates code that may be difficult to test and maintain. Consider refactoring into smaller functions.", RuleLevel::Warning)
+                .with_threshold(HIGH_CYCLOMATIC_COMPLEXITY_THRESHOLD as f64),
+            SarifRule::new("HM102", "High Cognitive Complexity", "Detects code that may be difficult to understand",
+                "High cognitive complexity makes code harder to understand. Consider simplifying control flow and reducing nested conditions.", RuleLevel::Warning)
+                .with_threshold(HIGH_COGNITIVE_COMPLEXITY_THRESHOLD as f64),
+            SarifRule::new("HM103", "Deep Nesting", "Identifies deeply nested code structures",
+                "Deeply nested code is harder to read and maintain. Consider extracting nested logic into separate functions.", RuleLevel::Note)
+                .with_threshold(DEEP_NESTING_THRESHOLD as f64),
+            SarifRule::new("HM104", "Long Parameter List", "Detects functions that take too many parameters",
+                "Long parameter lists make functions hard to call and test. Consider grouping related parameters into a struct or options object.", RuleLevel::Warning)
+                .with_threshold(LONG_PARAMETER_LIST_THRESHOLD as f64),
+            SarifRule::new("HM105", "Long Function", "Detects functions with too many lines",
+                "Long functions tend to do too many things at once. Consider splitting them into smaller, well-named helpers.", RuleLevel::Note)
+                .with_threshold(LONG_FUNCTION_THRESHOLD as f64),
+            SarifRule::new("HM201", "Low Maintainability", "Detects code with low maintainability scores",
+                "Low maintainability scores indicate code that may be expensive to modify. Focus on improving code structure and reducing complexity.", RuleLevel::Warning)
+                .with_threshold(20.0),
+            SarifRule::new("HM202", "Poor Code Health", "Identifies overall code health issues",
+                "Poor code health affects long-term project sustainability. Review coding standards and consider refactoring efforts.", RuleLevel::None)
+                .with_threshold(60.0),
+            SarifRule::new("HM301", "Large Project", "Warns about projects that may benefit from modularization",
+                "Large projects benefit from modular architecture. Consider organizing code into logical modules or packages.", RuleLevel::None)
+                .with_threshold(1000.0),
+            SarifRule::new("HM401", "Technical Debt Alert", "Highlights significant technical debt indicators",
+                "Technical debt accumulation can slow development. Prioritize refactoring efforts to improve code quality.", RuleLevel::Warning)
+                .with_threshold(70.0),
+            SarifRule::new("HM402", "Quality Assessment Insights", "Provides overall code quality insights",
+                "Quality assessment insights provide an overall view of code quality metrics.", RuleLevel::Note),
+            SarifRule::new("HM501", "Concentrated Ownership", "Detects directories and languages written almost entirely by one author",
+                "Code known by a single person is a bus factor risk. Spread reviews and changes in this area across more of the team.", RuleLevel::Note)
+                .with_threshold(DEFAULT_CONCENTRATION_THRESHOLD),
+        ];
+
+        Self { rules }
+    }
+
+    /// Built-in rules with overrides from the `[sarif.rules]` config section applied
+    pub fn from_config(config: &SarifConfig) -> Result<Self> {
+        let mut rule_set = Self::new();
+
+        for (id, overrides) in &config.rules {
+            let rule = rule_set
+                .rules
+                .iter_mut()
+                .find(|rule| rule.id.eq_ignore_ascii_case(id))
+                .ok_or_else(|| HowManyError::invalid_config(format!("Unknown SARIF rule in config: {}", id)))?;
+
+            if let Some(enabled) = overrides.enabled {
+                rule.enabled = enabled;
+            }
+
+            if let Some(level) = &overrides.level {
+                rule.level = level.parse().map_err(HowManyError::invalid_config)?;
+            }
+
+            if let Some(threshold) = overrides.threshold {
+                if rule.threshold.is_none() {
+                    return Err(HowManyError::invalid_config(format!("SARIF rule {} does not take a threshold", rule.id)));
+                }
+                if !threshold.is_finite() || threshold < 0.0 {
+                    return Err(HowManyError::invalid_config(format!("Invalid threshold for SARIF rule {}: {}", rule.id, threshold)));
+                }
+                rule.threshold = Some(threshold);
+            }
+        }
+
+        Ok(rule_set)
+    }
+
+    /// Every rule's effective settings, as a `[sarif.rules]` section
+    pub fn to_config(&self) -> SarifConfig {
+        let rules = self.rules
+            .iter()
+            .map(|rule| {
+                let settings = SarifRuleConfig {
+                    enabled: Some(rule.enabled),
+                    level: Some(rule.level.as_str().to_string()),
+                    threshold: rule.threshold,
+                };
+                (rule.id.to_string(), settings)
+            })
+            .collect();
+        SarifConfig { rules }
+    }
+
+    pub fn rules(&self) -> &[SarifRule] {
+        &self.rules
+    }
+
+    pub fn rule(&self, id: &str) -> Option<&SarifRule> {
+        self.rules.iter().find(|rule| rule.id == id)
+    }
+
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.rule(id).is_some_and(|rule| rule.enabled)
+    }
+
+    pub fn level(&self, id: &str) -> RuleLevel {
+        self.rule(id).map(|rule| rule.level).unwrap_or(RuleLevel::None)
+    }
+
+    /// Threshold of a rule, or 0 for rules without one
+    pub fn threshold(&self, id: &str) -> f64 {
+        self.rule(id).and_then(|rule| rule.threshold).unwrap_or(0.0)
+    }
+
+    /// Per-function limits for the complexity calculator, so the functions it
+    /// collects match what the function-level rules report
+    pub fn function_thresholds(&self) -> FunctionThresholds {
+        // A disabled rule should not pull functions into the findings at all
+        let limit = |id: &str| {
+            if self.is_enabled(id) {
+                self.threshold(id).floor() as usize
+            } else {
+                usize::MAX
+            }
+        };
+
+        FunctionThresholds {
+            max_cyclomatic_complexity: limit("HM101"),
+            max_cognitive_complexity: limit("HM102"),
+            max_nesting_depth: limit("HM103"),
+            max_parameters: limit("HM104"),
+            max_function_length: limit("HM105"),
+        }
+    }
+}
+
+impl SarifRuleSet {
+    /// Limits for `howmany diff`: the file length and per-function rules.
+    /// A disabled rule never fails the gate.
+    pub fn gate_thresholds(&self) -> GateThresholds {
+        GateThresholds {
+            max_file_lines: if self.is_enabled("HM001") { self.threshold("HM001").floor() as usize } else { usize::MAX },
+            functions: self.function_thresholds(),
+        }
+    }
+
+    /// Top author share, in percent, above which `--owners` flags an area, or
+    /// `None` when the concentrated ownership rule is disabled
+    pub fn ownership_concentration_threshold(&self) -> Option<f64> {
+        self.is_enabled("HM501").then(|| self.threshold("HM501"))
+    }
+}
+
+impl Default for SarifRuleSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
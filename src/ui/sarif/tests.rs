@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
     use crate::core::types::{CodeStats, FileStats};
-    use crate::ui::sarif::{SarifConverter, SarifReporter};
+    use crate::ui::sarif::{RuleLevel, SarifConverter, SarifReporter, SarifRuleSet};
+    use crate::utils::config::{SarifConfig, SarifRuleConfig};
     use serde_sarif::sarif::Sarif;
     use std::collections::HashMap;
     use tempfile::NamedTempFile;
@@ -17,6 +18,16 @@ mod tests {
             doc_lines: 50,
             blank_lines: 100,
             file_size: 25000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
         stats_by_extension.insert("rs".to_string(), (5, rust_stats));
 
@@ -27,6 +38,16 @@ mod tests {
             doc_lines: 25,
             blank_lines: 25,
             file_size: 12000,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
         stats_by_extension.insert("js".to_string(), (3, js_stats));
 
@@ -39,6 +60,12 @@ mod tests {
             total_blank_lines: 125,
             total_size: 37000,
             stats_by_extension,
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         }
     }
 
@@ -51,6 +78,16 @@ mod tests {
                 doc_lines: 15,
                 blank_lines: 10,
                 file_size: 5000,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
             ("src/lib.rs".to_string(), FileStats {
                 total_lines: 100,
@@ -59,6 +96,16 @@ mod tests {
                 doc_lines: 5,
                 blank_lines: 5,
                 file_size: 2500,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
             }),
         ]
     }
@@ -129,6 +176,70 @@ mod tests {
         assert_eq!(deserialized.version, sarif_log.version);
     }
 
+    #[test]
+    fn test_file_results_have_locations() {
+        let converter = SarifConverter::new();
+        let stats = create_test_stats();
+        let individual_files = vec![
+            ("src/big.rs".to_string(), FileStats {
+                total_lines: 1500,
+                code_lines: 1400,
+                comment_lines: 20,
+                doc_lines: 10,
+                blank_lines: 70,
+                file_size: 40000,
+                encoding: None,
+                inline_comment_lines: 0,
+                logical_lines: 0,
+                characters: 0,
+                non_whitespace_characters: 0,
+                tokens: 0,
+                line_lengths: Default::default(),
+                embedded: Default::default(),
+                metrics: Default::default(),
+                ..Default::default()
+            }),
+        ];
+
+        let sarif_log = converter.convert_basic_analysis(&stats, &individual_files).unwrap();
+        let results = sarif_log.runs[0].results.as_ref().unwrap();
+
+        for rule_id in ["HM001", "HM002"] {
+            let result = results.iter()
+                .find(|r| r.rule_id.as_deref() == Some(rule_id))
+                .unwrap_or_else(|| panic!("missing {} result", rule_id));
+            let location = &result.locations.as_ref().unwrap()[0];
+            let physical = location.physical_location.as_ref().unwrap();
+            assert_eq!(physical.artifact_location.as_ref().unwrap().uri.as_deref(), Some("src/big.rs"));
+            assert_eq!(physical.region.as_ref().unwrap().start_line, Some(1));
+        }
+    }
+
+    #[test]
+    fn test_size_outliers_are_relative_to_language() {
+        let converter = SarifConverter::new();
+        let file = |lines: usize| FileStats { total_lines: lines, code_lines: lines, ..Default::default() };
+        let mut individual_files: Vec<(String, FileStats)> = (0..5).map(|index| (format!("src/mod{}.rs", index), file(40))).collect();
+        // 12x the median of the Rust files
+        individual_files.push(("src/huge.rs".to_string(), file(480)));
+        // Verbose but typical for its own language
+        individual_files.extend((0..6).map(|index| (format!("gen/Model{}.java", index), file(900))));
+        // Too few Go files to know what is usual
+        individual_files.push(("cmd/a.go".to_string(), file(10)));
+        individual_files.push(("cmd/b.go".to_string(), file(500)));
+
+        let sarif_log = converter.convert_basic_analysis(&create_test_stats(), &individual_files).unwrap();
+        let outliers: Vec<_> = sarif_log.runs[0].results.as_ref().unwrap()
+            .iter()
+            .filter(|result| result.rule_id.as_deref() == Some("HM004"))
+            .collect();
+
+        assert_eq!(outliers.len(), 1);
+        let location = &outliers[0].locations.as_ref().unwrap()[0];
+        assert_eq!(location.physical_location.as_ref().unwrap().artifact_location.as_ref().unwrap().uri.as_deref(), Some("src/huge.rs"));
+        assert!(outliers[0].message.text.as_deref().unwrap().contains("12x the median of 40 lines for .rs files"));
+    }
+
     #[test] 
     fn test_file_path_normalization() {
         let converter = SarifConverter::new();
@@ -159,6 +270,45 @@ mod tests {
         let rule_ids: Vec<&str> = rules.iter().map(|r| r.id.as_str()).collect();
         assert!(rule_ids.contains(&"HM001")); // Large File
         assert!(rule_ids.contains(&"HM000")); // Project Summary
+        assert!(rule_ids.contains(&"HM104")); // Long Parameter List
+    }
+
+    #[test]
+    fn test_rule_set_from_config() {
+        let mut config = SarifConfig::default();
+        config.rules.insert("HM101".to_string(), SarifRuleConfig {
+            level: Some("error".to_string()),
+            threshold: Some(25.0),
+            ..Default::default()
+        });
+        config.rules.insert("HM402".to_string(), SarifRuleConfig {
+            enabled: Some(false),
+            ..Default::default()
+        });
+
+        let rules = SarifRuleSet::from_config(&config).unwrap();
+        assert_eq!(rules.level("HM101"), RuleLevel::Error);
+        assert_eq!(rules.threshold("HM101"), 25.0);
+        assert_eq!(rules.function_thresholds().max_cyclomatic_complexity, 25);
+        assert!(!rules.is_enabled("HM402"));
+
+        // Disabled rules are left out of the tool's rule metadata
+        let converter = SarifConverter::new().with_rules(rules);
+        let rule_ids: Vec<String> = converter.create_rule_definitions().into_iter().map(|r| r.id).collect();
+        assert!(rule_ids.contains(&"HM101".to_string()));
+        assert!(!rule_ids.contains(&"HM402".to_string()));
+
+        // Unknown rules and levels are configuration errors
+        let mut bad_rule = SarifConfig::default();
+        bad_rule.rules.insert("HM999".to_string(), SarifRuleConfig::default());
+        assert!(SarifRuleSet::from_config(&bad_rule).is_err());
+
+        let mut bad_level = SarifConfig::default();
+        bad_level.rules.insert("HM001".to_string(), SarifRuleConfig {
+            level: Some("critical".to_string()),
+            ..Default::default()
+        });
+        assert!(SarifRuleSet::from_config(&bad_level).is_err());
     }
 
     #[test]
@@ -173,6 +323,12 @@ mod tests {
             total_blank_lines: 0,
             total_size: 0,
             stats_by_extension: HashMap::new(),
+            total_inline_comment_lines: 0,
+            total_logical_lines: 0,
+            total_characters: 0,
+            total_non_whitespace_characters: 0,
+            total_tokens: 0,
+            line_lengths: Default::default(),
         };
         let individual_files = vec![];
 
@@ -0,0 +1,186 @@
+use crate::utils::errors::{HowManyError, Result};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Archive formats whose entries can be counted without extracting them to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    /// Gzip-compressed tarball, including published `.crate` files
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Detect the format from the file name
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") || name.ends_with(".crate") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Check whether a path is an archive howmany can read
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveFormat::detect(path).is_some()
+}
+
+/// Stream the regular files of an archive, loading one entry at a time into memory.
+///
+/// `visit` receives each entry's path inside the archive and its contents.
+/// Entries larger than `max_entry_size` bytes are skipped. Returns the number of skipped entries.
+pub fn for_each_entry(
+    path: &Path,
+    max_entry_size: u64,
+    mut visit: impl FnMut(&Path, &[u8]) -> Result<()>,
+) -> Result<usize> {
+    let format = ArchiveFormat::detect(path)
+        .ok_or_else(|| HowManyError::file_processing(format!("Unsupported archive: {}", path.display())))?;
+    let file = fs::File::open(path)?;
+
+    match format {
+        ArchiveFormat::Zip => read_zip(file, path, max_entry_size, &mut visit),
+        ArchiveFormat::Tar => read_tar(file, path, max_entry_size, &mut visit),
+        ArchiveFormat::TarGz => read_tar(GzDecoder::new(file), path, max_entry_size, &mut visit),
+    }
+}
+
+fn read_tar(
+    reader: impl Read,
+    path: &Path,
+    max_entry_size: u64,
+    visit: &mut impl FnMut(&Path, &[u8]) -> Result<()>,
+) -> Result<usize> {
+    let archive_error = |e: std::io::Error| {
+        HowManyError::file_processing(format!("Failed to read archive {}: {}", path.display(), e))
+    };
+
+    let mut archive = tar::Archive::new(reader);
+    let mut skipped = 0;
+    let mut contents = Vec::new();
+
+    for entry in archive.entries().map_err(archive_error)? {
+        let mut entry = entry.map_err(archive_error)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        if entry.size() > max_entry_size {
+            skipped += 1;
+            continue;
+        }
+
+        let entry_path = entry.path().map_err(archive_error)?.into_owned();
+        contents.clear();
+        entry.read_to_end(&mut contents).map_err(archive_error)?;
+        visit(&entry_path, &contents)?;
+    }
+
+    Ok(skipped)
+}
+
+fn read_zip(
+    file: fs::File,
+    path: &Path,
+    max_entry_size: u64,
+    visit: &mut impl FnMut(&Path, &[u8]) -> Result<()>,
+) -> Result<usize> {
+    let archive_error = |e: zip::result::ZipError| {
+        HowManyError::file_processing(format!("Failed to read archive {}: {}", path.display(), e))
+    };
+
+    let mut archive = zip::ZipArchive::new(file).map_err(archive_error)?;
+    let mut skipped = 0;
+    let mut contents = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(archive_error)?;
+        // Entries with absolute or `..` paths have no enclosed name and are ignored
+        let entry_path = match entry.enclosed_name() {
+            Some(entry_path) if entry.is_file() => entry_path,
+            _ => continue,
+        };
+        if entry.size() > max_entry_size {
+            skipped += 1;
+            continue;
+        }
+
+        contents.clear();
+        entry.read_to_end(&mut contents)?;
+        visit(&entry_path, &contents)?;
+    }
+
+    Ok(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn collect(path: &Path, max_entry_size: u64) -> (Vec<(PathBuf, String)>, usize) {
+        let mut entries = Vec::new();
+        let skipped = for_each_entry(path, max_entry_size, |entry_path, contents| {
+            entries.push((entry_path.to_path_buf(), String::from_utf8_lossy(contents).to_string()));
+            Ok(())
+        })
+        .unwrap();
+        (entries, skipped)
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(ArchiveFormat::detect(Path::new("release.ZIP")), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::detect(Path::new("serde-1.0.0.crate")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::detect(Path::new("src.tgz")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::detect(Path::new("src.tar")), Some(ArchiveFormat::Tar));
+        assert_eq!(ArchiveFormat::detect(Path::new("schema.sql.gz")), None);
+    }
+
+    #[test]
+    fn test_tar_gz_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("demo-0.1.0.crate");
+
+        let mut builder = tar::Builder::new(GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default()));
+        for (name, contents) in [("demo-0.1.0/src/lib.rs", "pub fn demo() {}\n"), ("demo-0.1.0/big.rs", "// 0123456789 0123456789\n")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, name, contents.as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let (entries, skipped) = collect(&path, 20);
+        assert_eq!(entries, vec![(PathBuf::from("demo-0.1.0/src/lib.rs"), "pub fn demo() {}\n".to_string())]);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_zip_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.zip");
+
+        let mut writer = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        writer.add_directory("src/", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.start_file("src/main.py", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"print('hi')\n").unwrap();
+        writer.finish().unwrap();
+
+        let (entries, skipped) = collect(&path, 1024);
+        assert_eq!(entries, vec![(PathBuf::from("src/main.py"), "print('hi')\n".to_string())]);
+        assert_eq!(skipped, 0);
+    }
+}
@@ -1,56 +1,232 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::UNIX_EPOCH;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::core::detector::extension_key;
+use crate::core::plugins;
+use crate::core::stats::complexity::FileAnalysis;
 use crate::core::types::FileStats;
+use crate::utils::compression;
 use crate::utils::errors::{HowManyError, Result};
-use serde::{Deserialize, Serialize};
+use crate::utils::hashing::HashAlgorithm;
+use crate::utils::paths::normalize_path;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Environment variable overriding the directory howmany keeps its caches in
+pub const CACHE_DIR_ENV: &str = "HOWMANY_CACHE_DIR";
+
+/// Files the cache keeps at most unless `[cache] max_entries` says otherwise
+pub const DEFAULT_MAX_ENTRIES: usize = 100_000;
+
+/// File of the cache of counts in the cache directory
+pub const FILE_CACHE_NAME: &str = "file_cache.json";
+
+/// File of the counts of past commits sampled by `howmany history`
+pub const HISTORY_CACHE_NAME: &str = "history_cache.json";
+
+/// Directory of the checkpoints of `--resume` runs
+pub const RUNS_DIR_NAME: &str = "runs";
+
+/// Directory of the file caches of each analyzed project
+pub const PROJECTS_DIR_NAME: &str = "projects";
+
+/// Everything howmany keeps in the cache directory, which may hold other files
+/// when `$HOWMANY_CACHE_DIR` points somewhere shared
+pub const CACHE_ENTRIES: &[&str] = &[FILE_CACHE_NAME, PROJECTS_DIR_NAME, HISTORY_CACHE_NAME, RUNS_DIR_NAME];
+
+/// Version of howmany that counted cached files. Counting rules change between
+/// releases, so counts cached by another version are discarded.
+pub const COUNTER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Directory of howmany's caches: `$HOWMANY_CACHE_DIR`, otherwise `howmany` in
+/// the user cache directory (`$XDG_CACHE_HOME` or `~/.cache` on Linux,
+/// `~/Library/Caches` on macOS)
+pub fn cache_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(CACHE_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    let cache_dir = dirs::cache_dir()
+        .ok_or_else(|| HowManyError::invalid_config("Could not find cache directory"))?;
+    
+    Ok(cache_dir.join("howmany"))
+}
+
+/// Seconds since the epoch, for the recency of entries
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// How the cache tells that a file is unchanged (`[hashing] cache_key = "content"`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKey {
+    /// Same modification time and size, or the same contents when only the modification time changed
+    #[default]
+    Mtime,
+    /// Same contents, hashed on every run. Catches edits that keep the modification
+    /// time and size, and reuses the counts of identical files at other paths.
+    Content,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheEntry {
-    pub stats: FileStats,
     pub last_modified: u64,
     pub file_size: u64,
+    /// Content hash tagged with its algorithm (e.g. `xxh3:…`); files with the
+    /// same contents share one record of counts
+    pub content_hash: String,
+    /// Spelling on disk when it differs from the NFC-normalized key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_path: Option<PathBuf>,
+    /// When the entry was last counted or found, in seconds since the epoch
+    #[serde(default)]
+    pub last_used: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileCache {
     entries: HashMap<PathBuf, CacheEntry>,
+    /// Counts by `stats_key`, stored once for identical files such as vendored copies
+    #[serde(default)]
+    stats: HashMap<String, FileStats>,
+    cache_version: u32,
+    #[serde(default)]
+    counter_version: String,
+    /// Directory analyzed, for caches kept per project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project_root: Option<PathBuf>,
+    /// Functions and structures by analyzer extension and content hash
+    #[serde(default)]
+    analyses: AnalysisCache,
+    /// Plugins registered when the entries were counted; their languages and metrics are part of the stats
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    plugins: Vec<String>,
+    #[serde(skip)]
+    hash_algorithm: HashAlgorithm,
+    #[serde(skip)]
+    cache_key: CacheKey,
+    #[serde(skip)]
+    max_entries: Option<usize>,
+    /// File the cache is saved to; the shared `file_cache.json` when `None`
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// Whether records of a shared cache were merged, so files without an entry are looked up by content
+    #[serde(skip)]
+    shared: bool,
+    /// Records pulled from the shared cache, pushed back with the local ones
+    /// so files of other checkouts aren't dropped from it
+    #[serde(skip)]
+    pulled: SharedRecords,
+}
+
+/// Counts and analyses exchanged with a shared cache. Paths and modification
+/// times differ between machines, so only records keyed by content are shared.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SharedRecords {
     cache_version: u32,
+    counter_version: String,
+    plugins: Vec<String>,
+    stats: HashMap<String, FileStats>,
+    analyses: HashMap<String, FileAnalysis>,
+}
+
+/// Key of the counts of a file with `content_hash`: identical contents are
+/// counted alike unless the file's name makes them another language
+fn stats_key(path: &Path, content_hash: &str) -> String {
+    let counted_path = compression::decompressed_path(path).unwrap_or_else(|| path.to_path_buf());
+    format!("{} {}", extension_key(&counted_path).unwrap_or_default().to_lowercase(), content_hash)
 }
 
 impl FileCache {
-    const CACHE_VERSION: u32 = 1;
+    const CACHE_VERSION: u32 = 8;
     
     pub fn new() -> Self {
         Self {
             entries: HashMap::new(),
+            stats: HashMap::new(),
             cache_version: Self::CACHE_VERSION,
+            counter_version: COUNTER_VERSION.to_string(),
+            project_root: None,
+            analyses: AnalysisCache::default(),
+            plugins: plugins::plugin_names(),
+            hash_algorithm: HashAlgorithm::default(),
+            cache_key: CacheKey::default(),
+            max_entries: Some(DEFAULT_MAX_ENTRIES),
+            path: None,
+            shared: false,
+            pulled: SharedRecords::default(),
         }
     }
     
+    /// Hash function for content hashes of new entries. Entries hashed with a
+    /// different algorithm never match and are simply re-counted.
+    pub fn with_hash_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = algorithm;
+        self.analyses.hash_algorithm = algorithm;
+        self
+    }
+    
+    /// Whether a file is looked up by its modification time or by its contents
+    pub fn with_cache_key(mut self, cache_key: CacheKey) -> Self {
+        self.cache_key = cache_key;
+        self
+    }
+    
+    /// Files kept at most; inserting beyond it evicts the least recently used
+    /// tenth of the entries. `None` keeps every file.
+    pub fn with_max_entries(mut self, max_entries: Option<usize>) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+    
     pub fn load() -> Result<Self> {
-        let cache_path = Self::cache_path()?;
+        Self::load_from(Self::cache_path()?)
+    }
+    
+    /// The cache of the project at `root`, kept apart from other projects' so
+    /// their entries never mix or evict each other
+    pub fn load_for_project(root: &Path) -> Result<Self> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut cache = Self::load_from(Self::project_cache_path(&root)?)?;
+        cache.project_root = Some(root);
+        Ok(cache)
+    }
+    
+    fn load_from(path: PathBuf) -> Result<Self> {
+        let cache = Self::inspect_file(&path)?;
         
+        // Check cache version compatibility, and that files are counted by the same howmany with the same plugins
+        let cache = if cache.cache_version == Self::CACHE_VERSION && cache.counter_version == COUNTER_VERSION && cache.plugins == plugins::plugin_names() {
+            cache
+        } else {
+            // Cache version mismatch, start fresh
+            Self::new()
+        };
+        Ok(Self { path: Some(path), ..cache })
+    }
+    
+    /// The cache as saved, even when its version or plugins would make a run start fresh
+    pub fn inspect() -> Result<Self> {
+        Self::inspect_file(&Self::cache_path()?)
+    }
+    
+    /// A cache file as saved, such as one of `project_caches`
+    pub fn inspect_file(cache_path: &Path) -> Result<Self> {
         if cache_path.exists() {
-            let content = fs::read_to_string(&cache_path)?;
-            let cache: FileCache = serde_json::from_str(&content)
-                .map_err(|e| HowManyError::invalid_config(format!("Failed to parse cache: {}", e)))?;
-            
-            // Check cache version compatibility
-            if cache.cache_version == Self::CACHE_VERSION {
-                Ok(cache)
-            } else {
-                // Cache version mismatch, start fresh
-                Ok(Self::new())
-            }
+            let content = fs::read_to_string(cache_path)?;
+            serde_json::from_str(&content)
+                .map_err(|e| HowManyError::invalid_config(format!("Failed to parse cache: {}", e)))
         } else {
             Ok(Self::new())
         }
     }
     
     pub fn save(&self) -> Result<()> {
-        let cache_path = Self::cache_path()?;
+        let cache_path = match &self.path {
+            Some(path) => path.clone(),
+            None => Self::cache_path()?,
+        };
         
         if let Some(parent) = cache_path.parent() {
             fs::create_dir_all(parent)?;
@@ -61,9 +237,26 @@ impl FileCache {
         Ok(())
     }
     
+    /// Entries are keyed by NFC-normalized path, so a file whose name switched
+    /// between NFC and NFD (e.g. copied between macOS and Linux) is still found.
+    /// By content, any file with the contents of a cached one is found.
     pub fn get(&self, path: &Path) -> Option<&FileStats> {
+        if self.cache_key == CacheKey::Content {
+            let current_hash = self.hash_algorithm.hash_file(path).ok()?;
+            return self.stats.get(&stats_key(path, &current_hash.to_string()));
+        }
+        
         if let Ok(metadata) = fs::metadata(path) {
-            if let Some(entry) = self.entries.get(path) {
+            let key = normalize_path(path);
+            let entry = self.entries.get(key.as_ref());
+            
+            // Another machine may have counted the contents
+            if entry.is_none() && self.shared {
+                let current_hash = self.hash_algorithm.hash_file(path).ok()?;
+                return self.stats.get(&stats_key(path, &current_hash.to_string()));
+            }
+            
+            if let Some(entry) = entry {
                 let current_modified = metadata.modified()
                     .ok()?
                     .duration_since(UNIX_EPOCH)
@@ -72,15 +265,27 @@ impl FileCache {
                 
                 let current_size = metadata.len();
                 
+                // Another spelling of the name may be a different file, so only trust its contents
+                let same_spelling = entry.original_path.as_deref().unwrap_or(key.as_ref()) == path;
+                
                 // Check if file hasn't changed
-                if entry.last_modified == current_modified && entry.file_size == current_size {
-                    return Some(&entry.stats);
+                if same_spelling && entry.last_modified == current_modified && entry.file_size == current_size {
+                    return self.stats.get(&stats_key(path, &entry.content_hash));
+                }
+                
+                // A fresh checkout, `touch` or rename changes the mtime but not the contents
+                if entry.file_size == current_size {
+                    let current_hash = self.hash_algorithm.hash_file(path).ok()?;
+                    if entry.content_hash == current_hash.to_string() {
+                        return self.stats.get(&stats_key(path, &entry.content_hash));
+                    }
                 }
             }
         }
         None
     }
     
+    /// Record the counts of a file. Files that can't be hashed aren't cached.
     pub fn insert(&mut self, path: PathBuf, stats: FileStats) -> Result<()> {
         if let Ok(metadata) = fs::metadata(&path) {
             let last_modified = metadata.modified()?
@@ -89,47 +294,187 @@ impl FileCache {
                 .as_secs();
             
             let file_size = metadata.len();
+            let Ok(content_hash) = self.hash_algorithm.hash_file(&path).map(|hash| hash.to_string()) else {
+                return Ok(());
+            };
+            
+            let key = normalize_path(&path).into_owned();
+            self.stats.insert(stats_key(&path, &content_hash), stats);
+            let original_path = if key != path { Some(path) } else { None };
             
             let entry = CacheEntry {
-                stats,
                 last_modified,
                 file_size,
+                content_hash,
+                original_path,
+                last_used: now(),
             };
             
-            self.entries.insert(path, entry);
+            self.entries.insert(key, entry);
+            if self.max_entries.is_some_and(|max_entries| self.entries.len() > max_entries) {
+                self.evict_least_recently_used();
+            }
         }
         Ok(())
     }
     
+    /// Mark the entry of a file found in the cache as just used. False when
+    /// the file has no entry, having been found by its contents.
+    pub fn touch(&mut self, path: &Path) -> bool {
+        match self.entries.get_mut(normalize_path(path).as_ref()) {
+            Some(entry) => {
+                entry.last_used = now();
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Evict the least recently used entries down to nine tenths of `max_entries`,
+    /// so a full cache isn't scanned again on every insert
+    fn evict_least_recently_used(&mut self) {
+        let Some(max_entries) = self.max_entries else {
+            return;
+        };
+        let keep = max_entries - max_entries / 10;
+        let mut by_use: Vec<(u64, PathBuf)> = self.entries.iter().map(|(key, entry)| (entry.last_used, key.clone())).collect();
+        by_use.sort();
+        for (_, key) in by_use.into_iter().take(self.entries.len().saturating_sub(keep)) {
+            self.entries.remove(&key);
+        }
+        self.remove_unused_stats();
+    }
+    
     pub fn remove(&mut self, path: &Path) {
-        self.entries.remove(path);
+        self.entries.remove(normalize_path(path).as_ref());
+        self.remove_unused_stats();
     }
     
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.stats.clear();
+        self.analyses.lock().clear();
     }
     
     pub fn cleanup_missing_files(&mut self) {
         let missing_paths: Vec<_> = self.entries
-            .keys()
-            .filter(|path| !path.exists())
-            .cloned()
+            .iter()
+            .filter(|(key, entry)| !entry.original_path.as_deref().unwrap_or(key).exists())
+            .map(|(key, _)| key.clone())
             .collect();
         
         for path in missing_paths {
             self.entries.remove(&path);
         }
+        self.remove_unused_stats();
+    }
+    
+    /// Drop the counts and analyses no remaining entry has the contents of
+    fn remove_unused_stats(&mut self) {
+        let used: HashSet<String> = self.entries
+            .iter()
+            .map(|(key, entry)| stats_key(entry.original_path.as_deref().unwrap_or(key), &entry.content_hash))
+            .collect();
+        self.stats.retain(|key, _| used.contains(key));
+        
+        let hashes: HashSet<&str> = self.entries.values().map(|entry| entry.content_hash.as_str()).collect();
+        self.analyses.lock().retain(|key, _| key.split_once(' ').is_some_and(|(_, content_hash)| hashes.contains(content_hash)));
     }
     
     pub fn size(&self) -> usize {
         self.entries.len()
     }
     
-    fn cache_path() -> Result<PathBuf> {
-        let cache_dir = dirs::cache_dir()
-            .ok_or_else(|| HowManyError::invalid_config("Could not find cache directory"))?;
+    /// Records of counts stored, fewer than `size` when files are identical
+    pub fn stored_stats(&self) -> usize {
+        self.stats.len()
+    }
+    
+    /// Analyses of functions and structures stored
+    pub fn stored_analyses(&self) -> usize {
+        self.analyses.lock().len()
+    }
+    
+    /// Handle on the cached analyses for the complexity calculator; what it
+    /// stores is saved with this cache
+    pub fn analysis_cache(&self) -> AnalysisCache {
+        self.analyses.clone()
+    }
+    
+    /// Counts and analyses of the files with an entry, added to the records
+    /// pulled from the shared cache. Both are keyed by content, so records
+    /// of the same key are the same counts.
+    pub fn shared_records(&self) -> SharedRecords {
+        let hashes: HashSet<&str> = self.entries.values().map(|entry| entry.content_hash.as_str()).collect();
+        let shared = |key: &String| key.split_once(' ').is_some_and(|(_, content_hash)| hashes.contains(content_hash));
+        let mut stats = self.pulled.stats.clone();
+        stats.extend(self.stats.iter().filter(|(key, _)| shared(key)).map(|(key, stats)| (key.clone(), stats.clone())));
+        let mut analyses = self.pulled.analyses.clone();
+        analyses.extend(self.analyses.lock().iter().filter(|(key, _)| shared(key)).map(|(key, analysis)| (key.clone(), analysis.clone())));
+        SharedRecords {
+            cache_version: Self::CACHE_VERSION,
+            counter_version: COUNTER_VERSION.to_string(),
+            plugins: plugins::plugin_names(),
+            stats,
+            analyses,
+        }
+    }
+    
+    /// Add the records of a shared cache that aren't cached here, returning how
+    /// many were added. Records of another howmany or other plugins are ignored.
+    pub fn merge_shared(&mut self, records: SharedRecords) -> usize {
+        self.shared = true;
+        if records.cache_version != Self::CACHE_VERSION || records.counter_version != COUNTER_VERSION || records.plugins != plugins::plugin_names() {
+            return 0;
+        }
         
-        Ok(cache_dir.join("howmany").join("file_cache.json"))
+        let mut added = 0;
+        for (key, stats) in &records.stats {
+            if let std::collections::hash_map::Entry::Vacant(vacant) = self.stats.entry(key.clone()) {
+                vacant.insert(stats.clone());
+                added += 1;
+            }
+        }
+        let mut analyses = self.analyses.lock();
+        for (key, analysis) in &records.analyses {
+            if let std::collections::hash_map::Entry::Vacant(vacant) = analyses.entry(key.clone()) {
+                vacant.insert(analysis.clone());
+                added += 1;
+            }
+        }
+        drop(analyses);
+        self.pulled = records;
+        added
+    }
+    
+    pub fn cache_path() -> Result<PathBuf> {
+        Ok(cache_dir()?.join(FILE_CACHE_NAME))
+    }
+    
+    /// File of the cache of the project at `root`, named by a hash of its canonical path
+    pub fn project_cache_path(root: &Path) -> Result<PathBuf> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let digest = HashAlgorithm::Xxh3.hash_bytes(root.to_string_lossy().as_bytes()).digest;
+        Ok(cache_dir()?.join(PROJECTS_DIR_NAME).join(format!("{}.json", digest)))
+    }
+    
+    /// Files of the caches of every project analyzed so far
+    pub fn project_caches() -> Result<Vec<PathBuf>> {
+        let dir = cache_dir()?.join(PROJECTS_DIR_NAME);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+    
+    /// Directory analyzed, for caches loaded with `load_for_project`
+    pub fn project_root(&self) -> Option<&Path> {
+        self.project_root.as_deref()
     }
 }
 
@@ -139,6 +484,47 @@ impl Default for FileCache {
     }
 }
 
+/// Functions and structures found in files, shared between a `FileCache` and
+/// the complexity calculator. Analyses are keyed by the analyzer's extension
+/// and the content hash, so identical files are analyzed once.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisCache {
+    analyses: Arc<Mutex<HashMap<String, FileAnalysis>>>,
+    hash_algorithm: HashAlgorithm,
+}
+
+impl AnalysisCache {
+    /// Key of the analysis of `contents` by the analyzer of `extension`
+    pub fn key(&self, extension: &str, contents: &[u8]) -> String {
+        format!("{} {}", extension.to_lowercase(), self.hash_algorithm.hash_bytes(contents))
+    }
+    
+    pub fn get(&self, key: &str) -> Option<FileAnalysis> {
+        self.lock().get(key).cloned()
+    }
+    
+    pub fn insert(&self, key: String, analysis: FileAnalysis) {
+        self.lock().insert(key, analysis);
+    }
+    
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, FileAnalysis>> {
+        self.analyses.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Serialize for AnalysisCache {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.lock().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AnalysisCache {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let analyses = HashMap::deserialize(deserializer)?;
+        Ok(Self { analyses: Arc::new(Mutex::new(analyses)), hash_algorithm: HashAlgorithm::default() })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,6 +550,16 @@ mod tests {
             blank_lines: 0,
             file_size: 12,
             doc_lines: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
         
         cache.insert(file_path.clone(), stats.clone()).unwrap();
@@ -186,6 +582,16 @@ mod tests {
             blank_lines: 0,
             file_size: 12,
             doc_lines: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
         
         cache.insert(file_path.clone(), stats).unwrap();
@@ -199,6 +605,201 @@ mod tests {
         assert!(cached_stats.is_none());
     }
     
+    #[test]
+    fn test_cache_hit_on_unchanged_contents() {
+        let project = TestProject::new("test_project").unwrap();
+        let file_path = project.create_file("test.rs", "fn main() {}").unwrap();
+        
+        let mut cache = FileCache::new();
+        let stats = FileStats {
+            total_lines: 1,
+            code_lines: 1,
+            comment_lines: 0,
+            blank_lines: 0,
+            file_size: 12,
+            doc_lines: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
+        };
+        
+        cache.insert(file_path.clone(), stats).unwrap();
+        
+        // Simulate a checkout that only changed the modification time
+        cache.entries.get_mut(&file_path).unwrap().last_modified = 0;
+        assert!(cache.get(&file_path).is_some());
+        
+        // Same size but different contents must still miss
+        cache.entries.get_mut(&file_path).unwrap().content_hash = "xxh3:0".to_string();
+        assert!(cache.get(&file_path).is_none());
+    }
+    
+    #[test]
+    fn test_cache_keyed_by_content() {
+        let project = TestProject::new("test_project").unwrap();
+        let file_path = project.create_file("test.rs", "fn main() {}").unwrap();
+        let copy_path = project.create_file("vendor/copy.rs", "fn main() {}").unwrap();
+        let stats = FileStats { total_lines: 1, code_lines: 1, file_size: 12, ..FileStats::default() };
+        
+        let mut cache = FileCache::new().with_cache_key(CacheKey::Content);
+        cache.insert(file_path.clone(), stats.clone()).unwrap();
+        cache.insert(copy_path.clone(), stats).unwrap();
+        assert_eq!(cache.size(), 2);
+        assert_eq!(cache.stored_stats(), 1);
+        
+        // Identical contents at a path never counted, as long as they are the same language
+        let other_path = project.create_file("other.rs", "fn main() {}").unwrap();
+        assert!(cache.get(&other_path).is_some());
+        assert!(cache.get(&project.create_file("other.py", "fn main() {}").unwrap()).is_none());
+        
+        // An edit keeping the modification time and size is noticed
+        let modified = fs::metadata(&file_path).unwrap().modified().unwrap();
+        fs::write(&file_path, "fn mian() {}").unwrap();
+        fs::File::options().write(true).open(&file_path).unwrap().set_modified(modified).unwrap();
+        assert!(cache.get(&file_path).is_none());
+        assert!(FileCache { cache_key: CacheKey::Mtime, ..cache }.get(&file_path).is_some());
+    }
+    
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let project = TestProject::new("test_project").unwrap();
+        let mut cache = FileCache::new().with_max_entries(Some(10));
+        let paths: Vec<PathBuf> = (0..10)
+            .map(|index| project.create_file(&format!("file{}.rs", index), &format!("fn f{}() {{}}", index)).unwrap())
+            .collect();
+        for (index, path) in paths.iter().enumerate() {
+            cache.insert(path.clone(), FileStats { total_lines: 1, ..FileStats::default() }).unwrap();
+            cache.entries.get_mut(normalize_path(path).as_ref()).unwrap().last_used = index as u64;
+        }
+        // The oldest file was used again just now
+        cache.touch(&paths[0]);
+        assert_eq!(cache.size(), 10);
+        
+        let newest = project.create_file("newest.rs", "fn newest() {}").unwrap();
+        cache.insert(newest.clone(), FileStats::default()).unwrap();
+        assert_eq!(cache.size(), 9);
+        assert_eq!(cache.stored_stats(), 9);
+        assert!(cache.get(&paths[0]).is_some());
+        assert!(cache.get(&newest).is_some());
+        assert!(cache.get(&paths[1]).is_none());
+        assert!(cache.get(&paths[2]).is_none());
+    }
+    
+    #[test]
+    fn test_cache_discarded_across_versions() {
+        let project = TestProject::new("test_project").unwrap();
+        let file_path = project.create_file("test.rs", "fn main() {}").unwrap();
+        let cache_path = project.root.join("cache.json");
+        
+        let mut cache = FileCache::load_from(cache_path.clone()).unwrap();
+        cache.insert(file_path.clone(), FileStats { total_lines: 1, ..FileStats::default() }).unwrap();
+        cache.save().unwrap();
+        assert!(FileCache::load_from(cache_path.clone()).unwrap().get(&file_path).is_some());
+        
+        // Counts of an older howmany are counted again
+        let saved = fs::read_to_string(&cache_path).unwrap();
+        fs::write(&cache_path, saved.replace(&format!("\"{}\"", COUNTER_VERSION), "\"0.0.0\"")).unwrap();
+        assert_eq!(FileCache::inspect_file(&cache_path).unwrap().size(), 1);
+        assert_eq!(FileCache::load_from(cache_path).unwrap().size(), 0);
+    }
+    
+    #[test]
+    fn test_analyses_saved_with_the_cache() {
+        let project = TestProject::new("test_project").unwrap();
+        let file_path = project.create_file("test.rs", "fn main() {}").unwrap();
+        let cache_path = project.root.join("cache.json");
+        
+        let mut cache = FileCache::load_from(cache_path.clone()).unwrap();
+        cache.insert(file_path.clone(), FileStats { total_lines: 1, ..FileStats::default() }).unwrap();
+        let analyses = cache.analysis_cache();
+        let key = analyses.key("rs", &fs::read(&file_path).unwrap());
+        analyses.insert(key.clone(), FileAnalysis::default());
+        cache.save().unwrap();
+        
+        let mut cache = FileCache::load_from(cache_path).unwrap();
+        assert!(cache.analysis_cache().get(&key).is_some());
+        assert!(cache.analysis_cache().get(&cache.analysis_cache().key("py", b"fn main() {}")).is_none());
+        
+        // Analyses go with the last file of their contents
+        cache.remove(&file_path);
+        assert_eq!(cache.stored_analyses(), 0);
+    }
+    
+    #[test]
+    fn test_shared_records_found_by_content() {
+        let project = TestProject::new("test_project").unwrap();
+        let file_path = project.create_file("test.rs", "fn main() {}").unwrap();
+        let mut runner = FileCache::new();
+        runner.insert(file_path.clone(), FileStats { total_lines: 1, ..FileStats::default() }).unwrap();
+        
+        // Another machine has the same contents at another path
+        let other_path = project.create_file("checkout/test.rs", "fn main() {}").unwrap();
+        let mut cache = FileCache::new();
+        assert!(cache.get(&other_path).is_none());
+        assert_eq!(cache.merge_shared(runner.shared_records()), 1);
+        assert!(cache.get(&other_path).is_some());
+        assert_eq!(cache.merge_shared(runner.shared_records()), 0);
+        
+        // Records of another howmany are ignored
+        let records = SharedRecords { counter_version: "0.0.0".to_string(), ..runner.shared_records() };
+        assert_eq!(FileCache::new().merge_shared(records), 0);
+    }
+    
+    #[test]
+    fn test_projects_cached_apart() {
+        let project = TestProject::new("test_project").unwrap();
+        let other = TestProject::new("test_project").unwrap();
+        let cache_path = FileCache::project_cache_path(&project.root).unwrap();
+        assert_ne!(cache_path, FileCache::project_cache_path(&other.root).unwrap());
+        assert_eq!(cache_path, FileCache::project_cache_path(&project.root.join(".")).unwrap());
+        assert_eq!(cache_path.parent().unwrap().file_name().unwrap(), PROJECTS_DIR_NAME);
+    }
+    
+    #[test]
+    fn test_cache_keyed_by_normalized_path() {
+        let project = TestProject::new("test_project").unwrap();
+        let nfd_path = project.create_file("cafe\u{301}.rs", "fn main() {}").unwrap();
+        let nfc_path = project.root.join("caf\u{e9}.rs");
+        
+        let mut cache = FileCache::new();
+        let stats = FileStats {
+            total_lines: 1,
+            code_lines: 1,
+            comment_lines: 0,
+            blank_lines: 0,
+            file_size: 12,
+            doc_lines: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
+        };
+        
+        cache.insert(nfd_path.clone(), stats).unwrap();
+        assert!(cache.entries.contains_key(&nfc_path));
+        assert!(cache.get(&nfd_path).is_some());
+        
+        // Renaming to the other spelling keeps the entry, matched by content
+        fs::rename(&nfd_path, &nfc_path).unwrap();
+        assert!(cache.get(&nfc_path).is_some());
+        
+        cache.cleanup_missing_files();
+        assert_eq!(cache.size(), if nfd_path.exists() { 1 } else { 0 });
+    }
+    
     #[test]
     fn test_cache_cleanup() {
         let project = TestProject::new("test_project").unwrap();
@@ -212,6 +813,16 @@ mod tests {
             blank_lines: 0,
             file_size: 12,
             doc_lines: 0,
+            encoding: None,
+            inline_comment_lines: 0,
+            logical_lines: 0,
+            characters: 0,
+            non_whitespace_characters: 0,
+            tokens: 0,
+            line_lengths: Default::default(),
+            embedded: Default::default(),
+            metrics: Default::default(),
+            ..Default::default()
         };
         
         cache.insert(file_path.clone(), stats).unwrap();
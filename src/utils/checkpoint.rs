@@ -0,0 +1,202 @@
+use crate::core::types::FileStats;
+use crate::utils::cache;
+use crate::utils::errors::Result;
+use crate::utils::hashing::HashAlgorithm;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files analyzed between two checkpoint writes, the most work an interruption can lose
+pub const CHECKPOINT_INTERVAL: usize = 500;
+
+/// One file found by the scan pass
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScannedFile {
+    pub path: PathBuf,
+    /// Index of the analyzed root the file was found under
+    pub root: usize,
+    /// Content hash at scan time, tagged with its algorithm; `None` if unreadable
+    pub hash: Option<String>,
+}
+
+/// Progress of a directory analysis run with `--resume`: the scan result
+/// (file list and content hashes) and the per-file results of the analysis
+/// pass so far. Written to the cache directory as the run goes and removed
+/// once it completes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunCheckpoint {
+    version: u32,
+    /// Roots and filter settings the scan was made with
+    settings: String,
+    files: Vec<ScannedFile>,
+    analyzed: HashMap<PathBuf, FileStats>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    hash_algorithm: HashAlgorithm,
+    /// Results recorded since the last write
+    #[serde(skip)]
+    unsaved: usize,
+    /// Position of each path in `files`
+    #[serde(skip)]
+    index: HashMap<PathBuf, usize>,
+}
+
+impl RunCheckpoint {
+    const VERSION: u32 = 1;
+
+    /// Hash the scanned files and write the checkpoint for a new run
+    pub fn create(settings: &str, files: Vec<(usize, PathBuf)>, hash_algorithm: HashAlgorithm) -> Result<Self> {
+        Self::create_at(Self::checkpoint_path(settings)?, settings, files, hash_algorithm)
+    }
+
+    /// The checkpoint an interrupted run with the same settings left behind
+    pub fn load(settings: &str, hash_algorithm: HashAlgorithm) -> Result<Option<Self>> {
+        Self::load_from(Self::checkpoint_path(settings)?, settings, hash_algorithm)
+    }
+
+    fn create_at(path: PathBuf, settings: &str, files: Vec<(usize, PathBuf)>, hash_algorithm: HashAlgorithm) -> Result<Self> {
+        let files = files
+            .into_par_iter()
+            .map(|(root, path)| {
+                let hash = hash_algorithm.hash_file(&path).ok().map(|hash| hash.to_string());
+                ScannedFile { path, root, hash }
+            })
+            .collect();
+
+        let mut checkpoint = Self {
+            version: Self::VERSION,
+            settings: settings.to_string(),
+            files,
+            analyzed: HashMap::new(),
+            path,
+            hash_algorithm,
+            unsaved: 0,
+            index: HashMap::new(),
+        }
+        .indexed();
+        checkpoint.save()?;
+        Ok(checkpoint)
+    }
+
+    /// A missing, unreadable or outdated checkpoint, or one made with other settings, is ignored
+    fn load_from(path: PathBuf, settings: &str, hash_algorithm: HashAlgorithm) -> Result<Option<Self>> {
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Ok(None);
+        };
+        let checkpoint = serde_json::from_str::<RunCheckpoint>(&content)
+            .ok()
+            .filter(|checkpoint| checkpoint.version == Self::VERSION && checkpoint.settings == settings)
+            .map(|checkpoint| {
+                Self {
+                    path,
+                    hash_algorithm,
+                    ..checkpoint
+                }
+                .indexed()
+            });
+        Ok(checkpoint)
+    }
+
+    fn indexed(mut self) -> Self {
+        self.index = self.files.iter().enumerate().map(|(position, file)| (file.path.clone(), position)).collect();
+        self
+    }
+
+    /// Scanned files paired with the index of their root, in scan order
+    pub fn files(&self) -> Vec<(usize, PathBuf)> {
+        self.files.iter().map(|file| (file.root, file.path.clone())).collect()
+    }
+
+    /// Number of files whose results are already recorded
+    pub fn analyzed_count(&self) -> usize {
+        self.analyzed.len()
+    }
+
+    /// The recorded result for a file, unless its contents changed since the scan
+    pub fn analyzed(&self, path: &Path) -> Option<&FileStats> {
+        let stats = self.analyzed.get(path)?;
+        let scanned_hash = self.files[*self.index.get(path)?].hash.as_ref()?;
+        let current_hash = self.hash_algorithm.hash_file(path).ok()?;
+        (*scanned_hash == current_hash.to_string()).then_some(stats)
+    }
+
+    /// Record a file's result, writing the checkpoint every `CHECKPOINT_INTERVAL` files
+    pub fn record(&mut self, path: &Path, stats: &FileStats) -> Result<()> {
+        self.analyzed.insert(path.to_path_buf(), stats.clone());
+        self.unsaved += 1;
+        if self.unsaved >= CHECKPOINT_INTERVAL {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first so an interruption never leaves a truncated checkpoint
+        let temporary = self.path.with_extension("json.tmp");
+        fs::write(&temporary, serde_json::to_string(self)?)?;
+        fs::rename(&temporary, &self.path)?;
+        self.unsaved = 0;
+        Ok(())
+    }
+
+    /// Delete the checkpoint of a completed run
+    pub fn remove(self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// One checkpoint per set of roots and settings, so unrelated runs never collide
+    fn checkpoint_path(settings: &str) -> Result<PathBuf> {
+        let key = HashAlgorithm::Xxh3.hash_bytes(settings.as_bytes()).digest;
+
+        Ok(cache::cache_dir()?.join(cache::RUNS_DIR_NAME).join(format!("{}.json", key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn stats(code_lines: usize) -> FileStats {
+        FileStats {
+            total_lines: code_lines,
+            code_lines,
+            ..FileStats::default()
+        }
+    }
+
+    #[test]
+    fn test_resume_skips_unchanged_files() {
+        let dir = TempDir::new().unwrap();
+        let (main, lib) = (dir.path().join("main.rs"), dir.path().join("lib.rs"));
+        fs::write(&main, "fn main() {}\n").unwrap();
+        fs::write(&lib, "pub fn lib() {}\n").unwrap();
+        let checkpoint_path = dir.path().join("run.json");
+
+        let files = vec![(0, main.clone()), (0, lib.clone())];
+        let mut checkpoint = RunCheckpoint::create_at(checkpoint_path.clone(), "roots=.", files.clone(), HashAlgorithm::Xxh3).unwrap();
+        checkpoint.record(&main, &stats(1)).unwrap();
+        checkpoint.record(&lib, &stats(1)).unwrap();
+        checkpoint.save().unwrap();
+
+        // Another settings string never picks up the checkpoint
+        assert!(RunCheckpoint::load_from(checkpoint_path.clone(), "roots=src", HashAlgorithm::Xxh3).unwrap().is_none());
+
+        fs::write(&lib, "pub fn lib() {}\npub fn more() {}\n").unwrap();
+        let resumed = RunCheckpoint::load_from(checkpoint_path, "roots=.", HashAlgorithm::Xxh3).unwrap().unwrap();
+        assert_eq!(resumed.files(), files);
+        assert_eq!(resumed.analyzed_count(), 2);
+        assert_eq!(resumed.analyzed(&main), Some(&stats(1)));
+        // Changed since the scan, so it is analyzed again
+        assert_eq!(resumed.analyzed(&lib), None);
+    }
+}
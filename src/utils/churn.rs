@@ -0,0 +1,210 @@
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::remote::ensure_work_tree;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Commits mined when `--churn` is given without a count
+pub const DEFAULT_CHURN_COMMITS: usize = 500;
+
+/// Hotspots kept in the report
+pub const HOTSPOT_LIMIT: usize = 20;
+
+/// Change frequency of the analyzed files over recent git history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChurnStats {
+    /// Commits requested with `--churn N`
+    pub commit_window: usize,
+    /// Non-merge commits actually read, fewer than the window in young repositories
+    pub commits_analyzed: usize,
+    /// Analyzed files changed at least once in the window
+    pub files_changed: usize,
+    /// Files ranked by hotspot score (changes × complexity), largest first
+    pub hotspots: Vec<Hotspot>,
+}
+
+impl ChurnStats {
+    /// Combine results for disjoint file sets, keeping the highest-scoring hotspots
+    pub fn merge<'a>(stats_list: impl IntoIterator<Item = &'a ChurnStats>) -> Option<ChurnStats> {
+        let mut merged: Option<ChurnStats> = None;
+
+        for stats in stats_list {
+            let total = merged.get_or_insert_with(ChurnStats::default);
+            total.commit_window = total.commit_window.max(stats.commit_window);
+            total.commits_analyzed = total.commits_analyzed.max(stats.commits_analyzed);
+            total.files_changed += stats.files_changed;
+            total.hotspots.extend(stats.hotspots.iter().cloned());
+        }
+
+        let mut merged = merged?;
+        merged.hotspots = rank_hotspots(merged.hotspots);
+        Some(merged)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotspot {
+    pub path: String,
+    /// Commits in the window that touched the file
+    pub changes: usize,
+    /// Sum of the cyclomatic complexity of the file's functions
+    pub complexity: usize,
+    pub score: usize,
+}
+
+/// Mines `git log` for how often files change and ranks them as hotspots
+pub struct ChurnAnalyzer {
+    commits: usize,
+}
+
+impl ChurnAnalyzer {
+    pub fn new(commits: usize) -> Self {
+        Self { commits }
+    }
+
+    /// Fail early with a clear message when `path` is not inside a git work tree
+    pub fn ensure_repository(path: &Path) -> Result<()> {
+        ensure_work_tree(path, "--churn")
+    }
+
+    /// Count changes in the last commits touching each root, then score the changed
+    /// files with `complexity`. Unchanged files are never scored.
+    pub fn analyze(
+        &self,
+        roots: &[PathBuf],
+        files: &[PathBuf],
+        complexity: impl Fn(&Path) -> usize,
+    ) -> Result<ChurnStats> {
+        let mut stats = ChurnStats {
+            commit_window: self.commits,
+            ..ChurnStats::default()
+        };
+        let mut changes: HashMap<PathBuf, usize> = HashMap::new();
+
+        for root in roots {
+            let (commits, root_changes) = self.change_counts(root)?;
+            stats.commits_analyzed = stats.commits_analyzed.max(commits);
+            for (path, count) in root_changes {
+                let entry = changes.entry(path).or_default();
+                // Overlapping roots see the same commits, so don't add them twice
+                *entry = (*entry).max(count);
+            }
+        }
+
+        let mut hotspots = Vec::new();
+        for file in files {
+            let Some(&file_changes) = file.canonicalize().ok().and_then(|path| changes.get(&path)) else {
+                continue;
+            };
+            stats.files_changed += 1;
+
+            let file_complexity = complexity(file);
+            if file_complexity > 0 {
+                hotspots.push(Hotspot {
+                    path: file.to_string_lossy().to_string(),
+                    changes: file_changes,
+                    complexity: file_complexity,
+                    score: file_changes * file_complexity,
+                });
+            }
+        }
+
+        stats.hotspots = rank_hotspots(hotspots);
+        Ok(stats)
+    }
+
+    /// Commits read and changes per absolute file path for one root
+    fn change_counts(&self, root: &Path) -> Result<(usize, HashMap<PathBuf, usize>)> {
+        let (dir, pathspec) = if root.is_file() {
+            let dir = root.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            (dir, root.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default())
+        } else {
+            (root, ".".to_string())
+        };
+
+        let toplevel = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim());
+        let limit = format!("--max-count={}", self.commits);
+        let log = git(dir, &["log", &limit, "--no-merges", "--format=%x1e", "--name-only", "--", &pathspec])?;
+
+        let (commits, changes) = parse_name_only_log(&log);
+        let changes = changes
+            .into_iter()
+            .map(|(name, count)| (toplevel.join(name), count))
+            .collect();
+        Ok((commits, changes))
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| HowManyError::repository(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(HowManyError::repository(format!(
+            "git {} failed in {}: {}",
+            args.first().copied().unwrap_or_default(),
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `git log --format=%x1e --name-only` output into the number of commits
+/// and the number of commits touching each repository-relative path
+pub fn parse_name_only_log(output: &str) -> (usize, HashMap<String, usize>) {
+    let mut commits = 0;
+    let mut changes: HashMap<String, usize> = HashMap::new();
+
+    for line in output.lines() {
+        if line.starts_with('\u{1e}') {
+            commits += 1;
+        } else if !line.trim().is_empty() {
+            *changes.entry(line.to_string()).or_default() += 1;
+        }
+    }
+
+    (commits, changes)
+}
+
+/// Sort by score, largest first, and keep the top `HOTSPOT_LIMIT`
+fn rank_hotspots(mut hotspots: Vec<Hotspot>) -> Vec<Hotspot> {
+    hotspots.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| b.changes.cmp(&a.changes)).then_with(|| a.path.cmp(&b.path)));
+    hotspots.truncate(HOTSPOT_LIMIT);
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_only_log() {
+        let log = "\u{1e}\n\nsrc/lib.rs\nsrc/main.rs\n\u{1e}\n\nsrc/lib.rs\n";
+        let (commits, changes) = parse_name_only_log(log);
+
+        assert_eq!(commits, 2);
+        assert_eq!(changes.get("src/lib.rs"), Some(&2));
+        assert_eq!(changes.get("src/main.rs"), Some(&1));
+    }
+
+    #[test]
+    fn test_rank_hotspots() {
+        let hotspot = |path: &str, changes, complexity| Hotspot {
+            path: path.to_string(),
+            changes,
+            complexity,
+            score: changes * complexity,
+        };
+
+        let ranked = rank_hotspots(vec![hotspot("a.rs", 2, 10), hotspot("b.rs", 10, 5), hotspot("c.rs", 20, 1)]);
+        let paths: Vec<_> = ranked.iter().map(|hotspot| hotspot.path.as_str()).collect();
+        // Equal scores rank the more frequently changed file first
+        assert_eq!(paths, ["b.rs", "c.rs", "a.rs"]);
+    }
+}
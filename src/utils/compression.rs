@@ -0,0 +1,50 @@
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::format::format_size;
+use flate2::read::MultiGzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Extension of single-file gzip sources such as `schema.sql.gz`
+pub const GZIP_EXTENSION: &str = "gz";
+
+/// Limit on decompressed contents when `--max-file-size 0` lifts the size limit
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Check whether a path is a gzip-compressed file
+pub fn is_gzip(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().eq_ignore_ascii_case(GZIP_EXTENSION))
+        .unwrap_or(false)
+}
+
+/// Path of the source inside a gzip file, e.g. `data/schema.sql` for `data/schema.sql.gz`
+pub fn decompressed_path(path: &Path) -> Option<PathBuf> {
+    if is_gzip(path) {
+        Some(path.with_extension(""))
+    } else {
+        None
+    }
+}
+
+/// Decompress a gzip file into memory, failing once the output grows past `max_size` bytes
+pub fn read_gzip(path: &Path, max_size: u64) -> Result<Vec<u8>> {
+    let file = fs::File::open(path)?;
+    let mut contents = Vec::new();
+
+    // Read one byte past the limit so oversized contents can be told apart from an exact fit
+    MultiGzDecoder::new(file)
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut contents)
+        .map_err(|e| HowManyError::file_processing(format!("Failed to decompress {}: {}", path.display(), e)))?;
+
+    if contents.len() as u64 > max_size {
+        return Err(HowManyError::file_processing(format!(
+            "{} decompresses to more than {}",
+            path.display(),
+            format_size(max_size)
+        )));
+    }
+
+    Ok(contents)
+}
@@ -1,17 +1,107 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use crate::core::detector::patterns::category::FileCategory;
+use crate::core::detector::patterns::generated::{self, GeneratedPatterns};
+use crate::core::stats::QualityWeights;
 use crate::utils::errors::{HowManyError, Result};
+use crate::utils::cache::{CacheKey, DEFAULT_MAX_ENTRIES};
+use crate::utils::hashing::HashAlgorithm;
+
+/// Per-project configuration file, looked up in the analyzed directory
+pub const PROJECT_CONFIG_FILE: &str = ".howmany.toml";
+
+/// `.howmany.toml` written by `howmany config init`: the default ignore
+/// patterns, and every other section commented out with an example
+pub const PROJECT_CONFIG_TEMPLATE: &str = r#"# howmany configuration for this project. Every setting is optional;
+# `howmany config show` prints the values in effect and `howmany doctor`
+# checks this file against the tree.
+
+# Paths left out of the analysis, matched like .gitignore entries. These
+# replace the built-in list, so keep the entries you still want.
+custom_ignore_patterns = ["*.tmp", "*.log", ".DS_Store", "node_modules/", "__pycache__/", "target/"]
+
+# Paths the analysis is restricted to; empty analyzes everything
+custom_include_patterns = []
+
+# Parts of the tree reported with their own totals
+# [areas]
+# frontend = ["web/**"]
+# backend = ["server/**", "shared/**"]
+
+# Size caps for the files matching a glob, warned about at `warn_at` percent
+# [budget]
+# "src/parser/**" = { max_code_lines = 20000, max_files = 40, warn_at = 80 }
+
+# Team members by email or git author name, for `--owners`
+# [teams]
+# platform = ["ada@example.com"]
+
+# [complexity]
+# skip = ["src/grammar/parser.rs"]
+# analyzers = { "*.mjs" = "js" }
+
+# [generated]
+# custom_markers = ["Autogenerated by our-tool"]
+# handwritten_declarations = ["types/"]
+
+# How prose files (.md, .rst, ...) add to the documentation totals: "full", "weighted" or "excluded"
+# [documentation]
+# prose = "weighted"
+# prose_weight = 0.25
+
+# Directory categories left out of the quality score, and the weights of its
+# components; a weight of 0 leaves a component out
+# [scoring]
+# exclude = ["examples", "benches", "docs"]
+# [scoring.weights]
+# documentation = 0.25
+# maintainability = 0.35
+# readability = 0.25
+# consistency = 0.15
+# complexity = 0
+# size = 0
+# duplication = 0
+
+# Levels and thresholds of the SARIF rules, also used by `howmany diff`
+# [sarif.rules.HM101]
+# level = "error"
+# threshold = 20
+
+# [cache]
+# max_entries = 100000
+# [cache.remote]
+# url = "s3://ci-cache/howmany"
+"#;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HowManyConfig {
     pub default_max_depth: Option<usize>,
     pub default_include_hidden: bool,
     pub default_ignore_gitignore: bool,
     pub custom_ignore_patterns: Vec<String>,
+    /// Globs restricting analysis to matching paths (empty means everything)
+    #[serde(default)]
+    pub custom_include_patterns: Vec<String>,
     pub language_extensions: HashMap<String, Vec<String>>,
     pub output_preferences: OutputPreferences,
     pub performance: PerformanceConfig,
+    pub sarif: SarifConfig,
+    pub hashing: HashingConfig,
+    pub cache: CacheConfig,
+    pub generated: GeneratedConfig,
+    pub documentation: DocumentationConfig,
+    pub scoring: ScoringConfig,
+    pub complexity: ComplexityConfig,
+    /// Named groups of path globs reported separately, e.g. `frontend = ["web/**"]`.
+    /// A file may belong to several areas.
+    pub areas: BTreeMap<String, Vec<String>>,
+    /// Size caps for the files matching a glob, e.g.
+    /// `"src/parser/**" = { max_code_lines = 20000 }`
+    pub budget: BTreeMap<String, BudgetConfig>,
+    /// Team members by email or git author name for `--owners`, e.g. `platform = ["ada@example.com"]`
+    pub teams: BTreeMap<String, Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +119,208 @@ pub struct PerformanceConfig {
     pub chunk_size: usize,
 }
 
+/// Content hashing used by the file cache (`[hashing] algorithm = "blake3"`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HashingConfig {
+    pub algorithm: HashAlgorithm,
+    /// Whether cached counts are found by modification time or by contents
+    pub cache_key: CacheKey,
+}
+
+/// The cache of file counts (`[cache] max_entries = 50000`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Files kept at most; the least recently used are evicted beyond it
+    pub max_entries: usize,
+    /// Cache shared between machines, such as CI runners (`[cache.remote]`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<RemoteCacheConfig>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { max_entries: DEFAULT_MAX_ENTRIES, remote: None }
+    }
+}
+
+/// Shared cache of counts and analyses keyed by content hash
+/// (`[cache.remote] url = "s3://ci-cache/howmany"`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteCacheConfig {
+    /// `https://` or `http://` URL of a directory accepting GET and PUT, `s3://bucket/prefix`,
+    /// or `redis://host:port`
+    pub url: String,
+    /// Name of the shared records, the project directory's name when unset
+    pub name: Option<String>,
+    /// Upload the records after a run that counted files; off for runners that only read
+    pub push: bool,
+    /// Environment variable holding a bearer token for HTTP servers
+    pub token_env: Option<String>,
+}
+
+impl Default for RemoteCacheConfig {
+    fn default() -> Self {
+        Self { url: String::new(), name: None, push: true, token_env: None }
+    }
+}
+
+/// Header markers that classify a file as generated (`[generated] markers = ["@generated"]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratedConfig {
+    /// Lines at the top of a file searched for a marker
+    pub header_lines: usize,
+    /// Built-in markers to look for, by name
+    pub markers: Vec<String>,
+    /// Additional marker texts, matched literally inside a header comment
+    pub custom_markers: Vec<String>,
+    /// Globs of hand-written `.d.ts` files, counted as code rather than as
+    /// generated declarations, e.g. `["types/", "src/env.d.ts"]`
+    pub handwritten_declarations: Vec<String>,
+}
+
+impl Default for GeneratedConfig {
+    fn default() -> Self {
+        Self {
+            header_lines: generated::HEADER_LINES,
+            markers: generated::MARKERS
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .chain([generated::LICENSE_ONLY.to_string()])
+                .collect(),
+            custom_markers: Vec::new(),
+            handwritten_declarations: Vec::new(),
+        }
+    }
+}
+
+impl GeneratedConfig {
+    /// Generated-code patterns with these markers; fails on an unknown marker name
+    pub fn patterns(&self) -> Result<GeneratedPatterns> {
+        GeneratedPatterns::with_markers(self.header_lines, &self.markers, &self.custom_markers)?
+            .with_handwritten_declarations(&self.handwritten_declarations)
+    }
+}
+
+/// How prose lines count toward the documentation totals
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProseDocs {
+    /// Every prose line is a documentation line
+    #[default]
+    Full,
+    /// Prose lines count with `prose_weight`
+    Weighted,
+    /// Prose lines don't count as documentation
+    Excluded,
+}
+
+/// Contribution of prose formats such as Markdown to the documentation
+/// metrics (`[documentation] prose = "weighted"`). Prose files still report
+/// their own doc lines; the policy applies when files are summed into totals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentationConfig {
+    pub prose: ProseDocs,
+    /// Weight of a prose line with `prose = "weighted"`, between 0 and 1
+    pub prose_weight: f64,
+    /// Extensions treated as prose
+    pub prose_extensions: Vec<String>,
+}
+
+impl Default for DocumentationConfig {
+    fn default() -> Self {
+        Self {
+            prose: ProseDocs::Full,
+            prose_weight: 0.25,
+            prose_extensions: ["md", "markdown", "mdx", "rst", "adoc", "txt"].map(String::from).to_vec(),
+        }
+    }
+}
+
+impl DocumentationConfig {
+    pub fn is_prose(&self, extension: &str) -> bool {
+        self.prose_extensions.iter().any(|prose| prose.eq_ignore_ascii_case(extension))
+    }
+
+    /// Documentation lines that prose files with `doc_lines` in total add to the totals
+    pub fn prose_doc_lines(&self, doc_lines: usize) -> usize {
+        match self.prose {
+            ProseDocs::Full => doc_lines,
+            ProseDocs::Weighted => (doc_lines as f64 * self.prose_weight.clamp(0.0, 1.0)).round() as usize,
+            ProseDocs::Excluded => 0,
+        }
+    }
+}
+
+/// Which files the project quality score is computed from
+/// (`[scoring] exclude = ["examples", "benches", "docs"]`) and how its
+/// components weigh. Files of an excluded category still count toward the
+/// line totals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    /// Categories left out of the quality score; an empty list scores every file
+    pub exclude: Vec<FileCategory>,
+    /// Weights of the components of the overall quality score
+    pub weights: QualityWeights,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self { exclude: FileCategory::ALL.to_vec(), weights: QualityWeights::default() }
+    }
+}
+
+impl ScoringConfig {
+    pub fn is_scored(&self, category: Option<FileCategory>) -> bool {
+        category.is_none_or(|category| !self.exclude.contains(&category))
+    }
+}
+
+/// Complexity analysis by path (`[complexity] skip = ["src/parser/generated/**"]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ComplexityConfig {
+    /// Globs of files left out of complexity analysis, such as huge generated parsers
+    pub skip: Vec<String>,
+    /// Analyzer for the files matching a glob, by an extension it handles,
+    /// e.g. `"*.mjs" = "js"`
+    pub analyzers: BTreeMap<String, String>,
+}
+
+/// Caps for one `[budget]` glob; unset caps aren't checked. A budget warns once
+/// a total reaches `warn_at` percent of its cap, before it is exceeded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetConfig {
+    pub max_code_lines: Option<usize>,
+    pub max_lines: Option<usize>,
+    pub max_files: Option<usize>,
+    /// Percentage of a cap at which to warn (80 when unset)
+    pub warn_at: Option<f64>,
+}
+
+/// SARIF rule overrides, keyed by rule ID (e.g. `[sarif.rules.HM101]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SarifConfig {
+    pub rules: HashMap<String, SarifRuleConfig>,
+}
+
+/// Override for a single SARIF rule; unset fields keep the built-in defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SarifRuleConfig {
+    pub enabled: Option<bool>,
+    /// One of "error", "warning", "note" or "none"
+    pub level: Option<String>,
+    pub threshold: Option<f64>,
+}
+
 impl Default for HowManyConfig {
     fn default() -> Self {
         Self {
@@ -43,9 +335,20 @@ impl Default for HowManyConfig {
                 "__pycache__/".to_string(),
                 "target/".to_string(),
             ],
+            custom_include_patterns: Vec::new(),
             language_extensions: Self::default_language_extensions(),
             output_preferences: OutputPreferences::default(),
             performance: PerformanceConfig::default(),
+            sarif: SarifConfig::default(),
+            hashing: HashingConfig::default(),
+            cache: CacheConfig::default(),
+            generated: GeneratedConfig::default(),
+            documentation: DocumentationConfig::default(),
+            scoring: ScoringConfig::default(),
+            complexity: ComplexityConfig::default(),
+            areas: BTreeMap::new(),
+            budget: BTreeMap::new(),
+            teams: BTreeMap::new(),
         }
     }
 }
@@ -76,15 +379,75 @@ impl HowManyConfig {
         let config_path = Self::config_path()?;
         
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            let config: HowManyConfig = toml::from_str(&content)
-                .map_err(|e| HowManyError::invalid_config(format!("Failed to parse config: {}", e)))?;
-            Ok(config)
+            Self::load_from(&config_path)
         } else {
             Ok(Self::default())
         }
     }
     
+    /// Config file `load_for_project` reads for `project_root`, or `None` when
+    /// the built-in defaults are used
+    pub fn source_for_project(project_root: &Path) -> Option<PathBuf> {
+        let project_config = project_root.join(PROJECT_CONFIG_FILE);
+        if project_config.is_file() {
+            return Some(project_config);
+        }
+        Self::config_path().ok().filter(|path| path.exists())
+    }
+    
+    /// Load `.howmany.toml` from the project root, falling back to the user config.
+    /// Parse errors in either file are reported with their line and column.
+    pub fn load_for_project(project_root: &Path) -> Result<Self> {
+        let project_config = project_root.join(PROJECT_CONFIG_FILE);
+        
+        if project_config.is_file() {
+            Self::load_from(&project_config)
+        } else {
+            Self::load()
+        }
+    }
+    
+    /// Write the commented `.howmany.toml` template to `project_root`,
+    /// replacing an existing file only when `force` is set
+    pub fn init_project(project_root: &Path, force: bool) -> Result<PathBuf> {
+        let path = project_root.join(PROJECT_CONFIG_FILE);
+        if path.exists() && !force {
+            return Err(HowManyError::invalid_config(format!("{} already exists (--force replaces it)", path.display())));
+        }
+        std::fs::write(&path, PROJECT_CONFIG_TEMPLATE)?;
+        Ok(path)
+    }
+    
+    fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| {
+            let location = match e.span() {
+                Some(span) => {
+                    let (line, column) = Self::line_column(&content, span.start);
+                    format!("{}:{}:{}", path.display(), line, column)
+                }
+                None => path.display().to_string(),
+            };
+            HowManyError::invalid_config(format!("Failed to parse config {}: {}", location, e.message().trim()))
+        })
+    }
+    
+    /// 1-based line and column of a byte offset
+    fn line_column(content: &str, offset: usize) -> (usize, usize) {
+        let before = &content[..offset.min(content.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |text| text.chars().count()) + 1;
+        (line, column)
+    }
+    
+    /// The configuration as TOML, with the keys of every table sorted
+    pub fn to_toml(&self) -> Result<String> {
+        // Going through `toml::Value` orders the hash maps' keys
+        toml::Value::try_from(self)
+            .and_then(|value| toml::to_string_pretty(&value))
+            .map_err(|e| HowManyError::invalid_config(format!("Failed to serialize config: {}", e)))
+    }
+    
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path()?;
         
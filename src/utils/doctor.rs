@@ -0,0 +1,457 @@
+use crate::core::filters::{GlobPatterns, HOWMANYIGNORE_FILE};
+use crate::core::plugins;
+use crate::core::stats::complexity::FunctionThresholds;
+use crate::ui::cli::{Config, OutputFormat};
+use crate::ui::sarif::SarifRuleSet;
+use crate::utils::cache::{self, FileCache};
+use crate::utils::churn::ChurnAnalyzer;
+use crate::utils::config::{HowManyConfig, PROJECT_CONFIG_FILE};
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::format;
+use crate::utils::ownership::OwnershipAnalyzer;
+use crate::utils::shared_cache::SharedCache;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use toml::Value;
+
+/// How serious a finding of `howmany doctor` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    /// Works, but likely not as intended
+    Warning,
+    /// Makes runs fail or ignore part of the setup
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub section: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Findings of `howmany doctor`, in the order they were checked
+#[derive(Debug, Default, Serialize)]
+pub struct DoctorReport {
+    pub findings: Vec<Finding>,
+}
+
+impl DoctorReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ok(&mut self, section: &'static str, message: impl Into<String>) {
+        self.add(section, Severity::Ok, message);
+    }
+
+    pub fn warning(&mut self, section: &'static str, message: impl Into<String>) {
+        self.add(section, Severity::Warning, message);
+    }
+
+    pub fn error(&mut self, section: &'static str, message: impl Into<String>) {
+        self.add(section, Severity::Error, message);
+    }
+
+    fn add(&mut self, section: &'static str, severity: Severity, message: impl Into<String>) {
+        self.findings.push(Finding { section, severity, message: message.into() });
+    }
+
+    /// Findings of a severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == severity).count()
+    }
+
+    /// Findings under a heading per section, followed by a count of the problems
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        let mut section = "";
+        for finding in &self.findings {
+            if finding.section != section {
+                if !section.is_empty() {
+                    output.push('\n');
+                }
+                section = finding.section;
+                output.push_str(section);
+                output.push('\n');
+            }
+            output.push_str(&format!("  {:<8} {}\n", finding.severity.to_string(), finding.message));
+        }
+
+        let (errors, warnings) = (self.count(Severity::Error), self.count(Severity::Warning));
+        output.push('\n');
+        if errors == 0 && warnings == 0 {
+            output.push_str("No problems found\n");
+        } else {
+            output.push_str(&format!(
+                "{} {}, {} {}\n",
+                errors,
+                if errors == 1 { "error" } else { "errors" },
+                warnings,
+                if warnings == 1 { "warning" } else { "warnings" }
+            ));
+        }
+        output
+    }
+
+    /// The exit status of `howmany doctor`: a configuration error when any check failed
+    pub fn outcome(&self) -> Result<()> {
+        match self.count(Severity::Error) {
+            0 => Ok(()),
+            1 => Err(HowManyError::invalid_config("doctor found 1 problem")),
+            errors => Err(HowManyError::invalid_config(format!("doctor found {} problems", errors))),
+        }
+    }
+}
+
+/// Check the config file of the project at `root`, test its patterns against
+/// the tree, check the caches and the options of `config`, reporting every
+/// problem found rather than stopping at the first. `check_settings` builds
+/// the analysis settings from the config file and function thresholds, as a run would.
+pub fn diagnose(config: &Config, root: &Path, check_settings: impl FnOnce(&HowManyConfig, FunctionThresholds) -> Result<()>) -> DoctorReport {
+    let mut report = DoctorReport::new();
+
+    let source = HowManyConfig::source_for_project(root);
+    let file_config = match HowManyConfig::load_for_project(root) {
+        Ok(file_config) => {
+            match &source {
+                Some(path) => report.ok("Configuration", format!("{} parses", path.display())),
+                None => report.ok("Configuration", format!("no {} or user config, so the built-in defaults apply (`howmany config init` writes one)", PROJECT_CONFIG_FILE)),
+            }
+            file_config
+        }
+        Err(e) => {
+            report.error("Configuration", e.to_string());
+            HowManyConfig::default()
+        }
+    };
+    if let Some(contents) = source.as_ref().and_then(|path| std::fs::read_to_string(path).ok()) {
+        for key in unknown_keys(&contents).unwrap_or_default() {
+            report.warning("Configuration", format!("unknown key `{}` is ignored", key));
+        }
+    }
+    if let Err(e) = plugins::discover(root) {
+        report.error("Configuration", e.to_string());
+    }
+    let thresholds = match SarifRuleSet::from_config(&file_config.sarif) {
+        Ok(sarif_rules) => sarif_rules.function_thresholds(),
+        Err(e) => {
+            report.error("Configuration", e.to_string());
+            FunctionThresholds::default()
+        }
+    };
+    match check_settings(&file_config, thresholds) {
+        Ok(()) => report.ok("Configuration", "settings and globs are valid"),
+        Err(e) => report.error("Configuration", e.to_string()),
+    }
+
+    check_patterns(&mut report, config, root, &file_config);
+    check_caches(&mut report, config, root, &file_config);
+    check_options(&mut report, config, root);
+    report
+}
+
+/// Every pattern is tested against the tree an analysis walks. The built-in
+/// ignore patterns cover many ecosystems, so those matching nothing are expected.
+fn check_patterns(report: &mut DoctorReport, config: &Config, root: &Path, file_config: &HowManyConfig) {
+    let ignore_patterns = config.resolve_ignore_patterns(file_config);
+    let include_patterns = config.resolve_include_patterns(file_config);
+    let ignores = GlobPatterns::new(&ignore_patterns).unwrap_or_else(|_| GlobPatterns::new::<&str>(&[]).expect("no patterns"));
+    let paths = tree_paths(root, config.include_hidden, &config.ignore_files, &ignores);
+    let default_ignores = HowManyConfig::default().custom_ignore_patterns;
+    let check_pattern = |report: &mut DoctorReport, label: String, pattern: &str, expected_unused: bool| match matching_paths(&paths, pattern) {
+        Ok(0) if expected_unused => {}
+        Ok(0) => report.warning("Patterns", format!("{} `{}` matches nothing", label, pattern)),
+        Ok(matches) => report.ok("Patterns", format!("{} `{}` matches {} {}", label, pattern, format::format_number(matches), if matches == 1 { "path" } else { "paths" })),
+        Err(e) => report.error("Patterns", format!("{} `{}` is invalid: {}", label, pattern, e)),
+    };
+    for pattern in &ignore_patterns {
+        check_pattern(report, "ignore pattern".to_string(), pattern, default_ignores.contains(pattern));
+    }
+    for pattern in &include_patterns {
+        if !config.include_hidden && names_hidden_path(pattern) {
+            report.warning("Patterns", format!("include pattern `{}` names a hidden path, which only --hidden walks", pattern));
+        } else {
+            check_pattern(report, "include pattern".to_string(), pattern, false);
+        }
+    }
+    for (area, patterns) in &file_config.areas {
+        for pattern in patterns {
+            check_pattern(report, format!("area `{}` pattern", area), pattern, false);
+        }
+    }
+    for pattern in file_config.budget.keys() {
+        check_pattern(report, "budget".to_string(), pattern, false);
+    }
+    for pattern in &file_config.complexity.skip {
+        check_pattern(report, "complexity.skip pattern".to_string(), pattern, false);
+    }
+}
+
+/// The cache directory must be writable, existing caches readable and a shared cache reachable
+fn check_caches(report: &mut DoctorReport, config: &Config, root: &Path, file_config: &HowManyConfig) {
+    match cache::cache_dir() {
+        Ok(dir) => {
+            let writable = std::fs::create_dir_all(&dir).and_then(|_| tempfile::NamedTempFile::new_in(&dir).map(drop));
+            match writable {
+                Ok(()) => report.ok("Cache", format!("{} is writable", dir.display())),
+                Err(e) => report.error("Cache", format!("{} is not writable, so nothing is cached: {}", dir.display(), e)),
+            }
+        }
+        Err(e) => report.error("Cache", e.to_string()),
+    }
+    for cache_path in [FileCache::cache_path(), FileCache::project_cache_path(root)].into_iter().flatten() {
+        if !cache_path.exists() {
+            continue;
+        }
+        match FileCache::inspect_file(&cache_path) {
+            Ok(file_cache) => report.ok("Cache", format!("{} holds {} files", cache_path.display(), format::format_number(file_cache.size()))),
+            Err(e) => report.warning("Cache", format!("{} is unreadable, so runs start it over: {}", cache_path.display(), e)),
+        }
+    }
+    if let Some(remote) = &file_config.cache.remote {
+        if config.no_cache {
+            report.warning("Cache", "--no-cache also skips the shared cache of [cache.remote]");
+        }
+        match SharedCache::new(remote) {
+            Ok(shared) => {
+                let shared = shared.for_project(root);
+                if on_path(shared.program()) {
+                    report.ok("Cache", format!("shared cache at {} is reached with {}", shared.location(), shared.program()));
+                } else {
+                    report.error("Cache", format!("shared cache at {} needs {}, which is not on PATH", shared.location(), shared.program()));
+                }
+            }
+            Err(e) => report.error("Cache", e.to_string()),
+        }
+        if let Some(variable) = remote.token_env.as_ref().filter(|variable| std::env::var_os(variable).is_none()) {
+            report.error("Cache", format!("[cache.remote] token_env names {}, which is not set", variable));
+        }
+    }
+}
+
+/// Options that conflict, or that need a git repository at `root`
+fn check_options(report: &mut DoctorReport, config: &Config, root: &Path) {
+    let findings = report.findings.len();
+    for problem in config.problems() {
+        report.error("Options", problem);
+    }
+    if config.fast_json && config.formats.iter().any(|format| format != &OutputFormat::Json) {
+        report.warning("Options", "--fast-json always prints JSON, so the other --output formats are ignored");
+    }
+    if config.owners {
+        if let Err(e) = OwnershipAnalyzer::ensure_repository(root) {
+            report.error("Options", e.to_string());
+        }
+    }
+    if config.churn.is_some() {
+        if let Err(e) = ChurnAnalyzer::ensure_repository(root) {
+            report.error("Options", e.to_string());
+        }
+    }
+    if report.findings.len() == findings {
+        report.ok("Options", "no conflicting options");
+    }
+}
+
+/// Keys of a config file that `HowManyConfig` doesn't have, such as a
+/// misspelled `cache.max_entrys`. Loading a config ignores them silently.
+pub fn unknown_keys(contents: &str) -> Result<Vec<String>> {
+    let invalid = |e: toml::de::Error| HowManyError::invalid_config(format!("Failed to parse config: {}", e.message().trim()));
+    let written: Value = toml::from_str(contents).map_err(invalid)?;
+    let config: HowManyConfig = toml::from_str(contents).map_err(invalid)?;
+    // Loading keeps every key it knows, so the keys missing after a round trip are the unknown ones
+    let known = Value::try_from(&config).map_err(|e| HowManyError::invalid_config(format!("Failed to serialize config: {}", e)))?;
+
+    let mut unknown = Vec::new();
+    collect_unknown_keys(&written, &known, "", &mut unknown);
+    Ok(unknown)
+}
+
+fn collect_unknown_keys(written: &Value, known: &Value, prefix: &str, unknown: &mut Vec<String>) {
+    let (Value::Table(written), Value::Table(known)) = (written, known) else {
+        return;
+    };
+    for (key, value) in written {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match known.get(key) {
+            Some(known_value) => collect_unknown_keys(value, known_value, &path, unknown),
+            None => unknown.push(path),
+        }
+    }
+}
+
+/// Paths below `root`, relative to it, that patterns are tested against. The
+/// walk honours the ignore files and hidden setting of an analysis but not its
+/// patterns, except that it doesn't descend into directories `ignores` match.
+pub fn tree_paths(root: &Path, include_hidden: bool, ignore_files: &[PathBuf], ignores: &GlobPatterns) -> Vec<PathBuf> {
+    let mut builder = WalkBuilder::new(root);
+    builder.hidden(!include_hidden).add_custom_ignore_filename(HOWMANYIGNORE_FILE);
+    for ignore_file in ignore_files {
+        builder.add_ignore(ignore_file);
+    }
+
+    // Pruned directories never come out of the walk, so every entry is recorded as it is filtered
+    let paths = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&paths);
+    let (root_path, ignores) = (root.to_path_buf(), ignores.clone());
+    builder.filter_entry(move |entry| {
+        let Ok(relative) = entry.path().strip_prefix(&root_path) else {
+            return true;
+        };
+        if relative.as_os_str().is_empty() {
+            return true;
+        }
+        if relative.starts_with(".git") {
+            return false;
+        }
+        let is_dir = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+        recorder.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(relative.to_path_buf());
+        !(is_dir && ignores.is_match(relative))
+    });
+    builder.build().for_each(drop);
+
+    let mut paths = std::mem::take(&mut *paths.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+    paths.sort();
+    paths
+}
+
+/// Paths one pattern matches. A negated pattern (`!keep.rs`) counts the paths it takes back.
+pub fn matching_paths(paths: &[PathBuf], pattern: &str) -> Result<usize> {
+    let pattern = pattern.trim();
+    let globs = GlobPatterns::new(&[pattern.strip_prefix('!').unwrap_or(pattern)])?;
+    Ok(paths.iter().filter(|path| globs.is_match(path)).count())
+}
+
+/// Whether a pattern names a hidden file or directory, which only `--hidden` walks
+pub fn names_hidden_path(pattern: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches('!');
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+    pattern.split('/').any(|component| component.starts_with('.') && component.len() > 1 && component != "..")
+}
+
+/// Whether `program` is an executable file in a directory of `$PATH`
+pub fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::config::PROJECT_CONFIG_TEMPLATE;
+
+    #[test]
+    fn test_unknown_keys_found_at_any_depth() {
+        let contents = r#"
+custom_ignore_patterns = ["dist/"]
+custom_ignore_pattern = ["build/"]
+
+[areas]
+frontend = ["web/**"]
+
+[cache]
+max_entrys = 10
+
+[sarif.rules.HM101]
+threshold = 20
+treshold = 30
+"#;
+        assert_eq!(unknown_keys(contents).unwrap(), vec!["cache.max_entrys", "custom_ignore_pattern", "sarif.rules.HM101.treshold"]);
+    }
+
+    #[test]
+    fn test_template_is_a_valid_config() {
+        assert!(unknown_keys(PROJECT_CONFIG_TEMPLATE).unwrap().is_empty());
+        let config: HowManyConfig = toml::from_str(PROJECT_CONFIG_TEMPLATE).unwrap();
+        assert_eq!(config.custom_ignore_patterns, HowManyConfig::default().custom_ignore_patterns);
+    }
+
+    #[test]
+    fn test_pattern_matches_counted() {
+        let paths: Vec<PathBuf> = ["src", "src/main.rs", "src/gen", "src/gen/api.rs", "web/app.min.js"].iter().map(PathBuf::from).collect();
+        assert_eq!(matching_paths(&paths, "src/gen/").unwrap(), 2);
+        assert_eq!(matching_paths(&paths, "*.min.js").unwrap(), 1);
+        assert_eq!(matching_paths(&paths, "!src/main.rs").unwrap(), 1);
+        assert_eq!(matching_paths(&paths, "fixtures/**").unwrap(), 0);
+        assert!(matching_paths(&paths, "src/[a-").is_err());
+
+        assert!(names_hidden_path(".github/**"));
+        assert!(names_hidden_path("!config/.env"));
+        assert!(!names_hidden_path("./src/**"));
+        assert!(!names_hidden_path("*.rs"));
+    }
+
+    #[test]
+    fn test_report_counts_problems() {
+        let mut report = DoctorReport::new();
+        report.ok("Configuration", "./.howmany.toml parses");
+        report.warning("Patterns", "ignore pattern `fixtures/**` matches nothing");
+        let rendered = report.render();
+        assert!(rendered.starts_with("Configuration\n  ok       ./.howmany.toml parses\n\nPatterns\n"));
+        assert!(rendered.ends_with("0 errors, 1 warning\n"));
+    }
+
+    fn findings(report: &DoctorReport, section: &str) -> Vec<(Severity, String)> {
+        report.findings.iter().filter(|finding| finding.section == section).map(|finding| (finding.severity, finding.message.clone())).collect()
+    }
+
+    #[test]
+    fn test_init_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = HowManyConfig::init_project(dir.path(), false).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), PROJECT_CONFIG_TEMPLATE);
+
+        std::fs::write(&path, "custom_ignore_patterns = []\n").unwrap();
+        let error = HowManyConfig::init_project(dir.path(), false).unwrap_err();
+        assert_eq!(error.category().exit_code(), 4);
+        HowManyConfig::init_project(dir.path(), true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), PROJECT_CONFIG_TEMPLATE);
+    }
+
+    #[test]
+    fn test_diagnose() {
+        use clap::Parser;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join(PROJECT_CONFIG_FILE), "custom_include_patterns = [\"src/**\", \"docs/**\", \".github/**\"]\n\n[cache]\nmax_entrys = 10\n").unwrap();
+
+        let config = Config::try_parse_from(["howmany"]).unwrap();
+        let report = diagnose(&config, dir.path(), |_, _| Ok(()));
+        let configuration = findings(&report, "Configuration");
+        assert!(configuration.contains(&(Severity::Warning, "unknown key `cache.max_entrys` is ignored".to_string())));
+        assert_eq!(configuration.last().unwrap(), &(Severity::Ok, "settings and globs are valid".to_string()));
+        assert_eq!(findings(&report, "Patterns"), vec![
+            (Severity::Ok, "include pattern `src/**` matches 1 path".to_string()),
+            (Severity::Warning, "include pattern `docs/**` matches nothing".to_string()),
+            (Severity::Warning, "include pattern `.github/**` names a hidden path, which only --hidden walks".to_string()),
+        ]);
+        assert_eq!(findings(&report, "Options"), vec![(Severity::Ok, "no conflicting options".to_string())]);
+
+        // Every problem is reported, and together they fail the run
+        let config = Config::try_parse_from(["howmany", "--owners"]).unwrap();
+        let report = diagnose(&config, dir.path(), |_, _| Err(HowManyError::filter("bad glob")));
+        assert!(findings(&report, "Configuration").contains(&(Severity::Error, "Filter error: bad glob".to_string())));
+        assert_eq!(findings(&report, "Options").len(), 1);
+        assert_eq!(findings(&report, "Options")[0].0, Severity::Error);
+        let error = report.outcome().unwrap_err();
+        assert_eq!(error.category().exit_code(), 4);
+        assert!(error.to_string().contains(&format!("doctor found {} problems", report.count(Severity::Error))));
+    }
+}
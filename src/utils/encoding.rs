@@ -0,0 +1,145 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+use std::borrow::Cow;
+
+/// Bytes sampled for the UTF-16 heuristic
+const UTF16_SAMPLE_BYTES: usize = 4096;
+
+/// Share of NUL bytes on one side of each code unit above which BOM-less text is taken as UTF-16
+const UTF16_NUL_RATIO: f64 = 0.3;
+
+/// Bytes at the start of a file probed for binary content
+pub const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Share of control bytes above which content that isn't UTF-8 is taken as binary
+const BINARY_CONTROL_RATIO: f64 = 0.1;
+
+/// File contents decoded to text, with the encoding they were read in
+#[derive(Debug)]
+pub struct DecodedText<'a> {
+    pub text: Cow<'a, str>,
+    /// Name of the source encoding (`windows-1252`, `UTF-16LE`), `None` for UTF-8
+    pub encoding: Option<&'static str>,
+}
+
+/// Decode source file contents of any common encoding.
+///
+/// A byte order mark decides first. BOM-less UTF-16 is recognized by its NUL
+/// bytes, valid UTF-8 is borrowed as is, and anything else is guessed from
+/// byte frequencies (Latin-1/windows-1252, Shift_JIS, GBK, ...).
+/// Bytes that don't decode become U+FFFD, so counting never fails on encoding.
+pub fn decode(contents: &[u8]) -> DecodedText<'_> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(contents) {
+        let (text, _) = encoding.decode_without_bom_handling(&contents[bom_length..]);
+        return DecodedText {
+            text,
+            encoding: (encoding != encoding_rs::UTF_8).then(|| encoding.name()),
+        };
+    }
+
+    // ASCII-only UTF-16 is valid UTF-8 too, so it is recognized first
+    let encoding = match detect_utf16(contents) {
+        Some(encoding) => encoding,
+        None => match std::str::from_utf8(contents) {
+            Ok(text) => return DecodedText { text: Cow::Borrowed(text), encoding: None },
+            Err(_) => {
+                let mut detector = EncodingDetector::new();
+                detector.feed(contents, true);
+                detector.guess(None, true)
+            }
+        },
+    };
+    let (text, _) = encoding.decode_without_bom_handling(contents);
+    DecodedText { text, encoding: Some(encoding.name()) }
+}
+
+/// Whether contents are binary rather than text of some encoding, judged by
+/// their first `BINARY_SNIFF_BYTES`: a NUL byte outside UTF-16 text, or
+/// non-UTF-8 bytes with many control characters. Legacy 8-bit encodings have
+/// few control characters, so they still count as text.
+pub fn looks_binary(contents: &[u8]) -> bool {
+    let sample = &contents[..contents.len().min(BINARY_SNIFF_BYTES)];
+    if sample.is_empty() || Encoding::for_bom(sample).is_some() || detect_utf16(sample).is_some() {
+        return false;
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+
+    // A character cut off at the end of the sample doesn't make it invalid
+    if std::str::from_utf8(sample).map_or_else(|e| e.error_len().is_none(), |_| true) {
+        return false;
+    }
+    let control_bytes = sample
+        .iter()
+        .filter(|byte| **byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    control_bytes as f64 / sample.len() as f64 > BINARY_CONTROL_RATIO
+}
+
+/// UTF-16 without a byte order mark: ASCII-heavy source has a NUL in the high byte of most code units
+fn detect_utf16(contents: &[u8]) -> Option<&'static Encoding> {
+    let sample = &contents[..contents.len().min(UTF16_SAMPLE_BYTES) & !1];
+    if sample.is_empty() {
+        return None;
+    }
+
+    let units = (sample.len() / 2) as f64;
+    let nul_ratio = |offset: usize| sample.iter().skip(offset).step_by(2).filter(|byte| **byte == 0).count() as f64 / units;
+    let (even, odd) = (nul_ratio(0), nul_ratio(1));
+
+    if odd > UTF16_NUL_RATIO && even < UTF16_NUL_RATIO / 10.0 {
+        Some(UTF_16LE)
+    } else if even > UTF16_NUL_RATIO && odd < UTF16_NUL_RATIO / 10.0 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_decode_encodings() {
+        let utf8 = decode("// Grüße\nfn main() {}\n".as_bytes());
+        assert!(matches!(utf8.text, Cow::Borrowed(_)));
+        assert_eq!(utf8.encoding, None);
+
+        // "// Grüße" in Latin-1
+        let latin1 = decode(b"// Gr\xfc\xdfe aus M\xfcnchen\nint main() { return 0; }\n");
+        assert_eq!(latin1.encoding, Some("windows-1252"));
+        assert!(latin1.text.starts_with("// Grüße aus München\n"));
+
+        let mut with_bom = vec![0xff, 0xfe];
+        with_bom.extend(utf16le("x = 1\ny = 2\n"));
+        assert_eq!(decode(&with_bom).text, "x = 1\ny = 2\n");
+        assert_eq!(decode(&with_bom).encoding, Some("UTF-16LE"));
+
+        let without_bom = utf16le("print('hi')\n");
+        assert_eq!(decode(&without_bom).text, "print('hi')\n");
+        assert_eq!(decode(&without_bom).encoding, Some("UTF-16LE"));
+    }
+
+    #[test]
+    fn test_binary_content() {
+        assert!(looks_binary(b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00"));
+        assert!(looks_binary(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"));
+        assert!(looks_binary(&[0x9c, 0x01, 0x02, 0xff, 0x03, 0x04, 0x05, 0x9d, 0x06, 0x07]));
+
+        // Text in any encoding, including UTF-16 with its NUL bytes, is not binary
+        assert!(!looks_binary(b"#!/bin/sh\necho hi\n"));
+        assert!(!looks_binary(b"// Gr\xfc\xdfe aus M\xfcnchen\n"));
+        assert!(!looks_binary(&utf16le("print('hi')\n")));
+        assert!(!looks_binary(b""));
+        // A multi-byte character cut off by the sample limit
+        let mut truncated = "x".repeat(BINARY_SNIFF_BYTES - 1).into_bytes();
+        truncated.extend("ü".as_bytes());
+        assert!(!looks_binary(&truncated));
+    }
+}
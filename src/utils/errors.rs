@@ -1,8 +1,38 @@
+use serde::Serialize;
 use std::io;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, HowManyError>;
 
+/// What kind of failure an error is, which sets the exit status of the run.
+/// The statuses are stable so that wrappers and CI scripts can tell failures apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorCategory {
+    /// Any other failure, such as a chart that couldn't be drawn (exit status 1)
+    Failure,
+    /// A limit was exceeded, such as by a changed file in `howmany diff` (exit status 2)
+    Threshold,
+    /// A file or directory couldn't be read or written (exit status 3)
+    Io,
+    /// Invalid options, configuration, patterns or report templates (exit status 4)
+    Config,
+    /// A git repository couldn't be read or fetched (exit status 5)
+    Repository,
+}
+
+impl ErrorCategory {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Failure => 1,
+            Self::Threshold => 2,
+            Self::Io => 3,
+            Self::Config => 4,
+            Self::Repository => 5,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum HowManyError {
     #[error("IO error: {0}")]
@@ -26,9 +56,24 @@ pub enum HowManyError {
     #[error("Counter error: {message}")]
     Counter { message: String },
     
+    #[error("Binary content: {path}")]
+    BinaryContent { path: String, size: u64 },
+    
+    #[error("File too large: {path} ({size} bytes, limit {limit})")]
+    FileTooLarge { path: String, size: u64, limit: u64 },
+    
     #[error("Display error: {message}")]
     Display { message: String },
     
+    #[error("Template error: {message}")]
+    Template { message: String },
+    
+    #[error("Repository error: {message}")]
+    Repository { message: String },
+    
+    #[error("Gate failed: {violations} limit(s) newly exceeded by changed files")]
+    GateFailed { violations: usize },
+    
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
     
@@ -53,7 +98,83 @@ impl HowManyError {
         Self::Counter { message: message.into() }
     }
     
+    /// A file whose contents are binary despite its name, skipped by the counter
+    pub fn binary_content(path: impl Into<String>, size: u64) -> Self {
+        Self::BinaryContent { path: path.into(), size }
+    }
+    
+    /// A file over the counter's size limit, skipped without being read
+    pub fn file_too_large(path: impl Into<String>, size: u64, limit: u64) -> Self {
+        Self::FileTooLarge { path: path.into(), size, limit }
+    }
+    
     pub fn display(message: impl Into<String>) -> Self {
         Self::Display { message: message.into() }
     }
+    
+    pub fn template(message: impl Into<String>) -> Self {
+        Self::Template { message: message.into() }
+    }
+    
+    pub fn repository(message: impl Into<String>) -> Self {
+        Self::Repository { message: message.into() }
+    }
+    
+    pub fn gate_failed(violations: usize) -> Self {
+        Self::GateFailed { violations }
+    }
+    
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::GateFailed { .. } => ErrorCategory::Threshold,
+            Self::Io(_) | Self::FileNotFound(_) => ErrorCategory::Io,
+            Self::InvalidConfig { .. } | Self::Filter { .. } | Self::Template { .. } | Self::Regex(_) => ErrorCategory::Config,
+            Self::Repository { .. } => ErrorCategory::Repository,
+            Self::ParseError(_)
+            | Self::FileProcessing { .. }
+            | Self::Counter { .. }
+            | Self::BinaryContent { .. }
+            | Self::FileTooLarge { .. }
+            | Self::Display { .. }
+            | Self::Serialization(_) => ErrorCategory::Failure,
+        }
+    }
+    
+    /// The error as printed for wrappers parsing JSON output:
+    /// `{"error": {"category", "exit_code", "message"}}`
+    pub fn to_json(&self) -> serde_json::Value {
+        let category = self.category();
+        serde_json::json!({ "error": { "category": category, "exit_code": category.exit_code(), "message": self.to_string() } })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_exit_codes() {
+        let cases = [
+            (HowManyError::display("chart"), 1),
+            (HowManyError::gate_failed(2), 2),
+            (HowManyError::FileNotFound("missing".to_string()), 3),
+            (HowManyError::Io(io::Error::new(io::ErrorKind::PermissionDenied, "denied")), 3),
+            (HowManyError::invalid_config("bad option"), 4),
+            (HowManyError::filter("bad glob"), 4),
+            (HowManyError::template("undefined variable"), 4),
+            (HowManyError::repository("not a git repository"), 5),
+        ];
+        for (error, exit_code) in cases {
+            assert_eq!(error.category().exit_code(), exit_code, "{}", error);
+        }
+    }
+    
+    #[test]
+    fn test_json_shape() {
+        let json = HowManyError::FileNotFound("/nonexistent".to_string()).to_json();
+        assert_eq!(json, serde_json::json!({
+            "error": { "category": "io", "exit_code": 3, "message": "File not found: /nonexistent" }
+        }));
+        assert_eq!(HowManyError::gate_failed(1).to_json()["error"]["category"], "threshold");
+    }
 } 
\ No newline at end of file
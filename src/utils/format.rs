@@ -0,0 +1,229 @@
+use lazy_static::lazy_static;
+use std::env;
+use std::time::Duration;
+
+const SIZE_UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+
+/// Working hours per day used when presenting effort estimates
+const HOURS_PER_WORK_DAY: f64 = 8.0;
+
+lazy_static! {
+    static ref CURRENT_LOCALE: NumberLocale = NumberLocale::from_env();
+}
+
+/// Digit grouping and decimal separators for human-readable numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLocale {
+    pub thousands_separator: char,
+    pub decimal_separator: char,
+}
+
+impl NumberLocale {
+    /// `1,234.5`
+    pub const ENGLISH: NumberLocale = NumberLocale { thousands_separator: ',', decimal_separator: '.' };
+    /// `1.234,5` (German, Spanish, Italian, Dutch, ...)
+    pub const CONTINENTAL: NumberLocale = NumberLocale { thousands_separator: '.', decimal_separator: ',' };
+    /// `1 234,5` (French, Russian, Nordic, ...)
+    pub const SPACED: NumberLocale = NumberLocale { thousands_separator: ' ', decimal_separator: ',' };
+    /// `1'234.5` (Swiss)
+    pub const SWISS: NumberLocale = NumberLocale { thousands_separator: '\'', decimal_separator: '.' };
+
+    /// Locale of the process, from `LC_ALL`, `LC_NUMERIC` or `LANG` (in that order)
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| Self::from_tag(&value))
+            .unwrap_or(Self::ENGLISH)
+    }
+
+    /// Locale for a POSIX or BCP 47 tag such as `de_DE.UTF-8` or `fr-CA`
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or("").replace('-', "_").to_lowercase();
+        let mut parts = tag.split('_');
+        let language = parts.next().unwrap_or("");
+        let region = parts.next().unwrap_or("");
+
+        if region == "ch" && matches!(language, "de" | "fr" | "it") {
+            return Self::SWISS;
+        }
+
+        match language {
+            "de" | "es" | "it" | "pt" | "nl" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr" => Self::CONTINENTAL,
+            "fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "no" | "hu" | "bg" | "et" | "lt" | "lv" => Self::SPACED,
+            _ => Self::ENGLISH,
+        }
+    }
+
+    /// Format an integer with digit grouping
+    pub fn format_integer(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let first_group = digits.len() % 3;
+        let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+
+        for (i, ch) in digits.chars().enumerate() {
+            if i > 0 && i % 3 == first_group {
+                result.push(self.thousands_separator);
+            }
+            result.push(ch);
+        }
+
+        result
+    }
+
+    /// Format a decimal with digit grouping and a fixed number of decimal places
+    pub fn format_decimal(&self, value: f64, decimal_places: usize) -> String {
+        let formatted = format!("{:.prec$}", value.abs(), prec = decimal_places);
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut result = String::new();
+        if value < 0.0 && formatted.chars().any(|c| c != '0' && c != '.') {
+            result.push('-');
+        }
+        result.push_str(&self.format_integer(integer.parse().unwrap_or(0)));
+        if let Some(fraction) = fraction {
+            result.push(self.decimal_separator);
+            result.push_str(fraction);
+        }
+
+        result
+    }
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        Self::ENGLISH
+    }
+}
+
+/// Locale used by the formatting helpers, detected once per process
+pub fn current_locale() -> NumberLocale {
+    *CURRENT_LOCALE
+}
+
+/// Format a count with digit grouping, e.g. `12,345`
+pub fn format_number(value: usize) -> String {
+    current_locale().format_integer(value as u64)
+}
+
+/// Format a byte count in binary units, e.g. `512 B` or `1.5 MB`
+pub fn format_size(bytes: u64) -> String {
+    format_size_with(bytes, current_locale())
+}
+
+pub fn format_size_with(bytes: u64, locale: NumberLocale) -> String {
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < SIZE_UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    if unit_index == 0 {
+        format!("{} {}", locale.format_integer(bytes), SIZE_UNITS[0])
+    } else {
+        format!("{} {}", locale.format_decimal(size, 1), SIZE_UNITS[unit_index])
+    }
+}
+
+/// Format an elapsed time, e.g. `850ms`, `2.35s`, `3m 05s` or `1h 02m`
+pub fn format_duration(duration: Duration) -> String {
+    format_duration_with(duration, current_locale())
+}
+
+pub fn format_duration_with(duration: Duration, locale: NumberLocale) -> String {
+    let total_secs = duration.as_secs();
+
+    if total_secs == 0 {
+        format!("{}ms", duration.as_millis())
+    } else if total_secs < 60 {
+        format!("{}s", locale.format_decimal(duration.as_secs_f64(), 2))
+    } else if total_secs < 3600 {
+        format!("{}m {:02}s", total_secs / 60, total_secs % 60)
+    } else {
+        format!("{}h {:02}m", total_secs / 3600, (total_secs % 3600) / 60)
+    }
+}
+
+/// Format an effort estimate given in hours, switching to work days past a day
+pub fn format_work_hours(hours: f64) -> String {
+    format_work_hours_with(hours, current_locale())
+}
+
+pub fn format_work_hours_with(hours: f64, locale: NumberLocale) -> String {
+    let days = hours / HOURS_PER_WORK_DAY;
+
+    if days >= 1.0 {
+        format!("{} days ({} hours)", locale.format_decimal(days, 1), locale.format_decimal(hours, 1))
+    } else {
+        format!("{} hours", locale.format_decimal(hours, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_grouping() {
+        let en = NumberLocale::ENGLISH;
+        assert_eq!(en.format_integer(0), "0");
+        assert_eq!(en.format_integer(999), "999");
+        assert_eq!(en.format_integer(1000), "1,000");
+        assert_eq!(en.format_integer(1234567), "1,234,567");
+
+        assert_eq!(NumberLocale::CONTINENTAL.format_integer(1234567), "1.234.567");
+        assert_eq!(NumberLocale::SPACED.format_integer(1234), "1 234");
+    }
+
+    #[test]
+    fn test_decimal_formatting() {
+        assert_eq!(NumberLocale::ENGLISH.format_decimal(1234.56, 1), "1,234.6");
+        assert_eq!(NumberLocale::CONTINENTAL.format_decimal(1234.56, 2), "1.234,56");
+        assert_eq!(NumberLocale::ENGLISH.format_decimal(-2.5, 1), "-2.5");
+        assert_eq!(NumberLocale::ENGLISH.format_decimal(-0.01, 1), "0.0");
+        assert_eq!(NumberLocale::ENGLISH.format_decimal(7.0, 0), "7");
+    }
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(NumberLocale::from_tag("en_US.UTF-8"), NumberLocale::ENGLISH);
+        assert_eq!(NumberLocale::from_tag("de_DE.UTF-8"), NumberLocale::CONTINENTAL);
+        assert_eq!(NumberLocale::from_tag("fr-CA"), NumberLocale::SPACED);
+        assert_eq!(NumberLocale::from_tag("de_CH"), NumberLocale::SWISS);
+        assert_eq!(NumberLocale::from_tag("C"), NumberLocale::ENGLISH);
+        assert_eq!(NumberLocale::from_tag("POSIX"), NumberLocale::ENGLISH);
+    }
+
+    #[test]
+    fn test_size_formatting() {
+        let en = NumberLocale::ENGLISH;
+        assert_eq!(format_size_with(0, en), "0 B");
+        assert_eq!(format_size_with(1023, en), "1,023 B");
+        assert_eq!(format_size_with(1024, en), "1.0 KB");
+        assert_eq!(format_size_with(1536 * 1024, en), "1.5 MB");
+        assert_eq!(format_size_with(5 * 1024 * 1024 * 1024 * 1024, en), "5.0 TB");
+        assert_eq!(format_size_with(1536, NumberLocale::CONTINENTAL), "1,5 KB");
+    }
+
+    #[test]
+    fn test_duration_formatting() {
+        let en = NumberLocale::ENGLISH;
+        assert_eq!(format_duration_with(Duration::from_millis(850), en), "850ms");
+        assert_eq!(format_duration_with(Duration::from_millis(2346), en), "2.35s");
+        assert_eq!(format_duration_with(Duration::from_secs(185), en), "3m 05s");
+        assert_eq!(format_duration_with(Duration::from_secs(3720), en), "1h 02m");
+    }
+
+    #[test]
+    fn test_work_hours_formatting() {
+        let en = NumberLocale::ENGLISH;
+        assert_eq!(format_work_hours_with(3.5, en), "3.5 hours");
+        assert_eq!(format_work_hours_with(20.0, en), "2.5 days (20.0 hours)");
+    }
+}
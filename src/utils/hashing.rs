@@ -0,0 +1,202 @@
+use crate::utils::errors::{HowManyError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use xxhash_rust::xxh3::Xxh3;
+
+/// Hash function used for file content hashes.
+///
+/// `xxh3` is fast and non-cryptographic. `blake3` is a cryptographic hash for
+/// users who need collision resistance against crafted inputs; it requires the
+/// `blake3` cargo feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Xxh3,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Xxh3 => "xxh3",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Whether support for this algorithm was compiled in
+    pub fn is_available(&self) -> bool {
+        match self {
+            HashAlgorithm::Xxh3 => true,
+            HashAlgorithm::Blake3 => cfg!(feature = "blake3"),
+        }
+    }
+
+    /// Fail early when a configured algorithm was not compiled in
+    pub fn ensure_available(self) -> Result<Self> {
+        if self.is_available() {
+            Ok(self)
+        } else {
+            Err(HowManyError::invalid_config(format!(
+                "Hash algorithm '{}' is not available in this build (rebuild with `--features {}`)",
+                self.as_str(),
+                self.as_str()
+            )))
+        }
+    }
+
+    pub fn hasher(&self) -> ContentHasher {
+        ContentHasher::new(*self)
+    }
+
+    pub fn hash_bytes(&self, data: &[u8]) -> ContentHash {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    /// Hash a file's contents without loading it into memory at once
+    pub fn hash_file(&self, path: &Path) -> Result<ContentHash> {
+        let mut file = fs::File::open(path)?;
+        let mut hasher = self.hasher();
+        let mut buffer = [0u8; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        Ok(hasher.finish())
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "xxh3" | "xxhash" => Ok(HashAlgorithm::Xxh3),
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            _ => Err(format!("Invalid hash algorithm: {} (expected xxh3 or blake3)", s)),
+        }
+    }
+}
+
+/// Incremental hasher for the selected algorithm
+pub struct ContentHasher {
+    algorithm: HashAlgorithm,
+    state: HasherState,
+}
+
+enum HasherState {
+    Xxh3(Box<Xxh3>),
+    #[cfg(feature = "blake3")]
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl ContentHasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => Self {
+                algorithm,
+                state: HasherState::Blake3(Box::new(blake3::Hasher::new())),
+            },
+            // Without the feature, configuration is rejected by `ensure_available`;
+            // library callers that skip it get the default hash rather than a panic
+            _ => Self {
+                algorithm: HashAlgorithm::Xxh3,
+                state: HasherState::Xxh3(Box::new(Xxh3::new())),
+            },
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            HasherState::Xxh3(hasher) => hasher.update(data),
+            #[cfg(feature = "blake3")]
+            HasherState::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    pub fn finish(self) -> ContentHash {
+        let digest = match self.state {
+            HasherState::Xxh3(hasher) => format!("{:032x}", hasher.digest128()),
+            #[cfg(feature = "blake3")]
+            HasherState::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        };
+
+        ContentHash {
+            algorithm: self.algorithm,
+            digest,
+        }
+    }
+}
+
+/// Hex digest tagged with the algorithm that produced it, e.g. `xxh3:1f3a…`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentHash {
+    pub algorithm: HashAlgorithm,
+    pub digest: String,
+}
+
+impl fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xxh3_is_default_and_deterministic() {
+        let algorithm = HashAlgorithm::default();
+        assert_eq!(algorithm, HashAlgorithm::Xxh3);
+
+        let first = algorithm.hash_bytes(b"fn main() {}");
+        let second = algorithm.hash_bytes(b"fn main() {}");
+        assert_eq!(first, second);
+        assert_ne!(first, algorithm.hash_bytes(b"fn main() { }"));
+        assert!(first.to_string().starts_with("xxh3:"));
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let mut hasher = HashAlgorithm::Xxh3.hasher();
+        hasher.update(b"fn main() ");
+        hasher.update(b"{}");
+        assert_eq!(hasher.finish(), HashAlgorithm::Xxh3.hash_bytes(b"fn main() {}"));
+    }
+
+    #[test]
+    fn test_algorithm_availability() {
+        assert!(HashAlgorithm::Xxh3.ensure_available().is_ok());
+        assert_eq!(HashAlgorithm::Blake3.ensure_available().is_ok(), cfg!(feature = "blake3"));
+        assert_eq!("BLAKE3".parse::<HashAlgorithm>(), Ok(HashAlgorithm::Blake3));
+        assert!("md5".parse::<HashAlgorithm>().is_err());
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn test_blake3_digest() {
+        let hash = HashAlgorithm::Blake3.hash_bytes(b"");
+        assert_eq!(hash.algorithm, HashAlgorithm::Blake3);
+        assert_eq!(hash.digest, "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+    }
+}
@@ -0,0 +1,173 @@
+use crate::core::plugins;
+
+/// A language as reports show it, with the extensions counted under it
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    pub name: &'static str,
+    /// Extensions of the language, the most common one first
+    pub extensions: &'static [&'static str],
+}
+
+/// Extensions of one language share a row: `ts` and `tsx` are TypeScript, C++
+/// headers are C++. `.h` is C, as it is for GitHub linguist. Build files are
+/// keyed by `extension_key` (`makefile`, `dockerfile`, `cmake`).
+const LANGUAGES: &[Language] = &[
+    Language { name: "Rust", extensions: &["rs"] },
+    Language { name: "Python", extensions: &["py", "pyi", "pyw", "pyx"] },
+    Language { name: "JavaScript", extensions: &["js", "jsx", "mjs", "cjs"] },
+    Language { name: "TypeScript", extensions: &["ts", "tsx", "mts", "cts"] },
+    Language { name: "Vue", extensions: &["vue"] },
+    Language { name: "Svelte", extensions: &["svelte"] },
+    Language { name: "HTML", extensions: &["html", "htm", "xhtml"] },
+    Language { name: "CSS", extensions: &["css"] },
+    Language { name: "Sass", extensions: &["scss", "sass"] },
+    Language { name: "Less", extensions: &["less"] },
+    Language { name: "JSON", extensions: &["json", "jsonc", "json5"] },
+    Language { name: "XML", extensions: &["xml", "xsd", "xsl", "xslt"] },
+    Language { name: "YAML", extensions: &["yaml", "yml"] },
+    Language { name: "TOML", extensions: &["toml"] },
+    Language { name: "Markdown", extensions: &["md", "markdown", "mdx"] },
+    Language { name: "Text", extensions: &["txt"] },
+    Language { name: "Java", extensions: &["java"] },
+    Language { name: "C", extensions: &["c", "h"] },
+    Language { name: "C++", extensions: &["cpp", "cc", "cxx", "c++", "hpp", "hh", "hxx", "h++", "ino"] },
+    Language { name: "Objective-C", extensions: &["mm"] },
+    Language { name: "C#", extensions: &["cs", "csx"] },
+    Language { name: "F#", extensions: &["fs", "fsx", "fsi"] },
+    Language { name: "Visual Basic", extensions: &["vb", "vbs"] },
+    Language { name: "Go", extensions: &["go"] },
+    Language { name: "PHP", extensions: &["php", "phtml"] },
+    Language { name: "Ruby", extensions: &["rb", "rake", "gemspec"] },
+    Language { name: "Swift", extensions: &["swift"] },
+    Language { name: "Kotlin", extensions: &["kt", "kts"] },
+    Language { name: "Scala", extensions: &["scala", "sc"] },
+    Language { name: "Groovy", extensions: &["groovy", "gradle"] },
+    Language { name: "Shell", extensions: &["sh", "bash", "zsh", "fish", "ksh"] },
+    Language { name: "PowerShell", extensions: &["ps1", "psm1", "psd1"] },
+    Language { name: "Batch", extensions: &["bat", "cmd"] },
+    Language { name: "Dart", extensions: &["dart"] },
+    Language { name: "R", extensions: &["r"] },
+    Language { name: "R Markdown", extensions: &["rmd"] },
+    Language { name: "MATLAB", extensions: &["m", "mlx"] },
+    Language { name: "Julia", extensions: &["jl"] },
+    Language { name: "SQL", extensions: &["sql"] },
+    Language { name: "Haskell", extensions: &["hs", "lhs", "hsc"] },
+    Language { name: "Elixir", extensions: &["ex", "exs", "eex", "heex"] },
+    Language { name: "Erlang", extensions: &["erl", "hrl"] },
+    Language { name: "Clojure", extensions: &["clj", "cljs", "cljc", "edn"] },
+    Language { name: "OCaml", extensions: &["ml", "mli"] },
+    Language { name: "Elm", extensions: &["elm"] },
+    Language { name: "Lua", extensions: &["lua"] },
+    Language { name: "Perl", extensions: &["pl", "pm", "pod"] },
+    Language { name: "Zig", extensions: &["zig"] },
+    Language { name: "COBOL", extensions: &["cob", "cbl", "cpy"] },
+    Language { name: "Fortran", extensions: &["f90", "f", "for", "f77", "ftn", "f95", "f03", "f08"] },
+    Language { name: "Ada", extensions: &["adb", "ads", "ada"] },
+    Language { name: "Pascal", extensions: &["pas", "dpr", "lpr"] },
+    Language { name: "Assembly", extensions: &["s", "asm"] },
+    Language { name: "Verilog", extensions: &["v", "vh", "sv", "svh"] },
+    Language { name: "VHDL", extensions: &["vhd", "vhdl"] },
+    Language { name: "CUDA", extensions: &["cu", "cuh"] },
+    Language { name: "HCL", extensions: &["tf", "tfvars", "hcl"] },
+    Language { name: "Protocol Buffers", extensions: &["proto"] },
+    Language { name: "GraphQL", extensions: &["graphql", "gql"] },
+    Language { name: "Nix", extensions: &["nix"] },
+    Language { name: "Makefile", extensions: &["makefile"] },
+    Language { name: "Dockerfile", extensions: &["dockerfile"] },
+    Language { name: "CMake", extensions: &["cmake"] },
+];
+
+/// Maps file extensions to the languages reports group them under.
+///
+/// Report keys are language names by default and extensions with
+/// `--by-extension`, so lookups accept either.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LanguageRegistry;
+
+impl LanguageRegistry {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Language of an extension, compared case-insensitively
+    pub fn language(&self, extension: &str) -> Option<&'static Language> {
+        Self::languages().find(|language| language.extensions.iter().any(|candidate| candidate.eq_ignore_ascii_case(extension)))
+    }
+
+    /// Language with the display name `name`, compared case-insensitively
+    pub fn named(&self, name: &str) -> Option<&'static Language> {
+        Self::languages().find(|language| language.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The built-in languages, then those added by plugins
+    fn languages() -> impl Iterator<Item = &'static Language> {
+        LANGUAGES.iter().chain(plugins::languages())
+    }
+
+    /// Language a report key stands for, whether it is a language name or an extension
+    pub fn resolve(&self, key: &str) -> Option<&'static Language> {
+        self.named(key).or_else(|| self.language(key))
+    }
+
+    /// Report key of an extension: its language's name, or the extension
+    /// itself when no language claims it
+    pub fn key(&self, extension: &str) -> String {
+        self.resolve(extension).map_or_else(|| extension.to_string(), |language| language.name.to_string())
+    }
+
+    /// Extension standing for a report key in tables keyed by extension, such
+    /// as icons and colors: a language's most common extension, or the key itself
+    pub fn extension<'a>(&self, key: &'a str) -> &'a str {
+        self.named(key).map_or(key, |language| language.extensions[0])
+    }
+
+    /// Whether files with `extension` are counted under the report key
+    pub fn covers(&self, key: &str, extension: &str) -> bool {
+        key.eq_ignore_ascii_case(extension)
+            || self.named(key).is_some_and(|language| language.extensions.iter().any(|candidate| candidate.eq_ignore_ascii_case(extension)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions_group_by_language() {
+        let registry = LanguageRegistry::new();
+        assert_eq!(registry.key("rs"), "Rust");
+        assert_eq!(registry.key("tsx"), "TypeScript");
+        assert_eq!(registry.key("TS"), "TypeScript");
+        assert_eq!(registry.key("hpp"), "C++");
+        assert_eq!(registry.key("h"), "C");
+        assert_eq!(registry.key("makefile"), "Makefile");
+        // Unknown extensions keep their own row
+        assert_eq!(registry.key("xyz"), "xyz");
+        assert_eq!(registry.key("no_ext"), "no_ext");
+    }
+
+    #[test]
+    fn test_language_names_are_their_own_keys() {
+        let registry = LanguageRegistry::new();
+        for language in LANGUAGES {
+            // Grouping grouped statistics again, as merging reports does, changes nothing
+            assert_eq!(registry.key(language.name), language.name);
+            assert_eq!(registry.resolve(language.name), Some(language));
+            assert_eq!(registry.language(registry.extension(language.name)), Some(language));
+            for extension in language.extensions {
+                assert_eq!(registry.language(extension), Some(language), "{} is claimed twice", extension);
+            }
+        }
+    }
+
+    #[test]
+    fn test_keys_cover_their_extensions() {
+        let registry = LanguageRegistry::new();
+        assert!(registry.covers("C++", "hh"));
+        assert!(registry.covers("ts", "ts"));
+        assert!(!registry.covers("ts", "tsx"));
+        assert!(!registry.covers("C", "cpp"));
+        assert_eq!(registry.extension("TypeScript"), "ts");
+        assert_eq!(registry.extension("tsx"), "tsx");
+    }
+}
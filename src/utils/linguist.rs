@@ -0,0 +1,107 @@
+use crate::utils::languages::LanguageRegistry;
+
+/// Color for languages without a linguist color and for grouped "Others" slices
+pub const DEFAULT_COLOR: &str = "#6c757d";
+
+/// Language colors from GitHub linguist's `languages.yml`, keyed by the
+/// extensions howmany counts. Embedded so charts look the same as a
+/// repository's language bar on GitHub without a network lookup.
+const LANGUAGE_COLORS: &[(&[&str], &str)] = &[
+    (&["rs"], "#dea584"),                              // Rust
+    (&["py", "pyi"], "#3572A5"),                       // Python
+    (&["js", "jsx", "mjs", "cjs"], "#f1e05a"),         // JavaScript
+    (&["ts", "mts", "cts"], "#3178c6"),                // TypeScript
+    (&["tsx"], "#3178c6"),                             // TSX
+    (&["vue"], "#41b883"),                             // Vue
+    (&["svelte"], "#ff3e00"),                          // Svelte
+    (&["html", "htm"], "#e34c26"),                     // HTML
+    (&["css"], "#663399"),                             // CSS
+    (&["scss"], "#c6538c"),                            // SCSS
+    (&["sass"], "#a53b70"),                            // Sass
+    (&["less"], "#1d365d"),                            // Less
+    (&["java"], "#b07219"),                            // Java
+    (&["c", "h"], "#555555"),                          // C
+    (&["cpp", "cc", "cxx", "hpp", "hh", "hxx"], "#f34b7d"), // C++
+    (&["cs"], "#178600"),                              // C#
+    (&["go"], "#00ADD8"),                              // Go
+    (&["php"], "#4F5D95"),                             // PHP
+    (&["rb"], "#701516"),                              // Ruby
+    (&["swift"], "#F05138"),                           // Swift
+    (&["kt", "kts"], "#A97BFF"),                       // Kotlin
+    (&["scala", "sc"], "#c22d40"),                     // Scala
+    (&["groovy"], "#4298b8"),                          // Groovy
+    (&["sh", "bash", "zsh"], "#89e051"),               // Shell
+    (&["json"], "#292929"),                            // JSON
+    (&["xml"], "#0060ac"),                             // XML
+    (&["yaml", "yml"], "#cb171e"),                     // YAML
+    (&["toml"], "#9c4221"),                            // TOML
+    (&["md"], "#083fa1"),                              // Markdown
+    (&["sql"], "#e38c00"),                             // SQL
+    (&["r"], "#198CE7"),                               // R
+    (&["rmd"], "#198ce7"),                             // RMarkdown
+    (&["dart"], "#00B4AB"),                            // Dart
+    (&["hs", "hsc"], "#5e5086"),                       // Haskell
+    (&["lhs"], "#5e5086"),                             // Literate Haskell
+    (&["ex", "exs"], "#6e4a7e"),                       // Elixir
+    (&["eex"], "#6e4a7e"),                             // HTML+EEX
+    (&["erl", "hrl"], "#B83998"),                      // Erlang
+    (&["jl"], "#a270ba"),                              // Julia
+    (&["lua"], "#000080"),                             // Lua
+    (&["pl", "pm", "pod"], "#0298c3"),                 // Perl
+    (&["m", "mlx"], "#e16737"),                        // MATLAB
+    (&["zig"], "#ec915c"),                             // Zig
+    (&["clj", "cljs", "cljc"], "#db5855"),             // Clojure
+    (&["ps1", "psm1", "psd1"], "#012456"),             // PowerShell
+    (&["bat", "cmd"], "#C1F12E"),                      // Batchfile
+    (&["vb"], "#945db7"),                              // Visual Basic .NET
+    (&["vbs"], "#15dcdc"),                             // VBScript
+    (&["fs", "fsx"], "#b845fc"),                       // F#
+    (&["ml", "mli"], "#ef7a08"),                       // OCaml
+    (&["elm"], "#60B5CC"),                             // Elm
+    (&["nim"], "#ffc200"),                             // Nim
+    (&["sol"], "#AA6746"),                             // Solidity
+    (&["tf", "tfvars", "hcl"], "#844FBA"),             // HCL
+    (&["graphql", "gql"], "#e10098"),                  // GraphQL
+    (&["nix"], "#7e7eff"),                             // Nix
+    (&["s", "asm"], "#6E4C13"),                        // Assembly
+    (&["v", "vh"], "#b2b7f8"),                         // Verilog
+    (&["sv", "svh"], "#DAE1C2"),                       // SystemVerilog
+    (&["vhd", "vhdl"], "#adb2cb"),                     // VHDL
+    (&["cu", "cuh"], "#3A4E3A"),                       // Cuda
+    (&["makefile"], "#427819"),                        // Makefile
+    (&["dockerfile"], "#384d54"),                      // Dockerfile
+    (&["cmake"], "#DA3434"),                           // CMake
+    (&["f", "for", "f77", "ftn"], "#4d41b1"),          // Fortran
+    (&["f90", "f95", "f03", "f08"], "#4d41b1"),        // Fortran Free Form
+    (&["adb", "ads", "ada"], "#02f88c"),               // Ada
+    (&["pas", "dpr", "lpr"], "#E3F171"),               // Pascal
+];
+
+/// Linguist's color for the language of a file extension or a language name
+pub fn language_color(ext: &str) -> Option<&'static str> {
+    let ext = LanguageRegistry::new().extension(ext);
+    LANGUAGE_COLORS
+        .iter()
+        .find(|(extensions, _)| extensions.iter().any(|candidate| candidate.eq_ignore_ascii_case(ext)))
+        .map(|(_, color)| *color)
+}
+
+/// Linguist's color for an extension, or `DEFAULT_COLOR` if linguist has none
+pub fn language_color_or_default(ext: &str) -> &'static str {
+    language_color(ext).unwrap_or(DEFAULT_COLOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linguist_colors() {
+        assert_eq!(language_color("rs"), Some("#dea584"));
+        assert_eq!(language_color("jsx"), language_color("js"));
+        assert_eq!(language_color("Rmd"), Some("#198ce7"));
+        assert_eq!(language_color("TypeScript"), language_color("ts"));
+        assert_eq!(language_color("txt"), None);
+        assert_eq!(language_color_or_default("txt"), DEFAULT_COLOR);
+    }
+}
@@ -1,6 +1,8 @@
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use crate::core::stats::aggregation::RunPerformance;
+use crate::utils::format::{format_duration, format_size};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
@@ -10,6 +12,8 @@ pub struct PerformanceMetrics {
     pub bytes_processed: u64,
     pub cache_hits: usize,
     pub cache_misses: usize,
+    /// Files with binary content under a source-like name, skipped by the counter
+    pub binary_files_skipped: usize,
     pub parallel_workers: usize,
     pub memory_usage_mb: f64,
     pub phase_timings: HashMap<String, Duration>,
@@ -24,6 +28,7 @@ impl PerformanceMetrics {
             bytes_processed: 0,
             cache_hits: 0,
             cache_misses: 0,
+            binary_files_skipped: 0,
             parallel_workers: rayon::current_num_threads(),
             memory_usage_mb: 0.0,
             phase_timings: HashMap::new(),
@@ -67,17 +72,36 @@ impl PerformanceMetrics {
         self.phase_timings.insert(phase.to_string(), duration);
     }
     
+    /// Cache and timing summary attached to the metadata of machine-readable outputs
+    pub fn run_performance(&self, cache_entries: usize) -> RunPerformance {
+        RunPerformance {
+            cache_hits: self.cache_hits,
+            cache_misses: self.cache_misses,
+            cache_hit_rate: self.cache_hit_rate(),
+            cache_entries,
+            binary_files_skipped: self.binary_files_skipped,
+            total_time_ms: self.total_duration.as_millis() as u64,
+            phase_timings_ms: self.phase_timings
+                .iter()
+                .map(|(phase, duration)| (phase.clone(), duration.as_millis() as u64))
+                .collect(),
+        }
+    }
+    
     pub fn print_summary(&self) {
         println!("\n📊 Performance Summary");
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("⏱️  Total time: {:.2}s", self.total_duration.as_secs_f64());
+        println!("⏱️  Total time: {}", format_duration(self.total_duration));
         println!("📁 Files processed: {}", self.files_processed);
         println!("📏 Lines processed: {}", self.lines_processed);
-        println!("💾 Bytes processed: {:.2} MB", self.bytes_processed as f64 / (1024.0 * 1024.0));
+        println!("💾 Bytes processed: {}", format_size(self.bytes_processed));
+        if self.binary_files_skipped > 0 {
+            println!("🚫 Binary files skipped: {}", self.binary_files_skipped);
+        }
         println!("🚀 Throughput:");
         println!("   • {:.0} files/sec", self.files_per_second());
         println!("   • {:.0} lines/sec", self.lines_per_second());
-        println!("   • {:.2} MB/sec", self.bytes_per_second() / (1024.0 * 1024.0));
+        println!("   • {}/sec", format_size(self.bytes_per_second() as u64));
         
         if self.cache_hits + self.cache_misses > 0 {
             println!("💾 Cache performance:");
@@ -98,7 +122,7 @@ impl PerformanceMetrics {
             
             for (phase, duration) in phases {
                 let percentage = (duration.as_secs_f64() / self.total_duration.as_secs_f64()) * 100.0;
-                println!("   • {}: {:.2}s ({:.1}%)", phase, duration.as_secs_f64(), percentage);
+                println!("   • {}: {} ({:.1}%)", phase, format_duration(*duration), percentage);
             }
         }
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -153,6 +177,10 @@ impl MetricsCollector {
         self.metrics.bytes_processed += bytes;
     }
     
+    pub fn record_binary_skipped(&mut self) {
+        self.metrics.binary_files_skipped += 1;
+    }
+    
     pub fn record_cache_hit(&mut self) {
         self.metrics.cache_hits += 1;
     }
@@ -161,6 +189,12 @@ impl MetricsCollector {
         self.metrics.cache_misses += 1;
     }
     
+    /// Record cache totals gathered elsewhere, e.g. by `CachedCodeCounter`
+    pub fn record_cache_stats(&mut self, hits: usize, misses: usize) {
+        self.metrics.cache_hits += hits;
+        self.metrics.cache_misses += misses;
+    }
+    
     pub fn add_phase_timing(&mut self, phase: &str, duration: Duration) {
         self.metrics.add_phase_timing(phase, duration);
     }
@@ -256,4 +290,19 @@ mod tests {
         assert_eq!(metrics.cache_hits, 1);
         assert_eq!(metrics.cache_misses, 1);
     }
+    
+    #[test]
+    fn test_run_performance() {
+        let mut collector = MetricsCollector::new();
+        collector.record_cache_stats(3, 1);
+        collector.add_phase_timing("counting", Duration::from_millis(42));
+        
+        let performance = collector.finish().run_performance(7);
+        
+        assert_eq!(performance.cache_hits, 3);
+        assert_eq!(performance.cache_misses, 1);
+        assert_eq!(performance.cache_hit_rate, 0.75);
+        assert_eq!(performance.cache_entries, 7);
+        assert_eq!(performance.phase_timings_ms.get("counting"), Some(&42));
+    }
 } 
\ No newline at end of file
@@ -1,8 +1,25 @@
+pub mod archive;
 pub mod cache;
+pub mod checkpoint;
+pub mod churn;
+pub mod compression;
+pub mod doctor;
 pub mod config;
+pub mod encoding;
 pub mod errors;
+pub mod format;
+pub mod hashing;
+pub mod languages;
+pub mod linguist;
 pub mod metrics;
+pub mod ownership;
+pub mod paths;
+pub mod pathspec;
+pub mod shard;
+pub mod shared_cache;
 pub mod progress;
+pub mod remote;
+pub mod warnings;
 
 pub use cache::FileCache;
 pub use config::HowManyConfig;
@@ -0,0 +1,517 @@
+use crate::core::detector::extension_key;
+use crate::utils::languages::LanguageRegistry;
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::remote::ensure_work_tree;
+use chrono::{DateTime, TimeZone, Utc};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Percentage of an area's lines one author must exceed for the area to be flagged
+pub const DEFAULT_CONCENTRATION_THRESHOLD: f64 = 90.0;
+
+/// Areas with fewer blamed lines are reported but never flagged as concentrated
+pub const MIN_CONCENTRATION_LINES: usize = 50;
+
+/// Line attribution for the analyzed files, computed from `git blame`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OwnershipStats {
+    /// Non-blank committed lines attributed to an author
+    pub total_lines: usize,
+    pub files_blamed: usize,
+    /// Files outside git or that blame could not read
+    pub files_skipped: usize,
+    /// Authors sorted by attributed lines, largest first
+    pub authors: Vec<AuthorStats>,
+    /// Configured `[teams]`, in name order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<TeamStats>,
+    /// Fewest authors who together own more than half of the lines
+    pub bus_factor: usize,
+    pub top_author_share: f64,
+    /// Files whose lines all come from one author
+    pub single_author_files: usize,
+    /// Authorship concentration per directory, most concentrated first
+    #[serde(default)]
+    pub directories: Vec<AreaOwnership>,
+    /// Authorship concentration per language (per file extension with `--by-extension`), most concentrated first
+    #[serde(default)]
+    pub languages: Vec<AreaOwnership>,
+    /// Top author share, in percent, above which an area is flagged; `None` when flagging is off
+    #[serde(default)]
+    pub concentration_threshold: Option<f64>,
+}
+
+impl OwnershipStats {
+    /// Combine results for disjoint file sets, summing lines by author email and team name
+    pub fn merge<'a>(stats_list: impl IntoIterator<Item = &'a OwnershipStats>) -> Option<OwnershipStats> {
+        let mut merged: Option<OwnershipStats> = None;
+        let mut authors: HashMap<String, AuthorStats> = HashMap::new();
+        let mut teams: BTreeMap<String, TeamStats> = BTreeMap::new();
+        let mut directories: AreaLines = HashMap::new();
+        let mut languages: AreaLines = HashMap::new();
+
+        for stats in stats_list {
+            let total = merged.get_or_insert_with(OwnershipStats::default);
+            total.total_lines += stats.total_lines;
+            total.files_blamed += stats.files_blamed;
+            total.files_skipped += stats.files_skipped;
+            total.single_author_files += stats.single_author_files;
+            total.concentration_threshold = total.concentration_threshold.or(stats.concentration_threshold);
+
+            for (areas, stats_areas) in [(&mut directories, &stats.directories), (&mut languages, &stats.languages)] {
+                for area in stats_areas {
+                    let lines = areas.entry(area.name.clone()).or_default();
+                    for (email, count) in &area.author_lines {
+                        *lines.entry(email.clone()).or_default() += count;
+                    }
+                }
+            }
+
+            for author in &stats.authors {
+                let entry = authors.entry(author.email.to_lowercase()).or_insert_with(|| AuthorStats {
+                    lines: 0,
+                    files: 0,
+                    last_touched: None,
+                    ..author.clone()
+                });
+                entry.lines += author.lines;
+                entry.files += author.files;
+                entry.last_touched = entry.last_touched.max(author.last_touched);
+            }
+            for team in &stats.teams {
+                let entry = teams.entry(team.name.clone()).or_insert_with(|| TeamStats {
+                    lines: 0,
+                    authors: 0,
+                    ..team.clone()
+                });
+                entry.lines += team.lines;
+                // The same members may appear in several inputs, so keep the largest count
+                entry.authors = entry.authors.max(team.authors);
+            }
+        }
+
+        let mut merged = merged?;
+        merged.authors = sort_authors(authors.into_values().collect(), merged.total_lines);
+        merged.bus_factor = bus_factor(&merged.authors, merged.total_lines);
+        merged.top_author_share = merged.authors.first().map_or(0.0, |author| author.share);
+        merged.directories = area_stats(directories, &merged.authors, merged.concentration_threshold);
+        merged.languages = area_stats(languages, &merged.authors, merged.concentration_threshold);
+        merged.teams = teams
+            .into_values()
+            .map(|team| TeamStats {
+                share: share(team.lines, merged.total_lines),
+                ..team
+            })
+            .collect();
+        Some(merged)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorStats {
+    pub name: String,
+    pub email: String,
+    pub lines: usize,
+    pub files: usize,
+    pub share: f64,
+    /// Newest commit among the author's surviving lines
+    pub last_touched: Option<DateTime<Utc>>,
+}
+
+/// How concentrated the authorship of one directory or language is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AreaOwnership {
+    /// Directory path or file extension
+    pub name: String,
+    pub lines: usize,
+    pub authors: usize,
+    pub top_author: String,
+    /// Share of the area's lines written by its top author
+    pub top_author_share: f64,
+    /// Whether the top author's share exceeds the concentration threshold
+    pub concentrated: bool,
+    /// Lines per lowercased author email, kept so areas can be merged across roots
+    #[serde(skip)]
+    author_lines: HashMap<String, usize>,
+}
+
+/// Lines per lowercased author email for each area name
+type AreaLines = HashMap<String, HashMap<String, usize>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamStats {
+    pub name: String,
+    pub lines: usize,
+    pub authors: usize,
+    pub share: f64,
+}
+
+/// Author of one blamed line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    pub author: String,
+    pub email: String,
+    pub time: i64,
+}
+
+/// Runs `git blame` over files and attributes their lines to authors and teams
+pub struct OwnershipAnalyzer {
+    /// Team name to member emails or names, compared case-insensitively
+    teams: BTreeMap<String, Vec<String>>,
+    concentration_threshold: Option<f64>,
+    by_extension: bool,
+}
+
+impl OwnershipAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            teams: BTreeMap::new(),
+            concentration_threshold: Some(DEFAULT_CONCENTRATION_THRESHOLD),
+            by_extension: false,
+        }
+    }
+
+    pub fn with_teams(mut self, teams: BTreeMap<String, Vec<String>>) -> Self {
+        self.teams = teams;
+        self
+    }
+
+    /// Flag directories and languages whose top author owns more than `threshold`
+    /// percent of the lines, or nothing with `None`
+    pub fn with_concentration_threshold(mut self, threshold: Option<f64>) -> Self {
+        self.concentration_threshold = threshold;
+        self
+    }
+
+    /// Attribute lines per file extension rather than per language
+    pub fn with_by_extension(mut self, by_extension: bool) -> Self {
+        self.by_extension = by_extension;
+        self
+    }
+
+    /// Fail early with a clear message when `path` is not inside a git work tree
+    pub fn ensure_repository(path: &Path) -> Result<()> {
+        ensure_work_tree(path, "--owners")
+    }
+
+    /// Blame every file in parallel and aggregate the results
+    pub fn analyze(&self, files: &[PathBuf]) -> OwnershipStats {
+        let blamed: Vec<Option<Vec<BlameLine>>> = files.par_iter().map(|file| blame_file(file).ok()).collect();
+
+        let mut stats = OwnershipStats {
+            files_skipped: blamed.iter().filter(|lines| lines.is_none()).count(),
+            ..OwnershipStats::default()
+        };
+        stats.files_blamed = blamed.len() - stats.files_skipped;
+
+        let mut authors: HashMap<String, AuthorStats> = HashMap::new();
+        let mut directories: AreaLines = HashMap::new();
+        let mut languages: AreaLines = HashMap::new();
+        for (file, lines) in files.iter().zip(blamed) {
+            let Some(lines) = lines else {
+                continue;
+            };
+            let mut file_authors: HashMap<String, usize> = HashMap::new();
+            for line in &lines {
+                let key = line.email.to_lowercase();
+                *file_authors.entry(key.clone()).or_default() += 1;
+
+                let author = authors.entry(key).or_insert_with(|| AuthorStats {
+                    name: line.author.clone(),
+                    email: line.email.clone(),
+                    lines: 0,
+                    files: 0,
+                    share: 0.0,
+                    last_touched: None,
+                });
+                author.lines += 1;
+                let touched = Utc.timestamp_opt(line.time, 0).single();
+                author.last_touched = author.last_touched.max(touched);
+            }
+
+            for email in file_authors.keys() {
+                if let Some(author) = authors.get_mut(email) {
+                    author.files += 1;
+                }
+            }
+            if file_authors.len() == 1 {
+                stats.single_author_files += 1;
+            }
+            stats.total_lines += lines.len();
+
+            let directory = file.parent().map(|dir| dir.to_string_lossy().to_string()).unwrap_or_default();
+            let extension = extension_key(file).map(|ext| ext.to_lowercase()).unwrap_or_else(|| "other".to_string());
+            let language = if self.by_extension { extension } else { LanguageRegistry::new().key(&extension) };
+            for (area, name) in [(&mut directories, directory), (&mut languages, language)] {
+                let area_lines = area.entry(name).or_default();
+                for (email, count) in &file_authors {
+                    *area_lines.entry(email.clone()).or_default() += count;
+                }
+            }
+        }
+
+        let authors = sort_authors(authors.into_values().collect(), stats.total_lines);
+
+        stats.bus_factor = bus_factor(&authors, stats.total_lines);
+        stats.top_author_share = authors.first().map_or(0.0, |author| author.share);
+        stats.teams = self.team_stats(&authors, stats.total_lines);
+        stats.concentration_threshold = self.concentration_threshold;
+        stats.directories = area_stats(directories, &authors, self.concentration_threshold);
+        stats.languages = area_stats(languages, &authors, self.concentration_threshold);
+        stats.authors = authors;
+        stats
+    }
+
+    fn team_stats(&self, authors: &[AuthorStats], total_lines: usize) -> Vec<TeamStats> {
+        self.teams
+            .iter()
+            .map(|(name, members)| {
+                let members: Vec<String> = members.iter().map(|member| member.to_lowercase()).collect();
+                let team_authors: Vec<&AuthorStats> = authors
+                    .iter()
+                    .filter(|author| {
+                        members.contains(&author.email.to_lowercase()) || members.contains(&author.name.to_lowercase())
+                    })
+                    .collect();
+                let lines = team_authors.iter().map(|author| author.lines).sum();
+
+                TeamStats {
+                    name: name.clone(),
+                    lines,
+                    authors: team_authors.len(),
+                    share: share(lines, total_lines),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for OwnershipAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Blame one file, returning the author of each non-blank committed line
+pub fn blame_file(path: &Path) -> Result<Vec<BlameLine>> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .ok_or_else(|| HowManyError::file_processing(format!("Not a file: {}", path.display())))?;
+
+    let output = Command::new("git")
+        .args(["blame", "--line-porcelain", "-w", "--"])
+        .arg(name)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| HowManyError::repository(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(HowManyError::repository(format!(
+            "git blame failed for {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(parse_line_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git blame --line-porcelain` output. Blank lines and uncommitted
+/// changes (the all-zero commit) are left out.
+pub fn parse_line_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut committed = false;
+    let mut author = String::new();
+    let mut email = String::new();
+    let mut time = 0;
+
+    for line in output.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if committed && !content.trim().is_empty() {
+                lines.push(BlameLine {
+                    author: author.clone(),
+                    email: email.clone(),
+                    time,
+                });
+            }
+        } else if let Some(value) = line.strip_prefix("author ") {
+            author = value.to_string();
+        } else if let Some(value) = line.strip_prefix("author-mail ") {
+            email = value.trim_start_matches('<').trim_end_matches('>').to_string();
+        } else if let Some(value) = line.strip_prefix("author-time ") {
+            time = value.parse().unwrap_or(0);
+        } else if let Some(commit) = line.split(' ').next().filter(|word| is_commit_hash(word)) {
+            committed = commit.bytes().any(|byte| byte != b'0');
+        }
+    }
+
+    lines
+}
+
+fn is_commit_hash(word: &str) -> bool {
+    word.len() >= 40 && word.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Sort authors by lines, largest first, and fill in their share of the total
+fn sort_authors(mut authors: Vec<AuthorStats>, total_lines: usize) -> Vec<AuthorStats> {
+    authors.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.email.cmp(&b.email)));
+    for author in &mut authors {
+        author.share = share(author.lines, total_lines);
+    }
+    authors
+}
+
+/// Fewest authors (largest first) whose lines exceed half of the total
+fn bus_factor(sorted_authors: &[AuthorStats], total_lines: usize) -> usize {
+    let mut covered = 0;
+    for (index, author) in sorted_authors.iter().enumerate() {
+        covered += author.lines;
+        if covered * 2 > total_lines {
+            return index + 1;
+        }
+    }
+    sorted_authors.len()
+}
+
+/// Summarize each area's top author, most concentrated (then largest) first
+fn area_stats(areas: AreaLines, authors: &[AuthorStats], threshold: Option<f64>) -> Vec<AreaOwnership> {
+    let mut areas: Vec<AreaOwnership> = areas
+        .into_iter()
+        .map(|(name, author_lines)| {
+            let lines: usize = author_lines.values().sum();
+            let (top_email, top_lines) = author_lines
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(email, count)| (email.clone(), *count))
+                .unwrap_or_default();
+            let top_author = authors
+                .iter()
+                .find(|author| author.email.to_lowercase() == top_email)
+                .map_or(top_email, |author| author.name.clone());
+            let top_author_share = share(top_lines, lines);
+
+            AreaOwnership {
+                name,
+                lines,
+                authors: author_lines.len(),
+                top_author,
+                top_author_share,
+                concentrated: threshold
+                    .is_some_and(|threshold| lines >= MIN_CONCENTRATION_LINES && top_author_share * 100.0 > threshold),
+                author_lines,
+            }
+        })
+        .collect();
+
+    areas.sort_by(|a, b| {
+        b.top_author_share
+            .total_cmp(&a.top_author_share)
+            .then_with(|| b.lines.cmp(&a.lines))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    areas
+}
+
+fn share(lines: usize, total_lines: usize) -> f64 {
+    if total_lines == 0 {
+        0.0
+    } else {
+        lines as f64 / total_lines as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PORCELAIN: &str = "\
+1f2e3d4c5b6a79880716253443526170819a0b1c 1 1 2
+author Ada Lovelace
+author-mail <ada@example.com>
+author-time 1700000000
+author-tz +0000
+summary Initial commit
+filename src/lib.rs
+\tpub fn add(a: i32, b: i32) -> i32 {
+1f2e3d4c5b6a79880716253443526170819a0b1c 2 2
+author Ada Lovelace
+author-mail <ada@example.com>
+author-time 1700000000
+filename src/lib.rs
+\t
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+author-time 1800000000
+filename src/lib.rs
+\t    a + b
+";
+
+    #[test]
+    fn test_parse_line_porcelain() {
+        let lines = parse_line_porcelain(PORCELAIN);
+
+        // The blank line and the uncommitted line are not attributed
+        assert_eq!(lines, vec![BlameLine {
+            author: "Ada Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            time: 1700000000,
+        }]);
+    }
+
+    #[test]
+    fn test_bus_factor() {
+        let author = |email: &str, lines| AuthorStats {
+            name: email.to_string(),
+            email: email.to_string(),
+            lines,
+            files: 1,
+            share: 0.0,
+            last_touched: None,
+        };
+
+        assert_eq!(bus_factor(&[author("a", 60), author("b", 40)], 100), 1);
+        assert_eq!(bus_factor(&[author("a", 40), author("b", 30), author("c", 30)], 100), 2);
+        assert_eq!(bus_factor(&[], 0), 0);
+    }
+
+    #[test]
+    fn test_area_stats_flags_concentrated_areas() {
+        let author_lines = |counts: &[(&str, usize)]| -> HashMap<String, usize> {
+            counts.iter().map(|(email, lines)| (email.to_string(), *lines)).collect()
+        };
+        let areas: AreaLines = [
+            ("src/core".to_string(), author_lines(&[("ada@example.com", 95), ("bob@example.com", 5)])),
+            ("src/ui".to_string(), author_lines(&[("ada@example.com", 50), ("bob@example.com", 50)])),
+            // Owned by one author, but too small to flag
+            ("docs".to_string(), author_lines(&[("bob@example.com", 10)])),
+        ]
+        .into_iter()
+        .collect();
+        let authors = [AuthorStats {
+            name: "Ada Lovelace".to_string(),
+            email: "Ada@example.com".to_string(),
+            lines: 145,
+            files: 2,
+            share: 0.0,
+            last_touched: None,
+        }];
+
+        let stats = area_stats(areas.clone(), &authors, Some(DEFAULT_CONCENTRATION_THRESHOLD));
+        let summary: Vec<_> = stats
+            .iter()
+            .map(|area| (area.name.as_str(), area.top_author.as_str(), area.concentrated))
+            .collect();
+        // Ties go to the first author by email
+        assert_eq!(summary, [
+            ("docs", "bob@example.com", false),
+            ("src/core", "Ada Lovelace", true),
+            ("src/ui", "Ada Lovelace", false),
+        ]);
+        assert!(area_stats(areas, &authors, None).iter().all(|area| !area.concentrated));
+    }
+}
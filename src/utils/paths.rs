@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// Path with its Unicode text in NFC form, used as a stable key for caches and comparisons.
+///
+/// Files should still be opened through their original path: Linux file systems treat
+/// NFC and NFD spellings as different names. Non-UTF-8 paths are returned unchanged.
+pub fn normalize_path(path: &Path) -> Cow<'_, Path> {
+    match path.to_str() {
+        Some(text) if !is_nfc(text) => Cow::Owned(PathBuf::from(text.nfc().collect::<String>())),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Paths whose names differ only by Unicode normalization, e.g. `café.rs` spelled
+/// with a precomposed `é` (NFC) and with `e` plus a combining accent (NFD)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizationConflict {
+    /// NFC spelling shared by all variants
+    pub normalized: String,
+    pub variants: Vec<String>,
+}
+
+/// Group paths by their normalized form and return the groups with more than one spelling
+pub fn find_normalization_conflicts<P: AsRef<Path>>(paths: &[P]) -> Vec<NormalizationConflict> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for path in paths {
+        let path = path.as_ref();
+        let normalized = normalize_path(path).to_string_lossy().to_string();
+        groups.entry(normalized).or_default().push(path.to_string_lossy().to_string());
+    }
+
+    groups
+        .into_iter()
+        .filter_map(|(normalized, mut variants)| {
+            variants.sort();
+            variants.dedup();
+            if variants.len() > 1 {
+                Some(NormalizationConflict { normalized, variants })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Finds vendored crate checkouts below an analyzed root: directories with both a
+/// `Cargo.toml` and a `.git` directory or file (as in git submodules)
+#[derive(Debug)]
+pub struct NestedProjectFinder {
+    roots: Vec<PathBuf>,
+    checked: HashMap<PathBuf, bool>,
+}
+
+impl NestedProjectFinder {
+    pub fn new(roots: &[PathBuf]) -> Self {
+        Self {
+            roots: roots.to_vec(),
+            checked: HashMap::new(),
+        }
+    }
+
+    /// Outermost nested project containing `file`, not counting the analyzed roots themselves
+    pub fn project_of(&mut self, file: &Path) -> Option<PathBuf> {
+        let root = self.roots.iter().find(|root| file.starts_with(root))?;
+        let relative = file.strip_prefix(root).ok()?.parent()?;
+        let mut dir = root.clone();
+
+        for component in relative.components() {
+            dir.push(component);
+            let is_project = *self
+                .checked
+                .entry(dir.clone())
+                .or_insert_with(|| dir.join("Cargo.toml").is_file() && dir.join(".git").exists());
+            if is_project {
+                return Some(dir);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NFC_NAME: &str = "src/caf\u{e9}.rs";
+    const NFD_NAME: &str = "src/cafe\u{301}.rs";
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(Path::new(NFD_NAME)), Path::new(NFC_NAME));
+        assert!(matches!(normalize_path(Path::new(NFC_NAME)), Cow::Borrowed(_)));
+        assert!(matches!(normalize_path(Path::new("src/main.rs")), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_find_normalization_conflicts() {
+        let paths = [NFC_NAME, NFD_NAME, "src/main.rs", NFC_NAME];
+        let conflicts = find_normalization_conflicts(&paths);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].normalized, NFC_NAME);
+        assert_eq!(conflicts[0].variants.len(), 2);
+
+        assert!(find_normalization_conflicts(&["a.rs", "b.rs"]).is_empty());
+    }
+
+    #[test]
+    fn test_nested_project_of() {
+        let root = tempfile::tempdir().unwrap();
+        let vendored = root.path().join("vendor/serde");
+        std::fs::create_dir_all(vendored.join(".git")).unwrap();
+        std::fs::create_dir_all(vendored.join("src")).unwrap();
+        std::fs::write(vendored.join("Cargo.toml"), "[package]\n").unwrap();
+        // A workspace member has a manifest but no checkout of its own
+        std::fs::create_dir_all(root.path().join("crates/core")).unwrap();
+        std::fs::write(root.path().join("crates/core/Cargo.toml"), "[package]\n").unwrap();
+
+        let mut finder = NestedProjectFinder::new(&[root.path().to_path_buf()]);
+        assert_eq!(finder.project_of(&vendored.join("src/lib.rs")), Some(vendored.clone()));
+        assert_eq!(finder.project_of(&root.path().join("crates/core/lib.rs")), None);
+        assert_eq!(finder.project_of(&root.path().join("main.rs")), None);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::utils::errors::{HowManyError, Result};
+use crate::utils::remote::ensure_work_tree;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Files selected by git pathspecs (`':!vendor'`, `':(glob)src/**/*.rs'`).
+///
+/// git resolves the pathspecs itself with `git ls-files`, so magic signatures,
+/// globbing and case rules behave exactly as in `git grep` or `git diff`.
+/// Tracked files and untracked files that aren't ignored are both selected.
+pub struct GitPathspec {
+    files: HashSet<PathBuf>,
+}
+
+impl GitPathspec {
+    /// Files under each root matching `pathspecs`, which are relative to the root
+    pub fn resolve(roots: &[PathBuf], pathspecs: &[String]) -> Result<Self> {
+        let mut files = HashSet::new();
+
+        for root in roots {
+            ensure_work_tree(root, "PATHSPEC")?;
+            let dir = if root.is_file() {
+                root.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."))
+            } else {
+                root.as_path()
+            };
+
+            let mut args = vec!["ls-files", "-z", "--cached", "--others", "--exclude-standard", "--"];
+            args.extend(pathspecs.iter().map(String::as_str));
+            files.extend(parse_ls_files(dir, &git(dir, &args)?));
+        }
+
+        Ok(Self { files })
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.files.contains(path)
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| HowManyError::repository(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(HowManyError::repository(format!(
+            "git {} failed in {}: {}",
+            args.first().copied().unwrap_or_default(),
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `git ls-files -z` output, whose paths are relative to `dir`, into
+/// paths joined onto `dir` the way the directory walk produces them
+pub fn parse_ls_files(dir: &Path, output: &str) -> HashSet<PathBuf> {
+    output
+        .split('\0')
+        .filter(|name| !name.is_empty())
+        .map(|name| dir.join(name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ls_files() {
+        let files = parse_ls_files(Path::new("."), "src/main.rs\0docs/a b.md\0");
+
+        assert_eq!(files.len(), 2);
+        assert!(files.contains(Path::new("./src/main.rs")));
+        // NUL separation keeps unusual names unquoted
+        assert!(files.contains(Path::new("./docs/a b.md")));
+
+        let pathspec = GitPathspec { files };
+        assert!(pathspec.matches(Path::new("./src/main.rs")));
+        assert!(!pathspec.matches(Path::new("./vendor/lib.rs")));
+    }
+}
@@ -1,6 +1,8 @@
-use indicatif::{ProgressBar, ProgressStyle, MultiProgress};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle, MultiProgress};
 use std::time::Duration;
 
+/// Progress of an analysis on the terminal: spinners for open-ended phases such
+/// as scanning, and a bar with the current file and ETA once the files are known
 pub struct ProgressReporter {
     multi_progress: MultiProgress,
     main_progress: ProgressBar,
@@ -9,11 +11,12 @@ pub struct ProgressReporter {
 impl ProgressReporter {
     pub fn new() -> Self {
         let multi_progress = MultiProgress::new();
-        let main_progress = multi_progress.add(ProgressBar::new(0));
+        // Shown by `set_total_files`, so an empty bar isn't drawn while files are still being found
+        let main_progress = ProgressBar::new(0);
         
         main_progress.set_style(
             ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({eta})")
+                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({eta}) {wide_msg}")
                 .unwrap()
                 .progress_chars("#>-")
         );
@@ -24,10 +27,22 @@ impl ProgressReporter {
         }
     }
     
+    /// Draw nothing when `visible` is false, e.g. when output isn't a terminal or with `--quiet`
+    pub fn with_visible(self, visible: bool) -> Self {
+        if !visible {
+            self.multi_progress.set_draw_target(ProgressDrawTarget::hidden());
+        }
+        self
+    }
+    
+    /// Show the bar, with `total` files to go. Call once.
     pub fn set_total_files(&self, total: u64) {
+        self.multi_progress.add(self.main_progress.clone());
         self.main_progress.set_length(total);
         self.main_progress.set_message("Analyzing files...");
+        self.main_progress.enable_steady_tick(Duration::from_millis(100));
     }
+
     
     pub fn increment(&self) {
         self.main_progress.inc(1);
@@ -0,0 +1,200 @@
+use crate::utils::errors::{HowManyError, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Repository to analyze, written as `URL[@REF]` where REF is a branch, tag or commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSpec {
+    pub url: String,
+    pub reference: Option<String>,
+}
+
+impl RepoSpec {
+    /// Split an optional `@ref` suffix off a repository URL or local path.
+    ///
+    /// Only an `@` after the last `/` or `:` starts a ref, so the user part
+    /// of `git@github.com:org/project` and `git@localhost:repo.git` is left
+    /// alone. Neither part may start with `-`, which git would read as an option.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let path_start = spec.rfind(['/', ':']).map(|i| i + 1).unwrap_or(0);
+
+        let (url, reference) = match spec[path_start..].rfind('@') {
+            Some(at) => {
+                let at = path_start + at;
+                (&spec[..at], Some(spec[at + 1..].to_string()))
+            }
+            None => (spec, None),
+        };
+
+        if url.is_empty() || reference.as_deref() == Some("") {
+            return Err(HowManyError::invalid_config(format!(
+                "Invalid repository '{}' (expected URL[@REF])",
+                spec
+            )));
+        }
+        if url.starts_with('-') || reference.as_deref().is_some_and(|reference| reference.starts_with('-')) {
+            return Err(HowManyError::invalid_config(format!(
+                "Invalid repository '{}' (the URL and ref can't start with '-')",
+                spec
+            )));
+        }
+
+        Ok(Self {
+            url: url.to_string(),
+            reference,
+        })
+    }
+
+    /// Local directory that is already a git work tree
+    fn local_work_tree(&self) -> Option<&Path> {
+        let path = Path::new(&self.url);
+        if path.join(".git").exists() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+}
+
+/// Checked-out repository; a temporary clone is deleted when this is dropped
+pub struct RepoCheckout {
+    path: PathBuf,
+    _temp_dir: Option<TempDir>,
+}
+
+impl RepoCheckout {
+    /// Use an existing work tree as is, or shallow-clone the repository into a temporary directory
+    pub fn open(spec: &RepoSpec) -> Result<Self> {
+        Self::open_with_depth(spec, 1)
+    }
+
+    /// Like `open`, but clone the last `depth` commits so history-based analyses have data
+    pub fn open_with_depth(spec: &RepoSpec, depth: usize) -> Result<Self> {
+        if let (Some(path), None) = (spec.local_work_tree(), &spec.reference) {
+            return Ok(Self {
+                path: path.to_path_buf(),
+                _temp_dir: None,
+            });
+        }
+
+        let temp_dir = tempfile::Builder::new().prefix("howmany-repo-").tempdir()?;
+        let target = temp_dir.path();
+        let depth = depth.max(1).to_string();
+        // Local clones ignore --depth unless addressed through file://
+        let url = match spec.local_work_tree() {
+            Some(path) => format!("file://{}", path.canonicalize()?.display()),
+            None => spec.url.clone(),
+        };
+
+        match &spec.reference {
+            // Fetching a single ref works for branches, tags and commit hashes alike
+            Some(reference) => {
+                run_git(target, &["init", "--quiet"])?;
+                run_git(target, &["remote", "add", "--", "origin", &url])?;
+                run_git(target, &["fetch", "--quiet", "--depth", &depth, "--", "origin", reference])?;
+                run_git(target, &["checkout", "--quiet", "FETCH_HEAD"])?;
+            }
+            None => {
+                let target_str = target.to_string_lossy();
+                run_git(target, &["clone", "--quiet", "--depth", &depth, "--", &url, &target_str])?;
+            }
+        }
+
+        Ok(Self {
+            path: target.to_path_buf(),
+            _temp_dir: Some(temp_dir),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Fail with a clear message when `path` is not inside a git work tree; `option` names the flag that needs one
+pub fn ensure_work_tree(path: &Path, option: &str) -> Result<()> {
+    let dir = if path.is_dir() { path } else { path.parent().unwrap_or(Path::new(".")) };
+    let output = Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| HowManyError::repository(format!("Failed to run git: {}", e)))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(HowManyError::repository(format!(
+            "{} needs a git repository, but {} is not inside one",
+            option,
+            path.display()
+        )))
+    }
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| HowManyError::repository(format!("Failed to run git: {}", e)))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(HowManyError::repository(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_with_ref() {
+        let spec = RepoSpec::parse("https://github.com/org/project@v1.2.0").unwrap();
+        assert_eq!(spec.url, "https://github.com/org/project");
+        assert_eq!(spec.reference.as_deref(), Some("v1.2.0"));
+
+        let spec = RepoSpec::parse("https://github.com/org/project").unwrap();
+        assert_eq!(spec.reference, None);
+    }
+
+    #[test]
+    fn test_parse_ssh_url() {
+        let spec = RepoSpec::parse("git@github.com:org/project.git").unwrap();
+        assert_eq!(spec.url, "git@github.com:org/project.git");
+        assert_eq!(spec.reference, None);
+
+        let spec = RepoSpec::parse("git@github.com:org/project.git@main").unwrap();
+        assert_eq!(spec.url, "git@github.com:org/project.git");
+        assert_eq!(spec.reference.as_deref(), Some("main"));
+
+        // Without a `/` in the path, the `:` still ends the host
+        let spec = RepoSpec::parse("git@localhost:repo.git").unwrap();
+        assert_eq!(spec.url, "git@localhost:repo.git");
+        assert_eq!(spec.reference, None);
+
+        let spec = RepoSpec::parse("git@localhost:repo.git@v2").unwrap();
+        assert_eq!(spec.url, "git@localhost:repo.git");
+        assert_eq!(spec.reference.as_deref(), Some("v2"));
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_parts() {
+        assert!(RepoSpec::parse("https://github.com/org/project@").is_err());
+        assert!(RepoSpec::parse("").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_options() {
+        assert!(RepoSpec::parse("https://github.com/org/project@--upload-pack=touch").is_err());
+        assert!(RepoSpec::parse("--upload-pack=touch /tmp/x").is_err());
+        assert!(RepoSpec::parse("https://github.com/org/project@main-2").is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::Path;
+use std::str::FromStr;
+
+/// One of `count` disjoint parts of a project's files (`--shard 2/8`), for
+/// spreading one analysis over the jobs of a CI matrix.
+///
+/// A file's shard only depends on its path relative to the analyzed root, so
+/// every job agrees on the split without coordinating, whatever order its walk
+/// finds the files in. Adding or removing files never moves the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    /// Zero-based
+    index: u64,
+    count: u64,
+}
+
+impl Shard {
+    /// Whether the file at `path`, relative to its root, belongs to this shard
+    pub fn contains(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = path.strip_prefix("./").unwrap_or(&path);
+        fnv1a(path.as_bytes()) % self.count == self.index
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid shard: {} (expected K/N with 1 <= K <= N, e.g. 2/8)", s);
+        let (index, count) = s.split_once('/').ok_or_else(invalid)?;
+        let index: u64 = index.trim().parse().map_err(|_| invalid())?;
+        let count: u64 = count.trim().parse().map_err(|_| invalid())?;
+        if index == 0 || index > count {
+            return Err(invalid());
+        }
+        Ok(Self { index: index - 1, count })
+    }
+}
+
+impl std::fmt::Display for Shard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index + 1, self.count)
+    }
+}
+
+/// 64-bit FNV-1a, which unlike the standard library's hasher is the same on
+/// every platform and release
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shards_partition_files() {
+        let shards: Vec<Shard> = (1..=4).map(|index| format!("{}/4", index).parse().unwrap()).collect();
+        let paths: Vec<String> = (0..200).map(|index| format!("./src/module_{}/file.rs", index)).collect();
+
+        for path in &paths {
+            let owners = shards.iter().filter(|shard| shard.contains(Path::new(path))).count();
+            assert_eq!(owners, 1, "{} belongs to {} shards", path, owners);
+        }
+        // Every shard gets some of the files
+        assert!(shards.iter().all(|shard| paths.iter().any(|path| shard.contains(Path::new(path)))));
+
+        // Root-relative spellings of the same file agree
+        assert_eq!(shards[0].contains(Path::new("./src/main.rs")), shards[0].contains(Path::new("src/main.rs")));
+
+        assert_eq!("1/1".parse::<Shard>().unwrap().to_string(), "1/1");
+        assert!("0/4".parse::<Shard>().is_err());
+        assert!("5/4".parse::<Shard>().is_err());
+        assert!("2".parse::<Shard>().is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::utils::cache::{SharedRecords, COUNTER_VERSION};
+use crate::utils::config::RemoteCacheConfig;
+use crate::utils::errors::{HowManyError, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use tempfile::NamedTempFile;
+
+/// Seconds curl waits for a shared cache on HTTP before giving up on it
+const HTTP_TIMEOUT_SECS: &str = "60";
+
+/// Where the records of a shared cache are kept, picked by the scheme of its URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Backend {
+    /// Directory of an HTTP server that answers GET and accepts PUT, such as a bucket behind a proxy
+    Http { url: String, token_env: Option<String> },
+    /// S3 prefix, read and written with the `aws` CLI
+    S3 { url: String },
+    /// Redis server, read and written with `redis-cli`
+    Redis { server: String },
+}
+
+/// Cache of counts and complexity analyses shared between machines, such as
+/// the runners of a CI pipeline (`[cache.remote]`).
+///
+/// Records are keyed by content hash, so runners with different checkouts of
+/// the same project reuse each other's results. The records of a project are
+/// one object, pulled before counting and pushed, with the pulled records
+/// kept, after a run that counted files; of runners pushing at the same time
+/// the last one wins. Transfers go through `curl`, `aws` or `redis-cli`, and
+/// a failing one leaves the local cache to work alone.
+#[derive(Debug, Clone)]
+pub struct SharedCache {
+    backend: Backend,
+    name: String,
+    push: bool,
+}
+
+impl SharedCache {
+    /// Shared cache at `[cache.remote] url`, failing on URLs of unknown schemes
+    pub fn new(config: &RemoteCacheConfig) -> Result<Self> {
+        let url = config.url.trim_end_matches('/');
+        let backend = if url.starts_with("https://") || url.starts_with("http://") {
+            Backend::Http { url: url.to_string(), token_env: config.token_env.clone() }
+        } else if url.starts_with("s3://") {
+            Backend::S3 { url: url.to_string() }
+        } else if url.starts_with("redis://") || url.starts_with("rediss://") {
+            Backend::Redis { server: url.to_string() }
+        } else {
+            return Err(HowManyError::invalid_config(format!(
+                "Invalid [cache.remote] url '{}' (expected https://, http://, s3:// or redis://)",
+                config.url
+            )));
+        };
+
+        Ok(Self {
+            backend,
+            name: config.name.clone().unwrap_or_default(),
+            push: config.push,
+        })
+    }
+
+    /// Name the records after the directory of the project at `project_root`,
+    /// unless `[cache.remote] name` is set
+    pub fn for_project(mut self, project_root: &Path) -> Self {
+        if self.name.is_empty() {
+            let root = project_root.canonicalize().unwrap_or_else(|_| project_root.to_path_buf());
+            self.name = root.file_name().map_or_else(|| "project".to_string(), |name| name.to_string_lossy().into_owned());
+        }
+        self
+    }
+
+    /// Whether runs upload their records
+    pub fn pushes(&self) -> bool {
+        self.push
+    }
+
+    /// Command the records are transferred with
+    pub fn program(&self) -> &'static str {
+        match self.backend {
+            Backend::Http { .. } => "curl",
+            Backend::S3 { .. } => "aws",
+            Backend::Redis { .. } => "redis-cli",
+        }
+    }
+
+    /// Key of the records. Records of another howmany are ignored, so each version keeps its own.
+    fn object(&self) -> String {
+        format!("howmany-{}-{}", COUNTER_VERSION, self.name)
+    }
+
+    /// Where the records are kept, for messages
+    pub fn location(&self) -> String {
+        match &self.backend {
+            Backend::Http { url, .. } | Backend::S3 { url } => format!("{}/{}.json", url, self.object()),
+            Backend::Redis { server } => format!("{} {}", server, self.object()),
+        }
+    }
+
+    /// Records pushed so far, `None` when there are none yet
+    pub fn pull(&self) -> Result<Option<SharedRecords>> {
+        let output = match &self.backend {
+            Backend::Http { token_env, .. } => {
+                let (mut command, _headers) = self.curl(token_env)?;
+                command.arg(self.location());
+                run(command, None)?
+            }
+            Backend::S3 { .. } => {
+                let mut command = Command::new("aws");
+                command.args(["s3", "cp", "--quiet", &self.location(), "-"]);
+                run(command, None)?
+            }
+            Backend::Redis { server } => {
+                let mut command = Command::new("redis-cli");
+                command.args(["--raw", "-u", server, "GET", &self.object()]);
+                run(command, None)?
+            }
+        };
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Nothing was pushed yet
+            if stderr.contains("404") || stderr.contains("NoSuchKey") {
+                return Ok(None);
+            }
+            return Err(self.failure("pull from", &stderr));
+        }
+
+        // A missing Redis key prints an empty line
+        if output.stdout.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        serde_json::from_slice(&output.stdout)
+            .map(Some)
+            .map_err(|e| self.failure("pull from", &format!("invalid records: {}", e)))
+    }
+
+    /// Replace the records with `records`
+    pub fn push(&self, records: &SharedRecords) -> Result<()> {
+        let contents = serde_json::to_vec(records)?;
+        let output = match &self.backend {
+            Backend::Http { token_env, .. } => {
+                let (mut command, _headers) = self.curl(token_env)?;
+                command.args(["-X", "PUT", "-H", "Content-Type: application/json", "--data-binary", "@-", &self.location()]);
+                run(command, Some(&contents))?
+            }
+            Backend::S3 { .. } => {
+                let mut command = Command::new("aws");
+                command.args(["s3", "cp", "--quiet", "-", &self.location()]);
+                run(command, Some(&contents))?
+            }
+            Backend::Redis { server } => {
+                let mut command = Command::new("redis-cli");
+                command.args(["-u", server, "-x", "SET", &self.object()]);
+                run(command, Some(&contents))?
+            }
+        };
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(self.failure("push to", &String::from_utf8_lossy(&output.stderr)))
+        }
+    }
+
+    /// curl failing on HTTP errors, with the bearer token of `token_env`. The
+    /// token is passed in a header file, which must outlive the command, so it
+    /// doesn't show in the process list.
+    fn curl(&self, token_env: &Option<String>) -> Result<(Command, Option<NamedTempFile>)> {
+        let mut command = Command::new("curl");
+        command.args(["--silent", "--show-error", "--fail", "--location", "--max-time", HTTP_TIMEOUT_SECS]);
+        let Some(variable) = token_env else {
+            return Ok((command, None));
+        };
+
+        let token = std::env::var(variable).map_err(|_| {
+            HowManyError::invalid_config(format!("[cache.remote] token_env names {}, which is not set", variable))
+        })?;
+        let mut headers = NamedTempFile::new()?;
+        writeln!(headers, "Authorization: Bearer {}", token)?;
+        command.arg("--header").arg(format!("@{}", headers.path().display()));
+        Ok((command, Some(headers)))
+    }
+
+    fn failure(&self, action: &str, reason: &str) -> HowManyError {
+        HowManyError::file_processing(format!("Failed to {} the shared cache at {}: {}", action, self.location(), reason.trim()))
+    }
+}
+
+/// Run a transfer command, feeding it `input`
+fn run(mut command: Command, input: Option<&[u8]>) -> Result<Output> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| HowManyError::file_processing(format!("Failed to run {} for the shared cache: {}", program, e)))?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input)?;
+    }
+    Ok(child.wait_with_output()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(url: &str) -> RemoteCacheConfig {
+        RemoteCacheConfig { url: url.to_string(), ..RemoteCacheConfig::default() }
+    }
+
+    #[test]
+    fn test_backend_from_url() {
+        let root = Path::new("/work/monorepo");
+        let shared = SharedCache::new(&config("https://cache.example.com/howmany/")).unwrap().for_project(root);
+        assert_eq!(shared.location(), format!("https://cache.example.com/howmany/howmany-{}-monorepo.json", COUNTER_VERSION));
+
+        let shared = SharedCache::new(&RemoteCacheConfig { name: Some("web".to_string()), ..config("s3://ci-cache") }).unwrap().for_project(root);
+        assert_eq!(shared.location(), format!("s3://ci-cache/howmany-{}-web.json", COUNTER_VERSION));
+
+        let shared = SharedCache::new(&config("redis://cache:6379")).unwrap().for_project(root);
+        assert_eq!(shared.backend, Backend::Redis { server: "redis://cache:6379".to_string() });
+        assert_eq!(shared.object(), format!("howmany-{}-monorepo", COUNTER_VERSION));
+        assert_eq!(shared.program(), "redis-cli");
+        assert!(shared.pushes());
+
+        assert!(SharedCache::new(&config("ftp://cache")).is_err());
+        assert!(SharedCache::new(&config("")).is_err());
+    }
+}
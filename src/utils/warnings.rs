@@ -0,0 +1,93 @@
+use crate::utils::errors::HowManyError;
+use crate::utils::format::{current_locale, NumberLocale};
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Files that failed to be counted, reported once grouped by cause instead of
+/// one warning per file. With `verbose`, each failure is also printed as it happens.
+#[derive(Debug, Default)]
+pub struct FailedFiles {
+    verbose: bool,
+    total: usize,
+    causes: BTreeMap<&'static str, usize>,
+}
+
+impl FailedFiles {
+    pub fn new(verbose: bool) -> Self {
+        Self { verbose, ..Self::default() }
+    }
+
+    pub fn add(&mut self, path: &Path, error: &HowManyError) {
+        if self.verbose {
+            eprintln!("Warning: Failed to process {}: {}", path.display(), error);
+        }
+        self.total += 1;
+        *self.causes.entry(cause(error)).or_insert(0) += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// One line for all failures, e.g. `5,000 files could not be read (permission denied: 4,992, invalid encoding: 8)`
+    pub fn summary(&self) -> Option<String> {
+        self.summary_with(current_locale())
+    }
+
+    pub fn summary_with(&self, locale: NumberLocale) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let causes: Vec<String> = self.causes
+            .iter()
+            .map(|(cause, count)| format!("{}: {}", cause, locale.format_integer(*count as u64)))
+            .collect();
+        Some(format!(
+            "{} {} could not be read ({})",
+            locale.format_integer(self.total as u64),
+            if self.total == 1 { "file" } else { "files" },
+            causes.join(", ")
+        ))
+    }
+}
+
+/// Short description of why a file failed, shared by all files failing the same way
+fn cause(error: &HowManyError) -> &'static str {
+    match error {
+        HowManyError::Io(error) => match error.kind() {
+            ErrorKind::PermissionDenied => "permission denied",
+            ErrorKind::NotFound => "not found",
+            ErrorKind::InvalidData => "invalid encoding",
+            ErrorKind::Interrupted | ErrorKind::TimedOut => "interrupted",
+            _ => "I/O error",
+        },
+        HowManyError::FileNotFound(_) => "not found",
+        _ => "processing error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_summary_groups_causes() {
+        let mut failed = FailedFiles::new(false);
+        assert_eq!(failed.summary_with(NumberLocale::ENGLISH), None);
+
+        for index in 0..1_200 {
+            let error = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+            failed.add(Path::new(&format!("locked/{}.rs", index)), &HowManyError::Io(error));
+        }
+        let error = io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8");
+        failed.add(Path::new("latin1.rs"), &HowManyError::Io(error));
+
+        assert_eq!(
+            failed.summary_with(NumberLocale::ENGLISH).unwrap(),
+            "1,201 files could not be read (invalid encoding: 1, permission denied: 1,200)"
+        );
+    }
+}